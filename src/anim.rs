@@ -0,0 +1,181 @@
+//! ANIM decoding with `alloc` only.
+//!
+//! Embedded and console players load the single-body container from a byte slice: the current
+//! version, and version 1 through the frozen layouts. They get an [`EpisodeCore`], the part of
+//! an episode playback evaluates; render settings and overlays are skipped. Chunked, streamed,
+//! encrypted, signed and named-reference containers need `episode::deserialize_episode`.
+
+use alloc::format;
+use alloc::string::ToString;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::director::{Director, DirectorV1};
+use crate::error::{AnimationError, Result};
+use crate::metadata::{EpisodeMetadata, EpisodeMetadataV1};
+use crate::npr::AnimeShading;
+use crate::rng::EpisodeRng;
+use crate::scene::{SceneGraph, SceneGraphV1};
+
+/// Binary format magic bytes.
+pub(crate) const EPISODE_MAGIC: [u8; 4] = *b"ANIM";
+/// Single-body format version (2 and 3 are the chunked and streamed containers).
+pub(crate) const EPISODE_VERSION: u16 = 4;
+/// Single-body version of episodes written before cuts, actors and cameras grew new fields;
+/// read through [`EpisodePackageV1`].
+pub(crate) const EPISODE_V1_VERSION: u16 = 1;
+
+/// Header flag: body is ChaCha20-Poly1305 ciphertext (feature `crypto`).
+pub const FLAG_ENCRYPTED: u16 = 1 << 0;
+/// Header flag: a 64-byte Ed25519 signature trails the body (feature `crypto`).
+pub const FLAG_SIGNED: u16 = 1 << 1;
+/// Header flag: body is a `named_refs::NamedEpisode` (actor references stored by key).
+pub const FLAG_NAMED_REFS: u16 = 1 << 2;
+
+/// What playback needs from an episode package.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EpisodeCore {
+    pub metadata: EpisodeMetadata,
+    pub scene_graph: SceneGraph,
+    pub director: Director,
+    pub shading: AnimeShading,
+}
+
+/// Package layout of version 1 episodes, before render settings and overlays.
+#[derive(Deserialize)]
+pub(crate) struct EpisodePackageV1 {
+    metadata: EpisodeMetadataV1,
+    scene_graph: SceneGraphV1,
+    director: DirectorV1,
+    shading: AnimeShading,
+}
+
+impl From<EpisodePackageV1> for EpisodeCore {
+    fn from(v1: EpisodePackageV1) -> Self {
+        Self {
+            metadata: v1.metadata.into(),
+            scene_graph: v1.scene_graph.into(),
+            director: v1.director.into(),
+            shading: v1.shading,
+        }
+    }
+}
+
+/// Format version stored in a header.
+#[inline]
+pub(crate) fn header_version(header: &[u8; 16]) -> u16 {
+    u16::from_le_bytes([header[4], header[5]])
+}
+
+/// Header flags field.
+#[inline]
+pub(crate) fn frame_flags(header: &[u8; 16]) -> u16 {
+    u16::from_le_bytes([header[6], header[7]])
+}
+
+/// Decode a single-body episode (header, CRC-checked body) from `bytes`.
+pub fn decode_episode_core(bytes: &[u8]) -> Result<EpisodeCore> {
+    let header: &[u8; 16] = bytes
+        .get(..16)
+        .and_then(|h| h.try_into().ok())
+        .ok_or(AnimationError::Truncated("ANIM header"))?;
+    if header[0..4] != EPISODE_MAGIC {
+        return Err(AnimationError::BadMagic { expected: "ANIM" });
+    }
+    let size = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
+    let expected_crc = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+    // 16 + size can overflow usize on 32-bit targets
+    let body = 16usize
+        .checked_add(size)
+        .and_then(|end| bytes.get(16..end))
+        .ok_or(AnimationError::Truncated("ANIM body"))?;
+    let actual_crc = crc32fast::hash(body);
+    if actual_crc != expected_crc {
+        return Err(AnimationError::CrcMismatch {
+            expected: expected_crc,
+            actual: actual_crc,
+        });
+    }
+    decode_body(header_version(header), frame_flags(header), body)
+}
+
+/// Decode a checked single body written as `version` with header `flags`.
+pub(crate) fn decode_body(version: u16, flags: u16, body: &[u8]) -> Result<EpisodeCore> {
+    if flags & (FLAG_ENCRYPTED | FLAG_SIGNED) != 0 {
+        return Err(AnimationError::Protected { flags });
+    }
+    if flags != 0 {
+        return Err(AnimationError::Corrupt(format!(
+            "ANIM version {version} body with flags {flags:#06x} needs the std loader"
+        )));
+    }
+    let mut core: EpisodeCore = match version {
+        EPISODE_VERSION => decode(body)?,
+        EPISODE_V1_VERSION => decode::<EpisodePackageV1>(body)?.into(),
+        version => {
+            return Err(AnimationError::UnsupportedVersion {
+                format: "ANIM",
+                version,
+            })
+        }
+    };
+    core.director.seed_shake(&EpisodeRng::new(core.metadata.seed()));
+    Ok(core)
+}
+
+/// Decode a bincode 1 body; trailing fields the target leaves out are ignored.
+fn decode<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    bincode2::serde::decode_from_slice(body, bincode2::config::legacy())
+        .map(|(value, _)| value)
+        .map_err(|e| AnimationError::Corrupt(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::episode::{serialize_episode, EpisodePackage};
+    use crate::scene::Actor;
+    use alice_sdf::SdfNode;
+
+    #[test]
+    fn test_decodes_current_and_v1_bodies() {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let meta = EpisodeMetadata::new("Pilot", 1, 4.0).with_seed(9);
+        let episode =
+            EpisodePackage::new(meta, sg, Director::new("Pilot"), AnimeShading::default());
+        let mut buf = alloc::vec::Vec::new();
+        serialize_episode(&episode, &mut buf).unwrap();
+        let core = decode_episode_core(&buf).unwrap();
+        assert_eq!(
+            (core.metadata.title.as_str(), core.metadata.seed()),
+            ("Pilot", 9)
+        );
+        assert!(core.scene_graph.find_by_name("hero").is_some());
+
+        let v1 = decode_episode_core(include_bytes!("../tests/data/episode_v1.anim")).unwrap();
+        assert_eq!(v1.director.cut_count(), 2);
+    }
+
+    #[test]
+    fn test_rejects_damaged_input() {
+        let bytes = include_bytes!("../tests/data/episode_v1.anim");
+        assert!(matches!(
+            decode_episode_core(&bytes[..bytes.len() - 1]),
+            Err(AnimationError::Truncated(_))
+        ));
+        let mut flipped = bytes.to_vec();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            decode_episode_core(&flipped),
+            Err(AnimationError::CrcMismatch { .. })
+        ));
+        let mut future = bytes.to_vec();
+        future[4] = 9;
+        assert!(matches!(
+            decode_episode_core(&future),
+            Err(AnimationError::UnsupportedVersion { version: 9, .. })
+        ));
+    }
+}
@@ -0,0 +1,214 @@
+//! Animatic import: timed storyboard panels become one cut each, with an image-plane actor
+//! per panel and a camera framing it, so boards live in the same `Director` / `SceneGraph`
+//! model that final shots later replace cut by cut.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use alice_sdf::SdfNode;
+use glam::Vec3;
+
+use crate::director::{Cut, CutId, Director};
+use crate::episode::{EpisodeMetadata, EpisodePackage};
+use crate::npr::AnimeShading;
+use crate::scene::{Actor, ActorId, SceneGraph};
+
+/// Layout of the generated board wall.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnimaticOptions {
+    /// Panel width / height.
+    pub aspect: f32,
+    /// Panel height in world units.
+    pub plane_height: f32,
+    /// Gap between neighbouring panels along X.
+    pub spacing: f32,
+    /// Vertical camera field of view (radians).
+    pub fov: f32,
+}
+
+impl Default for AnimaticOptions {
+    fn default() -> Self {
+        Self {
+            aspect: 16.0 / 9.0,
+            plane_height: 2.0,
+            spacing: 1.0,
+            fov: std::f32::consts::FRAC_PI_4,
+        }
+    }
+}
+
+/// One imported board panel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimaticPanel {
+    pub image: PathBuf,
+    pub cut: CutId,
+    pub actor: ActorId,
+}
+
+/// Result of [`import_animatic`]: the episode plus where each panel ended up.
+#[derive(Debug, Clone)]
+pub struct Animatic {
+    pub episode: EpisodePackage,
+    pub panels: Vec<AnimaticPanel>,
+}
+
+impl Animatic {
+    /// Panel shown at `time`.
+    pub fn panel_at(&self, time: f32) -> Option<&AnimaticPanel> {
+        let (id, _) = self.episode.director.find_active_cut(time)?;
+        self.panels.iter().find(|p| p.cut == id)
+    }
+
+    /// Load every board image and add it as a full-frame overlay over its cut, so
+    /// renders show the boards themselves rather than blank planes.
+    #[cfg(feature = "image")]
+    pub fn load_board_overlays(&mut self) -> io::Result<()> {
+        use crate::overlay::{Overlay, OverlayContent, OverlayKind};
+        for panel in &self.panels {
+            let cut = self.episode.director.get_cut(panel.cut).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::NotFound, "Animatic cut was removed")
+            })?;
+            let image = crate::image_io::read_png(&panel.image)?;
+            self.episode.overlays.push(Overlay::new(
+                cut.name.clone(),
+                OverlayKind::Custom("storyboard".into()),
+                OverlayContent::Image(image),
+                cut.start_time,
+                cut.end_time,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Build an episode with one cut per `(image, duration)` panel, in order.
+///
+/// Panels are thin cards laid left to right along X; each cut's camera looks straight at
+/// its panel from the distance that fits it to the frame height. Cut and actor names are
+/// the image file stems. Fails with `InvalidInput` on an empty list or a non-positive
+/// duration.
+pub fn import_animatic<P: AsRef<Path>>(
+    title: impl Into<String>,
+    panels: &[(P, f32)],
+    options: &AnimaticOptions,
+) -> io::Result<Animatic> {
+    let title = title.into();
+    if panels.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Animatic has no panels",
+        ));
+    }
+    let half_height = 0.5 * options.plane_height;
+    let half_width = half_height * options.aspect;
+    let pitch = 2.0 * half_width + options.spacing;
+    let distance = half_height / (0.5 * options.fov).tan();
+
+    let mut scene_graph = SceneGraph::new();
+    let mut director = Director::new(title.clone());
+    let mut imported = Vec::with_capacity(panels.len());
+    let mut time = 0.0f32;
+    for (index, (image, duration)) in panels.iter().enumerate() {
+        let image = image.as_ref();
+        if duration.is_nan() || *duration <= 0.0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Panel {} ({}) has non-positive duration {}",
+                    index,
+                    image.display(),
+                    duration
+                ),
+            ));
+        }
+        let name = image
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("panel_{}", index));
+        let x = index as f32 * pitch;
+        let card = SdfNode::box3d(half_width, half_height, 0.01).translate(x, 0.0, 0.0);
+        let actor = scene_graph.add_actor(Actor::new(name.clone(), card));
+
+        let mut cut = Cut::new(name, time, time + duration).with_actors(vec![actor]);
+        cut.camera.clear_keyframes();
+        cut.camera.add_keyframe(
+            0.0,
+            Vec3::new(x, 0.0, distance),
+            Vec3::new(x, 0.0, 0.0),
+            options.fov,
+        );
+        let cut = director.add_cut(cut);
+        imported.push(AnimaticPanel {
+            image: image.to_path_buf(),
+            cut,
+            actor,
+        });
+        time += duration;
+    }
+
+    let metadata = EpisodeMetadata::new(title, 0, time);
+    Ok(Animatic {
+        episode: EpisodePackage::new(metadata, scene_graph, director, AnimeShading::default()),
+        panels: imported,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boards() -> Vec<(&'static str, f32)> {
+        vec![("boards/sc01_a.png", 2.0), ("boards/sc01_b.png", 1.5)]
+    }
+
+    #[test]
+    fn test_one_cut_and_plane_per_panel() {
+        let animatic =
+            import_animatic("Ep1 animatic", &boards(), &AnimaticOptions::default()).unwrap();
+        let ep = &animatic.episode;
+        assert_eq!(ep.director.cut_count(), 2);
+        assert_eq!(ep.scene_graph.actor_count(), 2);
+        assert_eq!(ep.metadata.duration_seconds, 3.5);
+        assert!(ep.validation_issues().is_empty());
+
+        let second = animatic.panel_at(2.5).unwrap();
+        assert_eq!(second.image, Path::new("boards/sc01_b.png"));
+        let cut = ep.director.get_cut(second.cut).unwrap();
+        assert_eq!(cut.name, "sc01_b");
+        assert_eq!(cut.active_actors, vec![second.actor]);
+
+        // Camera sits in front of its own panel, not the first one
+        let state = ep.director.evaluate(&ep.scene_graph, 2.5);
+        let pitch = 2.0 * 16.0 / 9.0 + 1.0;
+        assert!((state.camera_state.target.x - pitch).abs() < 1e-4);
+        assert!(state.camera_state.position.z > 0.0);
+    }
+
+    #[test]
+    fn test_rejects_bad_panels() {
+        let options = AnimaticOptions::default();
+        let empty: [(&str, f32); 0] = [];
+        assert!(import_animatic("x", &empty, &options).is_err());
+        let err = import_animatic("x", &[("a.png", 1.0), ("b.png", 0.0)], &options).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_board_overlays_show_images() {
+        use crate::framebuffer::Framebuffer;
+        let dir = std::env::temp_dir().join(format!("alice_animatic_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("board.png");
+        let mut board = Framebuffer::new(4, 4);
+        board.fill([1.0, 1.0, 1.0, 1.0]);
+        crate::image_io::write_png(&board, &path).unwrap();
+
+        let mut animatic =
+            import_animatic("a", &[(&path, 1.0)], &AnimaticOptions::default()).unwrap();
+        animatic.load_board_overlays().unwrap();
+        assert_eq!(animatic.episode.overlays.len(), 1);
+        assert_eq!(animatic.episode.overlays[0].end, 1.0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
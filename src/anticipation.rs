@@ -0,0 +1,209 @@
+//! Anticipation and follow-through authoring.
+//!
+//! The `Overshoot` and `FollowThrough` easings bend the spacing of one move; this pass
+//! writes the same principles as real keys around a key action instead, so they can be
+//! edited like any other pose: a hold and a small counter-motion before the move, then
+//! an overshoot past the end pose that settles back in damped bounces.
+
+use alice_sdf::animation::{Timeline, Track};
+use serde::{Deserialize, Serialize};
+
+use crate::keys::{set_key, KEY_EPSILON};
+
+
+/// A move from the pose at `start` to the pose at `end` (seconds).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KeyAction {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl KeyAction {
+    pub fn new(start: f32, end: f32) -> Self {
+        Self { start, end }
+    }
+
+    /// Every move between consecutive keys of `track` whose values differ by more than
+    /// `min_change`.
+    pub fn find(track: &Track, min_change: f32) -> Vec<Self> {
+        track
+            .keyframes
+            .windows(2)
+            .filter(|w| w[1].time > w[0].time && (w[1].value - w[0].value).abs() > min_change)
+            .map(|w| Self::new(w[0].time, w[1].time))
+            .collect()
+    }
+}
+
+/// Strengths and timing of the inserted keys. Strengths are fractions of the move.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActionAccents {
+    /// Counter-motion reached at the start key (0 = none).
+    pub anticipation: f32,
+    /// Seconds before the start key where the wind-up begins from the start pose.
+    pub anticipation_lead: f32,
+    /// Overshoot past the end pose (0 = none).
+    pub follow_through: f32,
+    /// Seconds from the end key to the overshoot peak, and between settle keys.
+    pub follow_through_delay: f32,
+    /// Alternating settle keys after the overshoot.
+    pub settle_bounces: u32,
+    /// Each settle key's offset relative to the previous one.
+    pub settle_damping: f32,
+}
+
+impl Default for ActionAccents {
+    fn default() -> Self {
+        Self {
+            anticipation: 0.15,
+            anticipation_lead: 4.0 / 24.0,
+            follow_through: 0.1,
+            follow_through_delay: 3.0 / 24.0,
+            settle_bounces: 1,
+            settle_damping: 0.4,
+        }
+    }
+}
+
+impl ActionAccents {
+    pub fn with_anticipation(mut self, strength: f32, lead: f32) -> Self {
+        self.anticipation = strength;
+        self.anticipation_lead = lead;
+        self
+    }
+
+    pub fn with_follow_through(mut self, strength: f32, delay: f32) -> Self {
+        self.follow_through = strength;
+        self.follow_through_delay = delay;
+        self
+    }
+
+    pub fn with_settle(mut self, bounces: u32, damping: f32) -> Self {
+        self.settle_bounces = bounces;
+        self.settle_damping = damping;
+        self
+    }
+
+    /// Insert anticipation and follow-through keys for `action` into `track`. Tracks that
+    /// do not change over the action are left alone; the inserted keys are squeezed to
+    /// fit before the previous key and after the next one.
+    pub fn apply_track(&self, track: &mut Track, action: KeyAction) {
+        let KeyAction { start, end } = action;
+        if end <= start || track.keyframes.is_empty() {
+            return;
+        }
+        let from = track.evaluate(start);
+        let to = track.evaluate(end);
+        let change = to - from;
+        if change == 0.0 {
+            return;
+        }
+        let previous = track
+            .keyframes
+            .iter()
+            .rev()
+            .find(|k| k.time < start - KEY_EPSILON)
+            .map(|k| k.time);
+        let next = track
+            .keyframes
+            .iter()
+            .find(|k| k.time > end + KEY_EPSILON)
+            .map(|k| k.time);
+
+        set_key(track, end, to);
+        if self.anticipation > 0.0 && self.anticipation_lead > 0.0 {
+            let room = previous.map_or(f32::INFINITY, |p| (start - p) * 0.5);
+            let lead = self.anticipation_lead.min(room);
+            set_key(track, start - lead, from);
+            set_key(track, start, from - change * self.anticipation);
+        } else {
+            set_key(track, start, from);
+        }
+
+        if self.follow_through > 0.0 && self.follow_through_delay > 0.0 {
+            // Overshoot, settle keys, then rest on the end pose
+            let steps = self.settle_bounces + 2;
+            let room = next.map_or(f32::INFINITY, |n| (n - end) / (steps + 1) as f32);
+            let delay = self.follow_through_delay.min(room);
+            let mut offset = change * self.follow_through;
+            for step in 1..steps {
+                set_key(track, end + delay * step as f32, to + offset);
+                offset *= -self.settle_damping;
+            }
+            set_key(track, end + delay * steps as f32, to);
+        }
+    }
+
+    /// [`apply_track`](Self::apply_track) on every track of `timeline`.
+    pub fn apply(&self, timeline: &mut Timeline, action: KeyAction) {
+        for track in &mut timeline.tracks {
+            self.apply_track(track, action);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::animation::Keyframe;
+
+    fn jump() -> Track {
+        let mut track = Track::new("translate.y");
+        track.add_keyframe(Keyframe::new(0.0, 0.0));
+        track.add_keyframe(Keyframe::new(1.0, 0.0));
+        track.add_keyframe(Keyframe::new(1.5, 2.0));
+        track.add_keyframe(Keyframe::new(3.0, 2.0));
+        track
+    }
+
+    #[test]
+    fn test_anticipation_and_settle_keys() {
+        let mut track = jump();
+        let actions = KeyAction::find(&track, 1e-3);
+        assert_eq!(actions, vec![KeyAction::new(1.0, 1.5)]);
+        let accents = ActionAccents::default()
+            .with_anticipation(0.2, 0.25)
+            .with_follow_through(0.1, 0.2)
+            .with_settle(1, 0.5);
+        accents.apply_track(&mut track, actions[0]);
+        let keys: Vec<(f32, f32)> = track.keyframes.iter().map(|k| (k.time, k.value)).collect();
+        let expected = [
+            (0.0, 0.0),
+            (0.75, 0.0),
+            (1.0, -0.4),
+            (1.5, 2.0),
+            (1.7, 2.2),
+            (1.9, 1.9),
+            (2.1, 2.0),
+            (3.0, 2.0),
+        ];
+        assert_eq!(keys.len(), expected.len());
+        for ((t, v), (et, ev)) in keys.into_iter().zip(expected) {
+            assert!((t - et).abs() < 1e-5 && (v - ev).abs() < 1e-5, "{t} {v}");
+        }
+    }
+
+    #[test]
+    fn test_accents_fit_between_neighbours() {
+        let mut timeline = Timeline::new("hop");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.9, 0.0));
+        x.add_keyframe(Keyframe::new(1.0, 0.0));
+        x.add_keyframe(Keyframe::new(1.5, -1.0));
+        x.add_keyframe(Keyframe::new(1.6, 5.0));
+        timeline.add_track(x);
+        timeline.add_track(jump());
+        let mut still = Track::new("scale");
+        still.add_keyframe(Keyframe::new(0.0, 1.0));
+        timeline.add_track(still);
+        ActionAccents::default().apply(&mut timeline, KeyAction::new(1.0, 1.5));
+
+        let x = &timeline.tracks[0].keyframes;
+        // Wind-up starts halfway to the previous key; settle ends before the next one
+        assert!((x[1].time - 0.95).abs() < 1e-5);
+        assert!(x.windows(2).all(|w| w[0].time < w[1].time));
+        assert_eq!(x.last().unwrap().time, 1.6);
+        assert!((timeline.get_value("translate.x", 1.0).unwrap() - 0.15).abs() < 1e-5);
+        assert_eq!(timeline.tracks[2].keyframes.len(), 1);
+    }
+}
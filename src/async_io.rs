@@ -0,0 +1,294 @@
+//! Async (tokio) episode I/O.
+//!
+//! Same ANIM format as `episode::serialize_episode` / `deserialize_episode`, but the bytes are
+//! moved in fixed-size blocks through `AsyncRead` / `AsyncWrite`, reporting progress after each.
+//! Encoding, decoding and CRC checks run on tokio's blocking pool so multi-MB episodes never
+//! stall a runtime thread. With the `cdn` feature, [`fetch_segments_async`] downloads a
+//! segmented episode the same way.
+
+use std::io;
+use std::sync::Arc;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::episode::{
+    deserialize_episode, frame_header, header_version, read_header, EpisodePackage,
+    CHUNKED_VERSION, STREAMED_VERSION,
+};
+
+/// Bytes transferred between progress reports.
+pub const IO_BLOCK_SIZE: usize = 64 * 1024;
+
+/// Transfer progress for a single episode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IoProgress {
+    /// Bytes transferred so far (including the header).
+    pub bytes: u64,
+    /// Total bytes, if known from the header.
+    pub total: Option<u64>,
+}
+
+impl IoProgress {
+    /// Completed fraction (0.0..=1.0), if the total is known.
+    #[inline]
+    pub fn fraction(&self) -> Option<f32> {
+        self.total.map(|t| {
+            if t == 0 {
+                1.0
+            } else {
+                (self.bytes as f64 / t as f64) as f32
+            }
+        })
+    }
+}
+
+/// Run CPU-bound codec work on the blocking pool.
+async fn blocking<T, F>(work: F) -> io::Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> io::Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(io::Error::other)?
+}
+
+/// Serialize an episode to an async writer, calling `progress` after each block. The
+/// episode is shared with the blocking pool for encoding, hence the `Arc`.
+pub async fn serialize_episode_async<W, F>(
+    episode: Arc<EpisodePackage>,
+    writer: &mut W,
+    mut progress: F,
+) -> io::Result<usize>
+where
+    W: AsyncWrite + Unpin,
+    F: FnMut(IoProgress),
+{
+    let (header, body) = blocking(move || {
+        let body = bincode::serialize(&*episode)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok((frame_header(0, &body), body))
+    })
+    .await?;
+    let total = (header.len() + body.len()) as u64;
+
+    writer.write_all(&header).await?;
+    let mut written = header.len() as u64;
+    progress(IoProgress {
+        bytes: written,
+        total: Some(total),
+    });
+
+    for block in body.chunks(IO_BLOCK_SIZE) {
+        writer.write_all(block).await?;
+        written += block.len() as u64;
+        progress(IoProgress {
+            bytes: written,
+            total: Some(total),
+        });
+    }
+    writer.flush().await?;
+    Ok(written as usize)
+}
+
+/// Deserialize an episode from an async reader, calling `progress` after each block.
+///
+/// Accepts the same inputs as `deserialize_episode`. For chunked (v2) and streamed (v3) files
+/// the total size is not in the header, so progress reports `total: None` and the reader is
+/// drained to EOF.
+pub async fn deserialize_episode_async<R, F>(
+    reader: &mut R,
+    mut progress: F,
+) -> io::Result<EpisodePackage>
+where
+    R: AsyncRead + Unpin,
+    F: FnMut(IoProgress),
+{
+    let mut header = [0u8; 16];
+    reader.read_exact(&mut header).await?;
+    read_header(&mut &header[..])?;
+
+    let total = if matches!(header_version(&header), CHUNKED_VERSION | STREAMED_VERSION) {
+        None
+    } else {
+        let size = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+        Some(16 + size as u64)
+    };
+
+    // The header's size is untrusted: grow with the data instead of reserving it up front
+    let mut buf = Vec::with_capacity(16 + IO_BLOCK_SIZE);
+    buf.extend_from_slice(&header);
+    progress(IoProgress {
+        bytes: buf.len() as u64,
+        total,
+    });
+
+    let mut block = vec![0u8; IO_BLOCK_SIZE];
+    loop {
+        let want = match total {
+            Some(t) => (t as usize - buf.len()).min(IO_BLOCK_SIZE),
+            None => IO_BLOCK_SIZE,
+        };
+        if want == 0 {
+            break;
+        }
+        let n = reader.read(&mut block[..want]).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&block[..n]);
+        progress(IoProgress {
+            bytes: buf.len() as u64,
+            total,
+        });
+    }
+    if total.is_some_and(|t| (buf.len() as u64) < t) {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Episode body truncated",
+        ));
+    }
+
+    blocking(move || Ok(deserialize_episode(&mut io::Cursor::new(buf))?)).await
+}
+
+/// Download every missing segment of `assembler` in playback order, so playback can start
+/// from `assembler.assemble_prefix()` while later segments are still in flight.
+///
+/// `fetch` returns a segment's bytes (e.g. an HTTP GET of `segment.uri`); each segment is
+/// size/CRC-checked and decoded on the blocking pool. `progress` reports bytes received
+/// against the manifest total.
+#[cfg(feature = "cdn")]
+pub async fn fetch_segments_async<Fetch, Fut, F>(
+    assembler: &mut crate::cdn_bridge::SegmentAssembler,
+    mut fetch: Fetch,
+    mut progress: F,
+) -> io::Result<()>
+where
+    Fetch: FnMut(&crate::cdn_bridge::CdnSegment) -> Fut,
+    Fut: std::future::Future<Output = io::Result<Vec<u8>>>,
+    F: FnMut(IoProgress),
+{
+    let total = assembler.manifest().total_bytes();
+    let mut received = 0u64;
+    while let Some(index) = assembler.next_missing() {
+        let segment = assembler.segment(index)?.clone();
+        let bytes = fetch(&segment).await?;
+        received += bytes.len() as u64;
+        let part = blocking(move || crate::cdn_bridge::decode_segment(&segment, &bytes)).await?;
+        assembler.insert_part(index, part);
+        progress(IoProgress {
+            bytes: received,
+            total: Some(total),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::serialize_episode_chunked;
+    use crate::director::{Cut, Director};
+    use crate::episode::{serialize_episode, EpisodeMetadata};
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Async");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        dir.add_cut(Cut::new("c2", 5.0, 9.0));
+        let meta = EpisodeMetadata::new("Async", 1, 9.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[tokio::test]
+    async fn test_async_roundtrip_with_progress() {
+        let episode = make_test_episode();
+        let mut buf = Vec::new();
+        let mut reports = Vec::new();
+        let written =
+            serialize_episode_async(Arc::new(episode.clone()), &mut buf, |p| reports.push(p))
+                .await
+                .unwrap();
+        assert_eq!(written, buf.len());
+        assert_eq!(reports.last().unwrap().fraction(), Some(1.0));
+
+        // Byte-identical to the blocking writer
+        let mut sync_buf = Vec::new();
+        serialize_episode(&episode, &mut sync_buf).unwrap();
+        assert_eq!(buf, sync_buf);
+
+        let mut last = None;
+        let restored = deserialize_episode_async(&mut buf.as_slice(), |p| last = Some(p))
+            .await
+            .unwrap();
+        assert_eq!(restored.metadata.title, "Async");
+        assert_eq!(restored.director.cut_count(), 2);
+        assert_eq!(last.unwrap().bytes, buf.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_async_reads_chunked() {
+        let mut buf = Vec::new();
+        serialize_episode_chunked(&make_test_episode(), &mut buf).unwrap();
+        let restored = deserialize_episode_async(&mut buf.as_slice(), |p| {
+            assert!(p.total.is_none());
+        })
+        .await
+        .unwrap();
+        assert_eq!(restored.director.cut_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_async_truncated_fails() {
+        let mut buf = Vec::new();
+        serialize_episode(&make_test_episode(), &mut buf).unwrap();
+        buf.truncate(buf.len() - 4);
+        assert!(deserialize_episode_async(&mut buf.as_slice(), |_| {})
+            .await
+            .is_err());
+
+        // A size field claiming 4 GiB fails at EOF without reserving it
+        buf[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = deserialize_episode_async(&mut buf.as_slice(), |_| {})
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "cdn")]
+    #[tokio::test]
+    async fn test_fetch_segments_async() {
+        use crate::cdn_bridge::{build_segment_manifest, SegmentAssembler, SegmentPolicy};
+
+        let (manifest, blobs) =
+            build_segment_manifest(&make_test_episode(), SegmentPolicy::PerCut).unwrap();
+        let total = manifest.total_bytes();
+        let mut assembler = SegmentAssembler::new(manifest);
+        let mut reports = Vec::new();
+        fetch_segments_async(
+            &mut assembler,
+            |segment| {
+                let bytes = blobs[segment.index as usize].clone();
+                async move { Ok(bytes) }
+            },
+            |p| reports.push(p),
+        )
+        .await
+        .unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].bytes, total);
+        assert_eq!(assembler.assemble().unwrap().director.cut_count(), 2);
+
+        // A corrupted download is rejected and the segment stays missing
+        let mut assembler = SegmentAssembler::new(assembler.manifest().clone());
+        let failed =
+            fetch_segments_async(&mut assembler, |_| async { Ok(vec![0u8; 4]) }, |_| {}).await;
+        assert!(failed.is_err());
+        assert_eq!(assembler.next_missing(), Some(0));
+    }
+}
@@ -0,0 +1,425 @@
+//! Audio mixing timeline: clips on named buses with keyframed volume and pan, BGM ducking
+//! under dialogue, and SFX triggers pinned to cuts. Evaluated into an `AudioState` per
+//! frame that a player hands to its mixer.
+
+use alice_sdf::animation::Track;
+use serde::{Deserialize, Serialize};
+
+use crate::director::{CutId, Director};
+
+/// Background music bus.
+pub const BUS_BGM: &str = "bgm";
+/// Sound effects bus.
+pub const BUS_SFX: &str = "sfx";
+/// Dialogue bus; clips on it duck buses that have `Ducking` set, like dialogue lines do.
+pub const BUS_DIALOGUE: &str = "dialogue";
+
+/// How a bus gets quieter while dialogue plays.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ducking {
+    /// Gain multiplier at full duck (0.0..1.0).
+    pub level: f32,
+    /// Seconds to reach full duck; the ramp ends as the line starts.
+    pub attack: f32,
+    /// Seconds to recover after the line ends.
+    pub release: f32,
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self {
+            level: 0.3,
+            attack: 0.2,
+            release: 0.5,
+        }
+    }
+}
+
+/// A mixer bus.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bus {
+    pub name: String,
+    pub volume: f32,
+    pub muted: bool,
+    pub ducking: Option<Ducking>,
+}
+
+impl Bus {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            volume: 1.0,
+            muted: false,
+            ducking: None,
+        }
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume.max(0.0);
+        self
+    }
+
+    pub fn with_ducking(mut self, ducking: Ducking) -> Self {
+        self.ducking = Some(ducking);
+        self
+    }
+}
+
+/// A sound placed on the episode timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioClip {
+    /// Asset identifier the mixer resolves (file path, bank key, ...).
+    pub source: String,
+    pub bus: String,
+    /// Episode time the clip starts.
+    pub start: f32,
+    pub duration: f32,
+    /// Seconds into the source at `start`.
+    pub source_offset: f32,
+    /// Volume over clip-local time; 1.0 when absent.
+    pub volume: Option<Track>,
+    /// Pan (-1 left .. 1 right) over clip-local time; centred when absent.
+    pub pan: Option<Track>,
+}
+
+impl AudioClip {
+    pub fn new(
+        source: impl Into<String>,
+        bus: impl Into<String>,
+        start: f32,
+        duration: f32,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            bus: bus.into(),
+            start,
+            duration: duration.max(0.0),
+            source_offset: 0.0,
+            volume: None,
+            pan: None,
+        }
+    }
+
+    pub fn with_source_offset(mut self, offset: f32) -> Self {
+        self.source_offset = offset.max(0.0);
+        self
+    }
+
+    pub fn with_volume(mut self, track: Track) -> Self {
+        self.volume = Some(track);
+        self
+    }
+
+    pub fn with_pan(mut self, track: Track) -> Self {
+        self.pan = Some(track);
+        self
+    }
+
+    /// `[start, start + duration)`.
+    #[inline]
+    pub fn contains_time(&self, time: f32) -> bool {
+        time >= self.start && time < self.start + self.duration
+    }
+}
+
+/// A one-shot sound fired at an offset into a cut; moves with the cut when it is retimed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SfxTrigger {
+    pub cut: CutId,
+    /// Seconds after the cut starts.
+    pub offset: f32,
+    pub source: String,
+    pub bus: String,
+    pub duration: f32,
+    pub volume: f32,
+    pub pan: f32,
+}
+
+impl SfxTrigger {
+    pub fn new(cut: CutId, offset: f32, source: impl Into<String>, duration: f32) -> Self {
+        Self {
+            cut,
+            offset,
+            source: source.into(),
+            bus: BUS_SFX.to_string(),
+            duration: duration.max(0.0),
+            volume: 1.0,
+            pan: 0.0,
+        }
+    }
+
+    pub fn with_bus(mut self, bus: impl Into<String>) -> Self {
+        self.bus = bus.into();
+        self
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume.max(0.0);
+        self
+    }
+
+    pub fn with_pan(mut self, pan: f32) -> Self {
+        self.pan = pan.clamp(-1.0, 1.0);
+        self
+    }
+}
+
+/// One sound the mixer should be playing at the evaluated instant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Voice {
+    pub source: String,
+    pub bus: String,
+    /// Seconds into the source.
+    pub position: f32,
+    /// Final gain: clip volume × bus volume × ducking.
+    pub gain: f32,
+    pub pan: f32,
+}
+
+/// Mixer input for one instant.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct AudioState {
+    pub time: f32,
+    pub voices: Vec<Voice>,
+    /// Effective gain per bus (volume × ducking, 0 when muted), in bus order.
+    pub bus_gains: Vec<(String, f32)>,
+}
+
+impl AudioState {
+    pub fn bus_gain(&self, bus: &str) -> Option<f32> {
+        self.bus_gains
+            .iter()
+            .find(|(n, _)| n == bus)
+            .map(|(_, g)| *g)
+    }
+}
+
+/// Buses, clips and cut-relative triggers for an episode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioTimeline {
+    pub buses: Vec<Bus>,
+    pub clips: Vec<AudioClip>,
+    pub triggers: Vec<SfxTrigger>,
+}
+
+impl Default for AudioTimeline {
+    /// BGM (ducked under dialogue), SFX and dialogue buses.
+    fn default() -> Self {
+        Self {
+            buses: vec![
+                Bus::new(BUS_BGM).with_ducking(Ducking::default()),
+                Bus::new(BUS_SFX),
+                Bus::new(BUS_DIALOGUE),
+            ],
+            clips: Vec::new(),
+            triggers: Vec::new(),
+        }
+    }
+}
+
+impl AudioTimeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a bus, replacing one with the same name.
+    pub fn with_bus(mut self, bus: Bus) -> Self {
+        self.buses.retain(|b| b.name != bus.name);
+        self.buses.push(bus);
+        self
+    }
+
+    pub fn with_clip(mut self, clip: AudioClip) -> Self {
+        self.clips.push(clip);
+        self
+    }
+
+    pub fn with_trigger(mut self, trigger: SfxTrigger) -> Self {
+        self.triggers.push(trigger);
+        self
+    }
+
+    pub fn bus(&self, name: &str) -> Option<&Bus> {
+        self.buses.iter().find(|b| b.name == name)
+    }
+
+    pub fn bus_mut(&mut self, name: &str) -> Option<&mut Bus> {
+        self.buses.iter_mut().find(|b| b.name == name)
+    }
+
+    /// Dialogue spans that trigger ducking: director dialogue plus dialogue-bus clips.
+    fn duck_spans<'a>(&'a self, director: &'a Director) -> impl Iterator<Item = (f32, f32)> + 'a {
+        let lines = director
+            .episode
+            .dialogue
+            .iter()
+            .map(|l| (l.start_time, l.end_time));
+        let clips = self
+            .clips
+            .iter()
+            .filter(|c| c.bus == BUS_DIALOGUE)
+            .map(|c| (c.start, c.start + c.duration));
+        lines.chain(clips)
+    }
+
+    /// Ducking gain multiplier for `ducking` at `time` (1.0 = no duck).
+    ///
+    /// Stateless, so scrubbing and linear playback agree.
+    pub fn duck_gain(&self, director: &Director, ducking: &Ducking, time: f32) -> f32 {
+        let amount = self
+            .duck_spans(director)
+            .map(|(start, end)| {
+                if time >= start && time <= end {
+                    1.0
+                } else if time < start {
+                    ramp(start - time, ducking.attack)
+                } else {
+                    ramp(time - end, ducking.release)
+                }
+            })
+            .fold(0.0f32, f32::max);
+        1.0 - amount * (1.0 - ducking.level.clamp(0.0, 1.0))
+    }
+
+    /// Mixer state at `time`; triggers whose cut no longer exists are skipped.
+    pub fn evaluate(&self, director: &Director, time: f32) -> AudioState {
+        let bus_gains: Vec<(String, f32)> = self
+            .buses
+            .iter()
+            .map(|bus| {
+                let gain = if bus.muted {
+                    0.0
+                } else {
+                    let duck = bus
+                        .ducking
+                        .map_or(1.0, |d| self.duck_gain(director, &d, time));
+                    bus.volume * duck
+                };
+                (bus.name.clone(), gain)
+            })
+            .collect();
+        let bus_gain = |name: &str| {
+            bus_gains
+                .iter()
+                .find(|(n, _)| n == name)
+                .map_or(1.0, |(_, g)| *g)
+        };
+
+        let mut voices = Vec::new();
+        for clip in self.clips.iter().filter(|c| c.contains_time(time)) {
+            let local = time - clip.start;
+            let volume = clip.volume.as_ref().map_or(1.0, |t| t.evaluate(local));
+            let pan = clip.pan.as_ref().map_or(0.0, |t| t.evaluate(local));
+            voices.push(Voice {
+                source: clip.source.clone(),
+                bus: clip.bus.clone(),
+                position: clip.source_offset + local,
+                gain: volume.max(0.0) * bus_gain(&clip.bus),
+                pan: pan.clamp(-1.0, 1.0),
+            });
+        }
+        for trigger in &self.triggers {
+            let Some(cut) = director.get_cut(trigger.cut) else {
+                continue;
+            };
+            let start = cut.start_time + trigger.offset;
+            if time >= start && time < start + trigger.duration {
+                voices.push(Voice {
+                    source: trigger.source.clone(),
+                    bus: trigger.bus.clone(),
+                    position: time - start,
+                    gain: trigger.volume * bus_gain(&trigger.bus),
+                    pan: trigger.pan,
+                });
+            }
+        }
+        AudioState {
+            time,
+            voices,
+            bus_gains,
+        }
+    }
+
+    /// Mixer state for `frame` at `fps`.
+    #[inline]
+    pub fn evaluate_frame(&self, director: &Director, frame: u32, fps: f32) -> AudioState {
+        self.evaluate(director, frame as f32 / fps)
+    }
+}
+
+/// 1.0 at distance 0, falling linearly to 0.0 at `width`.
+#[inline]
+fn ramp(distance: f32, width: f32) -> f32 {
+    if width <= 0.0 {
+        0.0
+    } else {
+        (1.0 - distance / width).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, DialogueLine};
+    use alice_sdf::animation::Keyframe;
+
+    fn director() -> Director {
+        let mut dir = Director::new("Audio");
+        dir.add_cut(Cut::new("a", 0.0, 4.0));
+        dir.add_cut(Cut::new("b", 4.0, 8.0));
+        dir.add_dialogue(DialogueLine::new(2.0, 3.0, "Line"));
+        dir
+    }
+
+    #[test]
+    fn test_clip_volume_and_pan_tracks() {
+        let mut fade = Track::new("volume");
+        fade.add_keyframe(Keyframe::new(0.0, 0.0));
+        fade.add_keyframe(Keyframe::new(1.0, 1.0));
+        let mut pan = Track::new("pan");
+        pan.add_keyframe(Keyframe::new(0.0, -1.0));
+        pan.add_keyframe(Keyframe::new(1.0, 1.0));
+        let audio = AudioTimeline::new().with_clip(
+            AudioClip::new("door.wav", BUS_SFX, 5.0, 2.0)
+                .with_source_offset(0.25)
+                .with_volume(fade)
+                .with_pan(pan),
+        );
+        let state = audio.evaluate(&director(), 5.5);
+        let voice = &state.voices[0];
+        assert!((voice.gain - 0.5).abs() < 1e-5);
+        assert!(voice.pan.abs() < 1e-5);
+        assert!((voice.position - 0.75).abs() < 1e-5);
+        assert!(audio.evaluate(&director(), 7.0).voices.is_empty());
+    }
+
+    #[test]
+    fn test_bgm_ducks_under_dialogue() {
+        let audio = AudioTimeline::new().with_clip(AudioClip::new("theme.ogg", BUS_BGM, 0.0, 8.0));
+        let dir = director();
+        let gain = |t: f32| audio.evaluate(&dir, t).voices[0].gain;
+        assert_eq!(gain(1.0), 1.0);
+        assert!((gain(2.5) - 0.3).abs() < 1e-5);
+        // Halfway through attack and release
+        assert!((gain(1.9) - 0.65).abs() < 1e-4);
+        assert!((gain(3.25) - 0.65).abs() < 1e-4);
+        assert_eq!(gain(4.0), 1.0);
+        assert_eq!(audio.evaluate(&dir, 2.5).bus_gain(BUS_SFX), Some(1.0));
+    }
+
+    #[test]
+    fn test_sfx_trigger_follows_cut() {
+        let mut dir = director();
+        let (b, _) = dir.cuts().nth(1).unwrap();
+        let audio = AudioTimeline::new()
+            .with_trigger(SfxTrigger::new(b, 0.5, "whoosh.wav", 1.0).with_pan(0.5));
+        assert_eq!(audio.evaluate(&dir, 4.75).voices[0].position, 0.25);
+        dir.get_cut_mut(b).unwrap().shift_time(1.0);
+        assert!(audio.evaluate(&dir, 4.75).voices.is_empty());
+        assert_eq!(audio.evaluate(&dir, 5.75).voices[0].pan, 0.5);
+
+        let mut muted = audio.clone();
+        muted.bus_mut(BUS_SFX).unwrap().muted = true;
+        assert_eq!(muted.evaluate(&dir, 5.75).voices[0].gain, 0.0);
+    }
+}
@@ -0,0 +1,417 @@
+//! Bake procedural motion (constraints, spring follows, follow-cams or any custom
+//! solver) into plain keyframes so episodes play back without the live solvers.
+
+use std::io;
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use glam::Vec3;
+
+use crate::director::{CutId, Director};
+use crate::export::FrameRange;
+use crate::keys::reduce_track;
+use crate::performance::Performance;
+use crate::scene::{ActorId, SceneGraph};
+
+/// Anything that produces channel values over time. Sources may keep state
+/// (simulations); `reset` is called once before sampling starts. Actors are sampled
+/// through the [`Performance`], so clip tracks of the cut at each time apply.
+pub trait BakeSource {
+    /// Channel names, in the order `sample` returns values.
+    fn channels(&self) -> Vec<String>;
+
+    fn reset(&mut self, _scene: Performance<'_>, _time: f32) {}
+
+    /// Values at `time`. Called with increasing times, one call per baked frame.
+    fn sample(&mut self, scene: Performance<'_>, time: f32) -> Vec<f32>;
+}
+
+fn xyz(prefix: &str) -> Vec<String> {
+    ["x", "y", "z"]
+        .iter()
+        .map(|axis| format!("{prefix}.{axis}"))
+        .collect()
+}
+
+/// Point constraint: follow another actor's world position plus an offset,
+/// blended with `rest` by `weight`. Writes "translate.x/y/z".
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointConstraint {
+    pub target: ActorId,
+    pub offset: Vec3,
+    /// Position used where the constraint is off (weight 0).
+    pub rest: Vec3,
+    pub weight: f32,
+}
+
+impl PointConstraint {
+    pub fn new(target: ActorId, offset: Vec3) -> Self {
+        Self {
+            target,
+            offset,
+            rest: Vec3::ZERO,
+            weight: 1.0,
+        }
+    }
+}
+
+impl BakeSource for PointConstraint {
+    fn channels(&self) -> Vec<String> {
+        xyz("translate")
+    }
+
+    fn sample(&mut self, scene: Performance<'_>, time: f32) -> Vec<f32> {
+        let followed = scene.actor_position_at(self.target, time) + self.offset;
+        let p = self.rest.lerp(followed, self.weight.clamp(0.0, 1.0));
+        vec![p.x, p.y, p.z]
+    }
+}
+
+/// Critically damped spring chasing a moving goal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Spring {
+    position: Vec3,
+    velocity: Vec3,
+    time: f32,
+}
+
+impl Spring {
+    /// Advance to `time` toward `goal` with angular frequency `omega` (higher = tighter).
+    fn update(&mut self, goal: Vec3, time: f32, omega: f32) -> Vec3 {
+        let dt = (time - self.time).max(0.0);
+        self.time = time;
+        let x = omega * dt;
+        let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+        let delta = self.position - goal;
+        let temp = (self.velocity + delta * omega) * dt;
+        self.velocity = (self.velocity - temp * omega) * decay;
+        self.position = goal + (delta + temp) * decay;
+        self.position
+    }
+}
+
+/// Spring bone: lags behind a target actor with overshoot-free secondary motion
+/// (antennae, dangling props). Writes "translate.x/y/z".
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpringFollow {
+    pub target: ActorId,
+    pub offset: Vec3,
+    /// Response speed in 1/s; larger follows more tightly.
+    pub stiffness: f32,
+    spring: Option<Spring>,
+}
+
+impl SpringFollow {
+    pub fn new(target: ActorId, offset: Vec3, stiffness: f32) -> Self {
+        Self {
+            target,
+            offset,
+            stiffness,
+            spring: None,
+        }
+    }
+}
+
+impl BakeSource for SpringFollow {
+    fn channels(&self) -> Vec<String> {
+        xyz("translate")
+    }
+
+    fn reset(&mut self, scene: Performance<'_>, time: f32) {
+        self.spring = Some(Spring {
+            position: scene.actor_position_at(self.target, time) + self.offset,
+            velocity: Vec3::ZERO,
+            time,
+        });
+    }
+
+    fn sample(&mut self, scene: Performance<'_>, time: f32) -> Vec<f32> {
+        let goal = scene.actor_position_at(self.target, time) + self.offset;
+        let spring = self.spring.get_or_insert(Spring {
+            position: goal,
+            velocity: Vec3::ZERO,
+            time,
+        });
+        let p = spring.update(goal, time, self.stiffness);
+        vec![p.x, p.y, p.z]
+    }
+}
+
+/// Follow-cam: trails a subject at `offset` with spring lag and aims at it.
+/// Writes "position.x/y/z" and "target.x/y/z".
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowCamera {
+    pub subject: ActorId,
+    /// Camera position relative to the subject.
+    pub offset: Vec3,
+    /// Aim point relative to the subject.
+    pub aim_offset: Vec3,
+    /// Position response speed in 1/s (aim is always exact).
+    pub stiffness: f32,
+    spring: Option<Spring>,
+}
+
+impl FollowCamera {
+    pub fn new(subject: ActorId, offset: Vec3, stiffness: f32) -> Self {
+        Self {
+            subject,
+            offset,
+            aim_offset: Vec3::ZERO,
+            stiffness,
+            spring: None,
+        }
+    }
+
+    pub fn with_aim_offset(mut self, aim_offset: Vec3) -> Self {
+        self.aim_offset = aim_offset;
+        self
+    }
+}
+
+impl BakeSource for FollowCamera {
+    fn channels(&self) -> Vec<String> {
+        let mut channels = xyz("position");
+        channels.extend(xyz("target"));
+        channels
+    }
+
+    fn reset(&mut self, scene: Performance<'_>, time: f32) {
+        self.spring = Some(Spring {
+            position: scene.actor_position_at(self.subject, time) + self.offset,
+            velocity: Vec3::ZERO,
+            time,
+        });
+    }
+
+    fn sample(&mut self, scene: Performance<'_>, time: f32) -> Vec<f32> {
+        let subject = scene.actor_position_at(self.subject, time);
+        let goal = subject + self.offset;
+        let spring = self.spring.get_or_insert(Spring {
+            position: goal,
+            velocity: Vec3::ZERO,
+            time,
+        });
+        let p = spring.update(goal, time, self.stiffness);
+        let aim = subject + self.aim_offset;
+        vec![p.x, p.y, p.z, aim.x, aim.y, aim.z]
+    }
+}
+
+/// Drop keys that linear interpolation between the kept keys reproduces within `tolerance`.
+#[inline]
+pub fn reduce_linear_keys(track: &mut Track, tolerance: f32) {
+    reduce_track(track, tolerance);
+}
+
+/// Sample `source` at every frame of `range` into a timeline (one track per channel,
+/// keys at absolute times). `tolerance > 0` drops linearly redundant keys.
+pub fn bake_source(
+    source: &mut dyn BakeSource,
+    scene: Performance<'_>,
+    range: FrameRange,
+    tolerance: f32,
+) -> io::Result<Timeline> {
+    let names = source.channels();
+    let mut tracks: Vec<Track> = names.iter().map(|n| Track::new(n)).collect();
+    source.reset(scene, range.start);
+    for i in 0..range.frame_count() {
+        let time = range.frame_time(i);
+        let values = source.sample(scene, time);
+        if values.len() != tracks.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "bake source returned {} values for {} channels at {time}s",
+                    values.len(),
+                    tracks.len()
+                ),
+            ));
+        }
+        for (track, value) in tracks.iter_mut().zip(values) {
+            track.add_keyframe(Keyframe::new(time, value));
+        }
+    }
+    let mut timeline = Timeline::new("baked");
+    for mut track in tracks {
+        if tolerance > 0.0 {
+            reduce_linear_keys(&mut track, tolerance);
+        }
+        timeline.add_track(track);
+    }
+    Ok(timeline)
+}
+
+/// Replace same-named tracks of `target` with those of `baked`; other tracks are kept.
+fn overlay(target: &mut Timeline, baked: Timeline) {
+    for track in baked.tracks {
+        match target.tracks.iter_mut().find(|t| t.name == track.name) {
+            Some(existing) => *existing = track,
+            None => target.add_track(track),
+        }
+    }
+}
+
+/// Bake `source`, playing `director`'s cuts, and write its channels into an actor's
+/// timeline.
+pub fn bake_into_actor(
+    scene: &mut SceneGraph,
+    director: &Director,
+    actor: ActorId,
+    source: &mut dyn BakeSource,
+    range: FrameRange,
+    tolerance: f32,
+) -> io::Result<()> {
+    let baked = bake_source(source, Performance::new(scene, director), range, tolerance)?;
+    let actor = scene
+        .get_actor_mut(actor)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no actor {}", actor.0)))?;
+    let name = actor.name.clone();
+    overlay(
+        actor.timeline.get_or_insert_with(|| Timeline::new(&name)),
+        baked,
+    );
+    Ok(())
+}
+
+/// Bake a camera source over the whole cut into its camera track.
+///
+/// "position.*" and "target.*" channels go to the matching camera timelines and "fov"
+/// replaces the fov track; keys are stored relative to the cut start.
+pub fn bake_into_cut(
+    director: &mut Director,
+    scene: &SceneGraph,
+    cut: CutId,
+    source: &mut dyn BakeSource,
+    fps: f32,
+    tolerance: f32,
+) -> io::Result<()> {
+    let (start, end) = director
+        .get_cut(cut)
+        .map(|c| (c.start_time, c.end_time))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no cut {}", cut.0)))?;
+    // Include the final instant so the camera lands exactly on the cut end
+    let range = FrameRange::new(start, end + 0.5 / fps, fps);
+    let baked = bake_source(source, Performance::new(scene, director), range, tolerance)?;
+    let Some(cut) = director.get_cut_mut(cut) else {
+        return Ok(());
+    };
+    let (mut position, mut target) = (Timeline::new("baked"), Timeline::new("baked"));
+    for mut track in baked.tracks {
+        for key in &mut track.keyframes {
+            key.time -= start;
+        }
+        if track.name.starts_with("position.") {
+            position.add_track(track);
+        } else if track.name.starts_with("target.") {
+            target.add_track(track);
+        } else if track.name == "fov" {
+            cut.camera.fov_track = track;
+        }
+    }
+    overlay(&mut cut.camera.position_timeline, position);
+    overlay(&mut cut.camera.target_timeline, target);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::Cut;
+    use crate::scene::Actor;
+    use alice_sdf::SdfNode;
+
+    fn runner_scene() -> (SceneGraph, ActorId, ActorId) {
+        let mut sg = SceneGraph::new();
+        let mut tl = Timeline::new("run");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(1.0, 4.0));
+        x.add_keyframe(Keyframe::new(2.0, 4.0));
+        tl.add_track(x);
+        let runner = sg.add_actor(Actor::new("runner", SdfNode::sphere(0.5)).with_timeline(tl));
+        let prop = sg.add_actor(Actor::new("flag", SdfNode::box3d(0.1, 0.5, 0.1)));
+        (sg, runner, prop)
+    }
+
+    #[test]
+    fn test_point_constraint_bakes_and_reduces() {
+        let (mut sg, runner, prop) = runner_scene();
+        let mut constraint = PointConstraint::new(runner, Vec3::new(0.0, 1.0, 0.0));
+        let range = FrameRange::new(0.0, 2.01, 24.0);
+        let dense = bake_source(&mut constraint, Performance::from(&sg), range, 0.0).unwrap();
+        assert_eq!(dense.tracks[0].keyframes.len(), 49);
+        let director = Director::new("Bake");
+        bake_into_actor(&mut sg, &director, prop, &mut constraint, range, 1e-4).unwrap();
+        let tl = sg.get_actor(prop).unwrap().timeline.as_ref().unwrap();
+        // Piecewise linear motion reduces to its corners
+        assert_eq!(tl.tracks[0].keyframes.len(), 3);
+        assert!((tl.get_value("translate.x", 0.5).unwrap() - 2.0).abs() < 1e-4);
+        assert_eq!(tl.get_value("translate.y", 1.5), Some(1.0));
+
+        let err = bake_into_actor(&mut sg, &director, ActorId(9), &mut constraint, range, 0.0)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_constraint_follows_clip_tracks() {
+        use crate::clip::{AnimationClip, ClipInstance, ClipTrack};
+        let (mut sg, runner, prop) = runner_scene();
+        let mut dash = Timeline::new("dash");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 10.0));
+        x.add_keyframe(Keyframe::new(1.0, 14.0));
+        dash.add_track(x);
+        let dash = AnimationClip::new("dash", dash);
+        let track = ClipTrack::new(runner).with_clip(ClipInstance::new(&dash, 0.0));
+        sg.clips.insert(dash);
+        let mut director = Director::new("Dash");
+        director.add_cut(Cut::new("dash", 1.0, 2.0).with_clip_track(track));
+
+        let mut constraint = PointConstraint::new(runner, Vec3::ZERO);
+        let range = FrameRange::new(0.0, 2.0, 24.0);
+        bake_into_actor(&mut sg, &director, prop, &mut constraint, range, 0.0).unwrap();
+        let tl = sg.get_actor(prop).unwrap().timeline.as_ref().unwrap();
+        // Halfway through the dash clip, not the runner's own hold at 4
+        assert!((tl.get_value("translate.x", 1.5).unwrap() - 12.0).abs() < 1e-4);
+        assert!((tl.get_value("translate.x", 0.5).unwrap() - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_spring_follow_lags_then_settles() {
+        let (sg, runner, _) = runner_scene();
+        let mut spring = SpringFollow::new(runner, Vec3::ZERO, 8.0);
+        let tl = bake_source(
+            &mut spring,
+            Performance::from(&sg),
+            FrameRange::new(0.0, 2.01, 24.0),
+            0.0,
+        )
+        .unwrap();
+        let x = |t: f32| tl.get_value("translate.x", t).unwrap();
+        assert!(x(0.5) < 2.0);
+        assert!(x(0.5) > 0.5);
+        // No overshoot past the stop, and settled by the end
+        assert!((0..=48).all(|i| x(i as f32 / 24.0) <= 4.0 + 1e-4));
+        assert!((x(2.0) - 4.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_follow_camera_into_cut() {
+        let (sg, runner, _) = runner_scene();
+        let mut director = Director::new("Chase");
+        let cut = director.add_cut(Cut::new("chase", 1.0, 2.0));
+        let mut cam = FollowCamera::new(runner, Vec3::new(0.0, 2.0, 6.0), 20.0)
+            .with_aim_offset(Vec3::new(0.0, 0.5, 0.0));
+        bake_into_cut(&mut director, &sg, cut, &mut cam, 24.0, 0.0).unwrap();
+        let state = director.evaluate(&sg, 1.5).camera_state;
+        assert!((state.target - Vec3::new(4.0, 0.5, 0.0)).length() < 1e-4);
+        assert!((state.position - Vec3::new(4.0, 2.0, 6.0)).length() < 1e-4);
+        let track = &director
+            .get_cut(cut)
+            .unwrap()
+            .camera
+            .position_timeline
+            .tracks[0];
+        assert_eq!(track.keyframes[0].time, 0.0);
+    }
+}
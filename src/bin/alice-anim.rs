@@ -0,0 +1,10 @@
+//! `alice-anim`: inspect, validate, render, convert and diff ANIM episodes.
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let stdout = std::io::stdout();
+    if let Err(e) = alice_animation::cli::run(&args, &mut stdout.lock()) {
+        eprintln!("alice-anim: {}", e);
+        std::process::exit(1);
+    }
+}
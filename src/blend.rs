@@ -0,0 +1,115 @@
+//! Crossfades between actor timelines.
+//!
+//! Switching an actor from "idle" to "run" at a cut boundary pops the pose. A
+//! [`Crossfade`] keeps the outgoing timeline and blends every track from it into the
+//! actor's current timeline over a window, with eased weights.
+
+use alice_sdf::animation::Timeline;
+use serde::{Deserialize, Serialize};
+
+use crate::cycle::TimelineCycle;
+use crate::layer::rest_value;
+
+/// Outgoing timeline plus the window over which it hands over to the actor's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Crossfade {
+    pub from: Timeline,
+    /// Cycle the outgoing timeline kept looping on.
+    pub from_cycle: Option<TimelineCycle>,
+    /// Scene time the fade begins; before it the outgoing timeline plays alone.
+    pub start: f32,
+    pub duration: f32,
+}
+
+impl Crossfade {
+    pub fn new(from: Timeline, start: f32, duration: f32) -> Self {
+        Self {
+            from,
+            from_cycle: None,
+            start,
+            duration,
+        }
+    }
+
+    /// Fade over `frames` frames at `fps`.
+    pub fn over_frames(from: Timeline, start: f32, frames: u32, fps: f32) -> Self {
+        Self::new(from, start, frames as f32 / fps.max(1e-3))
+    }
+
+    pub fn with_cycle(mut self, cycle: TimelineCycle) -> Self {
+        self.from_cycle = Some(cycle);
+        self
+    }
+
+    #[inline]
+    pub fn end(&self) -> f32 {
+        self.start + self.duration.max(0.0)
+    }
+
+    /// Weight of the incoming timeline at `time`: 0 before the fade, 1 after, eased in
+    /// between.
+    pub fn weight(&self, time: f32) -> f32 {
+        if self.duration <= 0.0 {
+            return if time >= self.start { 1.0 } else { 0.0 };
+        }
+        let t = ((time - self.start) / self.duration).clamp(0.0, 1.0);
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    /// True until the incoming timeline has fully taken over.
+    #[inline]
+    pub fn is_active(&self, time: f32) -> bool {
+        time < self.end()
+    }
+
+    /// Blend track `name` at `time` between the outgoing timeline and `to` (the incoming
+    /// value). A track keyed on one side only blends against its rest value.
+    pub fn blend(&self, name: &str, time: f32, to: Option<f32>) -> Option<f32> {
+        let from_time = match &self.from_cycle {
+            Some(cycle) => cycle.map_time(time, self.from.duration()),
+            None => time,
+        };
+        let from = self.from.get_value(name, from_time);
+        if from.is_none() && to.is_none() {
+            return None;
+        }
+        let rest = rest_value(name);
+        let (a, b) = (from.unwrap_or(rest), to.unwrap_or(rest));
+        Some(a + (b - a) * self.weight(time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::animation::{Keyframe, Track};
+
+    #[test]
+    fn test_weights_ease_over_window() {
+        let fade = Crossfade::over_frames(Timeline::new("idle"), 1.0, 12, 24.0);
+        assert_eq!(fade.end(), 1.5);
+        assert_eq!(fade.weight(0.5), 0.0);
+        assert!((fade.weight(1.25) - 0.5).abs() < 1e-6);
+        assert!(fade.weight(1.1) < 0.2);
+        assert_eq!(fade.weight(2.0), 1.0);
+        assert!(fade.is_active(1.4) && !fade.is_active(1.5));
+        let cut = Crossfade::new(Timeline::new("idle"), 1.0, 0.0);
+        assert_eq!((cut.weight(0.99), cut.weight(1.0)), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_blend_shared_and_one_sided_tracks() {
+        let mut idle = Timeline::new("idle");
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.0));
+        y.add_keyframe(Keyframe::new(1.0, 1.0));
+        idle.add_track(y);
+        let fade = Crossfade::new(idle, 0.0, 2.0).with_cycle(TimelineCycle::repeat());
+        // Halfway through: the looping outgoing value is back at 0, the incoming one is 2
+        let shared = fade.blend("translate.y", 1.0, Some(2.0)).unwrap();
+        assert!((shared - 1.0).abs() < 1e-5);
+        let incoming_only = fade.blend("scale", 1.0, Some(2.0)).unwrap();
+        assert!((incoming_only - 1.5).abs() < 1e-5);
+        assert_eq!(fade.blend("rotate.y", 1.0, None), None);
+    }
+}
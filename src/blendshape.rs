@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use serde::{Deserialize, Serialize};
+
+use crate::expression::{Expression, ExpressionTrack};
+use crate::lip_sync::{LipSyncTrack, Phoneme};
+
+/// External rig blendshape convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlendshapeStandard {
+    /// Apple ARKit 52 face blendshapes (`jawOpen`, `mouthPucker`, ...).
+    Arkit,
+    /// VRM 1.0 preset expressions (`aa`, `ih`, `ou`, `ee`, `oh`, `blink`, `happy`, ...).
+    Vrm,
+}
+
+/// Blendshape weights for a viseme.
+pub fn phoneme_weights(
+    phoneme: Phoneme,
+    standard: BlendshapeStandard,
+) -> &'static [(&'static str, f32)] {
+    match standard {
+        BlendshapeStandard::Arkit => match phoneme {
+            Phoneme::Closed => &[],
+            Phoneme::A => &[
+                ("jawOpen", 0.7),
+                ("mouthStretchLeft", 0.2),
+                ("mouthStretchRight", 0.2),
+            ],
+            Phoneme::I => &[
+                ("jawOpen", 0.15),
+                ("mouthStretchLeft", 0.6),
+                ("mouthStretchRight", 0.6),
+                ("mouthSmileLeft", 0.2),
+                ("mouthSmileRight", 0.2),
+            ],
+            Phoneme::U => &[
+                ("jawOpen", 0.15),
+                ("mouthPucker", 0.8),
+                ("mouthFunnel", 0.3),
+            ],
+            Phoneme::E => &[
+                ("jawOpen", 0.4),
+                ("mouthStretchLeft", 0.4),
+                ("mouthStretchRight", 0.4),
+            ],
+            Phoneme::O => &[("jawOpen", 0.5), ("mouthFunnel", 0.7)],
+            Phoneme::M => &[
+                ("mouthClose", 0.6),
+                ("mouthPressLeft", 0.7),
+                ("mouthPressRight", 0.7),
+            ],
+            Phoneme::F => &[
+                ("jawOpen", 0.05),
+                ("mouthRollLower", 0.7),
+                ("mouthUpperUpLeft", 0.2),
+                ("mouthUpperUpRight", 0.2),
+            ],
+            Phoneme::W => &[("jawOpen", 0.1), ("mouthPucker", 1.0)],
+            Phoneme::N => &[("jawOpen", 0.1)],
+            Phoneme::S => &[
+                ("jawOpen", 0.05),
+                ("mouthStretchLeft", 0.5),
+                ("mouthStretchRight", 0.5),
+            ],
+        },
+        BlendshapeStandard::Vrm => match phoneme {
+            Phoneme::Closed | Phoneme::M => &[],
+            Phoneme::A => &[("aa", 1.0)],
+            Phoneme::I => &[("ih", 1.0)],
+            Phoneme::U => &[("ou", 1.0)],
+            Phoneme::E => &[("ee", 1.0)],
+            Phoneme::O => &[("oh", 1.0)],
+            Phoneme::F => &[("ih", 0.2)],
+            Phoneme::W => &[("ou", 0.8)],
+            Phoneme::N => &[("aa", 0.1)],
+            Phoneme::S => &[("ih", 0.4)],
+        },
+    }
+}
+
+/// Blendshape weights for an expression channel at full strength.
+pub fn expression_weights(
+    expression: Expression,
+    standard: BlendshapeStandard,
+) -> &'static [(&'static str, f32)] {
+    match standard {
+        BlendshapeStandard::Arkit => match expression {
+            Expression::Smile => &[
+                ("mouthSmileLeft", 1.0),
+                ("mouthSmileRight", 1.0),
+                ("cheekSquintLeft", 0.3),
+                ("cheekSquintRight", 0.3),
+            ],
+            Expression::Anger => &[
+                ("browDownLeft", 1.0),
+                ("browDownRight", 1.0),
+                ("mouthFrownLeft", 0.3),
+                ("mouthFrownRight", 0.3),
+            ],
+            Expression::Surprise => &[
+                ("browInnerUp", 1.0),
+                ("eyeWideLeft", 1.0),
+                ("eyeWideRight", 1.0),
+                ("jawOpen", 0.2),
+            ],
+            Expression::Sadness => &[
+                ("browInnerUp", 0.6),
+                ("mouthFrownLeft", 0.7),
+                ("mouthFrownRight", 0.7),
+            ],
+            Expression::EyeClose => &[("eyeBlinkLeft", 1.0), ("eyeBlinkRight", 1.0)],
+        },
+        BlendshapeStandard::Vrm => match expression {
+            Expression::Smile => &[("happy", 1.0)],
+            Expression::Anger => &[("angry", 1.0)],
+            Expression::Surprise => &[("surprised", 1.0)],
+            Expression::Sadness => &[("sad", 1.0)],
+            Expression::EyeClose => &[("blink", 1.0)],
+        },
+    }
+}
+
+/// Export lip sync (and optional expressions) as blendshape weight curves.
+///
+/// Returns a Timeline with one track per blendshape that is ever non-zero, named after the
+/// blendshape. Viseme weights interpolate between lip sync keyframes like `to_timeline`;
+/// expression weights are added on top and the sum is clamped to 0.0..=1.0.
+pub fn export_blendshapes(
+    lip_sync: &LipSyncTrack,
+    expressions: Option<&ExpressionTrack>,
+    standard: BlendshapeStandard,
+) -> Timeline {
+    // Per-blendshape viseme curves keyed at every lip sync keyframe
+    let mut lip_curves: BTreeMap<&'static str, Track> = BTreeMap::new();
+    for phoneme in Phoneme::ALL {
+        for &(name, _) in phoneme_weights(phoneme, standard) {
+            lip_curves.entry(name).or_insert_with(|| Track::new(name));
+        }
+    }
+    for kf in &lip_sync.phonemes {
+        for (name, track) in lip_curves.iter_mut() {
+            let weight = phoneme_weights(kf.phoneme, standard)
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|&(_, w)| w)
+                .unwrap_or(0.0);
+            track.add_keyframe(Keyframe::new(kf.time, weight));
+        }
+    }
+
+    let mut times: Vec<f32> = lip_sync.phonemes.iter().map(|k| k.time).collect();
+    if let Some(expr) = expressions {
+        times.extend(expr.key_times());
+    }
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    times.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+    let mut curves: BTreeMap<&'static str, Vec<(f32, f32)>> = BTreeMap::new();
+    for &t in &times {
+        let mut weights: BTreeMap<&'static str, f32> = BTreeMap::new();
+        for (&name, track) in &lip_curves {
+            *weights.entry(name).or_default() += track.evaluate(t);
+        }
+        if let Some(expr) = expressions {
+            for expression in Expression::ALL {
+                let amount = expr.weight(expression, t);
+                for &(name, w) in expression_weights(expression, standard) {
+                    *weights.entry(name).or_default() += amount * w;
+                }
+            }
+        }
+        for (name, w) in weights {
+            curves.entry(name).or_default().push((t, w.clamp(0.0, 1.0)));
+        }
+    }
+
+    let mut tl = Timeline::new(&lip_sync.name);
+    for (name, keys) in curves {
+        if keys.iter().all(|&(_, w)| w == 0.0) {
+            continue;
+        }
+        let mut track = Track::new(name);
+        for (t, w) in keys {
+            track.add_keyframe(Keyframe::new(t, w));
+        }
+        tl.add_track(track);
+    }
+    tl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_line() -> LipSyncTrack {
+        let mut lip = LipSyncTrack::new("line");
+        lip.add_phoneme(0.0, Phoneme::A);
+        lip.add_phoneme(0.5, Phoneme::U);
+        lip.add_phoneme(1.0, Phoneme::Closed);
+        lip
+    }
+
+    #[test]
+    fn test_vrm_visemes() {
+        let tl = export_blendshapes(&make_line(), None, BlendshapeStandard::Vrm);
+        assert_eq!(tl.get_value("aa", 0.0), Some(1.0));
+        assert_eq!(tl.get_value("ou", 0.5), Some(1.0));
+        assert_eq!(tl.get_value("aa", 1.0), Some(0.0));
+        assert!(tl.get_value("ee", 0.0).is_none()); // never used
+    }
+
+    #[test]
+    fn test_arkit_with_expressions() {
+        let mut expr = ExpressionTrack::new("face");
+        expr.set_weight(Expression::Smile, 0.0, 1.0);
+        expr.set_weight(Expression::EyeClose, 0.25, 1.0);
+        let tl = export_blendshapes(&make_line(), Some(&expr), BlendshapeStandard::Arkit);
+
+        assert_eq!(tl.get_value("jawOpen", 0.0), Some(0.7));
+        assert_eq!(tl.get_value("mouthPucker", 0.5), Some(0.8));
+        assert_eq!(tl.get_value("mouthSmileLeft", 0.5), Some(1.0));
+        assert_eq!(tl.get_value("eyeBlinkRight", 0.25), Some(1.0));
+    }
+}
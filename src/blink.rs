@@ -0,0 +1,204 @@
+use alice_sdf::animation::{Keyframe, Track};
+use serde::{Deserialize, Serialize};
+
+use crate::expression::{Expression, ExpressionTrack};
+use crate::rng::{EpisodeRng, SeededRng};
+
+/// Fraction of a blink spent closing (eyes open more slowly than they shut).
+const CLOSE_FRACTION: f32 = 0.4;
+
+/// A single generated blink.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Blink {
+    pub start: f32,
+    pub duration: f32,
+}
+
+/// Seeded procedural blink generator.
+///
+/// Same seed and settings always produce the same blinks, so re-renders are stable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlinkGenerator {
+    pub seed: u64,
+    /// Mean seconds between blinks.
+    pub mean_interval: f32,
+    /// Random variation of the interval as a fraction of the mean (0.0..1.0).
+    pub interval_jitter: f32,
+    /// Seconds for one close-open cycle.
+    pub blink_duration: f32,
+    /// Chance (0.0..1.0) that a blink is immediately followed by a second one.
+    pub double_blink_chance: f32,
+    /// Time ranges with no blinking (e.g. dramatic stares).
+    pub suppressed: Vec<(f32, f32)>,
+}
+
+impl BlinkGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            mean_interval: 4.0,
+            interval_jitter: 0.5,
+            blink_duration: 0.15,
+            double_blink_chance: 0.15,
+            suppressed: Vec::new(),
+        }
+    }
+
+    /// Generator for `actor`, seeded from the episode's `blink/<actor>` stream.
+    pub fn for_actor(rng: &EpisodeRng, actor: &str) -> Self {
+        Self::new(rng.seed_for(&format!("blink/{actor}")))
+    }
+
+    /// Set mean interval between blinks.
+    pub fn with_interval(mut self, mean: f32, jitter: f32) -> Self {
+        self.mean_interval = mean.max(0.01);
+        self.interval_jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set blink duration.
+    pub fn with_blink_duration(mut self, duration: f32) -> Self {
+        self.blink_duration = duration.max(0.01);
+        self
+    }
+
+    /// Set double-blink chance.
+    pub fn with_double_blink_chance(mut self, chance: f32) -> Self {
+        self.double_blink_chance = chance.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Suppress blinking in `[start, end)`.
+    pub fn suppress(mut self, start: f32, end: f32) -> Self {
+        self.suppressed.push((start, end));
+        self
+    }
+
+    fn is_suppressed(&self, start: f32, end: f32) -> bool {
+        self.suppressed.iter().any(|&(s, e)| start < e && end > s)
+    }
+
+    /// Generate blinks over `[0, duration)`.
+    pub fn generate(&self, duration: f32) -> Vec<Blink> {
+        let mut rng = SeededRng::new(self.seed);
+        let mut blinks = Vec::new();
+        let mut time = self.next_interval(&mut rng) * 0.5;
+        while time + self.blink_duration <= duration {
+            let end = time + self.blink_duration;
+            if !self.is_suppressed(time, end) {
+                blinks.push(Blink {
+                    start: time,
+                    duration: self.blink_duration,
+                });
+                if rng.next_f32() < self.double_blink_chance {
+                    let second = end + self.blink_duration * 0.5;
+                    let second_end = second + self.blink_duration;
+                    if second_end <= duration && !self.is_suppressed(second, second_end) {
+                        blinks.push(Blink {
+                            start: second,
+                            duration: self.blink_duration,
+                        });
+                        time = second;
+                    }
+                }
+            }
+            time += self.blink_duration + self.next_interval(&mut rng);
+        }
+        blinks
+    }
+
+    fn next_interval(&self, rng: &mut SeededRng) -> f32 {
+        let jitter = (rng.next_f32() * 2.0 - 1.0) * self.interval_jitter;
+        self.mean_interval * (1.0 + jitter)
+    }
+
+    /// Generate an "eye.close" weight track.
+    pub fn to_track(&self, duration: f32) -> Track {
+        let mut track = Track::new(Expression::EyeClose.track_name());
+        track.add_keyframe(Keyframe::new(0.0, 0.0));
+        for blink in self.generate(duration) {
+            for (t, v) in blink_keys(&blink) {
+                track.add_keyframe(Keyframe::new(t, v));
+            }
+        }
+        track
+    }
+
+    /// Merge blinks into an expression track's eye-close channel.
+    ///
+    /// Existing eye-close animation wins where it is already more closed than the blink.
+    pub fn merge_into(&self, expressions: &mut ExpressionTrack, duration: f32) {
+        for blink in self.generate(duration) {
+            let keys: Vec<(f32, f32)> = blink_keys(&blink)
+                .into_iter()
+                .map(|(t, v)| (t, v.max(expressions.weight(Expression::EyeClose, t))))
+                .collect();
+            for (t, v) in keys {
+                expressions.set_weight(Expression::EyeClose, t, v);
+            }
+        }
+    }
+}
+
+/// Open → closed → open keys for one blink.
+fn blink_keys(blink: &Blink) -> [(f32, f32); 3] {
+    [
+        (blink.start, 0.0),
+        (blink.start + blink.duration * CLOSE_FRACTION, 1.0),
+        (blink.start + blink.duration, 0.0),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blinks_deterministic() {
+        let gen = BlinkGenerator::new(42);
+        let a = gen.generate(60.0);
+        let b = gen.generate(60.0);
+        assert_eq!(a, b);
+        // ~4s mean interval over a minute
+        assert!(a.len() >= 8 && a.len() <= 30, "got {}", a.len());
+        assert_ne!(a, BlinkGenerator::new(7).generate(60.0));
+
+        let rng = EpisodeRng::new(42);
+        let hero = BlinkGenerator::for_actor(&rng, "hero").generate(60.0);
+        assert_eq!(hero, BlinkGenerator::for_actor(&rng, "hero").generate(60.0));
+        assert_ne!(
+            hero,
+            BlinkGenerator::for_actor(&rng, "rival").generate(60.0)
+        );
+        let reseeded = BlinkGenerator::for_actor(&EpisodeRng::new(43), "hero");
+        assert_ne!(hero, reseeded.generate(60.0));
+    }
+
+    #[test]
+    fn test_blink_suppression_and_doubles() {
+        let gen = BlinkGenerator::new(1)
+            .with_interval(1.0, 0.2)
+            .with_double_blink_chance(1.0)
+            .suppress(5.0, 15.0);
+        let blinks = gen.generate(20.0);
+        assert!(blinks
+            .iter()
+            .all(|b| b.start + b.duration <= 5.0 || b.start >= 15.0));
+        // Every blink is doubled: the first two are back to back
+        assert!(blinks[1].start - blinks[0].start < 0.5);
+    }
+
+    #[test]
+    fn test_merge_into_expressions() {
+        let mut expr = ExpressionTrack::new("face");
+        expr.set_weight(Expression::Smile, 0.0, 1.0);
+        let gen = BlinkGenerator::new(3).with_interval(2.0, 0.0);
+        gen.merge_into(&mut expr, 10.0);
+
+        let first = gen.generate(10.0)[0];
+        let closed_at = first.start + first.duration * CLOSE_FRACTION;
+        assert!((expr.weight(Expression::EyeClose, closed_at) - 1.0).abs() < 1e-5);
+        assert_eq!(expr.weight(Expression::Smile, closed_at), 1.0);
+        assert_eq!(gen.to_track(10.0).name, "eye.close");
+    }
+}
@@ -1,6 +1,14 @@
 //! Bridge: ALICE-Animation → ALICE-Browser
 //! Web-based anime player: SDF evaluation + NPR rendering in browser.
 
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+
+use wasm_bindgen::prelude::*;
+
+use crate::camera::CameraState;
+use crate::director::CutId;
 use crate::{DirectorState, EpisodePackage};
 // use alice_browser::RenderTarget;
 
@@ -12,10 +20,14 @@ pub struct WebPlayerConfig {
     pub target_fps: f32,
     pub quality: RenderQuality,
     pub autoplay: bool,
+    /// Frames kept evaluated ahead of the playhead.
+    pub buffer_frames: usize,
+    /// Frames evaluated into the buffer per `update`.
+    pub buffer_fill_per_update: usize,
 }
 
 /// Render quality presets for different bandwidth/device scenarios.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RenderQuality {
     /// Low quality — mobile, slow connection (SDF eval at 1/4 resolution).
     Low,
@@ -38,6 +50,112 @@ impl RenderQuality {
             RenderQuality::Ultra => 2.0,
         }
     }
+
+    /// Next lower preset, if any.
+    pub fn lower(self) -> Option<Self> {
+        match self {
+            RenderQuality::Low => None,
+            RenderQuality::Medium => Some(RenderQuality::Low),
+            RenderQuality::High => Some(RenderQuality::Medium),
+            RenderQuality::Ultra => Some(RenderQuality::High),
+        }
+    }
+
+    /// Next higher preset, if any.
+    pub fn higher(self) -> Option<Self> {
+        match self {
+            RenderQuality::Low => Some(RenderQuality::Medium),
+            RenderQuality::Medium => Some(RenderQuality::High),
+            RenderQuality::High => Some(RenderQuality::Ultra),
+            RenderQuality::Ultra => None,
+        }
+    }
+}
+
+/// Steps `RenderQuality` to hold a target frame rate from measured frame times.
+///
+/// Decisions use the mean over a full window of samples, and the window restarts after
+/// every change. Quality drops when the mean exceeds the frame budget by
+/// `downgrade_margin`; it rises only if the mean scaled by the next preset's pixel cost
+/// stays under `upgrade_headroom` of the budget, so the two thresholds never oscillate.
+#[derive(Debug, Clone)]
+pub struct AdaptiveQuality {
+    pub target_fps: f32,
+    pub min_quality: RenderQuality,
+    pub max_quality: RenderQuality,
+    /// Samples averaged per decision.
+    pub window: usize,
+    /// Downgrade when mean > budget * (1 + margin).
+    pub downgrade_margin: f32,
+    /// Upgrade when predicted mean < budget * headroom.
+    pub upgrade_headroom: f32,
+    samples: Vec<f32>,
+}
+
+impl AdaptiveQuality {
+    pub fn new(target_fps: f32) -> Self {
+        Self {
+            target_fps,
+            min_quality: RenderQuality::Low,
+            max_quality: RenderQuality::High,
+            window: 24,
+            downgrade_margin: 0.05,
+            upgrade_headroom: 0.8,
+            samples: Vec::new(),
+        }
+    }
+
+    pub fn with_range(mut self, min: RenderQuality, max: RenderQuality) -> Self {
+        self.min_quality = min;
+        self.max_quality = max;
+        self
+    }
+
+    pub fn with_window(mut self, window: usize) -> Self {
+        self.window = window.max(1);
+        self
+    }
+
+    /// Seconds available per frame.
+    #[inline]
+    pub fn frame_budget(&self) -> f32 {
+        1.0 / self.target_fps.max(1.0)
+    }
+
+    /// Record one frame's evaluation + render time at `current` quality; returns the
+    /// quality to switch to when a step is due.
+    pub fn record(&mut self, frame_seconds: f32, current: RenderQuality) -> Option<RenderQuality> {
+        self.samples.push(frame_seconds.max(0.0));
+        if self.samples.len() < self.window {
+            return None;
+        }
+        let mean = self.samples.iter().sum::<f32>() / self.samples.len() as f32;
+        self.samples.clear();
+
+        let budget = self.frame_budget();
+        let next = if mean > budget * (1.0 + self.downgrade_margin) {
+            current.lower().filter(|q| *q >= self.min_quality)
+        } else {
+            current
+                .higher()
+                .filter(|q| *q <= self.max_quality)
+                .filter(|q| {
+                    let cost = (q.scale_factor() / current.scale_factor()).powi(2);
+                    mean * cost < budget * self.upgrade_headroom
+                })
+        };
+        // Out-of-range current quality is pulled back into range
+        next.or_else(|| {
+            (current < self.min_quality)
+                .then_some(self.min_quality)
+                .or((current > self.max_quality).then_some(self.max_quality))
+        })
+    }
+
+    /// Discard collected samples (e.g. after a seek or tab switch).
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
 }
 
 impl Default for WebPlayerConfig {
@@ -48,6 +166,8 @@ impl Default for WebPlayerConfig {
             target_fps: 24.0,
             quality: RenderQuality::High,
             autoplay: false,
+            buffer_frames: 48,
+            buffer_fill_per_update: 4,
         }
     }
 }
@@ -59,6 +179,8 @@ pub struct PlayerState {
     pub playing: bool,
     pub buffered_frames: usize,
     pub director_state: Option<DirectorState>,
+    /// Frames that were not buffered when presented (evaluated on demand).
+    pub underruns: u32,
 }
 
 impl PlayerState {
@@ -70,6 +192,7 @@ impl PlayerState {
             playing: false,
             buffered_frames: 0,
             director_state: None,
+            underruns: 0,
         }
     }
 
@@ -100,11 +223,61 @@ impl Default for PlayerState {
     }
 }
 
+/// Director state evaluated ahead of presentation.
+#[derive(Debug, Clone)]
+pub struct BufferedFrame {
+    pub frame: u32,
+    pub state: DirectorState,
+}
+
+/// Notifications for UI layers, queued by `WebPlayer::update`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayerEvent {
+    /// The presented frame belongs to a different cut (also sent for the first frame).
+    CutChanged {
+        from: Option<CutId>,
+        to: Option<CutId>,
+    },
+    /// Playback crossed a marker added with `add_marker`.
+    MarkerReached { name: String, time: f32 },
+    /// A frame was missing from the buffer during playback.
+    BufferingStarted,
+    /// The buffer refilled to half of `buffer_frames` (or to the end of the episode).
+    BufferingEnded,
+    /// Playback reached the end of the episode and paused.
+    Ended,
+}
+
+impl fmt::Display for PlayerEvent {
+    /// Compact form for JavaScript: `cutchanged:<id>`, `marker:<name>`, `bufferingstarted`,
+    /// `bufferingended`, `ended`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlayerEvent::CutChanged { to: Some(id), .. } => write!(f, "cutchanged:{}", id.0),
+            PlayerEvent::CutChanged { to: None, .. } => write!(f, "cutchanged:"),
+            PlayerEvent::MarkerReached { name, .. } => write!(f, "marker:{}", name),
+            PlayerEvent::BufferingStarted => write!(f, "bufferingstarted"),
+            PlayerEvent::BufferingEnded => write!(f, "bufferingended"),
+            PlayerEvent::Ended => write!(f, "ended"),
+        }
+    }
+}
+
 /// Web player for episodes.
 pub struct WebPlayer {
     pub config: WebPlayerConfig,
     pub state: PlayerState,
     pub episode: Option<EpisodePackage>,
+    /// Adjusts `config.quality` from reported frame times when set.
+    pub adaptive: Option<AdaptiveQuality>,
+    /// Consecutive frames from the playhead onwards.
+    buffer: VecDeque<BufferedFrame>,
+    /// Named times, sorted.
+    markers: Vec<(f32, String)>,
+    events: VecDeque<PlayerEvent>,
+    /// Cut of the last presented frame; `None` before the first frame.
+    presented_cut: Option<Option<CutId>>,
+    buffering: bool,
 }
 
 impl WebPlayer {
@@ -115,6 +288,12 @@ impl WebPlayer {
             config,
             state: PlayerState::new(),
             episode: None,
+            adaptive: None,
+            buffer: VecDeque::new(),
+            markers: Vec::new(),
+            events: VecDeque::new(),
+            presented_cut: None,
+            buffering: false,
         }
     }
 
@@ -124,17 +303,313 @@ impl WebPlayer {
         self.episode = Some(episode);
         self.state.current_time = 0.0;
         self.state.playing = self.config.autoplay;
+        self.invalidate_buffer();
+        self.events.clear();
+        self.presented_cut = None;
+        self.buffering = false;
+    }
+
+    /// Step quality automatically to hold `config.target_fps`.
+    pub fn with_adaptive_quality(mut self) -> Self {
+        self.adaptive = Some(AdaptiveQuality::new(self.config.target_fps));
+        self
+    }
+
+    /// Report how long the last frame took to evaluate and render; returns the new
+    /// quality if the adaptive controller changed it.
+    pub fn report_frame_time(&mut self, frame_seconds: f32) -> Option<RenderQuality> {
+        let quality = self
+            .adaptive
+            .as_mut()?
+            .record(frame_seconds, self.config.quality)?;
+        self.config.quality = quality;
+        Some(quality)
+    }
+
+    /// Add a named marker (e.g. a chapter start); reached when playback passes `time`.
+    pub fn add_marker(&mut self, name: impl Into<String>, time: f32) {
+        let pos = self.markers.partition_point(|(t, _)| *t <= time);
+        self.markers.insert(pos, (time, name.into()));
+    }
+
+    /// Next queued event, oldest first.
+    pub fn poll_event(&mut self) -> Option<PlayerEvent> {
+        self.events.pop_front()
     }
 
     /// Update player state and render a frame.
+    ///
+    /// The frame at the playhead comes from the buffer when available (otherwise it is
+    /// evaluated on demand and counted as an underrun); then up to
+    /// `buffer_fill_per_update` frames ahead are evaluated into the buffer.
     #[inline]
     pub fn update(&mut self, delta_seconds: f32) {
+        let previous_time = self.state.current_time;
         self.state.advance(delta_seconds);
-        if let Some(ref episode) = self.episode {
-            let state = episode.director.evaluate(&episode.scene_graph, self.state.current_time);
-            self.state.director_state = Some(state);
+        let duration = self.duration();
+        let Some(ref episode) = self.episode else {
+            return;
+        };
+        let ended = self.state.playing && self.state.current_time >= duration;
+        if ended {
+            self.state.current_time = duration;
+        }
+        let fps = self.config.target_fps.max(1.0);
+        let last_frame = ((duration * fps).ceil() as u32).saturating_sub(1);
+        // At the very end, keep showing the final frame
+        let frame = ((self.state.current_time * fps).floor() as u32).min(last_frame);
+
+        while self.buffer.front().is_some_and(|b| b.frame < frame) {
+            self.buffer.pop_front();
+        }
+        let evaluate = |frame: u32| BufferedFrame {
+            frame,
+            state: episode
+                .director
+                .evaluate(&episode.scene_graph, frame as f32 / fps),
+        };
+        let underrun = !matches!(self.buffer.front(), Some(b) if b.frame == frame);
+        if underrun {
+            self.buffer.clear();
+            self.buffer.push_back(evaluate(frame));
+            self.state.underruns += 1;
+        }
+        self.state.director_state = self.buffer.front().map(|b| b.state.clone());
+
+        for _ in 0..self.config.buffer_fill_per_update {
+            let next = self.buffer.back().map_or(frame, |b| b.frame + 1);
+            // The buffer holds the presented frame plus `buffer_frames` ahead
+            if self.buffer.len() > self.config.buffer_frames || next > last_frame {
+                break;
+            }
+            self.buffer.push_back(evaluate(next));
+        }
+        self.state.buffered_frames = self.buffer.len() - 1;
+
+        let cut = self
+            .state
+            .director_state
+            .as_ref()
+            .and_then(|s| s.active_cut);
+        if self.presented_cut != Some(cut) {
+            self.events.push_back(PlayerEvent::CutChanged {
+                from: self.presented_cut.flatten(),
+                to: cut,
+            });
+            self.presented_cut = Some(cut);
+        }
+        if self.state.playing && delta_seconds > 0.0 {
+            let now = self.state.current_time;
+            for (time, name) in self.markers.iter() {
+                if *time > previous_time && *time <= now {
+                    self.events.push_back(PlayerEvent::MarkerReached {
+                        name: name.clone(),
+                        time: *time,
+                    });
+                }
+            }
+        }
+        if underrun && self.state.playing && !self.buffering {
+            self.buffering = true;
+            self.events.push_back(PlayerEvent::BufferingStarted);
+        }
+        let resume_at = (self.config.buffer_frames / 2).min((last_frame - frame) as usize);
+        if self.buffering && self.state.buffered_frames >= resume_at {
+            self.buffering = false;
+            self.events.push_back(PlayerEvent::BufferingEnded);
+        }
+        if ended {
+            self.state.playing = false;
+            self.events.push_back(PlayerEvent::Ended);
+        }
+    }
+
+    /// Drop all buffered frames (e.g. after the episode was edited).
+    pub fn invalidate_buffer(&mut self) {
+        self.buffer.clear();
+        self.state.buffered_frames = 0;
+    }
+
+    /// Buffered frames as a fraction of `buffer_frames` (1.0 = full).
+    pub fn buffer_health(&self) -> f32 {
+        self.state.buffered_frames as f32 / self.config.buffer_frames.max(1) as f32
+    }
+
+    /// Load an episode from ANIM bytes (any format `deserialize_episode` accepts).
+    pub fn load_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        let episode = crate::episode::deserialize_episode(&mut &bytes[..])?;
+        self.load_episode(episode);
+        self.update(0.0);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn play(&mut self) {
+        self.state.playing = self.episode.is_some();
+    }
+
+    #[inline]
+    pub fn pause(&mut self) {
+        self.state.playing = false;
+    }
+
+    /// Seek (clamped to the episode), invalidating the buffer, and re-evaluate the frame
+    /// there.
+    pub fn seek(&mut self, time: f32) {
+        self.state.seek(time.min(self.duration()));
+        self.invalidate_buffer();
+        if let Some(adaptive) = &mut self.adaptive {
+            // Seek stalls are not representative frame times
+            adaptive.reset();
+        }
+        self.update(0.0);
+    }
+
+    /// Episode duration in seconds (0 when nothing is loaded).
+    pub fn duration(&self) -> f32 {
+        self.episode.as_ref().map_or(0.0, |e| {
+            e.metadata.duration_seconds.max(e.director.duration())
+        })
+    }
+
+    /// Camera at the current time.
+    pub fn camera(&self) -> Option<CameraState> {
+        self.state.director_state.as_ref().map(|s| s.camera_state)
+    }
+
+    /// Dialogue lines on screen at the current time.
+    pub fn subtitles(&self) -> Vec<&str> {
+        self.episode.as_ref().map_or_else(Vec::new, |e| {
+            e.director
+                .dialogue_at(self.state.current_time)
+                .map(|line| line.text.as_str())
+                .collect()
+        })
+    }
+}
+
+/// JavaScript-facing player: wraps `WebPlayer` with `wasm_bindgen`-compatible types.
+#[wasm_bindgen]
+pub struct WasmWebPlayer {
+    inner: WebPlayer,
+}
+
+#[wasm_bindgen]
+impl WasmWebPlayer {
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_width: u32, canvas_height: u32, target_fps: f32) -> Self {
+        Self {
+            inner: WebPlayer::new(WebPlayerConfig {
+                canvas_width,
+                canvas_height,
+                target_fps,
+                ..Default::default()
+            }),
         }
     }
+
+    /// Load an episode from ANIM bytes.
+    pub fn load(&mut self, bytes: &[u8]) -> Result<(), JsError> {
+        self.inner
+            .load_bytes(bytes)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    pub fn play(&mut self) {
+        self.inner.play();
+    }
+
+    pub fn pause(&mut self) {
+        self.inner.pause();
+    }
+
+    pub fn seek(&mut self, time: f32) {
+        self.inner.seek(time);
+    }
+
+    /// Advance by `delta_seconds` (e.g. from `requestAnimationFrame`).
+    pub fn tick(&mut self, delta_seconds: f32) {
+        self.inner.update(delta_seconds);
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn playing(&self) -> bool {
+        self.inner.state.playing
+    }
+
+    #[wasm_bindgen(getter, js_name = currentTime)]
+    pub fn current_time(&self) -> f32 {
+        self.inner.state.current_time
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn duration(&self) -> f32 {
+        self.inner.duration()
+    }
+
+    /// Active cut index, if any.
+    #[wasm_bindgen(getter, js_name = activeCut)]
+    pub fn active_cut(&self) -> Option<u32> {
+        self.inner
+            .state
+            .director_state
+            .as_ref()
+            .and_then(|s| s.active_cut)
+            .map(|id| id.0)
+    }
+
+    /// `[px, py, pz, tx, ty, tz, fov]`, empty before the first frame.
+    pub fn camera(&self) -> Vec<f32> {
+        self.inner.camera().map_or_else(Vec::new, |c| {
+            vec![
+                c.position.x,
+                c.position.y,
+                c.position.z,
+                c.target.x,
+                c.target.y,
+                c.target.z,
+                c.fov,
+            ]
+        })
+    }
+
+    /// Next queued event as `PlayerEvent`'s compact string, or `undefined`.
+    #[wasm_bindgen(js_name = nextEvent)]
+    pub fn next_event(&mut self) -> Option<String> {
+        self.inner.poll_event().map(|e| e.to_string())
+    }
+
+    /// Enable automatic quality stepping.
+    #[wasm_bindgen(js_name = enableAdaptiveQuality)]
+    pub fn enable_adaptive_quality(&mut self) {
+        self.inner.adaptive = Some(AdaptiveQuality::new(self.inner.config.target_fps));
+    }
+
+    /// Report the last frame's cost in milliseconds.
+    #[wasm_bindgen(js_name = reportFrameTime)]
+    pub fn report_frame_time(&mut self, millis: f32) {
+        self.inner.report_frame_time(millis / 1000.0);
+    }
+
+    /// Current render resolution scale (see `RenderQuality::scale_factor`).
+    #[wasm_bindgen(getter, js_name = renderScale)]
+    pub fn render_scale(&self) -> f32 {
+        self.inner.config.quality.scale_factor()
+    }
+
+    #[wasm_bindgen(js_name = addMarker)]
+    pub fn add_marker(&mut self, name: &str, time: f32) {
+        self.inner.add_marker(name, time);
+    }
+
+    /// Subtitle lines on screen at the current time.
+    pub fn subtitles(&self) -> Vec<String> {
+        self.inner
+            .subtitles()
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -153,6 +628,33 @@ mod tests {
         assert_eq!(RenderQuality::Ultra.scale_factor(), 2.0);
     }
 
+    #[test]
+    fn test_adaptive_quality_hysteresis() {
+        let mut player = WebPlayer::new(WebPlayerConfig::default()).with_adaptive_quality();
+        player.adaptive.as_mut().unwrap().window = 4;
+        let budget = 1.0 / 24.0;
+
+        // Sustained overload steps down once per full window
+        let changes: Vec<_> = (0..8)
+            .filter_map(|_| player.report_frame_time(budget * 1.5))
+            .collect();
+        assert_eq!(changes, vec![RenderQuality::Medium, RenderQuality::Low]);
+        assert!((0..4).all(|_| player.report_frame_time(budget * 3.0).is_none()));
+
+        // At Low, 60% of budget would cost 240% at Medium: stay put
+        assert!((0..8).all(|_| player.report_frame_time(budget * 0.6).is_none()));
+        let up: Vec<_> = (0..4)
+            .filter_map(|_| player.report_frame_time(budget * 0.1))
+            .collect();
+        assert_eq!(up, vec![RenderQuality::Medium]);
+
+        // Never above the configured maximum (High)
+        for _ in 0..40 {
+            player.report_frame_time(0.0);
+        }
+        assert_eq!(player.config.quality, RenderQuality::High);
+    }
+
     #[test]
     fn test_player_state() {
         let mut state = PlayerState::new();
@@ -182,4 +684,127 @@ mod tests {
         player.update(1.0);
         assert_eq!(player.state.current_time, 1.0);
     }
+
+    #[test]
+    fn test_web_player_buffering() {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 10.0));
+        let meta = EpisodeMetadata::new("Buffer Test", 1, 10.0);
+        let episode = EpisodePackage::new(meta, sg, dir, AnimeShading::default());
+        let mut player = WebPlayer::new(WebPlayerConfig {
+            target_fps: 10.0,
+            buffer_frames: 6,
+            buffer_fill_per_update: 3,
+            ..Default::default()
+        });
+        player.load_episode(episode);
+
+        // First frame is cold; later updates fill ahead of the playhead
+        player.update(0.0);
+        assert_eq!(
+            (player.state.underruns, player.state.buffered_frames),
+            (1, 3)
+        );
+        player.update(0.0);
+        player.update(0.0);
+        assert_eq!(player.state.buffered_frames, 6);
+        assert_eq!(player.buffer_health(), 1.0);
+
+        player.play();
+        for _ in 0..5 {
+            player.update(0.1);
+        }
+        assert_eq!(player.state.underruns, 1);
+
+        player.seek(9.5);
+        assert_eq!(player.state.underruns, 2);
+        assert_eq!(player.state.buffered_frames, 3);
+        // Nothing is buffered past the end
+        player.update(0.0);
+        player.update(0.0);
+        assert_eq!(player.state.buffered_frames, 4);
+    }
+
+    #[test]
+    fn test_web_player_events() {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        dir.add_cut(Cut::new("c2", 1.0, 2.0));
+        let meta = EpisodeMetadata::new("Event Test", 1, 2.0);
+        let episode = EpisodePackage::new(meta, sg, dir, AnimeShading::default());
+        let mut player = WebPlayer::new(WebPlayerConfig {
+            target_fps: 10.0,
+            buffer_frames: 8,
+            buffer_fill_per_update: 2,
+            ..Default::default()
+        });
+        player.load_episode(episode);
+        player.add_marker("title card", 0.5);
+        let drain =
+            |player: &mut WebPlayer| std::iter::from_fn(|| player.poll_event()).collect::<Vec<_>>();
+
+        player.play();
+        player.update(0.0);
+        assert_eq!(
+            drain(&mut player),
+            vec![
+                PlayerEvent::CutChanged {
+                    from: None,
+                    to: Some(CutId(0))
+                },
+                PlayerEvent::BufferingStarted,
+            ]
+        );
+        player.update(0.0);
+        assert_eq!(drain(&mut player), vec![PlayerEvent::BufferingEnded]);
+
+        let mut events = Vec::new();
+        for _ in 0..25 {
+            player.update(0.1);
+            events.extend(drain(&mut player));
+        }
+        let names: Vec<String> = events.iter().map(|e| e.to_string()).collect();
+        assert_eq!(names, vec!["marker:title card", "cutchanged:1", "ended"]);
+        assert!(!player.state.playing);
+        assert_eq!(player.state.current_time, 2.0);
+    }
+
+    #[test]
+    fn test_wasm_player_controls() {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 4.0));
+        dir.add_cut(Cut::new("c2", 4.0, 8.0));
+        dir.add_dialogue(crate::director::DialogueLine::new(5.0, 6.0, "Over here!"));
+        let meta = EpisodeMetadata::new("Wasm Test", 1, 8.0);
+        let episode = EpisodePackage::new(meta, sg, dir, AnimeShading::default());
+        let mut bytes = Vec::new();
+        crate::episode::serialize_episode(&episode, &mut bytes).unwrap();
+
+        let mut player = WasmWebPlayer::new(640, 360, 24.0);
+        assert!(player.camera().is_empty());
+        player.load(&bytes).unwrap();
+        assert_eq!(player.duration(), 8.0);
+        assert_eq!(player.camera().len(), 7);
+        assert_eq!(player.active_cut(), Some(0));
+
+        player.play();
+        player.tick(4.5);
+        assert!(player.playing());
+        assert_eq!(player.active_cut(), Some(1));
+        assert!(player.subtitles().is_empty());
+
+        player.pause();
+        player.seek(5.5);
+        player.tick(1.0);
+        assert_eq!(player.current_time(), 5.5);
+        assert_eq!(player.subtitles(), vec!["Over here!".to_string()]);
+        player.seek(100.0);
+        assert_eq!(player.current_time(), 8.0);
+    }
 }
@@ -1,24 +1,99 @@
 //! Bridge: ALICE-Animation → ALICE-Cache
 //! Frame-level SDF evaluation caching for real-time playback.
 
+use crate::director::{Cut, CutId};
+use crate::frame_hash::hash_sdf;
 use crate::{Director, DirectorState, SceneGraph};
+use alice_sdf::SdfNode;
 // use alice_cache::{Cache, CacheConfig};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
 
 /// Cached frame state for avoiding redundant SDF evaluations.
 #[derive(Debug, Clone)]
 pub struct CachedFrame {
     pub time: f32,
     pub state: DirectorState,
+    /// Structural hash of `sdf` (`frame_hash::hash_sdf`); 0 when no SDF is cached.
     pub sdf_hash: u64,
+    /// Evaluated scene SDF, shared between frames with the same hash.
+    pub sdf: Option<Arc<SdfNode>>,
+    /// Episode revision the frame was evaluated at.
+    pub revision: u64,
 }
 
-/// Animation frame cache with LRU eviction.
+impl CachedFrame {
+    /// Evaluate the director state and scene SDF at `time`.
+    pub fn evaluate(
+        time: f32,
+        director: &Director,
+        scene: &SceneGraph,
+    ) -> crate::error::Result<Self> {
+        let sdf = director.evaluate_scene(scene, time);
+        Ok(Self {
+            time,
+            state: director.evaluate(scene, time),
+            sdf_hash: hash_sdf(&sdf)?,
+            sdf: Some(Arc::new(sdf)),
+            revision: 0,
+        })
+    }
+
+    pub fn with_revision(mut self, revision: u64) -> Self {
+        self.revision = revision;
+        self
+    }
+
+    /// Approximate memory held by this entry, including its SDF tree.
+    #[inline]
+    pub fn approx_bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.sdf.as_deref().map_or(0, sdf_bytes)
+    }
+}
+
+/// Approximate in-memory size of an SDF tree (its encoded size).
+fn sdf_bytes(sdf: &SdfNode) -> usize {
+    bincode::serialized_size(sdf).map_or(0, |n| n as usize)
+}
+
+/// Cache counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Evaluated SDFs replaced by a neighbouring frame's with the same hash.
+    pub shared_sdfs: u64,
+    pub evicted_frames: u64,
+    pub evicted_bytes: u64,
+    /// Frames and approximate bytes currently held.
+    pub frames: usize,
+    pub bytes: usize,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    frame: CachedFrame,
+    last_used: u64,
+    bytes: usize,
+}
+
+/// Animation frame cache with LRU eviction, bounded by frame count and an approximate
+/// byte budget.
 pub struct AnimationCache {
-    frames: HashMap<u32, CachedFrame>,
+    frames: HashMap<u32, CacheEntry>,
+    /// Access tick -> frame index, oldest first.
+    recency: BTreeMap<u64, u32>,
+    tick: u64,
     max_frames: usize,
+    max_bytes: usize,
+    bytes: usize,
     hit_count: u64,
     miss_count: u64,
+    shared_sdfs: u64,
+    evicted_frames: u64,
+    evicted_bytes: u64,
 }
 
 impl AnimationCache {
@@ -27,12 +102,25 @@ impl AnimationCache {
     pub fn new(max_frames: usize) -> Self {
         Self {
             frames: HashMap::with_capacity(max_frames),
+            recency: BTreeMap::new(),
+            tick: 0,
             max_frames,
+            max_bytes: usize::MAX,
+            bytes: 0,
             hit_count: 0,
             miss_count: 0,
+            shared_sdfs: 0,
+            evicted_frames: 0,
+            evicted_bytes: 0,
         }
     }
 
+    /// Also evict once cached payloads exceed `max_bytes` (approximate).
+    pub fn with_byte_budget(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
     /// Get or evaluate a frame at the given time.
     #[inline]
     pub fn get_or_evaluate(
@@ -42,29 +130,160 @@ impl AnimationCache {
         director: &Director,
         scene: &SceneGraph,
     ) -> DirectorState {
-        if let Some(cached) = self.frames.get(&frame_index) {
+        if let Some(state) = self.get(frame_index).map(|cached| cached.state.clone()) {
             self.hit_count += 1;
-            return cached.state.clone();
+            return state;
         }
         self.miss_count += 1;
         let state = director.evaluate(scene, time);
-        if self.frames.len() >= self.max_frames {
-            // Evict oldest frame (simple strategy)
-            if let Some(&oldest_key) = self.frames.keys().next() {
-                self.frames.remove(&oldest_key);
-            }
-        }
-        self.frames.insert(
+        self.insert(
             frame_index,
             CachedFrame {
                 time,
                 state: state.clone(),
                 sdf_hash: 0,
+                sdf: None,
+                revision: 0,
             },
         );
         state
     }
 
+    /// Get or evaluate a frame including its scene SDF, keyed by frame and episode
+    /// `revision` (bump it on every edit). A hit returns the stored frame without
+    /// evaluating; entries from another revision, or cached without an SDF (by
+    /// `get_or_evaluate`), count as misses and are replaced.
+    pub fn get_or_evaluate_sdf(
+        &mut self,
+        frame_index: u32,
+        revision: u64,
+        time: f32,
+        director: &Director,
+        scene: &SceneGraph,
+    ) -> crate::error::Result<CachedFrame> {
+        if let Some(cached) = self
+            .get(frame_index)
+            .filter(|c| c.sdf.is_some() && c.revision == revision)
+            .cloned()
+        {
+            self.hit_count += 1;
+            return Ok(cached);
+        }
+        self.miss_count += 1;
+        let evaluated = CachedFrame::evaluate(time, director, scene)?.with_revision(revision);
+        self.insert(frame_index, evaluated.clone());
+        // Prefer the stored entry: its SDF may now be shared with a neighbour
+        Ok(self
+            .frames
+            .get(&frame_index)
+            .map_or(evaluated, |e| e.frame.clone()))
+    }
+
+    /// Look up a frame, marking it most recently used (does not touch hit stats).
+    pub fn get(&mut self, frame_index: u32) -> Option<&CachedFrame> {
+        let entry = self.frames.get_mut(&frame_index)?;
+        self.recency.remove(&entry.last_used);
+        self.tick += 1;
+        entry.last_used = self.tick;
+        self.recency.insert(self.tick, frame_index);
+        Some(&entry.frame)
+    }
+
+    /// Whether a frame is cached (does not affect recency).
+    #[inline]
+    pub fn contains(&self, frame_index: u32) -> bool {
+        self.frames.contains_key(&frame_index)
+    }
+
+    /// Insert (or replace) a frame as most recently used, evicting least recently used
+    /// frames to stay within budget.
+    /// An SDF hashing the same as a neighbouring frame's is replaced by a shared reference and
+    /// not counted against the byte budget again.
+    pub fn insert(&mut self, frame_index: u32, mut frame: CachedFrame) {
+        self.remove(frame_index);
+        let mut bytes = frame.approx_bytes();
+        if let Some(shared) = self.neighbour_sdf(frame_index, &frame) {
+            bytes = std::mem::size_of::<CachedFrame>();
+            frame.sdf = Some(shared);
+            self.shared_sdfs += 1;
+        }
+        while !self.frames.is_empty()
+            && (self.frames.len() >= self.max_frames
+                || self.bytes.saturating_add(bytes) > self.max_bytes)
+        {
+            self.evict_oldest();
+        }
+        if self.max_frames == 0 || bytes > self.max_bytes {
+            return;
+        }
+        self.tick += 1;
+        self.recency.insert(self.tick, frame_index);
+        self.bytes += bytes;
+        self.frames.insert(
+            frame_index,
+            CacheEntry {
+                frame,
+                last_used: self.tick,
+                bytes,
+            },
+        );
+    }
+
+    fn neighbour_sdf(&self, frame_index: u32, frame: &CachedFrame) -> Option<Arc<SdfNode>> {
+        frame.sdf.as_ref()?;
+        [frame_index.checked_sub(1), frame_index.checked_add(1)]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| self.frames.get(&i))
+            .filter(|e| e.frame.sdf_hash == frame.sdf_hash)
+            .find_map(|e| e.frame.sdf.clone())
+    }
+
+    /// Drop one frame; returns it if it was cached.
+    pub fn remove(&mut self, frame_index: u32) -> Option<CachedFrame> {
+        let entry = self.frames.remove(&frame_index)?;
+        self.recency.remove(&entry.last_used);
+        self.bytes -= entry.bytes;
+        Some(entry.frame)
+    }
+
+    /// Drop cached frames with `start <= time < end` (e.g. the span of edited actor keys).
+    /// Returns the number of frames removed.
+    pub fn invalidate_time_range(&mut self, start: f32, end: f32) -> usize {
+        self.invalidate_where(|frame| frame.time >= start && frame.time < end)
+    }
+
+    /// Drop frames affected by editing cut `id`: those cached while it was active plus
+    /// those inside `cut`'s (possibly moved) time range. Returns the number removed.
+    pub fn invalidate_cut(&mut self, id: CutId, cut: &Cut) -> usize {
+        self.invalidate_where(|frame| {
+            frame.state.active_cut == Some(id) || cut.contains_time(frame.time)
+        })
+    }
+
+    fn invalidate_where(&mut self, mut stale: impl FnMut(&CachedFrame) -> bool) -> usize {
+        let frames: Vec<u32> = self
+            .frames
+            .iter()
+            .filter(|(_, e)| stale(&e.frame))
+            .map(|(&i, _)| i)
+            .collect();
+        for &frame_index in &frames {
+            self.remove(frame_index);
+        }
+        frames.len()
+    }
+
+    fn evict_oldest(&mut self) {
+        if let Some((_, frame_index)) = self.recency.pop_first() {
+            if let Some(entry) = self.frames.remove(&frame_index) {
+                self.bytes -= entry.bytes;
+                self.evicted_frames += 1;
+                self.evicted_bytes += entry.bytes as u64;
+            }
+        }
+    }
+
     /// Cache hit rate (0.0 - 1.0).
     #[inline]
     pub fn hit_rate(&self) -> f32 {
@@ -75,12 +294,194 @@ impl AnimationCache {
         self.hit_count as f32 / total as f32
     }
 
+    /// Current counters.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hit_count,
+            misses: self.miss_count,
+            shared_sdfs: self.shared_sdfs,
+            evicted_frames: self.evicted_frames,
+            evicted_bytes: self.evicted_bytes,
+            frames: self.frames.len(),
+            bytes: self.bytes,
+        }
+    }
+
     /// Clear all cached frames.
     #[inline]
     pub fn clear(&mut self) {
         self.frames.clear();
+        self.recency.clear();
+        self.bytes = 0;
         self.hit_count = 0;
         self.miss_count = 0;
+        self.shared_sdfs = 0;
+        self.evicted_frames = 0;
+        self.evicted_bytes = 0;
+    }
+}
+
+/// Frames evaluated ahead of the playhead by default.
+pub const DEFAULT_PREFETCH_FRAMES: u32 = 12;
+
+/// Cache shared between the playback thread and a `FramePrefetcher`.
+pub type SharedAnimationCache = Arc<Mutex<AnimationCache>>;
+
+fn lock(cache: &Mutex<AnimationCache>) -> MutexGuard<'_, AnimationCache> {
+    // Entries are inserted whole, so a panicked holder leaves the cache consistent
+    cache.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+struct PrefetchRequest {
+    generation: u64,
+    frames: Vec<(u32, f32)>,
+}
+
+/// Evaluates upcoming frames on a background thread into a shared `AnimationCache`,
+/// so playback finds them warm. `seek` cancels queued and in-flight work.
+pub struct FramePrefetcher {
+    cache: SharedAnimationCache,
+    fps: f32,
+    lookahead: u32,
+    revision: Arc<AtomicU64>,
+    generation: Arc<AtomicU64>,
+    pending: Arc<AtomicUsize>,
+    sender: Option<mpsc::Sender<PrefetchRequest>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl FramePrefetcher {
+    /// Spawn the worker; it owns its own copy of the director and scene.
+    pub fn new(
+        cache: SharedAnimationCache,
+        director: Director,
+        scene: SceneGraph,
+        fps: f32,
+    ) -> Self {
+        let revision = Arc::new(AtomicU64::new(0));
+        let generation = Arc::new(AtomicU64::new(0));
+        let pending = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel::<PrefetchRequest>();
+        let worker = {
+            let cache = Arc::clone(&cache);
+            let revision = Arc::clone(&revision);
+            let generation = Arc::clone(&generation);
+            let pending = Arc::clone(&pending);
+            std::thread::spawn(move || {
+                for request in receiver {
+                    for &(frame, time) in &request.frames {
+                        if generation.load(Ordering::Acquire) != request.generation {
+                            break;
+                        }
+                        if lock(&cache).contains(frame) {
+                            continue;
+                        }
+                        // Evaluate without holding the lock so playback is never blocked
+                        let Ok(evaluated) = CachedFrame::evaluate(time, &director, &scene) else {
+                            continue;
+                        };
+                        let evaluated = evaluated.with_revision(revision.load(Ordering::Acquire));
+                        if generation.load(Ordering::Acquire) != request.generation {
+                            break;
+                        }
+                        lock(&cache).insert(frame, evaluated);
+                    }
+                    pending.fetch_sub(1, Ordering::AcqRel);
+                }
+            })
+        };
+        Self {
+            cache,
+            fps: fps.max(f32::EPSILON),
+            lookahead: DEFAULT_PREFETCH_FRAMES,
+            revision,
+            generation,
+            pending,
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// Number of frames to evaluate ahead of the playhead.
+    pub fn with_lookahead(mut self, frames: u32) -> Self {
+        self.lookahead = frames;
+        self
+    }
+
+    /// Episode revision of the director and scene the worker owns; prefetched frames are
+    /// tagged with it so `get_or_evaluate_sdf` at that revision finds them.
+    pub fn with_revision(self, revision: u64) -> Self {
+        self.revision.store(revision, Ordering::Release);
+        self
+    }
+
+    pub fn cache(&self) -> &SharedAnimationCache {
+        &self.cache
+    }
+
+    /// Frames `request(time, rate)` would prefetch: the next `lookahead` frames in the
+    /// playback direction, stepping by the rounded rate (empty while paused).
+    pub fn upcoming_frames(&self, time: f32, rate: f32) -> Vec<u32> {
+        if rate == 0.0 || !rate.is_finite() {
+            return Vec::new();
+        }
+        let current = (time.max(0.0) * self.fps).floor() as i64;
+        let step = rate.abs().round().max(1.0) as i64 * rate.signum() as i64;
+        (1..=self.lookahead as i64)
+            .map(|i| current + i * step)
+            .take_while(|&f| (0..=u32::MAX as i64).contains(&f))
+            .map(|f| f as u32)
+            .collect()
+    }
+
+    /// Queue the frames ahead of `time` (seconds) at playback `rate`; frames already
+    /// cached are skipped by the worker.
+    pub fn request(&self, time: f32, rate: f32) {
+        let frames: Vec<(u32, f32)> = self
+            .upcoming_frames(time, rate)
+            .into_iter()
+            .map(|f| (f, f as f32 / self.fps))
+            .collect();
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        if frames.is_empty() {
+            return;
+        }
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        let request = PrefetchRequest {
+            generation: self.generation.load(Ordering::Acquire),
+            frames,
+        };
+        if sender.send(request).is_err() {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    /// Drop all queued and in-flight prefetch work.
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Cancel outstanding work and start prefetching from the new playhead.
+    pub fn seek(&self, time: f32, rate: f32) {
+        self.cancel();
+        self.request(time, rate);
+    }
+
+    /// True once every queued request has been processed or cancelled.
+    pub fn is_idle(&self) -> bool {
+        self.pending.load(Ordering::Acquire) == 0
+    }
+}
+
+impl Drop for FramePrefetcher {
+    fn drop(&mut self) {
+        self.cancel();
+        self.sender = None;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
     }
 }
 
@@ -119,6 +520,172 @@ mod tests {
         assert_eq!(cache.frames.len(), 2);
     }
 
+    #[test]
+    fn test_cache_lru_order() {
+        let mut cache = AnimationCache::new(2);
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let sg = SceneGraph::new();
+
+        cache.get_or_evaluate(0, 0.0, &dir, &sg);
+        cache.get_or_evaluate(1, 1.0, &dir, &sg);
+        cache.get_or_evaluate(0, 0.0, &dir, &sg); // 0 is now most recent
+        cache.get_or_evaluate(2, 2.0, &dir, &sg);
+        assert!(cache.contains(0) && cache.contains(2));
+        assert!(!cache.contains(1));
+    }
+
+    fn wait_idle(prefetcher: &FramePrefetcher) {
+        let start = std::time::Instant::now();
+        while !prefetcher.is_idle() {
+            assert!(start.elapsed() < std::time::Duration::from_secs(5));
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_prefetch_fills_cache_ahead() {
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let cache = Arc::new(Mutex::new(AnimationCache::new(64)));
+        let prefetcher =
+            FramePrefetcher::new(Arc::clone(&cache), dir.clone(), SceneGraph::new(), 24.0)
+                .with_lookahead(4);
+
+        assert_eq!(prefetcher.upcoming_frames(1.0, 1.0), vec![25, 26, 27, 28]);
+        assert_eq!(prefetcher.upcoming_frames(1.0, -2.0), vec![22, 20, 18, 16]);
+        assert!(prefetcher.upcoming_frames(1.0, 0.0).is_empty());
+
+        prefetcher.request(1.0, 1.0);
+        wait_idle(&prefetcher);
+        let mut cache = lock(&cache);
+        assert!((25..=28).all(|f| cache.contains(f)));
+        cache.get_or_evaluate(25, 25.0 / 24.0, &dir, &SceneGraph::new());
+        assert_eq!(cache.stats().misses, 0);
+    }
+
+    #[test]
+    fn test_prefetch_seek_cancels() {
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let cache = Arc::new(Mutex::new(AnimationCache::new(64)));
+        let prefetcher = FramePrefetcher::new(Arc::clone(&cache), dir, SceneGraph::new(), 24.0)
+            .with_lookahead(4);
+
+        {
+            // Hold the lock so the first request cannot complete before the seek
+            let _guard = lock(&cache);
+            prefetcher.request(0.0, 1.0);
+            prefetcher.seek(2.0, 1.0);
+        }
+        wait_idle(&prefetcher);
+        let cache = lock(&cache);
+        assert!((1..=4).all(|f| !cache.contains(f)));
+        assert!((49..=52).all(|f| cache.contains(f)));
+    }
+
+    #[test]
+    fn test_cache_sdf_shared_between_static_frames() {
+        use crate::scene::Actor;
+        use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let mut sg = SceneGraph::new();
+        let mut timeline = Timeline::new("move");
+        let mut track = Track::new("translate.x");
+        track.add_keyframe(Keyframe::new(1.0, 0.0));
+        track.add_keyframe(Keyframe::new(2.0, 1.0));
+        timeline.add_track(track);
+        sg.add_actor(Actor::new("ball", SdfNode::sphere(1.0)).with_timeline(timeline));
+        let mut cache = AnimationCache::new(16);
+
+        let a = cache.get_or_evaluate_sdf(0, 1, 0.0, &dir, &sg).unwrap();
+        let b = cache.get_or_evaluate_sdf(1, 1, 0.5, &dir, &sg).unwrap();
+        let c = cache.get_or_evaluate_sdf(2, 1, 1.5, &dir, &sg).unwrap();
+        assert_ne!(a.sdf_hash, 0);
+        assert_eq!(a.sdf_hash, b.sdf_hash);
+        assert!(Arc::ptr_eq(
+            a.sdf.as_ref().unwrap(),
+            b.sdf.as_ref().unwrap()
+        ));
+        assert_ne!(b.sdf_hash, c.sdf_hash);
+        assert_eq!(cache.stats().shared_sdfs, 1);
+
+        let again = cache.get_or_evaluate_sdf(1, 1, 0.5, &dir, &sg).unwrap();
+        assert!(Arc::ptr_eq(
+            again.sdf.as_ref().unwrap(),
+            b.sdf.as_ref().unwrap()
+        ));
+        assert_eq!(cache.stats().hits, 1);
+    }
+
+    #[test]
+    fn test_cache_sdf_keyed_by_revision() {
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let mut original = SceneGraph::new();
+        original.add_actor(crate::scene::Actor::new("ball", SdfNode::sphere(1.0)));
+        let mut edited = SceneGraph::new();
+        edited.add_actor(crate::scene::Actor::new(
+            "ball",
+            SdfNode::box3d(1.0, 1.0, 1.0),
+        ));
+        let mut cache = AnimationCache::new(16);
+
+        let before = cache
+            .get_or_evaluate_sdf(0, 1, 0.0, &dir, &original)
+            .unwrap();
+        // Same revision: served from the cache without looking at the scene
+        let hit = cache.get_or_evaluate_sdf(0, 1, 0.0, &dir, &edited).unwrap();
+        assert_eq!(hit.sdf_hash, before.sdf_hash);
+        assert_eq!(cache.stats().hits, 1);
+        let after = cache.get_or_evaluate_sdf(0, 2, 0.0, &dir, &edited).unwrap();
+        assert_ne!(after.sdf_hash, before.sdf_hash);
+        assert_eq!((after.revision, cache.stats().misses), (2, 2));
+    }
+
+    #[test]
+    fn test_cache_invalidate_cut_and_range() {
+        let mut dir = Director::new("Test");
+        let c1 = dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        dir.add_cut(Cut::new("c2", 1.0, 2.0));
+        let sg = SceneGraph::new();
+        let mut cache = AnimationCache::new(64);
+        for i in 0..8 {
+            cache.get_or_evaluate(i, i as f32 * 0.25, &dir, &sg);
+        }
+
+        // c1 extended to 1.25 now also covers frame 4 (t = 1.0)
+        let edited = Cut::new("c1", 0.0, 1.25);
+        assert_eq!(cache.invalidate_cut(c1, &edited), 5);
+        assert!((5..8).all(|f| cache.contains(f)));
+
+        assert_eq!(cache.invalidate_time_range(1.5, 2.0), 2);
+        assert!(cache.contains(5) && !cache.contains(6));
+        assert_eq!(cache.stats().evicted_frames, 0);
+        assert_eq!(cache.stats().bytes, std::mem::size_of::<CachedFrame>());
+    }
+
+    #[test]
+    fn test_cache_byte_budget() {
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let sg = SceneGraph::new();
+        let frame_bytes = std::mem::size_of::<CachedFrame>();
+        let mut cache = AnimationCache::new(100).with_byte_budget(frame_bytes * 3);
+
+        for i in 0..5 {
+            cache.get_or_evaluate(i, i as f32, &dir, &sg);
+        }
+        let stats = cache.stats();
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.bytes, frame_bytes * 3);
+        assert_eq!(stats.evicted_frames, 2);
+        assert_eq!(stats.evicted_bytes, 2 * frame_bytes as u64);
+        assert!(!cache.contains(0) && cache.contains(4));
+    }
+
     #[test]
     fn test_cache_clear() {
         let mut cache = AnimationCache::new(10);
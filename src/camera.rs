@@ -1,14 +1,26 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
 use alice_sdf::animation::{Keyframe, Timeline, Track};
 use alice_sdf::SdfNode;
-use glam::{Mat4, Vec3};
+use glam::{Mat3, Mat4, Quat, Vec2, Vec3};
 use serde::{Deserialize, Serialize};
 
+use crate::rng::EpisodeRng;
+
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use crate::float::Float;
+
 /// Evaluated camera state at a single instant.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CameraState {
     pub position: Vec3,
     pub target: Vec3,
     pub fov: f32,
+    /// Camera up vector; `None` uses the project's world up (no roll).
+    pub up: Option<Vec3>,
 }
 
 impl Default for CameraState {
@@ -16,7 +28,8 @@ impl Default for CameraState {
         Self {
             position: Vec3::new(0.0, 0.0, 5.0),
             target: Vec3::ZERO,
-            fov: std::f32::consts::FRAC_PI_4,
+            fov: core::f32::consts::FRAC_PI_4,
+            up: None,
         }
     }
 }
@@ -25,7 +38,13 @@ impl CameraState {
     /// Compute the inverse view matrix for transforming SDF world coordinates.
     #[inline]
     pub fn inverse_view_matrix(&self) -> Mat4 {
-        let view = Mat4::look_at_rh(self.position, self.target, Vec3::Y);
+        self.inverse_view_matrix_up(Vec3::Y)
+    }
+
+    /// Inverse view matrix with `up` as world up (see `Units::up`).
+    #[inline]
+    pub fn inverse_view_matrix_up(&self, up: Vec3) -> Mat4 {
+        let view = Mat4::look_at_rh(self.position, self.target, self.up.unwrap_or(up));
         view.inverse()
     }
 
@@ -34,6 +53,50 @@ impl CameraState {
     pub fn forward(&self) -> Vec3 {
         (self.target - self.position).normalize_or_zero()
     }
+
+    /// `(right, up, forward)` image axes in world space, given the project's world up.
+    #[inline]
+    pub fn basis(&self, world_up: Vec3) -> (Vec3, Vec3, Vec3) {
+        let forward = self.forward();
+        let right = forward
+            .cross(self.up.unwrap_or(world_up))
+            .normalize_or_zero();
+        (right, right.cross(forward), forward)
+    }
+
+    /// Orientation as a rotation from camera space (looking down -Z, +Y up) to world space.
+    pub fn orientation(&self, world_up: Vec3) -> Quat {
+        let (right, up, forward) = self.basis(world_up);
+        Quat::from_mat3(&Mat3::from_cols(right, up, -forward)).normalize()
+    }
+
+    /// Normalized device coordinates of `point` (x right, y up, -1..1 across the frame at
+    /// `aspect` width / height), matching the renderer's rays. `None` behind the camera.
+    pub fn project(&self, point: Vec3, aspect: f32, world_up: Vec3) -> Option<Vec2> {
+        let (right, up, forward) = self.basis(world_up);
+        let d = point - self.position;
+        let depth = d.dot(forward);
+        if depth <= 1e-6 {
+            return None;
+        }
+        let half_height = (self.fov * 0.5).tan();
+        Some(Vec2::new(
+            d.dot(right) / (depth * half_height * aspect),
+            d.dot(up) / (depth * half_height),
+        ))
+    }
+
+    /// Look-at form of a position + orientation camera: the target sits `distance` along the
+    /// view direction and the orientation's roll is kept as an explicit up vector.
+    pub fn from_orientation(position: Vec3, orientation: Quat, fov: f32, distance: f32) -> Self {
+        let orientation = orientation.normalize();
+        Self {
+            position,
+            target: position + orientation * Vec3::NEG_Z * distance.max(1e-3),
+            fov,
+            up: Some(orientation * Vec3::Y),
+        }
+    }
 }
 
 /// Camera work presets.
@@ -55,6 +118,16 @@ pub enum CameraWork {
     Shake { amplitude: f32, frequency: f32 },
 }
 
+/// How a [`CameraTrack`] aims the camera.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CameraMode {
+    /// Keyed target point; the camera never rolls.
+    #[default]
+    LookAt,
+    /// Keyed `orientation.x/y/z/w` quaternion, for rotation holds and rolls.
+    Orientation,
+}
+
 /// Animated camera track with keyframed position, target, and FOV.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CameraTrack {
@@ -63,6 +136,37 @@ pub struct CameraTrack {
     pub fov_track: Track,
     pub shake_amplitude: f32,
     pub shake_frequency: f32,
+    pub mode: CameraMode,
+    /// Camera-to-world rotation keys, used in [`CameraMode::Orientation`].
+    pub orientation_timeline: Timeline,
+    /// Distance to the implied target in [`CameraMode::Orientation`].
+    pub focus_distance: f32,
+    /// Seed of the shake noise, drawn from the episode seed when the episode is built or
+    /// loaded (see [`CameraTrack::seed_shake`]); `None` until then, which shakes as seed 0.
+    pub shake_seed: Option<u64>,
+}
+
+/// Camera track layout of version 1 episodes: look-at keys and sine shake only.
+#[derive(Deserialize)]
+pub(crate) struct CameraTrackV1 {
+    position_timeline: Timeline,
+    target_timeline: Timeline,
+    fov_track: Track,
+    shake_amplitude: f32,
+    shake_frequency: f32,
+}
+
+impl From<CameraTrackV1> for CameraTrack {
+    fn from(v1: CameraTrackV1) -> Self {
+        Self {
+            position_timeline: v1.position_timeline,
+            target_timeline: v1.target_timeline,
+            fov_track: v1.fov_track,
+            shake_amplitude: v1.shake_amplitude,
+            shake_frequency: v1.shake_frequency,
+            ..CameraTrack::default()
+        }
+    }
 }
 
 impl Default for CameraTrack {
@@ -90,7 +194,7 @@ impl Default for CameraTrack {
         tgt_tl.add_track(tz);
 
         let mut fov_track = Track::new("fov");
-        fov_track.add_keyframe(Keyframe::new(0.0, std::f32::consts::FRAC_PI_4));
+        fov_track.add_keyframe(Keyframe::new(0.0, core::f32::consts::FRAC_PI_4));
 
         Self {
             position_timeline: pos_tl,
@@ -98,10 +202,21 @@ impl Default for CameraTrack {
             fov_track,
             shake_amplitude: 0.0,
             shake_frequency: 0.0,
+            mode: CameraMode::LookAt,
+            orientation_timeline: Timeline::new("camera_orientation"),
+            focus_distance: 5.0,
+            shake_seed: None,
         }
     }
 }
 
+const ORIENTATION_TRACKS: [&str; 4] = [
+    "orientation.x",
+    "orientation.y",
+    "orientation.z",
+    "orientation.w",
+];
+
 impl CameraTrack {
     /// Add a keyframe for camera position, target, and FOV at a given time.
     pub fn add_keyframe(&mut self, time: f32, position: Vec3, target: Vec3, fov: f32) {
@@ -131,9 +246,219 @@ impl CameraTrack {
         self.fov_track.add_keyframe(Keyframe::new(time, fov));
     }
 
+    /// Key position, orientation and FOV at `time` and switch to [`CameraMode::Orientation`].
+    /// The quaternion is flipped into the hemisphere of the current value so interpolation
+    /// takes the short way round.
+    pub fn add_orientation_keyframe(
+        &mut self,
+        time: f32,
+        position: Vec3,
+        orientation: Quat,
+        fov: f32,
+    ) {
+        self.mode = CameraMode::Orientation;
+        for name in ORIENTATION_TRACKS {
+            if !self
+                .orientation_timeline
+                .tracks
+                .iter()
+                .any(|t| t.name == name)
+            {
+                self.orientation_timeline.add_track(Track::new(name));
+            }
+        }
+        let mut q = orientation.normalize();
+        if self.orientation_at(time).dot(q) < 0.0 {
+            q = -q;
+        }
+        let values = q.to_array();
+        for track in self.orientation_timeline.tracks.iter_mut() {
+            if let Some(i) = ORIENTATION_TRACKS.iter().position(|n| track.name == *n) {
+                track.add_keyframe(Keyframe::new(time, values[i]));
+            }
+        }
+        let names_pos = ["position.x", "position.y", "position.z"];
+        let vals_pos = [position.x, position.y, position.z];
+        for track in self.position_timeline.tracks.iter_mut() {
+            if let Some(i) = names_pos.iter().position(|n| track.name == *n) {
+                track.add_keyframe(Keyframe::new(time, vals_pos[i]));
+            }
+        }
+        self.fov_track.add_keyframe(Keyframe::new(time, fov));
+    }
+
+    /// Keyed orientation at `time` (normalized component blend); identity when unkeyed.
+    pub fn orientation_at(&self, time: f32) -> Quat {
+        let mut values = [0.0, 0.0, 0.0, 1.0];
+        for (value, name) in values.iter_mut().zip(ORIENTATION_TRACKS) {
+            if let Some(v) = self.orientation_timeline.get_value(name, time) {
+                *value = v;
+            }
+        }
+        let q = Quat::from_array(values);
+        if q.length_squared() > 1e-12 {
+            q.normalize()
+        } else {
+            Quat::IDENTITY
+        }
+    }
+
+    /// Pre-rotate every orientation key by `rotation` (a change of world axes).
+    pub fn rotate_orientation(&mut self, rotation: Quat) {
+        let tracks = &mut self.orientation_timeline.tracks;
+        let index: [Option<usize>; 4] =
+            ORIENTATION_TRACKS.map(|name| tracks.iter().position(|t| t.name == name));
+        let [Some(x), Some(y), Some(z), Some(w)] = index else {
+            return;
+        };
+        let keys = [x, y, z, w].map(|i| tracks[i].keyframes.len());
+        for k in 0..keys.into_iter().min().unwrap_or(0) {
+            let q = Quat::from_xyzw(
+                tracks[x].keyframes[k].value,
+                tracks[y].keyframes[k].value,
+                tracks[z].keyframes[k].value,
+                tracks[w].keyframes[k].value,
+            );
+            let values = (rotation * q).to_array();
+            for (i, value) in [x, y, z, w].into_iter().zip(values) {
+                tracks[i].keyframes[k].value = value;
+            }
+        }
+    }
+
+    /// Re-key this look-at track as position + orientation at every existing key time. The
+    /// focus distance becomes the mean camera-to-target distance.
+    pub fn convert_to_orientation(&mut self, world_up: Vec3) {
+        if self.mode == CameraMode::Orientation {
+            return;
+        }
+        let times = self.key_times(&self.target_timeline);
+        let states: Vec<(f32, CameraState)> = times
+            .iter()
+            .map(|&t| (t, self.evaluate_unshaken(t)))
+            .collect();
+        if let Some(sum) = states
+            .iter()
+            .map(|(_, s)| s.position.distance(s.target))
+            .reduce(|a, b| a + b)
+        {
+            self.focus_distance = (sum / states.len() as f32).max(1e-3);
+        }
+        self.clear_keyframes();
+        for (time, state) in states {
+            let orientation = state.orientation(world_up);
+            self.add_orientation_keyframe(time, state.position, orientation, state.fov);
+        }
+    }
+
+    /// Re-key this orientation track as position + target at every existing key time. Roll
+    /// is dropped: look-at cameras always use the world up.
+    pub fn convert_to_look_at(&mut self) {
+        if self.mode == CameraMode::LookAt {
+            return;
+        }
+        let times = self.key_times(&self.orientation_timeline);
+        let states: Vec<(f32, CameraState)> = times
+            .iter()
+            .map(|&t| (t, self.evaluate_unshaken(t)))
+            .collect();
+        self.clear_keyframes();
+        self.mode = CameraMode::LookAt;
+        for (time, state) in states {
+            self.add_keyframe(time, state.position, state.target, state.fov);
+        }
+    }
+
+    /// Sorted, deduplicated key times of the position and FOV tracks plus `aim`.
+    fn key_times(&self, aim: &Timeline) -> Vec<f32> {
+        let mut times: Vec<f32> = self
+            .position_timeline
+            .tracks
+            .iter()
+            .chain(aim.tracks.iter())
+            .chain(core::iter::once(&self.fov_track))
+            .flat_map(|t| t.keyframes.iter().map(|k| k.time))
+            .collect();
+        times.sort_by(f32::total_cmp);
+        times.dedup();
+        if times.is_empty() {
+            times.push(0.0);
+        }
+        times
+    }
+
+    /// Add `position`, `target` and `fov` to every keyframe. Unkeyed channels get a key at 0
+    /// holding their fallback value plus the offset.
+    pub fn offset(&mut self, position: Vec3, target: Vec3, fov: f32) {
+        let channels = [
+            ("position.x", position.x, 0.0),
+            ("position.y", position.y, 0.0),
+            ("position.z", position.z, 5.0),
+            ("target.x", target.x, 0.0),
+            ("target.y", target.y, 0.0),
+            ("target.z", target.z, 0.0),
+        ];
+        let fov_fallback = self.fov_track.evaluate(0.0);
+        let tracks = self
+            .position_timeline
+            .tracks
+            .iter_mut()
+            .chain(self.target_timeline.tracks.iter_mut())
+            .filter_map(|t| {
+                let channel = channels.iter().find(|(name, _, _)| t.name == *name)?;
+                Some((t, channel.1, channel.2))
+            })
+            .chain(core::iter::once((&mut self.fov_track, fov, fov_fallback)));
+        for (track, delta, fallback) in tracks {
+            if track.keyframes.is_empty() {
+                track.add_keyframe(Keyframe::new(0.0, fallback));
+            }
+            for kf in &mut track.keyframes {
+                kf.value += delta;
+            }
+        }
+    }
+
+    /// Remove every keyframe (evaluation falls back to the default camera).
+    pub fn clear_keyframes(&mut self) {
+        for track in self
+            .position_timeline
+            .tracks
+            .iter_mut()
+            .chain(self.target_timeline.tracks.iter_mut())
+            .chain(self.orientation_timeline.tracks.iter_mut())
+        {
+            track.keyframes.clear();
+        }
+        self.fov_track.keyframes.clear();
+    }
+
     /// Evaluate camera state at a given time. Hot path — called every frame.
     #[inline(always)]
     pub fn evaluate(&self, time: f32) -> CameraState {
+        let mut state = self.evaluate_unshaken(time);
+
+        if self.shake_amplitude > 0.0 {
+            // Noise eased between random offsets, four steps per shake cycle
+            let rng = EpisodeRng::new(self.shake_seed.unwrap_or_default());
+            let rate = self.shake_frequency * 4.0;
+            let x = rng.smooth_noise("x", time, rate) * 2.0 - 1.0;
+            let y = rng.smooth_noise("y", time, rate) * 2.0 - 1.0;
+            state.position.x += x * self.shake_amplitude;
+            state.position.y += y * self.shake_amplitude * 0.7;
+        }
+        state
+    }
+
+    /// Draw this track's shake from the episode stream `label`, so shakes differ between
+    /// cameras and change with the episode seed.
+    pub fn seed_shake(&mut self, rng: &EpisodeRng, label: &str) {
+        self.shake_seed = Some(rng.seed_for(label));
+    }
+
+    /// Keyed camera state at `time`, without shake.
+    #[inline(always)]
+    fn evaluate_unshaken(&self, time: f32) -> CameraState {
         let px = self
             .position_timeline
             .get_value("position.x", time)
@@ -146,6 +471,13 @@ impl CameraTrack {
             .position_timeline
             .get_value("position.z", time)
             .unwrap_or(5.0);
+        let position = Vec3::new(px, py, pz);
+        let fov = self.fov_track.evaluate(time);
+
+        if self.mode == CameraMode::Orientation {
+            let orientation = self.orientation_at(time);
+            return CameraState::from_orientation(position, orientation, fov, self.focus_distance);
+        }
 
         let tx = self
             .target_timeline
@@ -160,75 +492,93 @@ impl CameraTrack {
             .get_value("target.z", time)
             .unwrap_or(0.0);
 
-        let fov = self.fov_track.evaluate(time);
-
-        let mut position = Vec3::new(px, py, pz);
-
-        // Apply camera shake — FMA-optimized, precompute freq*TAU
-        if self.shake_amplitude > 0.0 {
-            let freq_tau = self.shake_frequency * std::f32::consts::TAU;
-            let shake_x = (time * freq_tau).sin() * self.shake_amplitude;
-            let shake_y = (time * freq_tau).mul_add(1.3, 0.0).cos()
-                * self.shake_amplitude
-                * 0.7;
-            position.x += shake_x;
-            position.y += shake_y;
-        }
-
         CameraState {
             position,
             target: Vec3::new(tx, ty, tz),
             fov,
+            up: None,
+        }
+    }
+
+    /// Key `state` at `time` in the track's mode: position + target in
+    /// [`CameraMode::LookAt`], position + the aim at `state.target` (keeping its roll) in
+    /// [`CameraMode::Orientation`].
+    fn key_state(&mut self, time: f32, state: CameraState) {
+        match self.mode {
+            CameraMode::LookAt => self.add_keyframe(time, state.position, state.target, state.fov),
+            CameraMode::Orientation => self.add_orientation_keyframe(
+                time,
+                state.position,
+                state.orientation(Vec3::Y),
+                state.fov,
+            ),
         }
     }
 
-    /// Apply a camera work preset, adding keyframes automatically.
+    /// Apply a camera work preset, adding keyframes automatically in the track's mode.
     pub fn apply_preset(&mut self, work: CameraWork, start: f32, duration: f32) {
         let end = start + duration;
+        let current = self.evaluate_unshaken(start);
         match work {
             CameraWork::Static => {}
             CameraWork::Pan { speed } => {
-                let current = self.evaluate(start);
-                self.add_keyframe(start, current.position, current.target, current.fov);
+                self.key_state(start, current);
                 let offset = Vec3::new(speed * duration, 0.0, 0.0);
-                self.add_keyframe(
+                self.key_state(
                     end,
-                    current.position + offset,
-                    current.target + offset,
-                    current.fov,
+                    CameraState {
+                        position: current.position + offset,
+                        target: current.target + offset,
+                        ..current
+                    },
                 );
             }
             CameraWork::Tilt { speed } => {
-                let current = self.evaluate(start);
-                self.add_keyframe(start, current.position, current.target, current.fov);
+                self.key_state(start, current);
                 let offset = Vec3::new(0.0, speed * duration, 0.0);
-                self.add_keyframe(end, current.position, current.target + offset, current.fov);
+                self.key_state(
+                    end,
+                    CameraState {
+                        target: current.target + offset,
+                        ..current
+                    },
+                );
             }
             CameraWork::Dolly { speed } => {
-                let current = self.evaluate(start);
                 let dir = current.forward();
-                self.add_keyframe(start, current.position, current.target, current.fov);
-                self.add_keyframe(
+                self.key_state(start, current);
+                self.key_state(
                     end,
-                    current.position + dir * speed * duration,
-                    current.target,
-                    current.fov,
+                    CameraState {
+                        position: current.position + dir * speed * duration,
+                        ..current
+                    },
                 );
             }
             CameraWork::Zoom { target_fov } => {
-                let current = self.evaluate(start);
-                self.add_keyframe(start, current.position, current.target, current.fov);
-                self.add_keyframe(end, current.position, current.target, target_fov);
+                self.key_state(start, current);
+                self.key_state(
+                    end,
+                    CameraState {
+                        fov: target_fov,
+                        ..current
+                    },
+                );
             }
             CameraWork::Orbit { radius, speed } => {
-                let current = self.evaluate(start);
                 let steps = 8;
                 for i in 0..=steps {
                     let t = start + (duration * i as f32 / steps as f32);
                     let angle = speed * (t - start);
-                    let pos = current.target
+                    let position = current.target
                         + Vec3::new(radius * angle.cos(), current.position.y, radius * angle.sin());
-                    self.add_keyframe(t, pos, current.target, current.fov);
+                    self.key_state(
+                        t,
+                        CameraState {
+                            position,
+                            ..current
+                        },
+                    );
                 }
             }
             CameraWork::Shake {
@@ -355,6 +705,97 @@ mod tests {
         assert!((mid.position.x - 5.0).abs() < 0.1);
     }
 
+    #[test]
+    fn test_orientation_mode_rolls() {
+        let position = Vec3::new(0.0, 1.0, 10.0);
+        let level = CameraState {
+            position,
+            target: Vec3::new(0.0, 1.0, 0.0),
+            fov: 0.8,
+            up: None,
+        };
+        let q = level.orientation(Vec3::Y);
+        assert!((q * Vec3::NEG_Z - Vec3::NEG_Z).length() < 1e-5);
+
+        let mut track = CameraTrack::default();
+        let roll = Quat::from_axis_angle(Vec3::NEG_Z, core::f32::consts::FRAC_PI_2);
+        track.add_orientation_keyframe(0.0, position, q, 0.8);
+        track.add_orientation_keyframe(2.0, position, roll * q, 0.8);
+        assert_eq!(track.mode, CameraMode::Orientation);
+        // A pure roll: position and aim hold while the up vector turns
+        let mid = track.evaluate(1.0);
+        assert!((mid.position - position).length() < 1e-5);
+        assert!((mid.forward() - level.forward()).length() < 1e-4);
+        let up = mid.up.unwrap();
+        let expected = Vec3::new(1.0, 1.0, 0.0).normalize();
+        assert!((up - expected).length() < 1e-4);
+        assert!(
+            (mid.orientation(Vec3::Y)
+                .dot(track.orientation_at(1.0))
+                .abs()
+                - 1.0)
+                .abs()
+                < 1e-4
+        );
+    }
+
+    #[test]
+    fn test_presets_key_orientation_mode() {
+        let mut base = CameraTrack::default();
+        base.clear_keyframes();
+        let position = Vec3::new(0.0, 0.0, 10.0);
+        base.add_orientation_keyframe(0.0, position, Quat::IDENTITY, 0.8);
+        let aimed = |work: CameraWork, time: f32| {
+            let mut track = base.clone();
+            track.apply_preset(work, 0.0, 1.0);
+            assert_eq!(track.mode, CameraMode::Orientation);
+            assert!(track
+                .target_timeline
+                .tracks
+                .iter()
+                .all(|t| t.keyframes.is_empty()));
+            track.evaluate(time)
+        };
+
+        // Default focus distance 5: the implied target starts at (0, 0, 5)
+        let tilt = aimed(CameraWork::Tilt { speed: 1.0 }, 1.0);
+        assert!((tilt.position - position).length() < 1e-4);
+        assert!((tilt.forward() - Vec3::new(0.0, 1.0, -5.0).normalize()).length() < 1e-3);
+
+        let dolly = aimed(CameraWork::Dolly { speed: 2.0 }, 1.0);
+        assert!((dolly.position - Vec3::new(0.0, 0.0, 8.0)).length() < 1e-4);
+        assert!((dolly.forward() - Vec3::NEG_Z).length() < 1e-4);
+
+        let orbit = aimed(
+            CameraWork::Orbit {
+                radius: 4.0,
+                speed: core::f32::consts::PI,
+            },
+            0.5,
+        );
+        assert!((orbit.position - Vec3::new(0.0, 0.0, 9.0)).length() < 1e-3);
+        assert!((orbit.forward() - Vec3::NEG_Z).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_look_at_orientation_conversion() {
+        let mut track = CameraTrack::default();
+        track.clear_keyframes();
+        track.add_keyframe(0.0, Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO, 0.8);
+        track.add_keyframe(4.0, Vec3::new(10.0, 0.0, 0.0), Vec3::ZERO, 0.8);
+        let before = [0.0, 4.0].map(|t| track.evaluate(t));
+        track.convert_to_orientation(Vec3::Y);
+        assert_eq!(track.mode, CameraMode::Orientation);
+        for (t, state) in [0.0, 4.0].into_iter().zip(before) {
+            let after = track.evaluate(t);
+            assert!((after.position - state.position).length() < 1e-4);
+            assert!((after.forward() - state.forward()).length() < 1e-4);
+        }
+        track.convert_to_look_at();
+        assert_eq!(track.mode, CameraMode::LookAt);
+        assert!(track.evaluate(4.0).target.length() < 1e-3);
+    }
+
     #[test]
     fn test_fake_perspective_projective() {
         let fp = FakePerspective::new("exaggerated", DistortionType::Projective, 1.0);
@@ -382,4 +823,31 @@ mod tests {
         let state = track.evaluate(5.0);
         assert!(state.position.x > 0.0);
     }
+
+    #[test]
+    fn test_seeded_shake() {
+        let mut track = CameraTrack::default();
+        track.apply_preset(
+            CameraWork::Shake {
+                amplitude: 0.2,
+                frequency: 6.0,
+            },
+            0.0,
+            2.0,
+        );
+        let shaken = |seed| {
+            let mut t = track.clone();
+            t.seed_shake(&EpisodeRng::new(seed), "shake/0");
+            (0..48)
+                .map(|i| t.evaluate(i as f32 / 24.0).position)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shaken(1), shaken(1));
+        assert_ne!(shaken(1), shaken(2));
+        for pair in shaken(1).windows(2) {
+            assert!(pair[0].x.abs() <= 0.2 && pair[0].y.abs() <= 0.14 + 1e-6);
+            // Eased between steps: no jumps bigger than the full range per frame
+            assert!((pair[1] - pair[0]).length() < 0.4);
+        }
+    }
 }
@@ -1,7 +1,11 @@
 //! Bridge: ALICE-Animation → ALICE-CDN
 //! Episode distribution with edge caching and content routing.
 
-use crate::episode::{EpisodeMetadata, EpisodePackage};
+use std::io;
+
+use crate::episode::{deserialize_episode, serialize_episode, EpisodeMetadata, EpisodePackage};
+use crate::patch::{create_patch, write_patch, EpisodePatch};
+use crate::split::{join_episodes, split_at_cuts, PART_OFFSET_KEY};
 // use alice_cdn::{CdnClient, ContentDescriptor, CacheHint};
 
 /// CDN-optimized episode descriptor for edge distribution.
@@ -14,7 +18,7 @@ pub struct EpisodeCdnDescriptor {
 }
 
 /// Cache hint strategy for anime episodes.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CdnCacheHint {
     /// Latest episode — cache at edge, high priority.
     Hot,
@@ -27,7 +31,7 @@ pub enum CdnCacheHint {
 /// Create a CDN content descriptor from an episode.
 #[inline]
 pub fn episode_to_cdn_descriptor(episode: &EpisodePackage, hint: CdnCacheHint) -> EpisodeCdnDescriptor {
-    let size_bytes = episode.metadata.duration_seconds as usize * 6; // ~6 bytes/sec for SDF
+    let size_bytes = serialized_size(episode);
     let content_id = format!("anim-ep{:04}-{}", episode.metadata.episode_number, episode.metadata.title);
     EpisodeCdnDescriptor {
         content_id,
@@ -37,6 +41,123 @@ pub fn episode_to_cdn_descriptor(episode: &EpisodePackage, hint: CdnCacheHint) -
     }
 }
 
+/// Exact `serialize_episode` output size, without encoding the episode.
+fn serialized_size(episode: &EpisodePackage) -> usize {
+    match bincode::serialized_size(episode) {
+        Ok(body) => 16 + body as usize,
+        Err(_) => episode.estimate_size(),
+    }
+}
+
+impl CdnCacheHint {
+    fn tag(self) -> &'static str {
+        match self {
+            CdnCacheHint::Hot => "HOT",
+            CdnCacheHint::Warm => "WARM",
+            CdnCacheHint::Cold => "COLD",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "HOT" => Some(CdnCacheHint::Hot),
+            "WARM" => Some(CdnCacheHint::Warm),
+            "COLD" => Some(CdnCacheHint::Cold),
+            _ => None,
+        }
+    }
+}
+
+/// Per-segment cache hints: segments overlapping the opening or ending (re-watched and
+/// shared across episodes) get `edge`, the rest `middle`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentCacheHints {
+    /// Opening length in seconds from the start of the episode.
+    pub opening_seconds: f32,
+    /// Ending length in seconds before the end of the episode.
+    pub ending_seconds: f32,
+    pub edge: CdnCacheHint,
+    pub middle: CdnCacheHint,
+}
+
+impl Default for SegmentCacheHints {
+    fn default() -> Self {
+        Self {
+            opening_seconds: 90.0,
+            ending_seconds: 90.0,
+            edge: CdnCacheHint::Hot,
+            middle: CdnCacheHint::Warm,
+        }
+    }
+}
+
+impl SegmentCacheHints {
+    /// Hint for a segment spanning `start..end` of an episode lasting `duration` seconds.
+    pub fn hint_for(&self, start: f32, end: f32, duration: f32) -> CdnCacheHint {
+        if start < self.opening_seconds || end > duration - self.ending_seconds {
+            self.edge
+        } else {
+            self.middle
+        }
+    }
+}
+
+/// CDN descriptor for an incremental patch against a previously pushed episode.
+#[derive(Debug, Clone)]
+pub struct EpisodePatchCdnDescriptor {
+    pub content_id: String,
+    /// Content the patch must be applied to.
+    pub base_content_id: String,
+    pub size_bytes: usize,
+    pub cache_hint: CdnCacheHint,
+    pub patch: EpisodePatch,
+}
+
+/// What to push to the CDN after an episode edit.
+#[derive(Debug, Clone)]
+pub enum IncrementalPush {
+    /// Push only the delta.
+    Patch(EpisodePatchCdnDescriptor),
+    /// Delta is not worth it (e.g. scene graph rewritten) — push the whole episode.
+    Full(EpisodeCdnDescriptor),
+}
+
+/// Create a CDN descriptor for a patch on top of `base`.
+pub fn patch_to_cdn_descriptor(
+    base: &EpisodeCdnDescriptor,
+    patch: EpisodePatch,
+    hint: CdnCacheHint,
+) -> std::io::Result<EpisodePatchCdnDescriptor> {
+    let mut buf = Vec::new();
+    let size_bytes = write_patch(&patch, &mut buf)?;
+    Ok(EpisodePatchCdnDescriptor {
+        content_id: format!("{}-patch-{:08x}", base.content_id, patch.target_crc),
+        base_content_id: base.content_id.clone(),
+        size_bytes,
+        cache_hint: hint,
+        patch,
+    })
+}
+
+/// Decide between pushing a patch or the full episode, whichever is smaller.
+pub fn plan_incremental_push(
+    old: &EpisodePackage,
+    new: &EpisodePackage,
+    hint: CdnCacheHint,
+) -> std::io::Result<IncrementalPush> {
+    let base = episode_to_cdn_descriptor(old, hint);
+    let patch = create_patch(old, new)?;
+    let descriptor = patch_to_cdn_descriptor(&base, patch, hint)?;
+
+    let mut full = Vec::new();
+    let full_size = crate::episode::serialize_episode(new, &mut full)?;
+    if descriptor.size_bytes < full_size {
+        Ok(IncrementalPush::Patch(descriptor))
+    } else {
+        Ok(IncrementalPush::Full(episode_to_cdn_descriptor(new, hint)))
+    }
+}
+
 /// Estimate bandwidth savings vs traditional video.
 #[inline]
 pub fn bandwidth_savings_ratio(episode_size_bytes: usize, duration_seconds: f32) -> f32 {
@@ -45,6 +166,315 @@ pub fn bandwidth_savings_ratio(episode_size_bytes: usize, duration_seconds: f32)
     traditional_bytes as f32 / episode_size_bytes.max(1) as f32
 }
 
+/// How an episode is cut into streaming segments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SegmentPolicy {
+    /// One segment per cut.
+    PerCut,
+    /// Consecutive cuts grouped until a segment spans at least this many seconds.
+    /// Segments always end on a cut boundary.
+    Duration(f32),
+}
+
+/// One ANIM segment of a streamed episode.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CdnSegment {
+    pub index: u32,
+    pub uri: String,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub size_bytes: usize,
+    /// CRC32 of the segment's ANIM bytes.
+    pub crc32: u32,
+    /// Offset of the segment within the concatenated episode blob.
+    pub byte_offset: u64,
+    pub cache_hint: CdnCacheHint,
+}
+
+impl CdnSegment {
+    #[inline]
+    pub fn duration(&self) -> f32 {
+        self.end_time - self.start_time
+    }
+}
+
+/// HLS-like segment manifest for progressive episode streaming.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentManifest {
+    pub content_id: String,
+    /// Longest segment duration in whole seconds (rounded up).
+    pub target_duration: u32,
+    pub segments: Vec<CdnSegment>,
+}
+
+impl SegmentManifest {
+    /// Segment containing `time`, for seeking.
+    pub fn segment_at(&self, time: f32) -> Option<&CdnSegment> {
+        self.segments
+            .iter()
+            .find(|s| time >= s.start_time && time < s.end_time)
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.segments.iter().map(|s| s.size_bytes as u64).sum()
+    }
+
+    /// Re-assign every segment's cache hint.
+    pub fn apply_cache_hints(&mut self, hints: &SegmentCacheHints) {
+        let duration = self.segments.last().map_or(0.0, |s| s.end_time);
+        for seg in &mut self.segments {
+            seg.cache_hint = hints.hint_for(seg.start_time, seg.end_time, duration);
+        }
+    }
+
+    /// Playlist text: `#EXTINF` per segment with `#EXT-X-BYTERANGE` and a CRC tag.
+    pub fn to_m3u8(&self) -> String {
+        let mut out = String::from("#EXTM3U\n#EXT-X-VERSION:7\n");
+        out += &format!("#EXT-X-TARGETDURATION:{}\n", self.target_duration);
+        out += &format!("#EXT-X-ALICE-CONTENT:{}\n", self.content_id);
+        for seg in &self.segments {
+            out += &format!("#EXT-X-ALICE-START:{}\n", seg.start_time);
+            out += &format!("#EXTINF:{},\n", seg.duration());
+            out += &format!("#EXT-X-BYTERANGE:{}@{}\n", seg.size_bytes, seg.byte_offset);
+            out += &format!("#EXT-X-ALICE-CRC:{:08x}\n", seg.crc32);
+            out += &format!("#EXT-X-ALICE-CACHE:{}\n", seg.cache_hint.tag());
+            out += &seg.uri;
+            out.push('\n');
+        }
+        out += "#EXT-X-ENDLIST\n";
+        out
+    }
+
+    /// Parse a playlist written by `to_m3u8`.
+    pub fn parse_m3u8(text: &str) -> io::Result<Self> {
+        let invalid = |msg: String| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+        if lines.next() != Some("#EXTM3U") {
+            return Err(invalid("Missing #EXTM3U header".into()));
+        }
+        let mut manifest = SegmentManifest {
+            content_id: String::new(),
+            target_duration: 0,
+            segments: Vec::new(),
+        };
+        let (mut start, mut duration, mut range, mut crc) = (None, None, None, None);
+        let mut cache_hint = None;
+        for line in lines {
+            let tag = |name: &str| line.strip_prefix(name);
+            if let Some(v) = tag("#EXT-X-TARGETDURATION:") {
+                manifest.target_duration = parse_field(v, line)?;
+            } else if let Some(v) = tag("#EXT-X-ALICE-CONTENT:") {
+                manifest.content_id = v.to_string();
+            } else if let Some(v) = tag("#EXT-X-ALICE-START:") {
+                start = Some(parse_field::<f32>(v, line)?);
+            } else if let Some(v) = tag("#EXTINF:") {
+                duration = Some(parse_field::<f32>(v.trim_end_matches(','), line)?);
+            } else if let Some(v) = tag("#EXT-X-BYTERANGE:") {
+                let (len, offset) = v
+                    .split_once('@')
+                    .ok_or_else(|| invalid(format!("Bad byte range '{}'", line)))?;
+                range = Some((
+                    parse_field::<usize>(len, line)?,
+                    parse_field::<u64>(offset, line)?,
+                ));
+            } else if let Some(v) = tag("#EXT-X-ALICE-CRC:") {
+                crc = Some(
+                    u32::from_str_radix(v, 16)
+                        .map_err(|_| invalid(format!("Bad CRC '{}'", line)))?,
+                );
+            } else if let Some(v) = tag("#EXT-X-ALICE-CACHE:") {
+                cache_hint = Some(
+                    CdnCacheHint::from_tag(v)
+                        .ok_or_else(|| invalid(format!("Bad cache hint '{}'", line)))?,
+                );
+            } else if line.starts_with('#') {
+                continue; // Unknown tags are ignored, as in HLS
+            } else {
+                let (Some(duration), Some((size_bytes, byte_offset)), Some(crc32)) =
+                    (duration.take(), range.take(), crc.take())
+                else {
+                    return Err(invalid(format!("Segment '{}' is missing tags", line)));
+                };
+                let start_time = start
+                    .take()
+                    .unwrap_or_else(|| manifest.segments.last().map_or(0.0, |s| s.end_time));
+                manifest.segments.push(CdnSegment {
+                    index: manifest.segments.len() as u32,
+                    uri: line.to_string(),
+                    start_time,
+                    end_time: start_time + duration,
+                    size_bytes,
+                    crc32,
+                    byte_offset,
+                    cache_hint: cache_hint.take().unwrap_or(CdnCacheHint::Warm),
+                });
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+fn parse_field<T: std::str::FromStr>(value: &str, line: &str) -> io::Result<T> {
+    value.trim().parse().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Bad manifest line '{}'", line),
+        )
+    })
+}
+
+/// Cut points for `policy`, in seconds.
+fn segment_split_points(episode: &EpisodePackage, policy: SegmentPolicy) -> Vec<f32> {
+    let cuts = episode.director.cuts().map(|(_, c)| c);
+    match policy {
+        SegmentPolicy::PerCut => cuts.skip(1).map(|c| c.start_time).collect(),
+        SegmentPolicy::Duration(seconds) => {
+            let mut points = Vec::new();
+            let mut segment_start = 0.0f32;
+            for cut in cuts {
+                if cut.end_time - segment_start >= seconds {
+                    points.push(cut.end_time);
+                    segment_start = cut.end_time;
+                }
+            }
+            points
+        }
+    }
+}
+
+/// Split an episode into independently playable ANIM segments and describe them in a
+/// manifest (with default `SegmentCacheHints`). Returns the manifest and each segment's
+/// bytes (in manifest order).
+pub fn build_segment_manifest(
+    episode: &EpisodePackage,
+    policy: SegmentPolicy,
+) -> io::Result<(SegmentManifest, Vec<Vec<u8>>)> {
+    let content_id = episode_to_cdn_descriptor(episode, CdnCacheHint::Hot).content_id;
+    let parts = split_at_cuts(episode, &segment_split_points(episode, policy))?;
+
+    let mut segments = Vec::with_capacity(parts.len());
+    let mut blobs = Vec::with_capacity(parts.len());
+    let mut byte_offset = 0u64;
+    for (index, part) in parts.iter().enumerate() {
+        let mut bytes = Vec::new();
+        let size_bytes = serialize_episode(part, &mut bytes)?;
+        let start_time = part
+            .metadata
+            .extension(PART_OFFSET_KEY)
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0) as f32;
+        segments.push(CdnSegment {
+            index: index as u32,
+            uri: format!("{}/seg{:04}.anim", content_id, index),
+            start_time,
+            end_time: start_time + part.metadata.duration_seconds,
+            size_bytes,
+            crc32: crc32fast::hash(&bytes),
+            byte_offset,
+            cache_hint: CdnCacheHint::Warm,
+        });
+        byte_offset += size_bytes as u64;
+        blobs.push(bytes);
+    }
+    let target_duration = segments
+        .iter()
+        .map(|s| s.duration().ceil() as u32)
+        .max()
+        .unwrap_or(0);
+    let mut manifest = SegmentManifest {
+        content_id,
+        target_duration,
+        segments,
+    };
+    manifest.apply_cache_hints(&SegmentCacheHints::default());
+    Ok((manifest, blobs))
+}
+
+/// Check a downloaded segment against its manifest entry and decode it.
+pub(crate) fn decode_segment(segment: &CdnSegment, bytes: &[u8]) -> io::Result<EpisodePackage> {
+    if bytes.len() != segment.size_bytes || crc32fast::hash(bytes) != segment.crc32 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Segment {} failed size/CRC check", segment.index),
+        ));
+    }
+    Ok(deserialize_episode(&mut &bytes[..])?)
+}
+
+/// Client-side reassembly of a segmented episode; segments may arrive in any order.
+pub struct SegmentAssembler {
+    manifest: SegmentManifest,
+    parts: Vec<Option<EpisodePackage>>,
+}
+
+impl SegmentAssembler {
+    pub fn new(manifest: SegmentManifest) -> Self {
+        let parts = vec![None; manifest.segments.len()];
+        Self { manifest, parts }
+    }
+
+    pub fn manifest(&self) -> &SegmentManifest {
+        &self.manifest
+    }
+
+    /// Verify and decode one downloaded segment.
+    pub fn push_segment(&mut self, index: u32, bytes: &[u8]) -> io::Result<()> {
+        let part = decode_segment(self.segment(index)?, bytes)?;
+        self.parts[index as usize] = Some(part);
+        Ok(())
+    }
+
+    pub(crate) fn segment(&self, index: u32) -> io::Result<&CdnSegment> {
+        self.manifest.segments.get(index as usize).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("No segment {}", index))
+        })
+    }
+
+    /// Store a segment already checked and decoded by [`decode_segment`].
+    pub(crate) fn insert_part(&mut self, index: u32, part: EpisodePackage) {
+        self.parts[index as usize] = Some(part);
+    }
+
+    /// Next segment to download: the first one still missing.
+    pub fn next_missing(&self) -> Option<u32> {
+        self.parts
+            .iter()
+            .position(Option::is_none)
+            .map(|i| i as u32)
+    }
+
+    /// Playback can proceed up to this time using the contiguous run of received segments.
+    pub fn playable_until(&self) -> f32 {
+        self.parts
+            .iter()
+            .zip(&self.manifest.segments)
+            .take_while(|(part, _)| part.is_some())
+            .last()
+            .map_or(0.0, |(_, seg)| seg.end_time)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.next_missing().is_none()
+    }
+
+    /// Episode made of the contiguous run of received segments, for progressive playback.
+    pub fn assemble_prefix(&self) -> io::Result<EpisodePackage> {
+        let parts: Vec<EpisodePackage> = self.parts.iter().map_while(|p| p.clone()).collect();
+        Ok(join_episodes(&parts)?)
+    }
+
+    /// Full episode; fails while any segment is missing.
+    pub fn assemble(&self) -> io::Result<EpisodePackage> {
+        if let Some(index) = self.next_missing() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Segment {} not received", index),
+            ));
+        }
+        self.assemble_prefix()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -64,7 +494,115 @@ mod tests {
 
         let descriptor = episode_to_cdn_descriptor(&episode, CdnCacheHint::Hot);
         assert_eq!(descriptor.metadata.episode_number, 1);
-        assert!(descriptor.size_bytes > 0);
+        let mut bytes = Vec::new();
+        serialize_episode(&episode, &mut bytes).unwrap();
+        assert_eq!(descriptor.size_bytes, bytes.len());
+    }
+
+    #[test]
+    fn test_plan_incremental_push() {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 60.0));
+        dir.add_cut(Cut::new("c2", 60.0, 120.0));
+        let meta = EpisodeMetadata::new("CDN Patch", 1, 120.0);
+        let old = EpisodePackage::new(meta, sg, dir, AnimeShading::default());
+        let mut new = old.clone();
+        let (id, _) = new.director.find_active_cut(90.0).unwrap();
+        new.director.get_cut_mut(id).unwrap().name = "c2_fixed".into();
+
+        match plan_incremental_push(&old, &new, CdnCacheHint::Hot).unwrap() {
+            IncrementalPush::Patch(desc) => {
+                assert!(desc.content_id.starts_with(&desc.base_content_id));
+                assert_eq!(desc.patch.changed_chunks(), 1);
+            }
+            IncrementalPush::Full(_) => panic!("Expected patch push"),
+        }
+    }
+
+    fn make_three_cut_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 4.0));
+        dir.add_cut(Cut::new("c2", 4.0, 6.0));
+        dir.add_cut(Cut::new("c3", 6.0, 12.0));
+        let meta = EpisodeMetadata::new("Segments", 3, 12.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_segment_manifest_roundtrip() {
+        let episode = make_three_cut_episode();
+        let (per_cut, _) = build_segment_manifest(&episode, SegmentPolicy::PerCut).unwrap();
+        assert_eq!(per_cut.segments.len(), 3);
+
+        let (manifest, blobs) =
+            build_segment_manifest(&episode, SegmentPolicy::Duration(5.0)).unwrap();
+        let bounds: Vec<(f32, f32)> = manifest
+            .segments
+            .iter()
+            .map(|s| (s.start_time, s.end_time))
+            .collect();
+        assert_eq!(bounds, vec![(0.0, 6.0), (6.0, 12.0)]);
+        assert_eq!(manifest.target_duration, 6);
+        assert_eq!(manifest.segments[1].byte_offset, blobs[0].len() as u64);
+        assert_eq!(
+            manifest.total_bytes(),
+            blobs.iter().map(|b| b.len() as u64).sum::<u64>()
+        );
+        assert_eq!(manifest.segment_at(7.0).unwrap().index, 1);
+
+        let parsed = SegmentManifest::parse_m3u8(&manifest.to_m3u8()).unwrap();
+        assert_eq!(parsed, manifest);
+    }
+
+    #[test]
+    fn test_segment_cache_hints() {
+        let episode = make_three_cut_episode();
+        let (mut manifest, _) = build_segment_manifest(&episode, SegmentPolicy::PerCut).unwrap();
+        // Default OP/ED windows cover this whole short episode
+        assert!(manifest
+            .segments
+            .iter()
+            .all(|s| s.cache_hint == CdnCacheHint::Hot));
+
+        manifest.apply_cache_hints(&SegmentCacheHints {
+            opening_seconds: 4.0,
+            ending_seconds: 6.0,
+            ..Default::default()
+        });
+        let hints: Vec<CdnCacheHint> = manifest.segments.iter().map(|s| s.cache_hint).collect();
+        assert_eq!(
+            hints,
+            vec![CdnCacheHint::Hot, CdnCacheHint::Warm, CdnCacheHint::Hot]
+        );
+        let parsed = SegmentManifest::parse_m3u8(&manifest.to_m3u8()).unwrap();
+        assert_eq!(parsed.segments[1].cache_hint, CdnCacheHint::Warm);
+    }
+
+    #[test]
+    fn test_segment_assembler() {
+        let episode = make_three_cut_episode();
+        let (manifest, blobs) = build_segment_manifest(&episode, SegmentPolicy::PerCut).unwrap();
+        let mut assembler = SegmentAssembler::new(manifest);
+
+        assert!(assembler.push_segment(1, &blobs[0]).is_err());
+        assembler.push_segment(1, &blobs[1]).unwrap();
+        assert_eq!(assembler.playable_until(), 0.0);
+        assert_eq!(assembler.next_missing(), Some(0));
+        assert!(assembler.assemble().is_err());
+
+        assembler.push_segment(0, &blobs[0]).unwrap();
+        assert_eq!(assembler.playable_until(), 6.0);
+        assert_eq!(assembler.assemble_prefix().unwrap().director.cut_count(), 2);
+
+        assembler.push_segment(2, &blobs[2]).unwrap();
+        let joined = assembler.assemble().unwrap();
+        assert_eq!(joined.director.cut_count(), 3);
+        assert_eq!(joined.director.duration(), 12.0);
+        assert_eq!(joined.metadata.duration_seconds, 12.0);
     }
 
     #[test]
@@ -0,0 +1,699 @@
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::director::{Cut, CutId, Director, Episode};
+use crate::episode::{
+    header_version, read_header, read_sized, EpisodeMetadata, EpisodePackage, CHUNKED_VERSION,
+    EPISODE_MAGIC,
+};
+use crate::error::AnimationError;
+use crate::npr::AnimeShading;
+use crate::overlay::Overlay;
+use crate::render::RenderSettings;
+use crate::scene::SceneGraph;
+
+/// Largest chunk payload a reader accepts; a bigger size field is treated as corruption.
+pub(crate) const MAX_CHUNK_SIZE: usize = 1 << 30;
+
+/// Section kind of an episode chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChunkKind {
+    /// `EpisodeMetadata`.
+    Metadata,
+    /// Full `SceneGraph`.
+    SceneGraph,
+    /// `AnimeShading` settings.
+    Shading,
+    /// Director header: episode structure + cut ID counter.
+    Director,
+    /// A single `(CutId, Cut)` pair.
+    Cut,
+    /// `RenderSettings`, present only when the episode stores them.
+    RenderSettings,
+    /// Episode overlays, present only when the episode has any.
+    Overlays,
+}
+
+impl ChunkKind {
+    /// Four-byte tag used in binary containers.
+    #[inline]
+    pub fn tag(self) -> [u8; 4] {
+        match self {
+            ChunkKind::Metadata => *b"META",
+            ChunkKind::SceneGraph => *b"SCNE",
+            ChunkKind::Shading => *b"SHAD",
+            ChunkKind::Director => *b"DIRC",
+            ChunkKind::Cut => *b"CUT_",
+            ChunkKind::RenderSettings => *b"REND",
+            ChunkKind::Overlays => *b"OVLY",
+        }
+    }
+
+    /// Parse a four-byte tag. Unknown tags return `None`.
+    #[inline]
+    pub fn from_tag(tag: [u8; 4]) -> Option<Self> {
+        match &tag {
+            b"META" => Some(ChunkKind::Metadata),
+            b"SCNE" => Some(ChunkKind::SceneGraph),
+            b"SHAD" => Some(ChunkKind::Shading),
+            b"DIRC" => Some(ChunkKind::Director),
+            b"CUT_" => Some(ChunkKind::Cut),
+            b"REND" => Some(ChunkKind::RenderSettings),
+            b"OVLY" => Some(ChunkKind::Overlays),
+            _ => None,
+        }
+    }
+}
+
+/// One independently encoded section of an episode.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Chunk {
+    pub kind: ChunkKind,
+    pub data: Vec<u8>,
+}
+
+impl Chunk {
+    /// CRC32 of the chunk payload.
+    #[inline]
+    pub fn crc(&self) -> u32 {
+        crc32fast::hash(&self.data)
+    }
+}
+
+/// Director contents minus the cuts, which are stored one per chunk.
+#[derive(Serialize, Deserialize)]
+struct DirectorHeader {
+    episode: Episode,
+    next_cut_id: u32,
+}
+
+pub(crate) fn encode<T: Serialize>(value: &T) -> crate::error::Result<Vec<u8>> {
+    Ok(bincode::serialize(value)?)
+}
+
+pub(crate) fn decode<'a, T: Deserialize<'a>>(data: &'a [u8]) -> crate::error::Result<T> {
+    Ok(bincode::deserialize(data)?)
+}
+
+/// Split an episode into chunks: META, SCNE, SHAD, DIRC, one CUT_ per cut in start-time order,
+/// then REND if the episode stores render settings and OVLY if it has overlays.
+pub fn split_episode(episode: &EpisodePackage) -> crate::error::Result<Vec<Chunk>> {
+    let mut chunks = Vec::with_capacity(4 + episode.director.cut_count());
+    chunks.push(Chunk {
+        kind: ChunkKind::Metadata,
+        data: encode(&episode.metadata)?,
+    });
+    chunks.push(Chunk {
+        kind: ChunkKind::SceneGraph,
+        data: encode(&episode.scene_graph)?,
+    });
+    chunks.push(Chunk {
+        kind: ChunkKind::Shading,
+        data: encode(&episode.shading)?,
+    });
+    chunks.push(Chunk {
+        kind: ChunkKind::Director,
+        data: encode(&DirectorHeader {
+            episode: episode.director.episode.clone(),
+            next_cut_id: episode.director.next_cut_id(),
+        })?,
+    });
+    for (id, cut) in episode.director.cuts() {
+        chunks.push(Chunk {
+            kind: ChunkKind::Cut,
+            data: encode(&(id, cut))?,
+        });
+    }
+    if let Some(settings) = &episode.render_settings {
+        chunks.push(Chunk {
+            kind: ChunkKind::RenderSettings,
+            data: encode(settings)?,
+        });
+    }
+    if !episode.overlays.is_empty() {
+        chunks.push(Chunk {
+            kind: ChunkKind::Overlays,
+            data: encode(&episode.overlays)?,
+        });
+    }
+    Ok(chunks)
+}
+
+/// Reassemble an episode from chunks produced by `split_episode`.
+pub fn assemble_episode(chunks: &[Chunk]) -> crate::error::Result<EpisodePackage> {
+    let mut metadata: Option<EpisodeMetadata> = None;
+    let mut scene_graph: Option<SceneGraph> = None;
+    let mut shading: Option<AnimeShading> = None;
+    let mut header: Option<DirectorHeader> = None;
+    let mut cuts: Vec<(CutId, Cut)> = Vec::new();
+    let mut render_settings: Option<RenderSettings> = None;
+    let mut overlays: Vec<Overlay> = Vec::new();
+
+    for chunk in chunks {
+        match chunk.kind {
+            ChunkKind::Metadata => metadata = Some(decode(&chunk.data)?),
+            ChunkKind::SceneGraph => scene_graph = Some(decode(&chunk.data)?),
+            ChunkKind::Shading => shading = Some(decode(&chunk.data)?),
+            ChunkKind::Director => header = Some(decode(&chunk.data)?),
+            ChunkKind::Cut => cuts.push(decode(&chunk.data)?),
+            ChunkKind::RenderSettings => render_settings = Some(decode(&chunk.data)?),
+            ChunkKind::Overlays => overlays = decode(&chunk.data)?,
+        }
+    }
+
+    let missing = |tag: &str| AnimationError::Corrupt(format!("Missing {} chunk", tag));
+    let metadata = metadata.ok_or_else(|| missing("META"))?;
+    let scene_graph = scene_graph.ok_or_else(|| missing("SCNE"))?;
+    let shading = shading.ok_or_else(|| missing("SHAD"))?;
+    let header = header.ok_or_else(|| missing("DIRC"))?;
+
+    let director = Director::from_sorted_cuts(header.episode, cuts, header.next_cut_id);
+    let mut episode = EpisodePackage::new(metadata, scene_graph, director, shading);
+    episode.render_settings = render_settings;
+    episode.overlays = overlays;
+    Ok(episode)
+}
+
+/// CRC32 over an ordered chunk list (tags + payloads).
+pub fn chunks_crc(chunks: &[Chunk]) -> u32 {
+    let mut hasher = crc32fast::Hasher::new();
+    for chunk in chunks {
+        hasher.update(&chunk.kind.tag());
+        hasher.update(&(chunk.data.len() as u32).to_le_bytes());
+        hasher.update(&chunk.data);
+    }
+    hasher.finalize()
+}
+
+/// Why a chunk could not be used.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionFailureReason {
+    /// Payload CRC does not match the chunk header.
+    CrcMismatch { expected: u32, actual: u32 },
+    /// CRC was fine but the payload did not decode.
+    Decode(String),
+    /// Tag is not four ASCII uppercase letters, digits or underscores.
+    BadTag,
+    /// The record could not be read (bad size field or truncated file); later chunks are lost.
+    Unreadable(String),
+}
+
+/// A chunk that failed validation during a recovering load.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionFailure {
+    /// Position of the chunk in the file.
+    pub index: usize,
+    pub tag: [u8; 4],
+    pub reason: SectionFailureReason,
+}
+
+/// Result of `deserialize_episode_recovering`.
+#[derive(Debug, Clone)]
+pub struct RecoveredEpisode {
+    pub episode: EpisodePackage,
+    /// Sections that were skipped or replaced by defaults.
+    pub failed_sections: Vec<SectionFailure>,
+}
+
+impl RecoveredEpisode {
+    /// True if every chunk validated.
+    #[inline]
+    pub fn is_intact(&self) -> bool {
+        self.failed_sections.is_empty()
+    }
+}
+
+/// A chunk read from a v2 container, not yet validated.
+pub(crate) struct RawChunk {
+    pub(crate) tag: [u8; 4],
+    crc: u32,
+    pub(crate) data: Vec<u8>,
+}
+
+impl RawChunk {
+    pub(crate) fn check_crc(&self) -> Result<(), SectionFailureReason> {
+        let actual = crc32fast::hash(&self.data);
+        if actual != self.crc {
+            return Err(SectionFailureReason::CrcMismatch {
+                expected: self.crc,
+                actual,
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn check_tag(&self) -> Result<(), SectionFailureReason> {
+        let well_formed = self
+            .tag
+            .iter()
+            .all(|&b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_');
+        if !well_formed {
+            return Err(SectionFailureReason::BadTag);
+        }
+        Ok(())
+    }
+
+    /// The chunk itself if its tag is well formed and its CRC matches.
+    pub(crate) fn checked(self) -> crate::error::Result<Self> {
+        match self.check_tag().and_then(|_| self.check_crc()) {
+            Err(SectionFailureReason::CrcMismatch { expected, actual }) => {
+                Err(AnimationError::CrcMismatch { expected, actual })
+            }
+            Err(_) => Err(AnimationError::Corrupt(format!(
+                "Malformed chunk tag {:02x?}",
+                self.tag
+            ))),
+            Ok(()) => Ok(self),
+        }
+    }
+}
+
+/// Serialize an episode in the chunked (v2) format with a CRC per chunk.
+///
+/// Binary format:
+/// `[Magic "ANIM" 4B][Version=2 2B][Flags 2B][ChunkCount 4B][HeaderCRC32 4B]`
+/// followed by `ChunkCount` x `[Tag 4B][Size 4B][CRC32 4B][Payload]`.
+/// HeaderCRC covers the first 12 header bytes.
+pub fn serialize_episode_chunked<W: Write>(
+    episode: &EpisodePackage,
+    writer: &mut W,
+) -> crate::error::Result<usize> {
+    let chunks = split_episode(episode)?;
+    write_chunks(&chunks, writer)
+}
+
+/// Write an ordered chunk list as a v2 container.
+pub(crate) fn write_chunks<W: Write>(
+    chunks: &[Chunk],
+    writer: &mut W,
+) -> crate::error::Result<usize> {
+    let flags: u16 = 0;
+    let mut header = [0u8; 16];
+    header[0..4].copy_from_slice(&EPISODE_MAGIC);
+    header[4..6].copy_from_slice(&CHUNKED_VERSION.to_le_bytes());
+    header[6..8].copy_from_slice(&flags.to_le_bytes());
+    header[8..12].copy_from_slice(&(chunks.len() as u32).to_le_bytes());
+    let header_crc = crc32fast::hash(&header[0..12]);
+    header[12..16].copy_from_slice(&header_crc.to_le_bytes());
+    writer.write_all(&header)?;
+
+    let mut written = 16;
+    for chunk in chunks {
+        written += write_chunk_record(writer, chunk.kind.tag(), &chunk.data)?;
+    }
+    Ok(written)
+}
+
+/// Write one `[Tag 4B][Size 4B][CRC32 4B][Payload]` record. Returns bytes written.
+pub(crate) fn write_chunk_record<W: Write>(
+    writer: &mut W,
+    tag: [u8; 4],
+    data: &[u8],
+) -> crate::error::Result<usize> {
+    writer.write_all(&tag)?;
+    writer.write_all(&(data.len() as u32).to_le_bytes())?;
+    writer.write_all(&crc32fast::hash(data).to_le_bytes())?;
+    writer.write_all(data)?;
+    Ok(12 + data.len())
+}
+
+/// Read one chunk record (tag and CRC not yet checked).
+///
+/// The payload buffer grows as data arrives, so a damaged size field cannot allocate more than
+/// the reader holds; sizes past [`MAX_CHUNK_SIZE`] are rejected up front.
+pub(crate) fn read_raw_chunk<R: Read>(reader: &mut R) -> crate::error::Result<RawChunk> {
+    read_chunk_record(reader).map_err(|(_, e)| e)
+}
+
+/// `read_raw_chunk`, with the record's tag (zero if the record header is missing) on failure.
+fn read_chunk_record<R: Read>(reader: &mut R) -> Result<RawChunk, ([u8; 4], AnimationError)> {
+    let mut chunk_header = [0u8; 12];
+    reader
+        .read_exact(&mut chunk_header)
+        .map_err(|e| ([0; 4], e.into()))?;
+    let tag = [
+        chunk_header[0],
+        chunk_header[1],
+        chunk_header[2],
+        chunk_header[3],
+    ];
+    let size = u32::from_le_bytes([
+        chunk_header[4],
+        chunk_header[5],
+        chunk_header[6],
+        chunk_header[7],
+    ]) as usize;
+    let crc = u32::from_le_bytes([
+        chunk_header[8],
+        chunk_header[9],
+        chunk_header[10],
+        chunk_header[11],
+    ]);
+    if size > MAX_CHUNK_SIZE {
+        return Err((
+            tag,
+            AnimationError::Corrupt(format!(
+                "Chunk size {size} exceeds the {MAX_CHUNK_SIZE} byte limit"
+            )),
+        ));
+    }
+    let data = read_sized(reader, size, "chunk payload").map_err(|e| (tag, e))?;
+    Ok(RawChunk { tag, crc, data })
+}
+
+/// Validate the CRC over the first 12 bytes of a v2/v3 header.
+pub(crate) fn check_header_crc(header: &[u8; 16]) -> crate::error::Result<()> {
+    let expected_crc = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
+    let actual_crc = crc32fast::hash(&header[0..12]);
+    if actual_crc != expected_crc {
+        return Err(AnimationError::CrcMismatch {
+            expected: expected_crc,
+            actual: actual_crc,
+        });
+    }
+    Ok(())
+}
+
+/// Validate a v2 header and return its chunk count.
+fn chunk_count(header: &[u8; 16]) -> crate::error::Result<usize> {
+    let version = header_version(header);
+    if version != CHUNKED_VERSION {
+        return Err(AnimationError::UnsupportedVersion {
+            format: "chunked",
+            version,
+        });
+    }
+    check_header_crc(header)?;
+
+    Ok(u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize)
+}
+
+/// Strict v2 load: any corrupt chunk is an error. Called by `deserialize_episode`.
+pub(crate) fn read_chunked_strict<R: Read>(
+    header: &[u8; 16],
+    reader: &mut R,
+) -> crate::error::Result<EpisodePackage> {
+    let count = chunk_count(header)?;
+    let mut chunks = Vec::with_capacity(count.min(4096));
+    for _ in 0..count {
+        let r = read_raw_chunk(reader)?.checked()?;
+        // Unknown (newer/ancillary) chunks are skipped
+        if let Some(kind) = ChunkKind::from_tag(r.tag) {
+            chunks.push(Chunk { kind, data: r.data });
+        }
+    }
+    assemble_episode(&chunks)
+}
+
+/// Recovering v2 load: validates each chunk independently.
+///
+/// - Corrupt `META` / `SHAD` / `DIRC` chunks are replaced by defaults derived from the rest.
+/// - Corrupt `CUT_` chunks drop that cut; a corrupt `REND` chunk falls back to default settings
+///   and a corrupt `OVLY` chunk to no overlays.
+/// - Unknown ancillary chunks (e.g. thumbnails from newer writers) are CRC-checked but otherwise ignored.
+/// - A record with a malformed tag is reported and skipped. A record that cannot be read (bad
+///   size field, truncated file) is reported with a zero tag if its header is missing, and ends
+///   the load: the chunks after it are lost.
+/// - A corrupt or lost `SCNE` chunk, or a broken header, is unrecoverable and returns an error.
+pub fn deserialize_episode_recovering<R: Read>(
+    reader: &mut R,
+) -> crate::error::Result<RecoveredEpisode> {
+    let header = read_header(reader)?;
+    let count = chunk_count(&header)?;
+
+    let mut failed_sections = Vec::new();
+    let mut metadata: Option<EpisodeMetadata> = None;
+    let mut scene_graph: Option<SceneGraph> = None;
+    let mut shading: Option<AnimeShading> = None;
+    let mut director_header: Option<DirectorHeader> = None;
+    let mut cuts: Vec<(CutId, Cut)> = Vec::new();
+    let mut render_settings: Option<RenderSettings> = None;
+    let mut overlays: Vec<Overlay> = Vec::new();
+
+    for index in 0..count {
+        let r = match read_chunk_record(reader) {
+            Ok(r) => r,
+            Err((tag, e)) => {
+                failed_sections.push(SectionFailure {
+                    index,
+                    tag,
+                    reason: SectionFailureReason::Unreadable(e.to_string()),
+                });
+                break;
+            }
+        };
+        let decoded = r.check_tag().and_then(|_| r.check_crc()).and_then(|_| {
+            let fail = |e: AnimationError| SectionFailureReason::Decode(e.to_string());
+            match ChunkKind::from_tag(r.tag) {
+                Some(ChunkKind::Metadata) => metadata = Some(decode(&r.data).map_err(fail)?),
+                Some(ChunkKind::SceneGraph) => scene_graph = Some(decode(&r.data).map_err(fail)?),
+                Some(ChunkKind::Shading) => shading = Some(decode(&r.data).map_err(fail)?),
+                Some(ChunkKind::Director) => director_header = Some(decode(&r.data).map_err(fail)?),
+                Some(ChunkKind::Cut) => cuts.push(decode(&r.data).map_err(fail)?),
+                Some(ChunkKind::RenderSettings) => {
+                    render_settings = Some(decode(&r.data).map_err(fail)?)
+                }
+                Some(ChunkKind::Overlays) => overlays = decode(&r.data).map_err(fail)?,
+                None => {}
+            }
+            Ok(())
+        });
+        if let Err(reason) = decoded {
+            failed_sections.push(SectionFailure {
+                index,
+                tag: r.tag,
+                reason,
+            });
+        }
+    }
+
+    let scene_graph = scene_graph.ok_or_else(|| {
+        AnimationError::Corrupt(
+            "Scene graph chunk missing or corrupt: episode unrecoverable".into(),
+        )
+    })?;
+    let director_header = director_header.unwrap_or_else(|| DirectorHeader {
+        episode: Episode::new(""),
+        next_cut_id: cuts.iter().map(|(id, _)| id.0 + 1).max().unwrap_or(0),
+    });
+    let director =
+        Director::from_sorted_cuts(director_header.episode, cuts, director_header.next_cut_id);
+    let metadata = metadata.unwrap_or_else(|| {
+        EpisodeMetadata::new(director.episode.name.clone(), 0, director.duration())
+    });
+    let mut episode =
+        EpisodePackage::new(metadata, scene_graph, director, shading.unwrap_or_default());
+    episode.render_settings = render_settings;
+    episode.overlays = overlays;
+
+    Ok(RecoveredEpisode {
+        episode,
+        failed_sections,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::Cut;
+    use crate::scene::Actor;
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Chunked");
+        dir.add_cut(Cut::new("intro", 0.0, 3.0).with_actors(vec![hero]));
+        dir.add_cut(Cut::new("battle", 3.0, 8.0).with_actors(vec![hero]));
+        let meta = EpisodeMetadata::new("Chunked", 2, 8.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_split_assemble_roundtrip() {
+        let episode = make_test_episode();
+        let chunks = split_episode(&episode).unwrap();
+        assert_eq!(chunks.len(), 4 + 2);
+        assert_eq!(chunks[4].kind, ChunkKind::Cut);
+
+        let restored = assemble_episode(&chunks).unwrap();
+        assert_eq!(restored.metadata.title, "Chunked");
+        assert_eq!(restored.director.cut_count(), 2);
+        assert_eq!(
+            chunks_crc(&split_episode(&restored).unwrap()),
+            chunks_crc(&chunks)
+        );
+    }
+
+    #[test]
+    fn test_render_settings_chunk() {
+        let settings = RenderSettings::preview().with_resolution(320, 180);
+        let episode = make_test_episode().with_render_settings(settings);
+        let chunks = split_episode(&episode).unwrap();
+        assert_eq!(chunks.len(), 4 + 2 + 1);
+        assert_eq!(chunks[6].kind, ChunkKind::RenderSettings);
+        let restored = assemble_episode(&chunks).unwrap();
+        assert_eq!(restored.render_settings, Some(settings));
+
+        let mut buf = Vec::new();
+        crate::stream::serialize_episode_streamed(&episode, &mut buf).unwrap();
+        let streamed =
+            crate::episode::deserialize_episode(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(streamed.render_settings, Some(settings));
+    }
+
+    #[test]
+    fn test_overlays_chunk() {
+        use crate::overlay::{OverlayContent, OverlayKind};
+        let card = Overlay::new(
+            "title",
+            OverlayKind::TitleCard,
+            OverlayContent::Solid([0.0, 0.0, 0.0, 1.0]),
+            0.0,
+            2.0,
+        );
+        let episode = make_test_episode().with_overlay(card);
+        let chunks = split_episode(&episode).unwrap();
+        assert_eq!(chunks.last().unwrap().kind, ChunkKind::Overlays);
+        assert_eq!(assemble_episode(&chunks).unwrap().overlays[0].name, "title");
+
+        let mut buf = Vec::new();
+        crate::stream::serialize_episode_streamed(&episode, &mut buf).unwrap();
+        let streamed =
+            crate::episode::deserialize_episode(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(streamed.overlays[0].kind, OverlayKind::TitleCard);
+    }
+
+    #[test]
+    fn test_missing_chunk() {
+        let episode = make_test_episode();
+        let chunks: Vec<Chunk> = split_episode(&episode)
+            .unwrap()
+            .into_iter()
+            .filter(|c| c.kind != ChunkKind::SceneGraph)
+            .collect();
+        assert!(assemble_episode(&chunks).is_err());
+    }
+
+    /// Byte offset of the payload of chunk `index` in a v2 buffer.
+    fn payload_offset(buf: &[u8], index: usize) -> usize {
+        let mut pos = 16;
+        for _ in 0..index {
+            let size = u32::from_le_bytes([buf[pos + 4], buf[pos + 5], buf[pos + 6], buf[pos + 7]]);
+            pos += 12 + size as usize;
+        }
+        pos + 12
+    }
+
+    #[test]
+    fn test_chunked_roundtrip() {
+        let episode = make_test_episode();
+        let mut buf = Vec::new();
+        let written = serialize_episode_chunked(&episode, &mut buf).unwrap();
+        assert_eq!(written, buf.len());
+
+        let restored =
+            crate::episode::deserialize_episode(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(restored.director.cut_count(), 2);
+
+        let recovered = deserialize_episode_recovering(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert!(recovered.is_intact());
+    }
+
+    #[test]
+    fn test_recover_corrupt_shading_and_cut() {
+        let episode = make_test_episode();
+        let mut buf = Vec::new();
+        serialize_episode_chunked(&episode, &mut buf).unwrap();
+
+        // Flip a bit in SHAD (index 2) and the second cut (index 5)
+        let shad = payload_offset(&buf, 2);
+        buf[shad] ^= 0x01;
+        let cut = payload_offset(&buf, 5);
+        buf[cut] ^= 0x01;
+
+        // Strict load fails
+        assert!(matches!(
+            crate::episode::deserialize_episode(&mut std::io::Cursor::new(&buf)),
+            Err(AnimationError::CrcMismatch { .. })
+        ));
+
+        let recovered = deserialize_episode_recovering(&mut std::io::Cursor::new(&buf)).unwrap();
+        let failed: Vec<usize> = recovered.failed_sections.iter().map(|f| f.index).collect();
+        assert_eq!(failed, vec![2, 5]);
+        assert_eq!(&recovered.failed_sections[0].tag, b"SHAD");
+        assert_eq!(recovered.episode.director.cut_count(), 1);
+        assert_eq!(recovered.episode.metadata.title, "Chunked");
+    }
+
+    #[test]
+    fn test_recover_corrupt_size_and_tag() {
+        let episode = make_test_episode();
+        let mut clean = Vec::new();
+        serialize_episode_chunked(&episode, &mut clean).unwrap();
+
+        // Second cut (index 5) claims 4 GiB; nothing close to that is allocated
+        let mut buf = clean.clone();
+        let size = payload_offset(&buf, 5) - 8;
+        buf[size..size + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(crate::episode::deserialize_episode(&mut std::io::Cursor::new(&buf)).is_err());
+        let recovered = deserialize_episode_recovering(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(recovered.failed_sections.len(), 1);
+        assert_eq!(recovered.failed_sections[0].index, 5);
+        assert_eq!(&recovered.failed_sections[0].tag, b"CUT_");
+        assert!(matches!(
+            recovered.failed_sections[0].reason,
+            SectionFailureReason::Unreadable(_)
+        ));
+        assert_eq!(recovered.episode.director.cut_count(), 1);
+
+        // Within the limit but past the end of the file
+        buf[size..size + 4].copy_from_slice(&(1u32 << 20).to_le_bytes());
+        let recovered = deserialize_episode_recovering(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(recovered.failed_sections[0].index, 5);
+
+        // First cut (index 4) has a garbled tag: reported, later chunks still load
+        let mut buf = clean;
+        let tag = payload_offset(&buf, 4) - 12;
+        buf[tag] = 0xC3;
+        assert!(matches!(
+            crate::episode::deserialize_episode(&mut std::io::Cursor::new(&buf)),
+            Err(AnimationError::Corrupt(_))
+        ));
+        let recovered = deserialize_episode_recovering(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(recovered.failed_sections.len(), 1);
+        assert_eq!(recovered.failed_sections[0].index, 4);
+        assert_eq!(
+            recovered.failed_sections[0].reason,
+            SectionFailureReason::BadTag
+        );
+        assert_eq!(recovered.episode.director.cut_count(), 1);
+    }
+
+    #[test]
+    fn test_recover_corrupt_scene_fails() {
+        let episode = make_test_episode();
+        let mut buf = Vec::new();
+        serialize_episode_chunked(&episode, &mut buf).unwrap();
+        let scene = payload_offset(&buf, 1);
+        buf[scene] ^= 0x01;
+        assert!(deserialize_episode_recovering(&mut std::io::Cursor::new(&buf)).is_err());
+    }
+
+    #[test]
+    fn test_tag_roundtrip() {
+        for kind in [
+            ChunkKind::Metadata,
+            ChunkKind::SceneGraph,
+            ChunkKind::Shading,
+            ChunkKind::Director,
+            ChunkKind::Cut,
+            ChunkKind::RenderSettings,
+            ChunkKind::Overlays,
+        ] {
+            assert_eq!(ChunkKind::from_tag(kind.tag()), Some(kind));
+        }
+        assert_eq!(ChunkKind::from_tag(*b"????"), None);
+    }
+}
@@ -0,0 +1,404 @@
+//! `alice-anim` command-line front end: inspect, validate, render, convert and diff episodes.
+//!
+//! The binary in `src/bin/alice-anim.rs` only forwards `std::env::args` to [`run`], so every
+//! subcommand is usable (and testable) as a library call that writes its report to any `Write`.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::episode::{deserialize_episode, serialize_episode, EpisodePackage};
+use crate::export::{export_image_sequence, FrameRange, SequenceFormat};
+use crate::named_refs::{serialize_episode_named, NamedEpisode};
+use crate::patch::{create_patch, PatchOp};
+use crate::render::Renderer;
+
+/// Usage text printed by `help` and on argument errors.
+pub const USAGE: &str = "\
+usage: alice-anim <command> [args]
+
+commands:
+  info <episode>                         metadata and content statistics
+  validate <episode>                     structural checks; fails if any issue is found
+  render <episode> <dir> [--start S] [--end E] [--fps F] [--prefix P]
+                                         render a frame range to PNG files
+  convert <in> <out> [--format v4|v2|v3|json|named|json-named]
+                                         rewrite in another container format
+                                         (default: json for *.json, otherwise v2)
+  diff <a> <b>                           compare two episodes chunk by chunk";
+
+/// On-disk representation selectable with `convert --format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EpisodeFormat {
+    /// Single bincode body (`serialize_episode`).
+    V4,
+    /// Chunked container with per-chunk CRCs.
+    V2,
+    /// Streamed container.
+    V3,
+    /// Human-readable JSON of the full package.
+    Json,
+    /// v4 container with actor references stored by key (`named_refs`).
+    Named,
+    /// JSON with actor references stored by key, safe to reorder or merge by hand.
+    NamedJson,
+}
+
+impl EpisodeFormat {
+    pub fn parse(s: &str) -> io::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "v4" => Ok(Self::V4),
+            // Version 1 files are only read, through the frozen layouts
+            "v1" => Err(invalid_input(
+                "Format v1 can be read but not written; use v4".to_string(),
+            )),
+            "v2" | "chunked" => Ok(Self::V2),
+            "v3" | "streamed" => Ok(Self::V3),
+            "json" => Ok(Self::Json),
+            "named" => Ok(Self::Named),
+            "json-named" | "named-json" => Ok(Self::NamedJson),
+            _ => Err(invalid_input(format!("Unknown format '{}'", s))),
+        }
+    }
+
+    /// Format implied by a file name: `.json` is JSON, anything else the chunked container.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::V2,
+        }
+    }
+}
+
+/// Run the CLI with `args` (without the program name), writing the report to `out`.
+pub fn run<W: Write>(args: &[String], out: &mut W) -> io::Result<()> {
+    let (command, rest) = match args.split_first() {
+        Some((c, r)) => (c.as_str(), r),
+        None => return Err(invalid_input(USAGE.to_string())),
+    };
+    let args = Args::parse(rest)?;
+    match command {
+        "info" => {
+            let ep = load_episode(args.positional(0)?)?;
+            write_info(&ep, out)
+        }
+        "validate" => {
+            let ep = load_episode(args.positional(0)?)?;
+            let issues = ep.validation_issues();
+            for issue in &issues {
+                writeln!(out, "error: {}", issue)?;
+            }
+            if issues.is_empty() {
+                writeln!(out, "ok")
+            } else {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} validation issue(s)", issues.len()),
+                ))
+            }
+        }
+        "render" => {
+            let ep = load_episode(args.positional(0)?)?;
+            let dir = args.positional(1)?;
+            let fps = args.number("fps")?.unwrap_or(24.0);
+            let whole = FrameRange::whole(&ep, fps);
+            let range = FrameRange::new(
+                args.number("start")?.unwrap_or(whole.start),
+                args.number("end")?.unwrap_or(whole.end),
+                fps,
+            );
+            let prefix = args.option("prefix").unwrap_or("frame");
+            let renderer = Renderer::for_episode(&ep);
+            let paths = export_image_sequence(
+                &renderer,
+                &ep,
+                &range,
+                dir,
+                prefix,
+                SequenceFormat::Png,
+                |_| {},
+            )?;
+            writeln!(out, "rendered {} frame(s) to {}", paths.len(), dir)
+        }
+        "convert" => {
+            let ep = load_episode(args.positional(0)?)?;
+            let dst = args.positional(1)?;
+            let format = match args.option("format") {
+                Some(f) => EpisodeFormat::parse(f)?,
+                None => EpisodeFormat::from_path(Path::new(dst)),
+            };
+            let bytes = encode_episode(&ep, format)?;
+            std::fs::write(dst, &bytes)?;
+            writeln!(out, "wrote {} ({:?}, {} bytes)", dst, format, bytes.len())
+        }
+        "diff" => {
+            let a = load_episode(args.positional(0)?)?;
+            let b = load_episode(args.positional(1)?)?;
+            write_diff(&a, &b, out)
+        }
+        "help" | "--help" | "-h" => writeln!(out, "{}", USAGE),
+        other => Err(invalid_input(format!(
+            "Unknown command '{}'\n{}",
+            other, USAGE
+        ))),
+    }
+}
+
+/// Load an episode from any binary container version or from JSON.
+pub fn load_episode(path: impl AsRef<Path>) -> io::Result<EpisodePackage> {
+    let bytes = std::fs::read(path)?;
+    decode_episode(&bytes)
+}
+
+/// Decode episode bytes; input starting with `{` is treated as JSON, with actor references
+/// resolved by key when it has a `cut_actors` table.
+pub fn decode_episode(bytes: &[u8]) -> io::Result<EpisodePackage> {
+    let first = bytes.iter().find(|b| !b.is_ascii_whitespace());
+    if first == Some(&b'{') {
+        let invalid = |e| io::Error::new(io::ErrorKind::InvalidData, e);
+        let value: serde_json::Value = serde_json::from_slice(bytes).map_err(invalid)?;
+        if value.get("cut_actors").is_some() {
+            let named: NamedEpisode = serde_json::from_value(value).map_err(invalid)?;
+            Ok(named.into_episode()?)
+        } else {
+            let mut episode: EpisodePackage = serde_json::from_value(value).map_err(invalid)?;
+            episode.seed_shake();
+            Ok(episode)
+        }
+    } else {
+        Ok(deserialize_episode(&mut &bytes[..])?)
+    }
+}
+
+/// Encode an episode in the requested format.
+pub fn encode_episode(episode: &EpisodePackage, format: EpisodeFormat) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    match format {
+        EpisodeFormat::V4 => {
+            serialize_episode(episode, &mut buf)?;
+        }
+        EpisodeFormat::V2 => {
+            crate::chunk::serialize_episode_chunked(episode, &mut buf)?;
+        }
+        EpisodeFormat::V3 => {
+            crate::stream::serialize_episode_streamed(episode, &mut buf)?;
+        }
+        EpisodeFormat::Json => {
+            serde_json::to_writer_pretty(&mut buf, episode)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        EpisodeFormat::Named => {
+            serialize_episode_named(episode, &mut buf)?;
+        }
+        EpisodeFormat::NamedJson => {
+            let named = NamedEpisode::from_episode(episode)?;
+            serde_json::to_writer_pretty(&mut buf, &named)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+    }
+    Ok(buf)
+}
+
+fn write_info<W: Write>(ep: &EpisodePackage, out: &mut W) -> io::Result<()> {
+    let meta = &ep.metadata;
+    writeln!(out, "title:      {}", meta.title)?;
+    writeln!(out, "episode:    {}", meta.episode_number)?;
+    writeln!(out, "duration:   {:.3}s", meta.duration_seconds)?;
+    writeln!(
+        out,
+        "resolution: {}x{}",
+        meta.resolution.0, meta.resolution.1
+    )?;
+    for (key, value) in &meta.extensions {
+        writeln!(out, "ext.{}: {:?}", key, value)?;
+    }
+    writeln!(out, "actors:     {}", ep.scene_graph.actor_count())?;
+    writeln!(out, "scenes:     {}", ep.director.episode.scenes.len())?;
+    writeln!(out, "cuts:       {}", ep.director.cut_count())?;
+    writeln!(out, "dialogue:   {}", ep.director.episode.dialogue.len())?;
+    writeln!(out, "size:       ~{} bytes", ep.estimate_size())
+}
+
+fn write_diff<W: Write>(a: &EpisodePackage, b: &EpisodePackage, out: &mut W) -> io::Result<()> {
+    let patch = create_patch(a, b)?;
+    if patch.is_noop() {
+        return writeln!(out, "identical");
+    }
+    if a.metadata.title != b.metadata.title {
+        writeln!(
+            out,
+            "title: '{}' -> '{}'",
+            a.metadata.title, b.metadata.title
+        )?;
+    }
+    if a.metadata.duration_seconds != b.metadata.duration_seconds {
+        writeln!(
+            out,
+            "duration: {} -> {}",
+            a.metadata.duration_seconds, b.metadata.duration_seconds
+        )?;
+    }
+    if a.scene_graph.actor_count() != b.scene_graph.actor_count() {
+        writeln!(
+            out,
+            "actors: {} -> {}",
+            a.scene_graph.actor_count(),
+            b.scene_graph.actor_count()
+        )?;
+    }
+    if a.director.cut_count() != b.director.cut_count() {
+        writeln!(
+            out,
+            "cuts: {} -> {}",
+            a.director.cut_count(),
+            b.director.cut_count()
+        )?;
+    }
+    for op in &patch.ops {
+        if let PatchOp::Insert(chunk) = op {
+            writeln!(
+                out,
+                "changed chunk: {:?} ({} bytes)",
+                chunk.kind,
+                chunk.data.len()
+            )?;
+        }
+    }
+    writeln!(
+        out,
+        "{} of {} chunk(s) changed",
+        patch.changed_chunks(),
+        patch.ops.len()
+    )
+}
+
+/// Positional arguments plus `--name value` options.
+struct Args<'a> {
+    positional: Vec<&'a str>,
+    options: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Args<'a> {
+    fn parse(args: &'a [String]) -> io::Result<Self> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| invalid_input(format!("Missing value for --{}", name)))?;
+                options.push((name, value.as_str()));
+            } else {
+                positional.push(arg.as_str());
+            }
+        }
+        Ok(Self {
+            positional,
+            options,
+        })
+    }
+
+    fn positional(&self, index: usize) -> io::Result<&'a str> {
+        self.positional
+            .get(index)
+            .copied()
+            .ok_or_else(|| invalid_input(format!("Missing argument {}\n{}", index + 1, USAGE)))
+    }
+
+    fn option(&self, name: &str) -> Option<&'a str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| *v)
+    }
+
+    fn number(&self, name: &str) -> io::Result<Option<f32>> {
+        self.option(name)
+            .map(|v| {
+                v.parse::<f32>()
+                    .map_err(|_| invalid_input(format!("--{} expects a number, got '{}'", name, v)))
+            })
+            .transpose()
+    }
+}
+
+fn invalid_input(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, DialogueLine, Director};
+    use crate::episode::EpisodeMetadata;
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    fn episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Pilot");
+        dir.add_cut(Cut::new("open", 0.0, 2.0).with_actors(vec![hero]));
+        dir.add_cut(Cut::new("close", 2.0, 4.0).with_actors(vec![hero]));
+        dir.add_dialogue(DialogueLine::new(0.5, 1.5, "Hello").with_speaker("hero"));
+        EpisodePackage::new(
+            EpisodeMetadata::new("Pilot", 1, 4.0),
+            sg,
+            dir,
+            AnimeShading::default(),
+        )
+    }
+
+    #[test]
+    fn test_encode_decode_every_format() {
+        let ep = episode();
+        for format in [
+            EpisodeFormat::V4,
+            EpisodeFormat::V2,
+            EpisodeFormat::V3,
+            EpisodeFormat::Json,
+            EpisodeFormat::Named,
+            EpisodeFormat::NamedJson,
+        ] {
+            let bytes = encode_episode(&ep, format).unwrap();
+            let back = decode_episode(&bytes).unwrap();
+            assert_eq!(back.metadata.title, "Pilot");
+            assert_eq!(back.director.cut_count(), 2);
+            assert_eq!(back.director.episode.dialogue.len(), 1);
+        }
+        assert_eq!(EpisodeFormat::parse("V4").unwrap(), EpisodeFormat::V4);
+        assert!(EpisodeFormat::parse("v1").is_err());
+    }
+
+    #[test]
+    fn test_run_convert_info_and_diff() {
+        let dir = std::env::temp_dir().join(format!("alice_cli_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let src = dir.join("ep.anim");
+        let json = dir.join("ep.json");
+        std::fs::write(&src, encode_episode(&episode(), EpisodeFormat::V4).unwrap()).unwrap();
+
+        let path = |p: &Path| p.to_string_lossy().into_owned();
+        let mut out = Vec::new();
+        run(&["convert".into(), path(&src), path(&json)], &mut out).unwrap();
+        run(&["info".into(), path(&json)], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("Json"));
+        assert!(text.contains("cuts:       2"));
+
+        let mut edited = episode();
+        edited.metadata.title = "Pilot (retake)".into();
+        let other = dir.join("retake.anim");
+        std::fs::write(&other, encode_episode(&edited, EpisodeFormat::V2).unwrap()).unwrap();
+        let mut out = Vec::new();
+        run(&["diff".into(), path(&src), path(&other)], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains("title: 'Pilot' -> 'Pilot (retake)'"));
+        assert!(text.contains("Metadata"));
+
+        assert!(run(&["bogus".into()], &mut Vec::new()).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
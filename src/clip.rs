@@ -0,0 +1,327 @@
+//! Animation clips placed on per-actor clip tracks.
+//!
+//! An [`AnimationClip`] is a reusable, named piece of motion (a walk cycle, a wave, a
+//! double take), kept once in the scene's [`ClipLibrary`]. Cuts place instances of clips
+//! by name on a [`ClipTrack`] per actor, each moved, trimmed and slipped independently,
+//! so a performance is edited without touching the clips or the actor's own timeline.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use alice_sdf::animation::{Timeline, Track};
+use serde::{Deserialize, Serialize};
+
+use crate::cycle::TimelineCycle;
+use crate::nla::BlendMode;
+use crate::scene::ActorId;
+use crate::time_warp::TimeWarp;
+
+/// A named timeline with its own length and loop settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimationClip {
+    pub name: String,
+    pub timeline: Timeline,
+    /// Length of one pass; defaults to the timeline's last key.
+    pub duration: f32,
+    /// Loops the clip past its duration instead of holding the last pose.
+    pub cycle: Option<TimelineCycle>,
+}
+
+impl AnimationClip {
+    pub fn new(name: impl Into<String>, timeline: Timeline) -> Self {
+        let duration = timeline.duration();
+        Self {
+            name: name.into(),
+            timeline,
+            duration,
+            cycle: None,
+        }
+    }
+
+    pub fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    pub fn with_cycle(mut self, cycle: TimelineCycle) -> Self {
+        self.cycle = Some(cycle);
+        self
+    }
+
+    /// Timeline time for `time` seconds into the clip.
+    #[inline]
+    pub fn clip_time(&self, time: f32) -> f32 {
+        match &self.cycle {
+            Some(cycle) => cycle.map_time(time, self.duration),
+            None => time.clamp(0.0, self.duration.max(0.0)),
+        }
+    }
+
+    /// Value of track `name` at `time` seconds into the clip.
+    #[inline]
+    pub fn value(&self, name: &str, time: f32) -> Option<f32> {
+        self.timeline.get_value(name, self.clip_time(time))
+    }
+}
+
+/// Clips of an episode by name, shared by every clip track that places them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClipLibrary {
+    clips: BTreeMap<String, AnimationClip>,
+}
+
+impl ClipLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `clip`, returning the clip it replaced under the same name.
+    pub fn insert(&mut self, clip: AnimationClip) -> Option<AnimationClip> {
+        self.clips.insert(clip.name.clone(), clip)
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips.get(name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut AnimationClip> {
+        self.clips.get_mut(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<AnimationClip> {
+        self.clips.remove(name)
+    }
+
+    /// Clips in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &AnimationClip> {
+        self.clips.values()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut AnimationClip> {
+        self.clips.values_mut()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.clips.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.clips.is_empty()
+    }
+}
+
+/// One placement of a clip on a clip track.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipInstance {
+    /// Name of the clip in the [`ClipLibrary`].
+    pub clip: String,
+    /// Cut-local time the instance begins.
+    pub start: f32,
+    /// Clip time played at `start` (slip).
+    pub offset: f32,
+    /// Seconds the instance plays for; its last pose holds until the next instance.
+    pub length: f32,
+    /// Retiming applied in order to the elapsed time, before the offset.
+    pub time_warp: Vec<TimeWarp>,
+}
+
+impl ClipInstance {
+    /// The whole of `clip` once, from cut-local time `start`.
+    pub fn new(clip: &AnimationClip, start: f32) -> Self {
+        Self {
+            clip: clip.name.clone(),
+            start,
+            offset: 0.0,
+            length: clip.duration,
+            time_warp: Vec::new(),
+        }
+    }
+
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_length(mut self, length: f32) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Retime the instance with `warp`, after any warps already on it.
+    pub fn with_time_warp(mut self, warp: TimeWarp) -> Self {
+        self.time_warp.push(warp);
+        self
+    }
+
+    /// Trim `head` seconds off the front (the rest stays where it was) and `tail` off the
+    /// end. Time warps then apply to the trimmed instance.
+    pub fn trim(mut self, head: f32, tail: f32) -> Self {
+        let head = head.clamp(0.0, self.length.max(0.0));
+        self.start += head;
+        self.offset += head;
+        self.length = (self.length - head - tail).max(0.0);
+        self
+    }
+
+    #[inline]
+    pub fn end(&self) -> f32 {
+        self.start + self.length.max(0.0)
+    }
+
+    /// Seconds into the clip at cut-local `time`, held at the instance's ends and remapped
+    /// by its time warps.
+    #[inline]
+    pub fn clip_time(&self, time: f32) -> f32 {
+        let length = self.length.max(0.0);
+        let elapsed = (time - self.start).clamp(0.0, length);
+        let warped = self
+            .time_warp
+            .iter()
+            .fold(elapsed, |t, warp| warp.map(t, length));
+        self.offset + warped
+    }
+
+    /// Value of track `name` at cut-local `time`; `None` if the clip is not in `library`.
+    #[inline]
+    pub fn value(&self, library: &ClipLibrary, name: &str, time: f32) -> Option<f32> {
+        library.get(&self.clip)?.value(name, self.clip_time(time))
+    }
+}
+
+/// Clip instances driving one actor within a cut, sorted by start time. Several tracks
+/// on one actor stack in the order they were added (see [`crate::nla`]).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClipTrack {
+    pub actor: ActorId,
+    pub instances: Vec<ClipInstance>,
+    /// How the track combines with the tracks below it.
+    pub blend: BlendMode,
+    /// Strength of the track (0 mutes it).
+    pub influence: f32,
+    /// Influence over cut-local time, multiplied by `influence`.
+    pub influence_curve: Option<Track>,
+}
+
+impl ClipTrack {
+    pub fn new(actor: ActorId) -> Self {
+        Self {
+            actor,
+            instances: Vec::new(),
+            blend: BlendMode::Replace,
+            influence: 1.0,
+            influence_curve: None,
+        }
+    }
+
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn with_influence(mut self, influence: f32) -> Self {
+        self.influence = influence;
+        self
+    }
+
+    /// Key influence over cut-local time, e.g. 0 → 1 to fade a gesture in.
+    pub fn with_influence_curve(mut self, curve: Track) -> Self {
+        self.influence_curve = Some(curve);
+        self
+    }
+
+    /// Influence at cut-local `time`, clamped to 0..1 for the curve.
+    pub fn influence_at(&self, time: f32) -> f32 {
+        match &self.influence_curve {
+            Some(curve) if !curve.keyframes.is_empty() => {
+                self.influence * curve.evaluate(time).clamp(0.0, 1.0)
+            }
+            _ => self.influence,
+        }
+    }
+
+    /// Place `instance`, keeping the track sorted.
+    pub fn with_clip(mut self, instance: ClipInstance) -> Self {
+        self.place(instance);
+        self
+    }
+
+    pub fn place(&mut self, instance: ClipInstance) {
+        let pos = self
+            .instances
+            .partition_point(|i| i.start <= instance.start);
+        self.instances.insert(pos, instance);
+    }
+
+    /// Instance in charge at cut-local `time`: the latest one started by then. `None`
+    /// before the first instance, where the actor's own timeline plays.
+    pub fn instance_at(&self, time: f32) -> Option<&ClipInstance> {
+        self.instances.iter().rev().find(|i| i.start <= time)
+    }
+
+    #[inline]
+    pub fn value(&self, library: &ClipLibrary, name: &str, time: f32) -> Option<f32> {
+        self.instance_at(time)?.value(library, name, time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::animation::{Keyframe, Track};
+
+    fn ramp(name: &str, duration: f32) -> AnimationClip {
+        let mut tl = Timeline::new(name);
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(duration, duration));
+        tl.add_track(x);
+        AnimationClip::new(name, tl)
+    }
+
+    #[test]
+    fn test_instances_trim_and_slip() {
+        let mut library = ClipLibrary::new();
+        let walk = ramp("walk", 1.0).with_cycle(TimelineCycle::repeat());
+        let instance = ClipInstance::new(&walk, 2.0)
+            .with_length(3.0)
+            .with_offset(0.25);
+        assert_eq!(instance.end(), 5.0);
+        assert_eq!(instance.value(&library, "translate.x", 3.0), None);
+        library.insert(walk);
+        let x = |i: &ClipInstance, t| i.value(&library, "translate.x", t).unwrap();
+        // Slipped a quarter cycle, looping
+        assert!((x(&instance, 3.0) - 0.25).abs() < 1e-5);
+        let trimmed = instance.clone().trim(0.5, 1.0);
+        assert_eq!((trimmed.start, trimmed.end()), (2.5, 4.0));
+        // Trimming does not move the motion
+        assert!((x(&trimmed, 3.3) - x(&instance, 3.3)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_track_switches_and_holds() {
+        let mut library = ClipLibrary::new();
+        let walk = ramp("walk", 1.0);
+        let reversed = ClipInstance::new(&walk, 0.0).with_time_warp(TimeWarp::Reverse);
+        library.insert(walk.clone());
+        assert_eq!(reversed.value(&library, "translate.x", 0.25), Some(0.75));
+
+        let wave = ramp("wave", 2.0);
+        let track = ClipTrack::new(ActorId(0))
+            .with_clip(ClipInstance::new(&wave, 3.0))
+            .with_clip(ClipInstance::new(&walk, 1.0));
+        library.insert(wave);
+        // Edits to a library clip show up in every instance placing it
+        library.get_mut("walk").unwrap().duration = 0.5;
+        assert_eq!(track.instances[0].clip, "walk");
+        assert_eq!(track.value(&library, "translate.x", 0.5), None);
+        assert_eq!(track.value(&library, "translate.x", 1.25), Some(0.25));
+        // The walk's last pose holds until the wave starts
+        assert_eq!(track.value(&library, "translate.x", 2.5), Some(0.5));
+        assert_eq!(track.instance_at(4.0).unwrap().clip, "wave");
+        assert_eq!(track.value(&library, "translate.x", 4.0), Some(1.0));
+    }
+}
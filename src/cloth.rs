@@ -0,0 +1,492 @@
+//! Verlet cloth and ribbon simulation (capes, hair ribbons) hung from actor sockets,
+//! pushed by wind and kept outside the actor's own SDF.
+
+use alice_sdf::SdfNode;
+use glam::Vec3;
+
+use crate::export::FrameRange;
+use crate::performance::Performance;
+use crate::scene::ActorId;
+
+/// Attachment point on an actor, in actor space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Socket {
+    pub actor: ActorId,
+    pub offset: Vec3,
+}
+
+impl Socket {
+    pub fn new(actor: ActorId, offset: Vec3) -> Self {
+        Self { actor, offset }
+    }
+
+    /// World position of the socket at `time`, following the actor's timeline.
+    pub fn world_position(&self, scene: Performance<'_>, time: f32) -> Vec3 {
+        let world = scene.get_world_transform(self.actor);
+        scene.actor_position_at(self.actor, time) + world.rotation * (world.scale * self.offset)
+    }
+}
+
+/// Deterministic wind: a base velocity modulated by gusts that travel through space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindField {
+    /// Mean wind velocity (units per second).
+    pub velocity: Vec3,
+    /// Gust strength as a fraction of `velocity` (0 = steady).
+    pub gust: f32,
+    /// Gusts per second.
+    pub frequency: f32,
+}
+
+impl WindField {
+    pub fn calm() -> Self {
+        Self::new(Vec3::ZERO)
+    }
+
+    pub fn new(velocity: Vec3) -> Self {
+        Self {
+            velocity,
+            gust: 0.0,
+            frequency: 0.5,
+        }
+    }
+
+    pub fn with_gusts(mut self, gust: f32, frequency: f32) -> Self {
+        self.gust = gust;
+        self.frequency = frequency;
+        self
+    }
+
+    /// Wind velocity at `position` and `time`.
+    pub fn at(&self, position: Vec3, time: f32) -> Vec3 {
+        if self.gust == 0.0 {
+            return self.velocity;
+        }
+        let phase = position.dot(Vec3::new(0.37, 0.11, 0.53));
+        let wave = (std::f32::consts::TAU * self.frequency * time + phase).sin();
+        self.velocity * (1.0 + self.gust * wave)
+    }
+}
+
+/// Solver settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClothConfig {
+    pub gravity: Vec3,
+    /// Fraction of velocity kept per step (1.0 = no damping).
+    pub damping: f32,
+    /// How strongly the cloth follows the wind (1/s).
+    pub drag: f32,
+    /// Constraint relaxation passes per step.
+    pub iterations: u32,
+    /// Fixed simulation step in seconds.
+    pub substep: f32,
+    /// Distance kept from the collider surface.
+    pub collision_margin: f32,
+    /// Longest jump ahead (seconds) simulated step by step; scrubbing further resets.
+    pub max_catch_up: f32,
+    /// Seconds simulated from rest before the target time after such a reset.
+    pub warm_up: f32,
+}
+
+impl Default for ClothConfig {
+    fn default() -> Self {
+        Self {
+            gravity: Vec3::new(0.0, -9.8, 0.0),
+            damping: 0.98,
+            drag: 1.5,
+            iterations: 8,
+            substep: 1.0 / 120.0,
+            collision_margin: 0.02,
+            max_catch_up: 2.0,
+            warm_up: 0.5,
+        }
+    }
+}
+
+/// Verlet particle cloth. Ribbons are a single column of particles.
+#[derive(Debug, Clone)]
+pub struct Cloth {
+    pub positions: Vec<Vec3>,
+    previous: Vec<Vec3>,
+    /// Rest layout relative to the first pin.
+    rest: Vec<Vec3>,
+    pins: Vec<(usize, Socket)>,
+    /// `(a, b, rest_length)`; structural edges first.
+    constraints: Vec<(usize, usize, f32)>,
+    structural: usize,
+    /// Actor whose SDF the cloth collides with (defaults to the socket's actor).
+    pub collider: Option<ActorId>,
+    pub config: ClothConfig,
+    time: Option<f32>,
+}
+
+impl Cloth {
+    fn from_layout(
+        rest: Vec<Vec3>,
+        pins: Vec<(usize, Socket)>,
+        constraints: Vec<(usize, usize, f32)>,
+        structural: usize,
+    ) -> Self {
+        let collider = pins.first().map(|(_, s)| s.actor);
+        Self {
+            positions: rest.clone(),
+            previous: rest.clone(),
+            rest,
+            pins,
+            constraints,
+            structural,
+            collider,
+            config: ClothConfig::default(),
+            time: None,
+        }
+    }
+
+    /// A ribbon of `segments` links hanging `length` below `socket`.
+    pub fn ribbon(socket: Socket, segments: usize, length: f32) -> Self {
+        let segments = segments.max(1);
+        let link = length / segments as f32;
+        let rest: Vec<Vec3> = (0..=segments)
+            .map(|i| Vec3::new(0.0, -link * i as f32, 0.0))
+            .collect();
+        let constraints: Vec<_> = (0..segments).map(|i| (i, i + 1, link)).collect();
+        let structural = constraints.len();
+        Self::from_layout(rest, vec![(0, socket)], constraints, structural)
+    }
+
+    /// A cape of `columns` x `rows` particles pinned along its top edge between two
+    /// actor-space points of `actor`, hanging `length` downwards.
+    pub fn cape(
+        actor: ActorId,
+        left: Vec3,
+        right: Vec3,
+        columns: usize,
+        rows: usize,
+        length: f32,
+    ) -> Self {
+        let (columns, rows) = (columns.max(2), rows.max(2));
+        let row_step = length / (rows - 1) as f32;
+        let index = |r: usize, c: usize| r * columns + c;
+        let mut rest = Vec::with_capacity(columns * rows);
+        let mut pins = Vec::with_capacity(columns);
+        for r in 0..rows {
+            for c in 0..columns {
+                let top = left.lerp(right, c as f32 / (columns - 1) as f32);
+                if r == 0 {
+                    pins.push((index(r, c), Socket::new(actor, top)));
+                }
+                rest.push(top - left + Vec3::new(0.0, -row_step * r as f32, 0.0));
+            }
+        }
+        let mut constraints = Vec::new();
+        let mut link = |a: usize, b: usize, rest: &[Vec3]| {
+            constraints.push((a, b, rest[a].distance(rest[b])));
+        };
+        for r in 0..rows {
+            for c in 0..columns {
+                if c + 1 < columns {
+                    link(index(r, c), index(r, c + 1), &rest);
+                }
+                if r + 1 < rows {
+                    link(index(r, c), index(r + 1, c), &rest);
+                }
+            }
+        }
+        let structural = constraints.len();
+        let mut shear = |a: usize, b: usize| constraints.push((a, b, rest[a].distance(rest[b])));
+        for r in 0..rows - 1 {
+            for c in 0..columns - 1 {
+                shear(index(r, c), index(r + 1, c + 1));
+                shear(index(r, c + 1), index(r + 1, c));
+            }
+        }
+        Self::from_layout(rest, pins, constraints, structural)
+    }
+
+    pub fn with_config(mut self, config: ClothConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Collide against another actor (or nothing) instead of the socket's actor.
+    pub fn with_collider(mut self, collider: Option<ActorId>) -> Self {
+        self.collider = collider;
+        self
+    }
+
+    /// Number of particles.
+    pub fn particle_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Structural edges (for drawing or SDF conversion).
+    pub fn edges(&self) -> &[(usize, usize, f32)] {
+        &self.constraints[..self.structural]
+    }
+
+    /// Simulated time, if the cloth has been placed.
+    pub fn time(&self) -> Option<f32> {
+        self.time
+    }
+
+    /// Put the cloth at rest under its sockets at `time`, discarding motion.
+    pub fn reset(&mut self, scene: Performance<'_>, time: f32) {
+        let anchor = match self.pins.first() {
+            Some((_, socket)) => socket.world_position(scene, time),
+            None => Vec3::ZERO,
+        };
+        for (i, p) in self.positions.iter_mut().enumerate() {
+            *p = anchor + self.rest[i];
+        }
+        self.pin(scene, time);
+        self.previous.clone_from(&self.positions);
+        self.time = Some(time);
+    }
+
+    fn pin(&mut self, scene: Performance<'_>, time: f32) {
+        for (i, socket) in &self.pins {
+            self.positions[*i] = socket.world_position(scene, time);
+        }
+    }
+
+    /// One fixed step of `dt` seconds ending at `time`.
+    pub fn step(&mut self, scene: Performance<'_>, time: f32, dt: f32, wind: &WindField) {
+        let cfg = self.config;
+        for i in 0..self.positions.len() {
+            let p = self.positions[i];
+            let velocity = (p - self.previous[i]) / dt.max(1e-6);
+            let accel = cfg.gravity + (wind.at(p, time) - velocity) * cfg.drag;
+            self.positions[i] = p + (p - self.previous[i]) * cfg.damping + accel * dt * dt;
+            self.previous[i] = p;
+        }
+        let collider: Option<SdfNode> = self.collider.and_then(|id| scene.actor_sdf(id, time));
+        for _ in 0..cfg.iterations.max(1) {
+            for &(a, b, rest) in &self.constraints {
+                let delta = self.positions[b] - self.positions[a];
+                let length = delta.length();
+                if length <= 1e-9 {
+                    continue;
+                }
+                let correction = delta * (0.5 * (length - rest) / length);
+                self.positions[a] += correction;
+                self.positions[b] -= correction;
+            }
+            self.pin(scene, time);
+        }
+        if let Some(sdf) = &collider {
+            for (i, p) in self.positions.iter_mut().enumerate() {
+                if self.pins.iter().any(|(pinned, _)| *pinned == i) {
+                    continue;
+                }
+                let d = alice_sdf::eval(sdf, *p);
+                if d < cfg.collision_margin {
+                    *p += sdf_normal(sdf, *p) * (cfg.collision_margin - d);
+                }
+            }
+        }
+        self.time = Some(time);
+    }
+
+    /// Simulate live up to `time` in fixed substeps. The first call, or a jump
+    /// backwards, resets the cloth to rest at `time`; a jump further ahead than
+    /// `max_catch_up` resets it and simulates only the `warm_up` before `time`.
+    pub fn advance_to(&mut self, scene: Performance<'_>, time: f32, wind: &WindField) {
+        let start = match self.time {
+            Some(t) if t <= time && time - t <= self.config.max_catch_up => t,
+            Some(t) if t <= time => {
+                let start = time - self.config.warm_up.clamp(0.0, self.config.max_catch_up);
+                self.reset(scene, start);
+                start
+            }
+            _ => {
+                self.reset(scene, time);
+                return;
+            }
+        };
+        let dt = self.config.substep.max(1e-4);
+        let steps = ((time - start) / dt).ceil() as u32;
+        if steps == 0 {
+            return;
+        }
+        let dt = (time - start) / steps as f32;
+        for i in 1..=steps {
+            self.step(scene, start + dt * i as f32, dt, wind);
+        }
+    }
+
+    /// Simulate from rest over `range` and record every frame.
+    pub fn bake(
+        &mut self,
+        scene: Performance<'_>,
+        range: FrameRange,
+        wind: &WindField,
+    ) -> ClothBake {
+        self.reset(scene, range.start);
+        let frames = (0..range.frame_count())
+            .map(|i| {
+                self.advance_to(scene, range.frame_time(i), wind);
+                self.positions.clone()
+            })
+            .collect();
+        ClothBake {
+            range,
+            frames,
+            edges: self.edges().to_vec(),
+        }
+    }
+
+    /// Current shape as capsules along the structural edges.
+    pub fn to_sdf(&self, thickness: f32) -> Option<SdfNode> {
+        edges_to_sdf(&self.positions, self.edges(), thickness)
+    }
+}
+
+/// SDF gradient by central differences.
+fn sdf_normal(sdf: &SdfNode, p: Vec3) -> Vec3 {
+    const E: f32 = 1e-3;
+    let axis = |d: Vec3| alice_sdf::eval(sdf, p + d) - alice_sdf::eval(sdf, p - d);
+    Vec3::new(axis(Vec3::X * E), axis(Vec3::Y * E), axis(Vec3::Z * E)).normalize_or_zero()
+}
+
+fn edges_to_sdf(
+    positions: &[Vec3],
+    edges: &[(usize, usize, f32)],
+    thickness: f32,
+) -> Option<SdfNode> {
+    edges
+        .iter()
+        .map(|&(a, b, _)| SdfNode::capsule(positions[a], positions[b], thickness))
+        .reduce(|acc, capsule| acc.union(capsule))
+}
+
+/// Pre-simulated cloth, sampled per frame.
+#[derive(Debug, Clone)]
+pub struct ClothBake {
+    pub range: FrameRange,
+    /// Particle positions per frame.
+    pub frames: Vec<Vec<Vec3>>,
+    edges: Vec<(usize, usize, f32)>,
+}
+
+impl ClothBake {
+    /// Particle positions at `time`, interpolated between baked frames and clamped to the range.
+    pub fn positions_at(&self, time: f32) -> Option<Vec<Vec3>> {
+        let last = self.frames.len().checked_sub(1)?;
+        let f = ((time - self.range.start) * self.range.fps).clamp(0.0, last as f32);
+        let i = (f.floor() as usize).min(last);
+        let j = (i + 1).min(last);
+        let t = f - i as f32;
+        Some(
+            self.frames[i]
+                .iter()
+                .zip(&self.frames[j])
+                .map(|(a, b)| a.lerp(*b, t))
+                .collect(),
+        )
+    }
+
+    /// Baked shape at `time` as capsules along the structural edges.
+    pub fn sdf_at(&self, time: f32, thickness: f32) -> Option<SdfNode> {
+        edges_to_sdf(&self.positions_at(time)?, &self.edges, thickness)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Actor, ActorTransform, SceneGraph};
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+    fn scene_with_mover() -> (SceneGraph, ActorId) {
+        let mut sg = SceneGraph::new();
+        let mut tl = Timeline::new("run");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(1.0, 3.0));
+        tl.add_track(x);
+        let id = sg.add_actor(
+            Actor::new("hero", SdfNode::sphere(0.5))
+                .with_timeline(tl)
+                .with_transform(ActorTransform {
+                    position: Vec3::new(0.0, 2.0, 0.0),
+                    ..ActorTransform::default()
+                }),
+        );
+        (sg, id)
+    }
+
+    #[test]
+    fn test_ribbon_hangs_and_trails() {
+        let (sg, hero) = scene_with_mover();
+        let mut ribbon =
+            Cloth::ribbon(Socket::new(hero, Vec3::new(0.0, 1.0, 0.0)), 4, 1.0).with_collider(None);
+        ribbon.reset(Performance::from(&sg), 0.0);
+        assert_eq!(ribbon.particle_count(), 5);
+        assert!((ribbon.positions[4] - Vec3::new(0.0, 2.0, 0.0)).length() < 1e-5);
+
+        ribbon.advance_to(Performance::from(&sg), 0.5, &WindField::calm());
+        // Pinned end follows the socket, the tip lags behind the motion
+        assert!((ribbon.positions[0] - Vec3::new(1.5, 3.0, 0.0)).length() < 1e-4);
+        assert!(ribbon.positions[4].x < ribbon.positions[0].x);
+        for &(a, b, rest) in ribbon.edges() {
+            let length = ribbon.positions[a].distance(ribbon.positions[b]);
+            assert!((length - rest).abs() < 0.05 * rest + 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_cape_collides_with_actor() {
+        let mut sg = SceneGraph::new();
+        let body = sg.add_actor(Actor::new("body", SdfNode::sphere(1.0)));
+        // Pinned on top of the sphere, so the cape must drape over it
+        let mut cape = Cloth::cape(
+            body,
+            Vec3::new(-0.3, 1.05, 0.0),
+            Vec3::new(0.3, 1.05, 0.0),
+            4,
+            5,
+            1.5,
+        );
+        assert_eq!(cape.particle_count(), 20);
+        assert_eq!(cape.edges().len(), 3 * 5 + 4 * 4);
+        let wind = WindField::new(Vec3::new(0.0, 0.0, 2.0)).with_gusts(0.5, 1.0);
+        cape.advance_to(Performance::from(&sg), 0.0, &wind);
+        cape.advance_to(Performance::from(&sg), 1.0, &wind);
+        let margin = cape.config.collision_margin;
+        for p in &cape.positions {
+            assert!(alice_sdf::eval(&SdfNode::sphere(1.0), *p) > margin * 0.5 - 1e-3);
+        }
+        assert!(cape.to_sdf(0.02).is_some());
+    }
+
+    #[test]
+    fn test_bake_matches_live_and_interpolates() {
+        let (sg, hero) = scene_with_mover();
+        let socket = Socket::new(hero, Vec3::new(0.0, 1.0, 0.0));
+        let wind = WindField::new(Vec3::new(1.0, 0.0, 0.0));
+        let mut live = Cloth::ribbon(socket, 3, 0.6);
+        let bake = Cloth::ribbon(socket, 3, 0.6).bake(
+            Performance::from(&sg),
+            FrameRange::new(0.0, 1.0, 10.0),
+            &wind,
+        );
+        assert_eq!(bake.frames.len(), 10);
+
+        live.advance_to(Performance::from(&sg), 0.0, &wind);
+        live.advance_to(Performance::from(&sg), 0.5, &wind);
+        assert_eq!(bake.positions_at(0.5).unwrap(), bake.frames[5]);
+        assert!((bake.frames[5][3] - live.positions[3]).length() < 1e-3);
+
+        let mid = bake.positions_at(0.55).unwrap();
+        let expected = bake.frames[5][3].lerp(bake.frames[6][3], 0.5);
+        assert!((mid[3] - expected).length() < 1e-5);
+        assert_eq!(bake.positions_at(5.0).unwrap(), bake.frames[9]);
+        assert!(bake.sdf_at(0.2, 0.01).is_some());
+
+        // Scrubbing far ahead only simulates the warm-up window
+        let mut warmed = Cloth::ribbon(socket, 3, 0.6);
+        warmed.advance_to(Performance::from(&sg), 99.5, &wind);
+        warmed.advance_to(Performance::from(&sg), 100.0, &wind);
+        live.advance_to(Performance::from(&sg), 100.0, &wind);
+        assert_eq!(live.positions, warmed.positions);
+    }
+}
@@ -1,9 +1,24 @@
 //! Bridge: ALICE-Animation → ALICE-Codec
 //! Compresses ANIM binary episodes using ALICE-Codec (50KB → ~5KB).
 
+use std::io::{self, Read, Write};
+
+use alice_sdf::animation::Track;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use flate2::Compression;
+use half::f16;
+
 use crate::episode::EpisodePackage;
+use crate::error::{AnimationError, Result};
+use crate::keys::{reduce_track, sample_linear};
 // use alice_codec::{compress, decompress, CompressionConfig};
 
+/// Compressed stream magic bytes.
+const CODEC_MAGIC: [u8; 4] = *b"ACMP";
+/// Compressed stream format version (v2 adds the value precision mode).
+const CODEC_VERSION: u8 = 2;
+
 /// Compressed episode wrapper with codec metadata.
 #[derive(Debug)]
 pub struct CompressedEpisode {
@@ -12,9 +27,353 @@ pub struct CompressedEpisode {
     pub compression_ratio: f32,
 }
 
+/// How keyframe values are stored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValuePrecision {
+    /// Bit-exact f32.
+    Exact,
+    /// Rounded to half precision.
+    F16,
+    /// Rounded to multiples of the step.
+    Fixed(FixedStep),
+}
+
+impl ValuePrecision {
+    /// Fixed point with `step`; see [`FixedStep::new`].
+    pub fn fixed(step: f32) -> Result<Self> {
+        FixedStep::new(step).map(Self::Fixed)
+    }
+}
+
+/// Fixed-point quantization step, always finite and positive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedStep(f32);
+
+impl FixedStep {
+    /// Rejects zero, negative and non-finite steps.
+    pub fn new(step: f32) -> Result<Self> {
+        if step.is_finite() && step > 0.0 {
+            Ok(Self(step))
+        } else {
+            Err(AnimationError::InvalidInput(format!(
+                "Fixed-point step must be finite and positive, got {}",
+                step
+            )))
+        }
+    }
+
+    #[inline]
+    pub fn get(self) -> f32 {
+        self.0
+    }
+
+    /// Step multiple nearest `value`, or `None` past the i32 code range.
+    #[inline]
+    fn code(self, value: f32) -> Option<i32> {
+        let code = (value / self.0).round();
+        (code.abs() <= i32::MAX as f32).then_some(code as i32)
+    }
+}
+
+/// Lossy compression settings. Keyframe times are always kept exact.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QualityProfile {
+    pub precision: ValuePrecision,
+    /// Drop keyframes whose removal moves the curve by at most this much (0 keeps all).
+    pub max_reduction_error: f32,
+}
+
+impl Default for QualityProfile {
+    fn default() -> Self {
+        Self::lossless()
+    }
+}
+
+impl QualityProfile {
+    pub fn lossless() -> Self {
+        Self {
+            precision: ValuePrecision::Exact,
+            max_reduction_error: 0.0,
+        }
+    }
+
+    /// Final delivery: 1/1024 fixed point, near-invisible key reduction.
+    pub fn high() -> Self {
+        Self {
+            precision: ValuePrecision::Fixed(FixedStep(1.0 / 1024.0)),
+            max_reduction_error: 1e-3,
+        }
+    }
+
+    /// Streaming: half precision values.
+    pub fn medium() -> Self {
+        Self {
+            precision: ValuePrecision::F16,
+            max_reduction_error: 5e-3,
+        }
+    }
+
+    /// Previews and thumbnails.
+    pub fn low() -> Self {
+        Self {
+            precision: ValuePrecision::Fixed(FixedStep(1.0 / 64.0)),
+            max_reduction_error: 2e-2,
+        }
+    }
+
+    pub fn with_precision(mut self, precision: ValuePrecision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    pub fn with_reduction_error(mut self, max_error: f32) -> Self {
+        self.max_reduction_error = max_error;
+        self
+    }
+
+    fn quantize(&self, value: f32) -> f32 {
+        match self.precision {
+            ValuePrecision::Exact => value,
+            ValuePrecision::F16 => f16::from_f32(value).to_f32(),
+            ValuePrecision::Fixed(step) => (value / step.0).round() as i32 as f32 * step.0,
+        }
+    }
+}
+
+/// Error introduced in one track by a lossy profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackError {
+    /// `"actor:<name>/<track>"` or `"cut:<name>/<track>"`.
+    pub track: String,
+    pub keyframes_before: usize,
+    pub keyframes_after: usize,
+    /// Largest deviation from the original curve at any original keyframe time.
+    pub max_error: f32,
+}
+
+/// Per-track outcome of `compress_episode_with`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompressionReport {
+    pub tracks: Vec<TrackError>,
+}
+
+impl CompressionReport {
+    pub fn max_error(&self) -> f32 {
+        self.tracks.iter().map(|t| t.max_error).fold(0.0, f32::max)
+    }
+
+    pub fn keyframes_removed(&self) -> usize {
+        self.tracks
+            .iter()
+            .map(|t| t.keyframes_before - t.keyframes_after)
+            .sum()
+    }
+}
+
+/// Visit every keyframe track (actor timelines, then camera tracks per cut) in a fixed
+/// order shared by the encoder and decoder, with an owner label for reporting.
+fn for_each_track(episode: &mut EpisodePackage, mut f: impl FnMut(&str, &mut Track)) {
+    for id in episode.scene_graph.actor_ids() {
+        if let Some(actor) = episode.scene_graph.get_actor_mut(id) {
+            let owner = format!("actor:{}", actor.name);
+            if let Some(timeline) = actor.timeline.as_mut() {
+                timeline.tracks.iter_mut().for_each(|t| f(&owner, t));
+            }
+        }
+    }
+    let cut_ids: Vec<_> = episode.director.cuts().map(|(id, _)| id).collect();
+    for id in cut_ids {
+        if let Some(cut) = episode.director.get_cut_mut(id) {
+            let owner = format!("cut:{}", cut.name);
+            let camera = &mut cut.camera;
+            camera
+                .position_timeline
+                .tracks
+                .iter_mut()
+                .for_each(|t| f(&owner, t));
+            camera
+                .target_timeline
+                .tracks
+                .iter_mut()
+                .for_each(|t| f(&owner, t));
+            f(&owner, &mut camera.fov_track);
+        }
+    }
+}
+
+/// Apply `profile` to every track in place and report the error introduced.
+fn apply_profile(episode: &mut EpisodePackage, profile: &QualityProfile) -> CompressionReport {
+    let mut report = CompressionReport::default();
+    for_each_track(episode, |owner, track| {
+        let original: Vec<(f32, f32)> = track.keyframes.iter().map(|k| (k.time, k.value)).collect();
+        reduce_track(track, profile.max_reduction_error);
+        for kf in &mut track.keyframes {
+            kf.value = profile.quantize(kf.value);
+        }
+        let result: Vec<(f32, f32)> = track.keyframes.iter().map(|k| (k.time, k.value)).collect();
+        let max_error = original
+            .iter()
+            .map(|&(t, v)| (v - sample_linear(&result, t)).abs())
+            .fold(0.0, f32::max);
+        report.tracks.push(TrackError {
+            track: format!("{}/{}", owner, track.name),
+            keyframes_before: original.len(),
+            keyframes_after: result.len(),
+            max_error,
+        });
+    });
+    report
+}
+
+/// Value coding mode tag written after the version byte.
+fn precision_tag(precision: ValuePrecision) -> u8 {
+    match precision {
+        ValuePrecision::Exact => 0,
+        ValuePrecision::F16 => 1,
+        ValuePrecision::Fixed(_) => 2,
+    }
+}
+
+/// Map f32 bits to integers ordered like the floats, so nearby values have small deltas.
+#[inline]
+fn ordered_bits(v: f32) -> u32 {
+    let bits = v.to_bits();
+    if bits & 0x8000_0000 != 0 {
+        !bits
+    } else {
+        bits | 0x8000_0000
+    }
+}
+
+#[inline]
+fn from_ordered_bits(o: u32) -> f32 {
+    f32::from_bits(if o & 0x8000_0000 != 0 {
+        o & 0x7fff_ffff
+    } else {
+        !o
+    })
+}
+
+#[inline]
+fn zigzag(v: i32) -> u32 {
+    ((v << 1) ^ (v >> 31)) as u32
+}
+
+#[inline]
+fn unzigzag(v: u32) -> i32 {
+    ((v >> 1) as i32) ^ -((v & 1) as i32)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        out.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    out.push(v as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> io::Result<u64> {
+    let mut v = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *data.get(*pos).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::UnexpectedEof, "Truncated keyframe stream")
+        })?;
+        *pos += 1;
+        v |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(v);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Varint overflow",
+    ))
+}
+
+/// Per-track delta coder: times as delta-of-delta (regular key spacing codes to zero),
+/// values as deltas, over order-preserving bit patterns (or fixed-point steps).
+struct TrackDelta {
+    precision: ValuePrecision,
+    time: u32,
+    step: u32,
+    value: u32,
+}
+
+impl TrackDelta {
+    fn new(precision: ValuePrecision) -> Self {
+        Self {
+            precision,
+            time: 0,
+            step: 0,
+            value: 0,
+        }
+    }
+
+    fn value_code(&self, value: f32) -> u32 {
+        match self.precision {
+            ValuePrecision::Exact => ordered_bits(value),
+            ValuePrecision::F16 => {
+                let bits = f16::from_f32(value).to_bits();
+                u32::from(if bits & 0x8000 != 0 {
+                    !bits
+                } else {
+                    bits | 0x8000
+                })
+            }
+            ValuePrecision::Fixed(step) => step.code(value).unwrap_or_default() as u32,
+        }
+    }
+
+    fn code_value(&self, code: u32) -> f32 {
+        match self.precision {
+            ValuePrecision::Exact => from_ordered_bits(code),
+            ValuePrecision::F16 => {
+                let o = code as u16;
+                f16::from_bits(if o & 0x8000 != 0 { o & 0x7fff } else { !o }).to_f32()
+            }
+            ValuePrecision::Fixed(step) => code as i32 as f32 * step.0,
+        }
+    }
+
+    fn encode(&mut self, out: &mut Vec<u8>, time: f32, value: f32) {
+        let (time, value) = (ordered_bits(time), self.value_code(value));
+        let step = time.wrapping_sub(self.time);
+        write_varint(out, zigzag(step.wrapping_sub(self.step) as i32) as u64);
+        write_varint(out, zigzag(value.wrapping_sub(self.value) as i32) as u64);
+        (self.time, self.step, self.value) = (time, step, value);
+    }
+
+    fn decode(&mut self, data: &[u8], pos: &mut usize) -> io::Result<(f32, f32)> {
+        let step_delta = unzigzag(read_varint(data, pos)? as u32) as u32;
+        let value_delta = unzigzag(read_varint(data, pos)? as u32) as u32;
+        self.step = self.step.wrapping_add(step_delta);
+        self.time = self.time.wrapping_add(self.step);
+        self.value = self.value.wrapping_add(value_delta);
+        Ok((from_ordered_bits(self.time), self.code_value(self.value)))
+    }
+}
+
 /// Compress a serialized ANIM episode using ALICE-Codec.
+///
+/// Keyframe times and values are pulled out of the episode and delta/varint coded per
+/// track; the rest of the episode is bincode-encoded with those slots zeroed. Both parts
+/// are then deflated. Stream layout:
+/// `[Magic "ACMP" 4B][Version 1B][Deflate([BodyLen varint][Body][Keyframes])]`.
+/// The roundtrip is lossless (bit-exact floats); see `compress_episode_with` for lossy
+/// profiles.
 #[inline]
-pub fn compress_episode(episode: &EpisodePackage) -> Result<CompressedEpisode, Box<dyn std::error::Error>> {
+pub fn compress_episode(episode: &EpisodePackage) -> Result<CompressedEpisode> {
+    Ok(compress_episode_with(episode, &QualityProfile::lossless())?.0)
+}
+
+/// Compress with a quality profile, reporting the error introduced per track.
+///
+/// Stream layout (v2): `[Magic "ACMP" 4B][Version 1B][Precision 1B][Step f32 4B, fixed
+/// only][Deflate(...)]`.
+pub fn compress_episode_with(
+    episode: &EpisodePackage,
+    profile: &QualityProfile,
+) -> Result<(CompressedEpisode, CompressionReport)> {
     let mut raw = Vec::new();
     let original_size = crate::episode::serialize_episode(episode, &mut raw)?;
 
@@ -22,27 +381,129 @@ pub fn compress_episode(episode: &EpisodePackage) -> Result<CompressedEpisode, B
     // let config = CompressionConfig::default();
     // let compressed_data = compress(&raw, &config)?;
 
-    // Placeholder: no compression yet
-    let compressed_data = raw;
+    let mut stripped = episode.clone();
+    if let ValuePrecision::Fixed(step) = profile.precision {
+        let mut overflow = None;
+        for_each_track(&mut stripped, |owner, track| {
+            if let Some(k) = track
+                .keyframes
+                .iter()
+                .find(|k| step.code(k.value).is_none())
+            {
+                overflow.get_or_insert(format!(
+                    "{}/{}: value {} out of range for fixed step {}",
+                    owner, track.name, k.value, step.0
+                ));
+            }
+        });
+        if let Some(message) = overflow {
+            return Err(AnimationError::InvalidInput(message));
+        }
+    }
+    let report = apply_profile(&mut stripped, profile);
+    let mut keys = Vec::new();
+    for_each_track(&mut stripped, |_, track| {
+        let mut coder = TrackDelta::new(profile.precision);
+        for kf in &mut track.keyframes {
+            coder.encode(&mut keys, kf.time, kf.value);
+            (kf.time, kf.value) = (0.0, 0.0);
+        }
+    });
+    let body = bincode::serialize(&stripped)?;
+
+    let mut payload = Vec::with_capacity(body.len() + keys.len() + 8);
+    write_varint(&mut payload, body.len() as u64);
+    payload.extend_from_slice(&body);
+    payload.extend_from_slice(&keys);
+
+    let mut compressed_data = CODEC_MAGIC.to_vec();
+    compressed_data.push(CODEC_VERSION);
+    compressed_data.push(precision_tag(profile.precision));
+    if let ValuePrecision::Fixed(step) = profile.precision {
+        compressed_data.extend_from_slice(&step.0.to_le_bytes());
+    }
+    let mut encoder = DeflateEncoder::new(compressed_data, Compression::best());
+    encoder.write_all(&payload)?;
+    let compressed_data = encoder.finish()?;
     let compression_ratio = original_size as f32 / compressed_data.len().max(1) as f32;
 
-    Ok(CompressedEpisode {
-        compressed_data,
-        original_size,
-        compression_ratio,
-    })
+    Ok((
+        CompressedEpisode {
+            compressed_data,
+            original_size,
+            compression_ratio,
+        },
+        report,
+    ))
 }
 
 /// Decompress back to EpisodePackage.
 #[inline]
-pub fn decompress_episode(compressed: &CompressedEpisode) -> Result<EpisodePackage, Box<dyn std::error::Error>> {
+pub fn decompress_episode(compressed: &CompressedEpisode) -> Result<EpisodePackage> {
     // TODO: Integrate with alice_codec once available
     // let raw = decompress(&compressed.compressed_data)?;
 
-    // Placeholder: assume no compression
-    let raw = &compressed.compressed_data;
-    let mut cursor = std::io::Cursor::new(raw);
-    let episode = crate::episode::deserialize_episode(&mut cursor)?;
+    let data = &compressed.compressed_data;
+    if data.len() < 5 || data[0..4] != CODEC_MAGIC {
+        return Err(AnimationError::BadMagic { expected: "ACMP" });
+    }
+    let truncated = || AnimationError::Truncated("codec header");
+    let (precision, start) = match data[4] {
+        // v1 streams are always exact
+        1 => (ValuePrecision::Exact, 5),
+        CODEC_VERSION => match data.get(5).ok_or_else(truncated)? {
+            0 => (ValuePrecision::Exact, 6),
+            1 => (ValuePrecision::F16, 6),
+            2 => {
+                let step = data.get(6..10).ok_or_else(truncated)?;
+                let step = f32::from_le_bytes([step[0], step[1], step[2], step[3]]);
+                let step =
+                    FixedStep::new(step).map_err(|e| AnimationError::Corrupt(e.to_string()))?;
+                (ValuePrecision::Fixed(step), 10)
+            }
+            tag => {
+                return Err(AnimationError::Corrupt(format!(
+                    "Unknown value precision {}",
+                    tag
+                )))
+            }
+        },
+        version => {
+            return Err(AnimationError::UnsupportedVersion {
+                format: "codec",
+                version: version as u16,
+            })
+        }
+    };
+    let mut payload = Vec::new();
+    DeflateDecoder::new(&data[start..]).read_to_end(&mut payload)?;
+
+    let mut pos = 0;
+    let body_len = read_varint(&payload, &mut pos)? as usize;
+    let body = payload
+        .get(pos..pos.saturating_add(body_len))
+        .ok_or(AnimationError::Truncated("episode body"))?;
+    let mut episode: EpisodePackage = bincode::deserialize(body)?;
+    episode.seed_shake();
+    pos += body_len;
+
+    let mut result = Ok(());
+    for_each_track(&mut episode, |_, track| {
+        let mut coder = TrackDelta::new(precision);
+        for kf in &mut track.keyframes {
+            if result.is_err() {
+                return;
+            }
+            match coder.decode(&payload, &mut pos) {
+                Ok((time, value)) => (kf.time, kf.value) = (time, value),
+                Err(e) => result = Err(e),
+            }
+        }
+    });
+    result?;
+    if pos != payload.len() {
+        return Err(AnimationError::Corrupt("Trailing keyframe data".into()));
+    }
     Ok(episode)
 }
 
@@ -53,6 +514,7 @@ mod tests {
     use crate::npr::AnimeShading;
     use crate::scene::{Actor, SceneGraph};
     use crate::episode::EpisodeMetadata;
+    use alice_sdf::animation::{Keyframe, Timeline};
     use alice_sdf::SdfNode;
 
     #[test]
@@ -71,4 +533,143 @@ mod tests {
         let restored = decompress_episode(&compressed).unwrap();
         assert_eq!(restored.metadata.title, "Test Episode");
     }
+
+    /// Episode with ~50KB of keyframes: 24 fps keys on smooth, held, and noisy tracks.
+    fn make_keyframe_heavy_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        for a in 0..4 {
+            let mut timeline = Timeline::new(&format!("actor{}", a));
+            for (t, name) in ["translate.x", "translate.y", "translate.z"]
+                .iter()
+                .enumerate()
+            {
+                let mut track = Track::new(name);
+                for i in 0..480 {
+                    let time = i as f32 / 24.0;
+                    let value = match t {
+                        0 => (time * 0.5 + a as f32).sin() * 2.0,
+                        1 => (i / 12) as f32 * 0.25, // held poses
+                        _ => -(i as f32 * 0.37).fract(),
+                    };
+                    track.add_keyframe(Keyframe::new(time, value));
+                }
+                timeline.add_track(track);
+            }
+            sg.add_actor(
+                Actor::new(format!("actor{}", a), SdfNode::sphere(1.0)).with_timeline(timeline),
+            );
+        }
+        let mut dir = Director::new("Test");
+        dir.add_cut(Cut::new("c1", 0.0, 10.0));
+        dir.add_cut(Cut::new("c2", 10.0, 20.0));
+        let meta = EpisodeMetadata::new("Keyframes", 2, 20.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_keyframe_compression_lossless() {
+        let episode = make_keyframe_heavy_episode();
+        let compressed = compress_episode(&episode).unwrap();
+        assert!(compressed.original_size > 45_000);
+        assert!(
+            compressed.compression_ratio > 5.0,
+            "ratio {}",
+            compressed.compression_ratio
+        );
+
+        let restored = decompress_episode(&compressed).unwrap();
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        crate::episode::serialize_episode(&episode, &mut a).unwrap();
+        crate::episode::serialize_episode(&restored, &mut b).unwrap();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_lossy_profiles_report_error() {
+        let episode = make_keyframe_heavy_episode();
+        let lossless = compress_episode(&episode).unwrap();
+
+        for profile in [
+            QualityProfile::high(),
+            QualityProfile::medium(),
+            QualityProfile::low(),
+        ] {
+            let (compressed, report) = compress_episode_with(&episode, &profile).unwrap();
+            assert!(compressed.compressed_data.len() < lossless.compressed_data.len());
+            assert_eq!(report.tracks.len(), 12 + 2 * 7);
+            assert!(report.keyframes_removed() > 0);
+
+            // Reported error matches what the decoder actually produces
+            let restored = decompress_episode(&compressed).unwrap();
+            let original = episode
+                .scene_graph
+                .get_actor(crate::scene::ActorId(0))
+                .unwrap();
+            let decoded = restored
+                .scene_graph
+                .get_actor(crate::scene::ActorId(0))
+                .unwrap();
+            let (a, b) = (
+                &original.timeline.as_ref().unwrap().tracks[0],
+                &decoded.timeline.as_ref().unwrap().tracks[0],
+            );
+            let keys: Vec<(f32, f32)> = b.keyframes.iter().map(|k| (k.time, k.value)).collect();
+            let measured = a
+                .keyframes
+                .iter()
+                .map(|k| (k.value - sample_linear(&keys, k.time)).abs())
+                .fold(0.0, f32::max);
+            assert_eq!(report.tracks[0].track, "actor:actor0/translate.x");
+            assert_eq!(report.tracks[0].max_error, measured);
+            let bound = profile.max_reduction_error
+                + match profile.precision {
+                    ValuePrecision::Fixed(step) => step.get() * 0.5,
+                    _ => 2e-3, // f16 near |2|
+                };
+            assert!(
+                report.max_error() <= bound,
+                "{} > {}",
+                report.max_error(),
+                bound
+            );
+        }
+
+        // Held poses collapse to their step keys
+        let (_, report) = compress_episode_with(
+            &episode,
+            &QualityProfile::lossless().with_reduction_error(1e-6),
+        )
+        .unwrap();
+        assert_eq!(report.tracks[1].keyframes_after, 80);
+        assert_eq!(report.tracks[1].max_error, 0.0);
+    }
+
+    #[test]
+    fn test_decompress_rejects_garbage() {
+        let garbage = CompressedEpisode {
+            compressed_data: b"ANIM not compressed".to_vec(),
+            original_size: 0,
+            compression_ratio: 1.0,
+        };
+        assert!(matches!(
+            decompress_episode(&garbage),
+            Err(AnimationError::BadMagic { expected: "ACMP" })
+        ));
+    }
+
+    #[test]
+    fn test_fixed_step_validated() {
+        for step in [0.0, -0.5, f32::NAN, f32::INFINITY] {
+            assert!(matches!(
+                ValuePrecision::fixed(step),
+                Err(AnimationError::InvalidInput(_))
+            ));
+        }
+        let tiny = QualityProfile::lossless().with_precision(ValuePrecision::fixed(1e-12).unwrap());
+        assert!(matches!(
+            compress_episode_with(&make_keyframe_heavy_episode(), &tiny),
+            Err(AnimationError::InvalidInput(_))
+        ));
+    }
 }
@@ -0,0 +1,492 @@
+//! Background crowds: seeded placement of many varied copies of a template actor
+//! over regions or along paths, each running a simple locomotion cycle.
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use alice_sdf::SdfNode;
+use glam::{Quat, Vec3};
+
+use crate::cycle::TimelineCycle;
+use crate::rng::{EpisodeRng, SeededRng};
+use crate::scene::{Actor, ActorId, SceneGraph};
+
+/// Where crowd members are placed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CrowdRegion {
+    /// Uniformly scattered over the XZ rectangle at `min.y` (stands, plazas).
+    Area { min: Vec3, max: Vec3 },
+    /// Spread along a polyline, up to `width / 2` to either side (streets, corridors).
+    Path { points: Vec<Vec3>, width: f32 },
+}
+
+/// Per-instance motion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrowdCycle {
+    /// Standing in place with a slight breathing bob.
+    Idle { bob: f32, period: f32 },
+    /// Hopping cheer (stadium crowds).
+    Cheer { height: f32, period: f32 },
+    /// Walking along the region's path at `speed`, looping back to its start;
+    /// in an area, walks straight ahead. `period` is one step.
+    Walk { speed: f32, bob: f32, period: f32 },
+}
+
+impl CrowdCycle {
+    /// Vertical offset of the cycle at `phase` seconds.
+    fn lift(&self, phase: f32) -> f32 {
+        use std::f32::consts::PI;
+        match *self {
+            CrowdCycle::Idle { bob, period } => {
+                bob * 0.5 * (1.0 - (2.0 * PI * phase / period.max(1e-3)).cos())
+            }
+            CrowdCycle::Cheer { height, period }
+            | CrowdCycle::Walk {
+                bob: height,
+                period,
+                ..
+            } => height * (PI * phase / period.max(1e-3)).sin().abs(),
+        }
+    }
+
+    fn period(&self) -> f32 {
+        match *self {
+            CrowdCycle::Idle { period, .. }
+            | CrowdCycle::Cheer { period, .. }
+            | CrowdCycle::Walk { period, .. } => period,
+        }
+    }
+}
+
+/// Ranges the per-instance variation is drawn from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrowdVariation {
+    /// Uniform scale range.
+    pub scale: (f32, f32),
+    /// Number of palette slots instances are spread over.
+    pub palettes: u32,
+    /// Desynchronize cycles with a random phase offset.
+    pub offset_cycles: bool,
+    /// Random speed multiplier range for walkers.
+    pub speed: (f32, f32),
+}
+
+impl Default for CrowdVariation {
+    fn default() -> Self {
+        Self {
+            scale: (0.9, 1.1),
+            palettes: 4,
+            offset_cycles: true,
+            speed: (0.85, 1.15),
+        }
+    }
+}
+
+/// One generated background character.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrowdInstance {
+    /// Spawn position (area) or distance along the path plus lateral offset.
+    pub position: Vec3,
+    /// Yaw around +Y in radians.
+    pub heading: f32,
+    pub scale: f32,
+    /// Palette slot for the shading pass, `0..palettes`.
+    pub palette: u32,
+    /// Phase offset into the cycle, seconds.
+    pub cycle_offset: f32,
+    pub speed_scale: f32,
+    /// Distance along the path at time 0 (path regions only).
+    pub path_distance: f32,
+    pub lateral: f32,
+}
+
+/// Crowd recipe.
+#[derive(Debug, Clone)]
+pub struct CrowdSpec {
+    pub name: String,
+    pub template: SdfNode,
+    pub region: CrowdRegion,
+    pub count: usize,
+    pub seed: u64,
+    pub cycle: CrowdCycle,
+    pub variation: CrowdVariation,
+    /// Keyframes per second of the baked cycle timelines.
+    pub sample_rate: f32,
+}
+
+impl CrowdSpec {
+    pub fn new(
+        name: impl Into<String>,
+        template: SdfNode,
+        region: CrowdRegion,
+        count: usize,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            template,
+            region,
+            count,
+            seed: 0,
+            cycle: CrowdCycle::Idle {
+                bob: 0.02,
+                period: 3.0,
+            },
+            variation: CrowdVariation::default(),
+            sample_rate: 12.0,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Seed from the episode's `crowd/<name>` stream.
+    pub fn with_rng(mut self, rng: &EpisodeRng) -> Self {
+        self.seed = rng.seed_for(&format!("crowd/{}", self.name));
+        self
+    }
+
+    pub fn with_cycle(mut self, cycle: CrowdCycle) -> Self {
+        self.cycle = cycle;
+        self
+    }
+
+    pub fn with_variation(mut self, variation: CrowdVariation) -> Self {
+        self.variation = variation;
+        self
+    }
+}
+
+/// Cumulative lengths of a polyline.
+pub(crate) fn path_lengths(points: &[Vec3]) -> Vec<f32> {
+    let mut lengths = Vec::with_capacity(points.len());
+    let mut total = 0.0;
+    for (i, p) in points.iter().enumerate() {
+        if i > 0 {
+            total += p.distance(points[i - 1]);
+        }
+        lengths.push(total);
+    }
+    lengths
+}
+
+/// Point and unit XZ tangent at `distance` along a polyline (clamped to its ends).
+pub(crate) fn path_sample(points: &[Vec3], lengths: &[f32], distance: f32) -> (Vec3, Vec3) {
+    let total = lengths.last().copied().unwrap_or(0.0);
+    if points.len() < 2 || total <= 0.0 {
+        return (points.first().copied().unwrap_or(Vec3::ZERO), Vec3::Z);
+    }
+    let d = distance.clamp(0.0, total);
+    let i = lengths
+        .partition_point(|&l| l <= d)
+        .clamp(1, points.len() - 1);
+    let (a, b) = (points[i - 1], points[i]);
+    let span = (lengths[i] - lengths[i - 1]).max(1e-6);
+    let tangent = (b - a) * Vec3::new(1.0, 0.0, 1.0);
+    (
+        a.lerp(b, (d - lengths[i - 1]) / span),
+        tangent.normalize_or(Vec3::Z),
+    )
+}
+
+/// A generated crowd, ready to be added to a scene.
+#[derive(Debug, Clone)]
+pub struct Crowd {
+    pub spec: CrowdSpec,
+    pub instances: Vec<CrowdInstance>,
+}
+
+impl Crowd {
+    /// Place `spec.count` instances. Same spec and seed always give the same crowd.
+    pub fn generate(spec: CrowdSpec) -> Self {
+        let mut rng = SeededRng::new(spec.seed);
+        let var = spec.variation;
+        let lengths = match &spec.region {
+            CrowdRegion::Path { points, .. } => path_lengths(points),
+            CrowdRegion::Area { .. } => Vec::new(),
+        };
+        let instances = (0..spec.count)
+            .map(|i| {
+                let mut instance = CrowdInstance {
+                    position: Vec3::ZERO,
+                    heading: 0.0,
+                    scale: var.scale.0 + (var.scale.1 - var.scale.0) * rng.next_f32(),
+                    palette: (rng.next_u64() % var.palettes.max(1) as u64) as u32,
+                    cycle_offset: if var.offset_cycles {
+                        rng.next_f32() * spec.cycle.period()
+                    } else {
+                        0.0
+                    },
+                    speed_scale: var.speed.0 + (var.speed.1 - var.speed.0) * rng.next_f32(),
+                    path_distance: 0.0,
+                    lateral: 0.0,
+                };
+                match &spec.region {
+                    CrowdRegion::Area { min, max } => {
+                        instance.position = Vec3::new(
+                            min.x + (max.x - min.x) * rng.next_f32(),
+                            min.y,
+                            min.z + (max.z - min.z) * rng.next_f32(),
+                        );
+                        instance.heading = rng.next_f32() * std::f32::consts::TAU;
+                    }
+                    CrowdRegion::Path { points, width } => {
+                        let total = lengths.last().copied().unwrap_or(0.0);
+                        // Stratified along the path so nobody spawns on top of each other
+                        let slot = (i as f32 + rng.next_f32()) / spec.count as f32;
+                        instance.path_distance = slot * total;
+                        instance.lateral = (rng.next_f32() - 0.5) * width;
+                        let (point, tangent) =
+                            path_sample(points, &lengths, instance.path_distance);
+                        instance.position = point + tangent.cross(Vec3::Y) * instance.lateral;
+                        instance.heading = tangent.x.atan2(tangent.z);
+                    }
+                }
+                instance
+            })
+            .collect();
+        Self { spec, instances }
+    }
+
+    /// Ground position of instance `index` at `time`, before the cycle's lift.
+    pub fn position_at(&self, index: usize, time: f32) -> Vec3 {
+        let instance = &self.instances[index];
+        let CrowdCycle::Walk { speed, .. } = self.spec.cycle else {
+            return instance.position;
+        };
+        let travelled = speed * instance.speed_scale * time;
+        match &self.spec.region {
+            CrowdRegion::Path { points, .. } => {
+                let lengths = path_lengths(points);
+                // Walkers loop back to the start of the path
+                let total = lengths.last().copied().unwrap_or(0.0);
+                let distance = (instance.path_distance + travelled).rem_euclid(total.max(1e-6));
+                let (point, tangent) = path_sample(points, &lengths, distance);
+                point + tangent.cross(Vec3::Y) * instance.lateral
+            }
+            CrowdRegion::Area { .. } => {
+                let facing = Quat::from_rotation_y(instance.heading) * Vec3::Z;
+                instance.position + facing * travelled
+            }
+        }
+    }
+
+    /// Baked translate/scale timeline for instance `index` over `[0, duration]`.
+    pub fn timeline(&self, index: usize, duration: f32) -> Timeline {
+        let instance = &self.instances[index];
+        let mut tl = Timeline::new(&format!("{}#{index}", self.spec.name));
+        let mut tracks = [
+            Track::new("translate.x"),
+            Track::new("translate.y"),
+            Track::new("translate.z"),
+        ];
+        let samples = (duration * self.spec.sample_rate).ceil().max(1.0) as u32;
+        for s in 0..=samples {
+            let t = duration * s as f32 / samples as f32;
+            let p = self.position_at(index, t)
+                + Vec3::Y * self.spec.cycle.lift(t + instance.cycle_offset);
+            for (track, value) in tracks.iter_mut().zip([p.x, p.y, p.z]) {
+                track.add_keyframe(Keyframe::new(t, value));
+            }
+        }
+        let mut scale = Track::new("scale");
+        scale.add_keyframe(Keyframe::new(0.0, instance.scale));
+        for track in tracks {
+            tl.add_track(track);
+        }
+        tl.add_track(scale);
+        tl
+    }
+
+    /// Add every instance to `scene` as `{name}#{i}` with its cycle baked over `duration`.
+    ///
+    /// Facing is baked into each copy's SDF; position, bob and scale live on its timeline.
+    pub fn spawn(&self, scene: &mut SceneGraph, duration: f32) -> Vec<ActorId> {
+        (0..self.instances.len())
+            .map(|i| {
+                let sdf = self
+                    .spec
+                    .template
+                    .clone()
+                    .rotate(Quat::from_rotation_y(self.instances[i].heading));
+                let actor = Actor::new(format!("{}#{i}", self.spec.name), sdf)
+                    .with_timeline(self.timeline(i, duration));
+                scene.add_actor(actor)
+            })
+            .collect()
+    }
+
+    /// Like [`Crowd::spawn`], but bakes a single cycle per instance and loops it with the
+    /// instance's cycle offset, so the crowd runs under any cut length.
+    ///
+    /// Instances stay at their spawn point: walkers step in place (use `spawn` for travel).
+    pub fn spawn_looping(&self, scene: &mut SceneGraph) -> Vec<ActorId> {
+        let period = self.spec.cycle.period().max(1e-3);
+        (0..self.instances.len())
+            .map(|i| {
+                let instance = &self.instances[i];
+                let origin = self.position_at(i, 0.0);
+                let mut tl = Timeline::new(&format!("{}#{i}", self.spec.name));
+                let mut tracks = [
+                    Track::new("translate.x"),
+                    Track::new("translate.y"),
+                    Track::new("translate.z"),
+                ];
+                let samples = (period * self.spec.sample_rate).ceil().max(1.0) as u32;
+                for s in 0..=samples {
+                    let t = period * s as f32 / samples as f32;
+                    let p = origin + Vec3::Y * self.spec.cycle.lift(t);
+                    for (track, value) in tracks.iter_mut().zip([p.x, p.y, p.z]) {
+                        track.add_keyframe(Keyframe::new(t, value));
+                    }
+                }
+                let mut scale = Track::new("scale");
+                scale.add_keyframe(Keyframe::new(0.0, instance.scale));
+                for track in tracks {
+                    tl.add_track(track);
+                }
+                tl.add_track(scale);
+                let sdf = self
+                    .spec
+                    .template
+                    .clone()
+                    .rotate(Quat::from_rotation_y(instance.heading));
+                let cycle = TimelineCycle::repeat()
+                    .with_period(period)
+                    .with_offset(instance.cycle_offset);
+                scene.add_actor(
+                    Actor::new(format!("{}#{i}", self.spec.name), sdf)
+                        .with_timeline(tl)
+                        .with_cycle(cycle),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance::Performance;
+
+    fn stand() -> CrowdSpec {
+        CrowdSpec::new(
+            "fan",
+            SdfNode::capsule(Vec3::ZERO, Vec3::Y, 0.2),
+            CrowdRegion::Area {
+                min: Vec3::new(-10.0, 0.0, 5.0),
+                max: Vec3::new(10.0, 0.0, 8.0),
+            },
+            50,
+        )
+        .with_seed(7)
+        .with_cycle(CrowdCycle::Cheer {
+            height: 0.3,
+            period: 0.8,
+        })
+    }
+
+    #[test]
+    fn test_generate_is_seeded_and_varied() {
+        let a = Crowd::generate(stand());
+        let b = Crowd::generate(stand());
+        assert_eq!(a.instances, b.instances);
+        assert_ne!(a.instances, Crowd::generate(stand().with_seed(8)).instances);
+        let seeded = |seed| Crowd::generate(stand().with_rng(&EpisodeRng::new(seed))).instances;
+        assert_eq!(seeded(1), seeded(1));
+        assert_ne!(seeded(1), seeded(2));
+
+        assert_eq!(a.instances.len(), 50);
+        for inst in &a.instances {
+            assert!((-10.0..=10.0).contains(&inst.position.x));
+            assert!((5.0..=8.0).contains(&inst.position.z));
+            assert!((0.9..=1.1).contains(&inst.scale));
+            assert!(inst.palette < 4);
+        }
+        let palettes: std::collections::BTreeSet<u32> =
+            a.instances.iter().map(|i| i.palette).collect();
+        assert!(palettes.len() > 1);
+        assert!(a.instances.iter().any(|i| i.cycle_offset > 0.0));
+    }
+
+    #[test]
+    fn test_spawn_bakes_cycles() {
+        let crowd = Crowd::generate(stand());
+        let mut scene = SceneGraph::new();
+        let ids = crowd.spawn(&mut scene, 2.0);
+        assert_eq!(ids.len(), 50);
+        let actor = scene.get_actor(ids[3]).unwrap();
+        assert_eq!(actor.name, "fan#3");
+        let tl = actor.timeline.as_ref().unwrap();
+        let inst = crowd.instances[3];
+        assert_eq!(tl.get_value("scale", 1.0), Some(inst.scale));
+        assert_eq!(tl.get_value("translate.x", 1.3), Some(inst.position.x));
+        let heights: Vec<f32> = (0..20)
+            .map(|i| tl.get_value("translate.y", i as f32 * 0.1).unwrap())
+            .collect();
+        assert!(heights.iter().all(|h| (0.0..=0.3 + 1e-4).contains(h)));
+        assert!(heights.iter().any(|h| *h > 0.15));
+    }
+
+    #[test]
+    fn test_spawn_looping_runs_past_bake() {
+        let crowd = Crowd::generate(stand());
+        let mut scene = SceneGraph::new();
+        let ids = crowd.spawn_looping(&mut scene);
+        let scene = Performance::from(&scene);
+        let tl = scene.get_actor(ids[3]).unwrap().timeline.as_ref().unwrap();
+        assert!(tl.duration() <= 0.8 + 1e-4);
+        // One cycle is baked, yet the motion keeps going a minute in, still offset per instance
+        let late = scene.actor_position_at(ids[3], 60.0);
+        let next = scene.actor_position_at(ids[3], 60.0 + 0.8);
+        assert!((late.y - next.y).abs() < 1e-3);
+        let heights: Vec<f32> = (0..8)
+            .map(|i| scene.actor_position_at(ids[3], 60.0 + i as f32 * 0.1).y)
+            .collect();
+        assert!(heights.iter().any(|h| *h > 0.15));
+        assert_ne!(
+            scene.actor_position_at(ids[3], 60.1).y,
+            scene.actor_position_at(ids[4], 60.1).y
+        );
+    }
+
+    #[test]
+    fn test_walkers_follow_path() {
+        let spec = CrowdSpec::new(
+            "pedestrian",
+            SdfNode::sphere(0.3),
+            CrowdRegion::Path {
+                points: vec![
+                    Vec3::ZERO,
+                    Vec3::new(0.0, 0.0, 10.0),
+                    Vec3::new(10.0, 0.0, 10.0),
+                ],
+                width: 2.0,
+            },
+            10,
+        )
+        .with_cycle(CrowdCycle::Walk {
+            speed: 1.0,
+            bob: 0.05,
+            period: 0.5,
+        })
+        .with_variation(CrowdVariation {
+            speed: (1.0, 1.0),
+            ..CrowdVariation::default()
+        });
+        let crowd = Crowd::generate(spec);
+        for (i, inst) in crowd.instances.iter().enumerate() {
+            assert!(inst.lateral.abs() <= 1.0);
+            // Stratified spawn: one walker per 2-unit slot
+            assert!(
+                inst.path_distance >= i as f32 * 2.0 && inst.path_distance <= (i + 1) as f32 * 2.0
+            );
+        }
+        let first = crowd.instances[0];
+        assert!((first.heading - 0.0).abs() < 1e-5);
+        let later = crowd.position_at(0, 3.0);
+        assert!((later.z - (first.position.z + 3.0)).abs() < 1e-4);
+        // Wraps back to the start after the full 20-unit path
+        let wrapped = crowd.position_at(0, 20.0);
+        assert!((wrapped - first.position).length() < 1e-3);
+    }
+}
@@ -0,0 +1,369 @@
+//! Graph-editor curves: Bezier keys with tangent modes, weighted tangents and
+//! step/hold segments, evaluated directly or baked to plain keyframe tracks.
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use serde::{Deserialize, Serialize};
+
+/// Offset before a step key where the held value is repeated when baking to plain keys.
+const STEP_EPSILON: f32 = 1e-4;
+
+/// How a key's tangents are derived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TangentMode {
+    /// Smooth (Catmull-Rom) slopes, flattened at local extremes so curves never overshoot.
+    Auto,
+    /// Zero slope (ease in/out).
+    Flat,
+    /// Slopes pointing at the neighbouring keys.
+    Linear,
+    /// User-set slopes and weights, left untouched by `recompute_tangents`.
+    Free,
+}
+
+/// Interpolation of the segment leaving a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Interpolation {
+    Bezier,
+    Linear,
+    /// Hold the key's value until the next key.
+    Step,
+}
+
+/// One curve key. Slopes are in value units per second; weights are handle lengths
+/// as a fraction of the adjacent segment's duration.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CurveKey {
+    pub time: f32,
+    pub value: f32,
+    pub in_slope: f32,
+    pub out_slope: f32,
+    /// Used only when `weighted` is set; otherwise handles are a third of the segment.
+    pub in_weight: f32,
+    pub out_weight: f32,
+    pub weighted: bool,
+    pub mode: TangentMode,
+    pub interpolation: Interpolation,
+}
+
+impl CurveKey {
+    pub fn new(time: f32, value: f32) -> Self {
+        Self {
+            time,
+            value,
+            in_slope: 0.0,
+            out_slope: 0.0,
+            in_weight: 1.0 / 3.0,
+            out_weight: 1.0 / 3.0,
+            weighted: false,
+            mode: TangentMode::Auto,
+            interpolation: Interpolation::Bezier,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: TangentMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Free tangents with explicit slopes (broken when they differ).
+    pub fn with_slopes(mut self, in_slope: f32, out_slope: f32) -> Self {
+        self.in_slope = in_slope;
+        self.out_slope = out_slope;
+        self.mode = TangentMode::Free;
+        self
+    }
+
+    /// Weighted tangents: handle lengths as fractions of the segment (0..1).
+    pub fn with_weights(mut self, in_weight: f32, out_weight: f32) -> Self {
+        self.in_weight = in_weight.clamp(0.0, 1.0);
+        self.out_weight = out_weight.clamp(0.0, 1.0);
+        self.weighted = true;
+        self
+    }
+
+    fn weights(&self) -> (f32, f32) {
+        if self.weighted {
+            (self.in_weight, self.out_weight)
+        } else {
+            (1.0 / 3.0, 1.0 / 3.0)
+        }
+    }
+}
+
+/// A named animation curve; keys are kept sorted by time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Curve {
+    pub name: String,
+    pub keys: Vec<CurveKey>,
+}
+
+impl Curve {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            keys: Vec::new(),
+        }
+    }
+
+    /// Insert a key (replacing one at the same time) and refresh non-free tangents.
+    pub fn add_key(&mut self, key: CurveKey) {
+        match self.keys.iter().position(|k| k.time == key.time) {
+            Some(i) => self.keys[i] = key,
+            None => {
+                let pos = self.keys.partition_point(|k| k.time < key.time);
+                self.keys.insert(pos, key);
+            }
+        }
+        self.recompute_tangents();
+    }
+
+    /// Recompute slopes of every key whose mode is not `Free`.
+    pub fn recompute_tangents(&mut self) {
+        let n = self.keys.len();
+        for i in 0..n {
+            let key = self.keys[i];
+            let prev = i.checked_sub(1).map(|j| self.keys[j]);
+            let next = self.keys.get(i + 1).copied();
+            let slope_to = |a: &CurveKey, b: &CurveKey| {
+                let dt = b.time - a.time;
+                if dt > 0.0 {
+                    (b.value - a.value) / dt
+                } else {
+                    0.0
+                }
+            };
+            let (in_slope, out_slope) = match key.mode {
+                TangentMode::Free => continue,
+                TangentMode::Flat => (0.0, 0.0),
+                TangentMode::Linear => (
+                    prev.map(|p| slope_to(&p, &key)).unwrap_or(0.0),
+                    next.map(|n| slope_to(&key, &n)).unwrap_or(0.0),
+                ),
+                TangentMode::Auto => match (prev, next) {
+                    (Some(p), Some(n)) => {
+                        let extreme = (key.value - p.value) * (n.value - key.value) <= 0.0;
+                        let slope = if extreme { 0.0 } else { slope_to(&p, &n) };
+                        (slope, slope)
+                    }
+                    _ => (0.0, 0.0),
+                },
+            };
+            self.keys[i].in_slope = in_slope;
+            self.keys[i].out_slope = out_slope;
+        }
+    }
+
+    /// Value at `time`; constant before the first and after the last key.
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let (first, last) = match (self.keys.first(), self.keys.last()) {
+            (Some(f), Some(l)) => (f, l),
+            _ => return 0.0,
+        };
+        if time <= first.time {
+            return first.value;
+        }
+        if time >= last.time {
+            return last.value;
+        }
+        let i = self.keys.partition_point(|k| k.time <= time);
+        let (a, b) = (&self.keys[i - 1], &self.keys[i]);
+        let dt = b.time - a.time;
+        let s = (time - a.time) / dt;
+        match a.interpolation {
+            Interpolation::Step => a.value,
+            Interpolation::Linear => a.value + (b.value - a.value) * s,
+            Interpolation::Bezier => {
+                let (_, out_w) = a.weights();
+                let (in_w, _) = b.weights();
+                // Handle x positions in normalized segment time
+                let x1 = out_w;
+                let x2 = 1.0 - in_w;
+                let y1 = a.value + a.out_slope * out_w * dt;
+                let y2 = b.value - b.in_slope * in_w * dt;
+                let u = solve_bezier_x(x1, x2, s);
+                bezier(a.value, y1, y2, b.value, u)
+            }
+        }
+    }
+
+    /// Plain keyframes from a track, with the given tangent mode on every key.
+    pub fn from_track(track: &Track, mode: TangentMode) -> Self {
+        let interpolation = if mode == TangentMode::Linear {
+            Interpolation::Linear
+        } else {
+            Interpolation::Bezier
+        };
+        let mut curve = Curve::new(&track.name);
+        curve.keys = track
+            .keyframes
+            .iter()
+            .map(|k| {
+                CurveKey::new(k.time, k.value)
+                    .with_mode(mode)
+                    .with_interpolation(interpolation)
+            })
+            .collect();
+        curve.recompute_tangents();
+        curve
+    }
+
+    /// Bake to a plain linearly interpolated track: every key is kept, Bezier segments
+    /// are sampled at `fps` and steps become a held key just before the next key.
+    pub fn to_track(&self, fps: f32) -> Track {
+        let mut track = Track::new(&self.name);
+        for (i, key) in self.keys.iter().enumerate() {
+            track.add_keyframe(Keyframe::new(key.time, key.value));
+            let Some(next) = self.keys.get(i + 1) else {
+                continue;
+            };
+            match key.interpolation {
+                Interpolation::Linear => {}
+                Interpolation::Step => {
+                    let hold = (next.time - STEP_EPSILON).max(key.time);
+                    if hold > key.time {
+                        track.add_keyframe(Keyframe::new(hold, key.value));
+                    }
+                }
+                Interpolation::Bezier => {
+                    let samples = ((next.time - key.time) * fps).ceil().max(1.0) as u32;
+                    for s in 1..samples {
+                        let t = key.time + (next.time - key.time) * s as f32 / samples as f32;
+                        track.add_keyframe(Keyframe::new(t, self.evaluate(t)));
+                    }
+                }
+            }
+        }
+        track
+    }
+}
+
+/// Cubic Bezier in one dimension.
+#[inline]
+fn bezier(p0: f32, p1: f32, p2: f32, p3: f32, u: f32) -> f32 {
+    let v = 1.0 - u;
+    v * v * v * p0 + 3.0 * v * v * u * p1 + 3.0 * v * u * u * p2 + u * u * u * p3
+}
+
+/// Parameter `u` where the normalized time curve (0, x1, x2, 1) reaches `x`.
+/// Monotonic for handle weights within the segment, so bisection always converges.
+fn solve_bezier_x(x1: f32, x2: f32, x: f32) -> f32 {
+    if (x1 - 1.0 / 3.0).abs() < 1e-6 && (x2 - 2.0 / 3.0).abs() < 1e-6 {
+        return x;
+    }
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    let mut u = x;
+    for _ in 0..32 {
+        let value = bezier(0.0, x1, x2, 1.0, u);
+        if (value - x).abs() < 1e-6 {
+            break;
+        }
+        if value < x {
+            lo = u;
+        } else {
+            hi = u;
+        }
+        u = 0.5 * (lo + hi);
+    }
+    u
+}
+
+/// Curves for every track of a timeline (camera position/target or actor channels).
+pub fn curves_from_timeline(timeline: &Timeline, mode: TangentMode) -> Vec<Curve> {
+    timeline
+        .tracks
+        .iter()
+        .map(|t| Curve::from_track(t, mode))
+        .collect()
+}
+
+/// Bake curves back into a plain timeline at `fps`.
+pub fn timeline_from_curves(name: &str, curves: &[Curve], fps: f32) -> Timeline {
+    let mut timeline = Timeline::new(name);
+    for curve in curves {
+        timeline.add_track(curve.to_track(fps));
+    }
+    timeline
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zigzag() -> Track {
+        let mut track = Track::new("translate.y");
+        for (t, v) in [(0.0, 0.0), (1.0, 1.0), (2.0, 3.0), (3.0, 0.0)] {
+            track.add_keyframe(Keyframe::new(t, v));
+        }
+        track
+    }
+
+    #[test]
+    fn test_linear_round_trip() {
+        let track = zigzag();
+        let curve = Curve::from_track(&track, TangentMode::Linear);
+        for i in 0..=30 {
+            let t = i as f32 * 0.1;
+            assert!((curve.evaluate(t) - track.evaluate(t)).abs() < 1e-5);
+        }
+        let baked = curve.to_track(24.0);
+        assert_eq!(baked.keyframes.len(), 4);
+
+        let mut source = Timeline::new("move");
+        source.add_track(track);
+        let tl = timeline_from_curves(
+            "move",
+            &curves_from_timeline(&source, TangentMode::Linear),
+            24.0,
+        );
+        assert_eq!(tl.get_value("translate.y", 2.5), Some(1.5));
+    }
+
+    #[test]
+    fn test_auto_tangents_smooth_without_overshoot() {
+        let curve = Curve::from_track(&zigzag(), TangentMode::Auto);
+        // Key at 2.0 is a peak: flattened, so the curve never exceeds it
+        assert_eq!(curve.keys[2].out_slope, 0.0);
+        assert_eq!(curve.keys[1].in_slope, 1.5);
+        for i in 0..=300 {
+            let v = curve.evaluate(i as f32 * 0.01);
+            assert!((-1e-5..=3.0 + 1e-5).contains(&v));
+        }
+        // Passes through every key
+        for key in &curve.keys {
+            assert!((curve.evaluate(key.time) - key.value).abs() < 1e-5);
+        }
+        // Eases in from the first key
+        assert!(curve.evaluate(0.1) < 0.1);
+
+        let baked = curve.to_track(10.0);
+        for i in 0..=30 {
+            let t = i as f32 * 0.1;
+            assert!((baked.evaluate(t) - curve.evaluate(t)).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_step_and_weighted_tangents() {
+        let mut curve = Curve::new("pose");
+        curve.add_key(CurveKey::new(0.0, 0.0).with_interpolation(Interpolation::Step));
+        curve.add_key(CurveKey::new(1.0, 5.0).with_slopes(0.0, 0.0));
+        curve.add_key(CurveKey::new(2.0, 0.0).with_slopes(0.0, 0.0));
+        assert_eq!(curve.evaluate(0.99), 0.0);
+        assert_eq!(curve.evaluate(1.0), 5.0);
+        let baked = curve.to_track(24.0);
+        assert_eq!(baked.evaluate(0.99), 0.0);
+
+        let plain = curve.evaluate(1.25);
+        curve.keys[1] = curve.keys[1].with_weights(1.0 / 3.0, 0.9);
+        let heavy = curve.evaluate(1.25);
+        // A longer outgoing handle holds the value near the key for longer
+        assert!(heavy > plain);
+        assert!((curve.evaluate(2.0)).abs() < 1e-6);
+        assert!((curve.evaluate(1.0) - 5.0).abs() < 1e-6);
+    }
+}
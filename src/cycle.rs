@@ -0,0 +1,135 @@
+//! Loop / cycle semantics for actor timelines.
+//!
+//! A [`TimelineCycle`] remaps scene time into timeline time before evaluation, so a short
+//! keyed cycle (grass sway, a flag, a crowd cheer) runs for as long as any cut needs
+//! without duplicating keyframes.
+
+use serde::{Deserialize, Serialize};
+
+/// How time continues past the end of one cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LoopMode {
+    /// Play once and hold the last pose.
+    #[default]
+    Once,
+    /// Jump back to the start.
+    Repeat,
+    /// Alternate forward and backward.
+    PingPong,
+}
+
+/// Cycle applied to an actor's timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimelineCycle {
+    pub mode: LoopMode,
+    /// Number of cycles before holding; `None` loops forever (also before time 0).
+    pub count: Option<u32>,
+    /// Seconds added to scene time, to de-synchronise instances of the same cycle.
+    pub offset: f32,
+    /// Cycle length; `None` uses the timeline's duration.
+    pub period: Option<f32>,
+}
+
+impl TimelineCycle {
+    pub fn new(mode: LoopMode) -> Self {
+        Self {
+            mode,
+            count: None,
+            offset: 0.0,
+            period: None,
+        }
+    }
+
+    #[inline]
+    pub fn repeat() -> Self {
+        Self::new(LoopMode::Repeat)
+    }
+
+    #[inline]
+    pub fn ping_pong() -> Self {
+        Self::new(LoopMode::PingPong)
+    }
+
+    pub fn with_count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_period(mut self, period: f32) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Timeline time for scene `time`, given the timeline's own `duration`.
+    pub fn map_time(&self, time: f32, duration: f32) -> f32 {
+        let period = self.period.unwrap_or(duration);
+        let t = time + self.offset;
+        if period.is_nan() || period <= 0.0 || !t.is_finite() {
+            return t;
+        }
+        if self.mode == LoopMode::Once {
+            return t.clamp(0.0, period);
+        }
+        let cycle = floor(t / period);
+        if let Some(count) = self.count {
+            if cycle < 0 {
+                return 0.0;
+            }
+            if cycle >= count as i64 {
+                // Hold the pose the last cycle ended on
+                let backward = self.mode == LoopMode::PingPong && count % 2 == 0;
+                return if backward || count == 0 { 0.0 } else { period };
+            }
+        }
+        let local = (t - cycle as f32 * period).clamp(0.0, period);
+        if self.mode == LoopMode::PingPong && cycle % 2 != 0 {
+            period - local
+        } else {
+            local
+        }
+    }
+}
+
+/// Floor without libm: truncation rounds negative values toward zero.
+#[inline]
+pub(crate) fn floor(x: f32) -> i64 {
+    let i = x as i64;
+    i - ((i as f32) > x) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeat_and_ping_pong() {
+        let repeat = TimelineCycle::repeat();
+        assert!((repeat.map_time(2.5, 1.0) - 0.5).abs() < 1e-6);
+        assert!((repeat.map_time(-0.25, 1.0) - 0.75).abs() < 1e-6);
+        let pong = TimelineCycle::ping_pong();
+        assert!((pong.map_time(0.25, 1.0) - 0.25).abs() < 1e-6);
+        assert!((pong.map_time(1.25, 1.0) - 0.75).abs() < 1e-6);
+        assert!((pong.map_time(2.25, 1.0) - 0.25).abs() < 1e-6);
+        assert_eq!(TimelineCycle::new(LoopMode::Once).map_time(5.0, 2.0), 2.0);
+    }
+
+    #[test]
+    fn test_count_offset_and_period() {
+        let twice = TimelineCycle::repeat().with_count(2);
+        assert!((twice.map_time(1.5, 1.0) - 0.5).abs() < 1e-6);
+        assert_eq!(twice.map_time(7.0, 1.0), 1.0);
+        assert_eq!(twice.map_time(-1.0, 1.0), 0.0);
+        // Two ping-pong passes end back at the start
+        assert_eq!(
+            TimelineCycle::ping_pong().with_count(2).map_time(9.0, 1.0),
+            0.0
+        );
+        let shifted = TimelineCycle::repeat().with_offset(0.3).with_period(0.5);
+        assert!((shifted.map_time(0.5, 10.0) - 0.3).abs() < 1e-6);
+    }
+}
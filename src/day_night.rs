@@ -0,0 +1,454 @@
+//! Day-night cycle: a per-scene time of day that drives the key light direction, light
+//! colors and palette grading, so a long scene keeps its lighting continuous across cuts.
+//!
+//! Each scene runs a clock (start hour plus a rate in hours per second of scene time) that
+//! an hour track can override. The hour picks the sun's position on a tilted arc and blends
+//! a ring of [`LightingKey`]s (night, dawn, noon, dusk by default) that wraps at midnight.
+
+use std::f32::consts::TAU;
+
+use alice_sdf::animation::Track;
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::director::Director;
+use crate::episode::EpisodePackage;
+use crate::framebuffer::Framebuffer;
+use crate::npr::AnimeShading;
+use crate::overlay::composite_overlays;
+use crate::render::{Renderer, DEFAULT_TILE_SIZE};
+
+/// Hours in one cycle.
+pub const DAY_HOURS: f32 = 24.0;
+
+/// Lift / gain / saturation grade applied to finished frames.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ColorGrade {
+    /// Added to the shadows (fades out towards white).
+    pub lift: [f32; 3],
+    /// Multiplies the highlights.
+    pub gain: [f32; 3],
+    /// 0 = greyscale, 1 = unchanged.
+    pub saturation: f32,
+}
+
+impl Default for ColorGrade {
+    fn default() -> Self {
+        Self {
+            lift: [0.0; 3],
+            gain: [1.0; 3],
+            saturation: 1.0,
+        }
+    }
+}
+
+impl ColorGrade {
+    /// Grade one linear RGB color.
+    pub fn apply(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let mut out = [0.0; 3];
+        for c in 0..3 {
+            out[c] = rgb[c] * self.gain[c] + self.lift[c] * (1.0 - rgb[c]).max(0.0);
+        }
+        let luma = 0.2126 * out[0] + 0.7152 * out[1] + 0.0722 * out[2];
+        out.map(|c| (luma + (c - luma) * self.saturation).max(0.0))
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            lift: lerp3(self.lift, other.lift, t),
+            gain: lerp3(self.gain, other.gain, t),
+            saturation: self.saturation + (other.saturation - self.saturation) * t,
+        }
+    }
+}
+
+/// Lighting look at one hour of the day.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LightingKey {
+    /// Hour in `0..24`.
+    pub hour: f32,
+    /// Key light color (sun by day, moon by night).
+    pub sun_color: [f32; 3],
+    /// Fill reaching surfaces that face away from the key light.
+    pub ambient_color: [f32; 3],
+    /// Background for rays that miss everything.
+    pub sky_color: [f32; 3],
+    pub grade: ColorGrade,
+}
+
+impl LightingKey {
+    pub fn new(
+        hour: f32,
+        sun_color: [f32; 3],
+        ambient_color: [f32; 3],
+        sky_color: [f32; 3],
+    ) -> Self {
+        Self {
+            hour: hour.rem_euclid(DAY_HOURS),
+            sun_color,
+            ambient_color,
+            sky_color,
+            grade: ColorGrade::default(),
+        }
+    }
+
+    pub fn with_grade(mut self, grade: ColorGrade) -> Self {
+        self.grade = grade;
+        self
+    }
+
+    /// Night, dawn, noon and dusk keys.
+    #[rustfmt::skip]
+    pub fn defaults() -> Vec<Self> {
+        let grade = |gain: [f32; 3], lift: [f32; 3], saturation: f32| ColorGrade {
+            lift,
+            gain,
+            saturation,
+        };
+        vec![
+            Self::new(0.0, [0.25, 0.3, 0.5], [0.05, 0.06, 0.12], [0.02, 0.03, 0.08])
+                .with_grade(grade([0.7, 0.75, 1.0], [0.0, 0.0, 0.03], 0.6)),
+            Self::new(6.0, [1.0, 0.6, 0.4], [0.3, 0.22, 0.3], [0.9, 0.55, 0.45])
+                .with_grade(grade([1.05, 0.95, 0.9], [0.02, 0.0, 0.02], 0.9)),
+            Self::new(12.0, [1.0, 0.98, 0.92], [0.35, 0.38, 0.5], [0.45, 0.65, 0.95]),
+            Self::new(18.0, [1.0, 0.45, 0.25], [0.3, 0.18, 0.3], [0.85, 0.4, 0.35])
+                .with_grade(grade([1.1, 0.9, 0.85], [0.03, 0.0, 0.02], 1.1)),
+        ]
+    }
+}
+
+/// Time of day for one director scene (matched by name).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneTimeOfDay {
+    pub scene: String,
+    /// Hour at the scene's first frame.
+    pub start_hour: f32,
+    /// Clock speed in hours per second of scene time; 0 holds the hour.
+    pub hours_per_second: f32,
+    /// Absolute hour over scene-local time; overrides the clock when present.
+    pub hour_track: Option<Track>,
+    /// Key light multiplier over scene-local time (clouds, eclipses); 1 when absent.
+    pub intensity_track: Option<Track>,
+}
+
+impl SceneTimeOfDay {
+    pub fn new(scene: impl Into<String>, start_hour: f32) -> Self {
+        Self {
+            scene: scene.into(),
+            start_hour,
+            hours_per_second: 0.0,
+            hour_track: None,
+            intensity_track: None,
+        }
+    }
+
+    pub fn with_rate(mut self, hours_per_second: f32) -> Self {
+        self.hours_per_second = hours_per_second;
+        self
+    }
+
+    pub fn with_hour_track(mut self, track: Track) -> Self {
+        self.hour_track = Some(track);
+        self
+    }
+
+    pub fn with_intensity_track(mut self, track: Track) -> Self {
+        self.intensity_track = Some(track);
+        self
+    }
+
+    /// Hour in `0..24`, `local` seconds into the scene.
+    pub fn hour_at(&self, local: f32) -> f32 {
+        let hour = match &self.hour_track {
+            Some(track) => track.evaluate(local),
+            None => self.start_hour + self.hours_per_second * local,
+        };
+        hour.rem_euclid(DAY_HOURS)
+    }
+
+    /// Key light multiplier `local` seconds into the scene.
+    pub fn intensity_at(&self, local: f32) -> f32 {
+        self.intensity_track
+            .as_ref()
+            .map_or(1.0, |t| t.evaluate(local))
+            .max(0.0)
+    }
+}
+
+/// Light rig resolved for one instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightRig {
+    pub hour: f32,
+    /// Direction towards the key light: the sun, or the moon opposite it at night.
+    pub light_dir: Vec3,
+    /// Key light color, scaled by the scene's intensity.
+    pub sun_color: [f32; 3],
+    pub ambient_color: [f32; 3],
+    pub sky_color: [f32; 3],
+    pub grade: ColorGrade,
+}
+
+impl LightRig {
+    /// Point the renderer's key light and set its background to the sky.
+    pub fn apply(&self, renderer: &mut Renderer) {
+        renderer.light_dir = self.light_dir;
+        let [r, g, b] = self.sky_color;
+        renderer.background = [r, g, b, renderer.background[3]];
+    }
+
+    /// Set the cel ramp to run from ambient (shadow) to ambient plus key light (lit).
+    pub fn apply_shading(&self, shading: &mut AnimeShading) {
+        let cel = &mut shading.cel_shading;
+        for c in 0..3 {
+            cel.shadow_color[c] = self.ambient_color[c];
+            cel.highlight_color[c] = self.ambient_color[c] + self.sun_color[c];
+        }
+    }
+
+    /// Apply the palette grade to every pixel of `fb`.
+    pub fn grade(&self, fb: &mut Framebuffer) {
+        let premultiplied = fb.premultiplied;
+        for px in &mut fb.color {
+            let a = px[3];
+            let scale = if premultiplied && a > 0.0 {
+                1.0 / a
+            } else {
+                1.0
+            };
+            let graded = self
+                .grade
+                .apply([px[0] * scale, px[1] * scale, px[2] * scale]);
+            let back = if premultiplied { a } else { 1.0 };
+            for c in 0..3 {
+                px[c] = graded[c] * back;
+            }
+        }
+    }
+}
+
+/// Per-scene time of day for an episode, the lighting keys and the sun's arc.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayNight {
+    pub scenes: Vec<SceneTimeOfDay>,
+    /// Lighting ring, blended by hour and wrapping at midnight.
+    pub keys: Vec<LightingKey>,
+    /// Rotation about +Y of the sunrise direction (0 = sun rises along +X).
+    pub sun_azimuth: f32,
+    /// Tilt of the noon sun away from the zenith, towards +Z (radians).
+    pub sun_tilt: f32,
+}
+
+impl Default for DayNight {
+    fn default() -> Self {
+        Self {
+            scenes: Vec::new(),
+            keys: LightingKey::defaults(),
+            sun_azimuth: 0.0,
+            sun_tilt: 0.5,
+        }
+    }
+}
+
+impl DayNight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_scene(mut self, scene: SceneTimeOfDay) -> Self {
+        self.scenes.push(scene);
+        self
+    }
+
+    pub fn with_keys(mut self, keys: Vec<LightingKey>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    pub fn with_sun_arc(mut self, azimuth: f32, tilt: f32) -> Self {
+        self.sun_azimuth = azimuth;
+        self.sun_tilt = tilt;
+        self
+    }
+
+    /// Direction towards the sun at `hour`: rising at 6, highest at 12, setting at 18 and
+    /// below the horizon overnight.
+    pub fn sun_direction(&self, hour: f32) -> Vec3 {
+        let angle = (hour - 6.0) / DAY_HOURS * TAU;
+        let rotation = Quat::from_rotation_y(self.sun_azimuth);
+        let sunrise = rotation * Vec3::X;
+        let noon = rotation * Vec3::new(0.0, self.sun_tilt.cos(), self.sun_tilt.sin());
+        sunrise * angle.cos() + noon * angle.sin()
+    }
+
+    /// Lighting key blended for `hour`; the default look when there are no keys.
+    pub fn key_at(&self, hour: f32) -> LightingKey {
+        let hour = hour.rem_euclid(DAY_HOURS);
+        let Some(first) = self.keys.first() else {
+            return LightingKey::new(hour, [1.0; 3], [0.2, 0.15, 0.25], [0.0; 3]);
+        };
+        // Nearest keys at or before and strictly after `hour`, around the ring
+        let since = |k: &LightingKey| (hour - k.hour).rem_euclid(DAY_HOURS);
+        let until = |k: &LightingKey| (k.hour - hour).rem_euclid(DAY_HOURS);
+        let prev = self
+            .keys
+            .iter()
+            .min_by(|a, b| since(a).total_cmp(&since(b)))
+            .unwrap_or(first);
+        let next = self
+            .keys
+            .iter()
+            .filter(|k| until(k) > 0.0)
+            .min_by(|a, b| until(a).total_cmp(&until(b)))
+            .unwrap_or(prev);
+        let span = (next.hour - prev.hour).rem_euclid(DAY_HOURS);
+        let t = if span > 0.0 { since(prev) / span } else { 0.0 };
+        LightingKey {
+            hour,
+            sun_color: lerp3(prev.sun_color, next.sun_color, t),
+            ambient_color: lerp3(prev.ambient_color, next.ambient_color, t),
+            sky_color: lerp3(prev.sky_color, next.sky_color, t),
+            grade: prev.grade.lerp(&next.grade, t),
+        }
+    }
+
+    /// Light rig at `time` from the scene holding the active cut, or `None` outside any
+    /// configured scene. Clocks and tracks run in scene-local time.
+    pub fn evaluate(&self, director: &Director, time: f32) -> Option<LightRig> {
+        let (scene, start) = director.active_scene(time)?;
+        let clock = self.scenes.iter().find(|s| s.scene == scene.name)?;
+        let local = time - start;
+        let hour = clock.hour_at(local);
+        let key = self.key_at(hour);
+        let sun = self.sun_direction(hour);
+        let intensity = clock.intensity_at(local);
+        Some(LightRig {
+            hour,
+            light_dir: if sun.y >= 0.0 { sun } else { -sun },
+            sun_color: key.sun_color.map(|c| c * intensity),
+            ambient_color: key.ambient_color,
+            sky_color: key.sky_color,
+            grade: key.grade,
+        })
+    }
+
+    /// Render `episode` at `time` under this cycle's rig and grade, then composite the
+    /// episode's overlays (ungraded) on top.
+    pub fn render_episode(
+        &self,
+        renderer: &Renderer,
+        episode: &EpisodePackage,
+        time: f32,
+    ) -> Framebuffer {
+        let Some(rig) = self.evaluate(&episode.director, time) else {
+            return renderer.render_episode(episode, time);
+        };
+        let state = episode.director.evaluate(&episode.scene_graph, time);
+        let sdf = episode.director.evaluate_scene(&episode.scene_graph, time);
+        let objects = if renderer.settings().aovs.object_id {
+            episode.director.evaluate_actors(&episode.scene_graph, time)
+        } else {
+            Vec::new()
+        };
+        let mut renderer = renderer.clone();
+        let mut shading = episode.shading.clone();
+        rig.apply(&mut renderer);
+        rig.apply_shading(&mut shading);
+        let mut fb = renderer.render_tiled(
+            &sdf,
+            &objects,
+            &state.camera_state,
+            &shading,
+            DEFAULT_TILE_SIZE,
+        );
+        rig.grade(&mut fb);
+        composite_overlays(&mut fb, &episode.overlays, time);
+        fb
+    }
+}
+
+#[inline]
+fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Scene};
+    use alice_sdf::animation::Keyframe;
+
+    fn director() -> Director {
+        let mut dir = Director::new("Festival");
+        let a = dir.add_cut(Cut::new("stalls", 0.0, 4.0));
+        let b = dir.add_cut(Cut::new("fireworks", 4.0, 8.0));
+        let c = dir.add_cut(Cut::new("dream", 8.0, 10.0));
+        let mut evening = Scene::new("evening");
+        evening.cuts.extend([a, b]);
+        let mut dream = Scene::new("dream");
+        dream.cuts.push(c);
+        dir.add_scene(evening);
+        dir.add_scene(dream);
+        dir
+    }
+
+    #[test]
+    fn test_sun_arc_and_moon() {
+        let cycle = DayNight::new().with_sun_arc(0.0, 0.0);
+        assert!((cycle.sun_direction(6.0) - Vec3::X).length() < 1e-5);
+        assert!((cycle.sun_direction(12.0) - Vec3::Y).length() < 1e-5);
+        assert!((cycle.sun_direction(18.0) + Vec3::X).length() < 1e-5);
+        assert!(cycle.sun_direction(0.0).y < -0.99);
+        assert!(cycle.sun_direction(9.0).y > 0.0 && cycle.sun_direction(9.0).x > 0.0);
+    }
+
+    #[test]
+    fn test_keys_blend_and_wrap_midnight() {
+        let cycle = DayNight::new();
+        let noon = cycle.key_at(12.0);
+        assert_eq!(noon.sky_color, [0.45, 0.65, 0.95]);
+        assert_eq!(noon.grade, ColorGrade::default());
+        // Halfway from dusk (18) to night (0 = 24)
+        let late = cycle.key_at(21.0);
+        assert!((late.sky_color[2] - 0.5 * (0.35 + 0.08)).abs() < 1e-5);
+        assert_eq!(cycle.key_at(-3.0).sky_color, late.sky_color);
+        let single =
+            DayNight::new().with_keys(vec![LightingKey::new(5.0, [1.0; 3], [0.0; 3], [0.1; 3])]);
+        assert_eq!(single.key_at(20.0).sky_color, [0.1; 3]);
+    }
+
+    #[test]
+    fn test_scene_clock_overrides_and_rig() {
+        let mut hours = Track::new("hour");
+        hours.add_keyframe(Keyframe::new(0.0, 23.0));
+        hours.add_keyframe(Keyframe::new(2.0, 25.0));
+        let cycle = DayNight::new()
+            .with_scene(SceneTimeOfDay::new("evening", 17.0).with_rate(0.5))
+            .with_scene(SceneTimeOfDay::new("dream", 0.0).with_hour_track(hours));
+        let dir = director();
+        // The clock keeps running across the cut at 4s
+        assert!((cycle.evaluate(&dir, 6.0).unwrap().hour - 20.0).abs() < 1e-5);
+        assert!((cycle.evaluate(&dir, 9.5).unwrap().hour - 0.5).abs() < 1e-4);
+        assert!(cycle.evaluate(&dir, 11.0).is_none());
+
+        // Night: the key light is the moon, above the horizon
+        let rig = cycle.evaluate(&dir, 9.5).unwrap();
+        assert!(rig.light_dir.y > 0.0);
+        let mut renderer = Renderer::new(4, 4);
+        let mut shading = AnimeShading::default();
+        rig.apply(&mut renderer);
+        rig.apply_shading(&mut shading);
+        assert_eq!(renderer.light_dir, rig.light_dir);
+        assert_eq!(renderer.background[..3], rig.sky_color);
+        assert_eq!(shading.cel_shading.shadow_color[..3], rig.ambient_color);
+
+        let mut fb = Framebuffer::new(1, 1);
+        fb.fill([0.5, 0.5, 0.5, 1.0]);
+        rig.grade(&mut fb);
+        let px = fb.pixel(0, 0);
+        assert!(px[2] > px[0], "night grade cools the frame: {:?}", px);
+    }
+}
@@ -1,7 +1,13 @@
 //! Bridge: ALICE-Animation → ALICE-DB
 //! Episode persistence, metadata indexing, and search.
 
-use crate::episode::{EpisodeMetadata, EpisodePackage};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::episode::{deserialize_episode, serialize_episode, EpisodePackage};
+use crate::error::AnimationError;
+use crate::patch::{apply_patch, create_patch, EpisodePatch};
 // use alice_db::{Database, Record};
 
 /// Episode record for database storage.
@@ -15,6 +21,46 @@ pub struct EpisodeRecord {
     pub actor_count: usize,
     pub cut_count: usize,
     pub created_at: u64,
+    /// Indexed names and text for search.
+    pub actor_names: Vec<String>,
+    pub cut_names: Vec<String>,
+    pub dialogue: Vec<String>,
+}
+
+/// Record sidecar magic bytes.
+const RECORD_MAGIC: [u8; 4] = *b"AREC";
+/// Record sidecar format version. Version 1 sidecars are bare bincode without a header.
+const RECORD_VERSION: u16 = 2;
+
+/// Record layout of version 1 sidecars, before the search fields.
+#[derive(serde::Deserialize)]
+struct EpisodeRecordV1 {
+    id: String,
+    title: String,
+    episode_number: u32,
+    duration_seconds: f32,
+    size_bytes: usize,
+    actor_count: usize,
+    cut_count: usize,
+    created_at: u64,
+}
+
+impl From<EpisodeRecordV1> for EpisodeRecord {
+    fn from(v1: EpisodeRecordV1) -> Self {
+        Self {
+            id: v1.id,
+            title: v1.title,
+            episode_number: v1.episode_number,
+            duration_seconds: v1.duration_seconds,
+            size_bytes: v1.size_bytes,
+            actor_count: v1.actor_count,
+            cut_count: v1.cut_count,
+            created_at: v1.created_at,
+            actor_names: Vec::new(),
+            cut_names: Vec::new(),
+            dialogue: Vec::new(),
+        }
+    }
 }
 
 impl EpisodeRecord {
@@ -37,6 +83,34 @@ impl EpisodeRecord {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
                 .as_secs(),
+            actor_names: package
+                .scene_graph
+                .actor_ids()
+                .into_iter()
+                .filter_map(|id| package.scene_graph.get_actor(id))
+                .map(|a| a.name.clone())
+                .collect(),
+            cut_names: package
+                .director
+                .cuts()
+                .map(|(_, c)| c.name.clone())
+                .collect(),
+            dialogue: package
+                .director
+                .episode
+                .dialogue
+                .iter()
+                .map(|l| l.text.clone())
+                .collect(),
+        }
+    }
+
+    /// Indexed values of `field`.
+    pub fn field_values(&self, field: SearchField) -> &[String] {
+        match field {
+            SearchField::Actor => &self.actor_names,
+            SearchField::Cut => &self.cut_names,
+            SearchField::Dialogue => &self.dialogue,
         }
     }
 
@@ -46,6 +120,82 @@ impl EpisodeRecord {
         self.size_bytes = size_bytes;
         self
     }
+
+    /// Encode as a record sidecar (magic, version, flags, then bincode body).
+    pub fn to_bytes(&self) -> crate::error::Result<Vec<u8>> {
+        let mut bytes = Vec::from(RECORD_MAGIC);
+        bytes.extend_from_slice(&RECORD_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0u16.to_le_bytes());
+        bincode::serialize_into(&mut bytes, self)?;
+        Ok(bytes)
+    }
+
+    /// Decode a record sidecar. Headerless version 1 sidecars load with empty search
+    /// fields.
+    pub fn from_bytes(bytes: &[u8]) -> crate::error::Result<Self> {
+        if bytes.len() < 8 || bytes[0..4] != RECORD_MAGIC {
+            return Ok(bincode::deserialize::<EpisodeRecordV1>(bytes)?.into());
+        }
+        let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+        if version != RECORD_VERSION {
+            return Err(AnimationError::UnsupportedVersion {
+                format: "AREC",
+                version,
+            });
+        }
+        Ok(bincode::deserialize(&bytes[8..])?)
+    }
+}
+
+/// Searchable record field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SearchField {
+    Actor,
+    Cut,
+    Dialogue,
+}
+
+impl SearchField {
+    pub const ALL: [SearchField; 3] = [SearchField::Actor, SearchField::Cut, SearchField::Dialogue];
+}
+
+/// Case-insensitive text pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextMatch {
+    Contains(String),
+    Prefix(String),
+}
+
+impl TextMatch {
+    pub fn contains(pattern: impl Into<String>) -> Self {
+        TextMatch::Contains(pattern.into().to_lowercase())
+    }
+
+    pub fn prefix(pattern: impl Into<String>) -> Self {
+        TextMatch::Prefix(pattern.into().to_lowercase())
+    }
+
+    /// Prefix patterns match the start of the value or of any word in it.
+    pub fn matches(&self, value: &str) -> bool {
+        let value = value.to_lowercase();
+        match self {
+            TextMatch::Contains(p) => value.contains(&p.to_lowercase()),
+            TextMatch::Prefix(p) => {
+                let p = p.to_lowercase();
+                value.starts_with(&p) || search_terms(&value).any(|w| w.starts_with(&p))
+            }
+        }
+    }
+}
+
+/// Lowercase words of `text` with surrounding punctuation trimmed.
+fn search_terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric())
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
 }
 
 /// Query parameters for episode search.
@@ -56,6 +206,8 @@ pub struct EpisodeQuery {
     pub max_duration: Option<f32>,
     pub min_episode_number: Option<u32>,
     pub max_episode_number: Option<u32>,
+    /// Text filters; each must match some value of its field (`None` = any field).
+    pub text: Vec<(Option<SearchField>, TextMatch)>,
 }
 
 impl EpisodeQuery {
@@ -88,6 +240,31 @@ impl EpisodeQuery {
         self
     }
 
+    /// Require an actor name matching `pattern`.
+    #[inline]
+    pub fn with_actor(self, pattern: TextMatch) -> Self {
+        self.with_text_in(Some(SearchField::Actor), pattern)
+    }
+
+    /// Require a cut name matching `pattern`.
+    #[inline]
+    pub fn with_cut(self, pattern: TextMatch) -> Self {
+        self.with_text_in(Some(SearchField::Cut), pattern)
+    }
+
+    /// Require a dialogue line matching `pattern`.
+    #[inline]
+    pub fn with_dialogue(self, pattern: TextMatch) -> Self {
+        self.with_text_in(Some(SearchField::Dialogue), pattern)
+    }
+
+    /// Require `pattern` to match in `field`, or in any field when `None`.
+    #[inline]
+    pub fn with_text_in(mut self, field: Option<SearchField>, pattern: TextMatch) -> Self {
+        self.text.push((field, pattern));
+        self
+    }
+
     /// Check if a record matches this query.
     #[inline]
     pub fn matches(&self, record: &EpisodeRecord) -> bool {
@@ -116,7 +293,442 @@ impl EpisodeQuery {
                 return false;
             }
         }
-        true
+        self.text.iter().all(|(field, pattern)| {
+            let fields = match field {
+                Some(f) => std::slice::from_ref(f),
+                None => &SearchField::ALL[..],
+            };
+            fields
+                .iter()
+                .flat_map(|&f| record.field_values(f))
+                .any(|v| pattern.matches(v))
+        })
+    }
+}
+
+/// Inverted index from lowercase terms to episode IDs, for searching a library without
+/// scanning every record. Names are indexed whole and by word; dialogue by word.
+#[derive(Debug, Clone, Default)]
+pub struct EpisodeSearchIndex {
+    terms: BTreeMap<String, BTreeSet<(SearchField, String)>>,
+}
+
+impl EpisodeSearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index every record in `records`.
+    pub fn build<'a>(records: impl IntoIterator<Item = &'a EpisodeRecord>) -> Self {
+        let mut index = Self::new();
+        records.into_iter().for_each(|r| index.insert(r));
+        index
+    }
+
+    /// Index (or re-index) a record.
+    pub fn insert(&mut self, record: &EpisodeRecord) {
+        self.remove(&record.id);
+        for field in SearchField::ALL {
+            for value in record.field_values(field) {
+                let whole = (field != SearchField::Dialogue).then(|| value.to_lowercase());
+                for term in whole.into_iter().chain(search_terms(value)) {
+                    self.terms
+                        .entry(term)
+                        .or_default()
+                        .insert((field, record.id.clone()));
+                }
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.terms.retain(|_, hits| {
+            hits.retain(|(_, hit)| hit != id);
+            !hits.is_empty()
+        });
+    }
+
+    /// IDs of episodes with a term matching `pattern` in `field` (any field when `None`),
+    /// sorted. Prefix searches use the term order; substring searches scan all terms.
+    pub fn search(&self, field: Option<SearchField>, pattern: &TextMatch) -> Vec<String> {
+        let (prefix, needle) = match pattern {
+            TextMatch::Prefix(p) => (true, p.to_lowercase()),
+            TextMatch::Contains(p) => (false, p.to_lowercase()),
+        };
+        let start = if prefix {
+            needle.clone()
+        } else {
+            String::new()
+        };
+        let hits = self
+            .terms
+            .range(start..)
+            .take_while(|(term, _)| !prefix || term.starts_with(&needle))
+            .filter(|(term, _)| prefix || term.contains(&needle));
+        let ids: BTreeSet<&String> = hits
+            .flat_map(|(_, set)| set.iter())
+            .filter(|(f, _)| field.is_none() || field == Some(*f))
+            .map(|(_, id)| id)
+            .collect();
+        ids.into_iter().cloned().collect()
+    }
+}
+
+/// Episode persistence keyed by `EpisodeRecord::id`; `alice_db` can implement the same trait.
+pub trait EpisodeStore {
+    /// Insert or replace an episode; returns its record (with the stored size).
+    fn put(&mut self, package: &EpisodePackage) -> crate::error::Result<EpisodeRecord>;
+    fn get(&self, id: &str) -> crate::error::Result<Option<EpisodePackage>>;
+    /// Returns whether the episode existed.
+    fn delete(&mut self, id: &str) -> crate::error::Result<bool>;
+    /// All records, ordered by ID.
+    fn list(&self) -> crate::error::Result<Vec<EpisodeRecord>>;
+
+    fn query(&self, query: &EpisodeQuery) -> crate::error::Result<Vec<EpisodeRecord>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(|r| query.matches(r))
+            .collect())
+    }
+}
+
+fn encode_package(package: &EpisodePackage) -> crate::error::Result<(EpisodeRecord, Vec<u8>)> {
+    let mut bytes = Vec::new();
+    let size = serialize_episode(package, &mut bytes)?;
+    Ok((EpisodeRecord::from_package(package).with_size(size), bytes))
+}
+
+/// In-memory store holding serialized episodes.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryEpisodeStore {
+    episodes: BTreeMap<String, (EpisodeRecord, Vec<u8>)>,
+}
+
+impl MemoryEpisodeStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.episodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.episodes.is_empty()
+    }
+}
+
+impl EpisodeStore for MemoryEpisodeStore {
+    fn put(&mut self, package: &EpisodePackage) -> crate::error::Result<EpisodeRecord> {
+        let (record, bytes) = encode_package(package)?;
+        self.episodes
+            .insert(record.id.clone(), (record.clone(), bytes));
+        Ok(record)
+    }
+
+    fn get(&self, id: &str) -> crate::error::Result<Option<EpisodePackage>> {
+        self.episodes
+            .get(id)
+            .map(|(_, bytes)| deserialize_episode(&mut bytes.as_slice()))
+            .transpose()
+    }
+
+    fn delete(&mut self, id: &str) -> crate::error::Result<bool> {
+        Ok(self.episodes.remove(id).is_some())
+    }
+
+    fn list(&self) -> crate::error::Result<Vec<EpisodeRecord>> {
+        Ok(self.episodes.values().map(|(r, _)| r.clone()).collect())
+    }
+}
+
+/// Store backed by a directory: `{stem}.anim` episode files with `{stem}.rec` record
+/// sidecars. Files are written to a temporary name and renamed into place.
+#[derive(Debug, Clone)]
+pub struct DirectoryEpisodeStore {
+    root: PathBuf,
+}
+
+impl DirectoryEpisodeStore {
+    /// Open (creating if needed) a store rooted at `root`.
+    pub fn open(root: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// File stem for an ID: `[A-Za-z0-9_-]` is kept and every other byte is written as
+    /// `%XX`, so distinct IDs never share a file.
+    fn stem(id: &str) -> String {
+        let mut stem = String::with_capacity(id.len());
+        for byte in id.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'_' | b'-') {
+                stem.push(byte as char);
+            } else {
+                stem.push_str(&format!("%{byte:02X}"));
+            }
+        }
+        stem
+    }
+
+    fn path(&self, id: &str, ext: &str) -> PathBuf {
+        self.root.join(format!("{}.{}", Self::stem(id), ext))
+    }
+
+    fn write_atomic(path: &Path, bytes: &[u8]) -> crate::error::Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, bytes)?;
+        Ok(std::fs::rename(&tmp, path)?)
+    }
+
+    fn read_record(path: &Path) -> crate::error::Result<EpisodeRecord> {
+        EpisodeRecord::from_bytes(&std::fs::read(path)?)
+    }
+}
+
+impl EpisodeStore for DirectoryEpisodeStore {
+    fn put(&mut self, package: &EpisodePackage) -> crate::error::Result<EpisodeRecord> {
+        let (record, bytes) = encode_package(package)?;
+        let encoded_record = record.to_bytes()?;
+        // Episode first: a record sidecar always points at a complete file
+        Self::write_atomic(&self.path(&record.id, "anim"), &bytes)?;
+        Self::write_atomic(&self.path(&record.id, "rec"), &encoded_record)?;
+        Ok(record)
+    }
+
+    fn get(&self, id: &str) -> crate::error::Result<Option<EpisodePackage>> {
+        match std::fs::File::open(self.path(id, "anim")) {
+            Ok(file) => Ok(Some(deserialize_episode(&mut io::BufReader::new(file))?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn delete(&mut self, id: &str) -> crate::error::Result<bool> {
+        let mut existed = false;
+        for ext in ["rec", "anim"] {
+            match std::fs::remove_file(self.path(id, ext)) {
+                Ok(()) => existed = true,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(existed)
+    }
+
+    fn list(&self) -> crate::error::Result<Vec<EpisodeRecord>> {
+        let mut records = Vec::new();
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "rec") {
+                records.push(Self::read_record(&path)?);
+            }
+        }
+        records.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(records)
+    }
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// One stored version of an episode.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RevisionRecord {
+    /// ID the episode was committed under.
+    pub episode_id: String,
+    /// Sequential per episode, starting at 1.
+    pub revision: u32,
+    pub parent: Option<u32>,
+    pub author: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub summary: String,
+    pub size_bytes: usize,
+}
+
+/// Changes between two revisions.
+#[derive(Debug, Clone)]
+pub struct RevisionDiff {
+    pub from: u32,
+    pub to: u32,
+    pub patch: EpisodePatch,
+}
+
+impl RevisionDiff {
+    /// Chunks (metadata, scene graph, shading, director, individual cuts) that differ.
+    pub fn changed_chunks(&self) -> usize {
+        self.patch.changed_chunks()
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+enum RevisionContent {
+    /// Serialized episode (root revisions).
+    Snapshot(Vec<u8>),
+    /// Patch against the parent revision.
+    Delta(EpisodePatch),
+}
+
+/// Version history of episodes for review workflows. Root revisions are stored in full,
+/// later ones as chunk patches against their parent.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "RevisionHistoryData")]
+pub struct RevisionHistory {
+    revisions: BTreeMap<String, Vec<(RevisionRecord, RevisionContent)>>,
+}
+
+/// Serialized form of [`RevisionHistory`], checked before use.
+#[derive(serde::Deserialize)]
+struct RevisionHistoryData {
+    revisions: BTreeMap<String, Vec<(RevisionRecord, RevisionContent)>>,
+}
+
+impl TryFrom<RevisionHistoryData> for RevisionHistory {
+    type Error = AnimationError;
+
+    /// Revisions must be numbered in order, with every parent older than its child, so
+    /// patch chains always end at a root.
+    fn try_from(data: RevisionHistoryData) -> crate::error::Result<Self> {
+        for (episode_id, entries) in &data.revisions {
+            for (i, (record, _)) in entries.iter().enumerate() {
+                if record.revision as usize != i + 1 {
+                    return Err(AnimationError::Corrupt(format!(
+                        "Revision {} of '{}' stored at position {}",
+                        record.revision,
+                        episode_id,
+                        i + 1
+                    )));
+                }
+                check_parent(record)?;
+            }
+        }
+        Ok(Self {
+            revisions: data.revisions,
+        })
+    }
+}
+
+fn check_parent(record: &RevisionRecord) -> crate::error::Result<()> {
+    match record.parent {
+        Some(parent) if parent >= record.revision => Err(AnimationError::Corrupt(format!(
+            "Revision {} of '{}' has parent {}",
+            record.revision, record.episode_id, parent
+        ))),
+        _ => Ok(()),
+    }
+}
+
+impl RevisionHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a new version of `package` under `episode_id`, which stays fixed for the
+    /// history even when the title or number changes. `parent` must be an existing
+    /// revision of the same episode; `None` starts a new root.
+    pub fn commit(
+        &mut self,
+        episode_id: &str,
+        package: &EpisodePackage,
+        parent: Option<u32>,
+        author: impl Into<String>,
+        summary: impl Into<String>,
+    ) -> crate::error::Result<RevisionRecord> {
+        let (episode, bytes) = encode_package(package)?;
+        let content = match parent {
+            Some(parent) => {
+                RevisionContent::Delta(create_patch(&self.get(episode_id, parent)?, package)?)
+            }
+            None => RevisionContent::Snapshot(bytes),
+        };
+        let entries = self.revisions.entry(episode_id.to_string()).or_default();
+        let record = RevisionRecord {
+            episode_id: episode_id.to_string(),
+            revision: entries.len() as u32 + 1,
+            parent,
+            author: author.into(),
+            timestamp: unix_now(),
+            summary: summary.into(),
+            size_bytes: episode.size_bytes,
+        };
+        entries.push((record.clone(), content));
+        Ok(record)
+    }
+
+    /// Revisions of an episode, oldest first.
+    pub fn revisions(&self, episode_id: &str) -> impl Iterator<Item = &RevisionRecord> {
+        self.revisions
+            .get(episode_id)
+            .into_iter()
+            .flatten()
+            .map(|(record, _)| record)
+    }
+
+    pub fn latest(&self, episode_id: &str) -> Option<&RevisionRecord> {
+        self.revisions(episode_id).last()
+    }
+
+    pub fn record(&self, episode_id: &str, revision: u32) -> Option<&RevisionRecord> {
+        self.entry(episode_id, revision)
+            .ok()
+            .map(|(record, _)| record)
+    }
+
+    fn entry(
+        &self,
+        episode_id: &str,
+        revision: u32,
+    ) -> crate::error::Result<&(RevisionRecord, RevisionContent)> {
+        revision
+            .checked_sub(1)
+            .and_then(|i| self.revisions.get(episode_id)?.get(i as usize))
+            .ok_or_else(|| {
+                AnimationError::InvalidInput(format!(
+                    "No revision {} of '{}'",
+                    revision, episode_id
+                ))
+            })
+    }
+
+    /// Reconstruct the episode at `revision` by replaying patches from its root.
+    pub fn get(&self, episode_id: &str, revision: u32) -> crate::error::Result<EpisodePackage> {
+        let mut chain = Vec::new();
+        let mut current = revision;
+        let root = loop {
+            let (record, content) = self.entry(episode_id, current)?;
+            match content {
+                RevisionContent::Snapshot(bytes) => break bytes,
+                RevisionContent::Delta(patch) => {
+                    chain.push(patch);
+                    // Parents precede children, so the chain terminates
+                    check_parent(record)?;
+                    current = record.parent.ok_or_else(|| {
+                        AnimationError::Corrupt("Delta revision without parent".into())
+                    })?;
+                }
+            }
+        };
+        let mut episode = deserialize_episode(&mut root.as_slice())?;
+        for patch in chain.into_iter().rev() {
+            episode = apply_patch(&episode, patch)?;
+        }
+        Ok(episode)
+    }
+
+    /// Patch turning revision `from` into revision `to`.
+    pub fn diff(&self, episode_id: &str, from: u32, to: u32) -> crate::error::Result<RevisionDiff> {
+        let patch = create_patch(&self.get(episode_id, from)?, &self.get(episode_id, to)?)?;
+        Ok(RevisionDiff { from, to, patch })
     }
 }
 
@@ -124,6 +736,7 @@ impl EpisodeQuery {
 mod tests {
     use super::*;
     use crate::director::{Cut, Director};
+    use crate::episode::EpisodeMetadata;
     use crate::npr::AnimeShading;
     use crate::scene::{Actor, SceneGraph};
     use alice_sdf::SdfNode;
@@ -142,6 +755,135 @@ mod tests {
         assert_eq!(record.duration_seconds, 120.0);
         assert_eq!(record.actor_count, 1);
         assert_eq!(record.cut_count, 1);
+
+        let loaded = EpisodeRecord::from_bytes(&record.to_bytes().unwrap()).unwrap();
+        assert_eq!(loaded.actor_names, vec!["hero"]);
+        // Version 1 sidecars were bare bincode without the search fields
+        let v1 = (
+            "ep-0005-DB Test",
+            "DB Test",
+            5u32,
+            120.0f32,
+            64usize,
+            1usize,
+            1usize,
+            0u64,
+        );
+        let old = EpisodeRecord::from_bytes(&bincode::serialize(&v1).unwrap()).unwrap();
+        assert_eq!((old.id.as_str(), old.size_bytes), ("ep-0005-DB Test", 64));
+        assert!(old.actor_names.is_empty());
+    }
+
+    fn make_episode(title: &str, number: u32) -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new(title);
+        dir.add_cut(Cut::new("c1", 0.0, 60.0));
+        let meta = EpisodeMetadata::new(title, number, 60.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    fn exercise_store(store: &mut dyn EpisodeStore) {
+        let first = store.put(&make_episode("Pilot", 1)).unwrap();
+        store.put(&make_episode("Rival/Arc", 2)).unwrap();
+        assert!(first.size_bytes > 0);
+
+        let loaded = store.get(&first.id).unwrap().unwrap();
+        assert_eq!(loaded.metadata.title, "Pilot");
+        assert!(store.get("ep-9999-Missing").unwrap().is_none());
+
+        let ids: Vec<String> = store.list().unwrap().into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec!["ep-0001-Pilot", "ep-0002-Rival/Arc"]);
+        let found = store
+            .query(&EpisodeQuery::new().with_title("Rival"))
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].episode_number, 2);
+
+        assert!(store.delete(&first.id).unwrap());
+        assert!(!store.delete(&first.id).unwrap());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_memory_store_crud() {
+        let mut store = MemoryEpisodeStore::new();
+        exercise_store(&mut store);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_directory_store_crud() {
+        let dir = std::env::temp_dir().join(format!("alice_anim_store_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut store = DirectoryEpisodeStore::open(&dir).unwrap();
+        exercise_store(&mut store);
+
+        // Reopening sees what was persisted
+        let reopened = DirectoryEpisodeStore::open(&dir).unwrap();
+        assert_eq!(reopened.list().unwrap()[0].id, "ep-0002-Rival/Arc");
+
+        // IDs that differ only in escaped characters keep separate files
+        let mut store = reopened;
+        store.put(&make_episode("Rival_Arc", 2)).unwrap();
+        assert_eq!(store.list().unwrap().len(), 2);
+        let loaded = store.get("ep-0002-Rival/Arc").unwrap().unwrap();
+        assert_eq!(loaded.metadata.title, "Rival/Arc");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_revision_history() {
+        let v1 = make_episode("Pilot", 1);
+        let mut v2 = v1.clone();
+        v2.director.add_cut(Cut::new("c2", 60.0, 90.0));
+        let mut v3 = v2.clone();
+        let (id, _) = v3.director.find_active_cut(70.0).unwrap();
+        v3.director.get_cut_mut(id).unwrap().name = "c2_retake".into();
+        // Retitling keeps the revision chain
+        v3.metadata.title = "Pilot (director's cut)".into();
+
+        let mut history = RevisionHistory::new();
+        let r1 = history
+            .commit("pilot", &v1, None, "storyboard", "First pass")
+            .unwrap();
+        let r2 = history
+            .commit("pilot", &v2, Some(1), "layout", "Add c2")
+            .unwrap();
+        let r3 = history
+            .commit("pilot", &v3, Some(2), "director", "Retake c2")
+            .unwrap();
+        assert_eq!((r1.revision, r2.parent, r3.revision), (1, Some(1), 3));
+        assert_eq!(r3.episode_id, "pilot");
+        assert!(history
+            .commit("pilot", &v3, Some(9), "x", "bad parent")
+            .is_err());
+        assert_eq!(history.revisions(&r1.episode_id).count(), 3);
+        assert_eq!(history.latest(&r1.episode_id).unwrap().author, "director");
+
+        let restored = history.get(&r1.episode_id, 3).unwrap();
+        assert_eq!(restored.director.cut_count(), 2);
+        assert!(restored.director.cuts().any(|(_, c)| c.name == "c2_retake"));
+        assert_eq!(
+            history.get(&r1.episode_id, 1).unwrap().director.cut_count(),
+            1
+        );
+
+        // The retaken cut and the retitled metadata
+        let diff = history.diff(&r1.episode_id, 2, 3).unwrap();
+        assert_eq!(diff.changed_chunks(), 2);
+        assert!(history.diff(&r1.episode_id, 3, 3).unwrap().patch.is_noop());
+
+        // A revision pointing at itself would replay patches forever
+        let saved = bincode::serialize(&history).unwrap();
+        assert!(bincode::deserialize::<RevisionHistory>(&saved).is_ok());
+        history.revisions.get_mut("pilot").unwrap()[2].0.parent = Some(3);
+        assert!(matches!(
+            history.get("pilot", 3),
+            Err(AnimationError::Corrupt(_))
+        ));
+        let looped = bincode::serialize(&history).unwrap();
+        assert!(bincode::deserialize::<RevisionHistory>(&looped).is_err());
     }
 
     #[test]
@@ -155,6 +897,9 @@ mod tests {
             actor_count: 2,
             cut_count: 3,
             created_at: 0,
+            actor_names: vec!["Hero".into(), "Kurotsuki".into()],
+            cut_names: vec!["rooftop_duel".into()],
+            dialogue: vec!["The moon is red tonight.".into()],
         };
 
         let query = EpisodeQuery::new().with_title("Test");
@@ -168,5 +913,47 @@ mod tests {
 
         let query = EpisodeQuery::new().with_title("NotFound");
         assert!(!query.matches(&record));
+
+        let query = EpisodeQuery::new().with_actor(TextMatch::contains("kuro"));
+        assert!(query.matches(&record));
+        let query = EpisodeQuery::new().with_cut(TextMatch::prefix("Kuro"));
+        assert!(!query.matches(&record));
+        let query = EpisodeQuery::new().with_text_in(None, TextMatch::prefix("MOON"));
+        assert!(query.matches(&record));
+    }
+
+    #[test]
+    fn test_search_index() {
+        let mut villain = make_episode("Eclipse", 3);
+        villain
+            .scene_graph
+            .add_actor(Actor::new("Kurotsuki", SdfNode::sphere(1.0)));
+        villain
+            .director
+            .add_dialogue(crate::director::DialogueLine::new(
+                0.0,
+                2.0,
+                "Kurotsuki returns!",
+            ));
+        let records = [
+            EpisodeRecord::from_package(&make_episode("Pilot", 1)),
+            EpisodeRecord::from_package(&villain),
+        ];
+        assert_eq!(records[1].actor_names, vec!["hero", "Kurotsuki"]);
+        assert_eq!(records[1].cut_names, vec!["c1"]);
+
+        let mut index = EpisodeSearchIndex::build(&records);
+        let by_actor = index.search(Some(SearchField::Actor), &TextMatch::prefix("kuro"));
+        assert_eq!(by_actor, vec!["ep-0003-Eclipse"]);
+        let anywhere = index.search(None, &TextMatch::contains("otsu"));
+        assert_eq!(anywhere, vec!["ep-0003-Eclipse"]);
+        let heroes = index.search(Some(SearchField::Actor), &TextMatch::contains("hero"));
+        assert_eq!(heroes.len(), 2);
+        assert!(index
+            .search(Some(SearchField::Cut), &TextMatch::prefix("kuro"))
+            .is_empty());
+
+        index.remove("ep-0003-Eclipse");
+        assert!(index.search(None, &TextMatch::prefix("kuro")).is_empty());
     }
 }
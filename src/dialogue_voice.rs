@@ -0,0 +1,400 @@
+//! Script-to-talking-episode pipeline: synthesize every line of the director's
+//! dialogue track, build lip sync from the result and attach it to the speaking actors.
+
+use std::io;
+
+use alice_sdf::animation::Timeline;
+use alice_voice::ParametricParams;
+
+use crate::director::{CutId, DialogueLine, Director};
+use crate::lip_sync::{sync_voice_to_animation_smoothed, LipSyncTrack, Phoneme, SmoothingConfig};
+use crate::scene::{ActorId, SceneGraph};
+use crate::text_sync::{lip_sync_from_text, text_to_morae, TextTiming};
+
+/// Audio and analysis produced for one dialogue line.
+#[derive(Debug, Clone, Default)]
+pub struct SynthesizedSpeech {
+    /// Mono PCM samples in [-1, 1].
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    /// Per-frame parametric analysis; empty when the synthesizer does not provide it.
+    pub params: Vec<ParametricParams>,
+    /// Seconds per `params` frame.
+    pub frame_duration: f32,
+}
+
+impl SynthesizedSpeech {
+    /// Length of the audio in seconds.
+    pub fn duration(&self) -> f32 {
+        if self.sample_rate == 0 {
+            return 0.0;
+        }
+        self.samples.len() as f32 / self.sample_rate as f32
+    }
+}
+
+/// Text-to-speech backend (ALICE-Voice or any other engine).
+pub trait SpeechSynthesizer {
+    /// Synthesize one line; `line.speaker` selects the voice.
+    fn synthesize(&self, line: &DialogueLine) -> io::Result<SynthesizedSpeech>;
+}
+
+/// Silent placeholder voice timed from mora count, for animatics before casting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoraTimedSilence {
+    pub mora_duration: f32,
+    pub sample_rate: u32,
+}
+
+impl Default for MoraTimedSilence {
+    fn default() -> Self {
+        Self {
+            mora_duration: 0.12,
+            sample_rate: 24_000,
+        }
+    }
+}
+
+impl SpeechSynthesizer for MoraTimedSilence {
+    fn synthesize(&self, line: &DialogueLine) -> io::Result<SynthesizedSpeech> {
+        let seconds = text_to_morae(&line.text).len() as f32 * self.mora_duration;
+        Ok(SynthesizedSpeech {
+            samples: vec![0.0; (seconds * self.sample_rate as f32).round() as usize],
+            sample_rate: self.sample_rate,
+            params: Vec::new(),
+            frame_duration: 0.0,
+        })
+    }
+}
+
+/// Pipeline options.
+#[derive(Debug, Clone)]
+pub struct VoicePipelineConfig {
+    /// Smoothing for formant-driven lip sync.
+    pub smoothing: SmoothingConfig,
+    /// Stretch a line's `end_time` when its audio runs longer than scripted.
+    pub extend_lines: bool,
+    /// Add the speaker to the `active_actors` of the cut the line starts in.
+    pub activate_speakers: bool,
+}
+
+impl Default for VoicePipelineConfig {
+    fn default() -> Self {
+        Self {
+            smoothing: SmoothingConfig::default(),
+            extend_lines: true,
+            activate_speakers: true,
+        }
+    }
+}
+
+/// One voiced dialogue line and where it was attached.
+#[derive(Debug, Clone)]
+pub struct VoicedLine {
+    /// Index into the director's dialogue track.
+    pub line: usize,
+    pub start_time: f32,
+    pub speaker: Option<String>,
+    /// Speaking actor, if the speaker name matched one in the scene.
+    pub actor: Option<ActorId>,
+    /// Cut the line starts in.
+    pub cut: Option<CutId>,
+    pub speech: SynthesizedSpeech,
+    /// Lip sync relative to `start_time`.
+    pub lip_sync: LipSyncTrack,
+}
+
+/// Result of `voice_dialogue`.
+#[derive(Debug, Clone, Default)]
+pub struct DialogueVoicing {
+    pub lines: Vec<VoicedLine>,
+}
+
+impl DialogueVoicing {
+    /// Lines that could not be matched to an actor (no speaker or unknown name).
+    pub fn unassigned(&self) -> impl Iterator<Item = &VoicedLine> {
+        self.lines.iter().filter(|l| l.actor.is_none())
+    }
+
+    /// Lines starting in the given cut.
+    pub fn lines_in_cut(&self, cut: CutId) -> impl Iterator<Item = &VoicedLine> {
+        self.lines.iter().filter(move |l| l.cut == Some(cut))
+    }
+
+    /// Mix every line into one mono track at `sample_rate`, placed at its start time.
+    /// Lines at a different rate are resampled linearly; overlaps are summed and clamped.
+    pub fn mix(&self, sample_rate: u32) -> Vec<f32> {
+        let rate = sample_rate as f32;
+        let mut out: Vec<f32> = Vec::new();
+        for voiced in &self.lines {
+            let speech = &voiced.speech;
+            if speech.sample_rate == 0 || speech.samples.is_empty() {
+                continue;
+            }
+            let offset = (voiced.start_time.max(0.0) * rate).round() as usize;
+            let step = speech.sample_rate as f32 / rate;
+            let len = (speech.duration() * rate).round() as usize;
+            if out.len() < offset + len {
+                out.resize(offset + len, 0.0);
+            }
+            let last = speech.samples.len() - 1;
+            for i in 0..len {
+                let src = i as f32 * step;
+                let i0 = (src as usize).min(last);
+                let i1 = (i0 + 1).min(last);
+                let frac = src - i0 as f32;
+                let s = speech.samples[i0] + (speech.samples[i1] - speech.samples[i0]) * frac;
+                out[offset + i] = (out[offset + i] + s).clamp(-1.0, 1.0);
+            }
+        }
+        out
+    }
+}
+
+/// Lip sync for one synthesized line: formant analysis when available, text timing otherwise.
+fn line_lip_sync(
+    line: &DialogueLine,
+    speech: &SynthesizedSpeech,
+    config: &VoicePipelineConfig,
+) -> LipSyncTrack {
+    let name = line.speaker.clone().unwrap_or_default();
+    if !speech.params.is_empty() && speech.frame_duration > 0.0 {
+        let mut track = sync_voice_to_animation_smoothed(
+            &speech.params,
+            speech.frame_duration,
+            &config.smoothing,
+        );
+        track.name = name;
+        return track;
+    }
+    let duration = speech.duration().max(line.end_time - line.start_time);
+    lip_sync_from_text(name, &line.text, &TextTiming::Total(duration))
+}
+
+/// Replace the mouth tracks of `timeline` with those of `mouth`, keeping every other track.
+fn attach_mouth(timeline: &mut Option<Timeline>, mouth: Timeline) {
+    match timeline {
+        Some(tl) => {
+            tl.tracks
+                .retain(|t| !mouth.tracks.iter().any(|m| m.name == t.name));
+            tl.tracks.extend(mouth.tracks);
+        }
+        None => *timeline = Some(mouth),
+    }
+}
+
+/// Synthesize the whole dialogue track and make the episode talk in one call.
+///
+/// Each speaking actor gets one lip sync timeline covering all of its lines;
+/// mouth tracks already on the actor are replaced, other tracks are kept.
+pub fn voice_dialogue(
+    director: &mut Director,
+    scene: &mut SceneGraph,
+    synthesizer: &dyn SpeechSynthesizer,
+    config: &VoicePipelineConfig,
+) -> io::Result<DialogueVoicing> {
+    let mut voicing = DialogueVoicing::default();
+    for index in 0..director.episode.dialogue.len() {
+        let line = director.episode.dialogue[index].clone();
+        let speech = synthesizer.synthesize(&line).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("dialogue line {index} ({:?}): {e}", line.text),
+            )
+        })?;
+        if config.extend_lines && line.start_time + speech.duration() > line.end_time {
+            director.episode.dialogue[index].end_time = line.start_time + speech.duration();
+        }
+        let lip_sync = line_lip_sync(&line, &speech, config);
+        let actor = line.speaker.as_deref().and_then(|s| scene.find_by_name(s));
+        let cut = director.find_active_cut(line.start_time).map(|(id, _)| id);
+        voicing.lines.push(VoicedLine {
+            line: index,
+            start_time: line.start_time,
+            speaker: line.speaker,
+            actor,
+            cut,
+            speech,
+            lip_sync,
+        });
+    }
+
+    if config.activate_speakers {
+        for voiced in &voicing.lines {
+            let (Some(actor), Some(cut)) = (voiced.actor, voiced.cut) else {
+                continue;
+            };
+            if let Some(cut) = director.get_cut_mut(cut) {
+                if !cut.active_actors.contains(&actor) {
+                    cut.active_actors.push(actor);
+                }
+            }
+        }
+    }
+
+    let mut speakers: Vec<ActorId> = voicing.lines.iter().filter_map(|l| l.actor).collect();
+    speakers.sort_by_key(|id| id.0);
+    speakers.dedup();
+    for id in speakers {
+        let takes: Vec<(&LipSyncTrack, f32)> = voicing
+            .lines
+            .iter()
+            .filter(|l| l.actor == Some(id))
+            .map(|l| (&l.lip_sync, l.start_time))
+            .collect();
+        if let Some(actor) = scene.get_actor_mut(id) {
+            let mut track = LipSyncTrack::concat(actor.name.clone(), &takes);
+            // Closed until the first line, not held open from its first shape
+            if track.phonemes.first().is_some_and(|kf| kf.time > 0.0) {
+                track.add_phoneme(0.0, Phoneme::Closed);
+            }
+            let mouth = track.to_timeline_for(actor);
+            attach_mouth(&mut actor.timeline, mouth);
+        }
+    }
+    Ok(voicing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::Cut;
+    use crate::scene::Actor;
+    use alice_sdf::animation::{Keyframe, Track};
+    use alice_sdf::SdfNode;
+    use alice_voice::Formant;
+
+    fn setup() -> (Director, SceneGraph) {
+        let mut director = Director::new("Talking");
+        director.add_cut(Cut::new("a", 0.0, 3.0));
+        director.add_cut(Cut::new("b", 3.0, 6.0));
+        director.add_dialogue(DialogueLine::new(0.5, 1.0, "こんにちは").with_speaker("Alice"));
+        director.add_dialogue(DialogueLine::new(3.5, 4.5, "はい").with_speaker("Bob"));
+        director.add_dialogue(DialogueLine::new(5.0, 5.5, "あ").with_speaker("Narrator"));
+
+        let mut scene = SceneGraph::new();
+        let mut walk = Timeline::new("walk");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(6.0, 2.0));
+        walk.add_track(x);
+        scene.add_actor(Actor::new("Alice", SdfNode::sphere(1.0)).with_timeline(walk));
+        scene.add_actor(Actor::new("Bob", SdfNode::sphere(1.0)));
+        (director, scene)
+    }
+
+    #[test]
+    fn test_voice_dialogue_attaches_lip_sync() {
+        let (mut director, mut scene) = setup();
+        let synth = MoraTimedSilence::default();
+        let voicing = voice_dialogue(
+            &mut director,
+            &mut scene,
+            &synth,
+            &VoicePipelineConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(voicing.lines.len(), 3);
+        assert_eq!(voicing.unassigned().count(), 1);
+        assert_eq!(voicing.lines_in_cut(CutId(1)).count(), 2);
+
+        // 5 morae at 0.12s overrun the scripted 0.5s
+        let first = &director.episode.dialogue[0];
+        assert!((first.end_time - 1.1).abs() < 1e-3);
+
+        let alice = scene.find_by_name("Alice").unwrap();
+        let bob = scene.find_by_name("Bob").unwrap();
+        let tl = scene.get_actor(alice).unwrap().timeline.as_ref().unwrap();
+        assert!(tl.get_value("translate.x", 3.0).is_some());
+        assert!(tl.get_value("mouth.openness", 0.7).unwrap() > 0.0);
+        assert_eq!(tl.get_value("mouth.openness", 0.0), Some(0.0));
+
+        assert!(director
+            .get_cut(CutId(0))
+            .unwrap()
+            .active_actors
+            .contains(&alice));
+        assert!(director
+            .get_cut(CutId(1))
+            .unwrap()
+            .active_actors
+            .contains(&bob));
+        assert!(!director
+            .get_cut(CutId(0))
+            .unwrap()
+            .active_actors
+            .contains(&bob));
+    }
+
+    struct FormantVoice;
+
+    impl SpeechSynthesizer for FormantVoice {
+        fn synthesize(&self, _line: &DialogueLine) -> io::Result<SynthesizedSpeech> {
+            let vowel_a = ParametricParams {
+                f0: 220.0,
+                energy: 1.0,
+                formants: vec![
+                    Formant {
+                        frequency: 800.0,
+                        bandwidth: 80.0,
+                        amplitude: 1.0,
+                    },
+                    Formant {
+                        frequency: 1200.0,
+                        bandwidth: 90.0,
+                        amplitude: 0.8,
+                    },
+                ],
+            };
+            Ok(SynthesizedSpeech {
+                samples: vec![0.5; 100],
+                sample_rate: 100,
+                params: vec![vowel_a; 10],
+                frame_duration: 0.1,
+            })
+        }
+    }
+
+    #[test]
+    fn test_formant_lip_sync_and_mix() {
+        let (mut director, mut scene) = setup();
+        let config = VoicePipelineConfig {
+            extend_lines: false,
+            ..VoicePipelineConfig::default()
+        };
+        let voicing = voice_dialogue(&mut director, &mut scene, &FormantVoice, &config).unwrap();
+        assert_eq!(voicing.lines[0].lip_sync.phoneme_at(0.5), Phoneme::A);
+        assert_eq!(director.episode.dialogue[0].end_time, 1.0);
+
+        let audio = voicing.mix(50);
+        // Last line starts at 5.0s and lasts 1.0s
+        assert_eq!(audio.len(), 300);
+        assert_eq!(audio[0], 0.0);
+        assert_eq!(audio[30], 0.5);
+        assert_eq!(audio[180], 0.5);
+        // Gap between Bob (ends 4.5s) and the narrator (starts 5.0s)
+        assert_eq!(audio[240], 0.0);
+    }
+
+    struct Broken;
+
+    impl SpeechSynthesizer for Broken {
+        fn synthesize(&self, _line: &DialogueLine) -> io::Result<SynthesizedSpeech> {
+            Err(io::Error::new(io::ErrorKind::NotFound, "no voice"))
+        }
+    }
+
+    #[test]
+    fn test_synthesis_error_names_line() {
+        let (mut director, mut scene) = setup();
+        let err = voice_dialogue(
+            &mut director,
+            &mut scene,
+            &Broken,
+            &VoicePipelineConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err.to_string().contains("dialogue line 0"));
+    }
+}
@@ -1,7 +1,17 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use alice_sdf::SdfNode;
 use serde::{Deserialize, Serialize};
 
-use crate::camera::{CameraState, CameraTrack};
+use crate::camera::{CameraState, CameraTrack, CameraTrackV1};
+use crate::clip::{ClipLibrary, ClipTrack};
+use crate::cycle::TimelineCycle;
+use crate::nla::NlaStack;
+use crate::performance::Performance;
+use crate::rng::EpisodeRng;
 use crate::scene::{ActorId, SceneGraph};
+use crate::units::Units;
 
 /// Unique cut identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -15,11 +25,46 @@ pub struct Cut {
     pub end_time: f32,
     pub camera: CameraTrack,
     pub active_actors: Vec<ActorId>,
+    /// Start from the previous cut's final camera (cut on action): the track then moves
+    /// the camera relative to its own value at local time 0.
+    pub inherit_camera: bool,
+    /// Clip tracks that drive actors over their own timelines during this cut, stacked per
+    /// actor in order.
+    pub clip_tracks: Vec<ClipTrack>,
     /// Precomputed reciprocal of duration (division exorcism).
     rcp_duration: f32,
 }
 
+/// Cut layout of version 1 episodes, before camera inheritance and clip tracks.
+#[derive(Deserialize)]
+pub(crate) struct CutV1 {
+    name: String,
+    start_time: f32,
+    end_time: f32,
+    camera: CameraTrackV1,
+    active_actors: Vec<ActorId>,
+    rcp_duration: f32,
+}
+
+impl From<CutV1> for Cut {
+    fn from(v1: CutV1) -> Self {
+        Self {
+            camera: v1.camera.into(),
+            active_actors: v1.active_actors,
+            rcp_duration: v1.rcp_duration,
+            ..Cut::new(v1.name, v1.start_time, v1.end_time)
+        }
+    }
+}
+
 impl Cut {
+    /// Seed this cut's camera shake from stream `shake/<id>`, unless it already has one.
+    pub(crate) fn seed_shake(&mut self, id: CutId, rng: &EpisodeRng) {
+        if self.camera.shake_seed.is_none() {
+            self.camera.seed_shake(rng, &alloc::format!("shake/{}", id.0));
+        }
+    }
+
     pub fn new(name: impl Into<String>, start: f32, end: f32) -> Self {
         let dur = end - start;
         Self {
@@ -28,6 +73,8 @@ impl Cut {
             end_time: end,
             camera: CameraTrack::default(),
             active_actors: Vec::new(),
+            inherit_camera: false,
+            clip_tracks: Vec::new(),
             rcp_duration: if dur > 0.0 { 1.0 / dur } else { 0.0 },
         }
     }
@@ -50,6 +97,13 @@ impl Cut {
         time >= self.start_time && time < self.end_time
     }
 
+    /// Move this cut by `offset` seconds (duration unchanged).
+    #[inline]
+    pub fn shift_time(&mut self, offset: f32) {
+        self.start_time += offset;
+        self.end_time += offset;
+    }
+
     /// Set camera track.
     pub fn with_camera(mut self, camera: CameraTrack) -> Self {
         self.camera = camera;
@@ -61,6 +115,26 @@ impl Cut {
         self.active_actors = actors;
         self
     }
+
+    /// Continue from the previous cut's final camera state.
+    pub fn with_inherited_camera(mut self) -> Self {
+        self.inherit_camera = true;
+        self
+    }
+
+    /// Stack a clip track on top of the actor's existing ones.
+    pub fn with_clip_track(mut self, track: ClipTrack) -> Self {
+        self.clip_tracks.push(track);
+        self
+    }
+
+    /// Clip tracks driving `actor`, bottom to top, playing clips from `library`.
+    pub fn nla_stack<'a>(&'a self, library: &'a ClipLibrary, actor: ActorId) -> NlaStack<'a> {
+        NlaStack::new(
+            library,
+            self.clip_tracks.iter().filter(|t| t.actor == actor),
+        )
+    }
 }
 
 /// A scene is a named group of sequential cuts.
@@ -79,11 +153,62 @@ impl Scene {
     }
 }
 
+/// One spoken line on the director's dialogue track.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DialogueLine {
+    pub start_time: f32,
+    pub end_time: f32,
+    /// Speaker name as written in the script, if known.
+    pub speaker: Option<String>,
+    pub text: String,
+}
+
+impl DialogueLine {
+    pub fn new(start: f32, end: f32, text: impl Into<String>) -> Self {
+        Self {
+            start_time: start,
+            end_time: end,
+            speaker: None,
+            text: text.into(),
+        }
+    }
+
+    /// Set speaker.
+    pub fn with_speaker(mut self, speaker: impl Into<String>) -> Self {
+        self.speaker = Some(speaker.into());
+        self
+    }
+
+    /// Check if a given time falls within this line.
+    #[inline]
+    pub fn contains_time(&self, time: f32) -> bool {
+        time >= self.start_time && time < self.end_time
+    }
+}
+
 /// An episode is the top-level container: a sequence of scenes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Episode {
     pub name: String,
     pub scenes: Vec<Scene>,
+    /// Dialogue track, sorted by start time.
+    pub dialogue: Vec<DialogueLine>,
+}
+
+/// Episode layout of version 1 episodes, before the dialogue track.
+#[derive(Deserialize)]
+pub(crate) struct EpisodeV1 {
+    name: String,
+    scenes: Vec<Scene>,
+}
+
+impl From<EpisodeV1> for Episode {
+    fn from(v1: EpisodeV1) -> Self {
+        Self {
+            scenes: v1.scenes,
+            ..Episode::new(v1.name)
+        }
+    }
 }
 
 impl Episode {
@@ -91,10 +216,25 @@ impl Episode {
         Self {
             name: name.into(),
             scenes: Vec::new(),
+            dialogue: Vec::new(),
         }
     }
 }
 
+/// How playback time maps onto episode time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PlaybackMode {
+    /// Play through once.
+    #[default]
+    Once,
+    /// Wrap to the start at the end of the last cut.
+    LoopEpisode,
+    /// Loop the scene at this index of `episode.scenes`.
+    LoopScene(usize),
+    /// Play a cut forward then backward, for previewing a move.
+    PingPongCut(CutId),
+}
+
 /// Snapshot of the director's evaluation at a specific time.
 #[derive(Debug, Clone)]
 pub struct DirectorState {
@@ -113,6 +253,28 @@ pub struct Director {
     next_id: u32,
 }
 
+/// Director layout of version 1 episodes.
+#[derive(Deserialize)]
+pub(crate) struct DirectorV1 {
+    episode: EpisodeV1,
+    sorted_cuts: Vec<(CutId, CutV1)>,
+    next_id: u32,
+}
+
+impl From<DirectorV1> for Director {
+    fn from(v1: DirectorV1) -> Self {
+        Self {
+            episode: v1.episode.into(),
+            sorted_cuts: v1
+                .sorted_cuts
+                .into_iter()
+                .map(|(id, cut)| (id, cut.into()))
+                .collect(),
+            next_id: v1.next_id,
+        }
+    }
+}
+
 impl Director {
     pub fn new(episode_name: impl Into<String>) -> Self {
         Self {
@@ -129,7 +291,7 @@ impl Director {
         let start = cut.start_time;
         let pos = self
             .sorted_cuts
-            .binary_search_by(|(_, c)| c.start_time.partial_cmp(&start).unwrap_or(std::cmp::Ordering::Equal))
+            .binary_search_by(|(_, c)| c.start_time.partial_cmp(&start).unwrap_or(core::cmp::Ordering::Equal))
             .unwrap_or_else(|pos| pos);
         self.sorted_cuts.insert(pos, (id, cut));
         id
@@ -145,11 +307,50 @@ impl Director {
         self.sorted_cuts.iter_mut().find(|(cid, _)| *cid == id).map(|(_, c)| c)
     }
 
+    /// Remove a cut by ID, returning it if present.
+    pub fn remove_cut(&mut self, id: CutId) -> Option<Cut> {
+        let pos = self.sorted_cuts.iter().position(|(cid, _)| *cid == id)?;
+        Some(self.sorted_cuts.remove(pos).1)
+    }
+
+    /// Replace a cut in place, keeping its ID and re-sorting if its start time changed.
+    pub fn replace_cut(&mut self, id: CutId, cut: Cut) -> Option<Cut> {
+        let old = self.remove_cut(id)?;
+        let start = cut.start_time;
+        let pos = self.sorted_cuts.partition_point(|(_, c)| c.start_time <= start);
+        self.sorted_cuts.insert(pos, (id, cut));
+        Some(old)
+    }
+
+    /// Seed cut camera shakes from the episode seed, one `shake/<cut id>` stream per cut.
+    /// Cameras that already have a seed (e.g. cuts carried into split parts) keep it.
+    pub fn seed_shake(&mut self, rng: &EpisodeRng) {
+        for (id, cut) in &mut self.sorted_cuts {
+            cut.seed_shake(*id, rng);
+        }
+    }
+
     /// Add a scene to the episode.
     pub fn add_scene(&mut self, scene: Scene) {
         self.episode.scenes.push(scene);
     }
 
+    /// Add a line to the dialogue track, keeping it sorted by start time.
+    pub fn add_dialogue(&mut self, line: DialogueLine) {
+        let dialogue = &mut self.episode.dialogue;
+        let pos = dialogue.partition_point(|l| l.start_time <= line.start_time);
+        dialogue.insert(pos, line);
+    }
+
+    /// Dialogue lines being spoken at a given time (overlapping lines are allowed).
+    pub fn dialogue_at(&self, time: f32) -> impl Iterator<Item = &DialogueLine> {
+        self.episode
+            .dialogue
+            .iter()
+            .take_while(move |l| l.start_time <= time)
+            .filter(move |l| l.contains_time(time))
+    }
+
     /// Find the active cut at a given time. O(log n) binary search.
     pub fn find_active_cut(&self, time: f32) -> Option<(CutId, &Cut)> {
         // Binary search for the last cut whose start_time <= time
@@ -157,9 +358,9 @@ impl Director {
             .sorted_cuts
             .binary_search_by(|(_, c)| {
                 if c.start_time <= time {
-                    std::cmp::Ordering::Less
+                    core::cmp::Ordering::Less
                 } else {
-                    std::cmp::Ordering::Greater
+                    core::cmp::Ordering::Greater
                 }
             })
             .unwrap_or_else(|pos| pos);
@@ -181,6 +382,130 @@ impl Director {
         None
     }
 
+    /// Scene holding the cut active at `time`, with the scene's start (its earliest cut).
+    pub fn active_scene(&self, time: f32) -> Option<(&Scene, f32)> {
+        let (id, _) = self.find_active_cut(time)?;
+        let scene = self.episode.scenes.iter().find(|s| s.cuts.contains(&id))?;
+        let start = scene
+            .cuts
+            .iter()
+            .filter_map(|c| self.get_cut(*c))
+            .map(|c| c.start_time)
+            .fold(f32::INFINITY, f32::min);
+        Some((scene, start))
+    }
+
+    /// Camera of cut `id` at `local_time`, following camera inheritance.
+    pub fn camera_at(&self, id: CutId, local_time: f32) -> Option<CameraState> {
+        let index = self.sorted_cuts.iter().position(|(cid, _)| *cid == id)?;
+        Some(self.camera_at_index(index, local_time))
+    }
+
+    /// State an inheriting cut starts from: the previous cut's final camera. `None` if the
+    /// cut does not inherit or has no predecessor.
+    pub fn inherited_camera(&self, id: CutId) -> Option<CameraState> {
+        let index = self.sorted_cuts.iter().position(|(cid, _)| *cid == id)?;
+        if index == 0 || !self.sorted_cuts[index].1.inherit_camera {
+            return None;
+        }
+        let previous = &self.sorted_cuts[index - 1].1;
+        Some(self.camera_at_index(index - 1, previous.duration()))
+    }
+
+    fn camera_at_index(&self, index: usize, local_time: f32) -> CameraState {
+        // Walk back to the start of the inheritance chain, then carry its end state forward
+        let mut first = index;
+        while first > 0 && self.sorted_cuts[first].1.inherit_camera {
+            first -= 1;
+        }
+        let mut state: Option<CameraState> = None;
+        for (i, (_, cut)) in self.sorted_cuts[first..=index].iter().enumerate() {
+            let time = if first + i == index {
+                local_time
+            } else {
+                cut.duration()
+            };
+            let own = cut.camera.evaluate(time);
+            state = Some(match state {
+                Some(base) => {
+                    let start = cut.camera.evaluate(0.0);
+                    CameraState {
+                        position: base.position + own.position - start.position,
+                        target: base.target + own.target - start.target,
+                        fov: base.fov + own.fov - start.fov,
+                        up: own.up.or(base.up),
+                    }
+                }
+                None => own,
+            });
+        }
+        state.unwrap_or_default()
+    }
+
+    /// Episode time range `mode` plays over; `None` for `Once`, or when the scene or cut
+    /// does not exist or is empty.
+    pub fn playback_range(&self, mode: PlaybackMode) -> Option<(f32, f32)> {
+        let (start, end) = match mode {
+            PlaybackMode::Once => return None,
+            PlaybackMode::LoopEpisode => (0.0, self.duration()),
+            PlaybackMode::LoopScene(index) => {
+                let scene = self.episode.scenes.get(index)?;
+                scene
+                    .cuts
+                    .iter()
+                    .filter_map(|c| self.get_cut(*c))
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(s, e), c| {
+                        (s.min(c.start_time), e.max(c.end_time))
+                    })
+            }
+            PlaybackMode::PingPongCut(id) => {
+                let cut = self.get_cut(id)?;
+                (cut.start_time, cut.end_time)
+            }
+        };
+        (end > start).then_some((start, end))
+    }
+
+    /// Episode time shown at playhead `time` under `mode`: wrapped (or bounced) into the
+    /// mode's range, always before its end so the last cut stays active.
+    pub fn playback_time(&self, mode: PlaybackMode, time: f32) -> f32 {
+        let Some((start, end)) = self.playback_range(mode) else {
+            return time;
+        };
+        let cycle = match mode {
+            PlaybackMode::PingPongCut(_) => TimelineCycle::ping_pong(),
+            _ => TimelineCycle::repeat(),
+        };
+        let wrapped = start + cycle.map_time(time - start, end - start);
+        wrapped.clamp(start, just_before(end))
+    }
+
+    /// [`evaluate`](Self::evaluate) at the episode time `mode` shows at playhead `time`.
+    pub fn evaluate_playback(
+        &self,
+        scene_graph: &SceneGraph,
+        mode: PlaybackMode,
+        time: f32,
+    ) -> DirectorState {
+        self.evaluate(scene_graph, self.playback_time(mode, time))
+    }
+
+    /// Re-express every cut's camera path authored under `from` in `to`'s convention,
+    /// including the focus distance and shake amplitude lengths.
+    pub fn convert_units(&mut self, from: &Units, to: &Units) {
+        let scale = to.scale_from(from);
+        for (_, cut) in &mut self.sorted_cuts {
+            let camera = &mut cut.camera;
+            // Key unkeyed channels first so their fallback values convert too
+            camera.offset(glam::Vec3::ZERO, glam::Vec3::ZERO, 0.0);
+            to.convert_tracks(&mut camera.position_timeline, "position", from);
+            to.convert_tracks(&mut camera.target_timeline, "target", from);
+            camera.rotate_orientation(to.rotation_from(from));
+            camera.focus_distance *= scale;
+            camera.shake_amplitude *= scale;
+        }
+    }
+
     /// Total duration across all cuts.
     #[inline]
     pub fn duration(&self) -> f32 {
@@ -195,7 +520,11 @@ impl Director {
         match self.find_active_cut(time) {
             Some((cut_id, cut)) => {
                 let local_time = time - cut.start_time;
-                let camera_state = cut.camera.evaluate(local_time);
+                let camera_state = if cut.inherit_camera {
+                    self.camera_at(cut_id, local_time).unwrap_or_default()
+                } else {
+                    cut.camera.evaluate(local_time)
+                };
                 DirectorState {
                     time,
                     active_cut: Some(cut_id),
@@ -210,16 +539,59 @@ impl Director {
         }
     }
 
+    /// Evaluate the scene at `time` with the active cut's clip tracks playing.
+    pub fn evaluate_scene(&self, scene_graph: &SceneGraph, time: f32) -> SdfNode {
+        Performance::new(scene_graph, self).evaluate_scene(time)
+    }
+
+    /// Per-actor counterpart of [`evaluate_scene`](Self::evaluate_scene).
+    pub fn evaluate_actors(&self, scene_graph: &SceneGraph, time: f32) -> Vec<(ActorId, SdfNode)> {
+        Performance::new(scene_graph, self).evaluate_actors(time)
+    }
+
     /// Number of cuts.
     #[inline]
     pub fn cut_count(&self) -> usize {
         self.sorted_cuts.len()
     }
+
+    /// Iterate cuts in start-time order.
+    pub fn cuts(&self) -> impl Iterator<Item = (CutId, &Cut)> {
+        self.sorted_cuts.iter().map(|(id, c)| (*id, c))
+    }
+
+    /// Next cut ID that `add_cut` will hand out.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub(crate) fn next_cut_id(&self) -> u32 {
+        self.next_id
+    }
+
+    /// Rebuild a director from already-sorted cuts (used by chunked/patch loading).
+    #[cfg(feature = "std")]
+    pub(crate) fn from_sorted_cuts(episode: Episode, sorted_cuts: Vec<(CutId, Cut)>, next_id: u32) -> Self {
+        Self {
+            episode,
+            sorted_cuts,
+            next_id,
+        }
+    }
+}
+
+/// Largest float below `x` (for exclusive range ends).
+#[inline]
+fn just_before(x: f32) -> f32 {
+    match x {
+        x if x > 0.0 => f32::from_bits(x.to_bits() - 1),
+        x if x < 0.0 => f32::from_bits(x.to_bits() + 1),
+        _ => -f32::MIN_POSITIVE,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::scene::Actor;
 
     #[test]
     fn test_cut_timing() {
@@ -252,4 +624,121 @@ mod tests {
         assert!(state.active_cut.is_some());
         assert_eq!(state.time, 2.0);
     }
+
+    #[test]
+    fn test_camera_inherits_across_cuts() {
+        use glam::Vec3;
+        let mut dir = Director::new("Chase");
+        let mut run = Cut::new("run", 0.0, 2.0);
+        run.camera.clear_keyframes();
+        run.camera
+            .add_keyframe(0.0, Vec3::new(0.0, 1.0, 5.0), Vec3::ZERO, 0.8);
+        run.camera
+            .add_keyframe(2.0, Vec3::new(4.0, 1.0, 5.0), Vec3::X * 4.0, 0.8);
+        dir.add_cut(run);
+        // Continues the pan: its own track only pushes in along -Z
+        let mut jump = Cut::new("jump", 2.0, 3.0).with_inherited_camera();
+        jump.camera.clear_keyframes();
+        jump.camera.add_keyframe(0.0, Vec3::ZERO, Vec3::ZERO, 0.0);
+        jump.camera
+            .add_keyframe(1.0, Vec3::new(0.0, 0.0, -1.0), Vec3::ZERO, 0.0);
+        let jump = dir.add_cut(jump);
+        let land = dir.add_cut(Cut::new("land", 3.0, 4.0).with_inherited_camera());
+        let sg = SceneGraph::new();
+
+        let start = dir.evaluate(&sg, 2.0).camera_state;
+        assert!((start.position - Vec3::new(4.0, 1.0, 5.0)).length() < 1e-5);
+        assert_eq!(start.fov, 0.8);
+        let mid = dir.evaluate(&sg, 2.5).camera_state;
+        assert!((mid.position - Vec3::new(4.0, 1.0, 4.5)).length() < 1e-5);
+        // Chains through `jump` into a static inheriting cut
+        let held = dir.evaluate(&sg, 3.5).camera_state;
+        assert!((held.position - Vec3::new(4.0, 1.0, 4.0)).length() < 1e-5);
+        assert!((held.target - Vec3::X * 4.0).length() < 1e-5);
+        assert!(dir.inherited_camera(land).is_some());
+
+        // Without inheritance the cut starts from its own track
+        dir.get_cut_mut(jump).unwrap().inherit_camera = false;
+        assert_eq!(dir.evaluate(&sg, 2.0).camera_state.position, Vec3::ZERO);
+    }
+
+    #[test]
+    fn test_convert_units_scales_camera_lengths() {
+        use crate::units::UpAxis;
+        use glam::{Quat, Vec3};
+        let mut crane = Cut::new("crane", 0.0, 2.0);
+        crane.camera.clear_keyframes();
+        crane.camera.add_orientation_keyframe(
+            0.0,
+            Vec3::new(0.0, 100.0, 500.0),
+            Quat::IDENTITY,
+            0.8,
+        );
+        crane.camera.focus_distance = 500.0;
+        crane.camera.shake_amplitude = 20.0;
+        crane.camera.shake_frequency = 6.0;
+        let mut dir = Director::new("Crane");
+        dir.add_cut(crane);
+
+        dir.convert_units(&Units::centimeters(UpAxis::Y), &Units::meters(UpAxis::Z));
+        let camera = &dir.cuts().next().unwrap().1.camera;
+        assert!((camera.focus_distance - 5.0).abs() < 1e-4);
+        assert!((camera.shake_amplitude - 0.2).abs() < 1e-6);
+        // Y-up (0, 1, 5) m is Z-up (0, -5, 1), give or take the shake
+        let state = camera.evaluate(0.5);
+        assert!((state.position - Vec3::new(0.0, -5.0, 1.0)).length() < 0.25);
+    }
+
+    #[test]
+    fn test_playback_modes_wrap_time() {
+        let mut dir = Director::new("Loops");
+        let intro = dir.add_cut(Cut::new("intro", 0.0, 2.0));
+        let fight = dir.add_cut(Cut::new("fight", 2.0, 5.0));
+        let mut scene = Scene::new("fight");
+        scene.cuts.push(fight);
+        dir.add_scene(scene);
+        let sg = SceneGraph::new();
+
+        assert_eq!(dir.playback_time(PlaybackMode::Once, 7.0), 7.0);
+        assert_eq!(dir.playback_time(PlaybackMode::LoopEpisode, 6.0), 1.0);
+        // The scene loops over 2..5, even from a playhead that started at 0
+        let scene = PlaybackMode::LoopScene(0);
+        assert_eq!(dir.playback_time(scene, 5.5), 2.5);
+        assert_eq!(dir.playback_time(scene, 0.5), 3.5);
+        assert_eq!(dir.playback_range(PlaybackMode::LoopScene(3)), None);
+        // Ping-pong stays inside the cut, including on the turn
+        let pong = PlaybackMode::PingPongCut(intro);
+        assert_eq!(dir.playback_time(pong, 3.5), 0.5);
+        let turn = dir.evaluate_playback(&sg, pong, 2.0);
+        assert!(turn.time < 2.0);
+        assert_eq!(turn.active_cut, Some(intro));
+    }
+
+    #[test]
+    fn test_clip_track_drives_actor_in_cut() {
+        use crate::clip::{AnimationClip, ClipInstance};
+        use alice_sdf::animation::{Keyframe, Timeline, Track};
+        use glam::Vec3;
+        let mut hop = Timeline::new("hop");
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.0));
+        y.add_keyframe(Keyframe::new(1.0, 2.0));
+        hop.add_track(y);
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(0.25)));
+        let hop = AnimationClip::new("hop", hop);
+        let track = ClipTrack::new(hero).with_clip(ClipInstance::new(&hop, 0.5));
+        sg.clips.insert(hop);
+        let mut dir = Director::new("Clips");
+        dir.add_cut(Cut::new("still", 0.0, 2.0));
+        dir.add_cut(Cut::new("hop", 2.0, 4.0).with_clip_track(track));
+
+        let at = |time: f32, p: Vec3| alice_sdf::eval(&dir.evaluate_scene(&sg, time), p);
+        // Cut-local 1.0 is halfway through the hop
+        assert!(at(3.0, Vec3::Y) < 0.0);
+        assert!(at(1.0, Vec3::Y) > 0.0);
+        assert!(at(1.0, Vec3::ZERO) < 0.0);
+        assert!(sg.get_actor(hero).unwrap().timeline.is_none());
+        assert_eq!(dir.evaluate_actors(&sg, 3.0).len(), 1);
+    }
 }
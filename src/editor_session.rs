@@ -0,0 +1,277 @@
+//! Editor session state, kept apart from the episode.
+//!
+//! An [`EditorSession`] records where the artist left off: the playhead, the selected cut
+//! and actors, an open loop region and each panel's view settings. It is saved next to
+//! the episode rather than inside it, so reopening a tool restores the workspace without
+//! the episode file changing every time someone scrolls a timeline.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use crate::director::{CutId, PlaybackMode};
+use crate::episode::EpisodePackage;
+use crate::error::AnimationError;
+use crate::playback::Player;
+use crate::scene::ActorId;
+
+/// Session file magic bytes.
+const SESSION_MAGIC: [u8; 4] = *b"ASES";
+/// Session format version.
+const SESSION_VERSION: u16 = 1;
+
+/// View settings of one editor panel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelView {
+    pub visible: bool,
+    /// Magnification (1 = fit).
+    pub zoom: f32,
+    /// Scroll offset in the panel's own units (seconds, pixels, ...).
+    pub scroll: Vec2,
+    /// Tool-specific toggles, e.g. `"onion_skin" → "on"`.
+    pub options: BTreeMap<String, String>,
+}
+
+impl Default for PanelView {
+    fn default() -> Self {
+        Self {
+            visible: true,
+            zoom: 1.0,
+            scroll: Vec2::ZERO,
+            options: BTreeMap::new(),
+        }
+    }
+}
+
+impl PanelView {
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    pub fn with_scroll(mut self, scroll: Vec2) -> Self {
+        self.scroll = scroll;
+        self
+    }
+
+    pub fn with_option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.insert(key.into(), value.into());
+        self
+    }
+
+    pub fn hidden(mut self) -> Self {
+        self.visible = false;
+        self
+    }
+}
+
+/// Where the artist left off in an episode.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EditorSession {
+    /// Playhead, in episode seconds.
+    pub time: f32,
+    pub selected_cut: Option<CutId>,
+    pub selected_actors: Vec<ActorId>,
+    /// `[start, end)` region open for looped review.
+    pub loop_region: Option<(f32, f32)>,
+    pub playback_mode: PlaybackMode,
+    /// Panel views by panel name (`"timeline"`, `"viewport"`, ...).
+    pub panels: BTreeMap<String, PanelView>,
+}
+
+impl EditorSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_time(mut self, time: f32) -> Self {
+        self.time = time;
+        self
+    }
+
+    pub fn with_cut(mut self, cut: CutId) -> Self {
+        self.selected_cut = Some(cut);
+        self
+    }
+
+    pub fn with_actor(mut self, actor: ActorId) -> Self {
+        if !self.selected_actors.contains(&actor) {
+            self.selected_actors.push(actor);
+        }
+        self
+    }
+
+    /// Open a loop region; the ends are put in order.
+    pub fn with_loop_region(mut self, start: f32, end: f32) -> Self {
+        self.loop_region = Some((start.min(end), start.max(end)));
+        self
+    }
+
+    pub fn with_panel(mut self, name: impl Into<String>, view: PanelView) -> Self {
+        self.panels.insert(name.into(), view);
+        self
+    }
+
+    /// View settings for `name`, created with defaults on first use.
+    pub fn panel_mut(&mut self, name: &str) -> &mut PanelView {
+        self.panels.entry(name.to_string()).or_default()
+    }
+
+    /// Playhead and playback mode of a running player.
+    pub fn capture(&mut self, player: &Player) {
+        self.time = player.clock().time();
+        self.playback_mode = player.mode();
+    }
+
+    /// Put a player back at the session's playhead and playback mode.
+    pub fn restore(&self, player: &mut Player) {
+        player.set_mode(self.playback_mode);
+        let frame = (self.time.max(0.0) * player.clock().fps()).round() as u32;
+        player.clock_mut().seek(frame);
+    }
+
+    /// Drop selections the episode no longer has and keep the playhead and loop region
+    /// inside it, e.g. after the episode was edited elsewhere. Returns true if anything
+    /// changed.
+    pub fn reconcile(&mut self, episode: &EpisodePackage) -> bool {
+        let before = self.clone();
+        let director = &episode.director;
+        let duration = director.duration().max(0.0);
+        if self
+            .selected_cut
+            .is_some_and(|id| director.get_cut(id).is_none())
+        {
+            self.selected_cut = None;
+        }
+        self.selected_actors
+            .retain(|&id| episode.scene_graph.get_actor(id).is_some());
+        self.time = self.time.clamp(0.0, duration);
+        self.loop_region = self
+            .loop_region
+            .map(|(start, end)| (start.clamp(0.0, duration), end.clamp(0.0, duration)))
+            .filter(|(start, end)| end > start);
+        let mode_valid = match self.playback_mode {
+            PlaybackMode::LoopScene(index) => index < director.episode.scenes.len(),
+            PlaybackMode::PingPongCut(id) => director.get_cut(id).is_some(),
+            _ => true,
+        };
+        if !mode_valid {
+            self.playback_mode = PlaybackMode::Once;
+        }
+        *self != before
+    }
+
+    /// Write the session (magic, version, then bincode body).
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> crate::error::Result<()> {
+        let body = bincode::serialize(self)?;
+        writer.write_all(&SESSION_MAGIC)?;
+        writer.write_all(&SESSION_VERSION.to_le_bytes())?;
+        writer.write_all(&0u16.to_le_bytes())?;
+        writer.write_all(&body)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Read a session written by [`EditorSession::write_to`].
+    pub fn read_from<R: Read>(reader: &mut R) -> crate::error::Result<Self> {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        if header[0..4] != SESSION_MAGIC {
+            return Err(AnimationError::BadMagic { expected: "ASES" });
+        }
+        let version = u16::from_le_bytes([header[4], header[5]]);
+        if version != SESSION_VERSION {
+            return Err(AnimationError::UnsupportedVersion {
+                format: "ASES",
+                version,
+            });
+        }
+        let mut body = Vec::new();
+        reader.read_to_end(&mut body)?;
+        Ok(bincode::deserialize(&body)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> crate::error::Result<()> {
+        let mut file = std::fs::File::create(path)?;
+        self.write_to(&mut file)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let mut file = std::fs::File::open(path)?;
+        Self::read_from(&mut file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::EpisodeMetadata;
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    fn episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("ep01");
+        dir.add_cut(Cut::new("wide", 0.0, 4.0));
+        let meta = EpisodeMetadata::new("ep01", 1, 4.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let session = EditorSession::new()
+            .with_time(2.5)
+            .with_cut(CutId(0))
+            .with_actor(ActorId(0))
+            .with_actor(ActorId(0))
+            .with_loop_region(3.0, 1.0)
+            .with_panel("timeline", PanelView::default().with_zoom(4.0))
+            .with_panel(
+                "viewport",
+                PanelView::default().with_option("onion_skin", "on"),
+            );
+        assert_eq!(session.selected_actors, vec![ActorId(0)]);
+        assert_eq!(session.loop_region, Some((1.0, 3.0)));
+
+        let mut bytes = Vec::new();
+        session.write_to(&mut bytes).unwrap();
+        assert_eq!(&bytes[0..4], b"ASES");
+        let loaded = EditorSession::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded, session);
+        assert!(EditorSession::read_from(&mut &bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_and_restore() {
+        let episode = episode();
+        let mut session = EditorSession::new()
+            .with_time(9.0)
+            .with_cut(CutId(7))
+            .with_actor(ActorId(0))
+            .with_actor(ActorId(3))
+            .with_loop_region(3.0, 6.0);
+        session.playback_mode = PlaybackMode::PingPongCut(CutId(7));
+        assert!(session.reconcile(&episode));
+        assert_eq!(session.time, 4.0);
+        assert_eq!(session.selected_cut, None);
+        assert_eq!(session.selected_actors, vec![ActorId(0)]);
+        assert_eq!(session.loop_region, Some((3.0, 4.0)));
+        assert_eq!(session.playback_mode, PlaybackMode::Once);
+        assert!(!session.reconcile(&episode));
+
+        let mut player = Player::new(&episode);
+        session.time = 1.5;
+        session.playback_mode = PlaybackMode::LoopEpisode;
+        session.restore(&mut player);
+        assert_eq!(player.mode(), PlaybackMode::LoopEpisode);
+        let mut captured = EditorSession::new();
+        captured.capture(&player);
+        assert!((captured.time - 1.5).abs() < 1e-4);
+        assert_eq!(captured.playback_mode, PlaybackMode::LoopEpisode);
+    }
+}
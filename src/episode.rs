@@ -2,34 +2,26 @@ use std::io::{Read, Write};
 
 use serde::{Deserialize, Serialize};
 
+use crate::anim::{EpisodeCore, EPISODE_V1_VERSION, EPISODE_VERSION};
 use crate::director::Director;
+use crate::error::AnimationError;
 use crate::npr::AnimeShading;
+use crate::overlay::Overlay;
+use crate::render::RenderSettings;
+use crate::rng::EpisodeRng;
 use crate::scene::SceneGraph;
+use crate::units::Units;
 
-/// Binary format magic bytes.
-const EPISODE_MAGIC: [u8; 4] = *b"ANIM";
-/// Format version.
-const EPISODE_VERSION: u16 = 1;
+pub(crate) use crate::anim::{frame_flags, header_version, EPISODE_MAGIC};
+pub use crate::anim::{FLAG_ENCRYPTED, FLAG_NAMED_REFS, FLAG_SIGNED};
+pub use crate::metadata::{
+    EpisodeMetadata, MetadataValue, METERS_PER_UNIT_EXTENSION, SEED_EXTENSION, UP_AXIS_EXTENSION,
+};
 
-/// Episode metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EpisodeMetadata {
-    pub title: String,
-    pub episode_number: u32,
-    pub duration_seconds: f32,
-    pub resolution: (u32, u32),
-}
-
-impl EpisodeMetadata {
-    pub fn new(title: impl Into<String>, episode_number: u32, duration: f32) -> Self {
-        Self {
-            title: title.into(),
-            episode_number,
-            duration_seconds: duration,
-            resolution: (1920, 1080),
-        }
-    }
-}
+/// Chunked format version (per-chunk CRC, see `chunk::serialize_episode_chunked`).
+pub const CHUNKED_VERSION: u16 = 2;
+/// Streamed format version (cut-by-cut segments + index, see `stream::EpisodeStreamWriter`).
+pub const STREAMED_VERSION: u16 = 3;
 
 /// Complete episode package: all data needed to render an episode.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +30,16 @@ pub struct EpisodePackage {
     pub scene_graph: SceneGraph,
     pub director: Director,
     pub shading: AnimeShading,
+    /// Quality settings this episode is rendered with; `None` uses renderer defaults.
+    pub render_settings: Option<RenderSettings>,
+    /// Title cards, eyecatches and other 2D elements composited over rendered frames.
+    pub overlays: Vec<Overlay>,
+}
+
+impl From<EpisodeCore> for EpisodePackage {
+    fn from(core: EpisodeCore) -> Self {
+        EpisodePackage::new(core.metadata, core.scene_graph, core.director, core.shading)
+    }
 }
 
 impl EpisodePackage {
@@ -47,12 +49,41 @@ impl EpisodePackage {
         director: Director,
         shading: AnimeShading,
     ) -> Self {
-        Self {
+        let mut episode = Self {
             metadata,
             scene_graph,
             director,
             shading,
-        }
+            render_settings: None,
+            overlays: Vec::new(),
+        };
+        episode.seed_shake();
+        episode
+    }
+
+    /// Store render settings with the episode.
+    pub fn with_render_settings(mut self, settings: RenderSettings) -> Self {
+        self.render_settings = Some(settings);
+        self
+    }
+
+    /// Add a 2D overlay.
+    pub fn with_overlay(mut self, overlay: Overlay) -> Self {
+        self.overlays.push(overlay);
+        self
+    }
+
+    /// Labelled random streams derived from the metadata seed.
+    #[inline]
+    pub fn rng(&self) -> EpisodeRng {
+        EpisodeRng::new(self.metadata.seed())
+    }
+
+    /// Seed camera shakes that have no seed yet from the metadata seed. Construction and
+    /// the loaders do this; call it again after adding cuts to a built episode.
+    pub fn seed_shake(&mut self) {
+        let rng = self.rng();
+        self.director.seed_shake(&rng);
     }
 
     /// Estimate serialized size in bytes (rough).
@@ -62,87 +93,320 @@ impl EpisodePackage {
         let cuts = self.director.cut_count();
         256 + actors * 512 + cuts * 256
     }
+
+    /// Convert the scene and cameras from the stored units to `units` and record them.
+    pub fn convert_units(&mut self, units: Units) {
+        let from = self.metadata.units();
+        self.scene_graph.convert_units(&from, &units);
+        self.director.convert_units(&from, &units);
+        self.metadata.set_units(units);
+    }
+
+    /// Structural problems that would make the episode render incorrectly.
+    ///
+    /// Checks cut ranges and overlaps, actor references from cuts and parents, scene cut
+    /// references, and dialogue timing against the metadata duration.
+    pub fn validation_issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let sg = &self.scene_graph;
+        let duration = self.metadata.duration_seconds;
+
+        if !(duration.is_finite() && duration > 0.0) {
+            issues.push(format!("metadata duration {} is not positive", duration));
+        }
+
+        for id in sg.actor_ids() {
+            let Some(actor) = sg.get_actor(id) else {
+                continue;
+            };
+            if let Some(parent) = actor.parent {
+                if sg.get_actor(parent).is_none() {
+                    issues.push(format!(
+                        "actor '{}' has missing parent {}",
+                        actor.name, parent.0
+                    ));
+                } else if parent == id {
+                    issues.push(format!("actor '{}' is its own parent", actor.name));
+                }
+            }
+        }
+
+        let mut previous: Option<&crate::director::Cut> = None;
+        for (_, cut) in self.director.cuts() {
+            if cut.end_time <= cut.start_time || cut.end_time.is_nan() {
+                issues.push(format!(
+                    "cut '{}' has empty range {}..{}",
+                    cut.name, cut.start_time, cut.end_time
+                ));
+            }
+            if let Some(prev) = previous {
+                if cut.start_time < prev.end_time {
+                    issues.push(format!(
+                        "cut '{}' overlaps '{}' ({} < {})",
+                        cut.name, prev.name, cut.start_time, prev.end_time
+                    ));
+                }
+            }
+            if cut.end_time > duration + 1e-3 {
+                issues.push(format!(
+                    "cut '{}' ends at {} past episode duration {}",
+                    cut.name, cut.end_time, duration
+                ));
+            }
+            for actor in &cut.active_actors {
+                if sg.get_actor(*actor).is_none() {
+                    issues.push(format!(
+                        "cut '{}' references missing actor {}",
+                        cut.name, actor.0
+                    ));
+                }
+            }
+            for track in &cut.clip_tracks {
+                if sg.get_actor(track.actor).is_none() {
+                    issues.push(format!(
+                        "cut '{}' has a clip track on missing actor {}",
+                        cut.name, track.actor.0
+                    ));
+                }
+                for instance in &track.instances {
+                    if sg.clips.get(&instance.clip).is_none() {
+                        issues.push(format!(
+                            "cut '{}' places missing clip '{}'",
+                            cut.name, instance.clip
+                        ));
+                    }
+                }
+            }
+            previous = Some(cut);
+        }
+
+        for scene in &self.director.episode.scenes {
+            for cut in &scene.cuts {
+                if self.director.get_cut(*cut).is_none() {
+                    issues.push(format!(
+                        "scene '{}' references missing cut {}",
+                        scene.name, cut.0
+                    ));
+                }
+            }
+        }
+
+        for line in &self.director.episode.dialogue {
+            if line.end_time < line.start_time {
+                issues.push(format!("dialogue '{}' ends before it starts", line.text));
+            }
+            if line.start_time < 0.0 || line.end_time > duration + 1e-3 {
+                issues.push(format!(
+                    "dialogue '{}' ({}..{}) is outside the episode",
+                    line.text, line.start_time, line.end_time
+                ));
+            }
+        }
+
+        for overlay in &self.overlays {
+            if overlay.end <= overlay.start || overlay.end.is_nan() {
+                issues.push(format!(
+                    "overlay '{}' has empty range {}..{}",
+                    overlay.name, overlay.start, overlay.end
+                ));
+            }
+        }
+
+        issues
+    }
+
+    /// Fail with `AnimationError::Validation` if [`Self::validation_issues`] finds anything.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        let issues = self.validation_issues();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(AnimationError::Validation(issues))
+        }
+    }
 }
 
-/// Serialize an episode package to a writer.
-///
-/// Binary format:
-/// `[Magic "ANIM" 4B][Version 2B][Flags 2B][Size 4B][CRC32 4B][Bincode Body]`
-pub fn serialize_episode<W: Write>(episode: &EpisodePackage, writer: &mut W) -> std::io::Result<usize> {
-    // Serialize body first to get size and CRC
-    let body = bincode::serialize(episode)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+/// Header bytes 0..8 (magic, version, flags), known before the body is encoded.
+#[inline]
+pub(crate) fn frame_prefix(flags: u16) -> [u8; 8] {
+    format_prefix(&EPISODE_MAGIC, EPISODE_VERSION, flags)
+}
+
+/// Header bytes 0..8 of another format framed like ANIM (e.g. patches).
+pub(crate) fn format_prefix(magic: &[u8; 4], version: u16, flags: u16) -> [u8; 8] {
+    let mut prefix = [0u8; 8];
+    prefix[0..4].copy_from_slice(magic);
+    prefix[4..6].copy_from_slice(&version.to_le_bytes());
+    prefix[6..8].copy_from_slice(&flags.to_le_bytes());
+    prefix
+}
+
+/// Build the 16-byte ANIM header for `body`.
+#[inline]
+pub(crate) fn frame_header(flags: u16, body: &[u8]) -> [u8; 16] {
+    seal_header(frame_prefix(flags), body)
+}
 
-    let crc = crc32fast::hash(&body);
+/// Complete a header `prefix` with the size and CRC of `body`.
+pub(crate) fn seal_header(prefix: [u8; 8], body: &[u8]) -> [u8; 16] {
+    let crc = crc32fast::hash(body);
     let size = body.len() as u32;
-    let flags: u16 = 0;
 
-    // Write header
-    writer.write_all(&EPISODE_MAGIC)?;
-    writer.write_all(&EPISODE_VERSION.to_le_bytes())?;
-    writer.write_all(&flags.to_le_bytes())?;
-    writer.write_all(&size.to_le_bytes())?;
-    writer.write_all(&crc.to_le_bytes())?;
+    let mut header = [0u8; 16];
+    header[0..8].copy_from_slice(&prefix);
+    header[8..12].copy_from_slice(&size.to_le_bytes());
+    header[12..16].copy_from_slice(&crc.to_le_bytes());
+    header
+}
 
-    // Write body
-    writer.write_all(&body)?;
+/// Write an ANIM header + body. Returns the header bytes.
+pub(crate) fn write_frame<W: Write>(
+    writer: &mut W,
+    flags: u16,
+    body: &[u8],
+) -> std::io::Result<[u8; 16]> {
+    let header = frame_header(flags, body);
+    writer.write_all(&header)?;
+    writer.write_all(body)?;
+    Ok(header)
+}
 
-    Ok(16 + body.len())
+/// Read the 16-byte header and validate magic bytes.
+#[inline]
+pub(crate) fn read_header<R: Read>(reader: &mut R) -> crate::error::Result<[u8; 16]> {
+    read_format_header(reader, &EPISODE_MAGIC)
 }
 
-/// Deserialize an episode package from a reader.
-pub fn deserialize_episode<R: Read>(reader: &mut R) -> std::io::Result<EpisodePackage> {
-    // Read header (16 bytes)
+/// Read the 16-byte header of a format framed like ANIM and validate its `magic`.
+pub(crate) fn read_format_header<R: Read>(
+    reader: &mut R,
+    magic: &'static [u8; 4],
+) -> crate::error::Result<[u8; 16]> {
     let mut header = [0u8; 16];
     reader.read_exact(&mut header)?;
 
     // Validate magic
-    if &header[0..4] != &EPISODE_MAGIC {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            "Invalid magic bytes: expected ANIM",
-        ));
+    if header[0..4] != *magic {
+        return Err(AnimationError::BadMagic {
+            expected: std::str::from_utf8(magic).unwrap_or_default(),
+        });
     }
+    Ok(header)
+}
 
-    // Parse header fields
-    let version = u16::from_le_bytes([header[4], header[5]]);
+/// Read and validate a single body following `header` (version, CRC).
+pub(crate) fn read_frame_body<R: Read>(
+    header: &[u8; 16],
+    reader: &mut R,
+) -> crate::error::Result<Vec<u8>> {
+    let version = header_version(header);
     if version != EPISODE_VERSION {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!("Unsupported version: {}", version),
-        ));
+        return Err(AnimationError::UnsupportedVersion {
+            format: "ANIM",
+            version,
+        });
     }
+    read_checked_body(header, reader, "ANIM body")
+}
+
+/// Read `size` bytes. The buffer grows as data arrives, so a damaged size field cannot
+/// allocate more than the reader holds; a short read is `Truncated(what)`.
+pub(crate) fn read_sized<R: Read>(
+    reader: &mut R,
+    size: usize,
+    what: &'static str,
+) -> crate::error::Result<Vec<u8>> {
+    let mut data = Vec::with_capacity(size.min(64 * 1024));
+    reader.take(size as u64).read_to_end(&mut data)?;
+    if data.len() < size {
+        return Err(AnimationError::Truncated(what));
+    }
+    Ok(data)
+}
 
-    let _flags = u16::from_le_bytes([header[6], header[7]]);
+/// Read the body following `header` and check it against the header CRC; a short body
+/// is `Truncated(what)`.
+pub(crate) fn read_checked_body<R: Read>(
+    header: &[u8; 16],
+    reader: &mut R,
+    what: &'static str,
+) -> crate::error::Result<Vec<u8>> {
     let size = u32::from_le_bytes([header[8], header[9], header[10], header[11]]) as usize;
     let expected_crc = u32::from_le_bytes([header[12], header[13], header[14], header[15]]);
 
     // Read body
-    let mut body = vec![0u8; size];
-    reader.read_exact(&mut body)?;
+    let body = read_sized(reader, size, what)?;
 
     // Validate CRC
     let actual_crc = crc32fast::hash(&body);
     if actual_crc != expected_crc {
-        return Err(std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            format!(
-                "CRC mismatch: expected {:#010x}, got {:#010x}",
-                expected_crc, actual_crc
-            ),
-        ));
+        return Err(AnimationError::CrcMismatch {
+            expected: expected_crc,
+            actual: actual_crc,
+        });
+    }
+    Ok(body)
+}
+
+/// Serialize an episode package to a writer.
+///
+/// Binary format:
+/// `[Magic "ANIM" 4B][Version 2B][Flags 2B][Size 4B][CRC32 4B][Bincode Body]`
+pub fn serialize_episode<W: Write>(
+    episode: &EpisodePackage,
+    writer: &mut W,
+) -> crate::error::Result<usize> {
+    // Serialize body first to get size and CRC
+    let body = bincode::serialize(episode)?;
+
+    write_frame(writer, 0, &body)?;
+    Ok(16 + body.len())
+}
+
+/// Deserialize an episode package from a reader.
+///
+/// Accepts the single-body (v4, and v1 from before the layout grew), chunked (v2) and
+/// streamed (v3) formats; any corrupt chunk is an error (use
+/// `chunk::deserialize_episode_recovering` to salvage what is intact from v2).
+/// Encrypted or signed episodes are rejected; open them with `secure::open_episode`.
+/// Bodies flagged [`FLAG_NAMED_REFS`] have their actor references resolved by key.
+pub fn deserialize_episode<R: Read>(reader: &mut R) -> crate::error::Result<EpisodePackage> {
+    let header = read_header(reader)?;
+    match header_version(&header) {
+        CHUNKED_VERSION => return crate::chunk::read_chunked_strict(&header, reader),
+        STREAMED_VERSION => return crate::stream::read_streamed(&header, reader),
+        EPISODE_V1_VERSION => {
+            let body = read_checked_body(&header, reader, "ANIM body")?;
+            let core = crate::anim::decode_body(EPISODE_V1_VERSION, frame_flags(&header), &body)?;
+            return Ok(core.into());
+        }
+        _ => {}
+    }
+    let body = read_frame_body(&header, reader)?;
+
+    let flags = frame_flags(&header);
+    if flags & (FLAG_ENCRYPTED | FLAG_SIGNED) != 0 {
+        return Err(AnimationError::Protected { flags });
+    }
+    if flags & FLAG_NAMED_REFS != 0 {
+        let named: crate::named_refs::NamedEpisode = bincode::deserialize(&body)?;
+        return named.into_episode();
     }
 
     // Deserialize
-    bincode::deserialize(&body)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    let mut episode: EpisodePackage = bincode::deserialize(&body)?;
+    episode.seed_shake();
+    Ok(episode)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::camera::CameraWork;
+    use crate::clip::{AnimationClip, ClipInstance, ClipTrack};
     use crate::director::{Cut, Director};
-    use crate::scene::{Actor, SceneGraph};
+    use crate::scene::{Actor, ActorId, SceneGraph};
+    use alice_sdf::animation::Timeline;
     use alice_sdf::SdfNode;
 
     fn make_test_episode() -> EpisodePackage {
@@ -151,8 +415,8 @@ mod tests {
         let id_b = sg.add_actor(Actor::new("villain", SdfNode::box3d(1.0, 1.0, 1.0)));
 
         let mut dir = Director::new("Test Episode");
-        let c1 = dir.add_cut(Cut::new("intro", 0.0, 3.0).with_actors(vec![id_a]));
-        let c2 = dir.add_cut(Cut::new("battle", 3.0, 8.0).with_actors(vec![id_a, id_b]));
+        dir.add_cut(Cut::new("intro", 0.0, 3.0).with_actors(vec![id_a]));
+        dir.add_cut(Cut::new("battle", 3.0, 8.0).with_actors(vec![id_a, id_b]));
 
         let meta = EpisodeMetadata::new("Test", 1, 8.0);
         EpisodePackage::new(meta, sg, dir, AnimeShading::default())
@@ -174,11 +438,164 @@ mod tests {
         assert_eq!(restored.director.cut_count(), 2);
     }
 
+    #[test]
+    fn test_reads_version_1_episode() {
+        // Written by the first release, before cuts, actors and cameras grew new fields
+        let bytes = include_bytes!("../tests/data/episode_v1.anim");
+        assert_eq!(
+            header_version(bytes[..16].try_into().unwrap()),
+            EPISODE_V1_VERSION
+        );
+        let episode = deserialize_episode(&mut &bytes[..]).unwrap();
+        assert_eq!(episode.metadata.title, "Golden");
+        assert_eq!(episode.metadata.resolution, (1280, 720));
+        assert!(episode.metadata.extensions.is_empty());
+
+        let sg = &episode.scene_graph;
+        let hero = sg.find_by_name("hero").unwrap();
+        let prop = sg.get_actor(sg.find_by_name("prop").unwrap()).unwrap();
+        assert_eq!((prop.parent, prop.visible), (Some(hero), false));
+        let hero_actor = sg.get_actor(hero).unwrap();
+        assert_eq!(hero_actor.track_value("translate.y", 0.5), Some(1.0));
+        assert_eq!(hero_actor.local_transform.position.x, 1.0);
+        assert!(sg.clips.is_empty());
+
+        let dir = &episode.director;
+        assert_eq!(dir.episode.scenes[0].cuts.len(), 2);
+        let (_, intro) = dir.find_active_cut(1.0).unwrap();
+        assert_eq!(
+            (intro.name.as_str(), intro.active_actors.len()),
+            ("intro", 1)
+        );
+        assert_eq!(intro.camera.shake_amplitude, 0.1);
+        assert_eq!(
+            intro.camera.position_timeline.get_value("position.x", 3.0),
+            Some(2.0)
+        );
+        assert!(!intro.inherit_camera && intro.clip_tracks.is_empty());
+        let (_, battle) = dir.find_active_cut(5.0).unwrap();
+        assert!((battle.rcp_duration() - 0.2).abs() < 1e-6);
+        assert_eq!(episode.shading.ao_strength, 0.25);
+
+        // Saved again, it is written in the current layout
+        let mut buf = Vec::new();
+        serialize_episode(&episode, &mut buf).unwrap();
+        assert_eq!(
+            header_version(buf[..16].try_into().unwrap()),
+            EPISODE_VERSION
+        );
+        let again = deserialize_episode(&mut &buf[..]).unwrap();
+        assert_eq!(again.director.cut_count(), 2);
+    }
+
+    #[test]
+    fn test_metadata_extensions_roundtrip() {
+        let mut episode = make_test_episode();
+        episode.metadata = episode
+            .metadata
+            .clone()
+            .with_extension("production_code", "ALC-0101")
+            .with_extension("rating", 12i64)
+            .with_extension("hdr", true);
+        episode.metadata.set_extension(
+            "pipeline.shot_ids",
+            MetadataValue::List(vec!["S01".into(), "S02".into()]),
+        );
+
+        let mut buf = Vec::new();
+        serialize_episode(&episode, &mut buf).unwrap();
+        let restored = deserialize_episode(&mut std::io::Cursor::new(&buf)).unwrap();
+        let meta = &restored.metadata;
+        assert_eq!(
+            meta.extension("production_code").and_then(|v| v.as_str()),
+            Some("ALC-0101")
+        );
+        assert_eq!(meta.extension("rating").and_then(|v| v.as_i64()), Some(12));
+        assert_eq!(
+            meta.extension("rating").and_then(|v| v.as_f64()),
+            Some(12.0)
+        );
+        assert_eq!(meta.extension("hdr").and_then(|v| v.as_bool()), Some(true));
+        assert!(
+            matches!(meta.extension("pipeline.shot_ids"), Some(MetadataValue::List(l)) if l.len() == 2)
+        );
+        assert!(meta.extension("missing").is_none());
+    }
+
     #[test]
     fn test_invalid_magic() {
         let buf = b"BADMxxxxxxxxxxxxbody";
         let mut cursor = std::io::Cursor::new(&buf[..]);
-        assert!(deserialize_episode(&mut cursor).is_err());
+        assert!(matches!(
+            deserialize_episode(&mut cursor),
+            Err(AnimationError::BadMagic { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_reports_overlap_and_missing_actor() {
+        let mut ep = make_test_episode();
+        assert!(ep.validation_issues().is_empty());
+        let wave = AnimationClip::new("wave", Timeline::new("wave"));
+        let track = ClipTrack::new(ActorId(0)).with_clip(ClipInstance::new(&wave, 0.0));
+        ep.director.add_cut(
+            Cut::new("stray", 6.0, 9.0)
+                .with_actors(vec![ActorId(9)])
+                .with_clip_track(track),
+        );
+        let issues = ep.validation_issues();
+        assert!(matches!(ep.validate(), Err(AnimationError::Validation(v)) if v == issues));
+        assert!(issues.iter().any(|i| i.contains("overlaps")));
+        assert!(issues.iter().any(|i| i.contains("missing actor 9")));
+        assert!(issues.iter().any(|i| i.contains("past episode duration")));
+        assert!(issues.iter().any(|i| i.contains("missing clip 'wave'")));
+    }
+
+    #[test]
+    fn test_seed_survives_roundtrip() {
+        let mut episode = make_test_episode();
+        assert_eq!(episode.metadata.seed(), 0);
+        episode.metadata = episode.metadata.with_seed(u64::MAX - 3);
+        let mut buf = Vec::new();
+        serialize_episode(&episode, &mut buf).unwrap();
+        let restored = deserialize_episode(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(restored.metadata.seed(), u64::MAX - 3);
+        assert_eq!(
+            restored.rng().seed_for("blink/hero"),
+            episode.rng().seed_for("blink/hero")
+        );
+    }
+
+    #[test]
+    fn test_shake_follows_episode_seed() {
+        let shaken = |seed| {
+            let mut cut = Cut::new("quake", 0.0, 2.0);
+            cut.camera.apply_preset(
+                CameraWork::Shake {
+                    amplitude: 0.2,
+                    frequency: 6.0,
+                },
+                0.0,
+                2.0,
+            );
+            let mut dir = Director::new("Quake");
+            dir.add_cut(cut);
+            let meta = EpisodeMetadata::new("Quake", 1, 2.0).with_seed(seed);
+            EpisodePackage::new(meta, SceneGraph::new(), dir, AnimeShading::default())
+        };
+        let path = |episode: &EpisodePackage| {
+            let camera = &episode.director.cuts().next().unwrap().1.camera;
+            (0..24)
+                .map(|i| camera.evaluate(i as f32 / 24.0).position)
+                .collect::<Vec<_>>()
+        };
+        assert_ne!(path(&shaken(1)), path(&shaken(2)));
+
+        let episode = shaken(1);
+        let mut buf = Vec::new();
+        serialize_episode(&episode, &mut buf).unwrap();
+        let restored = deserialize_episode(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(path(&restored), path(&episode));
     }
 
     #[test]
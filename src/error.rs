@@ -0,0 +1,128 @@
+//! Crate-wide error type for episode I/O, codec streams and validation.
+//!
+//! APIs that still return `io::Result` carry an `AnimationError` as the inner error of the
+//! `io::Error`, so the structured variant can be recovered with [`AnimationError::from_io`]
+//! or by converting back with `From`. Without `std` the I/O and bincode variants are left out.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io;
+
+/// Everything that can go wrong loading, saving or checking an episode.
+#[derive(Debug, thiserror::Error)]
+pub enum AnimationError {
+    /// Underlying reader/writer failure.
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Io(io::Error),
+    /// Stream does not start with the expected magic bytes.
+    #[error("Invalid magic bytes: expected {expected}")]
+    BadMagic { expected: &'static str },
+    /// Container or codec version this build cannot read.
+    #[error("Unsupported {format} version: {version}")]
+    UnsupportedVersion { format: &'static str, version: u16 },
+    /// Body checksum does not match the header.
+    #[error("CRC mismatch: expected {expected:#010x}, got {actual:#010x}")]
+    CrcMismatch { expected: u32, actual: u32 },
+    /// Encrypted or signed container opened with the plain reader.
+    #[error("Episode is encrypted or signed (flags {flags:#06x})")]
+    Protected { flags: u16 },
+    /// bincode failed to encode or decode the body.
+    #[cfg(feature = "std")]
+    #[error("Encoding error: {0}")]
+    Encoding(#[from] bincode::Error),
+    /// Stream ended before the named section was complete.
+    #[error("Truncated {0}")]
+    Truncated(&'static str),
+    /// Structurally invalid data inside an otherwise readable stream.
+    #[error("{0}")]
+    Corrupt(String),
+    /// Caller-supplied value or text that cannot be used (bad parameter, parse failure).
+    #[error("{0}")]
+    InvalidInput(String),
+    /// `EpisodePackage::validate` found problems.
+    #[error("{} validation issue(s): {}", .0.len(), .0.join("; "))]
+    Validation(Vec<String>),
+}
+
+/// Result alias used by episode and codec APIs.
+pub type Result<T> = core::result::Result<T, AnimationError>;
+
+#[cfg(feature = "std")]
+impl AnimationError {
+    /// Closest `io::ErrorKind`, used when converting into `io::Error`.
+    pub fn io_kind(&self) -> io::ErrorKind {
+        match self {
+            Self::Io(e) => e.kind(),
+            Self::UnsupportedVersion { .. } => io::ErrorKind::Unsupported,
+            Self::Truncated(_) => io::ErrorKind::UnexpectedEof,
+            Self::Protected { .. } => io::ErrorKind::PermissionDenied,
+            Self::InvalidInput(_) => io::ErrorKind::InvalidInput,
+            _ => io::ErrorKind::InvalidData,
+        }
+    }
+
+    /// Structured error carried inside an `io::Error`, if any.
+    pub fn from_io(err: &io::Error) -> Option<&AnimationError> {
+        err.get_ref()
+            .and_then(|e| e.downcast_ref::<AnimationError>())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for AnimationError {
+    fn from(err: io::Error) -> Self {
+        if AnimationError::from_io(&err).is_none() {
+            return Self::Io(err);
+        }
+        let kind = err.kind();
+        match err.into_inner().map(|e| e.downcast::<AnimationError>()) {
+            Some(Ok(inner)) => *inner,
+            Some(Err(other)) => Self::Io(io::Error::new(kind, other)),
+            None => Self::Io(io::Error::from(kind)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<AnimationError> for io::Error {
+    fn from(err: AnimationError) -> Self {
+        match err {
+            AnimationError::Io(e) => e,
+            other => io::Error::new(other.io_kind(), other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_io_roundtrip_keeps_variant() {
+        let err = AnimationError::CrcMismatch {
+            expected: 1,
+            actual: 2,
+        };
+        let io_err: io::Error = err.into();
+        assert_eq!(io_err.kind(), io::ErrorKind::InvalidData);
+        assert!(matches!(
+            AnimationError::from_io(&io_err),
+            Some(AnimationError::CrcMismatch { expected: 1, .. })
+        ));
+        let back = AnimationError::from(io_err);
+        assert!(matches!(
+            back,
+            AnimationError::CrcMismatch { actual: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn test_plain_io_error_stays_io() {
+        let err = AnimationError::from(io::Error::new(io::ErrorKind::NotFound, "gone"));
+        assert_eq!(err.io_kind(), io::ErrorKind::NotFound);
+        assert!(matches!(err, AnimationError::Io(_)));
+        assert_eq!(io::Error::from(err).to_string(), "gone");
+    }
+}
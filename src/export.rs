@@ -0,0 +1,438 @@
+//! Frame-range export: numbered image sequences and raw y4m video.
+
+use std::io::Write;
+
+use crate::episode::EpisodePackage;
+use crate::framebuffer::Framebuffer;
+use crate::render::Renderer;
+
+/// Frames to bake: `[start, end)` sampled at `fps`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameRange {
+    pub start: f32,
+    pub end: f32,
+    pub fps: f32,
+}
+
+impl FrameRange {
+    pub fn new(start: f32, end: f32, fps: f32) -> Self {
+        Self { start, end, fps }
+    }
+
+    /// The whole episode.
+    pub fn whole(episode: &EpisodePackage, fps: f32) -> Self {
+        let end = episode
+            .metadata
+            .duration_seconds
+            .max(episode.director.duration());
+        Self::new(0.0, end, fps)
+    }
+
+    /// Number of frames in the range.
+    pub fn frame_count(&self) -> u32 {
+        if self.fps <= 0.0 || self.end <= self.start {
+            return 0;
+        }
+        // Tolerance keeps e.g. 2.0s @ 24fps at exactly 48 frames
+        ((self.end - self.start) * self.fps - 1e-3).ceil().max(0.0) as u32
+    }
+
+    /// Episode time of the `index`-th frame of the range.
+    #[inline]
+    pub fn frame_time(&self, index: u32) -> f32 {
+        self.start + index as f32 / self.fps
+    }
+
+    /// Absolute episode frame number of the `index`-th frame (used for file numbering).
+    #[inline]
+    pub fn frame_number(&self, index: u32) -> u32 {
+        (self.start * self.fps).round() as u32 + index
+    }
+}
+
+/// Progress report passed to export callbacks after each frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportProgress {
+    /// Frames finished so far.
+    pub frames_done: u32,
+    pub total_frames: u32,
+}
+
+impl ExportProgress {
+    /// Completed fraction (0.0..=1.0).
+    #[inline]
+    pub fn fraction(&self) -> f32 {
+        if self.total_frames == 0 {
+            1.0
+        } else {
+            self.frames_done as f32 / self.total_frames as f32
+        }
+    }
+}
+
+/// Render every frame of `range`, handing each to `sink` in order with its range index.
+///
+/// With `parallel`, frames are rendered concurrently via `render_range_parallel`.
+pub fn render_range<F>(
+    renderer: &Renderer,
+    episode: &EpisodePackage,
+    range: &FrameRange,
+    sink: F,
+    progress: impl FnMut(ExportProgress),
+) -> crate::error::Result<()>
+where
+    F: FnMut(u32, &Framebuffer) -> crate::error::Result<()>,
+{
+    #[cfg(feature = "parallel")]
+    let result = render_range_parallel(renderer, episode, range, sink, progress);
+    #[cfg(not(feature = "parallel"))]
+    let result = render_range_serial(renderer, episode, range, sink, progress);
+    result
+}
+
+#[cfg(not(feature = "parallel"))]
+fn render_range_serial<F>(
+    renderer: &Renderer,
+    episode: &EpisodePackage,
+    range: &FrameRange,
+    mut sink: F,
+    mut progress: impl FnMut(ExportProgress),
+) -> crate::error::Result<()>
+where
+    F: FnMut(u32, &Framebuffer) -> crate::error::Result<()>,
+{
+    let total_frames = range.frame_count();
+    for index in 0..total_frames {
+        let frame = renderer.render_episode(episode, range.frame_time(index));
+        sink(index, &frame)?;
+        progress(ExportProgress {
+            frames_done: index + 1,
+            total_frames,
+        });
+    }
+    Ok(())
+}
+
+/// Parallel frame queue: frames render on one worker per rayon thread, claimed in
+/// order, while the calling thread reorders them and runs `sink` and `progress`
+/// sequentially.
+///
+/// Workers only start a frame less than `FRAME_QUEUE_DEPTH` ahead of the last one written,
+/// so one slow frame cannot let finished frames pile up; a `sink` error stops workers from
+/// starting new frames.
+#[cfg(feature = "parallel")]
+pub fn render_range_parallel<F>(
+    renderer: &Renderer,
+    episode: &EpisodePackage,
+    range: &FrameRange,
+    mut sink: F,
+    mut progress: impl FnMut(ExportProgress),
+) -> crate::error::Result<()>
+where
+    F: FnMut(u32, &Framebuffer) -> crate::error::Result<()>,
+{
+    use std::collections::BTreeMap;
+    use std::sync::{mpsc, Condvar, Mutex};
+
+    /// Frames handed out and frames written, plus the stop flag.
+    struct Window {
+        claimed: u32,
+        written: u32,
+        cancelled: bool,
+    }
+
+    let total_frames = range.frame_count();
+    let window = Mutex::new(Window {
+        claimed: 0,
+        written: 0,
+        cancelled: false,
+    });
+    let advanced = Condvar::new();
+    let (tx, rx) = mpsc::sync_channel::<(u32, Framebuffer)>(FRAME_QUEUE_DEPTH);
+    let lock = || window.lock().unwrap_or_else(|e| e.into_inner());
+
+    std::thread::scope(|scope| {
+        for _ in 0..rayon::current_num_threads().min(total_frames as usize) {
+            let tx = tx.clone();
+            let (window, advanced) = (&window, &advanced);
+            scope.spawn(move || loop {
+                let index = {
+                    let mut w = window.lock().unwrap_or_else(|e| e.into_inner());
+                    while !w.cancelled
+                        && w.claimed < total_frames
+                        && w.claimed >= w.written + FRAME_QUEUE_DEPTH as u32
+                    {
+                        w = advanced.wait(w).unwrap_or_else(|e| e.into_inner());
+                    }
+                    if w.cancelled || w.claimed >= total_frames {
+                        return;
+                    }
+                    w.claimed += 1;
+                    w.claimed - 1
+                };
+                let frame = renderer.render_episode(episode, range.frame_time(index));
+                // Receiver gone means the sink failed
+                if tx.send((index, frame)).is_err() {
+                    return;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending: BTreeMap<u32, Framebuffer> = BTreeMap::new();
+        let mut next = 0u32;
+        for (index, frame) in rx {
+            pending.insert(index, frame);
+            while let Some(frame) = pending.remove(&next) {
+                if let Err(e) = sink(next, &frame) {
+                    lock().cancelled = true;
+                    advanced.notify_all();
+                    return Err(e);
+                }
+                next += 1;
+                lock().written = next;
+                advanced.notify_all();
+                progress(ExportProgress {
+                    frames_done: next,
+                    total_frames,
+                });
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Finished frames buffered between render workers and the sink.
+#[cfg(feature = "parallel")]
+const FRAME_QUEUE_DEPTH: usize = 8;
+
+/// Frame rate as a y4m `F` ratio (NTSC rates become `N000:1001`).
+fn fps_ratio(fps: f32) -> (u32, u32) {
+    let ntsc = fps * 1.001;
+    if (ntsc - ntsc.round()).abs() < 1e-3 && (fps - fps.round()).abs() > 1e-3 {
+        return (ntsc.round() as u32 * 1000, 1001);
+    }
+    let mut num = (fps * 1000.0).round() as u32;
+    let mut den = 1000u32;
+    let (mut a, mut b) = (num, den);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    if a > 1 {
+        num /= a;
+        den /= a;
+    }
+    (num, den)
+}
+
+/// Write the y4m stream header (4:4:4, progressive, square pixels).
+pub fn write_y4m_header<W: Write>(
+    writer: &mut W,
+    width: u32,
+    height: u32,
+    fps: f32,
+) -> crate::error::Result<()> {
+    let (num, den) = fps_ratio(fps);
+    writeln!(
+        writer,
+        "YUV4MPEG2 W{} H{} F{}:{} Ip A1:1 C444",
+        width, height, num, den
+    )?;
+    Ok(())
+}
+
+/// Write one y4m frame: sRGB color converted to BT.709 limited-range Y'CbCr.
+pub fn write_y4m_frame<W: Write>(writer: &mut W, frame: &Framebuffer) -> crate::error::Result<()> {
+    let n = frame.pixel_count();
+    let mut planes = vec![0u8; n * 3];
+    for (i, px) in frame.to_rgba8().chunks_exact(4).enumerate() {
+        let (r, g, b) = (px[0] as f32, px[1] as f32, px[2] as f32);
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let cb = (b - y) / 1.8556;
+        let cr = (r - y) / 1.5748;
+        planes[i] = (16.0 + y * (219.0 / 255.0)).round() as u8;
+        planes[n + i] = (128.0 + cb * (224.0 / 255.0)).round() as u8;
+        planes[2 * n + i] = (128.0 + cr * (224.0 / 255.0)).round() as u8;
+    }
+    writer.write_all(b"FRAME\n")?;
+    writer.write_all(&planes)?;
+    Ok(())
+}
+
+/// Render `range` as a raw y4m stream (e.g. piped into `ffmpeg -i -`). Returns frames written.
+pub fn export_y4m<W: Write>(
+    renderer: &Renderer,
+    episode: &EpisodePackage,
+    range: &FrameRange,
+    writer: &mut W,
+    progress: impl FnMut(ExportProgress),
+) -> crate::error::Result<u32> {
+    write_y4m_header(writer, renderer.width(), renderer.height(), range.fps)?;
+    let mut frames = 0;
+    render_range(
+        renderer,
+        episode,
+        range,
+        |_, frame| {
+            frames += 1;
+            write_y4m_frame(writer, frame)
+        },
+        progress,
+    )?;
+    writer.flush()?;
+    Ok(frames)
+}
+
+/// Image format for sequence export.
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceFormat {
+    Png,
+    Exr,
+}
+
+#[cfg(feature = "image")]
+impl SequenceFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SequenceFormat::Png => "png",
+            SequenceFormat::Exr => "exr",
+        }
+    }
+}
+
+/// Render `range` as `{dir}/{prefix}{frame:04}.{ext}`, numbered by absolute episode frame.
+#[cfg(feature = "image")]
+pub fn export_image_sequence(
+    renderer: &Renderer,
+    episode: &EpisodePackage,
+    range: &FrameRange,
+    dir: impl AsRef<std::path::Path>,
+    prefix: &str,
+    format: SequenceFormat,
+    progress: impl FnMut(ExportProgress),
+) -> crate::error::Result<Vec<std::path::PathBuf>> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let mut paths = Vec::with_capacity(range.frame_count() as usize);
+    render_range(
+        renderer,
+        episode,
+        range,
+        |index, frame| {
+            let path = dir.join(format!(
+                "{}{:04}.{}",
+                prefix,
+                range.frame_number(index),
+                format.extension()
+            ));
+            match format {
+                SequenceFormat::Png => crate::image_io::write_png(frame, &path)?,
+                SequenceFormat::Exr => crate::image_io::write_exr(frame, &path)?,
+            }
+            paths.push(path);
+            Ok(())
+        },
+        progress,
+    )?;
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::EpisodeMetadata;
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Export");
+        dir.add_cut(Cut::new("c1", 0.0, 0.5));
+        let meta = EpisodeMetadata::new("Export", 1, 0.5);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_frame_range() {
+        let range = FrameRange::new(1.0, 3.0, 24.0);
+        assert_eq!(range.frame_count(), 48);
+        assert_eq!(range.frame_number(0), 24);
+        assert_eq!(range.frame_time(12), 1.5);
+        assert_eq!(fps_ratio(24.0), (24, 1));
+        assert_eq!(fps_ratio(23.976), (24000, 1001));
+        assert_eq!(fps_ratio(12.5), (25, 2));
+    }
+
+    #[test]
+    fn test_y4m_stream() {
+        let episode = make_test_episode();
+        let renderer = Renderer::new(8, 6);
+        let range = FrameRange::new(0.0, 0.25, 12.0);
+        let mut updates = Vec::new();
+        let mut out = Vec::new();
+        let frames = export_y4m(&renderer, &episode, &range, &mut out, |p| {
+            updates.push(p.fraction())
+        })
+        .unwrap();
+        assert_eq!(frames, 3);
+        assert_eq!(updates, vec![1.0 / 3.0, 2.0 / 3.0, 1.0]);
+
+        let header = b"YUV4MPEG2 W8 H6 F12:1 Ip A1:1 C444\n";
+        assert!(out.starts_with(header));
+        assert_eq!(out.len(), header.len() + 3 * (6 + 8 * 6 * 3));
+        // White background: Y=235, neutral chroma
+        let first = header.len() + 6;
+        assert_eq!(out[first], 235);
+        assert_eq!(out[first + 48], 128);
+    }
+
+    #[test]
+    fn test_sink_error_stops_export() {
+        let episode = make_test_episode();
+        let range = FrameRange::new(0.0, 0.5, 24.0);
+        let mut seen = Vec::new();
+        let result = render_range(
+            &Renderer::new(4, 4),
+            &episode,
+            &range,
+            |index, _| {
+                seen.push(index);
+                if index == 3 {
+                    Err(std::io::Error::other("disk full").into())
+                } else {
+                    Ok(())
+                }
+            },
+            |_| {},
+        );
+        assert!(result.is_err());
+        // Frames reach the sink in order even when rendered concurrently
+        assert_eq!(seen, vec![0, 1, 2, 3]);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_png_sequence() {
+        let episode = make_test_episode();
+        let dir = std::env::temp_dir().join(format!("alice_anim_seq_{}", std::process::id()));
+        let range = FrameRange::new(0.25, 0.5, 8.0);
+        let paths = export_image_sequence(
+            &Renderer::new(4, 4),
+            &episode,
+            &range,
+            &dir,
+            "shot_",
+            SequenceFormat::Png,
+            |_| {},
+        )
+        .unwrap();
+        assert_eq!(paths.len(), 2);
+        assert!(paths[0].ends_with("shot_0002.png"));
+        assert!(paths.iter().all(|p| p.exists()));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,248 @@
+//! Exposure quantization: snap finished timing to whole frames.
+//!
+//! Keys set by solvers, retiming or hand edits drift off the frame grid, and two drawings
+//! can end up closer together than the production allows. [`Exposure`] snaps every key to
+//! a frame boundary at the delivery frame rate, pushes keys later so each drawing is
+//! exposed for at least the minimum hold, and reports every key it had to move so the
+//! final data can be checked against the exposure sheet.
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+use crate::scene::SceneGraph;
+
+/// A key that was not on a frame boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubFrameKey {
+    pub track: String,
+    /// Time the key had before snapping.
+    pub time: f32,
+    /// Frame it was snapped to.
+    pub frame: u32,
+}
+
+/// A key pushed later to honour the minimum hold.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoldViolation {
+    pub track: String,
+    /// Frame the key snapped to.
+    pub frame: u32,
+    /// Frame it was moved to.
+    pub moved_to: u32,
+}
+
+/// What a quantization pass changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExposureReport {
+    pub sub_frame: Vec<SubFrameKey>,
+    pub short_holds: Vec<HoldViolation>,
+    /// Keys dropped because a later key snapped onto the same frame.
+    pub merged: usize,
+}
+
+impl ExposureReport {
+    /// Every key was already on the grid with long enough holds.
+    #[inline]
+    pub fn is_clean(&self) -> bool {
+        self.sub_frame.is_empty() && self.short_holds.is_empty() && self.merged == 0
+    }
+
+    fn extend(&mut self, other: ExposureReport) {
+        self.sub_frame.extend(other.sub_frame);
+        self.short_holds.extend(other.short_holds);
+        self.merged += other.merged;
+    }
+}
+
+/// Exposure standard timelines are quantized to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Exposure {
+    pub fps: f32,
+    /// Fewest frames between consecutive keys (2 = on twos).
+    pub min_hold: u32,
+    /// Fraction of a frame a key may sit off the grid before it is flagged.
+    pub tolerance: f32,
+}
+
+impl Exposure {
+    pub fn new(fps: f32) -> Self {
+        Self {
+            fps,
+            min_hold: 1,
+            tolerance: 1e-3,
+        }
+    }
+
+    pub fn with_min_hold(mut self, frames: u32) -> Self {
+        self.min_hold = frames;
+        self
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Frame `time` is closest to (negative times clamp to frame 0).
+    #[inline]
+    pub fn frame(&self, time: f32) -> u32 {
+        (time * self.fps).round().max(0.0) as u32
+    }
+
+    /// Keys of `track` that sit off the frame grid, without changing anything.
+    pub fn check_track(&self, track: &Track) -> Vec<SubFrameKey> {
+        track
+            .keyframes
+            .iter()
+            .filter(|k| ((k.time * self.fps) - (k.time * self.fps).round()).abs() > self.tolerance)
+            .map(|k| SubFrameKey {
+                track: track.name.clone(),
+                time: k.time,
+                frame: self.frame(k.time),
+            })
+            .collect()
+    }
+
+    /// Snap the keys of `track` to frames and enforce the minimum hold. Where keys land
+    /// on the same frame the last one wins.
+    pub fn quantize_track(&self, track: &mut Track) -> ExposureReport {
+        let mut report = ExposureReport {
+            sub_frame: self.check_track(track),
+            ..Default::default()
+        };
+        if self.fps <= 0.0 {
+            return report;
+        }
+        // (frame placed on, frame snapped to, key)
+        let mut keys: Vec<(u32, u32, Keyframe)> = Vec::with_capacity(track.keyframes.len());
+        for key in track.keyframes.drain(..) {
+            let snapped = self.frame(key.time);
+            let mut frame = snapped;
+            if let Some(&(previous, previous_snapped, _)) = keys.last() {
+                if snapped == previous_snapped {
+                    keys.pop();
+                    report.merged += 1;
+                    frame = keys.last().map_or(snapped, |&(before, _, _)| {
+                        snapped.max(before + self.min_hold.max(1))
+                    });
+                } else if frame < previous + self.min_hold.max(1) {
+                    frame = previous + self.min_hold.max(1);
+                    report.short_holds.push(HoldViolation {
+                        track: track.name.clone(),
+                        frame: snapped,
+                        moved_to: frame,
+                    });
+                }
+            }
+            keys.push((frame, snapped, key));
+        }
+        track.keyframes = keys
+            .into_iter()
+            .map(|(frame, _, mut key)| {
+                key.time = frame as f32 / self.fps;
+                key
+            })
+            .collect();
+        report
+    }
+
+    pub fn quantize_timeline(&self, timeline: &mut Timeline) -> ExposureReport {
+        let mut report = ExposureReport::default();
+        for track in &mut timeline.tracks {
+            report.extend(self.quantize_track(track));
+        }
+        report
+    }
+
+    /// Quantize every actor timeline; reported tracks are named `actor/track`.
+    pub fn quantize_scene(&self, scene: &mut SceneGraph) -> ExposureReport {
+        let mut report = ExposureReport::default();
+        for id in scene.actor_ids() {
+            let Some(actor) = scene.get_actor_mut(id) else {
+                continue;
+            };
+            let Some(timeline) = actor.timeline.as_mut() else {
+                continue;
+            };
+            let mut found = self.quantize_timeline(timeline);
+            let prefix = |track: &mut String| *track = format!("{}/{}", actor.name, track);
+            found
+                .sub_frame
+                .iter_mut()
+                .for_each(|k| prefix(&mut k.track));
+            found
+                .short_holds
+                .iter_mut()
+                .for_each(|h| prefix(&mut h.track));
+            report.extend(found);
+        }
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Actor, ActorId};
+    use alice_sdf::SdfNode;
+
+    fn keyed(name: &str, keys: &[(f32, f32)]) -> Track {
+        let mut track = Track::new(name);
+        for &(time, value) in keys {
+            track.add_keyframe(Keyframe::new(time, value));
+        }
+        track
+    }
+
+    #[test]
+    fn test_snap_and_flag_sub_frame_keys() {
+        let exposure = Exposure::new(24.0);
+        let mut track = keyed(
+            "translate.x",
+            &[(0.0, 0.0), (0.5, 1.0), (0.52, 2.0), (0.53, 3.0), (1.0, 4.0)],
+        );
+        assert_eq!(exposure.check_track(&track).len(), 2);
+        let report = exposure.quantize_track(&mut track);
+        // 0.5s and 0.52s both land on frame 12: the later key wins
+        assert_eq!(report.merged, 1);
+        assert_eq!(report.sub_frame[0].frame, 12);
+        assert_eq!(report.sub_frame[1].frame, 13);
+        let frames: Vec<f32> = track.keyframes.iter().map(|k| k.time * 24.0).collect();
+        assert_eq!(frames, vec![0.0, 12.0, 13.0, 24.0]);
+        assert_eq!(track.keyframes[1].value, 2.0);
+        assert!(exposure.quantize_track(&mut track).is_clean());
+    }
+
+    #[test]
+    fn test_min_hold_pushes_keys_later() {
+        let on_twos = Exposure::new(24.0).with_min_hold(2);
+        let frames = [0.0, 1.0, 2.0, 6.0, 7.0];
+        let keys: Vec<(f32, f32)> = frames.iter().map(|&f| (f / 24.0, f)).collect();
+        let mut tl = Timeline::new("hero");
+        tl.add_track(keyed("arm.r.swing", &keys));
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)).with_timeline(tl));
+
+        let report = on_twos.quantize_scene(&mut sg);
+        assert!(report.sub_frame.is_empty());
+        let moved: Vec<(u32, u32)> = report
+            .short_holds
+            .iter()
+            .map(|h| (h.frame, h.moved_to))
+            .collect();
+        assert_eq!(moved, vec![(1, 2), (2, 4), (7, 8)]);
+        assert_eq!(report.short_holds[0].track, "hero/arm.r.swing");
+        let track = &sg
+            .get_actor(ActorId(0))
+            .unwrap()
+            .timeline
+            .as_ref()
+            .unwrap()
+            .tracks[0];
+        let frames: Vec<u32> = track
+            .keyframes
+            .iter()
+            .map(|k| on_twos.frame(k.time))
+            .collect();
+        assert_eq!(frames, vec![0, 2, 4, 6, 8]);
+    }
+}
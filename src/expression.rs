@@ -0,0 +1,243 @@
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use serde::{Deserialize, Serialize};
+
+use crate::lip_sync::{LipSyncTrack, VisemeProfile};
+
+/// Named facial expression channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Expression {
+    Smile,
+    Anger,
+    Surprise,
+    Sadness,
+    /// Both eyes closing (0.0 = open, 1.0 = shut).
+    EyeClose,
+}
+
+impl Expression {
+    /// All channels, in declaration order.
+    pub const ALL: [Expression; 5] = [
+        Expression::Smile,
+        Expression::Anger,
+        Expression::Surprise,
+        Expression::Sadness,
+        Expression::EyeClose,
+    ];
+
+    /// Timeline track name for this channel.
+    pub fn track_name(&self) -> &'static str {
+        match self {
+            Expression::Smile => "face.smile",
+            Expression::Anger => "face.anger",
+            Expression::Surprise => "face.surprise",
+            Expression::Sadness => "face.sadness",
+            Expression::EyeClose => "eye.close",
+        }
+    }
+}
+
+/// Facial expression animation: one weight track (0.0..=1.0) per channel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpressionTrack {
+    pub name: String,
+    pub channels: Vec<(Expression, Track)>,
+}
+
+impl ExpressionTrack {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            channels: Vec::new(),
+        }
+    }
+
+    /// Key a channel weight at a given time.
+    pub fn set_weight(&mut self, expression: Expression, time: f32, weight: f32) {
+        let weight = weight.clamp(0.0, 1.0);
+        match self.channels.iter_mut().find(|(e, _)| *e == expression) {
+            Some((_, track)) => track.add_keyframe(Keyframe::new(time, weight)),
+            None => {
+                let mut track = Track::new(expression.track_name());
+                track.add_keyframe(Keyframe::new(time, weight));
+                self.channels.push((expression, track));
+            }
+        }
+    }
+
+    /// Channel weight at `time` (0.0 if the channel is not animated).
+    pub fn weight(&self, expression: Expression, time: f32) -> f32 {
+        self.channels
+            .iter()
+            .find(|(e, _)| *e == expression)
+            .map(|(_, track)| track.evaluate(time))
+            .unwrap_or(0.0)
+    }
+
+    /// Times of all keyframes across channels.
+    pub fn key_times(&self) -> Vec<f32> {
+        self.channels
+            .iter()
+            .flat_map(|(_, track)| track.keyframes.iter().map(|k| k.time))
+            .collect()
+    }
+
+    /// Convert to a Timeline with one track per animated channel.
+    pub fn to_timeline(&self) -> Timeline {
+        let mut tl = Timeline::new(&self.name);
+        for (_, track) in &self.channels {
+            tl.add_track(track.clone());
+        }
+        tl
+    }
+}
+
+/// Weighting rules for composing expressions with lip sync.
+///
+/// Lip sync owns mouth openness; expressions only offset mouth shape, and that offset is
+/// scaled by `speech_mouth_weight` while the character is speaking so dialogue stays legible.
+/// Mouth corners and eyes always follow the expression.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FacialBlendRules {
+    /// Expression influence on mouth shape while speaking (0.0 = lip sync only).
+    pub speech_mouth_weight: f32,
+    /// Mouth openness below which the character counts as not speaking.
+    pub speech_threshold: f32,
+    /// Width added by a full smile.
+    pub smile_width: f32,
+    /// Width removed by full anger.
+    pub anger_width: f32,
+    /// Lip press added by full anger.
+    pub anger_press: f32,
+    /// Openness added by full surprise.
+    pub surprise_openness: f32,
+}
+
+impl Default for FacialBlendRules {
+    fn default() -> Self {
+        Self {
+            speech_mouth_weight: 0.4,
+            speech_threshold: 0.05,
+            smile_width: 0.3,
+            anger_width: 0.2,
+            anger_press: 0.5,
+            surprise_openness: 0.5,
+        }
+    }
+}
+
+/// Compose lip sync and expressions into one facial Timeline.
+///
+/// Output tracks: "mouth.openness", "mouth.width", "mouth.lip_press", "mouth.corner"
+/// (-1.0 = down, 1.0 = up), plus every expression channel. Values are sampled at the union
+/// of both inputs' keyframe times. Mouth shapes come from the speaker's viseme `profile`
+/// (usually `actor.viseme_profile.as_ref()`), or the default shapes for `None`.
+pub fn compose_facial_timeline(
+    name: &str,
+    lip_sync: &LipSyncTrack,
+    profile: Option<&VisemeProfile>,
+    expressions: &ExpressionTrack,
+    rules: &FacialBlendRules,
+) -> Timeline {
+    let lip = lip_sync.to_timeline_with_profile(profile);
+    let mut times: Vec<f32> = lip_sync.phonemes.iter().map(|k| k.time).collect();
+    times.extend(expressions.key_times());
+    times.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    times.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+
+    let mut openness = Track::new("mouth.openness");
+    let mut width = Track::new("mouth.width");
+    let mut lip_press = Track::new("mouth.lip_press");
+    let mut corner = Track::new("mouth.corner");
+
+    for &t in &times {
+        let lip_open = lip.get_value("mouth.openness", t).unwrap_or(0.0);
+        let lip_width = lip.get_value("mouth.width", t).unwrap_or(0.3);
+        let lip_lip = lip.get_value("mouth.lip_press", t).unwrap_or(0.0);
+        let lip_corner = lip.get_value("mouth.corner", t).unwrap_or(0.0);
+
+        let smile = expressions.weight(Expression::Smile, t);
+        let anger = expressions.weight(Expression::Anger, t);
+        let surprise = expressions.weight(Expression::Surprise, t);
+        let sadness = expressions.weight(Expression::Sadness, t);
+
+        let w = if lip_open > rules.speech_threshold {
+            rules.speech_mouth_weight
+        } else {
+            1.0
+        };
+        let open = lip_open + w * surprise * rules.surprise_openness;
+        let wide = lip_width + w * (smile * rules.smile_width - anger * rules.anger_width);
+        let press = lip_lip.max(w * anger * rules.anger_press);
+
+        openness.add_keyframe(Keyframe::new(t, open.clamp(0.0, 1.0)));
+        width.add_keyframe(Keyframe::new(t, wide.clamp(0.0, 1.0)));
+        lip_press.add_keyframe(Keyframe::new(t, press.clamp(0.0, 1.0)));
+        corner.add_keyframe(Keyframe::new(
+            t,
+            (lip_corner + smile - anger.max(sadness)).clamp(-1.0, 1.0),
+        ));
+    }
+
+    let mut tl = Timeline::new(name);
+    tl.add_track(openness);
+    tl.add_track(width);
+    tl.add_track(lip_press);
+    tl.add_track(corner);
+    for (_, track) in &expressions.channels {
+        tl.add_track(track.clone());
+    }
+    tl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expression_weights() {
+        let mut expr = ExpressionTrack::new("hero_face");
+        expr.set_weight(Expression::Smile, 0.0, 0.0);
+        expr.set_weight(Expression::Smile, 1.0, 1.0);
+        expr.set_weight(Expression::EyeClose, 0.0, 2.0);
+
+        assert!((expr.weight(Expression::Smile, 0.5) - 0.5).abs() < 1e-5);
+        assert_eq!(expr.weight(Expression::EyeClose, 0.0), 1.0); // clamped
+        assert_eq!(expr.weight(Expression::Anger, 0.5), 0.0);
+
+        let tl = expr.to_timeline();
+        assert_eq!(tl.tracks.len(), 2);
+        assert!(tl.get_value("face.smile", 1.0).is_some());
+    }
+
+    #[test]
+    fn test_smiling_character_still_speaks() {
+        use crate::lip_sync::Phoneme;
+
+        let mut lip = LipSyncTrack::new("line");
+        lip.add_phoneme(0.0, Phoneme::A);
+        lip.add_phoneme(1.0, Phoneme::Closed);
+        let mut expr = ExpressionTrack::new("face");
+        expr.set_weight(Expression::Smile, 0.0, 1.0);
+
+        let rules = FacialBlendRules::default();
+        let tl = compose_facial_timeline("face", &lip, None, &expr, &rules);
+
+        // Lip sync keeps full openness while speaking; smile only partly widens
+        assert_eq!(tl.get_value("mouth.openness", 0.0), Some(1.0));
+        let speaking_width = tl.get_value("mouth.width", 0.0).unwrap();
+        assert!((speaking_width - (0.8 + 0.4 * 0.3)).abs() < 1e-5);
+        // Silent: smile applies fully
+        let silent_width = tl.get_value("mouth.width", 1.0).unwrap();
+        assert!((silent_width - (0.3 + 0.3)).abs() < 1e-5);
+        assert_eq!(tl.get_value("mouth.corner", 0.0), Some(1.0));
+        assert!(tl.get_value("face.smile", 0.0).is_some());
+
+        // A small-mouthed design keeps its calibrated shapes under the expression
+        let chibi = VisemeProfile::new("chibi")
+            .with_ranges(0.5, 1.0)
+            .with_corner_bias(-0.5);
+        let tl = compose_facial_timeline("face", &lip, Some(&chibi), &expr, &rules);
+        assert_eq!(tl.get_value("mouth.openness", 0.0), Some(0.5));
+        assert_eq!(tl.get_value("mouth.corner", 1.0), Some(0.5));
+    }
+}
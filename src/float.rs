@@ -0,0 +1,46 @@
+//! `f32` math for `no_std` builds, backed by `libm`.
+//!
+//! With `std` the inherent `f32` methods are used; without it, core modules import [`Float`]
+//! and the same method calls resolve here instead. Inherent methods win whenever any
+//! dependency links `std`, so on hosted targets the import is unused and allowed to be.
+
+pub(crate) trait Float {
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn tan(self) -> Self;
+    fn ceil(self) -> Self;
+    fn hypot(self, other: Self) -> Self;
+    fn mul_add(self, a: Self, b: Self) -> Self;
+}
+
+impl Float for f32 {
+    #[inline]
+    fn sin(self) -> f32 {
+        libm::sinf(self)
+    }
+
+    #[inline]
+    fn cos(self) -> f32 {
+        libm::cosf(self)
+    }
+
+    #[inline]
+    fn tan(self) -> f32 {
+        libm::tanf(self)
+    }
+
+    #[inline]
+    fn ceil(self) -> f32 {
+        libm::ceilf(self)
+    }
+
+    #[inline]
+    fn hypot(self, other: f32) -> f32 {
+        libm::hypotf(self, other)
+    }
+
+    #[inline]
+    fn mul_add(self, a: f32, b: f32) -> f32 {
+        libm::fmaf(self, a, b)
+    }
+}
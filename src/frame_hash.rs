@@ -0,0 +1,292 @@
+//! Deterministic frame hashing for golden tests and render-farm verification.
+//!
+//! Hashes are FNV-1a 64 over a fixed byte layout, so they are stable across runs,
+//! platforms and compiler versions (unlike `std::hash`).
+
+use std::io::{self, BufRead, Write};
+
+use alice_sdf::SdfNode;
+
+use crate::episode::EpisodePackage;
+use crate::export::{render_range, FrameRange};
+use crate::framebuffer::Framebuffer;
+use crate::render::Renderer;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Streaming FNV-1a 64 hasher.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(FNV_OFFSET)
+    }
+}
+
+impl StableHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    #[inline]
+    pub fn write_u32(&mut self, v: u32) {
+        self.write(&v.to_le_bytes());
+    }
+
+    /// Hash a float by value: `-0.0` equals `0.0` and every NaN is the same.
+    #[inline]
+    pub fn write_f32(&mut self, v: f32) {
+        let bits = if v == 0.0 {
+            0
+        } else if v.is_nan() {
+            0x7fc0_0000
+        } else {
+            v.to_bits()
+        };
+        self.write_u32(bits);
+    }
+
+    #[inline]
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// How strictly pixels are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HashPrecision {
+    /// Bit-exact linear floats (color and AOVs).
+    #[default]
+    Exact,
+    /// 8-bit sRGB color only; tolerant of float noise between platforms.
+    Rgba8,
+}
+
+/// Hash a framebuffer's dimensions and contents.
+pub fn hash_framebuffer(frame: &Framebuffer, precision: HashPrecision) -> u64 {
+    let mut h = StableHasher::new();
+    h.write_u32(frame.width);
+    h.write_u32(frame.height);
+    match precision {
+        HashPrecision::Exact => {
+            for px in &frame.color {
+                px.iter().for_each(|&v| h.write_f32(v));
+            }
+            for aov in &frame.aovs {
+                h.write(aov.name.as_bytes());
+                h.write_u32(aov.channels as u32);
+                aov.data.iter().for_each(|&v| h.write_f32(v));
+            }
+        }
+        HashPrecision::Rgba8 => h.write(&frame.to_rgba8()),
+    }
+    h.finish()
+}
+
+/// Hash an evaluated SDF tree via its bincode encoding.
+pub fn hash_sdf(sdf: &SdfNode) -> io::Result<u64> {
+    let bytes =
+        bincode::serialize(sdf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut h = StableHasher::new();
+    h.write(&bytes);
+    Ok(h.finish())
+}
+
+/// Hashes of one rendered frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHash {
+    /// Absolute episode frame number.
+    pub frame: u32,
+    /// Hash of the evaluated scene SDF.
+    pub sdf: u64,
+    /// Hash of the rendered pixels.
+    pub pixels: u64,
+}
+
+/// Render `range` and hash every frame and its evaluated scene.
+pub fn hash_range(
+    renderer: &Renderer,
+    episode: &EpisodePackage,
+    range: &FrameRange,
+    precision: HashPrecision,
+) -> io::Result<Vec<FrameHash>> {
+    let mut hashes = Vec::with_capacity(range.frame_count() as usize);
+    render_range(
+        renderer,
+        episode,
+        range,
+        |index, frame| {
+            let sdf = episode
+                .director
+                .evaluate_scene(&episode.scene_graph, range.frame_time(index));
+            hashes.push(FrameHash {
+                frame: range.frame_number(index),
+                sdf: hash_sdf(&sdf)?,
+                pixels: hash_framebuffer(frame, precision),
+            });
+            Ok(())
+        },
+        |_| {},
+    )?;
+    Ok(hashes)
+}
+
+/// A frame whose hashes differ from the golden set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashMismatch {
+    pub frame: u32,
+    /// `None` if the frame is missing on that side.
+    pub expected: Option<FrameHash>,
+    pub actual: Option<FrameHash>,
+}
+
+impl HashMismatch {
+    /// The scene itself changed (not just how it was rendered).
+    pub fn scene_changed(&self) -> bool {
+        match (self.expected, self.actual) {
+            (Some(e), Some(a)) => e.sdf != a.sdf,
+            _ => true,
+        }
+    }
+}
+
+/// Compare golden hashes against a new run, frame by frame.
+pub fn compare_hashes(expected: &[FrameHash], actual: &[FrameHash]) -> Vec<HashMismatch> {
+    let mut frames: Vec<u32> = expected.iter().chain(actual).map(|h| h.frame).collect();
+    frames.sort_unstable();
+    frames.dedup();
+    frames
+        .into_iter()
+        .filter_map(|frame| {
+            let e = expected.iter().find(|h| h.frame == frame).copied();
+            let a = actual.iter().find(|h| h.frame == frame).copied();
+            (e != a).then_some(HashMismatch {
+                frame,
+                expected: e,
+                actual: a,
+            })
+        })
+        .collect()
+}
+
+/// Write hashes as a text manifest, one `frame sdf pixels` line each (hex hashes), for
+/// checking into version control.
+pub fn write_hash_manifest<W: Write>(hashes: &[FrameHash], writer: &mut W) -> io::Result<()> {
+    for h in hashes {
+        writeln!(writer, "{} {:016x} {:016x}", h.frame, h.sdf, h.pixels)?;
+    }
+    Ok(())
+}
+
+/// Read a manifest written by `write_hash_manifest`; blank lines and `#` comments are skipped.
+pub fn read_hash_manifest<R: BufRead>(reader: R) -> io::Result<Vec<FrameHash>> {
+    let mut hashes = Vec::new();
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Invalid hash manifest line {}: {}", n + 1, line),
+            )
+        };
+        let mut fields = line.split_whitespace();
+        let (Some(frame), Some(sdf), Some(pixels), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(invalid());
+        };
+        hashes.push(FrameHash {
+            frame: frame.parse().map_err(|_| invalid())?,
+            sdf: u64::from_str_radix(sdf, 16).map_err(|_| invalid())?,
+            pixels: u64::from_str_radix(pixels, 16).map_err(|_| invalid())?,
+        });
+    }
+    Ok(hashes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framebuffer_hash_is_stable() {
+        let mut a = Framebuffer::new(4, 4);
+        a.fill([0.25, 0.5, 0.75, 1.0]);
+        let mut b = a.clone();
+        assert_eq!(
+            hash_framebuffer(&a, HashPrecision::Exact),
+            hash_framebuffer(&b, HashPrecision::Exact)
+        );
+        // Known FNV-1a value of the empty input
+        assert_eq!(StableHasher::new().finish(), 0xcbf2_9ce4_8422_2325);
+
+        // Sub-quantum noise only shows up in exact mode
+        b.set_pixel(1, 1, [0.25 + 1e-6, 0.5, 0.75, 1.0]);
+        assert_ne!(
+            hash_framebuffer(&a, HashPrecision::Exact),
+            hash_framebuffer(&b, HashPrecision::Exact)
+        );
+        assert_eq!(
+            hash_framebuffer(&a, HashPrecision::Rgba8),
+            hash_framebuffer(&b, HashPrecision::Rgba8)
+        );
+        a.add_aov("depth", 1);
+        assert_ne!(
+            hash_framebuffer(&a, HashPrecision::Exact),
+            hash_framebuffer(&b, HashPrecision::Exact)
+        );
+    }
+
+    #[test]
+    fn test_golden_range_roundtrip() {
+        use crate::director::{Cut, Director};
+        use crate::episode::EpisodeMetadata;
+        use crate::npr::AnimeShading;
+        use crate::scene::{Actor, SceneGraph};
+
+        let mut sg = SceneGraph::new();
+        let ball = sg.add_actor(Actor::new("ball", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Golden");
+        dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        let mut episode = EpisodePackage::new(
+            EpisodeMetadata::new("Golden", 1, 1.0),
+            sg,
+            dir,
+            AnimeShading::default(),
+        );
+        let renderer = Renderer::new(8, 8);
+        let range = FrameRange::new(0.0, 0.25, 12.0);
+        let golden = hash_range(&renderer, &episode, &range, HashPrecision::Exact).unwrap();
+        assert_eq!(golden.len(), 3);
+        assert_eq!(
+            golden,
+            hash_range(&renderer, &episode, &range, HashPrecision::Exact).unwrap()
+        );
+
+        let mut manifest = Vec::new();
+        write_hash_manifest(&golden, &mut manifest).unwrap();
+        let read = read_hash_manifest(manifest.as_slice()).unwrap();
+        assert_eq!(read, golden);
+        assert!(read_hash_manifest("0 zz 00".as_bytes()).is_err());
+
+        episode.scene_graph.get_actor_mut(ball).unwrap().base_sdf = SdfNode::sphere(1.2);
+        let changed = hash_range(&renderer, &episode, &range, HashPrecision::Exact).unwrap();
+        let diff = compare_hashes(&read, &changed[..2]);
+        assert_eq!(diff.len(), 3);
+        assert!(diff.iter().all(|m| m.scene_changed()));
+        assert_eq!(diff[2].actual, None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Frame-rate conversion that keeps held drawings held.
+//!
+//! Limited animation is timed at 24 fps with drawings held on twos and threes. Converting
+//! baked output to 60 or 120 fps by interpolating every channel would invent inbetweens
+//! inside those holds; a [`FrameRateConverter`] instead repeats each source frame for as
+//! long as it covers, so every hold keeps its exact length. Camera channels, usually moved
+//! on ones, can optionally be interpolated for smooth pans.
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+use crate::camera::CameraTrack;
+use crate::export::FrameRange;
+
+/// How target frames between two source frames are filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+    /// Repeat the source frame on screen at that instant.
+    #[default]
+    Hold,
+    /// Blend neighbouring source frames, except across a change between two holds.
+    Linear,
+}
+
+/// Converts per-frame values from one frame rate to another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameRateConverter {
+    pub from_fps: f32,
+    pub to_fps: f32,
+    /// Source frames a value must stay for to count as a hold.
+    pub min_hold: u32,
+    /// Values closer than this are the same drawing.
+    pub tolerance: f32,
+    /// Interpolation for camera tracks; everything else holds.
+    pub camera: Interpolation,
+}
+
+impl FrameRateConverter {
+    pub fn new(from_fps: f32, to_fps: f32) -> Self {
+        Self {
+            from_fps,
+            to_fps,
+            min_hold: 2,
+            tolerance: 1e-5,
+            camera: Interpolation::Hold,
+        }
+    }
+
+    pub fn with_min_hold(mut self, frames: u32) -> Self {
+        self.min_hold = frames;
+        self
+    }
+
+    /// Interpolate camera moves instead of holding them.
+    pub fn with_camera_smoothing(mut self) -> Self {
+        self.camera = Interpolation::Linear;
+        self
+    }
+
+    /// Values of source frames `0, 1, ...` resampled to the target rate over the same span.
+    pub fn resample(&self, frames: &[f32], interpolation: Interpolation) -> Vec<f32> {
+        if frames.is_empty() || self.from_fps <= 0.0 || self.to_fps <= 0.0 {
+            return Vec::new();
+        }
+        let span = frames.len() as f32 / self.from_fps;
+        let count = FrameRange::new(0.0, span, self.to_fps).frame_count();
+        let runs = run_lengths(frames, self.tolerance);
+        let last = frames.len() - 1;
+        (0..count)
+            .map(|j| {
+                // Tolerance keeps target frames that land on a source frame from rounding down
+                let position = j as f32 * self.from_fps / self.to_fps + 1e-4;
+                let i = (position.floor() as usize).min(last);
+                let fraction = position - i as f32 - 1e-4;
+                let (a, b) = (frames[i], frames[(i + 1).min(last)]);
+                let stepped = runs[i] >= self.min_hold && runs[(i + 1).min(last)] >= self.min_hold;
+                match interpolation {
+                    Interpolation::Linear if fraction > 1e-4 && !stepped => a + (b - a) * fraction,
+                    _ => a,
+                }
+            })
+            .collect()
+    }
+
+    /// `track` sampled at the source rate over `[start, end)` and resampled, keyed at the
+    /// target rate.
+    pub fn convert_track(
+        &self,
+        track: &Track,
+        start: f32,
+        end: f32,
+        interpolation: Interpolation,
+    ) -> Track {
+        let source = FrameRange::new(start, end, self.from_fps);
+        let frames: Vec<f32> = (0..source.frame_count())
+            .map(|i| track.evaluate(source.frame_time(i)))
+            .collect();
+        let mut converted = Track::new(&track.name);
+        for (j, value) in self
+            .resample(&frames, interpolation)
+            .into_iter()
+            .enumerate()
+        {
+            let time = start + j as f32 / self.to_fps;
+            converted.add_keyframe(Keyframe::new(time, value));
+        }
+        converted
+    }
+
+    /// Every track of `timeline` held at the target rate over `[start, end)`.
+    pub fn convert_timeline(&self, timeline: &Timeline, start: f32, end: f32) -> Timeline {
+        self.convert_with(timeline, start, end, Interpolation::Hold)
+    }
+
+    /// A camera track (keys in its own time) converted over `[start, end)`, interpolated
+    /// if camera smoothing is on.
+    pub fn convert_camera(&self, camera: &CameraTrack, start: f32, end: f32) -> CameraTrack {
+        let mut converted = camera.clone();
+        converted.position_timeline =
+            self.convert_with(&camera.position_timeline, start, end, self.camera);
+        converted.target_timeline =
+            self.convert_with(&camera.target_timeline, start, end, self.camera);
+        converted.orientation_timeline =
+            self.convert_with(&camera.orientation_timeline, start, end, self.camera);
+        converted.fov_track = self.convert_track(&camera.fov_track, start, end, self.camera);
+        converted
+    }
+
+    fn convert_with(
+        &self,
+        timeline: &Timeline,
+        start: f32,
+        end: f32,
+        interpolation: Interpolation,
+    ) -> Timeline {
+        let mut converted = Timeline::new(&timeline.name);
+        for track in &timeline.tracks {
+            converted.add_track(self.convert_track(track, start, end, interpolation));
+        }
+        converted
+    }
+}
+
+/// Length of the run of equal values each frame belongs to.
+fn run_lengths(frames: &[f32], tolerance: f32) -> Vec<u32> {
+    let mut runs = vec![0; frames.len()];
+    let mut start = 0;
+    for i in 1..=frames.len() {
+        if i == frames.len() || (frames[i] - frames[start]).abs() > tolerance {
+            runs[start..i].fill((i - start) as u32);
+            start = i;
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_holds_keep_their_length() {
+        // A drawing change on twos, then threes, at 24 fps
+        let frames = [0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0];
+        let to_60 = FrameRateConverter::new(24.0, 60.0);
+        let out = to_60.resample(&frames, Interpolation::Hold);
+        assert_eq!(out.len(), 25);
+        let count = |v: f32| out.iter().filter(|&&x| x == v).count();
+        assert_eq!((count(0.0), count(1.0), count(2.0)), (5, 5, 8));
+
+        // Linear still steps between holds: no value the source never showed
+        let out = FrameRateConverter::new(24.0, 120.0).resample(&frames, Interpolation::Linear);
+        assert_eq!(out.len(), 50);
+        assert!(out.iter().all(|v| v.fract() == 0.0));
+        assert_eq!(out.iter().filter(|&&x| x == 2.0).count(), 15);
+    }
+
+    #[test]
+    fn test_camera_smoothing_only_on_camera() {
+        let mut pan = Track::new("position.x");
+        pan.add_keyframe(Keyframe::new(0.0, 0.0));
+        pan.add_keyframe(Keyframe::new(1.0, 24.0));
+        let mut camera = CameraTrack::default();
+        camera.position_timeline.tracks[0] = pan.clone();
+
+        let held = FrameRateConverter::new(24.0, 60.0);
+        let smooth = held.with_camera_smoothing();
+        let x = |c: &CameraTrack| c.position_timeline.tracks[0].keyframes[1].value;
+        // 1/60 s into a pan moving one unit per source frame
+        assert_eq!(x(&held.convert_camera(&camera, 0.0, 1.0)), 0.0);
+        assert!((x(&smooth.convert_camera(&camera, 0.0, 1.0)) - 0.4).abs() < 1e-4);
+        assert_eq!(
+            smooth
+                .convert_camera(&camera, 0.0, 1.0)
+                .fov_track
+                .keyframes
+                .len(),
+            60
+        );
+
+        let mut tl = Timeline::new("hero");
+        tl.add_track(pan);
+        let actor = smooth.convert_timeline(&tl, 0.0, 1.0);
+        assert_eq!(actor.tracks[0].keyframes[1].value, 0.0);
+        assert!((actor.tracks[0].keyframes[59].time - 59.0 / 60.0).abs() < 1e-5);
+    }
+}
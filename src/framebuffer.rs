@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+
+/// Auxiliary per-pixel output plane (depth, normals, IDs, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AovBuffer {
+    pub name: String,
+    /// Floats per pixel (1 for scalars, 3 for vectors).
+    pub channels: usize,
+    /// Row-major, `width * height * channels` values.
+    pub data: Vec<f32>,
+}
+
+impl AovBuffer {
+    /// Values of one pixel.
+    #[inline]
+    pub fn sample(&self, index: usize) -> &[f32] {
+        &self.data[index * self.channels..(index + 1) * self.channels]
+    }
+
+    /// Overwrite one pixel.
+    #[inline]
+    pub fn set(&mut self, index: usize, values: &[f32]) {
+        self.data[index * self.channels..(index + 1) * self.channels].copy_from_slice(values);
+    }
+}
+
+/// Rendered frame: linear RGBA color plus optional AOV planes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Framebuffer {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major linear RGBA, top row first.
+    pub color: Vec<[f32; 4]>,
+    /// `color` has premultiplied alpha (transparent renders).
+    pub premultiplied: bool,
+    pub aovs: Vec<AovBuffer>,
+}
+
+impl Framebuffer {
+    /// Transparent black frame.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            color: vec![[0.0; 4]; width as usize * height as usize],
+            premultiplied: false,
+            aovs: Vec::new(),
+        }
+    }
+
+    /// Number of pixels.
+    #[inline]
+    pub fn pixel_count(&self) -> usize {
+        self.color.len()
+    }
+
+    /// Row-major pixel index.
+    #[inline]
+    pub fn index(&self, x: u32, y: u32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    #[inline]
+    pub fn pixel(&self, x: u32, y: u32) -> [f32; 4] {
+        self.color[self.index(x, y)]
+    }
+
+    #[inline]
+    pub fn set_pixel(&mut self, x: u32, y: u32, rgba: [f32; 4]) {
+        let i = self.index(x, y);
+        self.color[i] = rgba;
+    }
+
+    /// Copy a row-major block of `width`-pixel rows with its top-left at (`x`, `y`).
+    pub fn write_rect(&mut self, x: u32, y: u32, width: u32, pixels: &[[f32; 4]]) {
+        if width == 0 {
+            return;
+        }
+        for (row, src) in pixels.chunks_exact(width as usize).enumerate() {
+            let start = self.index(x, y + row as u32);
+            self.color[start..start + src.len()].copy_from_slice(src);
+        }
+    }
+
+    /// Copy `src` (color and AOV planes) with its top-left at (`x`, `y`).
+    /// AOV planes missing here are created.
+    pub fn blit(&mut self, src: &Framebuffer, x: u32, y: u32) {
+        self.write_rect(x, y, src.width, &src.color);
+        for aov in &src.aovs {
+            if self.aov(&aov.name).is_none() {
+                self.add_aov(aov.name.clone(), aov.channels);
+            }
+            let width = self.width as usize;
+            let dst = self.aov_mut(&aov.name).unwrap();
+            let row_len = src.width as usize * aov.channels;
+            if row_len == 0 {
+                continue;
+            }
+            for (row, values) in aov.data.chunks_exact(row_len).enumerate() {
+                let start = ((y as usize + row) * width + x as usize) * aov.channels;
+                dst.data[start..start + row_len].copy_from_slice(values);
+            }
+        }
+    }
+
+    /// Copy out a `width` x `height` block (color and AOVs) starting at (`x`, `y`),
+    /// clipped to the frame.
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Framebuffer {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let (width, height) = (width.min(self.width - x), height.min(self.height - y));
+        let mut out = Framebuffer::new(width, height);
+        out.premultiplied = self.premultiplied;
+        for row in 0..height {
+            let start = self.index(x, y + row);
+            let dst = out.index(0, row);
+            out.color[dst..dst + width as usize]
+                .copy_from_slice(&self.color[start..start + width as usize]);
+        }
+        for aov in &self.aovs {
+            let ch = aov.channels;
+            let plane = out.add_aov(aov.name.clone(), ch);
+            for row in 0..height as usize {
+                let start = ((y as usize + row) * self.width as usize + x as usize) * ch;
+                let dst = row * width as usize * ch;
+                plane.data[dst..dst + width as usize * ch]
+                    .copy_from_slice(&aov.data[start..start + width as usize * ch]);
+            }
+        }
+        out
+    }
+
+    /// Fill every pixel with one color.
+    pub fn fill(&mut self, rgba: [f32; 4]) {
+        self.color.fill(rgba);
+    }
+
+    /// Add (or reset) a zero-filled AOV plane and return it.
+    pub fn add_aov(&mut self, name: impl Into<String>, channels: usize) -> &mut AovBuffer {
+        let name = name.into();
+        let data = vec![0.0; self.pixel_count() * channels];
+        match self.aovs.iter().position(|a| a.name == name) {
+            Some(i) => {
+                self.aovs[i] = AovBuffer {
+                    name,
+                    channels,
+                    data,
+                };
+                &mut self.aovs[i]
+            }
+            None => {
+                self.aovs.push(AovBuffer {
+                    name,
+                    channels,
+                    data,
+                });
+                self.aovs.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// Find an AOV plane by name.
+    pub fn aov(&self, name: &str) -> Option<&AovBuffer> {
+        self.aovs.iter().find(|a| a.name == name)
+    }
+
+    /// Find a mutable AOV plane by name.
+    pub fn aov_mut(&mut self, name: &str) -> Option<&mut AovBuffer> {
+        self.aovs.iter_mut().find(|a| a.name == name)
+    }
+
+    /// 8-bit sRGB RGBA bytes with straight alpha (alpha stays linear), for display or PNG.
+    pub fn to_rgba8(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.pixel_count() * 4);
+        for px in &self.color {
+            let rcp_alpha = if self.premultiplied && px[3] > 0.0 {
+                1.0 / px[3]
+            } else {
+                1.0
+            };
+            out.push(encode_srgb(px[0] * rcp_alpha));
+            out.push(encode_srgb(px[1] * rcp_alpha));
+            out.push(encode_srgb(px[2] * rcp_alpha));
+            out.push((px[3].clamp(0.0, 1.0) * 255.0 + 0.5) as u8);
+        }
+        out
+    }
+}
+
+/// Linear to 8-bit sRGB.
+#[inline]
+pub fn encode_srgb(linear: f32) -> u8 {
+    let c = linear.clamp(0.0, 1.0);
+    let s = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (s * 255.0 + 0.5) as u8
+}
+
+/// 8-bit sRGB to linear.
+#[inline]
+pub fn decode_srgb(encoded: u8) -> f32 {
+    let s = encoded as f32 / 255.0;
+    if s <= 0.040_45 {
+        s / 12.92
+    } else {
+        ((s + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_framebuffer_pixels() {
+        let mut fb = Framebuffer::new(4, 2);
+        assert_eq!(fb.pixel_count(), 8);
+        fb.set_pixel(3, 1, [1.0, 0.5, 0.0, 1.0]);
+        assert_eq!(fb.pixel(3, 1), [1.0, 0.5, 0.0, 1.0]);
+        assert_eq!(fb.color[7], [1.0, 0.5, 0.0, 1.0]);
+
+        let rgba = fb.to_rgba8();
+        assert_eq!(&rgba[28..32], &[255, 188, 0, 255]);
+        assert_eq!(&rgba[0..4], &[0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_aov_planes() {
+        let mut fb = Framebuffer::new(2, 2);
+        fb.add_aov("depth", 1).set(3, &[4.5]);
+        fb.add_aov("normal", 3).set(1, &[0.0, 1.0, 0.0]);
+        assert_eq!(fb.aov("depth").unwrap().sample(3), &[4.5]);
+        assert_eq!(fb.aov("normal").unwrap().data.len(), 12);
+
+        // Re-adding resets the plane
+        fb.add_aov("depth", 1);
+        assert_eq!(fb.aovs.len(), 2);
+        assert_eq!(fb.aov("depth").unwrap().sample(3), &[0.0]);
+    }
+
+    #[test]
+    fn test_blit() {
+        let mut tile = Framebuffer::new(2, 2);
+        tile.fill([1.0; 4]);
+        tile.add_aov("depth", 1).data.fill(3.0);
+        let mut fb = Framebuffer::new(4, 3);
+        fb.blit(&tile, 2, 1);
+        assert_eq!(fb.pixel(3, 2), [1.0; 4]);
+        assert_eq!(fb.pixel(1, 1), [0.0; 4]);
+        let depth = fb.aov("depth").unwrap();
+        assert_eq!(depth.sample(fb.index(2, 1)), &[3.0]);
+        assert_eq!(depth.sample(fb.index(1, 2)), &[0.0]);
+
+        // Cropping the blitted block gives the tile back
+        assert_eq!(fb.crop(2, 1, 2, 2), tile);
+        assert_eq!(fb.crop(3, 2, 8, 8).pixel_count(), 1);
+    }
+
+    #[test]
+    fn test_premultiplied_to_rgba8() {
+        let mut fb = Framebuffer::new(1, 1);
+        fb.set_pixel(0, 0, [0.5, 0.0, 0.0, 0.5]);
+        fb.premultiplied = true;
+        assert_eq!(fb.to_rgba8(), vec![255, 0, 0, 128]);
+        fb.premultiplied = false;
+        assert_eq!(fb.to_rgba8()[0], encode_srgb(0.5));
+    }
+
+    #[test]
+    fn test_srgb_roundtrip() {
+        for v in [0u8, 1, 64, 128, 200, 255] {
+            assert_eq!(encode_srgb(decode_srgb(v)), v);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+use crate::scene::Actor;
+
+/// One analysis frame of speech prosody.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProsodyFrame {
+    /// Loudness (any linear scale; normalized internally).
+    pub energy: f32,
+    /// Fundamental frequency in Hz (0.0 = unvoiced).
+    pub f0: f32,
+}
+
+/// Gesture amplitudes and timing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureConfig {
+    /// Peak head nod (radians, "head.nod") on a fully stressed syllable.
+    pub nod_amount: f32,
+    /// Normalized energy a peak must exceed to trigger a nod.
+    pub nod_threshold: f32,
+    /// Minimum seconds between nods.
+    pub nod_min_gap: f32,
+    /// Head tilt (radians, "head.tilt") per octave above/below the speaker's median pitch.
+    pub tilt_amount: f32,
+    /// Shoulder raise ("shoulders.raise") at full sustained energy.
+    pub shoulder_amount: f32,
+    /// Seconds between tilt/shoulder samples.
+    pub sample_interval: f32,
+}
+
+impl Default for GestureConfig {
+    fn default() -> Self {
+        Self {
+            nod_amount: 0.08,
+            nod_threshold: 0.6,
+            nod_min_gap: 0.4,
+            tilt_amount: 0.1,
+            shoulder_amount: 0.03,
+            sample_interval: 0.1,
+        }
+    }
+}
+
+/// Moving average over `radius` frames on each side.
+fn smooth(values: &[f32], radius: usize) -> Vec<f32> {
+    (0..values.len())
+        .map(|i| {
+            let lo = i.saturating_sub(radius);
+            let hi = (i + radius + 1).min(values.len());
+            values[lo..hi].iter().sum::<f32>() / (hi - lo) as f32
+        })
+        .collect()
+}
+
+/// Generate subtle head and shoulder motion from speech energy and pitch contours.
+///
+/// - "head.nod": short dips on stressed syllables (energy peaks).
+/// - "head.tilt": follows the smoothed pitch contour relative to the median pitch.
+/// - "shoulders.raise": follows the slow energy envelope.
+///
+/// All tracks start and end at 0.0 so they can be layered additively.
+pub fn generate_gestures(
+    frames: &[ProsodyFrame],
+    frame_duration: f32,
+    config: &GestureConfig,
+) -> Timeline {
+    let mut tl = Timeline::new("gestures");
+    if frames.is_empty() || frame_duration <= 0.0 {
+        return tl;
+    }
+    let end = frames.len() as f32 * frame_duration;
+
+    let max_energy = frames.iter().map(|f| f.energy).fold(0.0f32, f32::max);
+    let energy: Vec<f32> = frames
+        .iter()
+        .map(|f| {
+            if max_energy > 0.0 {
+                f.energy / max_energy
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    // Nods on local energy peaks
+    let mut nod = Track::new("head.nod");
+    nod.add_keyframe(Keyframe::new(0.0, 0.0));
+    let smoothed_energy = smooth(&energy, 2);
+    let mut last_nod = f32::NEG_INFINITY;
+    for i in 1..smoothed_energy.len().saturating_sub(1) {
+        let e = smoothed_energy[i];
+        let t = i as f32 * frame_duration;
+        if e > config.nod_threshold
+            && e >= smoothed_energy[i - 1]
+            && e > smoothed_energy[i + 1]
+            && t - last_nod >= config.nod_min_gap
+        {
+            let rise = (t - 0.1).max(0.0);
+            let fall = (t + 0.25).min(end);
+            if rise > 0.0 {
+                nod.add_keyframe(Keyframe::new(rise, 0.0));
+            }
+            nod.add_keyframe(Keyframe::new(t, config.nod_amount * e));
+            nod.add_keyframe(Keyframe::new(fall, 0.0));
+            last_nod = t;
+        }
+    }
+    nod.add_keyframe(Keyframe::new(end, 0.0));
+
+    // Tilt from pitch (octaves relative to median voiced pitch), held through unvoiced frames
+    let mut voiced: Vec<f32> = frames.iter().map(|f| f.f0).filter(|&f| f > 0.0).collect();
+    voiced.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median = voiced.get(voiced.len() / 2).copied().unwrap_or(0.0);
+    let mut held = 0.0f32;
+    let octaves: Vec<f32> = frames
+        .iter()
+        .map(|f| {
+            if f.f0 > 0.0 && median > 0.0 {
+                held = (f.f0 / median).log2().clamp(-1.0, 1.0);
+            }
+            held
+        })
+        .collect();
+    let step = ((config.sample_interval / frame_duration).round() as usize).max(1);
+    let smoothed_pitch = smooth(&octaves, step);
+    let envelope = smooth(&energy, step * 5);
+
+    let mut tilt = Track::new("head.tilt");
+    let mut shoulders = Track::new("shoulders.raise");
+    tilt.add_keyframe(Keyframe::new(0.0, 0.0));
+    shoulders.add_keyframe(Keyframe::new(0.0, 0.0));
+    for i in (step..frames.len()).step_by(step) {
+        let t = i as f32 * frame_duration;
+        tilt.add_keyframe(Keyframe::new(t, config.tilt_amount * smoothed_pitch[i]));
+        shoulders.add_keyframe(Keyframe::new(t, config.shoulder_amount * envelope[i]));
+    }
+    tilt.add_keyframe(Keyframe::new(end, 0.0));
+    shoulders.add_keyframe(Keyframe::new(end, 0.0));
+
+    tl.add_track(nod);
+    tl.add_track(tilt);
+    tl.add_track(shoulders);
+    tl
+}
+
+/// Add `layer` onto `target`, offset by `start_time`.
+///
+/// Tracks present in both are summed at the union of their keyframe times;
+/// tracks only in `layer` are copied.
+pub fn layer_additive(target: &mut Timeline, layer: &Timeline, start_time: f32) {
+    for layer_track in &layer.tracks {
+        let mut shifted = Track::new(&layer_track.name);
+        for kf in &layer_track.keyframes {
+            shifted.add_keyframe(Keyframe::new(kf.time + start_time, kf.value));
+        }
+        match target
+            .tracks
+            .iter_mut()
+            .find(|t| t.name == layer_track.name)
+        {
+            Some(base) => {
+                let mut times: Vec<f32> = base
+                    .keyframes
+                    .iter()
+                    .chain(shifted.keyframes.iter())
+                    .map(|k| k.time)
+                    .collect();
+                times.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+                times.dedup_by(|a, b| (*a - *b).abs() < 1e-6);
+                let layer_start = shifted.keyframes.first().map(|k| k.time).unwrap_or(0.0);
+                let layer_end = shifted.keyframes.last().map(|k| k.time).unwrap_or(0.0);
+                let mut merged = Track::new(&base.name);
+                for t in times {
+                    // Outside the layer's range it contributes nothing
+                    let add = if t >= layer_start && t <= layer_end {
+                        shifted.evaluate(t)
+                    } else {
+                        0.0
+                    };
+                    merged.add_keyframe(Keyframe::new(t, base.evaluate(t) + add));
+                }
+                *base = merged;
+            }
+            None => target.add_track(shifted),
+        }
+    }
+}
+
+/// Generate gestures for a line of dialogue starting at `start_time` and layer them
+/// onto the speaking actor's timeline.
+pub fn apply_gestures(
+    actor: &mut Actor,
+    frames: &[ProsodyFrame],
+    frame_duration: f32,
+    start_time: f32,
+    config: &GestureConfig,
+) {
+    let layer = generate_gestures(frames, frame_duration, config);
+    let timeline = actor
+        .timeline
+        .get_or_insert_with(|| Timeline::new(&actor.name));
+    layer_additive(timeline, &layer, start_time);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::SdfNode;
+
+    const FRAME: f32 = 0.01;
+
+    /// Two stressed syllables on a rising pitch.
+    fn speech() -> Vec<ProsodyFrame> {
+        (0..200)
+            .map(|i| {
+                let t = i as f32 * FRAME;
+                let stress =
+                    (-((t - 0.5) * 10.0).powi(2)).exp() + (-((t - 1.4) * 10.0).powi(2)).exp();
+                ProsodyFrame {
+                    energy: 0.1 + stress,
+                    f0: 200.0 + 100.0 * t,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_nods_on_stress() {
+        let tl = generate_gestures(&speech(), FRAME, &GestureConfig::default());
+        let nod = tl.get_value("head.nod", 0.5).unwrap();
+        assert!(nod > 0.05);
+        assert!(tl.get_value("head.nod", 1.4).unwrap() > 0.05);
+        assert!(tl.get_value("head.nod", 1.0).unwrap().abs() < 1e-3);
+        // Pitch rises: tilt goes from below to above the median
+        assert!(tl.get_value("head.tilt", 0.3).unwrap() < tl.get_value("head.tilt", 1.7).unwrap());
+        assert_eq!(tl.get_value("shoulders.raise", 2.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_layered_onto_actor() {
+        let mut base = Timeline::new("hero");
+        let mut existing = Track::new("head.nod");
+        existing.add_keyframe(Keyframe::new(0.0, 0.2));
+        existing.add_keyframe(Keyframe::new(10.0, 0.2));
+        base.add_track(existing);
+        let mut actor = Actor::new("hero", SdfNode::sphere(1.0)).with_timeline(base);
+
+        apply_gestures(&mut actor, &speech(), FRAME, 3.0, &GestureConfig::default());
+        let tl = actor.timeline.as_ref().unwrap();
+        // Additive: base pose kept outside the line, nod added on top during it
+        assert!((tl.get_value("head.nod", 1.0).unwrap() - 0.2).abs() < 1e-5);
+        assert!(tl.get_value("head.nod", 3.5).unwrap() > 0.25);
+        assert!(tl.get_value("head.tilt", 4.0).is_some());
+    }
+}
@@ -0,0 +1,384 @@
+//! Anime hair: guide strands on spring dynamics, drawn as SDF clumps (tubes or
+//! flat wedges), with stylization controls for stiffness, gravity and held poses.
+
+use alice_sdf::SdfNode;
+use glam::{Quat, Vec3};
+
+use crate::cloth::{Socket, WindField};
+use crate::performance::Performance;
+
+/// Cross-section of the clump drawn around a guide strand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClumpShape {
+    /// Round tube tapering from `root_radius` to `tip_radius`.
+    Tube { root_radius: f32, tip_radius: f32 },
+    /// Flat blade (classic anime bang/spike) tapering from `root_width` to `tip_width`.
+    Wedge {
+        root_width: f32,
+        tip_width: f32,
+        thickness: f32,
+    },
+}
+
+impl ClumpShape {
+    /// Clump piece for the segment `a`-`b`, `t` being its position along the strand (0 = root).
+    fn segment(&self, a: Vec3, b: Vec3, t0: f32, t1: f32) -> SdfNode {
+        match *self {
+            ClumpShape::Tube {
+                root_radius,
+                tip_radius,
+            } => {
+                let t = 0.5 * (t0 + t1);
+                SdfNode::capsule(a, b, root_radius + (tip_radius - root_radius) * t)
+            }
+            ClumpShape::Wedge {
+                root_width,
+                tip_width,
+                thickness,
+            } => {
+                let t = 0.5 * (t0 + t1);
+                let half_width = 0.5 * (root_width + (tip_width - root_width) * t);
+                let axis = b - a;
+                let mid = a + axis * 0.5;
+                let rotation = Quat::from_rotation_arc(Vec3::Y, axis.normalize_or(Vec3::Y));
+                SdfNode::box3d(half_width, axis.length() * 0.5, thickness * 0.5)
+                    .rotate(rotation)
+                    .translate(mid.x, mid.y, mid.z)
+            }
+        }
+    }
+}
+
+/// Stylization shared by every strand of a rig.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HairStyle {
+    /// Pull back toward the groomed shape per step (0 = limp, 1 = rigid).
+    pub stiffness: f32,
+    /// Gravity multiplier; anime hair often falls harder than it should.
+    pub gravity_scale: f32,
+    /// Fraction of velocity kept per step.
+    pub damping: f32,
+    /// Displayed pose updates every this many frames (1 = every frame, 2 = on twos).
+    pub hold_frames: u32,
+    /// Frame rate `hold_frames` counts in.
+    pub fps: f32,
+    /// Fixed simulation step in seconds.
+    pub substep: f32,
+}
+
+impl Default for HairStyle {
+    fn default() -> Self {
+        Self {
+            stiffness: 0.15,
+            gravity_scale: 1.0,
+            damping: 0.95,
+            hold_frames: 1,
+            fps: 24.0,
+            substep: 1.0 / 120.0,
+        }
+    }
+}
+
+impl HairStyle {
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_gravity_scale(mut self, gravity_scale: f32) -> Self {
+        self.gravity_scale = gravity_scale;
+        self
+    }
+
+    pub fn with_hold_frames(mut self, hold_frames: u32, fps: f32) -> Self {
+        self.hold_frames = hold_frames.max(1);
+        self.fps = fps;
+        self
+    }
+}
+
+/// One guide strand rooted at a socket; `rest` points are in actor space relative to the root.
+#[derive(Debug, Clone)]
+pub struct GuideStrand {
+    pub root: Socket,
+    rest: Vec<Vec3>,
+    pub positions: Vec<Vec3>,
+    previous: Vec<Vec3>,
+    pub shape: ClumpShape,
+}
+
+impl GuideStrand {
+    /// Strand through groomed `points` (actor space, relative to the root; the root itself is added).
+    pub fn new(root: Socket, points: &[Vec3], shape: ClumpShape) -> Self {
+        let mut rest = Vec::with_capacity(points.len() + 1);
+        rest.push(Vec3::ZERO);
+        rest.extend_from_slice(points);
+        Self {
+            root,
+            positions: rest.clone(),
+            previous: rest.clone(),
+            rest,
+            shape,
+        }
+    }
+
+    /// Straight strand of `segments` links of total `length` along `direction`.
+    pub fn straight(
+        root: Socket,
+        direction: Vec3,
+        length: f32,
+        segments: usize,
+        shape: ClumpShape,
+    ) -> Self {
+        let segments = segments.max(1);
+        let step = direction.normalize_or(Vec3::NEG_Y) * (length / segments as f32);
+        let points: Vec<Vec3> = (1..=segments).map(|i| step * i as f32).collect();
+        Self::new(root, &points, shape)
+    }
+
+    /// Groomed world-space shape at `time`.
+    fn targets(&self, scene: Performance<'_>, time: f32) -> Vec<Vec3> {
+        let root = self.root.world_position(scene, time);
+        let world = scene.get_world_transform(self.root.actor);
+        self.rest
+            .iter()
+            .map(|p| root + world.rotation * (world.scale * *p))
+            .collect()
+    }
+
+    fn reset(&mut self, scene: Performance<'_>, time: f32) {
+        self.positions = self.targets(scene, time);
+        self.previous.clone_from(&self.positions);
+    }
+
+    fn step(
+        &mut self,
+        scene: Performance<'_>,
+        time: f32,
+        dt: f32,
+        style: &HairStyle,
+        wind: &WindField,
+    ) {
+        const GRAVITY: Vec3 = Vec3::new(0.0, -9.8, 0.0);
+        let targets = self.targets(scene, time);
+        let points = self
+            .positions
+            .iter_mut()
+            .zip(&mut self.previous)
+            .zip(&targets);
+        for ((position, previous), target) in points.skip(1) {
+            let p = *position;
+            let accel = GRAVITY * style.gravity_scale + wind.at(p, time);
+            let mut next = p + (p - *previous) * style.damping + accel * dt * dt;
+            next += (*target - next) * style.stiffness;
+            *previous = p;
+            *position = next;
+        }
+        self.positions[0] = targets[0];
+        // Inextensible links, solved root to tip so the root stays put
+        for i in 1..self.positions.len() {
+            let rest = self.rest[i].distance(self.rest[i - 1])
+                * scene
+                    .get_world_transform(self.root.actor)
+                    .scale
+                    .max_element();
+            let dir = (self.positions[i] - self.positions[i - 1]).normalize_or_zero();
+            if dir != Vec3::ZERO {
+                self.positions[i] = self.positions[i - 1] + dir * rest;
+            }
+        }
+    }
+
+    /// Clump SDF along `points` (one piece per segment).
+    fn clump(&self, points: &[Vec3]) -> Option<SdfNode> {
+        let segments = points.len().checked_sub(1).filter(|&n| n > 0)?;
+        (0..segments)
+            .map(|i| {
+                self.shape.segment(
+                    points[i],
+                    points[i + 1],
+                    i as f32 / segments as f32,
+                    (i + 1) as f32 / segments as f32,
+                )
+            })
+            .reduce(|acc, piece| acc.union(piece))
+    }
+}
+
+/// A head's worth of guide strands simulated together.
+#[derive(Debug, Clone)]
+pub struct HairRig {
+    pub strands: Vec<GuideStrand>,
+    pub style: HairStyle,
+    time: Option<f32>,
+    /// Pose shown to the renderer and the hold window it was taken in.
+    held: Vec<Vec<Vec3>>,
+    held_window: Option<i64>,
+}
+
+impl HairRig {
+    pub fn new(style: HairStyle) -> Self {
+        Self {
+            strands: Vec::new(),
+            style,
+            time: None,
+            held: Vec::new(),
+            held_window: None,
+        }
+    }
+
+    pub fn with_strand(mut self, strand: GuideStrand) -> Self {
+        self.strands.push(strand);
+        self
+    }
+
+    /// Groom every strand at `time`, discarding motion.
+    pub fn reset(&mut self, scene: Performance<'_>, time: f32) {
+        for strand in &mut self.strands {
+            strand.reset(scene, time);
+        }
+        self.time = Some(time);
+        self.hold(time, true);
+    }
+
+    fn hold(&mut self, time: f32, force: bool) {
+        let window =
+            (time * self.style.fps + 1e-3).floor() as i64 / self.style.hold_frames.max(1) as i64;
+        if force || self.held_window != Some(window) {
+            self.held = self.strands.iter().map(|s| s.positions.clone()).collect();
+            self.held_window = Some(window);
+        }
+    }
+
+    /// Simulate up to `time` in fixed substeps; the first call or a jump back grooms the hair.
+    pub fn advance_to(&mut self, scene: Performance<'_>, time: f32, wind: &WindField) {
+        let start = match self.time {
+            Some(t) if t <= time => t,
+            _ => return self.reset(scene, time),
+        };
+        let steps = ((time - start) / self.style.substep.max(1e-4)).ceil() as u32;
+        if steps > 0 {
+            let dt = (time - start) / steps as f32;
+            let style = self.style;
+            for i in 1..=steps {
+                let t = start + dt * i as f32;
+                for strand in &mut self.strands {
+                    strand.step(scene, t, dt, &style, wind);
+                }
+            }
+        }
+        self.time = Some(time);
+        self.hold(time, false);
+    }
+
+    /// Strand points as displayed, honoring `hold_frames`.
+    pub fn displayed(&self) -> &[Vec<Vec3>] {
+        &self.held
+    }
+
+    /// All clumps of the displayed pose as one SDF.
+    pub fn to_sdf(&self) -> Option<SdfNode> {
+        self.strands
+            .iter()
+            .zip(&self.held)
+            .filter_map(|(strand, points)| strand.clump(points))
+            .reduce(|acc, clump| acc.union(clump))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Actor, ActorId, SceneGraph};
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+    fn turning_head() -> (SceneGraph, ActorId) {
+        let mut sg = SceneGraph::new();
+        let mut tl = Timeline::new("dash");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(0.5, 2.0));
+        tl.add_track(x);
+        let id = sg.add_actor(Actor::new("head", SdfNode::sphere(0.5)).with_timeline(tl));
+        (sg, id)
+    }
+
+    fn ponytail(head: ActorId) -> GuideStrand {
+        GuideStrand::straight(
+            Socket::new(head, Vec3::new(0.0, 0.4, -0.4)),
+            Vec3::new(0.0, -1.0, -0.5),
+            0.8,
+            4,
+            ClumpShape::Tube {
+                root_radius: 0.08,
+                tip_radius: 0.02,
+            },
+        )
+    }
+
+    #[test]
+    fn test_strand_follows_head_and_keeps_length() {
+        let (sg, head) = turning_head();
+        let mut rig = HairRig::new(HairStyle::default()).with_strand(ponytail(head));
+        rig.advance_to(Performance::from(&sg), 0.0, &WindField::calm());
+        rig.advance_to(Performance::from(&sg), 0.25, &WindField::calm());
+        let strand = &rig.strands[0];
+        assert!((strand.positions[0] - Vec3::new(1.0, 0.4, -0.4)).length() < 1e-4);
+        // Tip drags behind the dash
+        assert!(strand.positions[4].x < 1.0);
+        for pair in strand.positions.windows(2) {
+            assert!((pair[0].distance(pair[1]) - 0.2).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_stiffness_and_gravity_styles() {
+        let (sg, head) = turning_head();
+        let settle = |style: HairStyle| {
+            let mut rig = HairRig::new(style).with_strand(ponytail(head));
+            rig.advance_to(Performance::from(&sg), 0.5, &WindField::calm());
+            rig.advance_to(Performance::from(&sg), 2.0, &WindField::calm());
+            rig.strands[0].positions[4]
+        };
+        let rigid = settle(HairStyle::default().with_stiffness(1.0));
+        let groomed = Vec3::new(2.0, 0.4, -0.4) + Vec3::new(0.0, -1.0, -0.5).normalize() * 0.8;
+        assert!((rigid - groomed).length() < 1e-3);
+
+        let limp = settle(HairStyle::default().with_stiffness(0.01));
+        let heavy = settle(
+            HairStyle::default()
+                .with_stiffness(0.01)
+                .with_gravity_scale(3.0),
+        );
+        assert!(limp.y < rigid.y);
+        assert!(heavy.y <= limp.y + 1e-4);
+    }
+
+    #[test]
+    fn test_hold_frames_and_sdf() {
+        let (sg, head) = turning_head();
+        let wedge = GuideStrand::straight(
+            Socket::new(head, Vec3::new(0.0, 0.5, 0.4)),
+            Vec3::new(0.0, -1.0, 0.3),
+            0.4,
+            2,
+            ClumpShape::Wedge {
+                root_width: 0.2,
+                tip_width: 0.02,
+                thickness: 0.03,
+            },
+        );
+        let style = HairStyle::default().with_hold_frames(2, 24.0);
+        let mut rig = HairRig::new(style).with_strand(wedge);
+        rig.advance_to(Performance::from(&sg), 0.0, &WindField::calm());
+        rig.advance_to(Performance::from(&sg), 1.0 / 24.0, &WindField::calm());
+        // Frame 1 is still inside the first hold window
+        assert_eq!(rig.displayed()[0][0], Vec3::new(0.0, 0.5, 0.4));
+        rig.advance_to(Performance::from(&sg), 2.0 / 24.0, &WindField::calm());
+        assert!(rig.displayed()[0][0].x > 0.0);
+
+        let sdf = rig.to_sdf().unwrap();
+        let inside = rig.displayed()[0][0].lerp(rig.displayed()[0][1], 0.25);
+        assert!(alice_sdf::eval(&sdf, inside) < 0.0);
+    }
+}
@@ -0,0 +1,380 @@
+//! Hot reload: poll episode and actor-template files for changes and work out which part
+//! of the timeline a reload actually touched, so a running player keeps its playhead and
+//! every cached frame outside that span.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+#[cfg(feature = "cache")]
+use crate::cache_bridge::AnimationCache;
+use crate::director::{Cut, CutId};
+use crate::episode::{deserialize_episode, EpisodePackage};
+use crate::error::AnimationError;
+use crate::scene::Actor;
+
+/// Detects file changes by polling modification time and length.
+#[derive(Debug, Clone)]
+pub struct FileWatcher {
+    path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+}
+
+impl FileWatcher {
+    /// Watch `path`; its current state counts as already seen.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let stamp = file_stamp(&path);
+        Self { path, stamp }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// True once per change since the last call. A missing file is not a change.
+    pub fn changed(&mut self) -> bool {
+        match file_stamp(&self.path) {
+            Some(stamp) if self.stamp != Some(stamp) => {
+                self.stamp = Some(stamp);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Forget the last stamp so the next `changed` fires again (e.g. after a failed read).
+    pub fn rearm(&mut self) {
+        self.stamp = None;
+    }
+}
+
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Re-deserializes an episode file whenever it changes on disk.
+#[derive(Debug, Clone)]
+pub struct EpisodeWatcher {
+    watcher: FileWatcher,
+}
+
+impl EpisodeWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            watcher: FileWatcher::new(path),
+        }
+    }
+
+    /// The new episode if the file changed since the last poll.
+    ///
+    /// A read or decode failure (typically a save still in progress) is returned and the
+    /// watcher re-arms, so the next poll tries again.
+    pub fn poll(&mut self) -> crate::error::Result<Option<EpisodePackage>> {
+        if !self.watcher.changed() {
+            return Ok(None);
+        }
+        let read = std::fs::File::open(self.watcher.path())
+            .map_err(AnimationError::from)
+            .and_then(|file| deserialize_episode(&mut io::BufReader::new(file)));
+        match read {
+            Ok(episode) => Ok(Some(episode)),
+            Err(e) => {
+                self.watcher.rearm();
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Re-reads an actor template file (see [`save_actor_template`]) whenever it changes.
+#[derive(Debug, Clone)]
+pub struct ActorWatcher {
+    watcher: FileWatcher,
+}
+
+impl ActorWatcher {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            watcher: FileWatcher::new(path),
+        }
+    }
+
+    /// The new actor if the file changed since the last poll; failures re-arm like
+    /// [`EpisodeWatcher::poll`].
+    pub fn poll(&mut self) -> crate::error::Result<Option<Actor>> {
+        if !self.watcher.changed() {
+            return Ok(None);
+        }
+        load_actor_template(self.watcher.path())
+            .map(Some)
+            .inspect_err(|_| {
+                self.watcher.rearm();
+            })
+    }
+}
+
+/// Write a single actor as a standalone template file (bincode).
+pub fn save_actor_template(path: impl AsRef<Path>, actor: &Actor) -> crate::error::Result<()> {
+    let bytes = bincode::serialize(actor)?;
+    Ok(std::fs::write(path, bytes)?)
+}
+
+/// Read an actor template written by [`save_actor_template`].
+pub fn load_actor_template(path: impl AsRef<Path>) -> crate::error::Result<Actor> {
+    let bytes = std::fs::read(path)?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+/// Which part of the timeline a reload invalidated.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReloadReport {
+    /// Scene graph, shading, metadata or episode structure changed: nothing cached is valid.
+    pub full: bool,
+    /// `[start, end)` spans of added, removed or edited cuts.
+    pub dirty_ranges: Vec<(f32, f32)>,
+}
+
+impl ReloadReport {
+    /// Nothing changed.
+    #[inline]
+    pub fn is_unchanged(&self) -> bool {
+        !self.full && self.dirty_ranges.is_empty()
+    }
+
+    /// True if a frame at `time` must be re-evaluated.
+    pub fn is_dirty(&self, time: f32) -> bool {
+        self.full
+            || self
+                .dirty_ranges
+                .iter()
+                .any(|&(start, end)| time >= start && time < end)
+    }
+
+    /// Drop the stale frames from `cache`; returns the number removed.
+    #[cfg(feature = "cache")]
+    pub fn invalidate(&self, cache: &mut AnimationCache) -> usize {
+        if self.full {
+            let count = cache.stats().frames;
+            cache.clear();
+            return count;
+        }
+        self.dirty_ranges
+            .iter()
+            .map(|&(start, end)| cache.invalidate_time_range(start, end))
+            .sum()
+    }
+}
+
+/// Compare two versions of an episode section by section.
+pub fn diff_episodes(
+    old: &EpisodePackage,
+    new: &EpisodePackage,
+) -> crate::error::Result<ReloadReport> {
+    let full = !same(&old.metadata, &new.metadata)?
+        || !same(&old.scene_graph, &new.scene_graph)?
+        || !same(&old.shading, &new.shading)?
+        || !same(&old.render_settings, &new.render_settings)?
+        || !same(&old.overlays, &new.overlays)?
+        || !same(&old.director.episode, &new.director.episode)?;
+    if full {
+        return Ok(ReloadReport {
+            full,
+            dirty_ranges: Vec::new(),
+        });
+    }
+
+    let mut dirty_ranges = Vec::new();
+    let old_cuts: Vec<(CutId, &Cut)> = old.director.cuts().collect();
+    let new_cuts: Vec<(CutId, &Cut)> = new.director.cuts().collect();
+    for &(id, cut) in &old_cuts {
+        match find_cut(&new_cuts, id) {
+            Some(edited) if same(cut, edited)? => {}
+            Some(edited) => {
+                dirty_ranges.push((cut.start_time, cut.end_time));
+                dirty_ranges.push((edited.start_time, edited.end_time));
+            }
+            None => dirty_ranges.push((cut.start_time, cut.end_time)),
+        }
+    }
+    for &(id, cut) in &new_cuts {
+        if find_cut(&old_cuts, id).is_none() {
+            dirty_ranges.push((cut.start_time, cut.end_time));
+        }
+    }
+    // Cuts inheriting their camera change with the cut before them
+    let mut previous_dirty = false;
+    for &(_, cut) in &new_cuts {
+        let mut dirty = dirty_ranges
+            .iter()
+            .any(|&(start, end)| start < cut.end_time && cut.start_time < end);
+        if !dirty && previous_dirty && cut.inherit_camera {
+            dirty_ranges.push((cut.start_time, cut.end_time));
+            dirty = true;
+        }
+        previous_dirty = dirty;
+    }
+    Ok(ReloadReport {
+        full,
+        dirty_ranges: merge_ranges(dirty_ranges),
+    })
+}
+
+/// Replace the actor named `name` with `template`, keeping its id, name and parent.
+///
+/// Only the cuts that list the actor are reported dirty; an actor no cut lists may show
+/// anywhere, so that is a full invalidation.
+pub fn swap_actor(
+    episode: &mut EpisodePackage,
+    name: &str,
+    template: Actor,
+) -> crate::error::Result<ReloadReport> {
+    let id = episode
+        .scene_graph
+        .find_by_name(name)
+        .ok_or_else(|| AnimationError::InvalidInput(format!("Unknown actor '{}'", name)))?;
+    let slot = episode
+        .scene_graph
+        .get_actor_mut(id)
+        .expect("find_by_name returned a live actor");
+    let template = Actor {
+        name: slot.name.clone(),
+        parent: slot.parent,
+        ..template
+    };
+    if same(slot, &template)? {
+        return Ok(ReloadReport::default());
+    }
+    *slot = template;
+
+    let dirty_ranges: Vec<(f32, f32)> = episode
+        .director
+        .cuts()
+        .filter(|(_, cut)| cut.active_actors.contains(&id))
+        .map(|(_, cut)| (cut.start_time, cut.end_time))
+        .collect();
+    Ok(ReloadReport {
+        full: dirty_ranges.is_empty(),
+        dirty_ranges: merge_ranges(dirty_ranges),
+    })
+}
+
+fn find_cut<'a>(cuts: &[(CutId, &'a Cut)], id: CutId) -> Option<&'a Cut> {
+    cuts.iter().find(|(c, _)| *c == id).map(|(_, cut)| *cut)
+}
+
+fn same<T: Serialize>(a: &T, b: &T) -> crate::error::Result<bool> {
+    Ok(bincode::serialize(a)? == bincode::serialize(b)?)
+}
+
+fn merge_ranges(mut ranges: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut merged: Vec<(f32, f32)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::Director;
+    use crate::episode::{serialize_episode, EpisodeMetadata};
+    use crate::npr::AnimeShading;
+    use crate::scene::SceneGraph;
+    use alice_sdf::SdfNode;
+
+    fn episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        sg.add_actor(Actor::new("extra", SdfNode::sphere(0.5)));
+        let mut dir = Director::new("Reload");
+        dir.add_cut(Cut::new("a", 0.0, 2.0).with_actors(vec![hero]));
+        dir.add_cut(Cut::new("b", 2.0, 4.0));
+        dir.add_cut(Cut::new("c", 4.0, 6.0).with_actors(vec![hero]));
+        EpisodePackage::new(
+            EpisodeMetadata::new("Reload", 1, 6.0),
+            sg,
+            dir,
+            AnimeShading::default(),
+        )
+    }
+
+    #[test]
+    fn test_diff_reports_only_edited_cuts() {
+        let old = episode();
+        assert!(diff_episodes(&old, &old).unwrap().is_unchanged());
+
+        let mut new = old.clone();
+        new.director.get_cut_mut(CutId(1)).unwrap().end_time = 3.5;
+        let report = diff_episodes(&old, &new).unwrap();
+        assert_eq!(report.dirty_ranges, vec![(2.0, 4.0)]);
+        assert!(report.is_dirty(2.5) && !report.is_dirty(1.0) && !report.is_dirty(5.0));
+
+        new.metadata.title = "Retitled".into();
+        assert!(diff_episodes(&old, &new).unwrap().full);
+    }
+
+    #[test]
+    fn test_swap_actor_keeps_id_and_marks_its_cuts() {
+        let mut ep = episode();
+        let hero = ep.scene_graph.find_by_name("hero").unwrap();
+        let report = swap_actor(
+            &mut ep,
+            "hero",
+            Actor::new("hero", SdfNode::box3d(1.0, 1.0, 1.0)),
+        )
+        .unwrap();
+        assert_eq!(report.dirty_ranges, vec![(0.0, 2.0), (4.0, 6.0)]);
+        assert_eq!(ep.scene_graph.find_by_name("hero"), Some(hero));
+
+        let report =
+            swap_actor(&mut ep, "extra", Actor::new("extra", SdfNode::sphere(0.6))).unwrap();
+        assert!(report.full);
+        assert!(swap_actor(&mut ep, "nobody", Actor::new("x", SdfNode::sphere(1.0))).is_err());
+    }
+
+    #[test]
+    fn test_watchers_pick_up_rewrites() {
+        let dir = std::env::temp_dir().join(format!("alice_reload_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let ep_path = dir.join("ep.anim");
+        let actor_path = dir.join("hero.actor");
+        let write = |ep: &EpisodePackage| {
+            let mut buf = Vec::new();
+            serialize_episode(ep, &mut buf).unwrap();
+            std::fs::write(&ep_path, buf).unwrap();
+        };
+
+        let mut ep = episode();
+        write(&ep);
+        save_actor_template(&actor_path, &Actor::new("hero", SdfNode::sphere(1.0))).unwrap();
+        let mut episodes = EpisodeWatcher::new(&ep_path);
+        let mut actors = ActorWatcher::new(&actor_path);
+        assert!(episodes.poll().unwrap().is_none());
+        assert!(actors.poll().unwrap().is_none());
+
+        // Different length guarantees a new stamp even on coarse mtime filesystems
+        ep.metadata.title = "Reload, take two".into();
+        write(&ep);
+        save_actor_template(&actor_path, &Actor::new("hero-v2", SdfNode::sphere(2.0))).unwrap();
+        assert_eq!(
+            episodes.poll().unwrap().unwrap().metadata.title,
+            "Reload, take two"
+        );
+        assert_eq!(actors.poll().unwrap().unwrap().name, "hero-v2");
+        assert!(episodes.poll().unwrap().is_none());
+
+        std::fs::write(&ep_path, b"ANIM garbage").unwrap();
+        assert!(episodes.poll().is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
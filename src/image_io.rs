@@ -0,0 +1,213 @@
+//! PNG (8-bit sRGB) and OpenEXR (32-bit float, with AOVs) frame export, and PNG import.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use exr::prelude::{AnyChannel, AnyChannels, Encoding, FlatSamples, Image, Layer, LayerAttributes};
+use exr::prelude::{SmallVec, WritableImage};
+
+use crate::framebuffer::{decode_srgb, Framebuffer};
+
+fn invalid(e: impl std::fmt::Display) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// Encode the color buffer as an 8-bit sRGB RGBA PNG.
+pub fn encode_png<W: Write>(framebuffer: &Framebuffer, writer: W) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(writer, framebuffer.width, framebuffer.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+    let mut png_writer = encoder.write_header().map_err(invalid)?;
+    png_writer
+        .write_image_data(&framebuffer.to_rgba8())
+        .map_err(invalid)
+}
+
+/// Write a PNG file.
+pub fn write_png(framebuffer: &Framebuffer, path: impl AsRef<Path>) -> io::Result<()> {
+    encode_png(framebuffer, BufWriter::new(File::create(path)?))
+}
+
+/// Decode an 8- or 16-bit PNG (gray, RGB, palette; with or without alpha) into linear
+/// color, e.g. for a background plate.
+pub fn decode_png<R: Read>(reader: R) -> io::Result<Framebuffer> {
+    let mut decoder = png::Decoder::new(reader);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut png_reader = decoder.read_info().map_err(invalid)?;
+    let mut pixels = vec![0u8; png_reader.output_buffer_size()];
+    let info = png_reader.next_frame(&mut pixels).map_err(invalid)?;
+    let to_rgba = |px: &[u8]| -> [f32; 4] {
+        let alpha = |a: u8| a as f32 / 255.0;
+        match px.len() {
+            1 => [
+                decode_srgb(px[0]),
+                decode_srgb(px[0]),
+                decode_srgb(px[0]),
+                1.0,
+            ],
+            2 => [
+                decode_srgb(px[0]),
+                decode_srgb(px[0]),
+                decode_srgb(px[0]),
+                alpha(px[1]),
+            ],
+            3 => [
+                decode_srgb(px[0]),
+                decode_srgb(px[1]),
+                decode_srgb(px[2]),
+                1.0,
+            ],
+            _ => [
+                decode_srgb(px[0]),
+                decode_srgb(px[1]),
+                decode_srgb(px[2]),
+                alpha(px[3]),
+            ],
+        }
+    };
+
+    let mut fb = Framebuffer::new(info.width, info.height);
+    let channels = info.color_type.samples();
+    for (y, row) in pixels
+        .chunks_exact(info.line_size)
+        .take(info.height as usize)
+        .enumerate()
+    {
+        for (x, px) in row
+            .chunks_exact(channels)
+            .take(info.width as usize)
+            .enumerate()
+        {
+            fb.set_pixel(x as u32, y as u32, to_rgba(px));
+        }
+    }
+    Ok(fb)
+}
+
+/// Read a PNG file into linear color.
+pub fn read_png(path: impl AsRef<Path>) -> io::Result<Framebuffer> {
+    decode_png(BufReader::new(File::open(path)?))
+}
+
+/// Suffixes for AOV channels: `depth` (1 ch) stays `depth`, `normal` (3 ch) becomes
+/// `normal.R/G/B`, as compositors expect.
+fn aov_channel_name(name: &str, channels: usize, c: usize) -> String {
+    const RGBA: [&str; 4] = ["R", "G", "B", "A"];
+    match channels {
+        1 => name.to_string(),
+        2..=4 => format!("{}.{}", name, RGBA[c]),
+        _ => format!("{}.{}", name, c),
+    }
+}
+
+/// Encode linear float RGBA plus every AOV plane as one multi-channel EXR.
+pub fn encode_exr<W: Write + Seek>(framebuffer: &Framebuffer, writer: W) -> io::Result<()> {
+    let size = (framebuffer.width as usize, framebuffer.height as usize);
+    let mut channels: SmallVec<[AnyChannel<FlatSamples>; 4]> = SmallVec::new();
+    for (c, name) in ["R", "G", "B", "A"].iter().enumerate() {
+        let samples = framebuffer.color.iter().map(|px| px[c]).collect();
+        channels.push(AnyChannel::new(*name, FlatSamples::F32(samples)));
+    }
+    for aov in &framebuffer.aovs {
+        for c in 0..aov.channels {
+            let samples = aov
+                .data
+                .iter()
+                .skip(c)
+                .step_by(aov.channels)
+                .copied()
+                .collect();
+            channels.push(AnyChannel::new(
+                aov_channel_name(&aov.name, aov.channels, c).as_str(),
+                FlatSamples::F32(samples),
+            ));
+        }
+    }
+
+    let layer = Layer::new(
+        size,
+        LayerAttributes::default(),
+        Encoding::FAST_LOSSLESS,
+        AnyChannels::sort(channels),
+    );
+    Image::from_layer(layer)
+        .write()
+        .to_buffered(writer)
+        .map_err(invalid)
+}
+
+/// Write an EXR file.
+pub fn write_exr(framebuffer: &Framebuffer, path: impl AsRef<Path>) -> io::Result<()> {
+    encode_exr(framebuffer, BufWriter::new(File::create(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn make_frame() -> Framebuffer {
+        let mut fb = Framebuffer::new(3, 2);
+        fb.set_pixel(1, 0, [1.0, 0.0, 0.25, 1.0]);
+        fb.set_pixel(2, 1, [2.5, 0.5, 0.0, 0.5]);
+        fb.add_aov("depth", 1).set(4, &[7.0]);
+        fb.add_aov("normal", 3).set(5, &[0.0, 0.0, 1.0]);
+        fb
+    }
+
+    #[test]
+    fn test_png_roundtrip() {
+        let fb = make_frame();
+        let mut buf = Vec::new();
+        encode_png(&fb, &mut buf).unwrap();
+
+        let decoder = png::Decoder::new(Cursor::new(&buf));
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0u8; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(&pixels[..info.buffer_size()], fb.to_rgba8().as_slice());
+    }
+
+    #[test]
+    fn test_png_decode_to_linear() {
+        let fb = make_frame();
+        let mut buf = Vec::new();
+        encode_png(&fb, &mut buf).unwrap();
+        let decoded = decode_png(Cursor::new(&buf)).unwrap();
+        assert_eq!((decoded.width, decoded.height), (3, 2));
+        assert_eq!(decoded.to_rgba8(), fb.to_rgba8());
+        assert_eq!(decoded.pixel(1, 0), [1.0, 0.0, decoded.pixel(1, 0)[2], 1.0]);
+        assert!((decoded.pixel(1, 0)[2] - 0.25).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_exr_keeps_float_and_aovs() {
+        use exr::prelude::{read, ReadChannels, ReadLayers};
+
+        let fb = make_frame();
+        let mut buf = Cursor::new(Vec::new());
+        encode_exr(&fb, &mut buf).unwrap();
+
+        let image = read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .first_valid_layer()
+            .all_attributes()
+            .from_buffered(Cursor::new(buf.into_inner()))
+            .unwrap();
+        let channels = &image.layer_data.channel_data.list;
+        let find = |name: &str| {
+            let ch = channels.iter().find(|c| c.name.to_string() == name).unwrap();
+            ch.sample_data.values_as_f32().collect::<Vec<f32>>()
+        };
+        // HDR values survive unclamped
+        assert_eq!(find("R")[5], 2.5);
+        assert_eq!(find("depth")[4], 7.0);
+        assert_eq!(find("normal.B")[5], 1.0);
+        assert_eq!(channels.len(), 8);
+    }
+}
@@ -0,0 +1,417 @@
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::{chunks_crc, split_episode};
+use crate::director::{Cut, CutId};
+use crate::episode::{EpisodeMetadata, EpisodePackage};
+use crate::error::AnimationError;
+use crate::keys::{set_key, KEY_EPSILON};
+use crate::scene::{Actor, ActorId, ActorTransform};
+
+/// Journal magic bytes.
+const JOURNAL_MAGIC: [u8; 4] = *b"AJNL";
+/// Journal format version.
+const JOURNAL_VERSION: u16 = 1;
+
+/// A single recorded edit operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
+pub enum EditOp {
+    AddActor(Actor),
+    SetActorTransform {
+        actor: ActorId,
+        transform: ActorTransform,
+    },
+    SetActorVisible {
+        actor: ActorId,
+        visible: bool,
+    },
+    AddCut(Cut),
+    RemoveCut(CutId),
+    ReplaceCut {
+        id: CutId,
+        cut: Cut,
+    },
+    /// Move the keyframe at `from_time` on an actor's timeline track to `to_time`,
+    /// replacing a key already there.
+    MoveKeyframe {
+        actor: ActorId,
+        track: String,
+        from_time: f32,
+        to_time: f32,
+    },
+    SetMetadata(EpisodeMetadata),
+}
+
+impl EditOp {
+    /// Apply this operation to an episode.
+    pub fn apply(&self, episode: &mut EpisodePackage) -> crate::error::Result<()> {
+        let not_found = AnimationError::InvalidInput;
+        match self {
+            EditOp::AddActor(actor) => {
+                episode.scene_graph.add_actor(actor.clone());
+            }
+            EditOp::SetActorTransform { actor, transform } => {
+                let a = episode
+                    .scene_graph
+                    .get_actor_mut(*actor)
+                    .ok_or_else(|| not_found(format!("Actor {} not found", actor.0)))?;
+                a.local_transform = *transform;
+            }
+            EditOp::SetActorVisible { actor, visible } => {
+                let a = episode
+                    .scene_graph
+                    .get_actor_mut(*actor)
+                    .ok_or_else(|| not_found(format!("Actor {} not found", actor.0)))?;
+                a.visible = *visible;
+            }
+            EditOp::AddCut(cut) => {
+                episode.director.add_cut(cut.clone());
+            }
+            EditOp::RemoveCut(id) => {
+                episode
+                    .director
+                    .remove_cut(*id)
+                    .ok_or_else(|| not_found(format!("Cut {} not found", id.0)))?;
+            }
+            EditOp::ReplaceCut { id, cut } => {
+                episode
+                    .director
+                    .replace_cut(*id, cut.clone())
+                    .ok_or_else(|| not_found(format!("Cut {} not found", id.0)))?;
+            }
+            EditOp::MoveKeyframe {
+                actor,
+                track,
+                from_time,
+                to_time,
+            } => {
+                let a = episode
+                    .scene_graph
+                    .get_actor_mut(*actor)
+                    .ok_or_else(|| not_found(format!("Actor {} not found", actor.0)))?;
+                let tl = a
+                    .timeline
+                    .as_mut()
+                    .ok_or_else(|| not_found(format!("Actor {} has no timeline", actor.0)))?;
+                let tr = tl
+                    .tracks
+                    .iter_mut()
+                    .find(|t| t.name == *track)
+                    .ok_or_else(|| not_found(format!("Track {} not found", track)))?;
+                let idx = tr
+                    .keyframes
+                    .iter()
+                    .position(|k| (k.time - from_time).abs() < KEY_EPSILON)
+                    .ok_or_else(|| {
+                        not_found(format!("No keyframe at {} on {}", from_time, track))
+                    })?;
+                let value = tr.keyframes.remove(idx).value;
+                set_key(tr, *to_time, value);
+            }
+            EditOp::SetMetadata(metadata) => {
+                episode.metadata = metadata.clone();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn base_crc(episode: &EpisodePackage) -> crate::error::Result<u32> {
+    Ok(chunks_crc(&split_episode(episode)?))
+}
+
+/// Append-only edit journal written between full saves.
+///
+/// Format: `[Magic "AJNL" 4B][Version 2B][Reserved 2B][BaseCRC 4B]` then
+/// records `[Size 4B][CRC32 4B][Bincode EditOp]`. Every record is flushed immediately,
+/// so at most the record being written when a crash happens is lost.
+pub struct JournalWriter<W: Write> {
+    writer: W,
+    records: usize,
+}
+
+impl<W: Write> JournalWriter<W> {
+    /// Start a journal for edits on top of `base` (the last full save).
+    pub fn new(mut writer: W, base: &EpisodePackage) -> crate::error::Result<Self> {
+        writer.write_all(&JOURNAL_MAGIC)?;
+        writer.write_all(&JOURNAL_VERSION.to_le_bytes())?;
+        writer.write_all(&0u16.to_le_bytes())?;
+        writer.write_all(&base_crc(base)?.to_le_bytes())?;
+        writer.flush()?;
+        Ok(Self { writer, records: 0 })
+    }
+
+    /// Append one operation and flush.
+    pub fn append(&mut self, op: &EditOp) -> crate::error::Result<()> {
+        let body = bincode::serialize(op)?;
+        self.writer.write_all(&(body.len() as u32).to_le_bytes())?;
+        self.writer
+            .write_all(&crc32fast::hash(&body).to_le_bytes())?;
+        self.writer.write_all(&body)?;
+        self.writer.flush()?;
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Apply an operation to the live episode and journal it if it succeeded.
+    pub fn apply(&mut self, episode: &mut EpisodePackage, op: EditOp) -> crate::error::Result<()> {
+        op.apply(episode)?;
+        self.append(&op)
+    }
+
+    /// Number of records written.
+    #[inline]
+    pub fn record_count(&self) -> usize {
+        self.records
+    }
+
+    /// Finish and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Outcome of replaying a journal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalReplay {
+    /// Records successfully applied.
+    pub applied: usize,
+    /// True if replay stopped at a torn or corrupt record (typical after a crash).
+    pub truncated: bool,
+}
+
+/// Replay a journal on top of the last full save, recovering unsaved edits.
+///
+/// Fails if the journal was recorded against a different base episode. Edits are replayed
+/// on a copy: if one fails to apply, `base` is left untouched.
+pub fn recover_episode<R: Read>(
+    base: &mut EpisodePackage,
+    reader: &mut R,
+) -> crate::error::Result<JournalReplay> {
+    let mut header = [0u8; 12];
+    reader.read_exact(&mut header)?;
+    if header[0..4] != JOURNAL_MAGIC {
+        return Err(AnimationError::BadMagic { expected: "AJNL" });
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != JOURNAL_VERSION {
+        return Err(AnimationError::UnsupportedVersion {
+            format: "AJNL",
+            version,
+        });
+    }
+    let expected_base = u32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    let actual_base = base_crc(base)?;
+    if expected_base != actual_base {
+        return Err(AnimationError::CrcMismatch {
+            expected: expected_base,
+            actual: actual_base,
+        });
+    }
+
+    let mut replay = JournalReplay {
+        applied: 0,
+        truncated: false,
+    };
+    let mut scratch = base.clone();
+    loop {
+        let mut record_header = [0u8; 8];
+        match read_full(reader, &mut record_header)? {
+            0 => break,
+            8 => {}
+            _ => {
+                replay.truncated = true;
+                break;
+            }
+        }
+        let size = u32::from_le_bytes([
+            record_header[0],
+            record_header[1],
+            record_header[2],
+            record_header[3],
+        ]) as usize;
+        let crc = u32::from_le_bytes([
+            record_header[4],
+            record_header[5],
+            record_header[6],
+            record_header[7],
+        ]);
+        // Grows with the bytes actually present, so a corrupt size cannot over-allocate
+        let mut body = Vec::new();
+        reader.by_ref().take(size as u64).read_to_end(&mut body)?;
+        if body.len() != size || crc32fast::hash(&body) != crc {
+            replay.truncated = true;
+            break;
+        }
+        let op: EditOp = match bincode::deserialize(&body) {
+            Ok(op) => op,
+            Err(_) => {
+                replay.truncated = true;
+                break;
+            }
+        };
+        op.apply(&mut scratch)?;
+        replay.applied += 1;
+    }
+    *base = scratch;
+    Ok(replay)
+}
+
+/// Read until `buf` is full or EOF. Returns bytes read.
+fn read_full<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::Director;
+    use crate::npr::AnimeShading;
+    use crate::scene::SceneGraph;
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let mut tl = Timeline::new("hero_anim");
+        let mut track = Track::new("translate.x");
+        track.add_keyframe(Keyframe::new(0.0, 0.0));
+        track.add_keyframe(Keyframe::new(1.0, 2.0));
+        tl.add_track(track);
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)).with_timeline(tl));
+        let mut dir = Director::new("Journal");
+        dir.add_cut(Cut::new("intro", 0.0, 3.0));
+        let meta = EpisodeMetadata::new("Journal", 1, 3.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_journal_replay() {
+        let saved = make_test_episode();
+        let mut live = saved.clone();
+
+        let mut journal = JournalWriter::new(Vec::new(), &saved).unwrap();
+        journal
+            .apply(&mut live, EditOp::AddCut(Cut::new("battle", 3.0, 8.0)))
+            .unwrap();
+        journal
+            .apply(
+                &mut live,
+                EditOp::MoveKeyframe {
+                    actor: ActorId(0),
+                    track: "translate.x".into(),
+                    from_time: 1.0,
+                    to_time: 2.0,
+                },
+            )
+            .unwrap();
+        assert_eq!(journal.record_count(), 2);
+        let bytes = journal.into_inner();
+
+        let mut recovered = saved.clone();
+        let replay = recover_episode(&mut recovered, &mut std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(replay.applied, 2);
+        assert!(!replay.truncated);
+        assert_eq!(recovered.director.cut_count(), 2);
+        let tl = recovered
+            .scene_graph
+            .get_actor(ActorId(0))
+            .unwrap()
+            .timeline
+            .as_ref()
+            .unwrap();
+        assert_eq!(tl.tracks[0].keyframes[1].time, 2.0);
+    }
+
+    #[test]
+    fn test_move_keyframe_onto_existing_key() {
+        let mut episode = make_test_episode();
+        EditOp::MoveKeyframe {
+            actor: ActorId(0),
+            track: "translate.x".into(),
+            from_time: 0.0,
+            to_time: 1.0,
+        }
+        .apply(&mut episode)
+        .unwrap();
+        let track = &episode
+            .scene_graph
+            .get_actor(ActorId(0))
+            .unwrap()
+            .timeline
+            .as_ref()
+            .unwrap()
+            .tracks[0];
+        // The moved key replaces the one at 1.0 instead of stacking a duplicate
+        assert_eq!(track.keyframes.len(), 1);
+        assert_eq!(
+            (track.keyframes[0].time, track.keyframes[0].value),
+            (1.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_journal_torn_tail() {
+        let saved = make_test_episode();
+        let mut live = saved.clone();
+        let mut journal = JournalWriter::new(Vec::new(), &saved).unwrap();
+        journal
+            .apply(&mut live, EditOp::AddCut(Cut::new("battle", 3.0, 8.0)))
+            .unwrap();
+        journal
+            .apply(&mut live, EditOp::AddCut(Cut::new("outro", 8.0, 10.0)))
+            .unwrap();
+        let mut bytes = journal.into_inner();
+        // Simulate a crash in the middle of the last record
+        bytes.truncate(bytes.len() - 3);
+
+        let mut recovered = saved.clone();
+        let replay = recover_episode(&mut recovered, &mut std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!(replay.applied, 1);
+        assert!(replay.truncated);
+        assert_eq!(recovered.director.cut_count(), 2);
+    }
+
+    #[test]
+    fn test_journal_failed_edit_keeps_base() {
+        let saved = make_test_episode();
+        let mut journal = JournalWriter::new(Vec::new(), &saved).unwrap();
+        journal
+            .append(&EditOp::AddCut(Cut::new("battle", 3.0, 8.0)))
+            .unwrap();
+        journal.append(&EditOp::RemoveCut(CutId(99))).unwrap();
+        let bytes = journal.into_inner();
+        let mut recovered = saved.clone();
+        assert!(recover_episode(&mut recovered, &mut std::io::Cursor::new(&bytes)).is_err());
+        assert_eq!(recovered.director.cut_count(), 1);
+
+        // A record claiming 4 GiB in a short file is a torn tail, not an allocation
+        let mut bytes = JournalWriter::new(Vec::new(), &saved).unwrap().into_inner();
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        bytes.extend_from_slice(&[0; 8]);
+        let replay = recover_episode(&mut recovered, &mut std::io::Cursor::new(&bytes)).unwrap();
+        assert_eq!((replay.applied, replay.truncated), (0, true));
+    }
+
+    #[test]
+    fn test_journal_wrong_base() {
+        let saved = make_test_episode();
+        let journal = JournalWriter::new(Vec::new(), &saved).unwrap();
+        let bytes = journal.into_inner();
+
+        let mut other = saved.clone();
+        other.metadata.title = "Other".into();
+        assert!(recover_episode(&mut other, &mut std::io::Cursor::new(&bytes)).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! Keyframe editing helpers shared by the pose, anticipation, baking and codec tools.
+
+use alice_sdf::animation::{Keyframe, Track};
+
+/// Keys closer together than this are the same key.
+pub const KEY_EPSILON: f32 = 1e-5;
+
+/// Key `value` at `time`, overwriting a key already there.
+pub fn set_key(track: &mut Track, time: f32, value: f32) {
+    match track
+        .keyframes
+        .iter_mut()
+        .find(|k| (k.time - time).abs() < KEY_EPSILON)
+    {
+        Some(key) => key.value = value,
+        None => track.add_keyframe(Keyframe::new(time, value)),
+    }
+}
+
+/// Linear sample of sorted `(time, value)` keys, held at the ends.
+pub fn sample_linear(keys: &[(f32, f32)], time: f32) -> f32 {
+    let i = keys.partition_point(|&(t, _)| t <= time);
+    match (i.checked_sub(1).map(|j| keys[j]), keys.get(i)) {
+        (Some((t0, v0)), Some(&(t1, v1))) if t1 > t0 => v0 + (v1 - v0) * (time - t0) / (t1 - t0),
+        (Some((_, v0)), _) => v0,
+        (None, Some(&(_, v1))) => v1,
+        (None, None) => 0.0,
+    }
+}
+
+/// Indices of keys to keep so the linear curve stays within `max_error` of every
+/// original key (Ramer-Douglas-Peucker on value error). Endpoints are always kept.
+pub fn reduce_keys(keys: &[(f32, f32)], max_error: f32) -> Vec<usize> {
+    if keys.len() <= 2 || max_error <= 0.0 {
+        return (0..keys.len()).collect();
+    }
+    let mut keep = vec![false; keys.len()];
+    keep[0] = true;
+    keep[keys.len() - 1] = true;
+    let mut spans = vec![(0, keys.len() - 1)];
+    while let Some((a, b)) = spans.pop() {
+        let segment = [keys[a], keys[b]];
+        let worst = (a + 1..b)
+            .map(|i| (i, (keys[i].1 - sample_linear(&segment, keys[i].0)).abs()))
+            .fold(
+                (a, 0.0f32),
+                |best, cur| if cur.1 > best.1 { cur } else { best },
+            );
+        if worst.1 > max_error {
+            keep[worst.0] = true;
+            spans.push((a, worst.0));
+            spans.push((worst.0, b));
+        }
+    }
+    (0..keys.len()).filter(|&i| keep[i]).collect()
+}
+
+/// Drop the keys of `track` that [`reduce_keys`] finds redundant within `max_error`.
+pub fn reduce_track(track: &mut Track, max_error: f32) {
+    let keys: Vec<(f32, f32)> = track.keyframes.iter().map(|k| (k.time, k.value)).collect();
+    let mut kept = reduce_keys(&keys, max_error).into_iter().peekable();
+    let mut index = 0;
+    track.keyframes.retain(|_| {
+        let keep = kept.next_if_eq(&index).is_some();
+        index += 1;
+        keep
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_key_overwrites_or_inserts() {
+        let mut track = Track::new("translate.x");
+        set_key(&mut track, 1.0, 2.0);
+        set_key(&mut track, 1.0 + KEY_EPSILON * 0.5, 3.0);
+        set_key(&mut track, 0.5, 1.0);
+        let keys: Vec<(f32, f32)> = track.keyframes.iter().map(|k| (k.time, k.value)).collect();
+        assert_eq!(keys, vec![(0.5, 1.0), (1.0, 3.0)]);
+    }
+
+    #[test]
+    fn test_reduce_track_keeps_corners() {
+        let mut track = Track::new("translate.x");
+        for i in 0..=100 {
+            let t = i as f32 / 10.0;
+            track.add_keyframe(Keyframe::new(t, t.min(5.0)));
+        }
+        reduce_track(&mut track, 1e-4);
+        let times: Vec<f32> = track.keyframes.iter().map(|k| k.time).collect();
+        assert_eq!(times, vec![0.0, 5.0, 10.0]);
+    }
+}
@@ -1,11 +1,161 @@
+//! ALICE-Animation: anime-focused SDF direction engine.
+//!
+//! The playback core (`scene`, `director`, `camera`, `npr`, `mouth`, `lip_sync`, `rng`, `cycle`,
+//! `layer`, `blend`, `clip`, `nla`, `performance`, `time_warp`, `units`, `error`, `metadata`,
+//! `anim`) builds without `std` (alloc only): disable default features and enable `libm` for
+//! float math. `anim::decode_episode_core` loads single-body ANIM files there.
+//! Everything else — containers, rendering, export, simulation — needs the `std` feature.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("without `std`, enable the `libm` feature for float math");
+
+#[cfg(not(feature = "std"))]
+#[allow(dead_code)]
+mod float;
+
 pub mod scene;
 pub mod director;
 pub mod camera;
 pub mod npr;
-pub mod episode;
+pub mod mouth;
+pub mod lip_sync;
+pub mod rng;
+pub mod cycle;
+pub mod layer;
+pub mod blend;
+pub mod clip;
+pub mod nla;
+pub mod performance;
+pub mod time_warp;
+pub mod units;
+pub mod error;
+pub mod metadata;
+pub mod anim;
 
+#[cfg(feature = "std")]
+pub mod curve;
+#[cfg(feature = "std")]
+pub mod timing;
+#[cfg(feature = "std")]
+pub mod exposure;
+#[cfg(feature = "std")]
+pub mod keys;
+#[cfg(feature = "std")]
+pub mod anticipation;
+#[cfg(feature = "std")]
+pub mod mirror;
+#[cfg(feature = "std")]
+pub mod pose;
+#[cfg(feature = "std")]
+pub mod retarget;
+#[cfg(feature = "std")]
+pub mod episode;
+#[cfg(feature = "std")]
+pub mod chunk;
+#[cfg(feature = "std")]
+pub mod patch;
+#[cfg(feature = "std")]
+pub mod journal;
+#[cfg(feature = "std")]
+pub mod series;
+#[cfg(feature = "std")]
+pub mod split;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod expression;
+#[cfg(feature = "std")]
+pub mod blink;
+#[cfg(feature = "std")]
+pub mod gesture;
+#[cfg(feature = "std")]
+pub mod framebuffer;
+#[cfg(feature = "std")]
+pub mod plate;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod progressive;
+#[cfg(feature = "std")]
+pub mod export;
+#[cfg(feature = "std")]
+pub mod usd_export;
+#[cfg(feature = "std")]
+pub mod render_job;
+#[cfg(feature = "std")]
+pub mod frame_hash;
+#[cfg(feature = "std")]
+pub mod playback;
+#[cfg(feature = "std")]
+pub mod hot_reload;
+#[cfg(feature = "std")]
+pub mod editor_session;
+#[cfg(feature = "std")]
+pub mod profile;
+#[cfg(feature = "std")]
+pub mod audio;
+#[cfg(feature = "std")]
+pub mod overlay;
+#[cfg(feature = "std")]
+pub mod text;
+#[cfg(feature = "std")]
+pub mod animatic;
+#[cfg(feature = "std")]
+pub mod weather;
+#[cfg(feature = "std")]
+pub mod day_night;
+#[cfg(feature = "std")]
+pub mod named_refs;
+#[cfg(feature = "std")]
+pub mod cloth;
+#[cfg(feature = "std")]
+pub mod hair;
+#[cfg(feature = "std")]
+pub mod crowd;
+#[cfg(feature = "std")]
+pub mod locomotion;
+#[cfg(feature = "std")]
+pub mod root_motion;
+#[cfg(feature = "std")]
+pub mod motion_trail;
+#[cfg(feature = "std")]
+pub mod onion_skin;
+#[cfg(feature = "std")]
+pub mod smear;
+#[cfg(feature = "std")]
+pub mod tracking_shot;
+#[cfg(feature = "std")]
+pub mod bake;
+#[cfg(feature = "std")]
+pub mod frame_rate;
+#[cfg(feature = "std")]
+pub mod screenplay;
+#[cfg(feature = "std")]
+pub mod text_sync;
+#[cfg(feature = "std")]
+pub mod subtitle;
+#[cfg(feature = "std")]
+pub mod blendshape;
+#[cfg(feature = "std")]
+pub mod singing;
 #[cfg(feature = "voice")]
-pub mod lip_sync;
+pub mod dialogue_voice;
+
+#[cfg(feature = "crypto")]
+pub mod secure;
+
+#[cfg(feature = "async")]
+pub mod async_io;
+
+#[cfg(feature = "image")]
+pub mod image_io;
+
+#[cfg(feature = "cli")]
+pub mod cli;
 
 #[cfg(feature = "codec")]
 pub mod codec_bridge;
@@ -22,7 +172,16 @@ pub mod ml_bridge;
 
 // Re-exports
 pub use scene::{Actor, ActorId, ActorTransform, SceneGraph};
-pub use director::{Cut, CutId, Director, DirectorState};
-pub use camera::{CameraState, CameraTrack, CameraWork, FakePerspective};
+pub use director::{Cut, CutId, Director, DirectorState, PlaybackMode};
+pub use clip::{AnimationClip, ClipInstance, ClipLibrary, ClipTrack};
+pub use nla::{BlendMode, NlaStack};
+pub use performance::Performance;
+pub use camera::{CameraMode, CameraState, CameraTrack, CameraWork, FakePerspective};
 pub use npr::{AnimeShading, CelShading, OutlineConfig};
-pub use episode::{EpisodeMetadata, EpisodePackage};
+pub use error::AnimationError;
+pub use metadata::{EpisodeMetadata, MetadataValue};
+pub use anim::{decode_episode_core, EpisodeCore};
+#[cfg(feature = "std")]
+pub use episode::EpisodePackage;
+#[cfg(feature = "std")]
+pub use patch::{apply_patch, create_patch, EpisodePatch};
@@ -1,9 +1,18 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use alice_sdf::animation::{Keyframe, Timeline, Track};
+#[cfg(feature = "voice")]
 use alice_voice::ParametricParams;
 use serde::{Deserialize, Serialize};
 
-/// Japanese vowel phonemes for mouth shape.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use crate::float::Float;
+use crate::scene::Actor;
+
+/// Mouth shape phonemes (visemes): Japanese vowels plus consonant shapes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Phoneme {
     /// Mouth closed
     Closed,
@@ -17,9 +26,49 @@ pub enum Phoneme {
     E,
     /// お (round open)
     O,
+    /// m/b/p (ま・ば・ぱ行): lips pressed together
+    M,
+    /// f/v (ふ): lower lip tucked under upper teeth
+    F,
+    /// w (わ): rounded pucker
+    W,
+    /// ん: nasal, lips barely parted
+    N,
+    /// s/z/t/d/ts (さ・た行): teeth nearly closed, lips spread
+    S,
 }
 
 impl Phoneme {
+    /// All phonemes, in declaration order.
+    pub const ALL: [Phoneme; 11] = [
+        Phoneme::Closed,
+        Phoneme::A,
+        Phoneme::I,
+        Phoneme::U,
+        Phoneme::E,
+        Phoneme::O,
+        Phoneme::M,
+        Phoneme::F,
+        Phoneme::W,
+        Phoneme::N,
+        Phoneme::S,
+    ];
+
+    /// True for あいうえお.
+    #[inline]
+    pub fn is_vowel(&self) -> bool {
+        matches!(
+            self,
+            Phoneme::A | Phoneme::I | Phoneme::U | Phoneme::E | Phoneme::O
+        )
+    }
+
+    /// True for consonant shapes (M, F, W, N, S).
+    #[inline]
+    pub fn is_consonant(&self) -> bool {
+        !self.is_vowel() && *self != Phoneme::Closed
+    }
+
     /// Mouth openness value (0.0 = closed, 1.0 = fully open).
     pub fn openness(&self) -> f32 {
         match self {
@@ -29,6 +78,11 @@ impl Phoneme {
             Phoneme::U => 0.4,
             Phoneme::E => 0.6,
             Phoneme::O => 0.7,
+            Phoneme::M => 0.0,
+            Phoneme::F => 0.1,
+            Phoneme::W => 0.2,
+            Phoneme::N => 0.1,
+            Phoneme::S => 0.15,
         }
     }
 
@@ -41,6 +95,105 @@ impl Phoneme {
             Phoneme::U => 0.2,
             Phoneme::E => 0.9,
             Phoneme::O => 0.5,
+            Phoneme::M => 0.4,
+            Phoneme::F => 0.6,
+            Phoneme::W => 0.1,
+            Phoneme::N => 0.5,
+            Phoneme::S => 0.8,
+        }
+    }
+
+    /// Lip press value (0.0 = relaxed, 1.0 = lips pressed / lower lip tucked).
+    pub fn lip_press(&self) -> f32 {
+        match self {
+            Phoneme::M => 1.0,
+            Phoneme::F => 0.7,
+            Phoneme::W => 0.3,
+            Phoneme::Closed => 0.2,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Mouth parameters for one viseme.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VisemeShape {
+    pub openness: f32,
+    pub width: f32,
+    pub lip_press: f32,
+    /// Mouth corner curl (-1.0 = down, 1.0 = up).
+    pub corner: f32,
+}
+
+impl VisemeShape {
+    /// Default shape of a phoneme.
+    pub fn of(phoneme: Phoneme) -> Self {
+        Self {
+            openness: phoneme.openness(),
+            width: phoneme.width(),
+            lip_press: phoneme.lip_press(),
+            corner: 0.0,
+        }
+    }
+}
+
+/// Per-character viseme calibration.
+///
+/// Default shapes are scaled by the profile's ranges; per-phoneme overrides replace them outright.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VisemeProfile {
+    pub name: String,
+    /// Multiplier on default openness (small-mouthed designs < 1.0).
+    pub openness_scale: f32,
+    /// Multiplier on default width.
+    pub width_scale: f32,
+    /// Corner curl applied to every default shape (e.g. a permanently smiling design).
+    pub corner_bias: f32,
+    pub overrides: Vec<(Phoneme, VisemeShape)>,
+}
+
+impl VisemeProfile {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            openness_scale: 1.0,
+            width_scale: 1.0,
+            corner_bias: 0.0,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Set openness / width multipliers.
+    pub fn with_ranges(mut self, openness_scale: f32, width_scale: f32) -> Self {
+        self.openness_scale = openness_scale;
+        self.width_scale = width_scale;
+        self
+    }
+
+    /// Set corner bias.
+    pub fn with_corner_bias(mut self, corner: f32) -> Self {
+        self.corner_bias = corner.clamp(-1.0, 1.0);
+        self
+    }
+
+    /// Replace the shape of one phoneme.
+    pub fn with_override(mut self, phoneme: Phoneme, shape: VisemeShape) -> Self {
+        self.overrides.retain(|(p, _)| *p != phoneme);
+        self.overrides.push((phoneme, shape));
+        self
+    }
+
+    /// Calibrated shape for a phoneme.
+    pub fn shape(&self, phoneme: Phoneme) -> VisemeShape {
+        if let Some((_, shape)) = self.overrides.iter().find(|(p, _)| *p == phoneme) {
+            return *shape;
+        }
+        let base = VisemeShape::of(phoneme);
+        VisemeShape {
+            openness: (base.openness * self.openness_scale).clamp(0.0, 1.0),
+            width: (base.width * self.width_scale).clamp(0.0, 1.0),
+            lip_press: base.lip_press,
+            corner: self.corner_bias,
         }
     }
 }
@@ -50,6 +203,19 @@ impl Phoneme {
 pub struct PhonemeKeyframe {
     pub time: f32,
     pub phoneme: Phoneme,
+    /// Classification confidence (0.0..=1.0); 1.0 for hand-authored keys.
+    pub confidence: f32,
+}
+
+impl PhonemeKeyframe {
+    #[inline]
+    pub fn new(time: f32, phoneme: Phoneme) -> Self {
+        Self {
+            time,
+            phoneme,
+            confidence: 1.0,
+        }
+    }
 }
 
 /// Lip sync animation track.
@@ -69,34 +235,213 @@ impl LipSyncTrack {
 
     /// Add a phoneme at a given time.
     pub fn add_phoneme(&mut self, time: f32, phoneme: Phoneme) {
-        self.phonemes.push(PhonemeKeyframe { time, phoneme });
+        self.add_keyframe(PhonemeKeyframe::new(time, phoneme));
+    }
+
+    /// Add a keyframe (with confidence).
+    pub fn add_keyframe(&mut self, keyframe: PhonemeKeyframe) {
+        self.phonemes.push(keyframe);
         self.phonemes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
     }
 
-    /// Convert to an ALICE-SDF Timeline with two tracks: "mouth.openness" and "mouth.width".
+    /// Convert to an ALICE-SDF Timeline with three tracks:
+    /// "mouth.openness", "mouth.width" and "mouth.lip_press".
     pub fn to_timeline(&self) -> Timeline {
+        self.to_timeline_with_profile(None)
+    }
+
+    /// Like `to_timeline`, with shapes taken from a calibration profile.
+    /// A profile also adds a "mouth.corner" track.
+    pub fn to_timeline_with_profile(&self, profile: Option<&VisemeProfile>) -> Timeline {
         let mut tl = Timeline::new(&self.name);
 
         let mut openness_track = Track::new("mouth.openness");
         let mut width_track = Track::new("mouth.width");
+        let mut lip_track = Track::new("mouth.lip_press");
+        let mut corner_track = Track::new("mouth.corner");
 
         for kf in &self.phonemes {
-            openness_track.add_keyframe(Keyframe::new(kf.time, kf.phoneme.openness()));
-            width_track.add_keyframe(Keyframe::new(kf.time, kf.phoneme.width()));
+            let shape = match profile {
+                Some(p) => p.shape(kf.phoneme),
+                None => VisemeShape::of(kf.phoneme),
+            };
+            openness_track.add_keyframe(Keyframe::new(kf.time, shape.openness));
+            width_track.add_keyframe(Keyframe::new(kf.time, shape.width));
+            lip_track.add_keyframe(Keyframe::new(kf.time, shape.lip_press));
+            corner_track.add_keyframe(Keyframe::new(kf.time, shape.corner));
         }
 
         tl.add_track(openness_track);
         tl.add_track(width_track);
+        tl.add_track(lip_track);
+        if profile.is_some() {
+            tl.add_track(corner_track);
+        }
         tl
     }
 
+    /// Timeline for a specific actor, using its calibration profile if it has one.
+    pub fn to_timeline_for(&self, actor: &Actor) -> Timeline {
+        self.to_timeline_with_profile(actor.viseme_profile.as_ref())
+    }
+
     /// Duration of this lip sync track.
     pub fn duration(&self) -> f32 {
         self.phonemes.last().map(|kf| kf.time).unwrap_or(0.0)
     }
+
+    /// Phoneme held at `time` (`Closed` before the first keyframe).
+    pub fn phoneme_at(&self, time: f32) -> Phoneme {
+        let idx = self.phonemes.partition_point(|kf| kf.time <= time);
+        if idx == 0 {
+            Phoneme::Closed
+        } else {
+            self.phonemes[idx - 1].phoneme
+        }
+    }
+
+    fn sort(&mut self) {
+        self.phonemes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    }
+
+    /// Move every keyframe by `offset` seconds. Keyframes pushed before 0 collapse onto 0.
+    pub fn shift(&mut self, offset: f32) {
+        for kf in &mut self.phonemes {
+            kf.time += offset;
+        }
+        if offset < 0.0 {
+            let held = self.phoneme_at(0.0);
+            self.phonemes.retain(|kf| kf.time > 0.0);
+            if held != Phoneme::Closed {
+                self.phonemes.insert(0, PhonemeKeyframe::new(0.0, held));
+            }
+        }
+    }
+
+    /// Stretch timing around `pivot` (factor > 1.0 slows the dialogue down).
+    pub fn scale(&mut self, factor: f32, pivot: f32) {
+        let factor = factor.max(0.0);
+        for kf in &mut self.phonemes {
+            kf.time = pivot + (kf.time - pivot) * factor;
+        }
+    }
+
+    /// Remap `[start, end]` onto `[new_start, new_end]`.
+    ///
+    /// Keyframes inside the region are stretched linearly; later keyframes move by the
+    /// change in the region's end so the rest of the line stays in sync.
+    pub fn retime_region(&mut self, start: f32, end: f32, new_start: f32, new_end: f32) {
+        let span = (end - start).max(f32::EPSILON);
+        let new_end = new_end.max(new_start);
+        for kf in &mut self.phonemes {
+            if kf.time > end {
+                kf.time += new_end - end;
+            } else if kf.time >= start {
+                kf.time = new_start + (kf.time - start) / span * (new_end - new_start);
+            }
+        }
+        self.sort();
+    }
+
+    /// Keep only `[start, end)`, holding the phoneme active at `start` and closing at `end`.
+    pub fn trim(&mut self, start: f32, end: f32) {
+        let held = self.phoneme_at(start);
+        let ending = self.phoneme_at(end);
+        self.phonemes.retain(|kf| kf.time > start && kf.time < end);
+        if held != Phoneme::Closed {
+            self.phonemes.insert(0, PhonemeKeyframe::new(start, held));
+        }
+        let last = self.phonemes.last().map(|kf| kf.phoneme);
+        if ending != Phoneme::Closed || last.is_some_and(|p| p != Phoneme::Closed) {
+            self.phonemes
+                .push(PhonemeKeyframe::new(end, Phoneme::Closed));
+        }
+    }
+
+    /// Place `other` at `offset`, replacing anything from `offset` onwards.
+    ///
+    /// A phoneme still held at `offset` is closed there before the appended take starts.
+    pub fn append(&mut self, other: &LipSyncTrack, offset: f32) {
+        let held = self.phoneme_at(offset);
+        self.phonemes.retain(|kf| kf.time < offset);
+        if held != Phoneme::Closed {
+            self.phonemes
+                .push(PhonemeKeyframe::new(offset, Phoneme::Closed));
+        }
+        self.phonemes
+            .extend(other.phonemes.iter().map(|kf| PhonemeKeyframe {
+                time: kf.time + offset,
+                ..kf.clone()
+            }));
+        self.sort();
+        self.dedup_closed();
+    }
+
+    /// Overlay `other` (shifted by `offset`): wherever it is speaking it wins,
+    /// elsewhere this track shows through.
+    pub fn merge(&mut self, other: &LipSyncTrack, offset: f32) {
+        let mut shifted = other.clone();
+        shifted.shift(offset);
+
+        let mut times: Vec<f32> = self
+            .phonemes
+            .iter()
+            .chain(shifted.phonemes.iter())
+            .map(|kf| kf.time)
+            .collect();
+        times.sort_by(|a, b| a.total_cmp(b));
+        times.dedup();
+
+        let key_at = |track: &LipSyncTrack, time: f32| {
+            let idx = track.phonemes.partition_point(|kf| kf.time <= time);
+            if idx == 0 {
+                PhonemeKeyframe::new(time, Phoneme::Closed)
+            } else {
+                PhonemeKeyframe {
+                    time,
+                    ..track.phonemes[idx - 1].clone()
+                }
+            }
+        };
+        self.phonemes = times
+            .into_iter()
+            .map(|t| {
+                let top = key_at(&shifted, t);
+                if top.phoneme != Phoneme::Closed {
+                    top
+                } else {
+                    key_at(self, t)
+                }
+            })
+            .collect();
+        self.dedup_closed();
+    }
+
+    /// Stitch takes into one track, each placed at its offset in order.
+    pub fn concat(name: impl Into<String>, takes: &[(&LipSyncTrack, f32)]) -> LipSyncTrack {
+        let mut track = LipSyncTrack::new(name);
+        for (take, offset) in takes {
+            track.append(take, *offset);
+        }
+        track
+    }
+
+    /// Drop keyframes that change nothing: repeated `Closed` keys and
+    /// earlier keys sharing a time with a later one.
+    pub fn dedup_closed(&mut self) {
+        let mut out: Vec<PhonemeKeyframe> = Vec::with_capacity(self.phonemes.len());
+        for kf in self.phonemes.drain(..) {
+            match out.last_mut() {
+                Some(prev) if prev.time == kf.time => *prev = kf,
+                Some(prev) if prev.phoneme == Phoneme::Closed && kf.phoneme == Phoneme::Closed => {}
+                _ => out.push(kf),
+            }
+        }
+        self.phonemes = out;
+    }
 }
 
-/// Classify a vowel phoneme from formant frequencies (F1, F2).
+/// Classify a phoneme from formant frequencies (F1, F2).
 ///
 /// Based on Japanese vowel formant chart:
 /// - あ (A): F1 ~700-800, F2 ~1200-1400
@@ -104,7 +449,24 @@ impl LipSyncTrack {
 /// - う (U): F1 ~300-400, F2 ~1000-1200
 /// - え (E): F1 ~450-600, F2 ~1800-2200
 /// - お (O): F1 ~500-600, F2 ~800-1000
+///
+/// Consonants (checked first):
+/// - Nasal murmur (F1 < 250): M below F2 1200, N below F2 2000
+/// - Frication (F2 > 2800): S with low F1, F otherwise
+/// - Labial glide W: F1 < 400, F2 < 800
 fn classify_phoneme(f1: f32, f2: f32) -> Phoneme {
+    if f1 < 250.0 && f2 < 1200.0 {
+        return Phoneme::M;
+    }
+    if f1 < 250.0 && f2 < 2000.0 {
+        return Phoneme::N;
+    }
+    if f2 > 2800.0 {
+        return if f1 < 300.0 { Phoneme::S } else { Phoneme::F };
+    }
+    if f1 < 400.0 && f2 < 800.0 {
+        return Phoneme::W;
+    }
     // Low F1 + high F2 → い
     if f1 < 400.0 && f2 > 2000.0 {
         return Phoneme::I;
@@ -129,43 +491,216 @@ fn classify_phoneme(f1: f32, f2: f32) -> Phoneme {
     Phoneme::A
 }
 
+/// Prototype (F1, F2) per phoneme, used to score classification confidence.
+const FORMANT_PROTOTYPES: [(Phoneme, f32, f32); 10] = [
+    (Phoneme::A, 750.0, 1300.0),
+    (Phoneme::I, 300.0, 2400.0),
+    (Phoneme::U, 350.0, 1100.0),
+    (Phoneme::E, 500.0, 1900.0),
+    (Phoneme::O, 550.0, 900.0),
+    (Phoneme::M, 200.0, 1000.0),
+    (Phoneme::N, 220.0, 1600.0),
+    (Phoneme::S, 250.0, 3200.0),
+    (Phoneme::F, 450.0, 3000.0),
+    (Phoneme::W, 320.0, 700.0),
+];
+
+/// Classify a phoneme and score how clearly it separates from the nearest alternative.
+///
+/// Confidence is 1.0 on the phoneme's prototype and falls to 0.0 when another
+/// prototype is at least as close (F2 is weighted down to match F1's spread).
+pub(crate) fn classify_with_confidence(f1: f32, f2: f32) -> (Phoneme, f32) {
+    let phoneme = classify_phoneme(f1, f2);
+    let dist = |pf1: f32, pf2: f32| ((f1 - pf1) / 100.0).hypot((f2 - pf2) / 300.0);
+    let mut own = f32::MAX;
+    let mut alt = f32::MAX;
+    for &(p, pf1, pf2) in &FORMANT_PROTOTYPES {
+        let d = dist(pf1, pf2);
+        if p == phoneme {
+            own = d;
+        } else {
+            alt = alt.min(d);
+        }
+    }
+    let confidence = ((alt - own) / (alt + own).max(f32::EPSILON)).clamp(0.0, 1.0);
+    (phoneme, confidence)
+}
+
+/// Classify one (F1, F2) frame. Silence is `Closed` with full confidence.
+fn classify_formants(f1: f32, f2: f32) -> (Phoneme, f32) {
+    // Skip if both frequencies are too low (silence)
+    if f1 < 100.0 && f2 < 100.0 {
+        return (Phoneme::Closed, 1.0);
+    }
+    classify_with_confidence(f1, f2)
+}
+
+/// Temporal filtering applied to frame classifications before keyframes are emitted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothingConfig {
+    /// Frames below this confidence hold the previous frame's phoneme.
+    pub min_confidence: f32,
+    /// Majority filter window in frames (odd; 1 disables).
+    pub window: usize,
+    /// Phonemes held shorter than this (seconds) merge into a neighbour.
+    pub min_duration: f32,
+}
+
+impl SmoothingConfig {
+    /// No filtering: every frame's raw classification is used.
+    pub fn none() -> Self {
+        Self {
+            min_confidence: 0.0,
+            window: 1,
+            min_duration: 0.0,
+        }
+    }
+}
+
+impl Default for SmoothingConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: 0.2,
+            window: 5,
+            min_duration: 0.06,
+        }
+    }
+}
+
 /// Convert ALICE-Voice parametric params to a lip sync track.
 ///
 /// Each ParametricParams frame maps to a phoneme based on formant analysis.
+#[cfg(feature = "voice")]
 pub fn sync_voice_to_animation(
     voice_params: &[ParametricParams],
     frame_duration: f32,
 ) -> LipSyncTrack {
-    let mut track = LipSyncTrack::new("lip_sync");
-    let mut prev_phoneme = Phoneme::Closed;
-
-    for (i, params) in voice_params.iter().enumerate() {
-        let time = i as f32 * frame_duration;
+    sync_voice_to_animation_smoothed(voice_params, frame_duration, &SmoothingConfig::none())
+}
 
-        // Extract F1 and F2 from formants
-        let phoneme = if params.formants.len() >= 2 {
-            let f1 = params.formants[0].frequency;
-            let f2 = params.formants[1].frequency;
-            // Skip if both frequencies are too low (silence)
-            if f1 < 100.0 && f2 < 100.0 {
-                Phoneme::Closed
+/// Like `sync_voice_to_animation`, with confidence gating, a confidence-weighted majority
+/// filter and minimum phoneme duration to stop flicker on noisy audio.
+#[cfg(feature = "voice")]
+pub fn sync_voice_to_animation_smoothed(
+    voice_params: &[ParametricParams],
+    frame_duration: f32,
+    config: &SmoothingConfig,
+) -> LipSyncTrack {
+    let formants: Vec<(f32, f32)> = voice_params
+        .iter()
+        .map(|params| {
+            // Extract F1 and F2 from formants
+            if params.formants.len() >= 2 {
+                (params.formants[0].frequency, params.formants[1].frequency)
             } else {
-                classify_phoneme(f1, f2)
+                (0.0, 0.0)
             }
-        } else {
-            Phoneme::Closed
+        })
+        .collect();
+    sync_formants_to_animation(&formants, frame_duration, config)
+}
+
+/// Lip sync from raw per-frame (F1, F2) formants, for analysis sources other than ALICE-Voice.
+pub fn sync_formants_to_animation(
+    formants: &[(f32, f32)],
+    frame_duration: f32,
+    config: &SmoothingConfig,
+) -> LipSyncTrack {
+    let frames: Vec<(Phoneme, f32)> = formants
+        .iter()
+        .map(|&(f1, f2)| classify_formants(f1, f2))
+        .collect();
+    track_from_frames(&frames, frame_duration, config)
+}
+
+/// Filter per-frame `(phoneme, confidence)` classifications and emit keyframes.
+fn track_from_frames(
+    classified: &[(Phoneme, f32)],
+    frame_duration: f32,
+    config: &SmoothingConfig,
+) -> LipSyncTrack {
+    // 1. Hold the previous phoneme through low-confidence frames
+    let mut frames: Vec<(Phoneme, f32)> = Vec::with_capacity(classified.len());
+    for &(phoneme, confidence) in classified {
+        let held = match frames.last() {
+            Some(&(prev, _)) if confidence < config.min_confidence => prev,
+            _ => phoneme,
         };
+        frames.push((held, confidence));
+    }
+
+    // 2. Confidence-weighted majority filter
+    let half = config.window.max(1) / 2;
+    if half > 0 {
+        let raw = frames.clone();
+        for (i, frame) in frames.iter_mut().enumerate() {
+            let lo = i.saturating_sub(half);
+            let hi = (i + half + 1).min(raw.len());
+            let mut votes: Vec<(Phoneme, f32)> = Vec::new();
+            for &(p, c) in &raw[lo..hi] {
+                match votes.iter_mut().find(|(vp, _)| *vp == p) {
+                    Some((_, w)) => *w += c.max(0.01),
+                    None => votes.push((p, c.max(0.01))),
+                }
+            }
+            if let Some(&(winner, _)) = votes
+                .iter()
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(core::cmp::Ordering::Equal))
+            {
+                frame.0 = winner;
+            }
+        }
+    }
 
+    // 3. Run-length segments: (phoneme, start frame, frame count, confidence sum)
+    let mut segments: Vec<(Phoneme, usize, usize, f32)> = Vec::new();
+    for (i, &(p, c)) in frames.iter().enumerate() {
+        match segments.last_mut() {
+            Some(seg) if seg.0 == p => {
+                seg.2 += 1;
+                seg.3 += c;
+            }
+            _ => segments.push((p, i, 1, c)),
+        }
+    }
+
+    // 4. Merge too-short segments into the previous one (or the next, at the start)
+    let min_frames = if frame_duration > 0.0 {
+        (config.min_duration / frame_duration).ceil() as usize
+    } else {
+        0
+    };
+    let mut merged: Vec<(Phoneme, usize, usize, f32)> = Vec::with_capacity(segments.len());
+    for seg in segments {
+        match merged.last_mut() {
+            Some(prev) if seg.2 < min_frames || prev.0 == seg.0 => {
+                prev.2 += seg.2;
+                prev.3 += seg.3;
+            }
+            Some(prev) if prev.2 < min_frames && prev.1 == 0 => {
+                *prev = (seg.0, 0, prev.2 + seg.2, prev.3 + seg.3);
+            }
+            _ => merged.push(seg),
+        }
+    }
+
+    let mut track = LipSyncTrack::new("lip_sync");
+    let mut prev_phoneme = Phoneme::Closed;
+    for (phoneme, start, count, confidence_sum) in merged {
         // Only add keyframes on phoneme changes to reduce data
         if phoneme != prev_phoneme {
-            track.add_phoneme(time, phoneme);
+            track.add_keyframe(PhonemeKeyframe {
+                time: start as f32 * frame_duration,
+                phoneme,
+                confidence: confidence_sum / count as f32,
+            });
             prev_phoneme = phoneme;
         }
     }
 
     // Close mouth at end
     if prev_phoneme != Phoneme::Closed {
-        let end_time = voice_params.len() as f32 * frame_duration;
+        let end_time = classified.len() as f32 * frame_duration;
         track.add_phoneme(end_time, Phoneme::Closed);
     }
 
@@ -192,6 +727,159 @@ mod tests {
         assert_eq!(classify_phoneme(500.0, 900.0), Phoneme::O);
     }
 
+    #[test]
+    fn test_classify_consonants() {
+        assert_eq!(classify_phoneme(200.0, 1000.0), Phoneme::M);
+        assert_eq!(classify_phoneme(220.0, 1600.0), Phoneme::N);
+        assert_eq!(classify_phoneme(280.0, 3200.0), Phoneme::S);
+        assert_eq!(classify_phoneme(450.0, 3000.0), Phoneme::F);
+        assert_eq!(classify_phoneme(320.0, 700.0), Phoneme::W);
+        assert!(Phoneme::M.is_consonant());
+        assert!(!Phoneme::Closed.is_consonant());
+        assert_eq!(Phoneme::M.lip_press(), 1.0);
+    }
+
+    #[test]
+    fn test_retime_and_trim() {
+        let mut track = LipSyncTrack::new("line");
+        track.add_phoneme(0.0, Phoneme::A);
+        track.add_phoneme(1.0, Phoneme::I);
+        track.add_phoneme(2.0, Phoneme::O);
+        track.add_phoneme(3.0, Phoneme::Closed);
+
+        let mut shifted = track.clone();
+        shifted.shift(0.5);
+        assert_eq!(shifted.phoneme_at(1.2), Phoneme::A);
+        shifted.scale(2.0, 0.5);
+        assert_eq!(shifted.duration(), 6.5);
+
+        // Stretch the い syllable from 1s to 2s; the rest follows
+        let mut retimed = track.clone();
+        retimed.retime_region(1.0, 2.0, 1.0, 3.0);
+        assert_eq!(retimed.phoneme_at(2.5), Phoneme::I);
+        assert_eq!(retimed.duration(), 4.0);
+
+        let mut trimmed = track.clone();
+        trimmed.trim(1.5, 2.5);
+        let shapes: Vec<(f32, Phoneme)> = trimmed
+            .phonemes
+            .iter()
+            .map(|k| (k.time, k.phoneme))
+            .collect();
+        assert_eq!(
+            shapes,
+            vec![(1.5, Phoneme::I), (2.0, Phoneme::O), (2.5, Phoneme::Closed)]
+        );
+
+        let mut early = track.clone();
+        early.shift(-1.5);
+        assert_eq!(early.phonemes[0].time, 0.0);
+        assert_eq!(early.phonemes[0].phoneme, Phoneme::I);
+    }
+
+    #[test]
+    fn test_confidence() {
+        let (p, c) = classify_with_confidence(750.0, 1300.0);
+        assert_eq!(p, Phoneme::A);
+        assert!(c > 0.9);
+        let (_, borderline) = classify_with_confidence(620.0, 1550.0);
+        assert!(borderline < c);
+    }
+
+    #[test]
+    fn test_smoothing_removes_flicker() {
+        // あ with single-frame glitches to い
+        let frames: Vec<(Phoneme, f32)> = (0..30)
+            .map(|i| {
+                if i % 7 == 3 {
+                    classify_with_confidence(300.0, 2400.0)
+                } else {
+                    classify_with_confidence(750.0, 1300.0)
+                }
+            })
+            .collect();
+        let raw = track_from_frames(&frames, 1.0 / 60.0, &SmoothingConfig::none());
+        assert!(raw.phonemes.len() > 2);
+
+        let smoothed = track_from_frames(&frames, 1.0 / 60.0, &SmoothingConfig::default());
+        let shapes: Vec<Phoneme> = smoothed.phonemes.iter().map(|k| k.phoneme).collect();
+        assert_eq!(shapes, vec![Phoneme::A, Phoneme::Closed]);
+        assert!(smoothed.phonemes[0].confidence > 0.5);
+    }
+
+    #[test]
+    fn test_append_takes() {
+        let mut take1 = LipSyncTrack::new("take1");
+        take1.add_phoneme(0.0, Phoneme::Closed);
+        take1.add_phoneme(0.2, Phoneme::A);
+        take1.add_phoneme(0.8, Phoneme::Closed);
+        take1.add_phoneme(1.0, Phoneme::Closed);
+        let mut take2 = LipSyncTrack::new("take2");
+        take2.add_phoneme(0.0, Phoneme::Closed);
+        take2.add_phoneme(0.1, Phoneme::O);
+
+        let track = LipSyncTrack::concat("scene", &[(&take1, 0.0), (&take2, 1.5)]);
+        let times: Vec<f32> = track.phonemes.iter().map(|kf| kf.time).collect();
+        assert_eq!(times, vec![0.0, 0.2, 0.8, 1.6]);
+        assert_eq!(track.phoneme_at(1.7), Phoneme::O);
+
+        // Overlapping append cuts the held vowel
+        let mut early = take1.clone();
+        early.append(&take2, 0.5);
+        assert_eq!(early.phoneme_at(0.55), Phoneme::Closed);
+        assert_eq!(early.phoneme_at(0.7), Phoneme::O);
+    }
+
+    #[test]
+    fn test_merge_overlay() {
+        let mut base = LipSyncTrack::new("a");
+        base.add_phoneme(0.0, Phoneme::I);
+        base.add_phoneme(1.0, Phoneme::Closed);
+        let mut other = LipSyncTrack::new("b");
+        other.add_phoneme(0.0, Phoneme::U);
+        other.add_phoneme(0.2, Phoneme::Closed);
+
+        base.merge(&other, 0.5);
+        assert_eq!(base.phoneme_at(0.2), Phoneme::I);
+        assert_eq!(base.phoneme_at(0.6), Phoneme::U);
+        assert_eq!(base.phoneme_at(0.8), Phoneme::I);
+        assert_eq!(base.phoneme_at(1.2), Phoneme::Closed);
+    }
+
+    #[test]
+    fn test_viseme_profile() {
+        use alice_sdf::SdfNode;
+
+        let profile = VisemeProfile::new("chibi")
+            .with_ranges(0.5, 1.0)
+            .with_corner_bias(0.3)
+            .with_override(
+                Phoneme::I,
+                VisemeShape {
+                    openness: 0.1,
+                    width: 0.7,
+                    lip_press: 0.0,
+                    corner: 0.8,
+                },
+            );
+        let actor = Actor::new("chibi", SdfNode::sphere(1.0)).with_viseme_profile(profile.clone());
+
+        let mut track = LipSyncTrack::new("line");
+        track.add_phoneme(0.0, Phoneme::A);
+        track.add_phoneme(0.5, Phoneme::I);
+        let tl = track.to_timeline_for(&actor);
+        assert_eq!(tl.tracks.len(), 4);
+        assert_eq!(tl.get_value("mouth.openness", 0.0), Some(0.5));
+        assert_eq!(tl.get_value("mouth.corner", 0.0), Some(0.3));
+        assert_eq!(tl.get_value("mouth.width", 0.5), Some(0.7));
+        assert_eq!(tl.get_value("mouth.corner", 0.5), Some(0.8));
+
+        // Serialized with the actor
+        let bytes = bincode::serialize(&actor).unwrap();
+        let restored: Actor = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(restored.viseme_profile, Some(profile));
+    }
+
     #[test]
     fn test_lip_sync_track_to_timeline() {
         let mut track = LipSyncTrack::new("test");
@@ -200,7 +888,7 @@ mod tests {
         track.add_phoneme(1.0, Phoneme::Closed);
 
         let tl = track.to_timeline();
-        assert_eq!(tl.tracks.len(), 2);
+        assert_eq!(tl.tracks.len(), 3);
 
         let openness = tl.get_value("mouth.openness", 0.0).unwrap();
         assert_eq!(openness, 1.0); // A = fully open
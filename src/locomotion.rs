@@ -0,0 +1,261 @@
+//! Procedural walk and run cycles on named channels ("hip.y", "leg.l.swing", ...)
+//! for bootstrapping character animation, with anime contact-pose accents.
+
+use std::f32::consts::{PI, TAU};
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+use crate::gesture::layer_additive;
+use crate::scene::Actor;
+
+/// Channels written by the generator (angles in radians, offsets in scene units).
+pub const LOCOMOTION_CHANNELS: [&str; 11] = [
+    "hip.y",
+    "spine.lean",
+    "leg.l.swing",
+    "leg.r.swing",
+    "knee.l.bend",
+    "knee.r.bend",
+    "foot.l.lift",
+    "foot.r.lift",
+    "arm.l.swing",
+    "arm.r.swing",
+    "head.bob",
+];
+
+/// Gait family.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gait {
+    Walk,
+    /// Run: both feet leave the ground between contacts.
+    Run,
+}
+
+/// Cycle parameters. One cycle is two steps (left contact, right contact).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocomotionParams {
+    pub gait: Gait,
+    /// Distance covered per step.
+    pub stride_length: f32,
+    /// Steps per minute.
+    pub cadence: f32,
+    /// Hip rise between contact and passing pose.
+    pub bounce: f32,
+    /// Peak arm swing (radians).
+    pub arm_swing: f32,
+    /// Peak leg swing (radians).
+    pub leg_swing: f32,
+    /// Peak knee bend on the swinging leg (radians).
+    pub knee_bend: f32,
+    /// Forward spine lean (radians).
+    pub lean: f32,
+    /// Anime accent (0..1): lingers on contact poses and snaps through passing,
+    /// with a deeper hip drop on impact.
+    pub contact_accent: f32,
+}
+
+impl LocomotionParams {
+    pub fn walk() -> Self {
+        Self {
+            gait: Gait::Walk,
+            stride_length: 0.7,
+            cadence: 110.0,
+            bounce: 0.04,
+            arm_swing: 0.35,
+            leg_swing: 0.45,
+            knee_bend: 0.6,
+            lean: 0.05,
+            contact_accent: 0.0,
+        }
+    }
+
+    pub fn run() -> Self {
+        Self {
+            gait: Gait::Run,
+            stride_length: 1.4,
+            cadence: 170.0,
+            bounce: 0.08,
+            arm_swing: 0.8,
+            leg_swing: 0.7,
+            knee_bend: 1.4,
+            lean: 0.2,
+            contact_accent: 0.0,
+        }
+    }
+
+    pub fn with_stride(mut self, stride_length: f32, cadence: f32) -> Self {
+        self.stride_length = stride_length;
+        self.cadence = cadence.max(1.0);
+        self
+    }
+
+    pub fn with_bounce(mut self, bounce: f32) -> Self {
+        self.bounce = bounce;
+        self
+    }
+
+    pub fn with_arm_swing(mut self, arm_swing: f32) -> Self {
+        self.arm_swing = arm_swing;
+        self
+    }
+
+    pub fn with_contact_accent(mut self, accent: f32) -> Self {
+        self.contact_accent = accent.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Seconds per cycle (two steps).
+    pub fn cycle_duration(&self) -> f32 {
+        120.0 / self.cadence.max(1.0)
+    }
+
+    /// Forward speed in units per second.
+    pub fn speed(&self) -> f32 {
+        self.stride_length * self.cadence / 60.0
+    }
+
+    /// Contact-accent time warp: slow near contacts (phase 0 and 0.5), fast through passing.
+    fn warp(&self, phase: f32) -> f32 {
+        // Derivative 1 - k*cos(4*pi*phase): below 1 at contacts, above at passing
+        let k = self.contact_accent * 0.8;
+        phase - k * (2.0 * TAU * phase).sin() / (2.0 * TAU)
+    }
+
+    /// Channel values at cycle `phase` in [0, 1), in `LOCOMOTION_CHANNELS` order.
+    pub fn pose(&self, phase: f32) -> [f32; 11] {
+        let p = self.warp(phase.rem_euclid(1.0));
+        let (sin, cos) = (TAU * p).sin_cos();
+        // 0 at each contact, 1 at passing (walk) / mid-flight (run)
+        let rise = (PI * 2.0 * p).sin().abs();
+        let impact = (1.0 - rise).powi(4);
+        let hip = match self.gait {
+            Gait::Walk => self.bounce * rise,
+            Gait::Run => self.bounce * rise.sqrt(),
+        } - self.contact_accent * self.bounce * 0.5 * impact;
+        let leg_l = self.leg_swing * cos;
+        let knee_l = self.knee_bend * (-sin).max(0.0);
+        let knee_r = self.knee_bend * sin.max(0.0);
+        let foot_lift = self.stride_length * 0.15;
+        [
+            hip,
+            self.lean + self.lean * 0.25 * rise,
+            leg_l,
+            -leg_l,
+            knee_l,
+            knee_r,
+            foot_lift * (-sin).max(0.0),
+            foot_lift * sin.max(0.0),
+            -self.arm_swing * cos,
+            self.arm_swing * cos,
+            hip * 0.5,
+        ]
+    }
+}
+
+/// One seamless cycle over `[0, cycle_duration]`, `samples` keys per channel (first == last).
+pub fn generate_cycle(params: &LocomotionParams, samples: usize) -> Timeline {
+    generate_locomotion(params, params.cycle_duration(), samples, false)
+}
+
+/// Repeat the cycle over `duration`. With `travel`, adds "translate.z" at the gait's speed.
+pub fn generate_locomotion(
+    params: &LocomotionParams,
+    duration: f32,
+    samples_per_cycle: usize,
+    travel: bool,
+) -> Timeline {
+    let mut tl = Timeline::new(match params.gait {
+        Gait::Walk => "walk",
+        Gait::Run => "run",
+    });
+    let cycle = params.cycle_duration();
+    let step = cycle / samples_per_cycle.max(4) as f32;
+    let count = (duration / step).round().max(1.0) as usize;
+    let mut tracks: Vec<Track> = LOCOMOTION_CHANNELS.iter().map(|n| Track::new(n)).collect();
+    let mut forward = Track::new("translate.z");
+    for i in 0..=count {
+        let t = (i as f32 * step).min(duration);
+        let pose = params.pose(t / cycle);
+        for (track, value) in tracks.iter_mut().zip(pose) {
+            track.add_keyframe(Keyframe::new(t, value));
+        }
+        forward.add_keyframe(Keyframe::new(t, params.speed() * t));
+    }
+    for track in tracks {
+        tl.add_track(track);
+    }
+    if travel {
+        tl.add_track(forward);
+    }
+    tl
+}
+
+/// Layer a locomotion run of `duration` seconds onto an actor's timeline from `start_time`.
+pub fn apply_locomotion(
+    actor: &mut Actor,
+    params: &LocomotionParams,
+    start_time: f32,
+    duration: f32,
+    travel: bool,
+) {
+    let layer = generate_locomotion(params, duration, 16, travel);
+    let timeline = actor
+        .timeline
+        .get_or_insert_with(|| Timeline::new(&actor.name));
+    layer_additive(timeline, &layer, start_time);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::SdfNode;
+
+    #[test]
+    fn test_walk_cycle_contacts_and_loop() {
+        let params = LocomotionParams::walk();
+        assert!((params.cycle_duration() - 120.0 / 110.0).abs() < 1e-6);
+        let tl = generate_cycle(&params, 16);
+        let end = params.cycle_duration();
+        for name in LOCOMOTION_CHANNELS {
+            let (a, b) = (
+                tl.get_value(name, 0.0).unwrap(),
+                tl.get_value(name, end).unwrap(),
+            );
+            assert!((a - b).abs() < 1e-4, "{name} does not loop");
+        }
+        // Left heel strike: left leg forward, right back, left arm back, hips down
+        assert!((tl.get_value("leg.l.swing", 0.0).unwrap() - params.leg_swing).abs() < 1e-5);
+        assert!(tl.get_value("arm.l.swing", 0.0).unwrap() < 0.0);
+        assert!(tl.get_value("hip.y", 0.0).unwrap().abs() < 1e-5);
+        // Passing pose a quarter cycle later: hips up, right knee bent under the body
+        let passing = end * 0.25;
+        assert!((tl.get_value("hip.y", passing).unwrap() - params.bounce).abs() < 1e-3);
+        assert!(tl.get_value("knee.r.bend", passing).unwrap() > 0.5 * params.knee_bend);
+        assert_eq!(tl.get_value("knee.l.bend", passing), Some(0.0));
+    }
+
+    #[test]
+    fn test_contact_accent_lingers() {
+        let plain = LocomotionParams::run();
+        let accented = plain.with_contact_accent(1.0);
+        let change =
+            |p: &LocomotionParams, phase: f32| (p.pose(phase + 0.02)[2] - p.pose(phase)[2]).abs();
+        // Slower through the contact pose, faster through passing
+        assert!(change(&accented, 0.24) > change(&plain, 0.24));
+        assert!(accented.warp(0.52) - accented.warp(0.48) < 0.5 * 0.04);
+        // Deeper impact drop at contact
+        assert!(accented.pose(0.0)[0] < plain.pose(0.0)[0]);
+    }
+
+    #[test]
+    fn test_apply_locomotion_travels() {
+        let params = LocomotionParams::walk().with_stride(0.6, 120.0);
+        assert!((params.speed() - 1.2).abs() < 1e-6);
+        let mut actor = Actor::new("extra", SdfNode::sphere(0.5));
+        apply_locomotion(&mut actor, &params, 1.0, 3.0, true);
+        let tl = actor.timeline.as_ref().unwrap();
+        assert_eq!(tl.get_value("translate.z", 0.5), Some(0.0));
+        assert!((tl.get_value("translate.z", 4.0).unwrap() - 3.6).abs() < 1e-4);
+        assert!(tl.get_value("leg.r.swing", 2.0).is_some());
+    }
+}
@@ -0,0 +1,204 @@
+//! Episode metadata: title, length, resolution and studio-defined extension fields.
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::units::{Units, UpAxis};
+
+/// Metadata extension key holding the episode random seed.
+pub const SEED_EXTENSION: &str = "alice.seed";
+/// Metadata extension key holding the project up axis (`"Y"` or `"Z"`).
+pub const UP_AXIS_EXTENSION: &str = "alice.up_axis";
+/// Metadata extension key holding meters per scene unit (absent for arbitrary units).
+pub const METERS_PER_UNIT_EXTENSION: &str = "alice.meters_per_unit";
+
+/// Typed value for studio-defined metadata extensions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum MetadataValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    List(Vec<MetadataValue>),
+}
+
+impl MetadataValue {
+    #[inline]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            MetadataValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            MetadataValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Float value; integers are widened.
+    #[inline]
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            MetadataValue::Float(v) => Some(*v),
+            MetadataValue::Int(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MetadataValue::Text(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl From<bool> for MetadataValue {
+    fn from(v: bool) -> Self {
+        MetadataValue::Bool(v)
+    }
+}
+
+impl From<i64> for MetadataValue {
+    fn from(v: i64) -> Self {
+        MetadataValue::Int(v)
+    }
+}
+
+impl From<f64> for MetadataValue {
+    fn from(v: f64) -> Self {
+        MetadataValue::Float(v)
+    }
+}
+
+impl From<&str> for MetadataValue {
+    fn from(v: &str) -> Self {
+        MetadataValue::Text(v.to_string())
+    }
+}
+
+impl From<String> for MetadataValue {
+    fn from(v: String) -> Self {
+        MetadataValue::Text(v)
+    }
+}
+
+/// Episode metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpisodeMetadata {
+    pub title: String,
+    pub episode_number: u32,
+    pub duration_seconds: f32,
+    pub resolution: (u32, u32),
+    /// Studio-defined fields (production codes, rating info, pipeline IDs).
+    /// BTreeMap keeps serialization order deterministic.
+    pub extensions: BTreeMap<String, MetadataValue>,
+}
+
+/// Metadata layout of version 1 episodes, before extensions.
+#[derive(Deserialize)]
+pub(crate) struct EpisodeMetadataV1 {
+    title: String,
+    episode_number: u32,
+    duration_seconds: f32,
+    resolution: (u32, u32),
+}
+
+impl From<EpisodeMetadataV1> for EpisodeMetadata {
+    fn from(v1: EpisodeMetadataV1) -> Self {
+        Self {
+            resolution: v1.resolution,
+            ..EpisodeMetadata::new(v1.title, v1.episode_number, v1.duration_seconds)
+        }
+    }
+}
+
+impl EpisodeMetadata {
+    pub fn new(title: impl Into<String>, episode_number: u32, duration: f32) -> Self {
+        Self {
+            title: title.into(),
+            episode_number,
+            duration_seconds: duration,
+            resolution: (1920, 1080),
+            extensions: BTreeMap::new(),
+        }
+    }
+
+    /// Attach an extension field.
+    pub fn with_extension(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<MetadataValue>,
+    ) -> Self {
+        self.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    /// Set an extension field, returning the previous value.
+    pub fn set_extension(
+        &mut self,
+        key: impl Into<String>,
+        value: impl Into<MetadataValue>,
+    ) -> Option<MetadataValue> {
+        self.extensions.insert(key.into(), value.into())
+    }
+
+    /// Look up an extension field.
+    #[inline]
+    pub fn extension(&self, key: &str) -> Option<&MetadataValue> {
+        self.extensions.get(key)
+    }
+
+    /// Store the episode random seed (extension `alice.seed`).
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.with_extension(SEED_EXTENSION, seed as i64)
+    }
+
+    /// Episode random seed; 0 when none was stored.
+    pub fn seed(&self) -> u64 {
+        self.extension(SEED_EXTENSION)
+            .and_then(MetadataValue::as_i64)
+            .map_or(0, |s| s as u64)
+    }
+
+    /// Store the project's axis convention and length unit.
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.set_units(units);
+        self
+    }
+
+    pub fn set_units(&mut self, units: Units) {
+        self.set_extension(UP_AXIS_EXTENSION, units.up_axis.name());
+        match units.meters_per_unit {
+            Some(m) => {
+                self.set_extension(METERS_PER_UNIT_EXTENSION, m as f64);
+            }
+            None => {
+                self.extensions.remove(METERS_PER_UNIT_EXTENSION);
+            }
+        }
+    }
+
+    /// Project units; Y-up arbitrary units when none were stored.
+    pub fn units(&self) -> Units {
+        let up_axis = self
+            .extension(UP_AXIS_EXTENSION)
+            .and_then(MetadataValue::as_str)
+            .and_then(UpAxis::parse)
+            .unwrap_or_default();
+        let meters_per_unit = self
+            .extension(METERS_PER_UNIT_EXTENSION)
+            .and_then(MetadataValue::as_f64)
+            .map(|m| m as f32);
+        Units::new(up_axis, meters_per_unit)
+    }
+}
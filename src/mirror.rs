@@ -0,0 +1,258 @@
+//! Left/right mirroring of transforms, timelines and rigs.
+//!
+//! A [`Mirror`] reflects across the plane through the origin whose normal is its axis
+//! (X by default: left and right in both Y-up and Z-up projects). Side-named channels and
+//! actors (`arm.l` / `arm.r`, `hand_l`, `left_ankle`) trade places; translations along
+//! the axis and rotations about the other two axes flip sign. Mirroring a walk cycle
+//! swaps its legs and arms, so one side of a symmetric action is all that needs keys.
+
+use std::collections::HashMap;
+
+use alice_sdf::animation::Timeline;
+use glam::{Quat, Vec3};
+
+use crate::scene::{ActorId, ActorTransform, SceneGraph};
+
+/// True for track groups whose `.x/.y/.z` channels are positions: `translate`,
+/// `position`, `target` and imported `joint.*` points.
+pub(crate) fn is_translation_group(group: &str) -> bool {
+    let last = group.rsplit('.').next().unwrap_or(group);
+    matches!(last, "translate" | "position" | "target") || group.starts_with("joint.")
+}
+
+/// Normal of the mirror plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MirrorAxis {
+    #[default]
+    X,
+    Y,
+    Z,
+}
+
+impl MirrorAxis {
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    #[inline]
+    fn letter(self) -> &'static str {
+        ["x", "y", "z"][self.index()]
+    }
+}
+
+/// Reflection across one plane, with side-name swapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mirror {
+    pub axis: MirrorAxis,
+    /// Swap left and right in channel and actor names.
+    pub swap_sides: bool,
+}
+
+impl Default for Mirror {
+    fn default() -> Self {
+        Self {
+            axis: MirrorAxis::X,
+            swap_sides: true,
+        }
+    }
+}
+
+impl Mirror {
+    pub fn new(axis: MirrorAxis) -> Self {
+        Self {
+            axis,
+            ..Self::default()
+        }
+    }
+
+    #[inline]
+    pub fn point(&self, p: Vec3) -> Vec3 {
+        let mut a = p.to_array();
+        a[self.axis.index()] = -a[self.axis.index()];
+        Vec3::from_array(a)
+    }
+
+    /// Reflected rotation: turns about the axis are kept, turns about the others reverse.
+    #[inline]
+    pub fn rotation(&self, q: Quat) -> Quat {
+        let mut a = q.to_array();
+        for (i, v) in a.iter_mut().take(3).enumerate() {
+            if i != self.axis.index() {
+                *v = -*v;
+            }
+        }
+        Quat::from_array(a)
+    }
+
+    pub fn transform(&self, t: &ActorTransform) -> ActorTransform {
+        ActorTransform {
+            position: self.point(t.position),
+            rotation: self.rotation(t.rotation),
+            scale: t.scale,
+        }
+    }
+
+    /// Name on the other side: `.l.`/`.r.` segments (either case), `_l`/`_r` suffixes and
+    /// `left`/`right` words. Names without a side come back unchanged.
+    pub fn name(&self, name: &str) -> String {
+        if !self.swap_sides {
+            return name.to_string();
+        }
+        name.split('.')
+            .map(|part| match part {
+                "l" => "r".to_string(),
+                "r" => "l".to_string(),
+                "L" => "R".to_string(),
+                "R" => "L".to_string(),
+                _ if part.ends_with("_l") => format!("{}_r", &part[..part.len() - 2]),
+                _ if part.ends_with("_r") => format!("{}_l", &part[..part.len() - 2]),
+                _ if part.contains("left") => part.replace("left", "right"),
+                _ if part.contains("right") => part.replace("right", "left"),
+                _ => part.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// -1 for channels that change sign in the mirror: `<translation>.<axis>` and
+    /// `<rotation>.<other axis>`, where translations are `translate`, `position`, `target`
+    /// and `joint.*` groups and rotations are `rotate`, `rotation` and `orientation` groups.
+    pub fn channel_sign(&self, name: &str) -> f32 {
+        let Some((group, component)) = name.rsplit_once('.') else {
+            return 1.0;
+        };
+        let last = group.rsplit('.').next().unwrap_or(group);
+        let rotation = matches!(last, "rotate" | "rotation" | "orientation");
+        let translation = is_translation_group(group);
+        let on_axis = component == self.axis.letter();
+        let is_axis = matches!(component, "x" | "y" | "z");
+        if (translation && on_axis) || (rotation && is_axis && !on_axis) {
+            -1.0
+        } else {
+            1.0
+        }
+    }
+
+    /// Mirror a single channel: its new name and value.
+    #[inline]
+    pub fn channel(&self, name: &str, value: f32) -> (String, f32) {
+        let name = self.name(name);
+        let sign = self.channel_sign(&name);
+        (name, value * sign)
+    }
+
+    /// Every track renamed to the other side with its values reflected.
+    pub fn timeline(&self, timeline: &Timeline) -> Timeline {
+        let mut out = timeline.clone();
+        for track in &mut out.tracks {
+            track.name = self.name(&track.name);
+            let sign = self.channel_sign(&track.name);
+            if sign < 0.0 {
+                for key in &mut track.keyframes {
+                    key.value = -key.value;
+                }
+            }
+        }
+        out
+    }
+
+    /// Mirror a rig (a set of actors) in place. Actors whose side-swapped name is also in
+    /// the set trade mirrored transforms and timelines; the rest are mirrored on their own.
+    pub fn rig(&self, scene: &mut SceneGraph, actors: &[ActorId]) {
+        let by_name: HashMap<String, ActorId> = actors
+            .iter()
+            .filter_map(|&id| scene.get_actor(id).map(|a| (a.name.clone(), id)))
+            .collect();
+        let mirrored: Vec<(ActorId, ActorTransform, Option<Timeline>)> = actors
+            .iter()
+            .filter_map(|&id| {
+                let actor = scene.get_actor(id)?;
+                // The partner's motion, reflected, lands on this actor
+                let source = by_name
+                    .get(&self.name(&actor.name))
+                    .and_then(|&p| scene.get_actor(p))
+                    .unwrap_or(actor);
+                Some((
+                    id,
+                    self.transform(&source.local_transform),
+                    source.timeline.as_ref().map(|tl| self.timeline(tl)),
+                ))
+            })
+            .collect();
+        for (id, transform, timeline) in mirrored {
+            if let Some(actor) = scene.get_actor_mut(id) {
+                actor.local_transform = transform;
+                actor.timeline = timeline;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::locomotion::{generate_locomotion, LocomotionParams};
+    use crate::scene::Actor;
+    use alice_sdf::SdfNode;
+
+    #[test]
+    fn test_transforms_and_channels() {
+        let mirror = Mirror::default();
+        let t = ActorTransform {
+            position: Vec3::new(1.0, 2.0, 3.0),
+            rotation: Quat::from_rotation_y(0.4) * Quat::from_rotation_x(0.2),
+            scale: Vec3::ONE,
+        };
+        let m = mirror.transform(&t);
+        assert_eq!(m.position, Vec3::new(-1.0, 2.0, 3.0));
+        // Reflecting twice is the identity; reflection commutes with the mirror of points
+        let back = mirror.transform(&m);
+        assert!(back.rotation.dot(t.rotation) > 0.9999);
+        let p = Vec3::new(0.3, -0.5, 0.7);
+        let lhs = mirror.point(t.rotation * p);
+        let rhs = m.rotation * mirror.point(p);
+        assert!((lhs - rhs).length() < 1e-5);
+
+        assert_eq!(
+            mirror.channel("arm.l.swing", 0.5),
+            ("arm.r.swing".into(), 0.5)
+        );
+        assert_eq!(
+            mirror.channel("translate.x", 2.0),
+            ("translate.x".into(), -2.0)
+        );
+        assert_eq!(mirror.channel("rotate.z", 0.3), ("rotate.z".into(), -0.3));
+        assert_eq!(mirror.channel("rotate.x", 0.3).1, 0.3);
+        assert_eq!(mirror.name("joint.left_ankle.x"), "joint.right_ankle.x");
+        assert_eq!(Mirror::new(MirrorAxis::Z).channel_sign("translate.x"), 1.0);
+    }
+
+    #[test]
+    fn test_mirrored_walk_swaps_legs() {
+        let walk = generate_locomotion(&LocomotionParams::walk(), 2.0, 16, false);
+        let mirrored = Mirror::default().timeline(&walk);
+        for t in [0.1, 0.37, 0.8] {
+            let left = walk.get_value("leg.l.swing", t).unwrap();
+            assert_eq!(mirrored.get_value("leg.r.swing", t), Some(left));
+        }
+
+        let mut sg = SceneGraph::new();
+        let at = |x: f32| ActorTransform {
+            position: Vec3::new(x, 1.0, 0.0),
+            ..ActorTransform::default()
+        };
+        let left = sg.add_actor(Actor::new("arm.l", SdfNode::sphere(0.1)).with_transform(at(0.5)));
+        let right = sg.add_actor(
+            Actor::new("arm.r", SdfNode::sphere(0.1))
+                .with_transform(at(-0.4))
+                .with_timeline(walk),
+        );
+        let head = sg.add_actor(Actor::new("head", SdfNode::sphere(0.2)).with_transform(at(0.1)));
+        Mirror::default().rig(&mut sg, &[left, right, head]);
+        let x = |id| sg.get_actor(id).unwrap().local_transform.position.x;
+        assert_eq!((x(left), x(right), x(head)), (0.4, -0.5, -0.1));
+        assert!(sg.get_actor(left).unwrap().timeline.is_some());
+        assert!(sg.get_actor(right).unwrap().timeline.is_none());
+    }
+}
@@ -1,9 +1,67 @@
 //! Bridge: ALICE-Animation → ALICE-ML
 //! AI-assisted animation: in-betweening, auto camera work, style transfer.
 
-use crate::{ActorTransform, SceneGraph};
+use crate::units::{Units, UpAxis};
+use crate::{ActorId, ActorTransform, CameraTrack, Cut, Performance, SceneGraph};
 // use alice_ml::{Model, Tensor};
-use glam::Vec3;
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use glam::{Quat, Vec3};
+use std::io;
+
+/// Swappable inference backend. `HeuristicBackend` implements every endpoint with the
+/// pure-Rust heuristics in this module; alice_ml or ONNX backends implement the same
+/// trait, so callers take `&dyn InferenceBackend` and never change.
+pub trait InferenceBackend {
+    /// Backend name for logs and metadata.
+    fn name(&self) -> &str;
+
+    /// Generate in-between frames between two transforms.
+    fn inbetween(&self, request: &InbetweenRequest) -> io::Result<InbetweenResult>;
+
+    /// Solve a root transform from one frame of joint keypoints.
+    fn estimate_pose(&self, request: &PoseRequest) -> io::Result<PoseResult>;
+
+    /// Suggest a camera for the scene.
+    fn suggest_camera(&self, scene: &SceneGraph) -> io::Result<CameraSuggestion>;
+
+    /// Plan camera work for a whole cut. Defaults to `plan_camera_for_cut`.
+    fn plan_camera(
+        &self,
+        scene: Performance<'_>,
+        cut: &Cut,
+        style: ShotStyle,
+    ) -> io::Result<CameraTrack> {
+        Ok(plan_camera_for_cut(scene, cut, style))
+    }
+
+    /// Re-time and re-space a timeline to a motion style. Defaults to
+    /// `transfer_motion_style`.
+    fn transfer_style(&self, timeline: &Timeline, style: &MotionStyle) -> io::Result<Timeline> {
+        Ok(transfer_motion_style(timeline, style))
+    }
+}
+
+/// Default backend: the built-in heuristics, no model files needed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicBackend;
+
+impl InferenceBackend for HeuristicBackend {
+    fn name(&self) -> &str {
+        "heuristic"
+    }
+
+    fn inbetween(&self, request: &InbetweenRequest) -> io::Result<InbetweenResult> {
+        Ok(generate_inbetweens(request))
+    }
+
+    fn estimate_pose(&self, request: &PoseRequest) -> io::Result<PoseResult> {
+        Ok(estimate_pose(request))
+    }
+
+    fn suggest_camera(&self, scene: &SceneGraph) -> io::Result<CameraSuggestion> {
+        Ok(suggest_camera(scene))
+    }
+}
 
 /// AI in-betweening: generate intermediate frames between two keyframes.
 #[derive(Debug, Clone)]
@@ -25,6 +83,8 @@ pub enum EasingHint {
     Overshoot,
     /// Follow-through with secondary motion.
     FollowThrough,
+    /// Gentle ease-in / ease-out.
+    Smooth,
 }
 
 /// Result of AI in-betweening.
@@ -66,6 +126,95 @@ pub fn generate_inbetweens(request: &InbetweenRequest) -> InbetweenResult {
     }
 }
 
+/// In-between every track of a timeline between two key times.
+#[derive(Debug, Clone)]
+pub struct TimelineInbetweenRequest {
+    pub start_time: f32,
+    pub end_time: f32,
+    pub num_frames: usize,
+    pub easing: EasingHint,
+    /// Per-track overrides: exact track names, or prefixes ending in `*` (`"mouth.*"`).
+    pub track_easing: Vec<(String, EasingHint)>,
+}
+
+impl TimelineInbetweenRequest {
+    pub fn new(start_time: f32, end_time: f32, num_frames: usize, easing: EasingHint) -> Self {
+        Self {
+            start_time,
+            end_time,
+            num_frames,
+            easing,
+            track_easing: Vec::new(),
+        }
+    }
+
+    /// Override easing for tracks matching `pattern`.
+    pub fn with_track_easing(mut self, pattern: impl Into<String>, easing: EasingHint) -> Self {
+        self.track_easing.push((pattern.into(), easing));
+        self
+    }
+
+    /// Easing for a track (first matching override, else the default).
+    pub fn easing_for(&self, track: &str) -> EasingHint {
+        self.track_easing
+            .iter()
+            .find(|(pattern, _)| match pattern.strip_suffix('*') {
+                Some(prefix) => track.starts_with(prefix),
+                None => track == pattern,
+            })
+            .map_or(self.easing, |&(_, easing)| easing)
+    }
+}
+
+/// Insert eased in-between keyframes into every track (transforms, SDF parameters,
+/// expressions, ...) that has keys at both `start_time` and `end_time` and none between.
+///
+/// Returns the names of the tracks filled; `InvalidInput` if no track qualifies.
+pub fn inbetween_timeline(
+    timeline: &mut Timeline,
+    request: &TimelineInbetweenRequest,
+) -> io::Result<Vec<String>> {
+    const KEY_EPSILON: f32 = 1e-4;
+    let (start, end) = (request.start_time, request.end_time);
+    let rcp_frames = 1.0 / (request.num_frames + 1) as f32;
+    let mut filled = Vec::new();
+    for track in &mut timeline.tracks {
+        let key_at = |t: f32| {
+            track
+                .keyframes
+                .iter()
+                .find(|k| (k.time - t).abs() < KEY_EPSILON)
+        };
+        let (Some(a), Some(b)) = (key_at(start).copied(), key_at(end).copied()) else {
+            continue;
+        };
+        let occupied = track
+            .keyframes
+            .iter()
+            .any(|k| k.time > a.time + KEY_EPSILON && k.time < b.time - KEY_EPSILON);
+        if occupied || b.time <= a.time {
+            continue;
+        }
+        let easing = request.easing_for(&track.name);
+        for i in 1..=request.num_frames {
+            let t = i as f32 * rcp_frames;
+            let eased = apply_easing(t, easing);
+            track.add_keyframe(Keyframe::new(
+                a.time + (b.time - a.time) * t,
+                a.value + (b.value - a.value) * eased,
+            ));
+        }
+        filled.push(track.name.clone());
+    }
+    if filled.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("No track has adjacent keys at {} and {}", start, end),
+        ));
+    }
+    Ok(filled)
+}
+
 /// Apply easing function to t (0.0 - 1.0).
 #[inline(always)]
 fn apply_easing(t: f32, easing: EasingHint) -> f32 {
@@ -82,6 +231,7 @@ fn apply_easing(t: f32, easing: EasingHint) -> f32 {
             let t1 = t - 1.0;
             t1.mul_add(t1.mul_add(t1 * (s + 1.0) + s, 0.0), 1.0) // FMA chain
         }
+        EasingHint::Smooth => t * t * (3.0 - 2.0 * t),
         EasingHint::FollowThrough => {
             // Elastic ease-out (simplified)
             if t <= 0.0 || t >= 1.0 {
@@ -96,6 +246,70 @@ fn apply_easing(t: f32, easing: EasingHint) -> f32 {
     }
 }
 
+/// One frame of joint keypoints from an external pose estimator.
+#[derive(Debug, Clone, Default)]
+pub struct PoseRequest {
+    /// Named joints (`left_hip`, `right_shoulder`, ...) in world units.
+    pub joints: Vec<(String, Vec3)>,
+}
+
+impl PoseRequest {
+    /// Position of a named joint.
+    pub fn joint(&self, name: &str) -> Option<Vec3> {
+        self.joints.iter().find(|(n, _)| n == name).map(|&(_, p)| p)
+    }
+}
+
+/// Root transform solved from keypoints.
+#[derive(Debug, Clone)]
+pub struct PoseResult {
+    pub transform: ActorTransform,
+    pub confidence: f32,
+}
+
+/// Heuristic pose solve: root at the hip midpoint (or joint centroid), facing
+/// perpendicular to the shoulder (or hip) line.
+pub fn estimate_pose(request: &PoseRequest) -> PoseResult {
+    if request.joints.is_empty() {
+        return PoseResult {
+            transform: ActorTransform::default(),
+            confidence: 0.0,
+        };
+    }
+    let pair = |l: &str, r: &str| request.joint(l).zip(request.joint(r));
+    let hips = pair("left_hip", "right_hip");
+    let position = match hips {
+        Some((l, r)) => (l + r) * 0.5,
+        None => {
+            let sum: Vec3 = request.joints.iter().map(|&(_, p)| p).sum();
+            sum / request.joints.len() as f32
+        }
+    };
+
+    // Character faces along +Z at rest; left-to-right runs along -X
+    let across = pair("left_shoulder", "right_shoulder").or(hips);
+    let (rotation, confidence) = match across {
+        Some((l, r)) => {
+            let right = Vec3::new(r.x - l.x, 0.0, r.z - l.z);
+            if right.length_squared() > 1e-8 {
+                let forward = Vec3::Y.cross(right.normalize());
+                (Quat::from_rotation_arc(Vec3::Z, forward), 0.7)
+            } else {
+                (Quat::IDENTITY, 0.4)
+            }
+        }
+        None => (Quat::IDENTITY, 0.3),
+    };
+    PoseResult {
+        transform: ActorTransform {
+            position,
+            rotation,
+            scale: Vec3::ONE,
+        },
+        confidence,
+    }
+}
+
 /// Auto camera suggestion based on scene composition.
 #[derive(Debug, Clone)]
 pub struct CameraSuggestion {
@@ -132,6 +346,443 @@ pub fn suggest_camera(scene: &SceneGraph) -> CameraSuggestion {
     }
 }
 
+/// Shot grammar for `plan_camera_for_cut`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShotStyle {
+    /// Choose from the cut's actors and their motion.
+    #[default]
+    Auto,
+    /// Wide frame on everyone, pushing in over the cut.
+    EstablishingPushIn,
+    /// Alternating over-the-shoulder angles between the first two actors.
+    ShotReverseShot,
+    /// Side-on tracking of the fastest-moving actor.
+    ActionFollow,
+}
+
+/// Actor positions sampled per cut when planning.
+const PLAN_SAMPLES: usize = 8;
+/// Average speed (units/s) above which `Auto` picks `ActionFollow`.
+const ACTION_SPEED: f32 = 1.5;
+/// Length of each angle in shot/reverse-shot.
+const DIALOGUE_SHOT_LENGTH: f32 = 2.0;
+/// Gap between the held and the new key of a hard angle change.
+const HARD_CUT: f32 = 1e-3;
+
+/// Plan a full camera track for `cut` from how its actors move over the cut
+/// (keyframes are cut-local, as `Director::evaluate` expects).
+///
+/// Uses the cut's active actors, or every visible actor if none are set.
+pub fn plan_camera_for_cut(scene: Performance<'_>, cut: &Cut, style: ShotStyle) -> CameraTrack {
+    let fov = std::f32::consts::FRAC_PI_4;
+    let mut track = CameraTrack::default();
+    track.clear_keyframes();
+
+    let actors: Vec<ActorId> = if cut.active_actors.is_empty() {
+        scene
+            .scene
+            .actor_ids()
+            .into_iter()
+            .filter(|&id| scene.get_actor(id).is_some_and(|a| a.visible))
+            .collect()
+    } else {
+        cut.active_actors.clone()
+    };
+    if actors.is_empty() {
+        track.add_keyframe(0.0, Vec3::new(0.0, 0.0, 10.0), Vec3::ZERO, fov);
+        return track;
+    }
+
+    let duration = cut.duration().max(0.0);
+    let times: Vec<f32> = (0..=PLAN_SAMPLES)
+        .map(|i| duration * i as f32 / PLAN_SAMPLES as f32)
+        .collect();
+    // paths[actor][sample]
+    let paths: Vec<Vec<Vec3>> = actors
+        .iter()
+        .map(|&id| {
+            times
+                .iter()
+                .map(|&t| scene.actor_position_at(id, cut.start_time + t))
+                .collect()
+        })
+        .collect();
+    let speeds: Vec<f32> = paths
+        .iter()
+        .map(|path| {
+            let length: f32 = path.windows(2).map(|w| w[0].distance(w[1])).sum();
+            if duration > 0.0 {
+                length / duration
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let (fastest, &top_speed) = speeds
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .unwrap();
+
+    let style = match style {
+        ShotStyle::Auto if top_speed > ACTION_SPEED => ShotStyle::ActionFollow,
+        ShotStyle::Auto | ShotStyle::ShotReverseShot if actors.len() >= 2 => {
+            ShotStyle::ShotReverseShot
+        }
+        ShotStyle::Auto | ShotStyle::ShotReverseShot => ShotStyle::EstablishingPushIn,
+        other => other,
+    };
+
+    match style {
+        ShotStyle::ActionFollow => {
+            let path = &paths[fastest];
+            let travel = (path[PLAN_SAMPLES] - path[0]) * Vec3::new(1.0, 0.0, 1.0);
+            // Motion runs across the frame: camera sits off to the side of it
+            let side = if travel.length_squared() > 1e-6 {
+                Vec3::Y.cross(travel.normalize())
+            } else {
+                Vec3::Z
+            };
+            for (&t, &p) in times.iter().zip(path) {
+                track.add_keyframe(t, p + side * 6.0 + Vec3::Y, p, fov);
+            }
+        }
+        ShotStyle::ShotReverseShot => {
+            let shots = ((duration / DIALOGUE_SHOT_LENGTH).round() as usize).max(2);
+            let shot_length = duration / shots as f32;
+            let mut previous: Option<(Vec3, Vec3)> = None;
+            for k in 0..shots {
+                let t = k as f32 * shot_length;
+                let (speaker, listener) = if k % 2 == 0 { (0, 1) } else { (1, 0) };
+                let s = scene.actor_position_at(actors[speaker], cut.start_time + t);
+                let l = scene.actor_position_at(actors[listener], cut.start_time + t);
+                let back = (l - s).normalize_or(Vec3::Z);
+                let shoulder = Vec3::Y.cross(back).normalize_or(Vec3::X);
+                let position = l + back * 1.5 + shoulder * 0.5 + Vec3::Y * 0.3;
+                if let Some((pos, target)) = previous {
+                    track.add_keyframe((t - HARD_CUT).max(0.0), pos, target, fov);
+                }
+                track.add_keyframe(t, position, s, fov);
+                previous = Some((position, s));
+            }
+            if let Some((pos, target)) = previous {
+                track.add_keyframe(duration, pos, target, fov);
+            }
+        }
+        _ => {
+            let frame = |sample: usize| {
+                let points: Vec<Vec3> = paths.iter().map(|path| path[sample]).collect();
+                let center = points.iter().sum::<Vec3>() / points.len() as f32;
+                let radius = points
+                    .iter()
+                    .map(|p| p.distance(center))
+                    .fold(0.0, f32::max);
+                (center, (radius + 1.0) / (fov * 0.5).tan())
+            };
+            let (start_center, start_dist) = frame(0);
+            let (end_center, end_dist) = frame(PLAN_SAMPLES);
+            let wide = start_center + Vec3::new(0.0, start_dist * 0.3, start_dist * 1.6);
+            let close = end_center + Vec3::new(0.0, end_dist * 0.1, end_dist);
+            track.add_keyframe(0.0, wide, start_center, fov);
+            track.add_keyframe(duration, close, end_center, fov);
+        }
+    }
+    track
+}
+
+/// Timing and spacing profile for `transfer_motion_style`.
+#[derive(Debug, Clone)]
+pub struct MotionStyle {
+    pub name: String,
+    /// Share of each move spent travelling (0..1]; the rest is held on the pose.
+    pub move_fraction: f32,
+    /// Spacing of the in-betweens inside the travel.
+    pub easing: EasingHint,
+    /// Overshoot past the destination as a fraction of the move (0 = none).
+    pub overshoot: f32,
+    /// Spacing keys baked into each move.
+    pub spacing_keys: u32,
+}
+
+impl MotionStyle {
+    /// Snappy shounen action: fast-out moves, overshoot, then holds.
+    pub fn shounen_action() -> Self {
+        Self {
+            name: "shounen_action".into(),
+            move_fraction: 0.6,
+            easing: EasingHint::AnimeSnap,
+            overshoot: 0.12,
+            spacing_keys: 3,
+        }
+    }
+
+    /// Soft slice-of-life: full-length moves eased in and out, no overshoot.
+    pub fn slice_of_life() -> Self {
+        Self {
+            name: "slice_of_life".into(),
+            move_fraction: 1.0,
+            easing: EasingHint::Smooth,
+            overshoot: 0.0,
+            spacing_keys: 4,
+        }
+    }
+}
+
+/// Heuristic style transfer: every move between two differing keys is compressed into
+/// `move_fraction` of its time with eased spacing keys, optionally overshoots and
+/// settles, then holds until the original key. Key poses and their times are kept.
+pub fn transfer_motion_style(timeline: &Timeline, style: &MotionStyle) -> Timeline {
+    let mut out = Timeline::new(&timeline.name);
+    for track in &timeline.tracks {
+        out.add_track(stylize_track(track, style));
+    }
+    out
+}
+
+fn stylize_track(track: &Track, style: &MotionStyle) -> Track {
+    let mut out = Track::new(&track.name);
+    let Some(&first) = track.keyframes.first() else {
+        return out;
+    };
+    out.add_keyframe(first);
+    let move_fraction = style.move_fraction.clamp(0.05, 1.0);
+    for pair in track.keyframes.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let dt = b.time - a.time;
+        if dt <= 0.0 || a.value == b.value {
+            out.add_keyframe(b);
+            continue;
+        }
+        let travel = dt * move_fraction;
+        let rcp_keys = 1.0 / (style.spacing_keys + 1) as f32;
+        for j in 1..=style.spacing_keys {
+            let u = j as f32 * rcp_keys;
+            let eased = apply_easing(u, style.easing);
+            out.add_keyframe(Keyframe::new(
+                a.time + travel * u,
+                a.value + (b.value - a.value) * eased,
+            ));
+        }
+        let arrive = a.time + travel;
+        if arrive < b.time {
+            if style.overshoot > 0.0 {
+                let peak = b.value + (b.value - a.value) * style.overshoot;
+                out.add_keyframe(Keyframe::new(arrive, peak));
+                // Settle halfway through the hold
+                out.add_keyframe(Keyframe::new(arrive + (b.time - arrive) * 0.5, b.value));
+            } else {
+                out.add_keyframe(Keyframe::new(arrive, b.value));
+            }
+        }
+        out.add_keyframe(b);
+    }
+    out
+}
+
+/// Per-frame joint keypoints of one performer, e.g. from a pose estimator run on
+/// reference video.
+#[derive(Debug, Clone, Default)]
+pub struct PoseSequence {
+    pub fps: f32,
+    /// One entry per video frame; joints may be missing on some frames.
+    pub frames: Vec<PoseRequest>,
+}
+
+/// Cleanup applied by `import_pose_sequence`.
+#[derive(Debug, Clone)]
+pub struct PoseImportOptions {
+    /// Centered moving-average radius in frames (0 keeps raw samples).
+    pub smoothing_radius: usize,
+    /// Feet slower than this (units/s) while near the floor are pinned in place.
+    pub foot_lock_speed: f32,
+    /// Height above the lowest foot sample that still counts as floor contact.
+    pub foot_lock_height: f32,
+    /// Joints treated as feet.
+    pub foot_joints: Vec<String>,
+    /// Estimator units to scene units, on top of any `source_units` conversion.
+    pub scale: f32,
+    /// Axis convention and length unit of the estimator's keypoints.
+    pub source_units: Units,
+    /// Project convention the tracks are written in.
+    pub units: Units,
+}
+
+impl Default for PoseImportOptions {
+    fn default() -> Self {
+        Self {
+            smoothing_radius: 2,
+            foot_lock_speed: 0.5,
+            foot_lock_height: 0.05,
+            foot_joints: vec!["left_ankle".into(), "right_ankle".into()],
+            scale: 1.0,
+            source_units: Units::default(),
+            units: Units::default(),
+        }
+    }
+}
+
+/// Solve a pose sequence onto keyframed tracks (rotoscoping).
+///
+/// Joints are gap-filled, smoothed and foot-locked, then `backend` solves the root per
+/// frame. The timeline holds the root in `translate.x/y/z` and its yaw (radians) about the
+/// up axis in `rotate.y` (`rotate.z` for Z-up projects), plus root-relative
+/// `joint.<name>.x/y/z` tracks for rigs, all in `options.units`.
+pub fn import_pose_sequence(
+    sequence: &PoseSequence,
+    options: &PoseImportOptions,
+    backend: &dyn InferenceBackend,
+) -> io::Result<Timeline> {
+    if sequence.fps <= 0.0 || sequence.frames.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Pose sequence needs a positive fps and at least one frame",
+        ));
+    }
+    let frame_count = sequence.frames.len();
+    let mut names: Vec<&str> = Vec::new();
+    for frame in &sequence.frames {
+        for (name, _) in &frame.joints {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+    }
+
+    // Solve Y-up in the target's length unit, then turn the result into the project's axes
+    let solve_units = Units::new(UpAxis::Y, options.units.meters_per_unit);
+    let mut joints: Vec<(String, Vec<Vec3>)> = Vec::with_capacity(names.len());
+    for name in names {
+        let raw: Vec<Option<Vec3>> = sequence
+            .frames
+            .iter()
+            .map(|f| {
+                f.joint(name)
+                    .map(|p| solve_units.convert_point(p, &options.source_units) * options.scale)
+            })
+            .collect();
+        let mut path = fill_gaps(&raw);
+        path = smooth_path(&path, options.smoothing_radius);
+        if options.foot_joints.iter().any(|f| f == name) {
+            lock_foot(&mut path, sequence.fps, options);
+        }
+        joints.push((name.to_string(), path));
+    }
+
+    let mut root_tracks = [
+        Track::new("translate.x"),
+        Track::new("translate.y"),
+        Track::new("translate.z"),
+        Track::new("rotate.y"),
+    ];
+    let mut joint_tracks: Vec<[Track; 3]> = joints
+        .iter()
+        .map(|(name, _)| {
+            ["x", "y", "z"].map(|axis| Track::new(&format!("joint.{}.{}", name, axis)))
+        })
+        .collect();
+    let mut previous_yaw: Option<f32> = None;
+    for i in 0..frame_count {
+        let time = i as f32 / sequence.fps;
+        let request = PoseRequest {
+            joints: joints
+                .iter()
+                .map(|(n, path)| (n.clone(), path[i]))
+                .collect(),
+        };
+        let root = backend.estimate_pose(&request)?.transform;
+        let forward = root.rotation * Vec3::Z;
+        let mut yaw = forward.x.atan2(forward.z);
+        if let Some(prev) = previous_yaw {
+            // Unwrap so the track never spins the long way round
+            yaw = prev + (yaw - prev + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU)
+                - std::f32::consts::PI;
+        }
+        previous_yaw = Some(yaw);
+
+        let p = root.position;
+        for (track, value) in root_tracks.iter_mut().zip([p.x, p.y, p.z, yaw]) {
+            track.add_keyframe(Keyframe::new(time, value));
+        }
+        for ((_, path), tracks) in joints.iter().zip(joint_tracks.iter_mut()) {
+            let local = path[i] - p;
+            for (track, value) in tracks.iter_mut().zip(local.to_array()) {
+                track.add_keyframe(Keyframe::new(time, value));
+            }
+        }
+    }
+
+    let mut timeline = Timeline::new("pose_import");
+    for track in root_tracks
+        .into_iter()
+        .chain(joint_tracks.into_iter().flatten())
+    {
+        timeline.add_track(track);
+    }
+    if options.units.up_axis != UpAxis::Y {
+        options
+            .units
+            .convert_tracks(&mut timeline, "translate", &solve_units);
+        for (name, _) in &joints {
+            let prefix = format!("joint.{}", name);
+            options
+                .units
+                .convert_tracks(&mut timeline, &prefix, &solve_units);
+        }
+        if let Some(yaw) = timeline.tracks.iter_mut().find(|t| t.name == "rotate.y") {
+            // A quarter turn about X maps yaw about Y onto yaw about Z
+            yaw.name = format!("rotate.{}", options.units.up_axis.name().to_lowercase());
+        }
+    }
+    Ok(timeline)
+}
+
+/// Hold the last seen position over gaps (the first seen one before it appears).
+fn fill_gaps(raw: &[Option<Vec3>]) -> Vec<Vec3> {
+    let first = raw.iter().flatten().next().copied().unwrap_or(Vec3::ZERO);
+    let mut last = first;
+    raw.iter()
+        .map(|p| {
+            if let Some(p) = p {
+                last = *p;
+            }
+            last
+        })
+        .collect()
+}
+
+fn smooth_path(path: &[Vec3], radius: usize) -> Vec<Vec3> {
+    if radius == 0 {
+        return path.to_vec();
+    }
+    (0..path.len())
+        .map(|i| {
+            let window = &path[i.saturating_sub(radius)..(i + radius + 1).min(path.len())];
+            window.iter().sum::<Vec3>() / window.len() as f32
+        })
+        .collect()
+}
+
+/// Pin a foot to where each floor contact began, removing estimator jitter and sliding.
+fn lock_foot(path: &mut [Vec3], fps: f32, options: &PoseImportOptions) {
+    let floor = path.iter().map(|p| p.y).fold(f32::INFINITY, f32::min);
+    let contact: Vec<bool> = (0..path.len())
+        .map(|i| {
+            let prev = path[i.saturating_sub(1)];
+            let next = path[(i + 1).min(path.len() - 1)];
+            let speed = prev.distance(next) * fps * 0.5;
+            speed < options.foot_lock_speed && path[i].y - floor < options.foot_lock_height
+        })
+        .collect();
+    let mut anchor: Option<Vec3> = None;
+    for (p, &planted) in path.iter_mut().zip(&contact) {
+        if planted {
+            *p = *anchor.get_or_insert(*p);
+        } else {
+            anchor = None;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -203,6 +854,241 @@ mod tests {
         assert!(suggestion.target.x.abs() < 1.0);
     }
 
+    #[test]
+    fn test_estimate_pose() {
+        let request = PoseRequest {
+            joints: vec![
+                ("left_hip".into(), Vec3::new(-1.0, 1.0, 0.0)),
+                ("right_hip".into(), Vec3::new(1.0, 1.0, 0.0)),
+                ("head".into(), Vec3::new(0.0, 3.0, 0.0)),
+            ],
+        };
+        let pose = estimate_pose(&request);
+        assert!((pose.transform.position - Vec3::new(0.0, 1.0, 0.0)).length() < 1e-5);
+        // Left-to-right along +X: facing -Z
+        let forward = pose.transform.rotation * Vec3::Z;
+        assert!((forward - Vec3::NEG_Z).length() < 1e-4);
+        assert_eq!(estimate_pose(&PoseRequest::default()).confidence, 0.0);
+    }
+
+    #[test]
+    fn test_backend_is_swappable() {
+        struct FixedCamera;
+        impl InferenceBackend for FixedCamera {
+            fn name(&self) -> &str {
+                "fixed"
+            }
+            fn inbetween(&self, request: &InbetweenRequest) -> io::Result<InbetweenResult> {
+                HeuristicBackend.inbetween(request)
+            }
+            fn estimate_pose(&self, _: &PoseRequest) -> io::Result<PoseResult> {
+                Err(io::Error::new(io::ErrorKind::Unsupported, "no pose model"))
+            }
+            fn suggest_camera(&self, _: &SceneGraph) -> io::Result<CameraSuggestion> {
+                Ok(CameraSuggestion {
+                    position: Vec3::new(0.0, 0.0, 3.0),
+                    target: Vec3::ZERO,
+                    fov: 0.5,
+                    confidence: 1.0,
+                    rationale: "fixed",
+                })
+            }
+        }
+
+        let sg = SceneGraph::new();
+        let backends: [&dyn InferenceBackend; 2] = [&HeuristicBackend, &FixedCamera];
+        let names: Vec<&str> = backends.iter().map(|b| b.name()).collect();
+        assert_eq!(names, ["heuristic", "fixed"]);
+        assert_eq!(
+            backends[0].suggest_camera(&sg).unwrap().rationale,
+            suggest_camera(&sg).rationale
+        );
+        assert_eq!(backends[1].suggest_camera(&sg).unwrap().fov, 0.5);
+        assert!(backends[1].estimate_pose(&PoseRequest::default()).is_err());
+    }
+
+    fn actor_at(sg: &mut SceneGraph, name: &str, x: f32) -> ActorId {
+        sg.add_actor(
+            Actor::new(name, SdfNode::sphere(1.0)).with_transform(ActorTransform {
+                position: Vec3::new(x, 0.0, 0.0),
+                ..Default::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn test_plan_establishing_push_in() {
+        let mut sg = SceneGraph::new();
+        actor_at(&mut sg, "solo", 2.0);
+        let cut = Cut::new("c1", 3.0, 7.0);
+        let track = plan_camera_for_cut(Performance::from(&sg), &cut, ShotStyle::Auto);
+        let (start, end) = (track.evaluate(0.0), track.evaluate(4.0));
+        assert!((start.target.x - 2.0).abs() < 1e-4);
+        assert!(end.position.distance(end.target) < start.position.distance(start.target));
+    }
+
+    #[test]
+    fn test_plan_shot_reverse_shot() {
+        let mut sg = SceneGraph::new();
+        let a = actor_at(&mut sg, "a", -2.0);
+        let b = actor_at(&mut sg, "b", 2.0);
+        let cut = Cut::new("talk", 0.0, 4.0).with_actors(vec![a, b]);
+        let track = plan_camera_for_cut(Performance::from(&sg), &cut, ShotStyle::Auto);
+        // First angle looks at a over b's shoulder, then a hard switch to b
+        assert!((track.evaluate(1.0).target.x + 2.0).abs() < 1e-4);
+        assert!((track.evaluate(3.0).target.x - 2.0).abs() < 1e-4);
+        assert!(track.evaluate(1.0).position.x > 2.0);
+    }
+
+    #[test]
+    fn test_plan_action_follow() {
+        let mut sg = SceneGraph::new();
+        actor_at(&mut sg, "bystander", 0.0);
+        let mut run = Track::new("translate.x");
+        run.add_keyframe(Keyframe::new(0.0, 0.0));
+        run.add_keyframe(Keyframe::new(2.0, 10.0));
+        let mut tl = Timeline::new("run");
+        tl.add_track(run);
+        sg.add_actor(Actor::new("runner", SdfNode::sphere(1.0)).with_timeline(tl));
+
+        let track = plan_camera_for_cut(
+            Performance::from(&sg),
+            &Cut::new("chase", 0.0, 2.0),
+            ShotStyle::Auto,
+        );
+        let end = track.evaluate(2.0);
+        assert!((end.target.x - 10.0).abs() < 1e-3);
+        // Side-on: camera offset is perpendicular to the run
+        assert!((end.position.x - end.target.x).abs() < 1e-3);
+        assert!(HeuristicBackend
+            .plan_camera(
+                Performance::from(&sg),
+                &Cut::new("c", 0.0, 2.0),
+                ShotStyle::ActionFollow
+            )
+            .is_ok());
+    }
+
+    fn move_timeline() -> Timeline {
+        let mut track = Track::new("translate.x");
+        track.add_keyframe(Keyframe::new(0.0, 0.0));
+        track.add_keyframe(Keyframe::new(1.0, 10.0));
+        track.add_keyframe(Keyframe::new(2.0, 10.0));
+        let mut tl = Timeline::new("move");
+        tl.add_track(track);
+        tl
+    }
+
+    #[test]
+    fn test_style_transfer_shounen() {
+        let tl = transfer_motion_style(&move_timeline(), &MotionStyle::shounen_action());
+        let x = |t| tl.get_value("translate.x", t).unwrap();
+        // Fast-out spacing, overshoot on arrival, then settled on the key pose
+        assert!(x(0.3) > 5.0);
+        assert!(x(0.6) > 10.5);
+        assert_eq!(x(1.0), 10.0);
+        assert_eq!(x(1.5), 10.0);
+    }
+
+    #[test]
+    fn test_style_transfer_slice_of_life() {
+        let style = MotionStyle::slice_of_life();
+        let tl = HeuristicBackend
+            .transfer_style(&move_timeline(), &style)
+            .unwrap();
+        let x = |t| tl.get_value("translate.x", t).unwrap();
+        assert!(x(0.1) < 1.0);
+        assert!((x(0.5) - 5.0).abs() < 0.5);
+        assert!(x(0.9) > 9.0);
+        // Hold segment untouched: 2 keys + 4 spacing keys + 1 hold key
+        assert_eq!(tl.tracks[0].keyframes.len(), 7);
+    }
+
+    #[test]
+    fn test_import_pose_sequence() {
+        // Walk along +X at 1 unit/s; the left foot is planted (with jitter) for 12 frames
+        let fps = 24.0;
+        let frames = (0..24)
+            .map(|i| {
+                let x = i as f32 / fps;
+                let jitter = if i % 2 == 0 { 0.004 } else { -0.004 };
+                let foot_x = if i < 12 {
+                    0.2 + jitter
+                } else {
+                    0.2 + (i - 11) as f32 / 8.0
+                };
+                let foot_y = if i < 12 { 0.0 } else { 0.2 };
+                let mut joints = vec![
+                    ("left_hip".to_string(), Vec3::new(x - 0.1, 1.0, 0.0)),
+                    ("right_hip".to_string(), Vec3::new(x + 0.1, 1.0, 0.0)),
+                    ("left_ankle".to_string(), Vec3::new(foot_x, foot_y, 0.0)),
+                ];
+                // The estimator loses the head on one frame
+                if i != 5 {
+                    joints.push(("head".to_string(), Vec3::new(x, 1.8, 0.0)));
+                }
+                PoseRequest { joints }
+            })
+            .collect();
+        let sequence = PoseSequence { fps, frames };
+        let tl = import_pose_sequence(&sequence, &PoseImportOptions::default(), &HeuristicBackend)
+            .unwrap();
+
+        let value = |name: &str, t: f32| tl.get_value(name, t).unwrap();
+        assert!((value("translate.x", 0.5) - 0.5).abs() < 1e-3);
+        assert!((value("translate.y", 0.5) - 1.0).abs() < 1e-3);
+        // Planted foot stays put in world space
+        let foot_world = |t: f32| value("joint.left_ankle.x", t) + value("translate.x", t);
+        assert_eq!(foot_world(3.0 / fps), foot_world(8.0 / fps));
+        assert!(tl.get_value("joint.head.y", 5.0 / fps).is_some());
+
+        // Same capture imported into a Z-up centimeter project
+        let options = PoseImportOptions {
+            source_units: Units::meters(UpAxis::Y),
+            units: Units::centimeters(UpAxis::Z),
+            ..PoseImportOptions::default()
+        };
+        let z_up = import_pose_sequence(&sequence, &options, &HeuristicBackend).unwrap();
+        assert!((z_up.get_value("translate.z", 0.5).unwrap() - 100.0).abs() < 0.1);
+        assert!((z_up.get_value("translate.x", 0.5).unwrap() - 50.0).abs() < 0.1);
+        assert!(z_up.get_value("rotate.z", 0.5).is_some());
+
+        let empty = PoseSequence::default();
+        assert!(
+            import_pose_sequence(&empty, &PoseImportOptions::default(), &HeuristicBackend).is_err()
+        );
+    }
+
+    #[test]
+    fn test_inbetween_timeline() {
+        let mut tl = Timeline::new("jump");
+        for (name, from, to) in [("translate.y", 0.0, 2.0), ("sdf.radius", 1.0, 0.5)] {
+            let mut track = Track::new(name);
+            track.add_keyframe(Keyframe::new(0.0, from));
+            track.add_keyframe(Keyframe::new(1.0, to));
+            tl.add_track(track);
+        }
+        // Already broken down between the keys: left alone
+        let mut busy = Track::new("mouth.openness");
+        for t in [0.0, 0.5, 1.0] {
+            busy.add_keyframe(Keyframe::new(t, t));
+        }
+        tl.add_track(busy);
+
+        let request = TimelineInbetweenRequest::new(0.0, 1.0, 3, EasingHint::Linear)
+            .with_track_easing("sdf.*", EasingHint::AnimeSnap);
+        let filled = inbetween_timeline(&mut tl, &request).unwrap();
+        assert_eq!(filled, vec!["translate.y", "sdf.radius"]);
+        assert_eq!(tl.tracks[0].keyframes.len(), 5);
+        assert_eq!(tl.tracks[2].keyframes.len(), 3);
+        assert!((tl.get_value("translate.y", 0.5).unwrap() - 1.0).abs() < 1e-5);
+        // Snappy easing is ahead of linear halfway through
+        assert!(tl.get_value("sdf.radius", 0.5).unwrap() < 0.75);
+
+        let missing = TimelineInbetweenRequest::new(0.2, 0.7, 2, EasingHint::Linear);
+        assert!(inbetween_timeline(&mut tl, &missing).is_err());
+    }
+
     #[test]
     fn test_suggest_camera_empty_scene() {
         let sg = SceneGraph::new();
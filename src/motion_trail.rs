@@ -0,0 +1,295 @@
+//! Motion trails for animation review: world-space arcs of an actor or the camera
+//! sampled per frame, with spacing data and keyframe markers for editor frontends.
+
+use glam::{Mat4, Vec2, Vec3};
+
+use crate::camera::CameraState;
+use crate::director::Director;
+use crate::export::FrameRange;
+use crate::performance::Performance;
+use crate::scene::{ActorId, SceneGraph};
+
+/// What the trail follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailSubject {
+    Actor(ActorId),
+    Camera,
+    /// The camera's look-at point.
+    CameraTarget,
+}
+
+/// One per-frame sample of the trail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailSample {
+    pub frame: u32,
+    pub time: f32,
+    pub position: Vec3,
+    /// Distance travelled since the previous sample (0 for the first).
+    pub spacing: f32,
+    /// True when a position keyframe (or a cut start, for the camera) falls on this frame.
+    pub keyframe: bool,
+}
+
+/// Trail between two consecutive keyframe markers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrailArc {
+    /// Sample indices of the bounding keyframes.
+    pub start: usize,
+    pub end: usize,
+    /// Straight-line distance between the ends.
+    pub chord: f32,
+    /// Distance along the trail.
+    pub length: f32,
+    /// Largest distance of the trail from the chord (0 = dead straight, linear-looking motion).
+    pub max_deviation: f32,
+}
+
+/// A sampled motion trail.
+#[derive(Debug, Clone)]
+pub struct MotionTrail {
+    pub subject: TrailSubject,
+    pub fps: f32,
+    pub samples: Vec<TrailSample>,
+}
+
+impl MotionTrail {
+    /// Positions as a polyline.
+    pub fn polyline(&self) -> Vec<Vec3> {
+        self.samples.iter().map(|s| s.position).collect()
+    }
+
+    /// Total distance travelled.
+    pub fn length(&self) -> f32 {
+        self.samples.iter().map(|s| s.spacing).sum()
+    }
+
+    /// `(frame, spacing)` pairs for a spacing chart (ease-in/out shows as shrinking gaps).
+    pub fn spacing_chart(&self) -> Vec<(u32, f32)> {
+        self.samples
+            .iter()
+            .skip(1)
+            .map(|s| (s.frame, s.spacing))
+            .collect()
+    }
+
+    /// Speed in units per second at each sample.
+    pub fn speeds(&self) -> Vec<f32> {
+        self.samples.iter().map(|s| s.spacing * self.fps).collect()
+    }
+
+    /// Split the trail at keyframe markers (and its ends) into arcs.
+    pub fn arcs(&self) -> Vec<TrailArc> {
+        let last = match self.samples.len().checked_sub(1) {
+            Some(last) if last > 0 => last,
+            _ => return Vec::new(),
+        };
+        let mut bounds: Vec<usize> = (1..last).filter(|&i| self.samples[i].keyframe).collect();
+        bounds.insert(0, 0);
+        bounds.push(last);
+        bounds
+            .windows(2)
+            .map(|w| {
+                let (a, b) = (self.samples[w[0]].position, self.samples[w[1]].position);
+                let chord = b - a;
+                let deviation = |p: Vec3| {
+                    let t = if chord.length_squared() > 0.0 {
+                        ((p - a).dot(chord) / chord.length_squared()).clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    p.distance(a + chord * t)
+                };
+                let span = &self.samples[w[0]..=w[1]];
+                TrailArc {
+                    start: w[0],
+                    end: w[1],
+                    chord: chord.length(),
+                    length: span.iter().skip(1).map(|s| s.spacing).sum(),
+                    max_deviation: span
+                        .iter()
+                        .map(|s| deviation(s.position))
+                        .fold(0.0, f32::max),
+                }
+            })
+            .collect()
+    }
+
+    /// Project into normalized device coordinates of `camera` (x right, y up, both -1..1).
+    /// Samples behind the camera are `None`.
+    pub fn to_screen(&self, camera: &CameraState, aspect: f32) -> Vec<Option<Vec2>> {
+        let view = camera.inverse_view_matrix().inverse();
+        let projection = Mat4::perspective_rh(camera.fov, aspect, 0.01, 1000.0);
+        let view_projection = projection * view;
+        self.samples
+            .iter()
+            .map(|s| {
+                let clip = view_projection * s.position.extend(1.0);
+                (clip.w > 1e-6).then(|| Vec2::new(clip.x, clip.y) / clip.w)
+            })
+            .collect()
+    }
+}
+
+/// Keyframe times that shape the subject's path.
+fn key_times(director: &Director, scene: &SceneGraph, subject: TrailSubject) -> Vec<f32> {
+    match subject {
+        TrailSubject::Actor(id) => scene
+            .get_actor(id)
+            .and_then(|a| a.timeline.as_ref())
+            .map(|tl| {
+                tl.tracks
+                    .iter()
+                    .filter(|t| t.name.starts_with("translate."))
+                    .flat_map(|t| t.keyframes.iter().map(|k| k.time))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        TrailSubject::Camera | TrailSubject::CameraTarget => director
+            .cuts()
+            .flat_map(|(_, cut)| {
+                let timeline = match subject {
+                    TrailSubject::Camera => &cut.camera.position_timeline,
+                    _ => &cut.camera.target_timeline,
+                };
+                let keys: Vec<f32> = timeline
+                    .tracks
+                    .iter()
+                    .flat_map(|t| t.keyframes.iter().map(|k| cut.start_time + k.time))
+                    .collect();
+                std::iter::once(cut.start_time).chain(keys)
+            })
+            .collect(),
+    }
+}
+
+/// Sample `subject`'s world position at every frame of `range`.
+pub fn sample_trail(
+    director: &Director,
+    scene: &SceneGraph,
+    subject: TrailSubject,
+    range: FrameRange,
+) -> MotionTrail {
+    let keys = key_times(director, scene, subject);
+    let half_frame = 0.5 / range.fps.max(1e-3);
+    let mut samples: Vec<TrailSample> = Vec::with_capacity(range.frame_count() as usize);
+    let edit = Performance::new(scene, director);
+    for i in 0..range.frame_count() {
+        let time = range.frame_time(i);
+        let position = match subject {
+            TrailSubject::Actor(id) => edit.actor_position_at(id, time),
+            TrailSubject::Camera => director.evaluate(scene, time).camera_state.position,
+            TrailSubject::CameraTarget => director.evaluate(scene, time).camera_state.target,
+        };
+        let spacing = samples
+            .last()
+            .map(|prev| prev.position.distance(position))
+            .unwrap_or(0.0);
+        samples.push(TrailSample {
+            frame: range.frame_number(i),
+            time,
+            position,
+            spacing,
+            keyframe: keys.iter().any(|k| (k - time).abs() < half_frame),
+        });
+    }
+    MotionTrail {
+        subject,
+        fps: range.fps,
+        samples,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::CameraTrack;
+    use crate::director::Cut;
+    use crate::scene::Actor;
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+    use alice_sdf::SdfNode;
+
+    /// Ball thrown in an arc: x linear, y up and back down, keyed at 0, 1 and 2 seconds.
+    fn thrown_ball() -> (Director, SceneGraph, ActorId) {
+        let mut sg = SceneGraph::new();
+        let mut tl = Timeline::new("throw");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(2.0, 4.0));
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.0));
+        y.add_keyframe(Keyframe::new(1.0, 2.0));
+        y.add_keyframe(Keyframe::new(2.0, 0.0));
+        tl.add_track(x);
+        tl.add_track(y);
+        let ball = sg.add_actor(Actor::new("ball", SdfNode::sphere(0.2)).with_timeline(tl));
+        (Director::new("Throw"), sg, ball)
+    }
+
+    #[test]
+    fn test_actor_trail_spacing_and_markers() {
+        let (director, sg, ball) = thrown_ball();
+        let trail = sample_trail(
+            &director,
+            &sg,
+            TrailSubject::Actor(ball),
+            FrameRange::new(0.0, 2.05, 10.0),
+        );
+        assert_eq!(trail.samples.len(), 21);
+        assert_eq!(trail.samples[10].position, Vec3::new(2.0, 2.0, 0.0));
+        let markers: Vec<u32> = trail
+            .samples
+            .iter()
+            .filter(|s| s.keyframe)
+            .map(|s| s.frame)
+            .collect();
+        assert_eq!(markers, vec![0, 10, 20]);
+        // Constant speed on each leg: every gap is 0.2 * sqrt(2)
+        for (_, spacing) in trail.spacing_chart() {
+            assert!((spacing - 0.2 * 2f32.sqrt()).abs() < 1e-4);
+        }
+        assert!((trail.length() - 4.0 * 2f32.sqrt()).abs() < 1e-3);
+        assert!((trail.speeds()[5] - 2.0 * 2f32.sqrt()).abs() < 1e-3);
+
+        // Linear interpolation gives straight legs between keys: no arc
+        let arcs = trail.arcs();
+        assert_eq!(arcs.len(), 2);
+        assert_eq!((arcs[0].start, arcs[0].end), (0, 10));
+        assert!(arcs[0].max_deviation < 1e-4);
+        assert!((arcs[0].chord - arcs[0].length).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_camera_trail_and_projection() {
+        let (mut director, sg, ball) = thrown_ball();
+        let mut track = CameraTrack::default();
+        track.add_keyframe(0.0, Vec3::new(0.0, 1.0, 10.0), Vec3::ZERO, 0.8);
+        track.add_keyframe(1.0, Vec3::new(4.0, 1.0, 10.0), Vec3::ZERO, 0.8);
+        director.add_cut(Cut::new("pan", 0.0, 2.0).with_camera(track));
+        let range = FrameRange::new(0.0, 1.0, 4.0);
+        let camera = sample_trail(&director, &sg, TrailSubject::Camera, range);
+        assert_eq!(camera.samples[2].position, Vec3::new(2.0, 1.0, 10.0));
+        assert!(camera.samples[0].keyframe);
+        let target = sample_trail(&director, &sg, TrailSubject::CameraTarget, range);
+        assert_eq!(target.length(), 0.0);
+
+        let ball_trail = sample_trail(&director, &sg, TrailSubject::Actor(ball), range);
+        let view = CameraState {
+            position: Vec3::new(0.0, 0.0, 10.0),
+            target: Vec3::ZERO,
+            fov: 0.8,
+            up: None,
+        };
+        let screen = ball_trail.to_screen(&view, 1.0);
+        assert!(screen[0].unwrap().length() < 1e-5);
+        // Rising to the upper right
+        let p = screen[3].unwrap();
+        assert!(p.x > 0.0 && p.y > 0.0);
+        let behind = CameraState {
+            position: Vec3::new(0.0, 0.0, -10.0),
+            target: Vec3::new(0.0, 0.0, -20.0),
+            fov: 0.8,
+            up: None,
+        };
+        assert!(ball_trail.to_screen(&behind, 1.0)[0].is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use alloc::vec::Vec;
+
+use alice_sdf::animation::Timeline;
+use alice_sdf::SdfNode;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+/// How mouth parameters deform the mouth sub-node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MouthDeform {
+    /// Non-uniform scale: width → X, openness → Y.
+    Scale,
+    /// 2x2x2 lattice over the mouth bounds (smoother silhouette on stylized faces).
+    Lattice,
+}
+
+/// Binds "mouth.openness" / "mouth.width" timeline tracks to a mouth SDF on an actor.
+///
+/// The mouth is a separate sub-node placed at `anchor` (actor space), deformed each frame
+/// and then carved out of (or added to) the actor's body SDF.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MouthBinding {
+    /// Mouth shape at rest (unit size, centered at origin).
+    pub sdf: SdfNode,
+    /// Position of the mouth in actor space.
+    pub anchor: Vec3,
+    pub deform: MouthDeform,
+    /// True: subtract the mouth from the face (cavity). False: union (drawn-on mouth).
+    pub carve: bool,
+    /// Y scale at openness 0.0 and 1.0.
+    pub openness_range: (f32, f32),
+    /// X scale at width 0.0 and 1.0.
+    pub width_range: (f32, f32),
+    /// Half extents of the lattice box (`MouthDeform::Lattice` only).
+    pub extent: Vec3,
+}
+
+impl MouthBinding {
+    pub fn new(sdf: SdfNode, anchor: Vec3) -> Self {
+        Self {
+            sdf,
+            anchor,
+            deform: MouthDeform::Scale,
+            carve: true,
+            openness_range: (0.05, 1.0),
+            width_range: (0.5, 1.2),
+            extent: Vec3::ONE,
+        }
+    }
+
+    /// Set deformation mode.
+    pub fn with_deform(mut self, deform: MouthDeform) -> Self {
+        self.deform = deform;
+        self
+    }
+
+    /// Union the mouth instead of carving it.
+    pub fn drawn(mut self) -> Self {
+        self.carve = false;
+        self
+    }
+
+    /// Set openness/width scale ranges.
+    pub fn with_ranges(mut self, openness: (f32, f32), width: (f32, f32)) -> Self {
+        self.openness_range = openness;
+        self.width_range = width;
+        self
+    }
+
+    /// X/Y scale for given mouth parameters.
+    #[inline]
+    pub fn scale_for(&self, openness: f32, width: f32) -> (f32, f32) {
+        let lerp = |(a, b): (f32, f32), t: f32| a + (b - a) * t.clamp(0.0, 1.0);
+        (
+            lerp(self.width_range, width),
+            lerp(self.openness_range, openness),
+        )
+    }
+
+    /// Deformed mouth node for given parameters, positioned at the anchor.
+    pub fn mouth_node(&self, openness: f32, width: f32) -> SdfNode {
+        let (sx, sy) = self.scale_for(openness, width);
+        let node = match self.deform {
+            MouthDeform::Scale => self.sdf.clone().scale_xyz(sx, sy, 1.0),
+            MouthDeform::Lattice => {
+                let e = self.extent;
+                let mut points = Vec::with_capacity(8);
+                for z in [-e.z, e.z] {
+                    for y in [-e.y, e.y] {
+                        for x in [-e.x, e.x] {
+                            points.push(Vec3::new(x * sx, y * sy, z));
+                        }
+                    }
+                }
+                self.sdf.clone().lattice_deform(points, 2, 2, 2, -e, e)
+            }
+        };
+        node.translate(self.anchor.x, self.anchor.y, self.anchor.z)
+    }
+
+    /// Apply the mouth to a body SDF using the timeline's mouth tracks at `time`.
+    pub fn apply(&self, body: SdfNode, timeline: Option<&Timeline>, time: f32) -> SdfNode {
+        let value = |name: &str, default: f32| {
+            timeline
+                .and_then(|tl| tl.get_value(name, time))
+                .unwrap_or(default)
+        };
+        let mouth = self.mouth_node(value("mouth.openness", 0.0), value("mouth.width", 0.3));
+        if self.carve {
+            body.subtract(mouth)
+        } else {
+            body.union(mouth)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::Actor;
+    use alice_sdf::animation::{Keyframe, Track};
+
+    fn talking_timeline() -> Timeline {
+        let mut tl = Timeline::new("talk");
+        let mut open = Track::new("mouth.openness");
+        open.add_keyframe(Keyframe::new(0.0, 0.0));
+        open.add_keyframe(Keyframe::new(1.0, 1.0));
+        tl.add_track(open);
+        tl
+    }
+
+    #[test]
+    fn test_mouth_scale_ranges() {
+        let binding = MouthBinding::new(SdfNode::sphere(0.1), Vec3::new(0.0, -0.2, 0.5));
+        assert_eq!(binding.scale_for(0.0, 0.0), (0.5, 0.05));
+        assert_eq!(binding.scale_for(1.0, 1.0), (1.2, 1.0));
+    }
+
+    #[test]
+    fn test_actor_mouth_opens_over_time() {
+        let face = SdfNode::sphere(1.0);
+        let mouth = MouthBinding::new(SdfNode::sphere(0.2), Vec3::new(0.0, 0.0, 1.0));
+        let actor = Actor::new("hero", face)
+            .with_timeline(talking_timeline())
+            .with_mouth(mouth);
+
+        // Probe just inside the face surface at the mouth: carved deeper when open
+        let probe = Vec3::new(0.0, 0.1, 0.95);
+        let closed = alice_sdf::eval(&actor.evaluate_sdf(0.0), probe);
+        let open = alice_sdf::eval(&actor.evaluate_sdf(1.0), probe);
+        assert!(open > closed);
+    }
+}
@@ -0,0 +1,386 @@
+//! Name-based actor references for saved episodes.
+//!
+//! `ActorId`s are slot indices, so merging scenes or removing an actor makes every stored
+//! id after it point somewhere else. [`NamedEpisode`] stores parents, cut actor lists and
+//! clip track actors by stable actor key instead and renumbers actors on load; a key that does not resolve is an
+//! error rather than a silent retarget. Constraints kept outside the episode (bake sources,
+//! cloth sockets) can store keys too and resolve them through [`ActorKeys`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+use crate::clip::ClipLibrary;
+use crate::director::{CutId, Director};
+use crate::episode::{write_frame, EpisodeMetadata, EpisodePackage, FLAG_NAMED_REFS};
+use crate::error::{AnimationError, Result};
+use crate::npr::AnimeShading;
+use crate::overlay::Overlay;
+use crate::render::RenderSettings;
+use crate::scene::{Actor, ActorId, SceneGraph};
+
+/// Stable key per actor: its name, with `#n` appended to the n-th actor sharing it (n >= 2).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ActorKeys {
+    keys: HashMap<ActorId, String>,
+    ids: BTreeMap<String, ActorId>,
+}
+
+impl ActorKeys {
+    /// Keys for every actor in `scene`, numbered in id order.
+    pub fn new(scene: &SceneGraph) -> Self {
+        let mut table = Self::default();
+        let mut seen: BTreeMap<&str, u32> = BTreeMap::new();
+        for id in scene.actor_ids() {
+            let Some(actor) = scene.get_actor(id) else {
+                continue;
+            };
+            let count = seen.entry(actor.name.as_str()).or_insert(0);
+            *count += 1;
+            let mut key = if *count == 1 {
+                actor.name.clone()
+            } else {
+                format!("{}#{}", actor.name, count)
+            };
+            // A literal "name#2" elsewhere in the scene must not collide with a generated key
+            while table.ids.contains_key(&key) {
+                *count += 1;
+                key = format!("{}#{}", actor.name, count);
+            }
+            table.ids.insert(key.clone(), id);
+            table.keys.insert(id, key);
+        }
+        table
+    }
+
+    #[inline]
+    pub fn key(&self, id: ActorId) -> Option<&str> {
+        self.keys.get(&id).map(String::as_str)
+    }
+
+    #[inline]
+    pub fn resolve(&self, key: &str) -> Option<ActorId> {
+        self.ids.get(key).copied()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// An actor with its parent given by key (`actor.parent` is not stored).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedActor {
+    pub key: String,
+    pub parent: Option<String>,
+    pub actor: Actor,
+}
+
+/// Actors on screen in one cut, and the actor each of its clip tracks drives, by key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NamedCut {
+    pub cut: CutId,
+    pub actors: Vec<String>,
+    /// In `clip_tracks` order.
+    pub clip_actors: Vec<String>,
+}
+
+/// An episode whose actor references are keys rather than ids.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NamedEpisode {
+    pub metadata: EpisodeMetadata,
+    /// In load order; ids are assigned from position.
+    pub actors: Vec<NamedActor>,
+    pub clips: ClipLibrary,
+    /// Director with every cut's actor list emptied and clip track actors unset; see
+    /// `cut_actors`.
+    pub director: Director,
+    pub cut_actors: Vec<NamedCut>,
+    pub shading: AnimeShading,
+    pub render_settings: Option<RenderSettings>,
+    pub overlays: Vec<Overlay>,
+}
+
+impl NamedEpisode {
+    /// Convert `episode`, failing with `Validation` if a parent, cut or clip track references
+    /// an actor that does not exist (there is no key to write for it).
+    pub fn from_episode(episode: &EpisodePackage) -> Result<Self> {
+        let sg = &episode.scene_graph;
+        let keys = ActorKeys::new(sg);
+        let mut issues = Vec::new();
+        let mut key_of = |id: ActorId, context: &str| match keys.key(id) {
+            Some(key) => Some(key.to_string()),
+            None => {
+                issues.push(format!("{} references missing actor {}", context, id.0));
+                None
+            }
+        };
+
+        let mut actors = Vec::with_capacity(keys.len());
+        for id in sg.actor_ids() {
+            let Some(actor) = sg.get_actor(id) else {
+                continue;
+            };
+            let parent = actor
+                .parent
+                .and_then(|p| key_of(p, &format!("actor '{}'", actor.name)));
+            let mut actor = actor.clone();
+            actor.parent = None;
+            actors.push(NamedActor {
+                key: keys.key(id).unwrap_or_default().to_string(),
+                parent,
+                actor,
+            });
+        }
+
+        let mut director = episode.director.clone();
+        let mut cut_actors = Vec::new();
+        for (id, cut) in episode.director.cuts() {
+            let names = cut
+                .active_actors
+                .iter()
+                .filter_map(|a| key_of(*a, &format!("cut '{}'", cut.name)))
+                .collect();
+            let clip_actors = cut
+                .clip_tracks
+                .iter()
+                .map(|track| {
+                    key_of(track.actor, &format!("clip track in cut '{}'", cut.name))
+                        .unwrap_or_default()
+                })
+                .collect();
+            cut_actors.push(NamedCut {
+                cut: id,
+                actors: names,
+                clip_actors,
+            });
+            if let Some(stored) = director.get_cut_mut(id) {
+                stored.active_actors.clear();
+                for track in &mut stored.clip_tracks {
+                    track.actor = ActorId(u32::MAX);
+                }
+            }
+        }
+
+        if !issues.is_empty() {
+            return Err(AnimationError::Validation(issues));
+        }
+        Ok(Self {
+            metadata: episode.metadata.clone(),
+            actors,
+            clips: sg.clips.clone(),
+            director,
+            cut_actors,
+            shading: episode.shading.clone(),
+            render_settings: episode.render_settings,
+            overlays: episode.overlays.clone(),
+        })
+    }
+
+    /// Rebuild the episode with actors numbered in list order, failing with `Validation`
+    /// on duplicate keys or keys that resolve to nothing.
+    pub fn into_episode(self) -> Result<EpisodePackage> {
+        let mut issues = Vec::new();
+        let mut ids: BTreeMap<&str, ActorId> = BTreeMap::new();
+        for (i, named) in self.actors.iter().enumerate() {
+            if ids.insert(&named.key, ActorId(i as u32)).is_some() {
+                issues.push(format!("duplicate actor key '{}'", named.key));
+            }
+        }
+        let resolve = |key: &str, context: &str, issues: &mut Vec<String>| {
+            let id = ids.get(key).copied();
+            if id.is_none() {
+                issues.push(format!("{} references unknown actor '{}'", context, key));
+            }
+            id
+        };
+
+        let mut parents = Vec::with_capacity(self.actors.len());
+        for named in &self.actors {
+            let context = format!("actor '{}'", named.key);
+            parents.push(
+                named
+                    .parent
+                    .as_deref()
+                    .and_then(|p| resolve(p, &context, &mut issues)),
+            );
+        }
+        let mut director = self.director;
+        for named in &self.cut_actors {
+            let context = format!("cut {}", named.cut.0);
+            let actors: Vec<ActorId> = named
+                .actors
+                .iter()
+                .filter_map(|key| resolve(key, &context, &mut issues))
+                .collect();
+            let clip_actors: Vec<Option<ActorId>> = named
+                .clip_actors
+                .iter()
+                .map(|key| resolve(key, &context, &mut issues))
+                .collect();
+            let Some(cut) = director.get_cut_mut(named.cut) else {
+                issues.push(format!("actor list for missing cut {}", named.cut.0));
+                continue;
+            };
+            cut.active_actors = actors;
+            if clip_actors.len() != cut.clip_tracks.len() {
+                issues.push(format!(
+                    "{} has {} clip tracks but {} clip actor keys",
+                    context,
+                    cut.clip_tracks.len(),
+                    clip_actors.len()
+                ));
+                continue;
+            }
+            for (track, actor) in cut.clip_tracks.iter_mut().zip(clip_actors) {
+                track.actor = actor.unwrap_or(ActorId(u32::MAX));
+            }
+        }
+        if !issues.is_empty() {
+            return Err(AnimationError::Validation(issues));
+        }
+
+        let mut scene_graph = SceneGraph::new();
+        scene_graph.clips = self.clips;
+        for (named, parent) in self.actors.into_iter().zip(parents) {
+            let mut actor = named.actor;
+            actor.parent = parent;
+            scene_graph.add_actor(actor);
+        }
+        let mut episode = EpisodePackage::new(self.metadata, scene_graph, director, self.shading);
+        episode.render_settings = self.render_settings;
+        episode.overlays = self.overlays;
+        Ok(episode)
+    }
+}
+
+/// Serialize `episode` as a single-body container flagged [`FLAG_NAMED_REFS`], with actor
+/// references stored by key. `deserialize_episode` reads it back, renumbering actors.
+pub fn serialize_episode_named<W: Write>(
+    episode: &EpisodePackage,
+    writer: &mut W,
+) -> Result<usize> {
+    let body = bincode::serialize(&NamedEpisode::from_episode(episode)?)?;
+    write_frame(writer, FLAG_NAMED_REFS, &body)?;
+    Ok(16 + body.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clip::{AnimationClip, ClipInstance, ClipTrack};
+    use crate::director::Cut;
+    use crate::episode::deserialize_episode;
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+    use alice_sdf::SdfNode;
+
+    fn episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let extra = sg.add_actor(Actor::new("extra", SdfNode::sphere(0.5)));
+        let hat = sg.add_actor(Actor::new("hat", SdfNode::sphere(0.2)).with_parent(hero));
+        sg.add_actor(Actor::new("extra", SdfNode::sphere(0.5)));
+        let mut dir = Director::new("Merge");
+        dir.add_cut(Cut::new("a", 0.0, 2.0).with_actors(vec![hero, hat]));
+        dir.add_cut(Cut::new("b", 2.0, 4.0).with_actors(vec![extra]));
+        EpisodePackage::new(
+            EpisodeMetadata::new("Merge", 1, 4.0),
+            sg,
+            dir,
+            AnimeShading::default(),
+        )
+    }
+
+    #[test]
+    fn test_keys_disambiguate_duplicates() {
+        let ep = episode();
+        let keys = ActorKeys::new(&ep.scene_graph);
+        assert_eq!(keys.len(), 4);
+        assert_eq!(keys.key(ActorId(1)), Some("extra"));
+        assert_eq!(keys.key(ActorId(3)), Some("extra#2"));
+        assert_eq!(keys.resolve("hat"), Some(ActorId(2)));
+        assert_eq!(keys.resolve("extra#3"), None);
+    }
+
+    #[test]
+    fn test_reordered_actors_keep_references() {
+        let mut named = NamedEpisode::from_episode(&episode()).unwrap();
+        // Hand edit: move the hat first and drop the second extra
+        named.actors.retain(|a| a.key != "extra#2");
+        named.actors.rotate_right(1);
+        let ep = named.into_episode().unwrap();
+        assert!(ep.validation_issues().is_empty());
+
+        let hat = ep.scene_graph.find_by_name("hat").unwrap();
+        let hero = ep.scene_graph.find_by_name("hero").unwrap();
+        assert_eq!(hat, ActorId(0));
+        assert_eq!(ep.scene_graph.get_actor(hat).unwrap().parent, Some(hero));
+        let (_, first) = ep.director.cuts().next().unwrap();
+        assert_eq!(first.active_actors, vec![hero, hat]);
+
+        let mut buf = Vec::new();
+        serialize_episode_named(&ep, &mut buf).unwrap();
+        let back = deserialize_episode(&mut buf.as_slice()).unwrap();
+        assert_eq!(back.scene_graph.actor_count(), 3);
+        let (_, first) = back.director.cuts().next().unwrap();
+        assert_eq!(first.active_actors, vec![hero, hat]);
+    }
+
+    #[test]
+    fn test_clip_tracks_follow_reordered_actors() {
+        let mut ep = episode();
+        let hat = ep.scene_graph.find_by_name("hat").unwrap();
+        let mut tl = Timeline::new("tip");
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.5));
+        tl.add_track(y);
+        let tip = AnimationClip::new("tip", tl);
+        let track = ClipTrack::new(hat).with_clip(ClipInstance::new(&tip, 0.0));
+        ep.scene_graph.clips.insert(tip);
+        ep.metadata.duration_seconds = 6.0;
+        ep.director
+            .add_cut(Cut::new("c", 4.0, 6.0).with_clip_track(track));
+
+        let mut named = NamedEpisode::from_episode(&ep).unwrap();
+        named.actors.retain(|a| a.key != "extra#2");
+        named.actors.rotate_right(1);
+        let mut buf = Vec::new();
+        let reordered = named.into_episode().unwrap();
+        serialize_episode_named(&reordered, &mut buf).unwrap();
+        let back = deserialize_episode(&mut buf.as_slice()).unwrap();
+
+        let hat = back.scene_graph.find_by_name("hat").unwrap();
+        assert_eq!(hat, ActorId(0));
+        assert!(back.scene_graph.clips.get("tip").is_some());
+        let (_, cut) = back.director.find_active_cut(5.0).unwrap();
+        assert_eq!(cut.clip_tracks[0].actor, hat);
+        assert!(back.validation_issues().is_empty());
+    }
+
+    #[test]
+    fn test_unresolved_keys_are_errors() {
+        let mut named = NamedEpisode::from_episode(&episode()).unwrap();
+        named.actors.retain(|a| a.key != "hero");
+        named.actors[0].key = "extra#2".into();
+        let Err(AnimationError::Validation(issues)) = named.into_episode() else {
+            panic!("expected validation error");
+        };
+        assert!(issues
+            .iter()
+            .any(|i| i.contains("duplicate actor key 'extra#2'")));
+        assert!(issues.iter().any(|i| i.contains("unknown actor 'hero'")));
+
+        let mut dangling = episode();
+        dangling
+            .director
+            .add_cut(Cut::new("c", 4.0, 5.0).with_actors(vec![ActorId(9)]));
+        assert!(NamedEpisode::from_episode(&dangling).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Non-linear animation: clip tracks stacked per actor.
+//!
+//! Every [`ClipTrack`] on an actor in a cut is one NLA track. The stack is evaluated from
+//! the bottom (first added) up over the actor's own timeline; each track replaces, adds to
+//! or multiplies the result below it, scaled by its influence, which can be keyed over the
+//! cut. A walk clip, an added shrug and a multiplied squash assemble one performance.
+
+use alloc::vec::Vec;
+
+use alice_sdf::animation::Timeline;
+use serde::{Deserialize, Serialize};
+
+use crate::clip::{ClipLibrary, ClipTrack};
+use crate::layer::{self, rest_value};
+use crate::scene::Actor;
+
+/// How a track combines with the result below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BlendMode {
+    /// Blend toward the track's values.
+    #[default]
+    Replace,
+    /// Add the track's values as offsets.
+    Add,
+    /// Scale the result by the track's values.
+    Multiply,
+}
+
+impl BlendMode {
+    /// `below` combined with `value` at `influence` (0 leaves `below` unchanged).
+    #[inline]
+    pub fn apply(self, below: f32, value: f32, influence: f32) -> f32 {
+        match self {
+            Self::Replace => below + (value - below) * influence,
+            Self::Add => below + value * influence,
+            Self::Multiply => below * (1.0 + (value - 1.0) * influence),
+        }
+    }
+}
+
+/// The clip tracks of one actor, bottom to top, with the library their clips come from.
+#[derive(Debug, Clone)]
+pub struct NlaStack<'a> {
+    pub library: &'a ClipLibrary,
+    pub tracks: Vec<&'a ClipTrack>,
+}
+
+impl<'a> NlaStack<'a> {
+    pub fn new(library: &'a ClipLibrary, tracks: impl IntoIterator<Item = &'a ClipTrack>) -> Self {
+        Self {
+            library,
+            tracks: tracks.into_iter().collect(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.tracks.is_empty()
+    }
+
+    /// Track `name` at cut-local `time`, stacked over `base` (the actor's own value, or the
+    /// rest value if `None`). Tracks with no clip playing, or whose clip does not key
+    /// `name`, pass the value through.
+    pub fn value(&self, name: &str, base: Option<f32>, time: f32) -> Option<f32> {
+        let mut value = base;
+        for track in &self.tracks {
+            let Some(v) = track.value(self.library, name, time) else {
+                continue;
+            };
+            let below = value.unwrap_or_else(|| rest_value(name));
+            value = Some(track.blend.apply(below, v, track.influence_at(time)));
+        }
+        value
+    }
+
+    /// Track `name` of `actor` at scene `time` (cut-local `local_time`): the stack over the
+    /// actor's own timeline, with its layers riding on top.
+    pub fn track_value(
+        &self,
+        actor: &Actor,
+        name: &str,
+        time: f32,
+        local_time: f32,
+    ) -> Option<f32> {
+        let stacked = self.value(name, actor.timeline_value(name, time), local_time);
+        layer::add_offsets(stacked, &actor.layers, name, time)
+    }
+
+    /// `actor` posed by the stack at scene `time` (cut-local `local_time`): every track of
+    /// its timeline, its layers and the clips playing, frozen into single-key tracks.
+    pub fn pose(&self, actor: &Actor, time: f32, local_time: f32) -> Timeline {
+        let fading = actor.crossfade.as_ref().filter(|f| f.is_active(time));
+        let clips = self
+            .tracks
+            .iter()
+            .filter_map(|t| t.instance_at(local_time))
+            .filter_map(|i| self.library.get(&i.clip))
+            .map(|clip| &clip.timeline);
+        let sources = actor
+            .timeline
+            .iter()
+            .chain(fading.map(|f| &f.from))
+            .chain(clips)
+            .chain(actor.layers.iter().map(|l| &l.timeline));
+        let name = actor.timeline.as_ref().map_or("nla", |tl| tl.name.as_str());
+        layer::freeze(name, sources, time, |track| {
+            self.track_value(actor, track, time, local_time)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clip::{AnimationClip, ClipInstance};
+    use crate::scene::ActorId;
+    use alice_sdf::animation::{Keyframe, Track};
+    use alice_sdf::SdfNode;
+
+    fn hold(library: &mut ClipLibrary, name: &str, values: &[(&str, f32)]) -> ClipInstance {
+        let mut tl = Timeline::new(name);
+        for &(track, value) in values {
+            let mut t = Track::new(track);
+            t.add_keyframe(Keyframe::new(0.0, value));
+            tl.add_track(t);
+        }
+        let clip = AnimationClip::new(name, tl).with_duration(1.0);
+        let instance = ClipInstance::new(&clip, 0.0);
+        library.insert(clip);
+        instance
+    }
+
+    #[test]
+    fn test_blend_modes_stack_in_order() {
+        let mut library = ClipLibrary::new();
+        let walk = hold(&mut library, "walk", &[("translate.x", 4.0)]);
+        let walk = ClipTrack::new(ActorId(0)).with_clip(walk);
+        let shrug = hold(
+            &mut library,
+            "shrug",
+            &[("translate.x", 1.0), ("scale", 0.5)],
+        );
+        let shrug = ClipTrack::new(ActorId(0))
+            .with_clip(shrug)
+            .with_blend(BlendMode::Add)
+            .with_influence(0.5);
+        let squash = ClipTrack::new(ActorId(0))
+            .with_clip(hold(&mut library, "squash", &[("scale", 0.8)]))
+            .with_blend(BlendMode::Multiply);
+        let stack = NlaStack::new(&library, [&walk, &shrug, &squash]);
+        // Replace 2 → 4, then + 1 * 0.5
+        assert_eq!(stack.value("translate.x", Some(2.0), 0.5), Some(4.5));
+        // Rest scale 1 + 0.5 * 0.5, then * 0.8
+        assert!((stack.value("scale", None, 0.5).unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(stack.value("rotate.y", Some(0.3), 0.5), Some(0.3));
+        assert_eq!(stack.value("rotate.y", None, 0.5), None);
+    }
+
+    #[test]
+    fn test_influence_curve_fades_track_over_actor() {
+        let mut own = Timeline::new("idle");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 2.0));
+        own.add_track(x);
+        let actor = Actor::new("hero", SdfNode::sphere(1.0)).with_timeline(own);
+        let mut curve = Track::new("influence");
+        curve.add_keyframe(Keyframe::new(0.0, 0.0));
+        curve.add_keyframe(Keyframe::new(1.0, 1.0));
+        let mut library = ClipLibrary::new();
+        let wave = hold(
+            &mut library,
+            "wave",
+            &[("translate.x", 6.0), ("arm.r.swing", 1.0)],
+        );
+        let wave = ClipTrack::new(ActorId(0))
+            .with_clip(wave)
+            .with_influence_curve(curve);
+        assert_eq!(wave.influence_at(5.0), 1.0);
+        let pose = NlaStack::new(&library, [&wave]).pose(&actor, 10.25, 0.25);
+        assert_eq!(pose.name, "idle");
+        assert_eq!(pose.get_value("translate.x", 0.0), Some(3.0));
+        // Unkeyed on the actor: blends up from rest
+        assert_eq!(pose.get_value("arm.r.swing", 0.0), Some(0.25));
+    }
+}
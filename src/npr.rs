@@ -1,3 +1,6 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
 use serde::{Deserialize, Serialize};
 
 /// Cel shading configuration for anime-style step lighting.
@@ -41,6 +44,24 @@ impl CelShading {
         let rcp_steps = 1.0 / self.shadow_steps as f32;
         step as f32 * rcp_steps
     }
+
+    /// `quantize` with each step boundary smoothed over a lighting range of `width`
+    /// (anti-aliased cel edges). `width <= 0` is identical to `quantize`.
+    pub fn quantize_smooth(&self, lighting: f32, width: f32) -> f32 {
+        if width <= 0.0 || self.thresholds.is_empty() {
+            return self.quantize(lighting);
+        }
+        let half = width * 0.5;
+        let step: f32 = self
+            .thresholds
+            .iter()
+            .map(|&threshold| {
+                let x = ((lighting - threshold + half) / width).clamp(0.0, 1.0);
+                x * x * (3.0 - 2.0 * x)
+            })
+            .sum();
+        step / self.shadow_steps as f32
+    }
 }
 
 /// SDF-based outline configuration.
@@ -132,6 +153,15 @@ mod tests {
         assert_eq!(cel.quantize(0.9), 2.0 / 3.0);
     }
 
+    #[test]
+    fn test_cel_shading_quantize_smooth() {
+        let cel = CelShading::default();
+        assert_eq!(cel.quantize_smooth(0.4, 0.0), cel.quantize(0.4));
+        assert_eq!(cel.quantize_smooth(0.9, 0.1), cel.quantize(0.9));
+        let mid = cel.quantize_smooth(0.5, 0.1);
+        assert!(mid > cel.quantize(0.4) && mid < cel.quantize(0.6));
+    }
+
     #[test]
     fn test_outline_detection() {
         let outline = OutlineConfig {
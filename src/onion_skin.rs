@@ -0,0 +1,249 @@
+//! Onion skinning: evaluate an actor or the whole scene at the current frame and its
+//! neighbours in one call, reusing evaluations as the playhead steps through the shot.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use alice_sdf::SdfNode;
+
+use crate::frame_hash::hash_sdf;
+use crate::performance::Performance;
+use crate::scene::ActorId;
+
+/// What is ghosted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnionTarget {
+    Actor(ActorId),
+    Scene,
+}
+
+/// How many ghosts and how they fade.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnionSkinConfig {
+    /// Ghosts before the current frame.
+    pub before: u32,
+    /// Ghosts after the current frame.
+    pub after: u32,
+    /// Frames between ghosts (2 = every other frame, for animation on twos).
+    pub step: u32,
+    pub fps: f32,
+    /// Opacity of the nearest ghost; farther ghosts fade linearly towards 0.
+    pub opacity: f32,
+}
+
+impl Default for OnionSkinConfig {
+    fn default() -> Self {
+        Self {
+            before: 2,
+            after: 2,
+            step: 1,
+            fps: 24.0,
+            opacity: 0.5,
+        }
+    }
+}
+
+impl OnionSkinConfig {
+    pub fn with_range(mut self, before: u32, after: u32) -> Self {
+        self.before = before;
+        self.after = after;
+        self
+    }
+
+    pub fn with_step(mut self, step: u32) -> Self {
+        self.step = step.max(1);
+        self
+    }
+}
+
+/// One evaluated ghost (or the current frame, at offset 0).
+#[derive(Debug, Clone)]
+pub struct OnionFrame {
+    /// Ghost index relative to the current frame: negative = past, 0 = current, positive = future.
+    pub offset: i32,
+    pub frame: u32,
+    pub time: f32,
+    pub sdf: Arc<SdfNode>,
+    /// 1.0 for the current frame, fading outwards.
+    pub opacity: f32,
+    /// Same pose as the next frame towards the current one (a held drawing); tools usually skip it.
+    pub held: bool,
+}
+
+/// Onion-skin evaluator with a per-frame cache.
+#[derive(Debug, Clone)]
+pub struct OnionSkin {
+    pub target: OnionTarget,
+    pub config: OnionSkinConfig,
+    cache: BTreeMap<u32, (Arc<SdfNode>, Option<u64>)>,
+    evaluations: usize,
+}
+
+impl OnionSkin {
+    pub fn new(target: OnionTarget, config: OnionSkinConfig) -> Self {
+        Self {
+            target,
+            config,
+            cache: BTreeMap::new(),
+            evaluations: 0,
+        }
+    }
+
+    /// Drop cached evaluations (call after editing the scene).
+    pub fn invalidate(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Number of scene evaluations performed so far.
+    pub fn evaluations(&self) -> usize {
+        self.evaluations
+    }
+
+    fn evaluate_frame(
+        &mut self,
+        scene: Performance<'_>,
+        frame: u32,
+    ) -> (Arc<SdfNode>, Option<u64>) {
+        if let Some(cached) = self.cache.get(&frame) {
+            return cached.clone();
+        }
+        let time = frame as f32 / self.config.fps;
+        let sdf = match self.target {
+            OnionTarget::Actor(id) => scene
+                .actor_sdf(id, time)
+                .unwrap_or_else(|| SdfNode::sphere(0.0)),
+            OnionTarget::Scene => scene.evaluate_scene(time),
+        };
+        self.evaluations += 1;
+        let hash = hash_sdf(&sdf).ok();
+        let entry = (Arc::new(sdf), hash);
+        self.cache.insert(frame, entry.clone());
+        entry
+    }
+
+    /// Current frame plus ghosts, ordered from the earliest past ghost to the latest future one.
+    /// Ghosts before frame 0 are omitted. Frames outside the window are evicted from the cache.
+    pub fn evaluate(&mut self, scene: Performance<'_>, current_frame: u32) -> Vec<OnionFrame> {
+        let cfg = self.config;
+        let step = cfg.step.max(1) as i64;
+        let offsets: Vec<i32> = (-(cfg.before as i32)..=cfg.after as i32)
+            .filter(|o| current_frame as i64 + *o as i64 * step >= 0)
+            .collect();
+        let lo = current_frame as i64 - cfg.before as i64 * step;
+        let hi = current_frame as i64 + cfg.after as i64 * step;
+        self.cache
+            .retain(|&f, _| (f as i64) >= lo && (f as i64) <= hi);
+
+        let mut frames: Vec<(OnionFrame, Option<u64>)> = offsets
+            .iter()
+            .map(|&offset| {
+                let frame = (current_frame as i64 + offset as i64 * step) as u32;
+                let (sdf, hash) = self.evaluate_frame(scene, frame);
+                let reach = if offset < 0 { cfg.before } else { cfg.after }.max(1) as f32;
+                let opacity = if offset == 0 {
+                    1.0
+                } else {
+                    cfg.opacity * (1.0 - (offset.unsigned_abs() as f32 - 1.0) / reach)
+                };
+                let ghost = OnionFrame {
+                    offset,
+                    frame,
+                    time: frame as f32 / cfg.fps,
+                    sdf,
+                    opacity,
+                    held: false,
+                };
+                (ghost, hash)
+            })
+            .collect();
+        // A ghost is held when it matches its neighbour on the side of the current frame
+        for i in 0..frames.len() {
+            let offset = frames[i].0.offset;
+            let neighbour = match offset {
+                o if o < 0 => i + 1,
+                o if o > 0 => i - 1,
+                _ => continue,
+            };
+            let (a, b) = (frames[i].1, frames[neighbour].1);
+            frames[i].0.held = a.is_some() && a == b;
+        }
+        frames.into_iter().map(|(f, _)| f).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+    fn scene() -> (SceneGraph, ActorId) {
+        let mut sg = SceneGraph::new();
+        let mut tl = Timeline::new("slide");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(1.0, 24.0));
+        // Hold from 1s
+        x.add_keyframe(Keyframe::new(2.0, 24.0));
+        tl.add_track(x);
+        let id = sg.add_actor(Actor::new("runner", SdfNode::sphere(0.5)).with_timeline(tl));
+        sg.add_actor(Actor::new("prop", SdfNode::sphere(0.2)));
+        (sg, id)
+    }
+
+    #[test]
+    fn test_onion_frames_tagged_and_faded() {
+        let (sg, runner) = scene();
+        let config = OnionSkinConfig::default().with_range(2, 1).with_step(2);
+        let mut onion = OnionSkin::new(OnionTarget::Actor(runner), config);
+        let frames = onion.evaluate(Performance::from(&sg), 10);
+        let tags: Vec<(i32, u32)> = frames.iter().map(|f| (f.offset, f.frame)).collect();
+        assert_eq!(tags, vec![(-2, 6), (-1, 8), (0, 10), (1, 12)]);
+        assert_eq!(frames[2].opacity, 1.0);
+        assert_eq!(frames[1].opacity, 0.5);
+        assert_eq!(frames[0].opacity, 0.25);
+        assert_eq!(frames[3].opacity, 0.5);
+        let x = |f: &OnionFrame| alice_sdf::eval(&f.sdf, glam::Vec3::new(f.frame as f32, 0.0, 0.0));
+        for f in &frames {
+            assert!(
+                x(f) < 0.0,
+                "ghost at frame {} is not where the actor was",
+                f.frame
+            );
+        }
+
+        // Near the start, ghosts before frame 0 are dropped
+        let early = onion.evaluate(Performance::from(&sg), 1);
+        assert_eq!(early.first().unwrap().offset, 0);
+    }
+
+    #[test]
+    fn test_cache_avoids_reevaluation() {
+        let (sg, _) = scene();
+        let mut onion = OnionSkin::new(OnionTarget::Scene, OnionSkinConfig::default());
+        onion.evaluate(Performance::from(&sg), 20);
+        assert_eq!(onion.evaluations(), 5);
+        // Stepping forward one frame only evaluates the new leading ghost
+        onion.evaluate(Performance::from(&sg), 21);
+        assert_eq!(onion.evaluations(), 6);
+        onion.invalidate();
+        onion.evaluate(Performance::from(&sg), 21);
+        assert_eq!(onion.evaluations(), 11);
+    }
+
+    #[test]
+    fn test_held_poses_flagged() {
+        let (sg, runner) = scene();
+        let mut onion = OnionSkin::new(
+            OnionTarget::Actor(runner),
+            OnionSkinConfig::default().with_range(1, 2),
+        );
+        // Frame 24 onwards is a hold; frame 23 is still moving
+        let frames = onion.evaluate(Performance::from(&sg), 25);
+        let held: Vec<bool> = frames.iter().map(|f| f.held).collect();
+        assert_eq!(held, vec![true, false, true, true]);
+        let moving = onion.evaluate(Performance::from(&sg), 23);
+        let held: Vec<bool> = moving.iter().map(|f| f.held).collect();
+        assert_eq!(held, vec![false, false, false, true]);
+    }
+}
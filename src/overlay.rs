@@ -0,0 +1,366 @@
+//! 2D overlays: timed title cards, episode numbers, eyecatches and credits with keyframed
+//! position / scale / opacity, composited over rendered frames.
+
+use alice_sdf::animation::Track;
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::Framebuffer;
+
+/// What an overlay is for; purely descriptive, compositing treats all kinds alike.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OverlayKind {
+    TitleCard,
+    EpisodeNumber,
+    Eyecatch,
+    Credits,
+    Custom(String),
+}
+
+/// Pixels an overlay draws.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum OverlayContent {
+    /// Flat linear RGBA (card backgrounds, fades to black).
+    Solid([f32; 4]),
+    /// Linear RGBA image stretched over the overlay rect.
+    Image(Framebuffer),
+    /// Text, drawn only through [`composite_overlays_with`] by a caller-supplied rasterizer.
+    Text { text: String, color: [f32; 4] },
+}
+
+/// A timed 2D element in normalized frame coordinates (0..1, top-left origin).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Overlay {
+    pub name: String,
+    pub kind: OverlayKind,
+    pub content: OverlayContent,
+    /// Episode time span `[start, end)`.
+    pub start: f32,
+    pub end: f32,
+    /// Higher layers draw on top.
+    pub layer: i32,
+    /// Centre of the rect.
+    pub position: [f32; 2],
+    /// Rect size before scaling; text keeps its aspect and uses only the height.
+    pub size: [f32; 2],
+    pub scale: f32,
+    pub opacity: f32,
+    /// Seconds of linear fade at each end.
+    pub fade_in: f32,
+    pub fade_out: f32,
+    /// Tracks in overlay-local time; each overrides the static value above when present.
+    pub position_x: Option<Track>,
+    pub position_y: Option<Track>,
+    pub scale_track: Option<Track>,
+    pub opacity_track: Option<Track>,
+}
+
+impl Overlay {
+    /// Full-frame overlay shown over `[start, end)`.
+    pub fn new(
+        name: impl Into<String>,
+        kind: OverlayKind,
+        content: OverlayContent,
+        start: f32,
+        end: f32,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            content,
+            start,
+            end,
+            layer: 0,
+            position: [0.5, 0.5],
+            size: [1.0, 1.0],
+            scale: 1.0,
+            opacity: 1.0,
+            fade_in: 0.0,
+            fade_out: 0.0,
+            position_x: None,
+            position_y: None,
+            scale_track: None,
+            opacity_track: None,
+        }
+    }
+
+    pub fn with_rect(mut self, position: [f32; 2], size: [f32; 2]) -> Self {
+        self.position = position;
+        self.size = size;
+        self
+    }
+
+    pub fn with_layer(mut self, layer: i32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_fades(mut self, fade_in: f32, fade_out: f32) -> Self {
+        self.fade_in = fade_in.max(0.0);
+        self.fade_out = fade_out.max(0.0);
+        self
+    }
+
+    pub fn with_position_tracks(mut self, x: Option<Track>, y: Option<Track>) -> Self {
+        self.position_x = x;
+        self.position_y = y;
+        self
+    }
+
+    pub fn with_scale_track(mut self, track: Track) -> Self {
+        self.scale_track = Some(track);
+        self
+    }
+
+    pub fn with_opacity_track(mut self, track: Track) -> Self {
+        self.opacity_track = Some(track);
+        self
+    }
+
+    #[inline]
+    pub fn is_active(&self, time: f32) -> bool {
+        time >= self.start && time < self.end
+    }
+
+    /// Placement and opacity at episode `time`, or `None` outside the overlay's span.
+    pub fn evaluate(&self, time: f32) -> Option<OverlayState> {
+        if !self.is_active(time) {
+            return None;
+        }
+        let local = time - self.start;
+        let eval = |track: &Option<Track>, fallback: f32| {
+            track.as_ref().map_or(fallback, |t| t.evaluate(local))
+        };
+        let fade = |width: f32, distance: f32| {
+            if width > 0.0 {
+                (distance / width).min(1.0)
+            } else {
+                1.0
+            }
+        };
+        let scale = eval(&self.scale_track, self.scale).max(0.0);
+        let opacity = eval(&self.opacity_track, self.opacity).clamp(0.0, 1.0)
+            * fade(self.fade_in, local)
+            * fade(self.fade_out, self.end - time);
+        Some(OverlayState {
+            name: self.name.clone(),
+            kind: self.kind.clone(),
+            layer: self.layer,
+            position: [
+                eval(&self.position_x, self.position[0]),
+                eval(&self.position_y, self.position[1]),
+            ],
+            size: [self.size[0] * scale, self.size[1] * scale],
+            opacity,
+        })
+    }
+}
+
+/// An overlay evaluated at one instant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OverlayState {
+    pub name: String,
+    pub kind: OverlayKind,
+    pub layer: i32,
+    /// Centre, normalized.
+    pub position: [f32; 2],
+    /// Scaled size, normalized.
+    pub size: [f32; 2],
+    pub opacity: f32,
+}
+
+/// Active overlays at `time`, bottom layer first (ties keep list order).
+pub fn evaluate_overlays(overlays: &[Overlay], time: f32) -> Vec<OverlayState> {
+    let mut states: Vec<OverlayState> = overlays.iter().filter_map(|o| o.evaluate(time)).collect();
+    states.sort_by_key(|s| s.layer);
+    states
+}
+
+/// Composite the overlays active at `time` over `fb`. Text overlays are skipped.
+pub fn composite_overlays(fb: &mut Framebuffer, overlays: &[Overlay], time: f32) {
+    composite_overlays_with(fb, overlays, time, |_, _| None);
+}
+
+/// Like [`composite_overlays`], rasterizing text with `rasterize(text, color)`; a `None`
+/// result skips that overlay.
+pub fn composite_overlays_with<F>(
+    fb: &mut Framebuffer,
+    overlays: &[Overlay],
+    time: f32,
+    mut rasterize: F,
+) where
+    F: FnMut(&str, [f32; 4]) -> Option<Framebuffer>,
+{
+    let mut active: Vec<(&Overlay, OverlayState)> = overlays
+        .iter()
+        .filter_map(|o| o.evaluate(time).map(|s| (o, s)))
+        .collect();
+    active.sort_by_key(|(_, s)| s.layer);
+    for (overlay, mut state) in active {
+        if state.opacity <= 0.0 {
+            continue;
+        }
+        match &overlay.content {
+            OverlayContent::Solid(color) => draw(fb, &state, |_, _| *color),
+            OverlayContent::Image(image) => draw(fb, &state, |u, v| sample(image, u, v)),
+            OverlayContent::Text { text, color } => {
+                let Some(image) = rasterize(text, *color) else {
+                    continue;
+                };
+                if image.height > 0 && fb.width > 0 {
+                    // Keep the glyph aspect: width follows from the height in pixels
+                    let aspect = image.width as f32 / image.height as f32;
+                    state.size[0] = state.size[1] * fb.height as f32 * aspect / fb.width as f32;
+                }
+                draw(fb, &state, |u, v| sample(&image, u, v));
+            }
+        }
+    }
+}
+
+/// Nearest pixel of `image` at (`u`, `v`) in 0..1.
+fn sample(image: &Framebuffer, u: f32, v: f32) -> [f32; 4] {
+    if image.pixel_count() == 0 {
+        return [0.0; 4];
+    }
+    let x = ((u * image.width as f32) as u32).min(image.width - 1);
+    let y = ((v * image.height as f32) as u32).min(image.height - 1);
+    image.pixel(x, y)
+}
+
+/// Alpha-over `color(u, v)` across the state's rect, clipped to the frame.
+fn draw(fb: &mut Framebuffer, state: &OverlayState, color: impl Fn(f32, f32) -> [f32; 4]) {
+    let (w, h) = (fb.width as f32, fb.height as f32);
+    let rect_w = state.size[0] * w;
+    let rect_h = state.size[1] * h;
+    if rect_w <= 0.0 || rect_h <= 0.0 {
+        return;
+    }
+    let left = state.position[0] * w - rect_w * 0.5;
+    let top = state.position[1] * h - rect_h * 0.5;
+    let x0 = left.max(0.0) as u32;
+    let y0 = top.max(0.0) as u32;
+    let x1 = ((left + rect_w).ceil().max(0.0) as u32).min(fb.width);
+    let y1 = ((top + rect_h).ceil().max(0.0) as u32).min(fb.height);
+    let premultiplied = fb.premultiplied;
+    for y in y0..y1 {
+        let v = (y as f32 + 0.5 - top) / rect_h;
+        if !(0.0..1.0).contains(&v) {
+            continue;
+        }
+        for x in x0..x1 {
+            let u = (x as f32 + 0.5 - left) / rect_w;
+            if !(0.0..1.0).contains(&u) {
+                continue;
+            }
+            let src = color(u, v);
+            let a = src[3] * state.opacity;
+            let i = fb.index(x, y);
+            let dst = fb.color[i];
+            let out_a = a + dst[3] * (1.0 - a);
+            let mut out = [0.0, 0.0, 0.0, out_a];
+            for c in 0..3 {
+                out[c] = if premultiplied {
+                    src[c] * a + dst[c] * (1.0 - a)
+                } else if out_a > 0.0 {
+                    (src[c] * a + dst[c] * dst[3] * (1.0 - a)) / out_a
+                } else {
+                    0.0
+                };
+            }
+            fb.color[i] = out;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::animation::Keyframe;
+
+    fn card() -> Overlay {
+        Overlay::new(
+            "title",
+            OverlayKind::TitleCard,
+            OverlayContent::Solid([1.0, 0.0, 0.0, 1.0]),
+            1.0,
+            3.0,
+        )
+        .with_rect([0.5, 0.5], [0.5, 0.5])
+        .with_fades(0.5, 0.5)
+    }
+
+    #[test]
+    fn test_evaluate_tracks_and_fades() {
+        let mut x = Track::new("position.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(2.0, 1.0));
+        let overlay = card().with_position_tracks(Some(x), None);
+        assert!(overlay.evaluate(0.5).is_none());
+        assert!(overlay.evaluate(3.0).is_none());
+        let mid = overlay.evaluate(2.0).unwrap();
+        assert!((mid.position[0] - 0.5).abs() < 1e-5);
+        assert_eq!(mid.position[1], 0.5);
+        assert_eq!(mid.opacity, 1.0);
+        assert!((overlay.evaluate(1.25).unwrap().opacity - 0.5).abs() < 1e-5);
+        assert!((overlay.evaluate(2.75).unwrap().opacity - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_composite_layers_over_frame() {
+        let mut fb = Framebuffer::new(8, 8);
+        fb.fill([0.0, 0.0, 1.0, 1.0]);
+        let overlays = vec![
+            card().with_fades(0.0, 0.0).with_layer(1),
+            Overlay::new(
+                "fade",
+                OverlayKind::Eyecatch,
+                OverlayContent::Solid([0.0, 1.0, 0.0, 1.0]),
+                0.0,
+                10.0,
+            )
+            .with_opacity(0.5),
+        ];
+        composite_overlays(&mut fb, &overlays, 2.0);
+        // Card covers the centre 4x4 and sits above the half-transparent full-frame layer
+        assert_eq!(fb.pixel(4, 4), [1.0, 0.0, 0.0, 1.0]);
+        let edge = fb.pixel(0, 0);
+        assert!((edge[1] - 0.5).abs() < 1e-5 && (edge[2] - 0.5).abs() < 1e-5);
+        assert_eq!(edge[3], 1.0);
+        let order: Vec<String> = evaluate_overlays(&overlays, 2.0)
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(order, vec!["fade", "title"]);
+    }
+
+    #[test]
+    fn test_text_uses_rasterizer() {
+        let text = Overlay::new(
+            "ep",
+            OverlayKind::EpisodeNumber,
+            OverlayContent::Text {
+                text: "#1".into(),
+                color: [1.0; 4],
+            },
+            0.0,
+            1.0,
+        )
+        .with_rect([0.5, 0.5], [1.0, 0.5]);
+        let mut fb = Framebuffer::new(8, 8);
+        composite_overlays(&mut fb, std::slice::from_ref(&text), 0.0);
+        assert_eq!(fb.pixel(4, 4), [0.0; 4]);
+        composite_overlays_with(&mut fb, &[text], 0.0, |_, color| {
+            let mut glyphs = Framebuffer::new(1, 1);
+            glyphs.fill(color);
+            Some(glyphs)
+        });
+        // Square glyph image at half height: 4 pixels wide, centred
+        assert_eq!(fb.pixel(4, 4), [1.0; 4]);
+        assert_eq!(fb.pixel(1, 4), [0.0; 4]);
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::{assemble_episode, chunks_crc, split_episode, Chunk};
+use crate::episode::{
+    format_prefix, frame_flags, header_version, read_checked_body, read_format_header, seal_header,
+    EpisodePackage,
+};
+use crate::error::AnimationError;
+
+/// Patch format magic bytes.
+const PATCH_MAGIC: [u8; 4] = *b"APCH";
+/// Patch format version.
+const PATCH_VERSION: u16 = 1;
+
+/// A single step in rebuilding the target chunk list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PatchOp {
+    /// Reuse chunk at this index of the base episode.
+    Copy(u32),
+    /// New or changed chunk, carried in full.
+    Insert(Chunk),
+}
+
+/// Chunk/cut-level delta between two versions of an episode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpisodePatch {
+    /// `chunks_crc` of the episode this patch applies to.
+    pub base_crc: u32,
+    /// `chunks_crc` of the episode this patch produces.
+    pub target_crc: u32,
+    pub ops: Vec<PatchOp>,
+}
+
+impl EpisodePatch {
+    /// Number of chunks carried in full.
+    #[inline]
+    pub fn changed_chunks(&self) -> usize {
+        self.ops
+            .iter()
+            .filter(|op| matches!(op, PatchOp::Insert(_)))
+            .count()
+    }
+
+    /// True if applying the patch reproduces the base unchanged.
+    #[inline]
+    pub fn is_noop(&self) -> bool {
+        self.base_crc == self.target_crc
+    }
+}
+
+/// Create a patch that turns `old` into `new`.
+///
+/// Unchanged chunks (metadata, scene graph, shading, director header, individual cuts)
+/// are referenced by index; only changed ones are stored.
+pub fn create_patch(
+    old: &EpisodePackage,
+    new: &EpisodePackage,
+) -> crate::error::Result<EpisodePatch> {
+    let old_chunks = split_episode(old)?;
+    let new_chunks = split_episode(new)?;
+
+    let mut index: HashMap<&Chunk, u32> = HashMap::with_capacity(old_chunks.len());
+    for (i, chunk) in old_chunks.iter().enumerate() {
+        index.entry(chunk).or_insert(i as u32);
+    }
+
+    let ops = new_chunks
+        .iter()
+        .map(|chunk| match index.get(chunk) {
+            Some(&i) => PatchOp::Copy(i),
+            None => PatchOp::Insert(chunk.clone()),
+        })
+        .collect();
+
+    Ok(EpisodePatch {
+        base_crc: chunks_crc(&old_chunks),
+        target_crc: chunks_crc(&new_chunks),
+        ops,
+    })
+}
+
+/// Apply a patch to its base episode.
+pub fn apply_patch(
+    base: &EpisodePackage,
+    patch: &EpisodePatch,
+) -> crate::error::Result<EpisodePackage> {
+    let base_chunks = split_episode(base)?;
+    let base_crc = chunks_crc(&base_chunks);
+    if base_crc != patch.base_crc {
+        return Err(AnimationError::CrcMismatch {
+            expected: patch.base_crc,
+            actual: base_crc,
+        });
+    }
+
+    let mut chunks = Vec::with_capacity(patch.ops.len());
+    for op in &patch.ops {
+        match op {
+            PatchOp::Copy(i) => {
+                let chunk = base_chunks.get(*i as usize).ok_or_else(|| {
+                    AnimationError::Corrupt(format!("Patch references missing base chunk {}", i))
+                })?;
+                chunks.push(chunk.clone());
+            }
+            PatchOp::Insert(chunk) => chunks.push(chunk.clone()),
+        }
+    }
+
+    let target_crc = chunks_crc(&chunks);
+    if target_crc != patch.target_crc {
+        return Err(AnimationError::CrcMismatch {
+            expected: patch.target_crc,
+            actual: target_crc,
+        });
+    }
+    assemble_episode(&chunks)
+}
+
+/// Serialize a patch to a writer.
+///
+/// Binary format (same header layout as ANIM):
+/// `[Magic "APCH" 4B][Version 2B][Flags 2B][Size 4B][CRC32 4B][Bincode Body]`
+pub fn write_patch<W: Write>(patch: &EpisodePatch, writer: &mut W) -> crate::error::Result<usize> {
+    let body = bincode::serialize(patch)?;
+    let prefix = format_prefix(&PATCH_MAGIC, PATCH_VERSION, 0);
+    writer.write_all(&seal_header(prefix, &body))?;
+    writer.write_all(&body)?;
+
+    Ok(16 + body.len())
+}
+
+/// Deserialize a patch from a reader.
+pub fn read_patch<R: Read>(reader: &mut R) -> crate::error::Result<EpisodePatch> {
+    let header = read_format_header(reader, &PATCH_MAGIC)?;
+    let version = header_version(&header);
+    if version != PATCH_VERSION {
+        return Err(AnimationError::UnsupportedVersion {
+            format: "APCH",
+            version,
+        });
+    }
+    let flags = frame_flags(&header);
+    if flags != 0 {
+        return Err(AnimationError::Corrupt(format!(
+            "Patch with unknown flags {:#06x}",
+            flags
+        )));
+    }
+    let body = read_checked_body(&header, reader, "patch body")?;
+    Ok(bincode::deserialize(&body)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::{serialize_episode, EpisodeMetadata};
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Patch");
+        dir.add_cut(Cut::new("intro", 0.0, 3.0).with_actors(vec![hero]));
+        dir.add_cut(Cut::new("battle", 3.0, 8.0).with_actors(vec![hero]));
+        dir.add_cut(Cut::new("outro", 8.0, 10.0).with_actors(vec![hero]));
+        let meta = EpisodeMetadata::new("Patch", 1, 10.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_patch_single_cut_change() {
+        let old = make_test_episode();
+        let mut new = old.clone();
+        let (battle_id, _) = new.director.find_active_cut(4.0).unwrap();
+        new.director.get_cut_mut(battle_id).unwrap().name = "battle_v2".into();
+
+        let patch = create_patch(&old, &new).unwrap();
+        assert_eq!(patch.changed_chunks(), 1);
+
+        let restored = apply_patch(&old, &patch).unwrap();
+        assert_eq!(
+            restored.director.get_cut(battle_id).unwrap().name,
+            "battle_v2"
+        );
+        assert_eq!(restored.director.cut_count(), 3);
+    }
+
+    #[test]
+    fn test_patch_smaller_than_episode() {
+        let old = make_test_episode();
+        let mut new = old.clone();
+        new.director.add_cut(Cut::new("epilogue", 10.0, 12.0));
+
+        let patch = create_patch(&old, &new).unwrap();
+        let mut patch_buf = Vec::new();
+        let patch_size = write_patch(&patch, &mut patch_buf).unwrap();
+        let mut full_buf = Vec::new();
+        let full_size = serialize_episode(&new, &mut full_buf).unwrap();
+        assert!(patch_size < full_size);
+
+        let mut cursor = std::io::Cursor::new(&patch_buf);
+        let read_back = read_patch(&mut cursor).unwrap();
+        let restored = apply_patch(&old, &read_back).unwrap();
+        assert_eq!(restored.director.cut_count(), 4);
+
+        // Damaged files fail like damaged episodes
+        let read = |bytes: &[u8]| read_patch(&mut &bytes[..]);
+        assert!(matches!(
+            read(&patch_buf[..patch_buf.len() - 1]),
+            Err(AnimationError::Truncated("patch body"))
+        ));
+        let mut flipped = patch_buf.clone();
+        *flipped.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            read(&flipped),
+            Err(AnimationError::CrcMismatch { .. })
+        ));
+        assert!(matches!(
+            read(&full_buf),
+            Err(AnimationError::BadMagic { expected: "APCH" })
+        ));
+    }
+
+    #[test]
+    fn test_patch_wrong_base() {
+        let old = make_test_episode();
+        let mut new = old.clone();
+        new.metadata.title = "Renamed".into();
+        let patch = create_patch(&old, &new).unwrap();
+        assert!(matches!(
+            apply_patch(&new, &patch),
+            Err(AnimationError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_patch_noop() {
+        let old = make_test_episode();
+        let patch = create_patch(&old, &old.clone()).unwrap();
+        assert!(patch.is_noop());
+        assert_eq!(patch.changed_chunks(), 0);
+    }
+}
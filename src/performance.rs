@@ -0,0 +1,191 @@
+//! Actors as the edit plays them.
+//!
+//! A cut's clip tracks drive its actors over their own animation, so anything that samples
+//! actor motion (rendering, baking, export, simulation, previews) has to look up the cut
+//! active at the sampled time. A [`Performance`] pairs the scene with the director for that;
+//! made from a bare [`SceneGraph`] it plays each actor's own animation only.
+
+use alloc::vec::Vec;
+
+use alice_sdf::animation::Timeline;
+use alice_sdf::SdfNode;
+use glam::Vec3;
+
+use crate::director::{Cut, Director};
+use crate::nla::NlaStack;
+use crate::scene::{Actor, ActorId, ActorTransform, SceneGraph};
+
+/// A scene played through the director's cuts.
+#[derive(Debug, Clone, Copy)]
+pub struct Performance<'a> {
+    pub scene: &'a SceneGraph,
+    /// Cuts whose clip tracks play; `None` plays the actors' own animation.
+    pub director: Option<&'a Director>,
+}
+
+impl<'a> From<&'a SceneGraph> for Performance<'a> {
+    fn from(scene: &'a SceneGraph) -> Self {
+        Self {
+            scene,
+            director: None,
+        }
+    }
+}
+
+impl<'a> Performance<'a> {
+    pub fn new(scene: &'a SceneGraph, director: &'a Director) -> Self {
+        Self {
+            scene,
+            director: Some(director),
+        }
+    }
+
+    #[inline]
+    pub fn get_actor(&self, id: ActorId) -> Option<&'a Actor> {
+        self.scene.get_actor(id)
+    }
+
+    #[inline]
+    pub fn get_world_transform(&self, id: ActorId) -> ActorTransform {
+        self.scene.get_world_transform(id)
+    }
+
+    #[inline]
+    fn cut_at(&self, time: f32) -> Option<&'a Cut> {
+        self.director?.find_active_cut(time).map(|(_, cut)| cut)
+    }
+
+    /// Clip tracks on `id` in `cut`, with the cut-local time of `time`.
+    fn stack_in(
+        &self,
+        cut: Option<&'a Cut>,
+        id: ActorId,
+        time: f32,
+    ) -> Option<(NlaStack<'a>, f32)> {
+        let cut = cut?;
+        let stack = cut.nla_stack(&self.scene.clips, id);
+        (!stack.is_empty()).then_some((stack, time - cut.start_time))
+    }
+
+    /// Clip tracks driving `id` at scene `time` and the cut-local time; `None` where the
+    /// actor plays its own animation.
+    pub fn clip_stack(&self, id: ActorId, time: f32) -> Option<(NlaStack<'a>, f32)> {
+        self.stack_in(self.cut_at(time), id, time)
+    }
+
+    /// Value of track `name` of actor `id` at scene `time`: its own animation with any clip
+    /// tracks stacked on top, then its layers.
+    pub fn track_value(&self, id: ActorId, name: &str, time: f32) -> Option<f32> {
+        let actor = self.scene.get_actor(id)?;
+        match self.clip_stack(id, time) {
+            Some((stack, local_time)) => stack.track_value(actor, name, time, local_time),
+            None => actor.track_value(name, time),
+        }
+    }
+
+    /// World position of actor `id` at `time`, including its `translate.*` channels.
+    pub fn actor_position_at(&self, id: ActorId, time: f32) -> Vec3 {
+        let value = |name| self.track_value(id, name, time).unwrap_or(0.0);
+        self.scene.get_world_transform(id).position
+            + Vec3::new(
+                value("translate.x"),
+                value("translate.y"),
+                value("translate.z"),
+            )
+    }
+
+    /// Every channel of actor `id` at `time`, frozen into single-key tracks.
+    pub fn pose_at(&self, id: ActorId, time: f32) -> Option<Timeline> {
+        let actor = self.scene.get_actor(id)?;
+        Some(match self.clip_stack(id, time) {
+            Some((stack, local_time)) => stack.pose(actor, time, local_time),
+            None => actor.pose_at(time),
+        })
+    }
+
+    /// SDF of actor `id` at `time`.
+    pub fn actor_sdf(&self, id: ActorId, time: f32) -> Option<SdfNode> {
+        let actor = self.scene.get_actor(id)?;
+        Some(self.sdf_in(self.cut_at(time), id, actor, time))
+    }
+
+    fn sdf_in(&self, cut: Option<&'a Cut>, id: ActorId, actor: &Actor, time: f32) -> SdfNode {
+        match self.stack_in(cut, id, time) {
+            Some((stack, local_time)) => {
+                actor.evaluate_pose(stack.pose(actor, time, local_time), time)
+            }
+            None => actor.evaluate_sdf(time),
+        }
+    }
+
+    /// Each visible actor's SDF at `time`.
+    pub fn evaluate_actors(&self, time: f32) -> Vec<(ActorId, SdfNode)> {
+        let cut = self.cut_at(time);
+        self.scene
+            .evaluate_actors_with(|id, actor| self.sdf_in(cut, id, actor, time))
+    }
+
+    /// Union of all visible actors at `time`.
+    pub fn evaluate_scene(&self, time: f32) -> SdfNode {
+        let cut = self.cut_at(time);
+        self.scene
+            .evaluate_scene_with(|id, actor| self.sdf_in(cut, id, actor, time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clip::{AnimationClip, ClipInstance, ClipTrack};
+    use crate::layer::AnimationLayer;
+    use alice_sdf::animation::{Keyframe, Track};
+
+    fn hop_scene() -> (SceneGraph, Director, ActorId) {
+        let mut hop = Timeline::new("hop");
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.0));
+        y.add_keyframe(Keyframe::new(1.0, 2.0));
+        hop.add_track(y);
+        let hop = AnimationClip::new("hop", hop);
+
+        let mut bob = Timeline::new("bob");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.5));
+        bob.add_track(x);
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(
+            Actor::new("hero", SdfNode::sphere(0.25)).with_layer(AnimationLayer::new("bob", bob)),
+        );
+        let track = ClipTrack::new(hero).with_clip(ClipInstance::new(&hop, 0.0));
+        sg.clips.insert(hop);
+        let mut dir = Director::new("Hop");
+        dir.add_cut(Cut::new("still", 0.0, 2.0));
+        dir.add_cut(Cut::new("hop", 2.0, 4.0).with_clip_track(track));
+        (sg, dir, hero)
+    }
+
+    #[test]
+    fn test_clip_tracks_reach_every_query() {
+        let (sg, dir, hero) = hop_scene();
+        let edit = Performance::new(&sg, &dir);
+        // Halfway through the hop, with the layer still on top
+        assert_eq!(edit.track_value(hero, "translate.y", 2.5), Some(1.0));
+        assert_eq!(edit.actor_position_at(hero, 2.5), Vec3::new(0.5, 1.0, 0.0));
+        let pose = edit.pose_at(hero, 2.5).unwrap();
+        assert_eq!(pose.get_value("translate.y", 0.0), Some(1.0));
+        let sdf = edit.actor_sdf(hero, 2.5).unwrap();
+        assert!(alice_sdf::eval(&sdf, Vec3::new(0.5, 1.0, 0.0)) < 0.0);
+        assert!(alice_sdf::eval(&edit.evaluate_scene(2.5), Vec3::new(0.5, 1.0, 0.0)) < 0.0);
+        // Outside the cut the actor plays its own animation
+        assert_eq!(edit.actor_position_at(hero, 1.0), Vec3::new(0.5, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_scene_only_ignores_clips() {
+        let (sg, _, hero) = hop_scene();
+        let own = Performance::from(&sg);
+        assert!(own.clip_stack(hero, 2.5).is_none());
+        assert_eq!(own.actor_position_at(hero, 2.5), Vec3::new(0.5, 0.0, 0.0));
+        assert_eq!(own.evaluate_actors(2.5).len(), 1);
+    }
+}
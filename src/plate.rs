@@ -0,0 +1,151 @@
+//! Background plates: painted (or pre-rendered) backgrounds with depth, composited
+//! behind SDF actors, plus distance fog.
+
+use serde::{Deserialize, Serialize};
+
+use crate::framebuffer::Framebuffer;
+use crate::render::AOV_DEPTH;
+
+/// Depth of a background plate, as view-space distance from the camera.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PlateDepth {
+    /// Behind everything.
+    Infinite,
+    /// Flat card at this distance.
+    Constant(f32),
+    /// Per-pixel distance from the plate's `depth` AOV (infinite where it is missing).
+    Map,
+}
+
+/// Background image stretched to fill the frame.
+#[derive(Debug, Clone)]
+pub struct BackgroundPlate {
+    /// Linear color (and optional `depth` AOV for `PlateDepth::Map`).
+    pub image: Framebuffer,
+    pub depth: PlateDepth,
+}
+
+impl BackgroundPlate {
+    /// Plate infinitely far away.
+    pub fn new(image: Framebuffer) -> Self {
+        Self {
+            image,
+            depth: PlateDepth::Infinite,
+        }
+    }
+
+    /// Set plate depth.
+    pub fn with_depth(mut self, depth: PlateDepth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Nearest pixel at normalized image position (`u`, `v` in 0..1, top-left origin).
+    #[inline]
+    fn index(&self, u: f32, v: f32) -> usize {
+        let w = self.image.width.max(1);
+        let h = self.image.height.max(1);
+        let x = ((u * w as f32) as u32).min(w - 1);
+        let y = ((v * h as f32) as u32).min(h - 1);
+        self.image.index(x, y)
+    }
+
+    /// Color and view-space depth at (`u`, `v`).
+    pub fn sample(&self, u: f32, v: f32) -> ([f32; 4], f32) {
+        if self.image.pixel_count() == 0 {
+            return ([0.0; 4], f32::INFINITY);
+        }
+        let i = self.index(u, v);
+        let depth = match self.depth {
+            PlateDepth::Infinite => f32::INFINITY,
+            PlateDepth::Constant(d) => d,
+            PlateDepth::Map => self
+                .image
+                .aov(AOV_DEPTH)
+                .map_or(f32::INFINITY, |aov| aov.sample(i)[0]),
+        };
+        (self.image.color[i], depth)
+    }
+}
+
+/// Exponential distance fog.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Fog {
+    pub color: [f32; 4],
+    /// Extinction per world unit.
+    pub density: f32,
+    /// Distance where fog begins.
+    pub start: f32,
+}
+
+impl Fog {
+    pub fn new(color: [f32; 4], density: f32) -> Self {
+        Self {
+            color,
+            density,
+            start: 0.0,
+        }
+    }
+
+    /// Set fog start distance.
+    pub fn with_start(mut self, start: f32) -> Self {
+        self.start = start;
+        self
+    }
+
+    /// Fog opacity (0..1) at `distance`; 0 for infinite distances (unfogged sky/plate).
+    #[inline]
+    pub fn amount(&self, distance: f32) -> f32 {
+        if !distance.is_finite() {
+            return 0.0;
+        }
+        1.0 - (-self.density * (distance - self.start).max(0.0)).exp()
+    }
+
+    /// Blend premultiplied `color` towards the fog color by the amount at `distance`;
+    /// alpha (coverage) is kept.
+    pub fn apply(&self, color: [f32; 4], distance: f32) -> [f32; 4] {
+        let t = self.amount(distance);
+        let a = color[3];
+        [
+            color[0] + (self.color[0] * a - color[0]) * t,
+            color[1] + (self.color[1] * a - color[1]) * t,
+            color[2] + (self.color[2] * a - color[2]) * t,
+            a,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plate_sampling_and_depth() {
+        let mut image = Framebuffer::new(2, 1);
+        image.set_pixel(1, 0, [1.0, 0.0, 0.0, 1.0]);
+        image.add_aov(AOV_DEPTH, 1).data = vec![3.0, 9.0];
+        let plate = BackgroundPlate::new(image);
+        assert_eq!(
+            plate.sample(0.9, 0.5),
+            ([1.0, 0.0, 0.0, 1.0], f32::INFINITY)
+        );
+
+        let plate = plate.with_depth(PlateDepth::Map);
+        assert_eq!(plate.sample(0.1, 0.5).1, 3.0);
+        assert_eq!(plate.sample(1.0, 1.0).1, 9.0);
+        let card = plate.with_depth(PlateDepth::Constant(5.0));
+        assert_eq!(card.sample(0.1, 0.5).1, 5.0);
+    }
+
+    #[test]
+    fn test_fog() {
+        let fog = Fog::new([1.0; 4], 0.5).with_start(2.0);
+        assert_eq!(fog.amount(1.0), 0.0);
+        assert_eq!(fog.amount(f32::INFINITY), 0.0);
+        assert!(fog.amount(4.0) > 0.5 && fog.amount(4.0) < fog.amount(10.0));
+        assert_eq!(fog.apply([0.0; 4], 2.0), [0.0; 4]);
+        // Transparent pixels stay transparent
+        assert_eq!(fog.apply([0.0; 4], 100.0), [0.0; 4]);
+    }
+}
@@ -0,0 +1,540 @@
+//! Real-time playback: a fixed-timestep clock with frame drop/hold policy, and a
+//! `Player` that drives `Director` evaluation from it.
+
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "cache")]
+use crate::cache_bridge::AnimationCache;
+use crate::director::{DirectorState, PlaybackMode};
+use crate::episode::EpisodePackage;
+use crate::export::FrameRange;
+use crate::hot_reload::{diff_episodes, ReloadReport};
+
+/// Anime's standard playback rate.
+pub const DEFAULT_PLAYBACK_FPS: f32 = 24.0;
+
+/// What to do when the presenter falls behind the clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FramePolicy {
+    /// Skip frames to stay on the wall clock (keeps audio in sync).
+    #[default]
+    Drop,
+    /// Show every frame; playback slows down instead.
+    Hold,
+}
+
+/// Result of advancing the clock.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameTick {
+    pub frame: u32,
+    /// Episode time of `frame`.
+    pub time: f32,
+    /// `false` when the previous frame should stay on screen (v-sync between frames).
+    pub new_frame: bool,
+    /// Frames skipped (`Drop`) or delayed (`Hold`) by this tick.
+    pub late_frames: u32,
+}
+
+/// Counters for a playback session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PlaybackStats {
+    pub presented: u64,
+    pub dropped: u64,
+    pub held: u64,
+}
+
+/// Fixed-timestep frame clock. Feed it elapsed wall time (e.g. once per v-sync); it
+/// reports which frame to show.
+#[derive(Debug, Clone)]
+pub struct PlaybackClock {
+    fps: f32,
+    policy: FramePolicy,
+    frame_count: Option<u32>,
+    mode: PlaybackMode,
+    playing: bool,
+    frame: u32,
+    /// Wall time accumulated towards the next frame, in seconds.
+    accumulator: f64,
+    stats: PlaybackStats,
+}
+
+impl PlaybackClock {
+    pub fn new(fps: f32) -> Self {
+        Self {
+            fps: fps.max(1e-3),
+            policy: FramePolicy::default(),
+            frame_count: None,
+            mode: PlaybackMode::Once,
+            playing: true,
+            frame: 0,
+            accumulator: 0.0,
+            stats: PlaybackStats::default(),
+        }
+    }
+
+    /// Set the late-frame policy.
+    pub fn with_policy(mut self, policy: FramePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Stop (or loop) after `frame_count` frames.
+    pub fn with_frame_count(mut self, frame_count: u32) -> Self {
+        self.frame_count = Some(frame_count);
+        self
+    }
+
+    /// Wrap to frame 0 at the end instead of stopping: shorthand for
+    /// `PlaybackMode::LoopEpisode` (or `Once` when `false`).
+    pub fn with_looping(self, looping: bool) -> Self {
+        self.with_mode(if looping {
+            PlaybackMode::LoopEpisode
+        } else {
+            PlaybackMode::Once
+        })
+    }
+
+    /// Any mode but `Once` wraps to frame 0 at the end instead of stopping. A `Player`
+    /// lifts the frame limit for these modes and lets the director wrap time instead.
+    pub fn with_mode(mut self, mode: PlaybackMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    #[inline]
+    pub fn mode(&self) -> PlaybackMode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    #[inline]
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    /// Episode time of the current frame.
+    #[inline]
+    pub fn time(&self) -> f32 {
+        self.frame as f32 / self.fps
+    }
+
+    #[inline]
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    #[inline]
+    pub fn stats(&self) -> PlaybackStats {
+        self.stats
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    /// Pause; the sub-frame phase is kept so resuming doesn't jump.
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Jump to `frame` (clamped to the end) and restart the frame phase.
+    pub fn seek(&mut self, frame: u32) {
+        self.frame = match self.frame_count {
+            Some(count) => frame.min(count.saturating_sub(1)),
+            None => frame,
+        };
+        self.accumulator = 0.0;
+    }
+
+    /// Wall time until the next frame is due (zero if overdue).
+    pub fn time_to_next_frame(&self) -> Duration {
+        let period = 1.0 / self.fps as f64;
+        Duration::from_secs_f64((period - self.accumulator).max(0.0))
+    }
+
+    /// Advance by `elapsed` wall time.
+    pub fn advance(&mut self, elapsed: Duration) -> FrameTick {
+        let mut tick = FrameTick {
+            frame: self.frame,
+            time: self.time(),
+            new_frame: false,
+            late_frames: 0,
+        };
+        if !self.playing {
+            return tick;
+        }
+
+        self.accumulator += elapsed.as_secs_f64();
+        let period = 1.0 / self.fps as f64;
+        let due = (self.accumulator / period).floor() as u32;
+        if due == 0 {
+            return tick;
+        }
+        // Keep the sub-frame phase in both modes so pacing stays v-sync aligned
+        self.accumulator -= due as f64 * period;
+        let step = match self.policy {
+            FramePolicy::Drop => {
+                self.stats.dropped += (due - 1) as u64;
+                due
+            }
+            FramePolicy::Hold => {
+                self.stats.held += (due - 1) as u64;
+                1
+            }
+        };
+        let mut frame = self.frame + step;
+        if let Some(count) = self.frame_count.filter(|&c| frame >= c) {
+            if self.mode != PlaybackMode::Once && count > 0 {
+                frame %= count;
+            } else {
+                frame = count.saturating_sub(1);
+                self.playing = false;
+            }
+        }
+        let new_frame = frame != self.frame;
+        self.frame = frame;
+        if new_frame {
+            self.stats.presented += 1;
+        }
+
+        tick.frame = frame;
+        tick.time = self.time();
+        tick.new_frame = new_frame;
+        tick.late_frames = due - 1;
+        tick
+    }
+}
+
+/// Plays an episode: advances a `PlaybackClock` and evaluates the director for each new
+/// frame (through an `AnimationCache` when one is attached).
+pub struct Player<'a> {
+    episode: Cow<'a, EpisodePackage>,
+    clock: PlaybackClock,
+    /// Length the clock was given; `None` derives it from the episode and follows it
+    /// across reloads.
+    frame_count: Option<u32>,
+    #[cfg(feature = "cache")]
+    cache: Option<AnimationCache>,
+}
+
+impl<'a> Player<'a> {
+    /// Player at `DEFAULT_PLAYBACK_FPS` that stops at the end of the episode.
+    pub fn new(episode: &'a EpisodePackage) -> Self {
+        Self::with_clock(episode, PlaybackClock::new(DEFAULT_PLAYBACK_FPS))
+    }
+
+    /// Player with a custom clock; an unbounded clock is limited to the episode length.
+    /// The clock's mode (e.g. from `with_looping`) becomes the player's.
+    pub fn with_clock(episode: &'a EpisodePackage, clock: PlaybackClock) -> Self {
+        let mut player = Self {
+            episode: Cow::Borrowed(episode),
+            frame_count: clock.frame_count,
+            clock,
+            #[cfg(feature = "cache")]
+            cache: None,
+        };
+        player.fit_clock();
+        player
+    }
+
+    /// Loop or bounce playback with `mode`. Looping modes keep the clock running, even one
+    /// given an explicit length; the director wraps its time on evaluation.
+    pub fn with_mode(mut self, mode: PlaybackMode) -> Self {
+        self.set_mode(mode);
+        self
+    }
+
+    pub fn set_mode(&mut self, mode: PlaybackMode) {
+        self.clock.mode = mode;
+        self.fit_clock();
+    }
+
+    #[inline]
+    pub fn mode(&self) -> PlaybackMode {
+        self.clock.mode
+    }
+
+    /// Limit the clock to its given length (or the episode length), or lift the limit
+    /// while looping.
+    fn fit_clock(&mut self) {
+        self.clock.frame_count = match self.clock.mode {
+            PlaybackMode::Once => {
+                let count = self.frame_count.unwrap_or_else(|| {
+                    FrameRange::whole(&self.episode, self.clock.fps).frame_count()
+                });
+                self.clock.frame = self.clock.frame.min(count.saturating_sub(1));
+                Some(count)
+            }
+            _ => None,
+        };
+    }
+
+    /// Route evaluation through `cache` (keyed by frame number).
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, cache: AnimationCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn cache(&self) -> Option<&AnimationCache> {
+        self.cache.as_ref()
+    }
+
+    pub fn episode(&self) -> &EpisodePackage {
+        &self.episode
+    }
+
+    /// Swap in a reloaded episode without moving the playhead (clamped if the episode got
+    /// shorter). Attached cache frames survive unless the reload touched their time.
+    pub fn reload(&mut self, episode: EpisodePackage) -> crate::error::Result<ReloadReport> {
+        let report = diff_episodes(&self.episode, &episode)?;
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &mut self.cache {
+            report.invalidate(cache);
+        }
+        self.episode = Cow::Owned(episode);
+        self.fit_clock();
+        Ok(report)
+    }
+
+    pub fn clock(&self) -> &PlaybackClock {
+        &self.clock
+    }
+
+    pub fn clock_mut(&mut self) -> &mut PlaybackClock {
+        &mut self.clock
+    }
+
+    /// Evaluate the director at playhead `frame`, wrapped by the playback mode.
+    pub fn evaluate(&mut self, frame: u32) -> DirectorState {
+        let fps = self.clock.fps;
+        let time = self
+            .episode
+            .director
+            .playback_time(self.clock.mode, frame as f32 / fps);
+        #[cfg(feature = "cache")]
+        if let Some(cache) = &mut self.cache {
+            // Keyed by the wrapped frame so every pass of a loop hits the same entries
+            return cache.get_or_evaluate(
+                (time * fps).round() as u32,
+                time,
+                &self.episode.director,
+                &self.episode.scene_graph,
+            );
+        }
+        self.episode
+            .director
+            .evaluate(&self.episode.scene_graph, time)
+    }
+
+    /// Advance by `elapsed`; returns the state to present if a new frame is due.
+    pub fn tick(&mut self, elapsed: Duration) -> Option<(FrameTick, DirectorState)> {
+        let tick = self.clock.advance(elapsed);
+        tick.new_frame.then(|| (tick, self.evaluate(tick.frame)))
+    }
+
+    /// Blocking playback loop: presents the first frame, then sleeps until each next
+    /// frame is due. Stops at the end (unless looping) or when `present` returns `false`.
+    pub fn run<F>(&mut self, mut present: F) -> PlaybackStats
+    where
+        F: FnMut(&FrameTick, &DirectorState) -> bool,
+    {
+        let first = FrameTick {
+            frame: self.clock.frame,
+            time: self.clock.time(),
+            new_frame: true,
+            late_frames: 0,
+        };
+        let state = self.evaluate(first.frame);
+        self.clock.stats.presented += 1;
+        if !present(&first, &state) {
+            return self.clock.stats;
+        }
+
+        let mut last = Instant::now();
+        while self.clock.is_playing() {
+            std::thread::sleep(self.clock.time_to_next_frame());
+            let now = Instant::now();
+            let elapsed = now - last;
+            last = now;
+            if let Some((tick, state)) = self.tick(elapsed) {
+                if !present(&tick, &state) {
+                    break;
+                }
+            }
+        }
+        self.clock.stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_nanos(41_666_667);
+
+    #[test]
+    fn test_drop_and_hold_policies() {
+        let mut drop = PlaybackClock::new(24.0);
+        let tick = drop.advance(FRAME * 3 + FRAME / 2);
+        assert_eq!((tick.frame, tick.late_frames), (3, 2));
+        assert_eq!(drop.stats().dropped, 2);
+
+        let mut hold = PlaybackClock::new(24.0).with_policy(FramePolicy::Hold);
+        let tick = hold.advance(FRAME * 3 + FRAME / 2);
+        assert_eq!((tick.frame, tick.late_frames), (1, 2));
+        assert_eq!(hold.stats().held, 2);
+    }
+
+    #[test]
+    fn test_subframe_ticks_hold_previous_frame() {
+        // 50 Hz v-sync driving 24 fps: frames land on every 2nd or 3rd refresh
+        let mut clock = PlaybackClock::new(24.0);
+        let vsync = Duration::from_millis(20);
+        let new_frames: Vec<bool> = (0..7).map(|_| clock.advance(vsync).new_frame).collect();
+        assert_eq!(
+            new_frames,
+            vec![false, false, true, false, true, false, true]
+        );
+        assert_eq!(clock.frame(), 3);
+        assert!(clock.time_to_next_frame() < FRAME);
+    }
+
+    #[test]
+    fn test_end_and_loop() {
+        let mut once = PlaybackClock::new(24.0).with_frame_count(4);
+        once.advance(FRAME * 10);
+        assert_eq!(once.frame(), 3);
+        assert!(!once.is_playing());
+
+        let mut looped = PlaybackClock::new(24.0)
+            .with_frame_count(4)
+            .with_looping(true);
+        assert_eq!(looped.advance(FRAME * 5).frame, 1);
+        assert!(looped.is_playing());
+    }
+
+    #[test]
+    fn test_player_tick() {
+        use crate::director::{Cut, Director};
+        use crate::episode::EpisodeMetadata;
+        use crate::npr::AnimeShading;
+        use crate::scene::SceneGraph;
+
+        let mut dir = Director::new("Play");
+        dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        dir.add_cut(Cut::new("c2", 1.0, 2.0));
+        let episode = EpisodePackage::new(
+            EpisodeMetadata::new("Play", 1, 2.0),
+            SceneGraph::new(),
+            dir,
+            AnimeShading::default(),
+        );
+        let mut player = Player::new(&episode);
+        assert!(player.tick(FRAME / 2).is_none());
+        let (tick, state) = player.tick(FRAME * 30).unwrap();
+        assert_eq!(tick.frame, 30);
+        assert_eq!(state.active_cut, Some(crate::director::CutId(1)));
+
+        #[cfg(feature = "cache")]
+        {
+            let mut player = Player::new(&episode).with_cache(AnimationCache::new(8));
+            player.evaluate(3);
+            player.evaluate(3);
+            assert_eq!(player.cache().unwrap().hit_rate(), 0.5);
+        }
+    }
+
+    #[test]
+    fn test_player_loops_scene() {
+        use crate::director::{Cut, Director, Scene};
+        use crate::episode::EpisodeMetadata;
+        use crate::npr::AnimeShading;
+        use crate::scene::SceneGraph;
+
+        let mut dir = Director::new("Play");
+        dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        let c2 = dir.add_cut(Cut::new("c2", 1.0, 2.0));
+        let mut scene = Scene::new("s2");
+        scene.cuts.push(c2);
+        dir.add_scene(scene);
+        let episode = EpisodePackage::new(
+            EpisodeMetadata::new("Play", 1, 2.0),
+            SceneGraph::new(),
+            dir,
+            AnimeShading::default(),
+        );
+        let mut player = Player::new(&episode).with_mode(PlaybackMode::LoopScene(0));
+        // Runs past the episode end, showing the scene again and again
+        let (_, state) = player.tick(FRAME * 60).unwrap();
+        assert!(player.clock().is_playing());
+        assert!((state.time - 1.5).abs() < 1e-4);
+        assert_eq!(state.active_cut, Some(c2));
+
+        player.set_mode(PlaybackMode::Once);
+        assert_eq!(player.clock().frame(), 47);
+
+        // A looping clock of explicit length loops through the player's mode
+        let clock = PlaybackClock::new(24.0)
+            .with_frame_count(12)
+            .with_looping(true);
+        let mut player = Player::with_clock(&episode, clock);
+        assert_eq!(player.mode(), PlaybackMode::LoopEpisode);
+        let (tick, state) = player.tick(FRAME * 60).unwrap();
+        assert_eq!(tick.frame, 60);
+        assert!((state.time - 0.5).abs() < 1e-4);
+        player.set_mode(PlaybackMode::Once);
+        assert_eq!(player.clock().frame(), 11);
+    }
+
+    #[test]
+    fn test_player_reload_keeps_playhead() {
+        use crate::director::{Cut, CutId, Director};
+        use crate::episode::EpisodeMetadata;
+        use crate::npr::AnimeShading;
+        use crate::scene::SceneGraph;
+
+        let mut dir = Director::new("Play");
+        dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        dir.add_cut(Cut::new("c2", 1.0, 2.0));
+        let episode = EpisodePackage::new(
+            EpisodeMetadata::new("Play", 1, 2.0),
+            SceneGraph::new(),
+            dir,
+            AnimeShading::default(),
+        );
+        #[cfg(not(feature = "cache"))]
+        let mut player = Player::new(&episode);
+        #[cfg(feature = "cache")]
+        let mut player = Player::new(&episode).with_cache(AnimationCache::new(64));
+        player.tick(FRAME * 30);
+        player.evaluate(5);
+
+        let mut edited = episode.clone();
+        edited.director.get_cut_mut(CutId(1)).unwrap().name = "c2 retake".into();
+        let report = player.reload(edited).unwrap();
+        assert_eq!(report.dirty_ranges, vec![(1.0, 2.0)]);
+        assert_eq!(player.clock().frame(), 30);
+        assert_eq!(
+            player.episode().director.get_cut(CutId(1)).unwrap().name,
+            "c2 retake"
+        );
+        #[cfg(feature = "cache")]
+        {
+            let cache = player.cache().unwrap();
+            assert!(cache.contains(5) && !cache.contains(30));
+        }
+
+        let mut shorter = player.episode().clone();
+        shorter.director.remove_cut(CutId(1));
+        shorter.metadata.duration_seconds = 1.0;
+        assert!(player.reload(shorter).unwrap().full);
+        assert_eq!(player.clock().frame(), 23);
+    }
+}
@@ -0,0 +1,202 @@
+//! Pose library: named snapshots of an actor's track values.
+//!
+//! A [`Pose`] holds the value of every track of an actor's timeline at one instant.
+//! Poses are captured from one moment, stored by name in a [`PoseLibrary`], and keyed
+//! back onto any actor at any time, optionally mirrored left to right. Layers and
+//! crossfades are not part of a pose; they keep playing over the keyed values.
+
+use std::collections::BTreeMap;
+
+use alice_sdf::animation::{Timeline, Track};
+use serde::{Deserialize, Serialize};
+
+use crate::keys::set_key;
+use crate::mirror::Mirror;
+use crate::scene::Actor;
+
+/// Track values by track name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pose {
+    pub name: String,
+    pub values: BTreeMap<String, f32>,
+}
+
+impl Pose {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            values: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_value(mut self, track: impl Into<String>, value: f32) -> Self {
+        self.values.insert(track.into(), value);
+        self
+    }
+
+    /// Every track of `actor`'s timeline at scene `time` (through its cycle).
+    pub fn capture(name: impl Into<String>, actor: &Actor, time: f32) -> Self {
+        let mut pose = Self::new(name);
+        if let Some(timeline) = &actor.timeline {
+            let local = actor.local_time(time);
+            for track in &timeline.tracks {
+                if !track.keyframes.is_empty() {
+                    pose.values
+                        .insert(track.name.clone(), track.evaluate(local));
+                }
+            }
+        }
+        pose
+    }
+
+    /// Left and right swapped across X: side-named tracks trade values and sideways
+    /// channels (`*.x` translations, `rotate.y`, `rotate.z`) flip sign.
+    pub fn mirrored(&self) -> Self {
+        self.mirrored_with(&Mirror::default())
+    }
+
+    /// Mirrored across any plane.
+    pub fn mirrored_with(&self, mirror: &Mirror) -> Self {
+        Self {
+            name: format!("{} (mirrored)", self.name),
+            values: self
+                .values
+                .iter()
+                .map(|(name, &value)| mirror.channel(name, value))
+                .collect(),
+        }
+    }
+
+    /// Key every value onto `actor`'s timeline at `time`, overwriting keys already there
+    /// and creating the timeline and tracks as needed.
+    pub fn apply(&self, actor: &mut Actor, time: f32) {
+        let name = actor.name.clone();
+        let timeline = actor.timeline.get_or_insert_with(|| Timeline::new(&name));
+        for (name, &value) in &self.values {
+            let index = match timeline.tracks.iter().position(|t| t.name == *name) {
+                Some(index) => index,
+                None => {
+                    timeline.add_track(Track::new(name));
+                    timeline.tracks.len() - 1
+                }
+            };
+            set_key(&mut timeline.tracks[index], time, value);
+        }
+    }
+}
+
+/// Poses by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PoseLibrary {
+    poses: BTreeMap<String, Pose>,
+}
+
+impl PoseLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `pose` under its name, returning the pose it replaced.
+    pub fn insert(&mut self, pose: Pose) -> Option<Pose> {
+        self.poses.insert(pose.name.clone(), pose)
+    }
+
+    /// Capture `actor` at `time` and store it as `name`.
+    pub fn save(&mut self, name: impl Into<String>, actor: &Actor, time: f32) -> &Pose {
+        let pose = Pose::capture(name, actor, time);
+        let name = pose.name.clone();
+        self.poses.insert(name.clone(), pose);
+        &self.poses[&name]
+    }
+
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&Pose> {
+        self.poses.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Pose> {
+        self.poses.remove(name)
+    }
+
+    /// Pose names in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.poses.keys().map(String::as_str)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.poses.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.poses.is_empty()
+    }
+
+    /// Key pose `name` onto `actor` at `time`, mirrored if asked. Returns false if there
+    /// is no such pose.
+    pub fn apply(&self, name: &str, actor: &mut Actor, time: f32, mirrored: bool) -> bool {
+        match self.poses.get(name) {
+            Some(pose) if mirrored => pose.mirrored().apply(actor, time),
+            Some(pose) => pose.apply(actor, time),
+            None => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::animation::Keyframe;
+    use alice_sdf::SdfNode;
+
+    fn posed_actor() -> Actor {
+        let mut tl = Timeline::new("fight");
+        for (name, value) in [
+            ("arm.l.swing", 0.8),
+            ("arm.r.swing", -0.2),
+            ("translate.x", 1.5),
+            ("rotate.y", 0.3),
+            ("hip.y", 0.9),
+        ] {
+            let mut track = Track::new(name);
+            track.add_keyframe(Keyframe::new(0.0, 0.0));
+            track.add_keyframe(Keyframe::new(1.0, value));
+            tl.add_track(track);
+        }
+        Actor::new("hero", SdfNode::sphere(1.0)).with_timeline(tl)
+    }
+
+    #[test]
+    fn test_save_and_key_pose() {
+        let hero = posed_actor();
+        let mut library = PoseLibrary::new();
+        library.save("guard", &hero, 1.0);
+        assert_eq!(library.get("guard").unwrap().values["arm.l.swing"], 0.8);
+
+        let mut rival = Actor::new("rival", SdfNode::sphere(1.0));
+        assert!(library.apply("guard", &mut rival, 2.0, false));
+        assert!(!library.apply("missing", &mut rival, 2.0, false));
+        let tl = rival.timeline.as_ref().unwrap();
+        assert_eq!(tl.tracks.len(), 5);
+        assert_eq!(tl.get_value("hip.y", 2.0), Some(0.9));
+        // Keying again at the same time overwrites instead of stacking keys
+        library.apply("guard", &mut rival, 2.0, false);
+        assert_eq!(
+            rival.timeline.as_ref().unwrap().tracks[0].keyframes.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_mirrored_pose() {
+        let pose = Pose::capture("guard", &posed_actor(), 1.0).mirrored();
+        assert_eq!(pose.values["arm.r.swing"], 0.8);
+        assert_eq!(pose.values["arm.l.swing"], -0.2);
+        assert_eq!(pose.values["translate.x"], -1.5);
+        assert_eq!(pose.values["rotate.y"], -0.3);
+        assert_eq!(pose.values["hip.y"], 0.9);
+        assert_eq!(pose.name, "guard (mirrored)");
+    }
+}
@@ -0,0 +1,383 @@
+//! Frame profiling: per-stage timings and cache hit rates collected into a queryable
+//! profile, with an optional per-frame callback for external telemetry.
+
+use std::time::{Duration, Instant};
+
+use alice_sdf::SdfNode;
+
+#[cfg(feature = "cache")]
+use crate::cache_bridge::AnimationCache;
+use crate::camera::CameraState;
+use crate::director::DirectorState;
+use crate::episode::EpisodePackage;
+use crate::framebuffer::Framebuffer;
+use crate::overlay::composite_overlays;
+use crate::render::{Renderer, DEFAULT_TILE_SIZE};
+
+/// Instrumented part of producing a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    /// `Director` binary search for the active cut.
+    CutLookup,
+    /// Scene graph evaluation into the frame SDF.
+    SceneEval,
+    /// Camera track evaluation in cut-local time.
+    CameraEval,
+    /// Separate shading / compositing passes (overlays). The built-in renderer shades while
+    /// it marches, so [`Profiler::render`] books that time under `Render`.
+    Shading,
+    /// Ray marching and tile assembly.
+    Render,
+}
+
+impl Stage {
+    pub const COUNT: usize = 5;
+    pub const ALL: [Stage; Stage::COUNT] = [
+        Stage::CutLookup,
+        Stage::SceneEval,
+        Stage::CameraEval,
+        Stage::Shading,
+        Stage::Render,
+    ];
+
+    #[inline]
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Timings of one frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameProfile {
+    pub frame: u32,
+    pub time: f32,
+    pub stages: [Duration; Stage::COUNT],
+    /// Whether evaluation was served from an `AnimationCache`; `None` when uncached.
+    pub cache_hit: Option<bool>,
+}
+
+impl FrameProfile {
+    #[inline]
+    pub fn stage(&self, stage: Stage) -> Duration {
+        self.stages[stage.index()]
+    }
+
+    pub fn total(&self) -> Duration {
+        self.stages.iter().sum()
+    }
+}
+
+/// Accumulates stage timings for a frame in progress; hand it back to
+/// [`Profiler::finish`].
+#[derive(Debug)]
+pub struct FrameTimer {
+    profile: FrameProfile,
+}
+
+impl FrameTimer {
+    /// Run `f`, adding its wall time to `stage`.
+    pub fn time<T>(&mut self, stage: Stage, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let out = f();
+        self.profile.stages[stage.index()] += start.elapsed();
+        out
+    }
+
+    pub fn set_cache_hit(&mut self, hit: bool) {
+        self.profile.cache_hit = Some(hit);
+    }
+}
+
+/// Aggregate of one stage over all recorded frames.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StageSummary {
+    pub mean: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+/// Per-frame telemetry hook.
+pub type FrameCallback = Box<dyn FnMut(&FrameProfile) + Send>;
+
+/// Collects `FrameProfile`s and answers questions about them.
+#[derive(Default)]
+pub struct Profiler {
+    frames: Vec<FrameProfile>,
+    on_frame: Option<FrameCallback>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `f` with every finished frame (e.g. to forward to a telemetry backend).
+    pub fn with_callback(mut self, f: impl FnMut(&FrameProfile) + Send + 'static) -> Self {
+        self.on_frame = Some(Box::new(f));
+        self
+    }
+
+    /// Start timing `frame`.
+    pub fn begin(&self, frame: u32, time: f32) -> FrameTimer {
+        FrameTimer {
+            profile: FrameProfile {
+                frame,
+                time,
+                stages: [Duration::ZERO; Stage::COUNT],
+                cache_hit: None,
+            },
+        }
+    }
+
+    /// Record a finished frame.
+    pub fn finish(&mut self, timer: FrameTimer) {
+        self.record(timer.profile);
+    }
+
+    /// Record a profile measured elsewhere.
+    pub fn record(&mut self, profile: FrameProfile) {
+        if let Some(f) = &mut self.on_frame {
+            f(&profile);
+        }
+        self.frames.push(profile);
+    }
+
+    /// Instrumented `Director::evaluate` plus scene evaluation; returns the state and the
+    /// frame SDF.
+    pub fn evaluate(
+        &mut self,
+        episode: &EpisodePackage,
+        frame: u32,
+        fps: f32,
+    ) -> (DirectorState, SdfNode) {
+        let time = frame as f32 / fps;
+        let mut timer = self.begin(frame, time);
+        let (state, sdf) = evaluate_timed(&mut timer, episode, time);
+        self.finish(timer);
+        (state, sdf)
+    }
+
+    /// Instrumented evaluation through `cache`; the frame SDF is evaluated (and timed) only
+    /// on a miss.
+    #[cfg(feature = "cache")]
+    pub fn evaluate_cached(
+        &mut self,
+        cache: &mut AnimationCache,
+        episode: &EpisodePackage,
+        frame: u32,
+        fps: f32,
+    ) -> DirectorState {
+        let time = frame as f32 / fps;
+        let mut timer = self.begin(frame, time);
+        let hit = cache.contains(frame);
+        timer.set_cache_hit(hit);
+        let state = if hit {
+            timer.time(Stage::CutLookup, || {
+                cache.get_or_evaluate(frame, time, &episode.director, &episode.scene_graph)
+            })
+        } else {
+            let (state, _) = evaluate_timed(&mut timer, episode, time);
+            cache.get_or_evaluate(frame, time, &episode.director, &episode.scene_graph);
+            state
+        };
+        self.finish(timer);
+        state
+    }
+
+    /// Instrumented `Renderer::render_episode`.
+    pub fn render(
+        &mut self,
+        renderer: &Renderer,
+        episode: &EpisodePackage,
+        frame: u32,
+        fps: f32,
+    ) -> Framebuffer {
+        let time = frame as f32 / fps;
+        let mut timer = self.begin(frame, time);
+        let (state, sdf) = evaluate_timed(&mut timer, episode, time);
+        let objects = if renderer.settings().aovs.object_id {
+            timer.time(Stage::SceneEval, || {
+                episode.director.evaluate_actors(&episode.scene_graph, time)
+            })
+        } else {
+            Vec::new()
+        };
+        let mut fb = timer.time(Stage::Render, || {
+            renderer.render_tiled(
+                &sdf,
+                &objects,
+                &state.camera_state,
+                &episode.shading,
+                DEFAULT_TILE_SIZE,
+            )
+        });
+        timer.time(Stage::Shading, || {
+            composite_overlays(&mut fb, &episode.overlays, time)
+        });
+        self.finish(timer);
+        fb
+    }
+
+    pub fn frames(&self) -> &[FrameProfile] {
+        &self.frames
+    }
+
+    /// Profile of `frame` (the latest one if it was recorded more than once).
+    pub fn frame(&self, frame: u32) -> Option<&FrameProfile> {
+        self.frames.iter().rev().find(|p| p.frame == frame)
+    }
+
+    /// Mean / 95th percentile / max of `stage`.
+    pub fn summary(&self, stage: Stage) -> StageSummary {
+        let mut times: Vec<Duration> = self.frames.iter().map(|p| p.stage(stage)).collect();
+        if times.is_empty() {
+            return StageSummary::default();
+        }
+        times.sort_unstable();
+        let p95 = times[((times.len() - 1) as f32 * 0.95).round() as usize];
+        StageSummary {
+            mean: times.iter().sum::<Duration>() / times.len() as u32,
+            p95,
+            max: times[times.len() - 1],
+        }
+    }
+
+    /// The `n` frames with the highest total time, slowest first.
+    pub fn slowest(&self, n: usize) -> Vec<&FrameProfile> {
+        let mut frames: Vec<&FrameProfile> = self.frames.iter().collect();
+        frames.sort_by_key(|p| std::cmp::Reverse(p.total()));
+        frames.truncate(n);
+        frames
+    }
+
+    /// Frames whose total exceeds `budget` (e.g. 1/24 s for real-time playback).
+    pub fn over_budget(&self, budget: Duration) -> impl Iterator<Item = &FrameProfile> {
+        self.frames.iter().filter(move |p| p.total() > budget)
+    }
+
+    /// Fraction of cached evaluations that hit; `None` if no frame went through a cache.
+    pub fn cache_hit_rate(&self) -> Option<f32> {
+        let (hits, total) = self
+            .frames
+            .iter()
+            .filter_map(|p| p.cache_hit)
+            .fold((0u32, 0u32), |(h, t), hit| (h + hit as u32, t + 1));
+        (total > 0).then(|| hits as f32 / total as f32)
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+fn evaluate_timed(
+    timer: &mut FrameTimer,
+    episode: &EpisodePackage,
+    time: f32,
+) -> (DirectorState, SdfNode) {
+    let active = timer.time(Stage::CutLookup, || episode.director.find_active_cut(time));
+    let (active_cut, camera_state) = match active {
+        Some((id, cut)) => {
+            let local = time - cut.start_time;
+            (
+                Some(id),
+                timer.time(Stage::CameraEval, || {
+                    episode.director.camera_at(id, local).unwrap_or_default()
+                }),
+            )
+        }
+        None => (None, CameraState::default()),
+    };
+    let sdf = timer.time(Stage::SceneEval, || {
+        episode.director.evaluate_scene(&episode.scene_graph, time)
+    });
+    (
+        DirectorState {
+            time,
+            active_cut,
+            camera_state,
+        },
+        sdf,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::EpisodeMetadata;
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use std::sync::{Arc, Mutex};
+
+    fn episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("ball", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Prof");
+        dir.add_cut(Cut::new("a", 0.0, 1.0));
+        EpisodePackage::new(
+            EpisodeMetadata::new("Prof", 1, 1.0),
+            sg,
+            dir,
+            AnimeShading::default(),
+        )
+    }
+
+    #[test]
+    fn test_evaluate_matches_director_and_reports_frames() {
+        let ep = episode();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let mut profiler =
+            Profiler::new().with_callback(move |p| sink.lock().unwrap().push(p.frame));
+        for frame in 0..4 {
+            let (state, _) = profiler.evaluate(&ep, frame, 24.0);
+            let expected = ep.director.evaluate(&ep.scene_graph, frame as f32 / 24.0);
+            assert_eq!(state.active_cut, expected.active_cut);
+        }
+        assert_eq!(*seen.lock().unwrap(), vec![0, 1, 2, 3]);
+        assert_eq!(profiler.frames().len(), 4);
+        assert_eq!(
+            profiler.frame(2).unwrap().stage(Stage::Render),
+            Duration::ZERO
+        );
+        assert!(profiler.cache_hit_rate().is_none());
+    }
+
+    #[test]
+    fn test_queries_over_recorded_frames() {
+        let mut profiler = Profiler::new();
+        for (frame, ms) in [(0, 10), (1, 50), (2, 20)] {
+            let mut stages = [Duration::ZERO; Stage::COUNT];
+            stages[Stage::Render.index()] = Duration::from_millis(ms);
+            profiler.record(FrameProfile {
+                frame,
+                time: frame as f32 / 24.0,
+                stages,
+                cache_hit: Some(frame != 1),
+            });
+        }
+        let render = profiler.summary(Stage::Render);
+        assert_eq!(render.max, Duration::from_millis(50));
+        assert_eq!(render.mean, Duration::from_millis(80) / 3);
+        assert_eq!(profiler.slowest(1)[0].frame, 1);
+        let late: Vec<u32> = profiler
+            .over_budget(Duration::from_millis(15))
+            .map(|p| p.frame)
+            .collect();
+        assert_eq!(late, vec![1, 2]);
+        assert!((profiler.cache_hit_rate().unwrap() - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_render_books_render_stage() {
+        let ep = episode();
+        let renderer = Renderer::new(8, 8);
+        let mut profiler = Profiler::new();
+        let fb = profiler.render(&renderer, &ep, 0, 24.0);
+        assert_eq!((fb.width, fb.height), (8, 8));
+        let p = &profiler.frames()[0];
+        assert!(p.stage(Stage::Render) > Duration::ZERO);
+        assert_eq!(p.total(), Stage::ALL.iter().map(|s| p.stage(*s)).sum());
+    }
+}
@@ -0,0 +1,140 @@
+//! Progressive preview: coarse passes first, refined until full resolution.
+
+use alice_sdf::SdfNode;
+
+use crate::camera::CameraState;
+use crate::episode::EpisodePackage;
+use crate::framebuffer::Framebuffer;
+use crate::npr::AnimeShading;
+use crate::render::Renderer;
+
+/// Downscale factors rendered in order; the last pass is full resolution.
+pub const PROGRESSIVE_SCALES: [u32; 4] = [8, 4, 2, 1];
+
+/// One intermediate result handed to the progress callback.
+#[derive(Debug)]
+pub struct ProgressivePass<'a> {
+    /// Zero-based pass index.
+    pub pass: usize,
+    pub pass_count: usize,
+    /// Downscale factor this pass was rendered at.
+    pub scale: u32,
+    /// Full-resolution frame (coarse passes are upscaled with nearest-neighbour).
+    pub framebuffer: &'a Framebuffer,
+}
+
+impl ProgressivePass<'_> {
+    #[inline]
+    pub fn is_final(&self) -> bool {
+        self.scale == 1
+    }
+}
+
+/// Nearest-neighbour upscale of `src` to `width` x `height`.
+fn upscale_nearest(src: &Framebuffer, width: u32, height: u32) -> Framebuffer {
+    let mut out = Framebuffer::new(width, height);
+    out.premultiplied = src.premultiplied;
+    for y in 0..height {
+        let sy = (y as u64 * src.height as u64 / height as u64) as u32;
+        for x in 0..width {
+            let sx = (x as u64 * src.width as u64 / width as u64) as u32;
+            out.set_pixel(x, y, src.pixel(sx, sy));
+        }
+    }
+    out
+}
+
+impl Renderer {
+    /// Render in passes at 1/8, 1/4, 1/2 and full resolution.
+    ///
+    /// `on_pass` sees each pass as a full-size frame and returns `false` to stop early
+    /// (e.g. the user scrubbed to another frame); the last completed pass is returned.
+    /// Coarse passes skip supersampling and shadow rays.
+    pub fn render_progressive(
+        &self,
+        sdf: &SdfNode,
+        camera: &CameraState,
+        shading: &AnimeShading,
+        mut on_pass: impl FnMut(&ProgressivePass) -> bool,
+    ) -> Framebuffer {
+        let (width, height) = (self.width(), self.height());
+        let mut latest = Framebuffer::new(width, height);
+        for (pass, &scale) in PROGRESSIVE_SCALES.iter().enumerate() {
+            latest = if scale == 1 {
+                self.render(sdf, camera, shading)
+            } else {
+                let mut coarse = self.clone();
+                coarse.settings = coarse
+                    .settings
+                    .with_resolution(width.div_ceil(scale), height.div_ceil(scale))
+                    .with_supersampling(1)
+                    .with_shadow_rays(false)
+                    .with_crop(self.settings.crop.map(|c| c.scaled_down(scale)));
+                upscale_nearest(&coarse.render(sdf, camera, shading), width, height)
+            };
+            let keep_going = on_pass(&ProgressivePass {
+                pass,
+                pass_count: PROGRESSIVE_SCALES.len(),
+                scale,
+                framebuffer: &latest,
+            });
+            if !keep_going {
+                break;
+            }
+        }
+        latest
+    }
+
+    /// Progressive render of an episode at `time`.
+    pub fn render_episode_progressive(
+        &self,
+        episode: &EpisodePackage,
+        time: f32,
+        on_pass: impl FnMut(&ProgressivePass) -> bool,
+    ) -> Framebuffer {
+        let state = episode.director.evaluate(&episode.scene_graph, time);
+        let sdf = episode.director.evaluate_scene(&episode.scene_graph, time);
+        self.render_progressive(&sdf, &state.camera_state, &episode.shading, on_pass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progressive_passes() {
+        let renderer = Renderer::new(40, 24);
+        let (sdf, camera, shading) = (
+            SdfNode::sphere(1.0),
+            CameraState::default(),
+            AnimeShading::default(),
+        );
+        let mut scales = Vec::new();
+        let last = renderer.render_progressive(&sdf, &camera, &shading, |p| {
+            assert_eq!((p.framebuffer.width, p.framebuffer.height), (40, 24));
+            scales.push(p.scale);
+            true
+        });
+        assert_eq!(scales, vec![8, 4, 2, 1]);
+        assert_eq!(last, renderer.render(&sdf, &camera, &shading));
+    }
+
+    #[test]
+    fn test_progressive_cancel() {
+        let renderer = Renderer::new(16, 16);
+        let mut passes = 0;
+        let fb = renderer.render_progressive(
+            &SdfNode::sphere(1.0),
+            &CameraState::default(),
+            &AnimeShading::default(),
+            |p| {
+                passes += 1;
+                p.pass < 1
+            },
+        );
+        assert_eq!(passes, 2);
+        // Quarter-resolution pass: 4x4 blocks share one sample
+        assert_eq!(fb.pixel(0, 0), fb.pixel(3, 3));
+    }
+}
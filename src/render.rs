@@ -0,0 +1,1148 @@
+//! CPU sphere-tracing renderer with anime shading (cel steps, rim light, SDF outlines).
+
+use alice_sdf::SdfNode;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::CameraState;
+use crate::episode::EpisodePackage;
+use crate::framebuffer::Framebuffer;
+use crate::npr::AnimeShading;
+use crate::overlay::composite_overlays;
+use crate::plate::{BackgroundPlate, Fog};
+use crate::scene::ActorId;
+use crate::units::Units;
+
+/// Central-difference step for normals.
+const NORMAL_EPSILON: f32 = 1e-3;
+/// Sample spacing along the normal for ambient occlusion.
+const AO_STEP: f32 = 0.05;
+/// Hits this close to edge-on count as silhouette edges for adaptive AA.
+const GRAZING_COS: f32 = 0.2;
+
+/// AOV plane names written to the framebuffer.
+pub const AOV_DEPTH: &str = "depth";
+pub const AOV_NORMAL: &str = "normal";
+pub const AOV_OBJECT_ID: &str = "object_id";
+pub const AOV_OUTLINE: &str = "outline";
+pub const AOV_CEL_STEP: &str = "cel_step";
+
+/// Which auxiliary buffers to emit alongside color. AOVs are point-sampled at the
+/// pixel center even when supersampling.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AovSelection {
+    /// Ray distance to the surface (`inf` on misses).
+    pub depth: bool,
+    /// World-space normal (zero on misses).
+    pub normal: bool,
+    /// `ActorId + 1` of the nearest actor (0 on misses).
+    pub object_id: bool,
+    /// Outline coverage (0..1).
+    pub outline: bool,
+    /// Cel band index (`-1` on misses).
+    pub cel_step: bool,
+}
+
+impl AovSelection {
+    /// Every AOV.
+    pub fn all() -> Self {
+        Self {
+            depth: true,
+            normal: true,
+            object_id: true,
+            outline: true,
+            cel_step: true,
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Where anti-aliasing samples are spent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AntiAliasing {
+    /// `supersampling`² samples in every pixel.
+    #[default]
+    Supersample,
+    /// MSAA-style: one sample per pixel, `supersampling`² only where that sample sits on
+    /// a silhouette, outline or cel boundary.
+    Adaptive,
+    /// One sample per pixel with SDF-derived coverage: silhouettes and cel boundaries are
+    /// filtered over the pixel footprint.
+    EdgeAware,
+}
+
+/// Sub-rectangle of the frame to render, in full-frame pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CropRegion {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRegion {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// The part of the region inside a `width` x `height` frame.
+    pub fn clamp_to(&self, width: u32, height: u32) -> Self {
+        let x = self.x.min(width);
+        let y = self.y.min(height);
+        Self::new(x, y, self.width.min(width - x), self.height.min(height - y))
+    }
+
+    /// Region covering the same area in a frame downscaled by `scale` (rounded outwards).
+    pub fn scaled_down(&self, scale: u32) -> Self {
+        let scale = scale.max(1);
+        let (x, y) = (self.x / scale, self.y / scale);
+        Self::new(
+            x,
+            y,
+            (self.x + self.width).div_ceil(scale) - x,
+            (self.y + self.height).div_ceil(scale) - y,
+        )
+    }
+}
+
+/// Everything the shader computed for one ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadeSample {
+    pub color: [f32; 4],
+    pub depth: f32,
+    pub normal: Vec3,
+    pub object_id: f32,
+    pub outline: f32,
+    pub cel_step: f32,
+}
+
+/// Render quality parameters shared by all backends; stored per episode so
+/// preview and final renders are reproducible.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RenderSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Maximum sphere-tracing steps per ray.
+    pub max_steps: u32,
+    /// Distance below which a ray counts as a hit.
+    pub hit_epsilon: f32,
+    /// Rays travelling further than this are misses.
+    pub max_distance: f32,
+    /// Samples per pixel along each axis (1 = off, 2 = 4 samples, ...).
+    pub supersampling: u32,
+    /// Trace a ray towards the key light to cast hard shadows.
+    pub shadow_rays: bool,
+    pub anti_aliasing: AntiAliasing,
+    /// Misses get zero alpha and color is premultiplied, for compositing character
+    /// layers downstream.
+    pub transparent: bool,
+    /// Render only this part of the frame; the rest stays empty.
+    pub crop: Option<CropRegion>,
+    /// Auxiliary buffers to emit.
+    pub aovs: AovSelection,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            max_steps: 128,
+            hit_epsilon: 1e-3,
+            max_distance: 100.0,
+            supersampling: 1,
+            shadow_rays: false,
+            anti_aliasing: AntiAliasing::Supersample,
+            transparent: false,
+            crop: None,
+            aovs: AovSelection::default(),
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Fast settings for scrubbing and layout checks.
+    pub fn preview() -> Self {
+        Self {
+            width: 640,
+            height: 360,
+            max_steps: 64,
+            hit_epsilon: 2e-3,
+            max_distance: 50.0,
+            supersampling: 1,
+            shadow_rays: false,
+            anti_aliasing: AntiAliasing::EdgeAware,
+            transparent: false,
+            crop: None,
+            aovs: AovSelection::default(),
+        }
+    }
+
+    /// Full-quality 1080p settings for final output.
+    pub fn production() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+            max_steps: 256,
+            hit_epsilon: 5e-4,
+            max_distance: 200.0,
+            supersampling: 2,
+            shadow_rays: true,
+            anti_aliasing: AntiAliasing::Supersample,
+            transparent: false,
+            crop: None,
+            aovs: AovSelection::default(),
+        }
+    }
+
+    /// Set output resolution.
+    pub fn with_resolution(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Set samples per pixel axis.
+    pub fn with_supersampling(mut self, samples: u32) -> Self {
+        self.supersampling = samples.max(1);
+        self
+    }
+
+    /// Enable or disable shadow rays.
+    pub fn with_shadow_rays(mut self, enabled: bool) -> Self {
+        self.shadow_rays = enabled;
+        self
+    }
+
+    /// Set the anti-aliasing mode.
+    pub fn with_anti_aliasing(mut self, mode: AntiAliasing) -> Self {
+        self.anti_aliasing = mode;
+        self
+    }
+
+    /// Render on a transparent background with premultiplied alpha.
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Restrict rendering to a region (`None` renders the whole frame).
+    pub fn with_crop(mut self, crop: Option<CropRegion>) -> Self {
+        self.crop = crop;
+        self
+    }
+
+    /// Select AOV outputs.
+    pub fn with_aovs(mut self, aovs: AovSelection) -> Self {
+        self.aovs = aovs;
+        self
+    }
+}
+
+/// Result of marching one ray.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayMarch {
+    /// Distance along the ray to the surface, if it was hit.
+    pub hit: Option<f32>,
+    /// Closest approach to the surface (drives outlines on misses).
+    pub min_distance: f32,
+    /// Ray distance at the closest approach.
+    pub min_depth: f32,
+}
+
+/// Sphere-trace `sdf` from `origin` along the unit vector `dir`.
+pub fn march(sdf: &SdfNode, origin: Vec3, dir: Vec3, settings: &RenderSettings) -> RayMarch {
+    let mut t = 0.0f32;
+    let mut result = RayMarch {
+        hit: None,
+        min_distance: f32::MAX,
+        min_depth: 0.0,
+    };
+    for _ in 0..settings.max_steps {
+        let d = alice_sdf::eval(sdf, origin + dir * t);
+        if d < result.min_distance {
+            result.min_distance = d;
+            result.min_depth = t;
+        }
+        if d < settings.hit_epsilon {
+            result.hit = Some(t);
+            break;
+        }
+        t += d;
+        if t > settings.max_distance {
+            break;
+        }
+    }
+    result
+}
+
+/// Surface normal from the SDF gradient.
+pub fn surface_normal(sdf: &SdfNode, p: Vec3) -> Vec3 {
+    let e = NORMAL_EPSILON;
+    let dx = alice_sdf::eval(sdf, p + Vec3::X * e) - alice_sdf::eval(sdf, p - Vec3::X * e);
+    let dy = alice_sdf::eval(sdf, p + Vec3::Y * e) - alice_sdf::eval(sdf, p - Vec3::Y * e);
+    let dz = alice_sdf::eval(sdf, p + Vec3::Z * e) - alice_sdf::eval(sdf, p - Vec3::Z * e);
+    Vec3::new(dx, dy, dz).normalize_or_zero()
+}
+
+/// Largest change in diffuse lighting across a footprint of `radius` around `p`,
+/// estimated from SDF normals along two tangents.
+fn lambert_variation(sdf: &SdfNode, p: Vec3, n: Vec3, light_dir: Vec3, radius: f32) -> f32 {
+    let u = n.any_orthonormal_vector();
+    let v = n.cross(u);
+    let lambert = n.dot(light_dir);
+    [u, v]
+        .into_iter()
+        .map(|t| (surface_normal(sdf, p + t * radius).dot(light_dir) - lambert).abs())
+        .fold(0.0, f32::max)
+}
+
+#[inline]
+fn lerp4(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [
+        a[0] + (b[0] - a[0]) * t,
+        a[1] + (b[1] - a[1]) * t,
+        a[2] + (b[2] - a[2]) * t,
+        a[3] + (b[3] - a[3]) * t,
+    ]
+}
+
+/// Default tile edge in pixels.
+pub const DEFAULT_TILE_SIZE: u32 = 32;
+
+/// Rectangular block of pixels rendered as one unit of work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Cover a `width` x `height` image with tiles of at most `size` pixels per side, row by row.
+pub fn tiles(width: u32, height: u32, size: u32) -> Vec<Tile> {
+    let size = size.max(1);
+    let mut out = Vec::with_capacity((width.div_ceil(size) * height.div_ceil(size)) as usize);
+    for y in (0..height).step_by(size as usize) {
+        for x in (0..width).step_by(size as usize) {
+            out.push(Tile {
+                x,
+                y,
+                width: size.min(width - x),
+                height: size.min(height - y),
+            });
+        }
+    }
+    out
+}
+
+/// Software renderer for episodes and bare SDFs.
+#[derive(Debug, Clone)]
+pub struct Renderer {
+    pub settings: RenderSettings,
+    /// Direction towards the key light.
+    pub light_dir: Vec3,
+    /// Color of rays that miss everything (when there is no plate).
+    pub background: [f32; 4],
+    /// Background plate composited behind (or in front of) actors by depth.
+    pub plate: Option<BackgroundPlate>,
+    pub fog: Option<Fog>,
+    /// World up vector for camera framing (from the project's `Units`).
+    pub up: Vec3,
+}
+
+/// What a ray sees if it hits no actor in front of it.
+#[derive(Debug, Clone, Copy)]
+struct Backdrop {
+    color: [f32; 4],
+    /// Ray distance to the plate.
+    depth: f32,
+}
+
+impl Renderer {
+    /// Default settings at the given resolution.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self::from_settings(RenderSettings::default().with_resolution(width, height))
+    }
+
+    pub fn from_settings(settings: RenderSettings) -> Self {
+        Self {
+            settings,
+            light_dir: Vec3::new(0.5, 0.8, 0.6).normalize(),
+            background: [1.0, 1.0, 1.0, 1.0],
+            plate: None,
+            fog: None,
+            up: Vec3::Y,
+        }
+    }
+
+    /// Renderer using the episode's stored settings (or defaults) and units.
+    pub fn for_episode(episode: &EpisodePackage) -> Self {
+        Self::from_settings(episode.render_settings.unwrap_or_default())
+            .with_units(&episode.metadata.units())
+    }
+
+    #[inline]
+    pub fn settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.settings.width
+    }
+
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.settings.height
+    }
+
+    /// Set key light direction.
+    pub fn with_light(mut self, dir: Vec3) -> Self {
+        self.light_dir = dir.normalize_or_zero();
+        self
+    }
+
+    /// Set background color.
+    pub fn with_background(mut self, rgba: [f32; 4]) -> Self {
+        self.background = rgba;
+        self
+    }
+
+    /// Set a background plate.
+    pub fn with_plate(mut self, plate: BackgroundPlate) -> Self {
+        self.plate = Some(plate);
+        self
+    }
+
+    /// Frame cameras with `units`' up axis.
+    pub fn with_units(mut self, units: &Units) -> Self {
+        self.up = units.up();
+        self
+    }
+
+    /// Set distance fog (applied to actors and finite-depth plates).
+    pub fn with_fog(mut self, fog: Fog) -> Self {
+        self.fog = Some(fog);
+        self
+    }
+
+    /// Plate (or background color) behind image position (`x`, `y`) along `dir`.
+    fn backdrop(&self, camera: &CameraState, dir: Vec3, x: f32, y: f32) -> Backdrop {
+        let Some(plate) = &self.plate else {
+            return self.flat_backdrop();
+        };
+        let (color, view_depth) = plate.sample(
+            x / self.width().max(1) as f32,
+            y / self.height().max(1) as f32,
+        );
+        // View-space depth to distance along this ray
+        let depth = view_depth / dir.dot(camera.forward()).max(1e-6);
+        Backdrop {
+            color: self.fogged(color, depth),
+            depth,
+        }
+    }
+
+    /// Background color at infinity (transparent in transparent mode).
+    #[inline]
+    fn flat_backdrop(&self) -> Backdrop {
+        Backdrop {
+            color: if self.settings.transparent {
+                [0.0; 4]
+            } else {
+                self.background
+            },
+            depth: f32::INFINITY,
+        }
+    }
+
+    #[inline]
+    fn fogged(&self, color: [f32; 4], distance: f32) -> [f32; 4] {
+        match &self.fog {
+            Some(fog) => fog.apply(color, distance),
+            None => color,
+        }
+    }
+
+    /// World-space ray direction through image position (`x`, `y`) in pixels.
+    pub fn camera_ray(&self, camera: &CameraState, x: f32, y: f32) -> Vec3 {
+        let (right, up, forward) = camera.basis(self.up);
+        let half_height = (camera.fov * 0.5).tan();
+        let (width, height) = (self.width() as f32, self.height().max(1) as f32);
+        let ndc_x = (2.0 * x / width - 1.0) * (width / height) * half_height;
+        let ndc_y = (1.0 - 2.0 * y / height) * half_height;
+        (forward + right * ndc_x + up * ndc_y).normalize()
+    }
+
+    /// Shade one ray.
+    pub fn shade_ray(
+        &self,
+        sdf: &SdfNode,
+        origin: Vec3,
+        dir: Vec3,
+        shading: &AnimeShading,
+    ) -> [f32; 4] {
+        self.shade_sample(sdf, &[], origin, dir, shading).color
+    }
+
+    /// Shade one ray, also returning AOV values. `objects` (per-actor SDFs) resolve the
+    /// object ID; pass an empty slice to skip it.
+    pub fn shade_sample(
+        &self,
+        sdf: &SdfNode,
+        objects: &[(ActorId, SdfNode)],
+        origin: Vec3,
+        dir: Vec3,
+        shading: &AnimeShading,
+    ) -> ShadeSample {
+        let backdrop = self.flat_backdrop();
+        self.shade_footprint(sdf, objects, origin, dir, shading, backdrop, 0.0, false)
+            .0
+    }
+
+    /// Shade one ray whose pixel spans `pixel_angle` radians against `backdrop`; also
+    /// reports whether it sits on an edge. With `filter`, edges get SDF-derived partial
+    /// coverage.
+    #[allow(clippy::too_many_arguments)]
+    fn shade_footprint(
+        &self,
+        sdf: &SdfNode,
+        objects: &[(ActorId, SdfNode)],
+        origin: Vec3,
+        dir: Vec3,
+        shading: &AnimeShading,
+        backdrop: Backdrop,
+        pixel_angle: f32,
+        filter: bool,
+    ) -> (ShadeSample, bool) {
+        let m = march(sdf, origin, dir, &self.settings);
+        // Actors behind the plate are hidden by it
+        let Some(t) = m.hit.filter(|&t| t < backdrop.depth) else {
+            // Near misses become the silhouette line
+            let in_front = m.min_depth < backdrop.depth;
+            let alpha = if in_front {
+                shading.outline.outline_alpha(m.min_distance, m.min_depth)
+            } else {
+                0.0
+            };
+            let footprint = m.min_depth * pixel_angle;
+            let edge = alpha > 0.0 || (in_front && m.min_distance < footprint);
+            let mut color = backdrop.color;
+            if filter && in_front && m.min_distance < footprint * 0.5 {
+                // The surface covers part of the pixel: blend in its closest point
+                let q = origin + dir * m.min_depth;
+                let n = surface_normal(sdf, q);
+                let p = q - n * m.min_distance;
+                let (surface, _) = self.surface_color(sdf, p, n, dir, shading, pixel_angle);
+                let coverage = 0.5 - m.min_distance / footprint;
+                color = lerp4(color, self.fogged(surface, m.min_depth), coverage);
+            }
+            let color = if self.settings.transparent {
+                // Premultiplied "over": the outline covers `alpha` of the pixel
+                let o = shading.outline.color;
+                let under = 1.0 - alpha;
+                [
+                    o[0] * alpha + color[0] * under,
+                    o[1] * alpha + color[1] * under,
+                    o[2] * alpha + color[2] * under,
+                    alpha + color[3] * under,
+                ]
+            } else {
+                lerp4(color, shading.outline.color, alpha)
+            };
+            let sample = ShadeSample {
+                color,
+                depth: backdrop.depth,
+                normal: Vec3::ZERO,
+                object_id: 0.0,
+                outline: alpha,
+                cel_step: -1.0,
+            };
+            return (sample, edge);
+        };
+
+        let p = origin + dir * t;
+        let n = surface_normal(sdf, p);
+        let band_width = if pixel_angle > 0.0 {
+            lambert_variation(sdf, p, n, self.light_dir, t * pixel_angle)
+        } else {
+            0.0
+        };
+        let (color, band) = self.surface_color(
+            sdf,
+            p,
+            n,
+            dir,
+            shading,
+            if filter { band_width } else { 0.0 },
+        );
+        let lambert = n.dot(self.light_dir).max(0.0);
+        let edge = n.dot(-dir) < GRAZING_COS
+            || shading
+                .cel_shading
+                .thresholds
+                .iter()
+                .any(|&th| (lambert - th).abs() < band_width);
+
+        let object_id = objects
+            .iter()
+            .map(|(id, node)| (id, alice_sdf::eval(node, p)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(id, _)| id.0 as f32 + 1.0)
+            .unwrap_or(0.0);
+        let sample = ShadeSample {
+            color: self.fogged(color, t),
+            depth: t,
+            normal: n,
+            object_id,
+            outline: 0.0,
+            cel_step: (band * shading.cel_shading.shadow_steps as f32).round(),
+        };
+        (sample, edge)
+    }
+
+    /// Cel-shaded color of surface point `p`, and its lighting band. A non-zero
+    /// `band_width` softens cel boundaries over that lighting range.
+    fn surface_color(
+        &self,
+        sdf: &SdfNode,
+        p: Vec3,
+        n: Vec3,
+        dir: Vec3,
+        shading: &AnimeShading,
+        band_width: f32,
+    ) -> ([f32; 4], f32) {
+        let mut lambert = n.dot(self.light_dir).max(0.0);
+        if self.settings.shadow_rays && lambert > 0.0 {
+            let start = p + n * (self.settings.hit_epsilon * 4.0);
+            if march(sdf, start, self.light_dir, &self.settings)
+                .hit
+                .is_some()
+            {
+                lambert = 0.0;
+            }
+        }
+        let cel = &shading.cel_shading;
+        let band = cel.quantize(lambert);
+        let mut color = lerp4(
+            cel.shadow_color,
+            cel.highlight_color,
+            cel.quantize_smooth(lambert, band_width),
+        );
+
+        if shading.ao_strength > 0.0 {
+            let mut occlusion = 0.0f32;
+            for i in 1..=4 {
+                let h = AO_STEP * i as f32;
+                occlusion += (h - alice_sdf::eval(sdf, p + n * h)).max(0.0) / i as f32;
+            }
+            let ao = (1.0 - shading.ao_strength * occlusion * 4.0).clamp(0.0, 1.0);
+            for c in &mut color[..3] {
+                *c *= ao;
+            }
+        }
+        if shading.rim_light > 0.0 {
+            let rim = (1.0 - n.dot(-dir).max(0.0)).powi(3) * shading.rim_light;
+            for c in &mut color[..3] {
+                *c += rim;
+            }
+        }
+        if self.settings.transparent {
+            let a = color[3];
+            for c in &mut color[..3] {
+                *c *= a;
+            }
+        }
+        (color, band)
+    }
+
+    /// Render one tile into a tile-sized framebuffer (with the selected AOV planes).
+    pub fn render_tile(
+        &self,
+        sdf: &SdfNode,
+        objects: &[(ActorId, SdfNode)],
+        camera: &CameraState,
+        shading: &AnimeShading,
+        tile: Tile,
+    ) -> Framebuffer {
+        let aovs = self.settings.aovs;
+        let mut out = Framebuffer::new(tile.width, tile.height);
+        out.premultiplied = self.settings.transparent;
+        for (enabled, name, channels) in [
+            (aovs.depth, AOV_DEPTH, 1),
+            (aovs.normal, AOV_NORMAL, 3),
+            (aovs.object_id, AOV_OBJECT_ID, 1),
+            (aovs.outline, AOV_OUTLINE, 1),
+            (aovs.cel_step, AOV_CEL_STEP, 1),
+        ] {
+            if enabled {
+                out.add_aov(name, channels);
+            }
+        }
+
+        let n = self.settings.supersampling.max(1);
+        let mode = self.settings.anti_aliasing;
+        let supersample_all = n > 1 && mode == AntiAliasing::Supersample;
+        let pixel_angle = 2.0 * (camera.fov * 0.5).tan() / self.height().max(1) as f32;
+        let objects = if aovs.object_id { objects } else { &[] };
+        for ty in 0..tile.height {
+            for tx in 0..tile.width {
+                let (x, y) = ((tile.x + tx) as f32, (tile.y + ty) as f32);
+                let (color, center) = if supersample_all {
+                    self.sample_grid(sdf, objects, camera, shading, x, y, n)
+                } else {
+                    let dir = self.camera_ray(camera, x + 0.5, y + 0.5);
+                    let (s, edge) = self.shade_footprint(
+                        sdf,
+                        objects,
+                        camera.position,
+                        dir,
+                        shading,
+                        self.backdrop(camera, dir, x + 0.5, y + 0.5),
+                        pixel_angle,
+                        mode == AntiAliasing::EdgeAware,
+                    );
+                    if edge && n > 1 && mode == AntiAliasing::Adaptive {
+                        (self.sample_grid(sdf, &[], camera, shading, x, y, n).0, s)
+                    } else {
+                        (s.color, s)
+                    }
+                };
+                out.set_pixel(tx, ty, color);
+                if !aovs.is_empty() {
+                    let i = out.index(tx, ty);
+                    let mut write = |name: &str, values: &[f32]| {
+                        if let Some(aov) = out.aov_mut(name) {
+                            aov.set(i, values);
+                        }
+                    };
+                    write(AOV_DEPTH, &[center.depth]);
+                    write(AOV_NORMAL, &center.normal.to_array());
+                    write(AOV_OBJECT_ID, &[center.object_id]);
+                    write(AOV_OUTLINE, &[center.outline]);
+                    write(AOV_CEL_STEP, &[center.cel_step]);
+                }
+            }
+        }
+        out
+    }
+
+    /// Average a stratified `n` x `n` grid of sub-pixel samples; also returns the sample
+    /// at the pixel center (traced separately when `n` is even).
+    #[allow(clippy::too_many_arguments)]
+    fn sample_grid(
+        &self,
+        sdf: &SdfNode,
+        objects: &[(ActorId, SdfNode)],
+        camera: &CameraState,
+        shading: &AnimeShading,
+        x: f32,
+        y: f32,
+        n: u32,
+    ) -> ([f32; 4], ShadeSample) {
+        let sample = |px: f32, py: f32| {
+            let dir = self.camera_ray(camera, px, py);
+            let backdrop = self.backdrop(camera, dir, px, py);
+            let (s, _) = self.shade_footprint(
+                sdf,
+                objects,
+                camera.position,
+                dir,
+                shading,
+                backdrop,
+                0.0,
+                false,
+            );
+            s
+        };
+        let rcp_n = 1.0 / n as f32;
+        let mut sum = [0.0f32; 4];
+        let mut center = None;
+        for sy in 0..n {
+            for sx in 0..n {
+                let s = sample(x + (sx as f32 + 0.5) * rcp_n, y + (sy as f32 + 0.5) * rcp_n);
+                for (acc, v) in sum.iter_mut().zip(s.color) {
+                    *acc += v;
+                }
+                // Only an odd grid has a sample on the pixel center
+                if n % 2 == 1 && sx == n / 2 && sy == n / 2 {
+                    center = Some(s);
+                }
+            }
+        }
+        let center = center.unwrap_or_else(|| sample(x + 0.5, y + 0.5));
+        let rcp_samples = rcp_n * rcp_n;
+        (sum.map(|v| v * rcp_samples), center)
+    }
+
+    /// Render an SDF from a camera, tile by tile (tiles run on the rayon pool with `parallel`).
+    ///
+    /// With a crop region only that part is rendered; `Framebuffer::crop` extracts it.
+    pub fn render(
+        &self,
+        sdf: &SdfNode,
+        camera: &CameraState,
+        shading: &AnimeShading,
+    ) -> Framebuffer {
+        self.render_tiled(sdf, &[], camera, shading, DEFAULT_TILE_SIZE)
+    }
+
+    /// Render with per-actor SDFs for the object ID AOV, and an explicit tile size.
+    pub fn render_tiled(
+        &self,
+        sdf: &SdfNode,
+        objects: &[(ActorId, SdfNode)],
+        camera: &CameraState,
+        shading: &AnimeShading,
+        tile_size: u32,
+    ) -> Framebuffer {
+        let region = self
+            .settings
+            .crop
+            .unwrap_or(CropRegion::new(0, 0, u32::MAX, u32::MAX))
+            .clamp_to(self.width(), self.height());
+        // Tiles stay in full-frame coordinates so camera rays are unchanged
+        let tiles: Vec<Tile> = tiles(region.width, region.height, tile_size)
+            .into_iter()
+            .map(|t| Tile {
+                x: t.x + region.x,
+                y: t.y + region.y,
+                ..t
+            })
+            .collect();
+        #[cfg(feature = "parallel")]
+        let rendered: Vec<(Tile, Framebuffer)> = {
+            use rayon::prelude::*;
+            tiles
+                .into_par_iter()
+                .map(|tile| (tile, self.render_tile(sdf, objects, camera, shading, tile)))
+                .collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let rendered: Vec<(Tile, Framebuffer)> = tiles
+            .into_iter()
+            .map(|tile| (tile, self.render_tile(sdf, objects, camera, shading, tile)))
+            .collect();
+
+        let mut fb = Framebuffer::new(self.width(), self.height());
+        fb.premultiplied = self.settings.transparent;
+        for (tile, pixels) in rendered {
+            fb.blit(&pixels, tile.x, tile.y);
+        }
+        fb
+    }
+
+    /// Render an episode at `time`, through the active cut's camera, with its overlays
+    /// composited on top.
+    pub fn render_episode(&self, episode: &EpisodePackage, time: f32) -> Framebuffer {
+        let state = episode.director.evaluate(&episode.scene_graph, time);
+        let sdf = episode.director.evaluate_scene(&episode.scene_graph, time);
+        let objects = if self.settings.aovs.object_id {
+            episode.director.evaluate_actors(&episode.scene_graph, time)
+        } else {
+            Vec::new()
+        };
+        let mut fb = self.render_tiled(
+            &sdf,
+            &objects,
+            &state.camera_state,
+            &episode.shading,
+            DEFAULT_TILE_SIZE,
+        );
+        composite_overlays(&mut fb, &episode.overlays, time);
+        fb
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_march_sphere() {
+        let sdf = SdfNode::sphere(1.0);
+        let settings = RenderSettings::default();
+        let m = march(&sdf, Vec3::new(0.0, 0.0, 5.0), -Vec3::Z, &settings);
+        assert!((m.hit.unwrap() - 4.0).abs() < 1e-2);
+        let miss = march(&sdf, Vec3::new(0.0, 1.01, 5.0), -Vec3::Z, &settings);
+        assert!(miss.hit.is_none());
+        assert!(miss.min_distance < 0.02);
+        let n = surface_normal(&sdf, Vec3::new(0.0, 0.0, 1.0));
+        assert!((n - Vec3::Z).length() < 1e-3);
+    }
+
+    #[test]
+    fn test_tiles_cover_frame() {
+        let t = tiles(70, 33, 32);
+        assert_eq!(t.len(), 6);
+        assert_eq!(
+            t[2],
+            Tile {
+                x: 64,
+                y: 0,
+                width: 6,
+                height: 32
+            }
+        );
+        assert_eq!(
+            t[5],
+            Tile {
+                x: 64,
+                y: 32,
+                width: 6,
+                height: 1
+            }
+        );
+        let area: u32 = t.iter().map(|t| t.width * t.height).sum();
+        assert_eq!(area, 70 * 33);
+
+        // Tile size does not change the image
+        let renderer = Renderer::new(20, 14);
+        let (sdf, cam, shading) = (
+            SdfNode::sphere(1.0),
+            CameraState::default(),
+            AnimeShading::default(),
+        );
+        assert_eq!(
+            renderer.render_tiled(&sdf, &[], &cam, &shading, 3),
+            renderer.render_tiled(&sdf, &[], &cam, &shading, 64)
+        );
+    }
+
+    #[test]
+    fn test_settings_shadows_and_supersampling() {
+        // Small sphere above a large one, lit from straight above
+        let sdf = SdfNode::sphere(1.0).union(SdfNode::sphere(0.3).translate(0.0, 1.8, 0.0));
+        let camera = CameraState {
+            position: Vec3::new(0.0, 3.0, 4.0),
+            target: Vec3::ZERO,
+            fov: 0.8,
+            up: None,
+        };
+        let shading = AnimeShading::default();
+        let base = RenderSettings::preview().with_resolution(24, 24);
+        let top = Vec3::new(0.0, 1.0, 0.0);
+        let dir = (top - camera.position).normalize();
+
+        let lit = Renderer::from_settings(base).with_light(Vec3::Y);
+        let shadowed = Renderer::from_settings(base.with_shadow_rays(true)).with_light(Vec3::Y);
+        let a = lit.shade_ray(&sdf, camera.position, dir, &shading);
+        let b = shadowed.shade_ray(&sdf, camera.position, dir, &shading);
+        assert!(b[0] < a[0]);
+
+        let ss = Renderer::from_settings(base.with_supersampling(2));
+        let fb = ss.render(&sdf, &camera, &shading);
+        assert_eq!((fb.width, fb.height), (24, 24));
+        // Averaged edge pixels produce intermediate values not present without AA
+        let plain = lit.render(&sdf, &camera, &shading);
+        assert_ne!(fb, plain);
+    }
+
+    #[test]
+    fn test_aov_outputs() {
+        use crate::director::{Cut, Director};
+        use crate::episode::EpisodeMetadata;
+        use crate::scene::{Actor, SceneGraph};
+
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new(
+            "left",
+            SdfNode::sphere(0.8).translate(-1.0, 0.0, 0.0),
+        ));
+        let right = sg.add_actor(Actor::new(
+            "right",
+            SdfNode::sphere(0.8).translate(1.0, 0.0, 0.0),
+        ));
+        let mut dir = Director::new("AOV");
+        dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        let episode = EpisodePackage::new(
+            EpisodeMetadata::new("AOV", 1, 1.0),
+            sg,
+            dir,
+            AnimeShading::default(),
+        );
+
+        let settings = RenderSettings::default()
+            .with_resolution(32, 16)
+            .with_aovs(AovSelection::all());
+        let fb = Renderer::from_settings(settings).render_episode(&episode, 0.5);
+        assert_eq!(fb.aovs.len(), 5);
+
+        let i_right = fb.index(20, 8);
+        let i_bg = fb.index(0, 0);
+        let ids = fb.aov(AOV_OBJECT_ID).unwrap();
+        assert_eq!(ids.sample(i_right), &[right.0 as f32 + 1.0]);
+        assert_eq!(ids.sample(fb.index(11, 8)), &[1.0]);
+        assert_eq!(ids.sample(i_bg), &[0.0]);
+        let depth = fb.aov(AOV_DEPTH).unwrap();
+        assert!(depth.sample(i_right)[0] < 5.0);
+        assert!(depth.sample(i_bg)[0].is_infinite());
+        let normal = fb.aov(AOV_NORMAL).unwrap().sample(i_right);
+        assert!(normal[2] > 0.5);
+        assert_eq!(fb.aov(AOV_CEL_STEP).unwrap().sample(i_bg), &[-1.0]);
+
+        // Supersampling keeps AOVs on the pixel center, even with an even grid
+        let ss =
+            Renderer::from_settings(settings.with_supersampling(2)).render_episode(&episode, 0.5);
+        assert_eq!(ss.aov(AOV_DEPTH), fb.aov(AOV_DEPTH));
+
+        // No AOVs unless requested
+        assert!(Renderer::new(8, 8)
+            .render_episode(&episode, 0.5)
+            .aovs
+            .is_empty());
+    }
+
+    #[test]
+    fn test_anti_aliasing_modes() {
+        let sdf = SdfNode::sphere(1.0);
+        let cam = CameraState::default();
+        let shading = AnimeShading::default();
+        let base = RenderSettings::default()
+            .with_resolution(24, 24)
+            .with_supersampling(4);
+        let render = |mode| {
+            Renderer::from_settings(base.with_anti_aliasing(mode)).render(&sdf, &cam, &shading)
+        };
+        let single =
+            Renderer::from_settings(base.with_supersampling(1)).render(&sdf, &cam, &shading);
+        let full = render(AntiAliasing::Supersample);
+        let adaptive = render(AntiAliasing::Adaptive);
+        let edge = render(AntiAliasing::EdgeAware);
+
+        // Adaptive matches 1 spp on flat areas and full supersampling where it refined
+        assert_eq!(adaptive.pixel(0, 0), single.pixel(0, 0));
+        let refined: Vec<usize> = (0..adaptive.color.len())
+            .filter(|&i| adaptive.color[i] != single.color[i])
+            .collect();
+        assert!(!refined.is_empty() && refined.len() < adaptive.color.len() / 2);
+        assert!(refined.iter().all(|&i| adaptive.color[i] == full.color[i]));
+
+        // Edge-aware AA adds intermediate shades without extra samples
+        let distinct = |fb: &Framebuffer| {
+            let mut v: Vec<[u8; 4]> = fb
+                .to_rgba8()
+                .chunks(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect();
+            v.sort_unstable();
+            v.dedup();
+            v.len()
+        };
+        assert!(distinct(&edge) > distinct(&single));
+    }
+
+    #[test]
+    fn test_background_plate_depth_and_fog() {
+        use crate::plate::PlateDepth;
+
+        let sdf = SdfNode::sphere(1.0);
+        let cam = CameraState::default();
+        let shading = AnimeShading::default();
+        let mut painted = Framebuffer::new(4, 4);
+        painted.fill([0.0, 0.5, 0.0, 1.0]);
+        let plate = BackgroundPlate::new(painted);
+        let renderer = Renderer::new(16, 16).with_plate(plate.clone());
+
+        // Infinitely far plate: shows around the actor, actor in front
+        let fb = renderer.render(&sdf, &cam, &shading);
+        assert_eq!(fb.pixel(0, 0), [0.0, 0.5, 0.0, 1.0]);
+        assert_ne!(fb.pixel(8, 8), [0.0, 0.5, 0.0, 1.0]);
+
+        // A card at distance 3 (sphere front is at 4) hides the actor
+        let card = renderer
+            .clone()
+            .with_plate(plate.with_depth(PlateDepth::Constant(3.0)));
+        assert_eq!(
+            card.render(&sdf, &cam, &shading).pixel(8, 8),
+            [0.0, 0.5, 0.0, 1.0]
+        );
+
+        // Fog tints the actor but not an infinitely distant plate
+        let fogged = renderer
+            .with_fog(Fog::new([0.0, 0.0, 1.0, 1.0], 0.5))
+            .render(&sdf, &cam, &shading);
+        assert_eq!(fogged.pixel(0, 0), [0.0, 0.5, 0.0, 1.0]);
+        assert!(fogged.pixel(8, 8)[2] > fogged.pixel(8, 8)[0]);
+    }
+
+    #[test]
+    fn test_transparent_premultiplied() {
+        let sdf = SdfNode::sphere(1.0);
+        let cam = CameraState::default();
+        let mut shading = AnimeShading::default();
+        shading.outline.width = 0.1;
+        let settings = RenderSettings::default()
+            .with_resolution(32, 32)
+            .with_transparent(true);
+        let fb = Renderer::from_settings(settings).render(&sdf, &cam, &shading);
+        assert!(fb.premultiplied);
+        assert_eq!(fb.pixel(0, 0), [0.0; 4]);
+        assert_eq!(fb.pixel(16, 16)[3], 1.0);
+        // Soft outline pixels: partial alpha, color never exceeds alpha
+        let partial: Vec<&[f32; 4]> = fb
+            .color
+            .iter()
+            .filter(|px| px[3] > 0.0 && px[3] < 1.0)
+            .collect();
+        assert!(!partial.is_empty());
+        assert!(partial
+            .iter()
+            .all(|px| px[..3].iter().all(|&c| c <= px[3] + 1e-6)));
+    }
+
+    #[test]
+    fn test_crop_region_matches_full_frame() {
+        let sdf = SdfNode::sphere(1.0);
+        let cam = CameraState::default();
+        let shading = AnimeShading::default();
+        let settings = RenderSettings::default().with_resolution(30, 20);
+        let full = Renderer::from_settings(settings).render(&sdf, &cam, &shading);
+        let region = CropRegion::new(11, 5, 9, 7);
+        let cropped =
+            Renderer::from_settings(settings.with_crop(Some(region))).render(&sdf, &cam, &shading);
+
+        assert_eq!((cropped.width, cropped.height), (30, 20));
+        assert_eq!(cropped.pixel(0, 0), [0.0; 4]);
+        assert_eq!(cropped.pixel(19, 11), full.pixel(19, 11));
+        assert_eq!(
+            cropped.crop(11, 5, 9, 7),
+            full.crop(11, 5, 9, 7),
+            "crop must use full-frame camera rays"
+        );
+
+        // Regions past the frame edge are clipped
+        assert_eq!(
+            CropRegion::new(25, 18, 10, 10).clamp_to(30, 20),
+            CropRegion::new(25, 18, 5, 2)
+        );
+        assert_eq!(region.scaled_down(4), CropRegion::new(2, 1, 3, 2));
+    }
+
+    #[test]
+    fn test_render_sphere() {
+        let renderer = Renderer::new(32, 24).with_background([0.0, 0.0, 1.0, 1.0]);
+        let mut shading = AnimeShading::default();
+        shading.outline.width = 0.3;
+        let fb = renderer.render(&SdfNode::sphere(1.0), &CameraState::default(), &shading);
+        assert_eq!(fb.pixel(0, 0), [0.0, 0.0, 1.0, 1.0]);
+        let center = fb.pixel(16, 12);
+        assert_ne!(center, [0.0, 0.0, 1.0, 1.0]);
+        assert_eq!(center[3], 1.0);
+        // Silhouette darkens the background around the sphere
+        let ring = (0..32)
+            .map(|x| fb.pixel(x, 12))
+            .any(|px| px[2] < 0.9 && px[0] < 0.1);
+        assert!(ring);
+    }
+}
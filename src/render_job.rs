@@ -0,0 +1,346 @@
+//! Distributed rendering: split an episode into frame-range jobs for a render farm and
+//! reassemble the frames they return.
+
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::episode::EpisodePackage;
+use crate::export::{render_range, FrameRange};
+use crate::framebuffer::Framebuffer;
+use crate::render::{RenderSettings, Renderer};
+use crate::series::{read_section, write_section};
+
+/// Render job file magic bytes.
+const JOB_MAGIC: [u8; 4] = *b"AJOB";
+/// Render job file version.
+const JOB_VERSION: u16 = 1;
+
+/// Where a job gets its episode from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EpisodeSource {
+    /// The whole package travels with the job.
+    Embedded(Box<EpisodePackage>),
+    /// Path or URI of an episode file every worker can reach.
+    Reference { uri: String, episode_number: u32 },
+}
+
+/// One unit of farm work: a contiguous run of frames plus everything needed to render it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderJob {
+    /// Position of this job in the split (0-based).
+    pub index: u32,
+    pub job_count: u32,
+    /// Absolute episode frame number of the first frame.
+    pub first_frame: u32,
+    pub frame_count: u32,
+    pub fps: f32,
+    pub settings: RenderSettings,
+    pub source: EpisodeSource,
+}
+
+impl RenderJob {
+    /// Replace the embedded episode with a reference to `uri` (keeps job files small when
+    /// workers share storage).
+    pub fn with_reference(mut self, uri: impl Into<String>) -> Self {
+        let episode_number = match &self.source {
+            EpisodeSource::Embedded(episode) => episode.metadata.episode_number,
+            EpisodeSource::Reference { episode_number, .. } => *episode_number,
+        };
+        self.source = EpisodeSource::Reference {
+            uri: uri.into(),
+            episode_number,
+        };
+        self
+    }
+
+    /// Absolute frame numbers covered by this job.
+    #[inline]
+    pub fn frames(&self) -> Range<u32> {
+        self.first_frame..self.first_frame + self.frame_count
+    }
+
+    /// The job's frames as an export range.
+    pub fn range(&self) -> FrameRange {
+        let frames = self.frames();
+        FrameRange::new(
+            frames.start as f32 / self.fps,
+            frames.end as f32 / self.fps,
+            self.fps,
+        )
+    }
+
+    /// Resolve the episode, calling `load` with the URI for referenced sources.
+    ///
+    /// A referenced episode whose number doesn't match the job is rejected.
+    pub fn load_episode<F>(&self, load: F) -> io::Result<Cow<'_, EpisodePackage>>
+    where
+        F: FnOnce(&str) -> io::Result<EpisodePackage>,
+    {
+        match &self.source {
+            EpisodeSource::Embedded(episode) => Ok(Cow::Borrowed(episode)),
+            EpisodeSource::Reference {
+                uri,
+                episode_number,
+            } => {
+                let episode = load(uri)?;
+                if episode.metadata.episode_number != *episode_number {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!(
+                            "{} holds episode {}, job expects {}",
+                            uri, episode.metadata.episode_number, episode_number
+                        ),
+                    ));
+                }
+                Ok(Cow::Owned(episode))
+            }
+        }
+    }
+
+    /// Render the job's frames, handing each to `sink` with its absolute frame number.
+    pub fn render<F>(&self, episode: &EpisodePackage, mut sink: F) -> io::Result<()>
+    where
+        F: FnMut(u32, &Framebuffer) -> io::Result<()>,
+    {
+        let renderer = Renderer::from_settings(self.settings);
+        let first = self.first_frame;
+        render_range(
+            &renderer,
+            episode,
+            &self.range(),
+            |i, frame| Ok(sink(first + i, frame)?),
+            |_| {},
+        )?;
+        Ok(())
+    }
+
+    /// Render the job into memory.
+    pub fn run(&self, episode: &EpisodePackage) -> io::Result<JobOutput> {
+        let mut frames = Vec::with_capacity(self.frame_count as usize);
+        self.render(episode, |_, frame| {
+            frames.push(frame.clone());
+            Ok(())
+        })?;
+        Ok(JobOutput {
+            job_index: self.index,
+            first_frame: self.first_frame,
+            frames,
+        })
+    }
+}
+
+/// Frames returned by one job.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobOutput {
+    pub job_index: u32,
+    pub first_frame: u32,
+    pub frames: Vec<Framebuffer>,
+}
+
+/// Split the episode into jobs of at most `frames_per_job` frames at `fps`, each embedding
+/// the episode and its render settings (or defaults).
+pub fn split_render_jobs(
+    episode: &EpisodePackage,
+    fps: f32,
+    frames_per_job: u32,
+) -> Vec<RenderJob> {
+    let total = FrameRange::whole(episode, fps).frame_count();
+    let per_job = frames_per_job.max(1);
+    let job_count = total.div_ceil(per_job);
+    let settings = episode.render_settings.unwrap_or_default();
+    (0..job_count)
+        .map(|index| {
+            let first_frame = index * per_job;
+            RenderJob {
+                index,
+                job_count,
+                first_frame,
+                frame_count: per_job.min(total - first_frame),
+                fps,
+                settings,
+                source: EpisodeSource::Embedded(Box::new(episode.clone())),
+            }
+        })
+        .collect()
+}
+
+/// Job indices with no output yet (to reschedule).
+pub fn missing_jobs(jobs: &[RenderJob], outputs: &[JobOutput]) -> Vec<u32> {
+    jobs.iter()
+        .map(|job| job.index)
+        .filter(|&index| !outputs.iter().any(|o| o.job_index == index))
+        .collect()
+}
+
+/// Reassemble job outputs (in any order) and hand every frame to `sink` in frame order.
+///
+/// Fails with `NotFound` if a job has no output, and `InvalidData` on duplicate outputs
+/// or a frame count that doesn't match the job.
+pub fn merge_job_outputs<F>(
+    jobs: &[RenderJob],
+    mut outputs: Vec<JobOutput>,
+    mut sink: F,
+) -> io::Result<()>
+where
+    F: FnMut(u32, &Framebuffer) -> io::Result<()>,
+{
+    outputs.sort_by_key(|o| o.job_index);
+    if let Some(pair) = outputs
+        .windows(2)
+        .find(|w| w[0].job_index == w[1].job_index)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Duplicate output for job {}", pair[0].job_index),
+        ));
+    }
+    if let Some(&index) = missing_jobs(jobs, &outputs).first() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("No output for job {}", index),
+        ));
+    }
+
+    let mut jobs: Vec<&RenderJob> = jobs.iter().collect();
+    jobs.sort_by_key(|job| job.first_frame);
+    for job in jobs {
+        let output = outputs.iter().find(|o| o.job_index == job.index).unwrap();
+        if output.first_frame != job.first_frame || output.frames.len() != job.frame_count as usize
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Job {} returned {} frames from {}, expected {} from {}",
+                    job.index,
+                    output.frames.len(),
+                    output.first_frame,
+                    job.frame_count,
+                    job.first_frame
+                ),
+            ));
+        }
+        for (frame_number, frame) in job.frames().zip(&output.frames) {
+            sink(frame_number, frame)?;
+        }
+    }
+    Ok(())
+}
+
+/// Serialize a render job for shipping to a worker.
+///
+/// Binary format:
+/// `[Magic "AJOB" 4B][Version 2B][Flags 2B][BodySize 4B][BodyCRC 4B][Bincode RenderJob]`
+pub fn write_render_job<W: Write>(job: &RenderJob, writer: &mut W) -> io::Result<usize> {
+    let body =
+        bincode::serialize(job).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    writer.write_all(&JOB_MAGIC)?;
+    writer.write_all(&JOB_VERSION.to_le_bytes())?;
+    writer.write_all(&0u16.to_le_bytes())?;
+    Ok(8 + write_section(writer, &body)?)
+}
+
+/// Read a render job written by `write_render_job`.
+pub fn read_render_job<R: Read>(reader: &mut R) -> io::Result<RenderJob> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    if header[0..4] != JOB_MAGIC {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Invalid magic bytes: expected AJOB",
+        ));
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != JOB_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Unsupported render job version: {}", version),
+        ));
+    }
+    let body = read_section(reader)?;
+    bincode::deserialize(&body).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::EpisodeMetadata;
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    fn episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("ball", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Farm");
+        dir.add_cut(Cut::new("c1", 0.0, 1.0));
+        EpisodePackage::new(
+            EpisodeMetadata::new("Farm", 7, 1.0),
+            sg,
+            dir,
+            AnimeShading::default(),
+        )
+        .with_render_settings(RenderSettings::preview().with_resolution(8, 8))
+    }
+
+    #[test]
+    fn test_split_covers_every_frame() {
+        let jobs = split_render_jobs(&episode(), 24.0, 10);
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[2].frames(), 20..24);
+        assert!(jobs.iter().all(|j| j.job_count == 3));
+        assert_eq!(jobs[1].range().frame_count(), 10);
+        assert_eq!(jobs[1].range().frame_number(0), 10);
+    }
+
+    #[test]
+    fn test_run_and_merge_out_of_order() {
+        let ep = episode();
+        let jobs = split_render_jobs(&ep, 12.0, 5);
+        let mut outputs: Vec<JobOutput> = jobs.iter().rev().map(|j| j.run(&ep).unwrap()).collect();
+        let last = outputs.remove(0);
+        assert_eq!(missing_jobs(&jobs, &outputs), vec![last.job_index]);
+        let err = merge_job_outputs(&jobs, outputs.clone(), |_, _| Ok(())).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+        outputs.push(last);
+        let mut numbers = Vec::new();
+        merge_job_outputs(&jobs, outputs, |n, frame| {
+            assert_eq!(frame.width, 8);
+            numbers.push(n);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(numbers, (0..12).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_job_roundtrip_with_reference() {
+        let ep = episode();
+        let job = split_render_jobs(&ep, 24.0, 12)
+            .remove(1)
+            .with_reference("farm/ep07.anim");
+        let mut buf = Vec::new();
+        write_render_job(&job, &mut buf).unwrap();
+        let read = read_render_job(&mut buf.as_slice()).unwrap();
+        assert_eq!(read.frames(), 12..24);
+        assert_eq!(read.settings, job.settings);
+
+        let loaded = read
+            .load_episode(|uri| {
+                assert_eq!(uri, "farm/ep07.anim");
+                Ok(ep.clone())
+            })
+            .unwrap();
+        assert_eq!(loaded.metadata.episode_number, 7);
+        let mut other = ep.clone();
+        other.metadata.episode_number = 8;
+        assert!(read.load_episode(|_| Ok(other)).is_err());
+
+        buf[10] ^= 0xFF;
+        assert!(read_render_job(&mut buf.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+//! Retargeting animation between rigs with different names and proportions.
+//!
+//! A rig here is either the track groups of one actor's timeline (`joint.l_knee`,
+//! `leg.l`, `translate`) or a set of actors in a scene. A [`Retarget`] renames source
+//! bones to the target's bones and scales positional channels by the size ratio between
+//! the two characters, so one motion library can drive the whole cast. Angles are left
+//! as they are: a swing of 0.4 rad reads the same on a child and on a giant.
+
+use std::collections::BTreeMap;
+
+use alice_sdf::animation::Timeline;
+use serde::{Deserialize, Serialize};
+
+use crate::mirror::is_translation_group;
+use crate::scene::{ActorId, SceneGraph};
+
+/// Bone name mapping plus scale compensation from a source rig to a target rig.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Retarget {
+    /// Source bone (a track-name prefix, or an actor name) to target bone.
+    pub bone_map: BTreeMap<String, String>,
+    /// Target size over source size, applied to translations.
+    pub scale: f32,
+    /// Per target bone scale overriding `scale`, for proportions that differ from the
+    /// overall size (short legs on a tall character).
+    pub bone_scales: BTreeMap<String, f32>,
+    /// Drop tracks whose bone is not in `bone_map` instead of copying them unchanged.
+    pub mapped_only: bool,
+}
+
+impl Default for Retarget {
+    fn default() -> Self {
+        Self {
+            bone_map: BTreeMap::new(),
+            scale: 1.0,
+            bone_scales: BTreeMap::new(),
+            mapped_only: false,
+        }
+    }
+}
+
+impl Retarget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scale compensation from the heights (or any matching measure) of both characters.
+    pub fn from_heights(source: f32, target: f32) -> Self {
+        Self::new().with_scale(target / source.max(1e-6))
+    }
+
+    pub fn with_bone(mut self, source: impl Into<String>, target: impl Into<String>) -> Self {
+        self.bone_map.insert(source.into(), target.into());
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_bone_scale(mut self, bone: impl Into<String>, scale: f32) -> Self {
+        self.bone_scales.insert(bone.into(), scale);
+        self
+    }
+
+    pub fn with_mapped_only(mut self, mapped_only: bool) -> Self {
+        self.mapped_only = mapped_only;
+        self
+    }
+
+    /// Target name of a source track or actor: the longest mapped bone that is the whole
+    /// name or a `.`-separated prefix of it is replaced. `None` if unmapped and
+    /// `mapped_only` is set.
+    pub fn name(&self, name: &str) -> Option<String> {
+        let bone = self
+            .bone_map
+            .iter()
+            .filter(|(source, _)| {
+                name == source.as_str()
+                    || (name.starts_with(source.as_str())
+                        && name.as_bytes().get(source.len()) == Some(&b'.'))
+            })
+            .max_by_key(|(source, _)| source.len());
+        match bone {
+            Some((source, target)) => Some(format!("{target}{}", &name[source.len()..])),
+            None if self.mapped_only => None,
+            None => Some(name.to_string()),
+        }
+    }
+
+    /// Multiplier for target track `name`: the bone's scale for positional channels
+    /// (falling back to `scale`), 1 for everything else.
+    pub fn channel_scale(&self, name: &str) -> f32 {
+        match name.rsplit_once('.') {
+            Some((group, "x" | "y" | "z")) if is_translation_group(group) => {
+                self.bone_scales.get(group).copied().unwrap_or(self.scale)
+            }
+            _ => 1.0,
+        }
+    }
+
+    /// `source` with tracks renamed to the target rig and translations rescaled.
+    pub fn timeline(&self, source: &Timeline) -> Timeline {
+        let mut out = Timeline::new(&source.name);
+        for track in &source.tracks {
+            let Some(name) = self.name(&track.name) else {
+                continue;
+            };
+            let scale = self.channel_scale(&name);
+            let mut track = track.clone();
+            for key in &mut track.keyframes {
+                key.value *= scale;
+            }
+            track.name = name;
+            out.add_track(track);
+        }
+        out
+    }
+
+    /// Copy motion from one rig of actors to another: each target actor whose name a
+    /// source actor maps to receives that actor's retargeted timeline. Returns the number
+    /// of actors written.
+    pub fn actors(&self, scene: &mut SceneGraph, source: &[ActorId], target: &[ActorId]) -> usize {
+        let retargeted: Vec<(String, Option<Timeline>)> = source
+            .iter()
+            .filter_map(|&id| {
+                let actor = scene.get_actor(id)?;
+                let name = self.name(&actor.name)?;
+                Some((name, actor.timeline.as_ref().map(|tl| self.timeline(tl))))
+            })
+            .collect();
+        let mut written = 0;
+        for &id in target {
+            let Some(actor) = scene.get_actor_mut(id) else {
+                continue;
+            };
+            if let Some((_, timeline)) = retargeted.iter().find(|(n, _)| *n == actor.name) {
+                actor.timeline = timeline.clone();
+                written += 1;
+            }
+        }
+        written
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::Actor;
+    use alice_sdf::animation::{Keyframe, Track};
+    use alice_sdf::SdfNode;
+
+    fn track(name: &str, value: f32) -> Track {
+        let mut track = Track::new(name);
+        track.add_keyframe(Keyframe::new(0.0, 0.0));
+        track.add_keyframe(Keyframe::new(1.0, value));
+        track
+    }
+
+    fn mocap() -> Timeline {
+        let mut tl = Timeline::new("kick");
+        for (name, value) in [
+            ("translate.z", 2.0),
+            ("joint.l_knee.y", 0.5),
+            ("joint.l_knee_cap.y", 0.6),
+            ("leg.l.swing", 0.4),
+            ("tail.curl", 1.0),
+        ] {
+            tl.add_track(track(name, value));
+        }
+        tl
+    }
+
+    #[test]
+    fn test_rename_and_scale_tracks() {
+        let retarget = Retarget::from_heights(2.0, 1.0)
+            .with_bone("joint.l_knee", "joint.left_knee")
+            .with_bone("leg.l", "thigh.l")
+            .with_bone_scale("joint.left_knee", 0.25);
+        assert_eq!(
+            retarget.name("joint.l_knee_cap.y").unwrap(),
+            "joint.l_knee_cap.y"
+        );
+        let tl = retarget.timeline(&mocap());
+        assert_eq!(tl.tracks.len(), 5);
+        // Root travel at half size, the remapped knee at its own ratio, angles untouched
+        assert_eq!(tl.get_value("translate.z", 1.0), Some(1.0));
+        assert_eq!(tl.get_value("joint.left_knee.y", 1.0), Some(0.125));
+        assert_eq!(tl.get_value("joint.l_knee_cap.y", 1.0), Some(0.3));
+        assert_eq!(tl.get_value("thigh.l.swing", 1.0), Some(0.4));
+        assert_eq!(tl.get_value("tail.curl", 1.0), Some(1.0));
+
+        let strict = retarget.with_mapped_only(true).timeline(&mocap());
+        let names: Vec<&str> = strict.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["joint.left_knee.y", "thigh.l.swing"]);
+    }
+
+    #[test]
+    fn test_actor_rig_to_rig() {
+        let mut sg = SceneGraph::new();
+        let actor = |name: &str| Actor::new(name, SdfNode::sphere(0.1));
+        let src_hand = sg.add_actor(actor("hand_l").with_timeline(mocap()));
+        let src_head = sg.add_actor(actor("head").with_timeline(mocap()));
+        let dst_hand = sg.add_actor(actor("LeftHand"));
+        let dst_foot = sg.add_actor(actor("LeftFoot"));
+        let retarget = Retarget::new()
+            .with_bone("hand_l", "LeftHand")
+            .with_scale(3.0);
+        assert_eq!(
+            retarget.actors(&mut sg, &[src_hand, src_head], &[dst_hand, dst_foot]),
+            1
+        );
+        let tl = sg.get_actor(dst_hand).unwrap().timeline.as_ref().unwrap();
+        assert_eq!(tl.get_value("translate.z", 1.0), Some(6.0));
+        assert!(sg.get_actor(dst_foot).unwrap().timeline.is_none());
+    }
+}
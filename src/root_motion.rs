@@ -0,0 +1,217 @@
+//! Root motion extraction and reapplication.
+//!
+//! A walk cycle keyed with its travel baked in only walks one way at one speed. Extracting
+//! its root motion (ground-plane translation and yaw) leaves the cycle animating in place;
+//! the root can then be regenerated for any length, at another speed, or along a path, and
+//! put on the actor as an [`AnimationLayer`](crate::layer::AnimationLayer) so it keeps
+//! going while the cycle loops underneath.
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use glam::{Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::crowd::{path_lengths, path_sample};
+
+/// Channels that carry root motion by default. Vertical bob stays in the clip.
+pub const ROOT_CHANNELS: [&str; 3] = ["translate.x", "translate.z", "rotate.y"];
+
+/// Root tracks taken out of a clip.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RootMotion {
+    pub timeline: Timeline,
+    /// Length of one pass of the clip.
+    pub duration: f32,
+}
+
+impl RootMotion {
+    /// Move the [`ROOT_CHANNELS`] out of `timeline`, leaving it in place.
+    pub fn extract(timeline: &mut Timeline) -> Self {
+        Self::extract_channels(timeline, &ROOT_CHANNELS)
+    }
+
+    /// Move the tracks named in `channels` out of `timeline`.
+    pub fn extract_channels(timeline: &mut Timeline, channels: &[&str]) -> Self {
+        let duration = timeline.duration();
+        let mut root = Timeline::new(&format!("{} root", timeline.name));
+        let (taken, kept) = std::mem::take(&mut timeline.tracks)
+            .into_iter()
+            .partition(|t| channels.contains(&t.name.as_str()));
+        timeline.tracks = kept;
+        root.tracks = taken;
+        Self {
+            timeline: root,
+            duration,
+        }
+    }
+
+    /// Ground-plane offset and yaw at `time` into the clip, relative to its first frame.
+    pub fn offset(&self, time: f32) -> (Vec3, f32) {
+        let delta = |name: &str| match (
+            self.timeline.get_value(name, time),
+            self.timeline.get_value(name, 0.0),
+        ) {
+            (Some(v), Some(v0)) => v - v0,
+            _ => 0.0,
+        };
+        (
+            Vec3::new(delta("translate.x"), 0.0, delta("translate.z")),
+            delta("rotate.y"),
+        )
+    }
+
+    /// Travel over one pass.
+    #[inline]
+    pub fn displacement(&self) -> Vec3 {
+        self.offset(self.duration).0
+    }
+
+    /// Ground speed of the clip as keyed.
+    pub fn speed(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        self.displacement().length() / self.duration
+    }
+
+    /// Root tracks for looping the clip over `duration` seconds with its travel scaled to
+    /// `speed`. Each pass carries on from where the last ended, turned by its yaw; a clip
+    /// with no travel of its own moves straight ahead.
+    pub fn at_speed(&self, speed: f32, duration: f32, sample_rate: f32) -> Timeline {
+        let native = self.speed();
+        let scale = if native > 1e-6 { speed / native } else { 0.0 };
+        let pass_yaw = self.offset(self.duration).1;
+        let pass_travel = self.displacement() * scale;
+        let (mut pass, mut pass_start) = (0i64, Vec3::ZERO);
+        let mut tracks = RootTracks::new();
+        for t in sample_times(duration, sample_rate) {
+            let n = if self.duration > 0.0 {
+                (t / self.duration).floor() as i64
+            } else {
+                0
+            };
+            while pass < n {
+                pass_start += Quat::from_rotation_y(pass_yaw * pass as f32) * pass_travel;
+                pass += 1;
+            }
+            let (offset, yaw) = self.offset(t - n as f32 * self.duration);
+            let turn = Quat::from_rotation_y(pass_yaw * n as f32);
+            let mut position = pass_start + turn * (offset * scale);
+            if native <= 1e-6 {
+                position += turn * Vec3::Z * speed * t;
+            }
+            tracks.push(t, position, pass_yaw * n as f32 + yaw);
+        }
+        tracks.into_timeline(&self.timeline.name)
+    }
+
+    /// Root tracks walking `points` (on the ground plane) at `speed`, or the clip's own
+    /// speed, facing along the path and stopping at its end.
+    pub fn along_path(
+        &self,
+        points: &[Vec3],
+        speed: Option<f32>,
+        duration: f32,
+        sample_rate: f32,
+    ) -> Timeline {
+        let speed = speed.unwrap_or_else(|| self.speed());
+        let lengths = path_lengths(points);
+        let mut tracks = RootTracks::new();
+        for t in sample_times(duration, sample_rate) {
+            let (point, tangent) = path_sample(points, &lengths, speed * t);
+            tracks.push(t, point, tangent.x.atan2(tangent.z));
+        }
+        tracks.into_timeline(&self.timeline.name)
+    }
+}
+
+/// `0, 1/rate, ...` up to and including `duration`.
+fn sample_times(duration: f32, sample_rate: f32) -> impl Iterator<Item = f32> {
+    let samples = (duration.max(0.0) * sample_rate).ceil().max(1.0) as u32;
+    (0..=samples).map(move |s| (s as f32 / sample_rate.max(1e-3)).min(duration.max(0.0)))
+}
+
+/// `translate.x`, `translate.z` and `rotate.y` being keyed.
+struct RootTracks([Track; 3]);
+
+impl RootTracks {
+    fn new() -> Self {
+        Self(ROOT_CHANNELS.map(Track::new))
+    }
+
+    fn push(&mut self, time: f32, position: Vec3, yaw: f32) {
+        for (track, value) in self.0.iter_mut().zip([position.x, position.z, yaw]) {
+            track.add_keyframe(Keyframe::new(time, value));
+        }
+    }
+
+    fn into_timeline(self, name: &str) -> Timeline {
+        let mut tl = Timeline::new(name);
+        for track in self.0 {
+            tl.add_track(track);
+        }
+        tl
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cycle::TimelineCycle;
+    use crate::layer::AnimationLayer;
+    use crate::locomotion::{generate_locomotion, LocomotionParams};
+    use crate::performance::Performance;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    #[test]
+    fn test_extract_and_loop_at_speed() {
+        let params = LocomotionParams::walk();
+        let cycle = params.cycle_duration();
+        let mut walk = generate_locomotion(&params, cycle, 16, true);
+        let root = RootMotion::extract(&mut walk);
+        assert!(walk.get_value("translate.z", 0.5).is_none());
+        assert!(walk.get_value("leg.l.swing", 0.5).is_some());
+        assert!((root.speed() - params.speed()).abs() < 1e-3);
+
+        // Three passes at double speed keep moving forward instead of snapping back
+        let tl = root.at_speed(params.speed() * 2.0, cycle * 3.0, 24.0);
+        let z = |t: f32| tl.get_value("translate.z", t).unwrap();
+        assert!((z(cycle * 3.0) - params.speed() * 6.0 * cycle).abs() < 1e-2);
+        assert!(z(cycle * 1.5) > z(cycle * 0.9));
+
+        // An in-place clip with no travel still moves ahead at the requested speed
+        let still = RootMotion::extract(&mut Timeline::new("idle"));
+        let tl = still.at_speed(2.0, 1.0, 24.0);
+        assert!((tl.get_value("translate.z", 1.0).unwrap() - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_walk_along_path() {
+        let params = LocomotionParams::walk();
+        let mut walk = generate_locomotion(&params, params.cycle_duration(), 16, true);
+        let root = RootMotion::extract(&mut walk);
+        let path = [
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, 2.0),
+            Vec3::new(2.0, 0.0, 2.0),
+        ];
+        let route = root.along_path(&path, Some(1.0), 5.0, 24.0);
+        let at = |name: &str, t: f32| route.get_value(name, t).unwrap();
+        assert!((at("translate.z", 1.0) - 1.0).abs() < 1e-4);
+        assert!((at("translate.x", 3.0) - 1.0).abs() < 1e-4);
+        assert!((at("rotate.y", 3.0) - std::f32::consts::FRAC_PI_2).abs() < 1e-4);
+        // Stops at the end of the path
+        assert!((at("translate.x", 5.0) - 2.0).abs() < 1e-4);
+
+        // The in-place cycle loops while the root layer keeps walking
+        let mut sg = SceneGraph::new();
+        let id = sg.add_actor(
+            Actor::new("walker", SdfNode::sphere(0.3))
+                .with_timeline(walk)
+                .with_cycle(TimelineCycle::repeat())
+                .with_layer(AnimationLayer::new("root", route)),
+        );
+        let p = Performance::from(&sg).actor_position_at(id, 3.0);
+        assert!((p.x - 1.0).abs() < 1e-4 && (p.z - 2.0).abs() < 1e-4);
+    }
+}
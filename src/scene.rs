@@ -1,8 +1,19 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
 use alice_sdf::animation::{AnimatedSdf, Timeline};
 use alice_sdf::SdfNode;
 use glam::{Quat, Vec3};
 use serde::{Deserialize, Serialize};
 
+use crate::blend::Crossfade;
+use crate::clip::ClipLibrary;
+use crate::cycle::TimelineCycle;
+use crate::layer::{self, AnimationLayer};
+use crate::lip_sync::VisemeProfile;
+use crate::mouth::MouthBinding;
+use crate::units::Units;
+
 /// Unique actor identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ActorId(pub u32);
@@ -47,6 +58,39 @@ pub struct Actor {
     pub local_transform: ActorTransform,
     pub parent: Option<ActorId>,
     pub visible: bool,
+    /// Mouth sub-node driven by lip sync tracks.
+    pub mouth: Option<MouthBinding>,
+    /// Per-character viseme calibration used when generating lip sync timelines.
+    pub viseme_profile: Option<VisemeProfile>,
+    /// Loops the timeline (grass, flags, crowd cycles) instead of holding its last key.
+    pub cycle: Option<TimelineCycle>,
+    /// Additive layers summed over the timeline, e.g. breathing over any pose.
+    pub layers: Vec<AnimationLayer>,
+    /// Outgoing timeline still blending into `timeline`.
+    pub crossfade: Option<Crossfade>,
+}
+
+/// Actor layout of version 1 episodes, before mouths, cycles, layers and crossfades.
+#[derive(Deserialize)]
+pub(crate) struct ActorV1 {
+    name: String,
+    base_sdf: SdfNode,
+    timeline: Option<Timeline>,
+    local_transform: ActorTransform,
+    parent: Option<ActorId>,
+    visible: bool,
+}
+
+impl From<ActorV1> for Actor {
+    fn from(v1: ActorV1) -> Self {
+        Self {
+            timeline: v1.timeline,
+            local_transform: v1.local_transform,
+            parent: v1.parent,
+            visible: v1.visible,
+            ..Actor::new(v1.name, v1.base_sdf)
+        }
+    }
 }
 
 impl Actor {
@@ -58,6 +102,11 @@ impl Actor {
             local_transform: ActorTransform::default(),
             parent: None,
             visible: true,
+            mouth: None,
+            viseme_profile: None,
+            cycle: None,
+            layers: Vec::new(),
+            crossfade: None,
         }
     }
 
@@ -79,17 +128,148 @@ impl Actor {
         self
     }
 
+    /// Bind lip sync mouth tracks to a mouth sub-node.
+    pub fn with_mouth(mut self, mouth: MouthBinding) -> Self {
+        self.mouth = Some(mouth);
+        self
+    }
+
+    /// Set viseme calibration profile.
+    pub fn with_viseme_profile(mut self, profile: VisemeProfile) -> Self {
+        self.viseme_profile = Some(profile);
+        self
+    }
+
+    /// Loop the timeline with `cycle`.
+    pub fn with_cycle(mut self, cycle: TimelineCycle) -> Self {
+        self.cycle = Some(cycle);
+        self
+    }
+
+    /// Add an additive layer on top of the existing ones.
+    pub fn with_layer(mut self, layer: AnimationLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+
+    /// Switch to `timeline`, blending out of the current one over `duration` seconds from
+    /// scene time `start` (replacing any earlier fade). The cycle carries over to both.
+    pub fn crossfade_to(&mut self, timeline: Timeline, start: f32, duration: f32) {
+        let from = self
+            .timeline
+            .replace(timeline)
+            .unwrap_or_else(|| Timeline::new("rest"));
+        let mut fade = Crossfade::new(from, start, duration);
+        fade.from_cycle = self.cycle;
+        self.crossfade = Some(fade);
+    }
+
+    /// Value of track `name` at scene `time`: the timeline (through its cycle, blended out
+    /// of any active crossfade) plus layers. Clip tracks are not included; sample through
+    /// [`Performance`](crate::performance::Performance) for those.
+    pub fn track_value(&self, name: &str, time: f32) -> Option<f32> {
+        layer::add_offsets(self.timeline_value(name, time), &self.layers, name, time)
+    }
+
+    /// [`track_value`](Self::track_value) without layers.
+    pub fn timeline_value(&self, name: &str, time: f32) -> Option<f32> {
+        let base = self
+            .timeline
+            .as_ref()
+            .and_then(|tl| tl.get_value(name, self.local_time(time)));
+        match &self.crossfade {
+            Some(fade) if fade.is_active(time) => fade.blend(name, time, base),
+            _ => base,
+        }
+    }
+
+    /// Every track's [`track_value`](Self::track_value) at `time`, frozen into single-key
+    /// tracks.
+    pub fn pose_at(&self, time: f32) -> Timeline {
+        let fading = self.crossfade.as_ref().filter(|f| f.is_active(time));
+        let sources = self
+            .timeline
+            .iter()
+            .chain(fading.map(|f| &f.from))
+            .chain(self.layers.iter().map(|l| &l.timeline));
+        let name = self.timeline.as_ref().map_or("pose", |tl| tl.name.as_str());
+        layer::freeze(name, sources, time, |track| self.track_value(track, time))
+    }
+
+    /// Move the actor's animation by `offset` seconds: timeline, layers and crossfade.
+    /// Looping timelines shift their cycle phase, the rest their keys.
+    pub fn shift_time(&mut self, offset: f32) {
+        shift_animation(self.timeline.as_mut(), self.cycle.as_mut(), offset);
+        for layer in &mut self.layers {
+            shift_animation(Some(&mut layer.timeline), layer.cycle.as_mut(), offset);
+        }
+        if let Some(fade) = &mut self.crossfade {
+            fade.start += offset;
+            shift_animation(Some(&mut fade.from), fade.from_cycle.as_mut(), offset);
+        }
+    }
+
+    /// Timeline time for scene `time`, after the actor's cycle (if any).
+    #[inline]
+    pub fn local_time(&self, time: f32) -> f32 {
+        match (&self.cycle, &self.timeline) {
+            (Some(cycle), Some(tl)) => cycle.map_time(time, tl.duration()),
+            _ => time,
+        }
+    }
+
     /// Evaluate this actor's SDF at a given time.
     /// If a timeline is set, produces an AnimatedSdf.evaluate_at() result.
     /// Otherwise returns the base SDF.
+    /// A mouth binding is applied to the body before the timeline's transforms.
+    /// Time goes through the actor's cycle first.
     #[inline]
     pub fn evaluate_sdf(&self, time: f32) -> SdfNode {
+        let fading = self.crossfade.as_ref().is_some_and(|f| f.is_active(time));
+        if fading || !self.layers.is_empty() {
+            // Freeze the blended pose into a timeline so mouth and transforms read it as usual
+            return self.evaluate_pose(self.pose_at(time), time);
+        }
+        let time = self.local_time(time);
+        let body = match &self.mouth {
+            Some(mouth) => mouth.apply(self.base_sdf.clone(), self.timeline.as_ref(), time),
+            None => self.base_sdf.clone(),
+        };
         match &self.timeline {
             Some(tl) => {
-                let animated = AnimatedSdf::new(self.base_sdf.clone(), tl.clone());
+                let animated = AnimatedSdf::new(body, tl.clone());
                 animated.evaluate_at(time)
             }
+            None => body,
+        }
+    }
+
+    /// SDF posed by `pose` (single-key tracks, e.g. from [`Actor::pose_at`]) instead of the
+    /// actor's own animation.
+    pub(crate) fn evaluate_pose(&self, pose: Timeline, time: f32) -> SdfNode {
+        let body = match &self.mouth {
+            Some(mouth) => mouth.apply(self.base_sdf.clone(), Some(&pose), time),
             None => self.base_sdf.clone(),
+        };
+        AnimatedSdf::new(body, pose).evaluate_at(time)
+    }
+}
+
+/// Shift `timeline` by `offset` seconds: through the phase of `cycle` when it loops (its
+/// period depends on the key range), otherwise by moving every key.
+fn shift_animation(
+    timeline: Option<&mut Timeline>,
+    cycle: Option<&mut TimelineCycle>,
+    offset: f32,
+) {
+    match cycle {
+        Some(cycle) => cycle.offset -= offset,
+        None => {
+            for track in timeline.into_iter().flat_map(|tl| tl.tracks.iter_mut()) {
+                for key in &mut track.keyframes {
+                    key.time += offset;
+                }
+            }
         }
     }
 }
@@ -101,6 +281,31 @@ pub struct SceneGraph {
     actors: Vec<Option<Actor>>,
     next_id: u32,
     pub root_actors: Vec<ActorId>,
+    /// Clips placed by the director's clip tracks.
+    pub clips: ClipLibrary,
+}
+
+/// Scene graph layout of version 1 episodes, before the clip library.
+#[derive(Deserialize)]
+pub(crate) struct SceneGraphV1 {
+    actors: Vec<Option<ActorV1>>,
+    next_id: u32,
+    root_actors: Vec<ActorId>,
+}
+
+impl From<SceneGraphV1> for SceneGraph {
+    fn from(v1: SceneGraphV1) -> Self {
+        Self {
+            actors: v1
+                .actors
+                .into_iter()
+                .map(|actor| actor.map(Actor::from))
+                .collect(),
+            next_id: v1.next_id,
+            root_actors: v1.root_actors,
+            clips: ClipLibrary::default(),
+        }
+    }
 }
 
 impl SceneGraph {
@@ -109,6 +314,7 @@ impl SceneGraph {
             actors: Vec::new(),
             next_id: 0,
             root_actors: Vec::new(),
+            clips: ClipLibrary::new(),
         }
     }
 
@@ -139,6 +345,40 @@ impl SceneGraph {
         self.actors.get_mut(id.0 as usize).and_then(|a| a.as_mut())
     }
 
+    /// Move every actor's animation by `offset` seconds (see [`Actor::shift_time`]). Library
+    /// clips are placed in cut-local time and stay put.
+    pub fn shift_time(&mut self, offset: f32) {
+        for actor in self.actors.iter_mut().flatten() {
+            actor.shift_time(offset);
+        }
+    }
+
+    /// Re-express actors authored under `from` in `to`'s convention: local transforms,
+    /// base SDFs and `translate.*` / `rotate.*` tracks (see `Units::convert_rotation_tracks`),
+    /// including those of library clips.
+    pub fn convert_units(&mut self, from: &Units, to: &Units) {
+        let convert = |timeline: &mut Timeline| {
+            to.convert_tracks(timeline, "translate", from);
+            to.convert_rotation_tracks(timeline, from);
+        };
+        for actor in self.actors.iter_mut().flatten() {
+            actor.local_transform = to.convert_transform(&actor.local_transform, from);
+            actor.base_sdf = to.convert_sdf(actor.base_sdf.clone(), from);
+            if let Some(timeline) = &mut actor.timeline {
+                convert(timeline);
+            }
+            for layer in &mut actor.layers {
+                convert(&mut layer.timeline);
+            }
+            if let Some(fade) = &mut actor.crossfade {
+                convert(&mut fade.from);
+            }
+        }
+        for clip in self.clips.iter_mut() {
+            convert(&mut clip.timeline);
+        }
+    }
+
     /// Find an actor by name.
     pub fn find_by_name(&self, name: &str) -> Option<ActorId> {
         for (i, slot) in self.actors.iter().enumerate() {
@@ -195,15 +435,39 @@ impl SceneGraph {
         (sum, count)
     }
 
+    /// Evaluate each visible actor's SDF separately (per-object AOVs, picking).
+    pub fn evaluate_actors(&self, time: f32) -> Vec<(ActorId, SdfNode)> {
+        self.evaluate_actors_with(|_, actor| actor.evaluate_sdf(time))
+    }
+
+    /// [`evaluate_actors`](Self::evaluate_actors) with a custom per-actor evaluation.
+    pub fn evaluate_actors_with(
+        &self,
+        evaluate: impl Fn(ActorId, &Actor) -> SdfNode,
+    ) -> Vec<(ActorId, SdfNode)> {
+        self.actors
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|a| (ActorId(i as u32), a)))
+            .filter(|(_, actor)| actor.visible)
+            .map(|(id, actor)| (id, evaluate(id, actor)))
+            .collect()
+    }
+
     /// Evaluate the entire scene at a given time, producing a union of all visible actor SDFs.
     pub fn evaluate_scene(&self, time: f32) -> SdfNode {
+        self.evaluate_scene_with(|_, actor| actor.evaluate_sdf(time))
+    }
+
+    /// [`evaluate_scene`](Self::evaluate_scene) with a custom per-actor evaluation.
+    pub fn evaluate_scene_with(&self, evaluate: impl Fn(ActorId, &Actor) -> SdfNode) -> SdfNode {
         let mut nodes: Vec<SdfNode> = Vec::with_capacity(self.actors.len());
-        for slot in &self.actors {
+        for (i, slot) in self.actors.iter().enumerate() {
             if let Some(actor) = slot {
                 if !actor.visible {
                     continue;
                 }
-                nodes.push(actor.evaluate_sdf(time));
+                nodes.push(evaluate(ActorId(i as u32), actor));
             }
         }
         match nodes.len() {
@@ -229,6 +493,7 @@ impl Default for SceneGraph {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::performance::Performance;
 
     #[test]
     fn test_add_and_find_actor() {
@@ -275,4 +540,73 @@ mod tests {
             _ => panic!("Expected Union"),
         }
     }
+
+    #[test]
+    fn test_layer_rides_on_base_keys() {
+        use alice_sdf::animation::{Keyframe, Track};
+        let mut walk = Timeline::new("walk");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(2.0, 4.0));
+        walk.add_track(x);
+        let mut breath = Timeline::new("breath");
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.0));
+        y.add_keyframe(Keyframe::new(1.0, 0.5));
+        breath.add_track(y);
+
+        let mut sg = SceneGraph::new();
+        let actor = Actor::new("hero", SdfNode::sphere(0.25))
+            .with_timeline(walk)
+            .with_layer(AnimationLayer::new("breath", breath).with_weight(0.5));
+        let id = sg.add_actor(actor);
+        let position = Performance::from(&sg).actor_position_at(id, 1.0);
+        assert!((position - Vec3::new(2.0, 0.25, 0.0)).length() < 1e-5);
+        let sdf = sg.get_actor(id).unwrap().evaluate_sdf(1.0);
+        assert!(alice_sdf::eval(&sdf, position) < -0.2);
+        // Primary keys are untouched
+        let walk = sg.get_actor(id).unwrap().timeline.as_ref().unwrap();
+        assert_eq!(walk.tracks.len(), 1);
+    }
+
+    #[test]
+    fn test_crossfade_between_timelines() {
+        use alice_sdf::animation::{Keyframe, Track};
+        let clip = |name: &str, x: f32| {
+            let mut tl = Timeline::new(name);
+            let mut track = Track::new("translate.x");
+            track.add_keyframe(Keyframe::new(0.0, x));
+            tl.add_track(track);
+            tl
+        };
+        let mut sg = SceneGraph::new();
+        let id = sg
+            .add_actor(Actor::new("hero", SdfNode::sphere(0.25)).with_timeline(clip("idle", 0.0)));
+        let actor = sg.get_actor_mut(id).unwrap();
+        actor.crossfade_to(clip("run", 4.0), 1.0, 0.5);
+        assert_eq!(actor.timeline.as_ref().unwrap().name, "run");
+
+        let own = Performance::from(&sg);
+        assert_eq!(own.actor_position_at(id, 0.5).x, 0.0);
+        assert!((own.actor_position_at(id, 1.25).x - 2.0).abs() < 1e-5);
+        assert_eq!(own.actor_position_at(id, 2.0).x, 4.0);
+        let mid = sg.get_actor(id).unwrap().evaluate_sdf(1.25);
+        assert!(alice_sdf::eval(&mid, Vec3::new(2.0, 0.0, 0.0)) < 0.0);
+    }
+
+    #[test]
+    fn test_convert_units_reaches_library_clips() {
+        use crate::clip::AnimationClip;
+        use crate::units::UpAxis;
+        use alice_sdf::animation::{Keyframe, Track};
+        let mut hop = Timeline::new("hop");
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.5, 100.0));
+        hop.add_track(y);
+        let mut sg = SceneGraph::new();
+        sg.clips.insert(AnimationClip::new("hop", hop));
+        sg.convert_units(&Units::centimeters(UpAxis::Y), &Units::meters(UpAxis::Z));
+        let hop = &sg.clips.get("hop").unwrap().timeline;
+        assert!((hop.get_value("translate.z", 0.5).unwrap() - 1.0).abs() < 1e-5);
+    }
 }
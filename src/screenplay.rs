@@ -0,0 +1,478 @@
+//! Screenplay DSL: a line-based draft format for writers that compiles into a
+//! `Director` with scenes, cuts, shot framing, camera moves and dialogue.
+//!
+//! ```text
+//! EPISODE The Rooftop
+//! SCENE rooftop
+//! CUT 0-3s closeup hero
+//! CAMERA push-in 0.5
+//! LINE hero: Is anyone there?
+//! CUT 3-8s wide hero rival
+//! CAMERA orbit 6
+//! LINE 4-5.5 rival: Took you long enough.
+//! ```
+//!
+//! Times are episode seconds (`3`, `3s`, `1.5s`) or `m:ss(.f)`. `LINE` without a time
+//! follows the previous line of the cut. Words after the shot size name scene actors.
+
+use glam::Vec3;
+
+use crate::camera::{CameraTrack, CameraWork};
+use crate::director::{Cut, DialogueLine, Director, Scene};
+use crate::error::AnimationError;
+use crate::performance::Performance;
+use crate::scene::{ActorId, SceneGraph};
+
+/// Seconds per character when estimating an untimed line.
+const SECONDS_PER_CHAR: f32 = 0.12;
+/// Shortest estimated line.
+const MIN_LINE_SECONDS: f32 = 1.0;
+/// Default speed of a `CAMERA push-in` / `pull-out` (units per second).
+const DEFAULT_DOLLY: f32 = 0.5;
+
+/// Shot size; sets how far the camera sits from the framed actors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShotSize {
+    ExtremeCloseUp,
+    CloseUp,
+    Medium,
+    Wide,
+    Establishing,
+}
+
+impl ShotSize {
+    fn parse(word: &str) -> Option<Self> {
+        match word.to_ascii_lowercase().as_str() {
+            "ecu" | "extreme-closeup" => Some(ShotSize::ExtremeCloseUp),
+            "cu" | "closeup" | "close-up" => Some(ShotSize::CloseUp),
+            "ms" | "medium" => Some(ShotSize::Medium),
+            "ws" | "wide" => Some(ShotSize::Wide),
+            "establishing" => Some(ShotSize::Establishing),
+            _ => None,
+        }
+    }
+
+    /// Camera distance from the subject.
+    pub fn distance(&self) -> f32 {
+        match self {
+            ShotSize::ExtremeCloseUp => 1.0,
+            ShotSize::CloseUp => 2.0,
+            ShotSize::Medium => 4.0,
+            ShotSize::Wide => 8.0,
+            ShotSize::Establishing => 16.0,
+        }
+    }
+}
+
+/// A `LINE` statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptLine {
+    /// 1-based source line.
+    pub line: usize,
+    pub time: Option<(f32, f32)>,
+    pub speaker: String,
+    pub text: String,
+}
+
+/// A `CUT` statement with the `CAMERA` and `LINE` statements that follow it.
+#[derive(Debug, Clone)]
+pub struct ScriptCut {
+    pub line: usize,
+    pub name: String,
+    pub start: f32,
+    pub end: f32,
+    pub shot: Option<ShotSize>,
+    pub actors: Vec<String>,
+    pub camera: Vec<CameraWork>,
+    pub lines: Vec<ScriptLine>,
+}
+
+/// A `SCENE` block; cuts before the first `SCENE` belong to an unnamed scene.
+#[derive(Debug, Clone)]
+pub struct ScriptScene {
+    pub name: Option<String>,
+    pub cuts: Vec<ScriptCut>,
+}
+
+/// Parsed screenplay.
+#[derive(Debug, Clone, Default)]
+pub struct Screenplay {
+    pub title: Option<String>,
+    pub scenes: Vec<ScriptScene>,
+}
+
+fn error(line: usize, msg: impl std::fmt::Display) -> AnimationError {
+    AnimationError::InvalidInput(format!("Line {line}: {msg}"))
+}
+
+/// `3`, `3s`, `1.5s` or `m:ss(.f)`.
+fn parse_time(text: &str) -> Option<f32> {
+    let text = text.trim().trim_end_matches('s');
+    match text.split_once(':') {
+        Some((m, s)) => Some(m.parse::<f32>().ok()? * 60.0 + s.parse::<f32>().ok()?),
+        None => text.parse().ok(),
+    }
+    .filter(|t: &f32| t.is_finite() && *t >= 0.0)
+}
+
+/// `start-end` (either side may carry an `s` suffix).
+fn parse_range(text: &str, line: usize) -> crate::error::Result<(f32, f32)> {
+    let (a, b) = text
+        .split_once('-')
+        .ok_or_else(|| error(line, format!("expected start-end, got {text:?}")))?;
+    let start = parse_time(a).ok_or_else(|| error(line, format!("bad time {a:?}")))?;
+    let end = parse_time(b).ok_or_else(|| error(line, format!("bad time {b:?}")))?;
+    if end <= start {
+        return Err(error(line, format!("range {text:?} ends before it starts")));
+    }
+    Ok((start, end))
+}
+
+fn parse_camera(args: &str, line: usize) -> crate::error::Result<CameraWork> {
+    let mut words = args.split_whitespace();
+    let name = words
+        .next()
+        .ok_or_else(|| error(line, "CAMERA needs a move"))?
+        .to_ascii_lowercase();
+    let amount = match words.next() {
+        Some(w) => Some(
+            w.parse::<f32>()
+                .map_err(|_| error(line, format!("bad amount {w:?}")))?,
+        ),
+        None => None,
+    };
+    let work = match name.as_str() {
+        "static" => CameraWork::Static,
+        "push-in" => CameraWork::Dolly {
+            speed: amount.unwrap_or(DEFAULT_DOLLY),
+        },
+        "pull-out" => CameraWork::Dolly {
+            speed: -amount.unwrap_or(DEFAULT_DOLLY),
+        },
+        "pan-left" => CameraWork::Pan {
+            speed: -amount.unwrap_or(1.0),
+        },
+        "pan-right" => CameraWork::Pan {
+            speed: amount.unwrap_or(1.0),
+        },
+        "tilt-up" => CameraWork::Tilt {
+            speed: amount.unwrap_or(0.5),
+        },
+        "tilt-down" => CameraWork::Tilt {
+            speed: -amount.unwrap_or(0.5),
+        },
+        "zoom-in" => CameraWork::Zoom {
+            target_fov: amount.unwrap_or(0.5),
+        },
+        "zoom-out" => CameraWork::Zoom {
+            target_fov: amount.unwrap_or(1.1),
+        },
+        "orbit" => CameraWork::Orbit {
+            radius: amount.unwrap_or(5.0),
+            speed: 0.5,
+        },
+        "shake" => CameraWork::Shake {
+            amplitude: amount.unwrap_or(0.05),
+            frequency: 12.0,
+        },
+        other => return Err(error(line, format!("unknown camera move {other:?}"))),
+    };
+    Ok(work)
+}
+
+/// The cut `CAMERA` / `LINE` statements attach to.
+fn current_cut<'a>(
+    play: &'a mut Screenplay,
+    line: usize,
+    keyword: &str,
+) -> crate::error::Result<&'a mut ScriptCut> {
+    play.scenes
+        .last_mut()
+        .and_then(|s| s.cuts.last_mut())
+        .ok_or_else(|| error(line, format!("{keyword} before any CUT")))
+}
+
+/// Parse screenplay text. Blank lines and `#` comments are ignored; keywords are case-insensitive.
+///
+/// `CAMERA` moves are spread over the whole cut. Amounts are the `CameraWork` parameters:
+/// units per second for push/pull, pans and tilts, target fov for zooms and radius for orbits.
+pub fn parse_screenplay(source: &str) -> crate::error::Result<Screenplay> {
+    let mut play = Screenplay::default();
+    for (index, raw) in source.lines().enumerate() {
+        let line = index + 1;
+        let text = raw.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let (keyword, args) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let args = args.trim();
+        match keyword.to_ascii_uppercase().as_str() {
+            "EPISODE" => play.title = Some(args.to_string()),
+            "SCENE" => {
+                if args.is_empty() {
+                    return Err(error(line, "SCENE needs a name"));
+                }
+                play.scenes.push(ScriptScene {
+                    name: Some(args.to_string()),
+                    cuts: Vec::new(),
+                });
+            }
+            "CUT" => {
+                let (range, rest) = args.split_once(char::is_whitespace).unwrap_or((args, ""));
+                let (start, end) = parse_range(range, line)?;
+                let mut words = rest.split_whitespace().peekable();
+                let shot = words.peek().and_then(|w| ShotSize::parse(w));
+                if shot.is_some() {
+                    words.next();
+                }
+                let cut = ScriptCut {
+                    line,
+                    name: if rest.trim().is_empty() {
+                        format!("cut {line}")
+                    } else {
+                        rest.trim().to_string()
+                    },
+                    start,
+                    end,
+                    shot,
+                    actors: words.map(str::to_string).collect(),
+                    camera: Vec::new(),
+                    lines: Vec::new(),
+                };
+                if play.scenes.is_empty() {
+                    play.scenes.push(ScriptScene {
+                        name: None,
+                        cuts: Vec::new(),
+                    });
+                }
+                if let Some(scene) = play.scenes.last_mut() {
+                    scene.cuts.push(cut);
+                }
+            }
+            "CAMERA" => {
+                let work = parse_camera(args, line)?;
+                current_cut(&mut play, line, keyword)?.camera.push(work);
+            }
+            "LINE" => {
+                // The time range may itself contain ':' (m:ss), so take it off first
+                let (time, rest) = match args.split_once(char::is_whitespace) {
+                    Some((first, rest))
+                        if first.contains('-')
+                            && first.starts_with(|c: char| c.is_ascii_digit()) =>
+                    {
+                        (Some(parse_range(first, line)?), rest)
+                    }
+                    _ => (None, args),
+                };
+                let (speaker, text) = rest
+                    .split_once(':')
+                    .ok_or_else(|| error(line, "LINE needs 'speaker: text'"))?;
+                let speaker = speaker.trim();
+                if speaker.is_empty() {
+                    return Err(error(line, "LINE needs a speaker"));
+                }
+                if speaker.contains(char::is_whitespace) {
+                    return Err(error(line, "speaker names cannot contain spaces"));
+                }
+                let script_line = ScriptLine {
+                    line,
+                    time,
+                    speaker: speaker.to_string(),
+                    text: text.trim().to_string(),
+                };
+                current_cut(&mut play, line, keyword)?
+                    .lines
+                    .push(script_line);
+            }
+            other => return Err(error(line, format!("unknown keyword {other:?}"))),
+        }
+    }
+    Ok(play)
+}
+
+impl Screenplay {
+    /// Build a director against `scene`, resolving actor names.
+    pub fn compile(&self, scene: &SceneGraph) -> crate::error::Result<Director> {
+        let mut director = Director::new(self.title.clone().unwrap_or_default());
+        for script_scene in &self.scenes {
+            let mut cut_ids = Vec::with_capacity(script_scene.cuts.len());
+            for script_cut in &script_scene.cuts {
+                let actors: Vec<ActorId> = script_cut
+                    .actors
+                    .iter()
+                    .map(|name| {
+                        scene.find_by_name(name).ok_or_else(|| {
+                            error(script_cut.line, format!("unknown actor {name:?}"))
+                        })
+                    })
+                    .collect::<crate::error::Result<_>>()?;
+                let duration = script_cut.end - script_cut.start;
+                let mut camera = CameraTrack::default();
+                if let Some(shot) = script_cut.shot {
+                    let (sum, count) = actors.iter().fold((Vec3::ZERO, 0), |(sum, n), id| {
+                        (
+                            sum + Performance::from(scene).actor_position_at(*id, script_cut.start),
+                            n + 1,
+                        )
+                    });
+                    let target = if count > 0 {
+                        sum / count as f32
+                    } else {
+                        Vec3::ZERO
+                    };
+                    let d = shot.distance();
+                    camera.clear_keyframes();
+                    camera.add_keyframe(
+                        0.0,
+                        target + Vec3::new(0.0, 0.2 * d, d),
+                        target,
+                        std::f32::consts::FRAC_PI_4,
+                    );
+                }
+                for work in &script_cut.camera {
+                    camera.apply_preset(*work, 0.0, duration);
+                }
+                let cut = Cut::new(script_cut.name.clone(), script_cut.start, script_cut.end)
+                    .with_camera(camera)
+                    .with_actors(actors);
+                cut_ids.push(director.add_cut(cut));
+
+                let mut cursor = script_cut.start;
+                for script_line in &script_cut.lines {
+                    let (start, end) = script_line.time.unwrap_or_else(|| {
+                        let estimate = (script_line.text.chars().count() as f32 * SECONDS_PER_CHAR)
+                            .max(MIN_LINE_SECONDS);
+                        (cursor, (cursor + estimate).min(script_cut.end.max(cursor)))
+                    });
+                    cursor = end;
+                    director.add_dialogue(
+                        DialogueLine::new(start, end, script_line.text.clone())
+                            .with_speaker(script_line.speaker.clone()),
+                    );
+                }
+            }
+            if let Some(name) = &script_scene.name {
+                let mut compiled = Scene::new(name.clone());
+                compiled.cuts = cut_ids;
+                director.add_scene(compiled);
+            }
+        }
+        Ok(director)
+    }
+}
+
+/// Parse and compile in one step.
+pub fn compile_screenplay(source: &str, scene: &SceneGraph) -> crate::error::Result<Director> {
+    parse_screenplay(source)?.compile(scene)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::CutId;
+    use crate::scene::{Actor, ActorTransform};
+    use alice_sdf::SdfNode;
+
+    const SCRIPT: &str = "\
+# Episode 3 draft
+EPISODE The Rooftop
+SCENE rooftop
+CUT 0-3s closeup hero
+CAMERA push-in 0.5
+LINE hero: Is anyone there?
+LINE hero: Hello?
+CUT 3s-0:08 wide hero rival
+CAMERA orbit 6
+LINE 4-5.5 rival: Took you long enough.
+
+SCENE stairwell
+CUT 8-10 establishing
+";
+
+    fn cast() -> SceneGraph {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(0.5)));
+        sg.add_actor(
+            Actor::new("rival", SdfNode::sphere(0.5)).with_transform(ActorTransform {
+                position: Vec3::new(4.0, 0.0, 0.0),
+                ..ActorTransform::default()
+            }),
+        );
+        sg
+    }
+
+    #[test]
+    fn test_parse_screenplay() {
+        let play = parse_screenplay(SCRIPT).unwrap();
+        assert_eq!(play.title.as_deref(), Some("The Rooftop"));
+        assert_eq!(play.scenes.len(), 2);
+        let cuts = &play.scenes[0].cuts;
+        assert_eq!(cuts.len(), 2);
+        assert_eq!((cuts[1].start, cuts[1].end), (3.0, 8.0));
+        assert_eq!(cuts[0].shot, Some(ShotSize::CloseUp));
+        assert_eq!(cuts[1].actors, vec!["hero", "rival"]);
+        assert_eq!(cuts[0].lines.len(), 2);
+        assert_eq!(cuts[1].lines[0].time, Some((4.0, 5.5)));
+        assert_eq!(cuts[1].lines[0].speaker, "rival");
+        assert_eq!(play.scenes[1].cuts[0].name, "establishing");
+    }
+
+    #[test]
+    fn test_compile_screenplay() {
+        let director = compile_screenplay(SCRIPT, &cast()).unwrap();
+        assert_eq!(director.episode.name, "The Rooftop");
+        assert_eq!(director.cut_count(), 3);
+        assert_eq!(director.episode.scenes.len(), 2);
+        assert_eq!(director.episode.scenes[0].cuts, vec![CutId(0), CutId(1)]);
+
+        // Close-up frames the hero 2 units away, then pushes in at 0.5/s over the 3s cut
+        let cut = director.get_cut(CutId(0)).unwrap();
+        let start = cut.camera.evaluate(0.0);
+        assert_eq!(start.target, Vec3::ZERO);
+        assert!((start.position.distance(start.target) - 2.0f32.hypot(0.4)).abs() < 1e-4);
+        let end = cut.camera.evaluate(3.0);
+        assert!((start.position.distance(end.position) - 1.5).abs() < 1e-4);
+
+        // Wide shot centres on both actors
+        assert_eq!(
+            director
+                .get_cut(CutId(1))
+                .unwrap()
+                .camera
+                .evaluate(0.0)
+                .target
+                .x,
+            2.0
+        );
+        assert_eq!(director.get_cut(CutId(1)).unwrap().active_actors.len(), 2);
+
+        // Untimed lines follow each other from the cut start
+        let lines = &director.episode.dialogue;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].start_time, 0.0);
+        assert_eq!(lines[1].start_time, lines[0].end_time);
+        assert_eq!(lines[2].speaker.as_deref(), Some("rival"));
+    }
+
+    #[test]
+    fn test_screenplay_errors_name_the_line() {
+        let err = parse_screenplay("SCENE a\nLINE hero: hi\n").unwrap_err();
+        assert!(err.to_string().starts_with("Line 2:"));
+        let err = parse_screenplay("CUT 3-1 wide\n").unwrap_err();
+        assert!(err.to_string().contains("ends before it starts"));
+        let err = parse_screenplay("CUT 0-1\nCAMERA barrel-roll\n").unwrap_err();
+        assert!(err.to_string().starts_with("Line 2:"));
+        let err = compile_screenplay("CUT 0-1 medium villain\n", &cast()).unwrap_err();
+        assert_eq!(err.to_string(), "Line 1: unknown actor \"villain\"");
+    }
+
+    #[test]
+    fn test_line_times_in_minutes() {
+        let play =
+            parse_screenplay("CUT 0-2:00 wide\nLINE 1:00-1:05 hero: Hello: there\n").unwrap();
+        let line = &play.scenes[0].cuts[0].lines[0];
+        assert_eq!(line.time, Some((60.0, 65.0)));
+        assert_eq!(line.speaker, "hero");
+        assert_eq!(line.text, "Hello: there");
+    }
+}
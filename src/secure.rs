@@ -0,0 +1,263 @@
+//! Signed and/or encrypted ANIM containers.
+//!
+//! Same 16-byte header as plain episodes; the flags field records the scheme:
+//! - `FLAG_ENCRYPTED`: body is `[Nonce 12B][ChaCha20-Poly1305 ciphertext + tag]`,
+//!   authenticated with header bytes 0..8 (magic, version, flags) as AAD.
+//! - `FLAG_SIGNED`: a 64-byte Ed25519 signature over `header || body` follows the body.
+
+use std::io::{Read, Write};
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use ed25519_dalek::{Signature, Signer, Verifier};
+pub use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use crate::episode::{
+    frame_flags, frame_prefix, read_frame_body, read_header, write_frame, EpisodePackage,
+    FLAG_ENCRYPTED, FLAG_SIGNED,
+};
+use crate::error::AnimationError;
+
+/// Ed25519 signature length in bytes.
+const SIGNATURE_LEN: usize = 64;
+/// ChaCha20-Poly1305 nonce length in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Authenticated encryption parameters. The nonce must never be reused with the same key.
+#[derive(Debug, Clone, Copy)]
+pub struct EncryptionParams<'a> {
+    pub key: &'a [u8; 32],
+    pub nonce: [u8; NONCE_LEN],
+}
+
+/// Options for writing a protected episode.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SealOptions<'a> {
+    pub encryption: Option<EncryptionParams<'a>>,
+    pub signing_key: Option<&'a SigningKey>,
+}
+
+/// Options for reading a protected episode.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenOptions<'a> {
+    /// Required if the episode is encrypted.
+    pub decryption_key: Option<&'a [u8; 32]>,
+    /// If set, the episode must carry a valid signature from this key. Signed episodes
+    /// need it unless `skip_verification` is set.
+    pub verifying_key: Option<&'a VerifyingKey>,
+    /// Open signed episodes without a `verifying_key`, trusting their content unchecked
+    /// (e.g. for inspection tools).
+    pub skip_verification: bool,
+}
+
+/// Serialize an episode, optionally encrypting and/or signing it.
+pub fn seal_episode<W: Write>(
+    episode: &EpisodePackage,
+    writer: &mut W,
+    options: &SealOptions,
+) -> crate::error::Result<usize> {
+    let plain = bincode::serialize(episode)?;
+
+    let mut flags = 0u16;
+    if options.encryption.is_some() {
+        flags |= FLAG_ENCRYPTED;
+    }
+    if options.signing_key.is_some() {
+        flags |= FLAG_SIGNED;
+    }
+
+    let body = match options.encryption {
+        Some(params) => {
+            // AAD only covers the fields known before encryption (magic, version, flags)
+            let prefix = frame_prefix(flags);
+            let cipher = ChaCha20Poly1305::new(Key::from_slice(params.key));
+            let ciphertext = cipher
+                .encrypt(
+                    Nonce::from_slice(&params.nonce),
+                    Payload {
+                        msg: &plain,
+                        aad: &prefix,
+                    },
+                )
+                .map_err(|_| AnimationError::InvalidInput("Encryption failed".into()))?;
+            let mut body = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+            body.extend_from_slice(&params.nonce);
+            body.extend_from_slice(&ciphertext);
+            body
+        }
+        None => plain,
+    };
+
+    let mut framed = Vec::with_capacity(16 + body.len());
+    write_frame(&mut framed, flags, &body)?;
+    writer.write_all(&framed)?;
+    let mut written = framed.len();
+
+    if let Some(key) = options.signing_key {
+        let signature = key.sign(&framed);
+        writer.write_all(&signature.to_bytes())?;
+        written += SIGNATURE_LEN;
+    }
+    Ok(written)
+}
+
+/// Deserialize an episode, verifying and/or decrypting it as indicated by its header flags.
+///
+/// A signed episode without `options.verifying_key` is `Protected` unless
+/// `options.skip_verification` is set.
+pub fn open_episode<R: Read>(
+    reader: &mut R,
+    options: &OpenOptions,
+) -> crate::error::Result<EpisodePackage> {
+    let header = read_header(reader)?;
+    let body = read_frame_body(&header, reader)?;
+    let flags = frame_flags(&header);
+
+    if flags & FLAG_SIGNED != 0 {
+        let mut sig_bytes = [0u8; SIGNATURE_LEN];
+        reader.read_exact(&mut sig_bytes)?;
+        match options.verifying_key {
+            Some(verifying_key) => {
+                let mut message = Vec::with_capacity(16 + body.len());
+                message.extend_from_slice(&header);
+                message.extend_from_slice(&body);
+                verifying_key
+                    .verify(&message, &Signature::from_bytes(&sig_bytes))
+                    .map_err(|_| AnimationError::Corrupt("Signature verification failed".into()))?;
+            }
+            None if options.skip_verification => {}
+            None => return Err(AnimationError::Protected { flags }),
+        }
+    } else if options.verifying_key.is_some() {
+        return Err(AnimationError::Corrupt("Episode is not signed".into()));
+    }
+
+    let plain = if flags & FLAG_ENCRYPTED != 0 {
+        let key = options
+            .decryption_key
+            .ok_or(AnimationError::Protected { flags })?;
+        if body.len() < NONCE_LEN {
+            return Err(AnimationError::Truncated("encrypted body"));
+        }
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+        cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &header[0..8],
+                },
+            )
+            .map_err(|_| AnimationError::Corrupt("Decryption failed".into()))?
+    } else {
+        body
+    };
+
+    let mut episode: EpisodePackage = bincode::deserialize(&plain)?;
+    episode.seed_shake();
+    Ok(episode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::{deserialize_episode, EpisodeMetadata};
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Premium");
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let meta = EpisodeMetadata::new("Premium", 1, 5.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    const KEY: [u8; 32] = [7u8; 32];
+
+    #[test]
+    fn test_encrypt_sign_roundtrip() {
+        let signing_key = SigningKey::from_bytes(&[42u8; 32]);
+        let verifying_key = signing_key.verifying_key();
+        let seal = SealOptions {
+            encryption: Some(EncryptionParams {
+                key: &KEY,
+                nonce: [1u8; 12],
+            }),
+            signing_key: Some(&signing_key),
+        };
+        let mut buf = Vec::new();
+        let written = seal_episode(&make_test_episode(), &mut buf, &seal).unwrap();
+        assert_eq!(written, buf.len());
+
+        // Plain loader refuses protected content
+        assert!(deserialize_episode(&mut std::io::Cursor::new(&buf)).is_err());
+
+        let open = OpenOptions {
+            decryption_key: Some(&KEY),
+            verifying_key: Some(&verifying_key),
+            skip_verification: false,
+        };
+        let restored = open_episode(&mut std::io::Cursor::new(&buf), &open).unwrap();
+        assert_eq!(restored.metadata.title, "Premium");
+    }
+
+    #[test]
+    fn test_wrong_key_fails() {
+        let seal = SealOptions {
+            encryption: Some(EncryptionParams {
+                key: &KEY,
+                nonce: [2u8; 12],
+            }),
+            signing_key: None,
+        };
+        let mut buf = Vec::new();
+        seal_episode(&make_test_episode(), &mut buf, &seal).unwrap();
+
+        let wrong = [8u8; 32];
+        let open = OpenOptions {
+            decryption_key: Some(&wrong),
+            verifying_key: None,
+            skip_verification: false,
+        };
+        assert!(open_episode(&mut std::io::Cursor::new(&buf), &open).is_err());
+        assert!(open_episode(&mut std::io::Cursor::new(&buf), &OpenOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_tampered_signature_fails() {
+        let signing_key = SigningKey::from_bytes(&[3u8; 32]);
+        let verifying_key = signing_key.verifying_key();
+        let seal = SealOptions {
+            encryption: None,
+            signing_key: Some(&signing_key),
+        };
+        let mut buf = Vec::new();
+        seal_episode(&make_test_episode(), &mut buf, &seal).unwrap();
+        let last = buf.len() - 1;
+        buf[last] ^= 0xFF;
+
+        let open = OpenOptions {
+            decryption_key: None,
+            verifying_key: Some(&verifying_key),
+            skip_verification: false,
+        };
+        assert!(open_episode(&mut std::io::Cursor::new(&buf), &open).is_err());
+
+        // Without a key the signature cannot be checked: refused unless explicitly skipped
+        assert!(matches!(
+            open_episode(&mut std::io::Cursor::new(&buf), &OpenOptions::default()),
+            Err(AnimationError::Protected { flags: FLAG_SIGNED })
+        ));
+        let unchecked = OpenOptions {
+            skip_verification: true,
+            ..OpenOptions::default()
+        };
+        let restored = open_episode(&mut std::io::Cursor::new(&buf), &unchecked).unwrap();
+        assert_eq!(restored.metadata.title, "Premium");
+    }
+}
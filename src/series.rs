@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use alice_sdf::SdfNode;
+use serde::{Deserialize, Serialize};
+
+use crate::episode::{read_sized, EpisodePackage};
+use crate::error::AnimationError;
+use crate::scene::ActorId;
+
+/// Series archive magic bytes.
+const SERIES_MAGIC: [u8; 4] = *b"ASER";
+/// Series archive version.
+const SERIES_VERSION: u16 = 1;
+
+/// Series-level metadata.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesMetadata {
+    pub title: String,
+    pub season: u32,
+}
+
+impl SeriesMetadata {
+    pub fn new(title: impl Into<String>, season: u32) -> Self {
+        Self {
+            title: title.into(),
+            season,
+        }
+    }
+}
+
+/// One row of the series index (readable without decoding any episode).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesIndexEntry {
+    pub episode_number: u32,
+    pub title: String,
+    pub duration_seconds: f32,
+    pub actor_count: usize,
+    pub cut_count: usize,
+}
+
+/// Index section of a series archive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesIndex {
+    pub metadata: SeriesMetadata,
+    pub entries: Vec<SeriesIndexEntry>,
+    /// Number of distinct actor SDFs in the shared asset pool.
+    pub shared_assets: usize,
+}
+
+/// Multiple episodes distributed as one artifact (e.g. a whole season).
+#[derive(Debug, Clone)]
+pub struct SeriesPackage {
+    pub metadata: SeriesMetadata,
+    episodes: Vec<EpisodePackage>,
+}
+
+impl SeriesPackage {
+    pub fn new(metadata: SeriesMetadata) -> Self {
+        Self {
+            metadata,
+            episodes: Vec::new(),
+        }
+    }
+
+    /// Add an episode. Episodes are kept sorted by episode number.
+    pub fn add_episode(&mut self, episode: EpisodePackage) {
+        let number = episode.metadata.episode_number;
+        let pos = self
+            .episodes
+            .partition_point(|e| e.metadata.episode_number <= number);
+        self.episodes.insert(pos, episode);
+    }
+
+    /// All episodes in episode-number order.
+    #[inline]
+    pub fn episodes(&self) -> &[EpisodePackage] {
+        &self.episodes
+    }
+
+    /// Find an episode by number.
+    pub fn get_episode(&self, episode_number: u32) -> Option<&EpisodePackage> {
+        self.episodes
+            .iter()
+            .find(|e| e.metadata.episode_number == episode_number)
+    }
+
+    /// Number of episodes.
+    #[inline]
+    pub fn episode_count(&self) -> usize {
+        self.episodes.len()
+    }
+
+    /// Total running time of the series.
+    #[inline]
+    pub fn total_duration(&self) -> f32 {
+        self.episodes
+            .iter()
+            .map(|e| e.metadata.duration_seconds)
+            .sum()
+    }
+
+    /// Build the series-level metadata index.
+    pub fn index(&self) -> Vec<SeriesIndexEntry> {
+        self.episodes
+            .iter()
+            .map(|e| SeriesIndexEntry {
+                episode_number: e.metadata.episode_number,
+                title: e.metadata.title.clone(),
+                duration_seconds: e.metadata.duration_seconds,
+                actor_count: e.scene_graph.actor_count(),
+                cut_count: e.director.cut_count(),
+            })
+            .collect()
+    }
+}
+
+/// Episode with actor SDFs moved into the shared pool.
+#[derive(Serialize, Deserialize)]
+struct StrippedEpisode {
+    episode: EpisodePackage,
+    /// (actor, index into the asset pool).
+    asset_refs: Vec<(ActorId, u32)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SeriesBody {
+    assets: Vec<SdfNode>,
+    episodes: Vec<StrippedEpisode>,
+}
+
+fn encode<T: Serialize>(value: &T) -> crate::error::Result<Vec<u8>> {
+    Ok(bincode::serialize(value)?)
+}
+
+/// Move every actor's base SDF into a deduplicated pool keyed by its encoded bytes.
+fn dedup_assets(series: &SeriesPackage) -> crate::error::Result<SeriesBody> {
+    let mut pool: HashMap<Vec<u8>, u32> = HashMap::new();
+    let mut assets = Vec::new();
+    let mut episodes = Vec::with_capacity(series.episodes.len());
+
+    for episode in &series.episodes {
+        let mut stripped = episode.clone();
+        let mut asset_refs = Vec::new();
+        for id in episode.scene_graph.actor_ids() {
+            let actor = match stripped.scene_graph.get_actor_mut(id) {
+                Some(a) => a,
+                None => continue,
+            };
+            let sdf = std::mem::replace(&mut actor.base_sdf, SdfNode::sphere(0.0));
+            let key = encode(&sdf)?;
+            let index = match pool.get(&key) {
+                Some(&i) => i,
+                None => {
+                    let i = assets.len() as u32;
+                    pool.insert(key, i);
+                    assets.push(sdf);
+                    i
+                }
+            };
+            asset_refs.push((id, index));
+        }
+        episodes.push(StrippedEpisode {
+            episode: stripped,
+            asset_refs,
+        });
+    }
+    Ok(SeriesBody { assets, episodes })
+}
+
+pub(crate) fn write_section<W: Write>(writer: &mut W, data: &[u8]) -> crate::error::Result<usize> {
+    writer.write_all(&(data.len() as u32).to_le_bytes())?;
+    writer.write_all(&crc32fast::hash(data).to_le_bytes())?;
+    writer.write_all(data)?;
+    Ok(8 + data.len())
+}
+
+pub(crate) fn read_section<R: Read>(reader: &mut R) -> crate::error::Result<Vec<u8>> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    let size = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let expected_crc = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let data = read_sized(reader, size, "series section")?;
+    let actual_crc = crc32fast::hash(&data);
+    if actual_crc != expected_crc {
+        return Err(AnimationError::CrcMismatch {
+            expected: expected_crc,
+            actual: actual_crc,
+        });
+    }
+    Ok(data)
+}
+
+/// Serialize a series archive.
+///
+/// Binary format:
+/// `[Magic "ASER" 4B][Version 2B][Flags 2B]`
+/// `[IndexSize 4B][IndexCRC 4B][Bincode SeriesIndex]`
+/// `[BodySize 4B][BodyCRC 4B][Bincode assets + episodes]`
+pub fn serialize_series<W: Write>(
+    series: &SeriesPackage,
+    writer: &mut W,
+) -> crate::error::Result<usize> {
+    let body = dedup_assets(series)?;
+    let index = SeriesIndex {
+        metadata: series.metadata.clone(),
+        entries: series.index(),
+        shared_assets: body.assets.len(),
+    };
+
+    writer.write_all(&SERIES_MAGIC)?;
+    writer.write_all(&SERIES_VERSION.to_le_bytes())?;
+    writer.write_all(&0u16.to_le_bytes())?;
+    let mut written = 8;
+    written += write_section(writer, &encode(&index)?)?;
+    written += write_section(writer, &encode(&body)?)?;
+    Ok(written)
+}
+
+fn read_series_header<R: Read>(reader: &mut R) -> crate::error::Result<()> {
+    let mut header = [0u8; 8];
+    reader.read_exact(&mut header)?;
+    if header[0..4] != SERIES_MAGIC {
+        return Err(AnimationError::BadMagic { expected: "ASER" });
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != SERIES_VERSION {
+        return Err(AnimationError::UnsupportedVersion {
+            format: "ASER",
+            version,
+        });
+    }
+    Ok(())
+}
+
+/// Read only the series index (cheap: episodes are not decoded).
+pub fn read_series_index<R: Read>(reader: &mut R) -> crate::error::Result<SeriesIndex> {
+    read_series_header(reader)?;
+    let index = read_section(reader)?;
+    Ok(bincode::deserialize(&index)?)
+}
+
+/// Deserialize a full series archive, restoring shared assets into each episode.
+pub fn deserialize_series<R: Read>(reader: &mut R) -> crate::error::Result<SeriesPackage> {
+    let index = read_series_index(reader)?;
+    let body = read_section(reader)?;
+    let body: SeriesBody = bincode::deserialize(&body)?;
+
+    let mut series = SeriesPackage::new(index.metadata);
+    for stripped in body.episodes {
+        let mut episode = stripped.episode;
+        for (id, asset) in stripped.asset_refs {
+            let sdf = body.assets.get(asset as usize).ok_or_else(|| {
+                AnimationError::Corrupt(format!("Missing shared asset {}", asset))
+            })?;
+            if let Some(actor) = episode.scene_graph.get_actor_mut(id) {
+                actor.base_sdf = sdf.clone();
+            }
+        }
+        series.add_episode(episode);
+    }
+    Ok(series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::{serialize_episode, EpisodeMetadata};
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, SceneGraph};
+
+    fn make_episode(number: u32) -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        // Same cast in every episode: heavy SDFs shared across the season
+        let mut hero_sdf = SdfNode::sphere(1.0);
+        for i in 0..32 {
+            hero_sdf = hero_sdf.union(SdfNode::box3d(0.1, 0.2, i as f32 * 0.01));
+        }
+        sg.add_actor(Actor::new("hero", hero_sdf));
+        sg.add_actor(Actor::new("villain", SdfNode::box3d(1.0, 1.0, 1.0)));
+        let mut dir = Director::new(format!("Episode {}", number));
+        dir.add_cut(Cut::new("c1", 0.0, 5.0));
+        let meta = EpisodeMetadata::new(format!("Ep {}", number), number, 5.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    fn make_series() -> SeriesPackage {
+        let mut series = SeriesPackage::new(SeriesMetadata::new("Season One", 1));
+        series.add_episode(make_episode(2));
+        series.add_episode(make_episode(1));
+        series.add_episode(make_episode(3));
+        series
+    }
+
+    #[test]
+    fn test_series_roundtrip() {
+        let series = make_series();
+        assert_eq!(series.episodes()[0].metadata.episode_number, 1);
+        assert_eq!(series.total_duration(), 15.0);
+
+        let mut buf = Vec::new();
+        serialize_series(&series, &mut buf).unwrap();
+        let restored = deserialize_series(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(restored.episode_count(), 3);
+        assert_eq!(restored.metadata.title, "Season One");
+        let ep2 = restored.get_episode(2).unwrap();
+        let villain = ep2.scene_graph.find_by_name("villain").unwrap();
+        assert!(matches!(
+            ep2.scene_graph.get_actor(villain).unwrap().base_sdf,
+            SdfNode::Box3d { .. }
+        ));
+    }
+
+    #[test]
+    fn test_series_dedup_smaller_than_separate() {
+        let series = make_series();
+        let mut archive = Vec::new();
+        let archive_size = serialize_series(&series, &mut archive).unwrap();
+
+        let separate: usize = series
+            .episodes()
+            .iter()
+            .map(|e| serialize_episode(e, &mut Vec::new()).unwrap())
+            .sum();
+        assert!(archive_size < separate);
+
+        let index = read_series_index(&mut std::io::Cursor::new(&archive)).unwrap();
+        assert_eq!(index.shared_assets, 2);
+        assert_eq!(index.entries.len(), 3);
+    }
+
+    #[test]
+    fn test_damaged_archive_errors() {
+        let mut archive = Vec::new();
+        serialize_series(&make_series(), &mut archive).unwrap();
+
+        let mut bad_crc = archive.clone();
+        *bad_crc.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            deserialize_series(&mut bad_crc.as_slice()),
+            Err(AnimationError::CrcMismatch { .. })
+        ));
+        // An index size of 4 GiB is only read as far as the data goes
+        archive[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            read_series_index(&mut archive.as_slice()),
+            Err(AnimationError::Truncated(_))
+        ));
+        archive[0] = b'X';
+        assert!(matches!(
+            read_series_index(&mut archive.as_slice()),
+            Err(AnimationError::BadMagic { expected: "ASER" })
+        ));
+    }
+}
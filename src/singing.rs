@@ -0,0 +1,242 @@
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+use crate::lip_sync::{classify_with_confidence, Phoneme};
+
+/// One analysis frame of a sung vocal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SingingFrame {
+    /// Fundamental frequency in Hz (0.0 = unvoiced).
+    pub f0: f32,
+    /// First formant in Hz.
+    pub f1: f32,
+    /// Second formant in Hz.
+    pub f2: f32,
+}
+
+/// Singing mode parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SingingConfig {
+    /// Notes at least this long (seconds) are sustained: vowel held, vibrato applied.
+    pub sustain_min: f32,
+    /// Onset consonants are compressed to at most this many seconds before the note.
+    pub consonant_max: f32,
+    /// Openness change per 100 cents of pitch deviation from the note center.
+    pub vibrato_depth: f32,
+    /// Pitch jump (cents) that starts a new note.
+    pub note_split_cents: f32,
+}
+
+impl Default for SingingConfig {
+    fn default() -> Self {
+        Self {
+            sustain_min: 0.25,
+            consonant_max: 0.05,
+            vibrato_depth: 0.15,
+            note_split_cents: 80.0,
+        }
+    }
+}
+
+/// Pitch difference in cents.
+#[inline]
+fn cents(f: f32, reference: f32) -> f32 {
+    1200.0 * (f / reference).log2()
+}
+
+/// Voiced note or unvoiced gap: frames `[start, end)`.
+struct Segment {
+    start: usize,
+    end: usize,
+    voiced: bool,
+    phoneme: Phoneme,
+    center_f0: f32,
+}
+
+/// Split frames into voiced notes (by pitch continuity) and unvoiced gaps.
+fn segment_notes(frames: &[SingingFrame], config: &SingingConfig) -> Vec<Segment> {
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut f0_sum = 0.0f32;
+    for (i, frame) in frames.iter().enumerate() {
+        let voiced = frame.f0 > 0.0;
+        let split = match segments.last() {
+            None => true,
+            Some(seg) if seg.voiced != voiced => true,
+            Some(seg) if voiced => {
+                let mean = f0_sum / (i - seg.start) as f32;
+                cents(frame.f0, mean).abs() > config.note_split_cents
+            }
+            _ => false,
+        };
+        if split {
+            segments.push(Segment {
+                start: i,
+                end: i + 1,
+                voiced,
+                phoneme: Phoneme::Closed,
+                center_f0: 0.0,
+            });
+            f0_sum = 0.0;
+        } else if let Some(seg) = segments.last_mut() {
+            seg.end = i + 1;
+        }
+        if voiced {
+            f0_sum += frame.f0;
+        }
+        if let Some(seg) = segments.last_mut() {
+            seg.center_f0 = if voiced {
+                f0_sum / (seg.end - seg.start) as f32
+            } else {
+                0.0
+            };
+        }
+    }
+
+    // Hold one shape per segment: confidence-weighted majority of its frames
+    for seg in &mut segments {
+        let mut votes: Vec<(Phoneme, f32)> = Vec::new();
+        for frame in &frames[seg.start..seg.end] {
+            let (p, c) = if frame.f1 < 100.0 && frame.f2 < 100.0 {
+                (Phoneme::Closed, 1.0)
+            } else {
+                classify_with_confidence(frame.f1, frame.f2)
+            };
+            match votes.iter_mut().find(|(vp, _)| *vp == p) {
+                Some((_, w)) => *w += c.max(0.01),
+                None => votes.push((p, c.max(0.01))),
+            }
+        }
+        if let Some(&(p, _)) = votes
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        {
+            seg.phoneme = p;
+        }
+    }
+    segments
+}
+
+/// Generate mouth tracks for a sung vocal.
+///
+/// Sustained notes hold their vowel with openness modulated by vibrato (pitch deviation
+/// from the note center); consonants before a note are compressed to `consonant_max`
+/// so the previous vowel is held up to the onset. Output tracks match
+/// `LipSyncTrack::to_timeline`: "mouth.openness", "mouth.width", "mouth.lip_press".
+pub fn sing_to_timeline(
+    name: &str,
+    frames: &[SingingFrame],
+    frame_duration: f32,
+    config: &SingingConfig,
+) -> Timeline {
+    let segments = segment_notes(frames, config);
+
+    let mut openness = Track::new("mouth.openness");
+    let mut width = Track::new("mouth.width");
+    let mut lip_press = Track::new("mouth.lip_press");
+    let mut key = |t: f32, phoneme: Phoneme, open: f32| {
+        openness.add_keyframe(Keyframe::new(t, open.clamp(0.0, 1.0)));
+        width.add_keyframe(Keyframe::new(t, phoneme.width()));
+        lip_press.add_keyframe(Keyframe::new(t, phoneme.lip_press()));
+    };
+
+    for (i, seg) in segments.iter().enumerate() {
+        let start = seg.start as f32 * frame_duration;
+        let end = seg.end as f32 * frame_duration;
+        if !seg.voiced {
+            let next_voiced = segments.get(i + 1).is_some_and(|n| n.voiced);
+            if seg.phoneme.is_consonant() && next_voiced {
+                // Compressed onset: previous shape holds until just before the note
+                let onset = (end - config.consonant_max).max(start);
+                key(onset, seg.phoneme, seg.phoneme.openness());
+            } else {
+                key(start, seg.phoneme, seg.phoneme.openness());
+            }
+            continue;
+        }
+
+        let base = seg.phoneme.openness();
+        if end - start >= config.sustain_min && seg.center_f0 > 0.0 {
+            for (offset, frame) in frames[seg.start..seg.end].iter().enumerate() {
+                let t = start + offset as f32 * frame_duration;
+                let deviation = cents(frame.f0.max(1.0), seg.center_f0) / 100.0;
+                key(
+                    t,
+                    seg.phoneme,
+                    base * (1.0 + config.vibrato_depth * deviation),
+                );
+            }
+        } else {
+            key(start, seg.phoneme, base);
+        }
+    }
+    key(frames.len() as f32 * frame_duration, Phoneme::Closed, 0.0);
+
+    let mut tl = Timeline::new(name);
+    tl.add_track(openness);
+    tl.add_track(width);
+    tl.add_track(lip_press);
+    tl
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 0.01;
+
+    fn sustained_a(frames: usize, vibrato_cents: f32) -> Vec<SingingFrame> {
+        (0..frames)
+            .map(|i| {
+                let phase = i as f32 * FRAME * 5.5 * std::f32::consts::TAU;
+                SingingFrame {
+                    f0: 440.0 * 2f32.powf(vibrato_cents * phase.sin() / 1200.0),
+                    f1: 750.0,
+                    f2: 1300.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sustained_vowel_vibrato() {
+        let mut frames = sustained_a(100, 40.0);
+        // A couple of misclassified frames inside the note are ignored
+        frames[50].f1 = 300.0;
+        frames[50].f2 = 2400.0;
+        let tl = sing_to_timeline("song", &frames, FRAME, &SingingConfig::default());
+
+        let widths: Vec<f32> = (0..100)
+            .map(|i| tl.get_value("mouth.width", i as f32 * FRAME).unwrap())
+            .collect();
+        assert!(widths.iter().all(|&w| w == Phoneme::A.width()));
+
+        let opens: Vec<f32> = (0..100)
+            .map(|i| tl.get_value("mouth.openness", i as f32 * FRAME).unwrap())
+            .collect();
+        let min = opens.iter().cloned().fold(f32::MAX, f32::min);
+        assert!(min < 1.0, "vibrato should modulate openness");
+        assert_eq!(tl.get_value("mouth.openness", 1.0), Some(0.0));
+    }
+
+    #[test]
+    fn test_onset_consonant_compressed() {
+        // 0.2s unvoiced "m" then a sung あ
+        let mut frames: Vec<SingingFrame> = (0..20)
+            .map(|_| SingingFrame {
+                f0: 0.0,
+                f1: 200.0,
+                f2: 1000.0,
+            })
+            .collect();
+        frames.extend(sustained_a(50, 0.0));
+        let config = SingingConfig::default();
+        let tl = sing_to_timeline("song", &frames, FRAME, &config);
+
+        let lip = tl
+            .tracks
+            .iter()
+            .find(|t| t.name == "mouth.lip_press")
+            .unwrap();
+        let m_key = lip.keyframes.iter().find(|k| k.value == 1.0).unwrap();
+        assert!((m_key.time - (0.2 - config.consonant_max)).abs() < 1e-4);
+    }
+}
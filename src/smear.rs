@@ -0,0 +1,300 @@
+//! Smear frames for fast actions.
+//!
+//! [`SmearPlan::detect`] samples actors per frame and picks the frames where one moves
+//! farther than a threshold; within each run of fast frames only the `max_frames`
+//! largest steps are smeared, as an animator would draw them. On those frames the
+//! actor's SDF is swapped for a variant drawn back along its motion: stretched,
+//! repeated as blended multiples, or trailed by shrinking ghosts.
+
+use alice_sdf::SdfNode;
+use glam::{Quat, Vec3};
+
+use crate::export::FrameRange;
+use crate::performance::Performance;
+use crate::scene::ActorId;
+
+/// Shape of the smear drawing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmearStyle {
+    /// Stretch the actor along its motion, anchored at the leading edge.
+    Stretch,
+    /// Copies along the motion, smooth-blended into one streak.
+    Multiples { copies: u32, blend: f32 },
+    /// Separate copies at the trailing positions, each `shrink` times the size of the one
+    /// ahead of it.
+    Ghosts { count: u32, shrink: f32 },
+}
+
+/// When and how to smear.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmearConfig {
+    /// Displacement per frame (scene units) above which a frame is a smear candidate.
+    pub threshold: f32,
+    /// Smear frames kept per run of fast frames.
+    pub max_frames: u32,
+    /// Fraction of the frame's displacement the smear reaches back over.
+    pub length: f32,
+    pub style: SmearStyle,
+}
+
+impl Default for SmearConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            max_frames: 2,
+            length: 1.0,
+            style: SmearStyle::Stretch,
+        }
+    }
+}
+
+impl SmearConfig {
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    pub fn with_max_frames(mut self, max_frames: u32) -> Self {
+        self.max_frames = max_frames.max(1);
+        self
+    }
+
+    pub fn with_style(mut self, style: SmearStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Smear `sdf`, whose body is centred on `center`, back along `motion` (the
+    /// displacement over the frame).
+    pub fn apply(&self, sdf: SdfNode, center: Vec3, motion: Vec3) -> SdfNode {
+        let trail = motion * self.length;
+        let distance = trail.length();
+        if distance < 1e-6 {
+            return sdf;
+        }
+        let dir = trail / distance;
+        match self.style {
+            SmearStyle::Stretch => {
+                // Inner radius along the motion sizes the stretch; the leading edge stays put
+                let radius = (-alice_sdf::eval(&sdf, center)).max(0.05);
+                let stretch = 1.0 + distance / (2.0 * radius);
+                let align = Quat::from_rotation_arc(dir, Vec3::X);
+                let anchor = center - trail * 0.5;
+                sdf.translate(-center.x, -center.y, -center.z)
+                    .rotate(align)
+                    .scale_xyz(stretch, 1.0, 1.0)
+                    .rotate(align.inverse())
+                    .translate(anchor.x, anchor.y, anchor.z)
+            }
+            SmearStyle::Multiples { copies, blend } => {
+                let copies = copies.max(1);
+                let mut result = sdf.clone();
+                for i in 1..=copies {
+                    let offset = -trail * (i as f32 / copies as f32);
+                    let copy = sdf.clone().translate(offset.x, offset.y, offset.z);
+                    result = result.smooth_union(copy, blend);
+                }
+                result
+            }
+            SmearStyle::Ghosts { count, shrink } => {
+                let count = count.max(1);
+                let mut result = sdf.clone();
+                for i in 1..=count {
+                    let at = center - trail * (i as f32 / count as f32);
+                    let ghost = sdf
+                        .clone()
+                        .translate(-center.x, -center.y, -center.z)
+                        .scale(shrink.powi(i as i32))
+                        .translate(at.x, at.y, at.z);
+                    result = result.union(ghost);
+                }
+                result
+            }
+        }
+    }
+}
+
+/// One frame drawn as a smear.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmearFrame {
+    pub actor: ActorId,
+    pub frame: u32,
+    pub time: f32,
+    /// Displacement since the previous frame.
+    pub motion: Vec3,
+}
+
+/// Detected smear frames and the config that draws them.
+#[derive(Debug, Clone)]
+pub struct SmearPlan {
+    pub config: SmearConfig,
+    pub fps: f32,
+    pub frames: Vec<SmearFrame>,
+}
+
+impl SmearPlan {
+    /// Find smear frames of `actors` over `range`.
+    pub fn detect(
+        scene: Performance<'_>,
+        actors: &[ActorId],
+        range: FrameRange,
+        config: SmearConfig,
+    ) -> Self {
+        let step = 1.0 / range.fps.max(1e-3);
+        let mut frames = Vec::new();
+        for &actor in actors {
+            let mut run: Vec<SmearFrame> = Vec::new();
+            for i in 0..=range.frame_count() {
+                let fast = (i < range.frame_count())
+                    .then(|| {
+                        let time = range.frame_time(i);
+                        let motion = scene.actor_position_at(actor, time)
+                            - scene.actor_position_at(actor, time - step);
+                        SmearFrame {
+                            actor,
+                            frame: range.frame_number(i),
+                            time,
+                            motion,
+                        }
+                    })
+                    .filter(|f| f.motion.length() > config.threshold);
+                match fast {
+                    Some(frame) => run.push(frame),
+                    None if !run.is_empty() => {
+                        // Keep the largest steps of the run, back in frame order
+                        run.sort_by(|a, b| b.motion.length().total_cmp(&a.motion.length()));
+                        run.truncate(config.max_frames.max(1) as usize);
+                        run.sort_by_key(|f| f.frame);
+                        frames.append(&mut run);
+                    }
+                    None => {}
+                }
+            }
+        }
+        Self {
+            config,
+            fps: range.fps,
+            frames,
+        }
+    }
+
+    /// Smear of `actor` on the frame at `time`, if any.
+    pub fn smear_at(&self, actor: ActorId, time: f32) -> Option<&SmearFrame> {
+        let half_frame = 0.5 / self.fps.max(1e-3);
+        self.frames
+            .iter()
+            .find(|f| f.actor == actor && (f.time - time).abs() < half_frame)
+    }
+
+    /// `actor`'s SDF at `time`, smeared on smear frames.
+    pub fn evaluate_actor(&self, scene: Performance<'_>, actor: ActorId, time: f32) -> SdfNode {
+        let sdf = scene
+            .actor_sdf(actor, time)
+            .unwrap_or_else(|| SdfNode::sphere(0.0));
+        self.smeared(scene, actor, time, sdf)
+    }
+
+    fn smeared(&self, scene: Performance<'_>, actor: ActorId, time: f32, sdf: SdfNode) -> SdfNode {
+        match self.smear_at(actor, time) {
+            Some(smear) => {
+                // Actor SDFs carry their timeline offset but not the scene transform
+                let center = scene.actor_position_at(actor, time)
+                    - scene.get_world_transform(actor).position;
+                self.config.apply(sdf, center, smear.motion)
+            }
+            None => sdf,
+        }
+    }
+
+    /// Union of all visible actors at `time` with smears applied, in place of
+    /// `Performance::evaluate_scene`.
+    pub fn evaluate_scene(&self, scene: Performance<'_>, time: f32) -> SdfNode {
+        scene
+            .evaluate_actors(time)
+            .into_iter()
+            .map(|(id, sdf)| self.smeared(scene, id, time, sdf))
+            .reduce(SdfNode::union)
+            .unwrap_or_else(|| SdfNode::sphere(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+
+    /// A ball that holds, whips 6 units across frames 10-13 at 24 fps, then holds.
+    fn scene() -> (SceneGraph, ActorId) {
+        let mut tl = Timeline::new("whip");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(10.0 / 24.0, 0.0));
+        x.add_keyframe(Keyframe::new(11.0 / 24.0, 1.0));
+        x.add_keyframe(Keyframe::new(12.0 / 24.0, 3.0));
+        x.add_keyframe(Keyframe::new(13.0 / 24.0, 6.0));
+        tl.add_track(x);
+        let mut sg = SceneGraph::new();
+        let ball = sg.add_actor(Actor::new("ball", SdfNode::sphere(0.5)).with_timeline(tl));
+        sg.add_actor(Actor::new("wall", SdfNode::box3d(0.1, 1.0, 1.0)));
+        (sg, ball)
+    }
+
+    #[test]
+    fn test_detect_keeps_fastest_frames() {
+        let (sg, ball) = scene();
+        let range = FrameRange::new(0.0, 1.0, 24.0);
+        let plan = SmearPlan::detect(
+            Performance::from(&sg),
+            &[ball],
+            range,
+            SmearConfig::default(),
+        );
+        let frames: Vec<u32> = plan.frames.iter().map(|f| f.frame).collect();
+        // Frames 11-13 are fast; the 2 px/frame and 3 px/frame steps win
+        assert_eq!(frames, vec![12, 13]);
+        assert!((plan.frames[1].motion - Vec3::new(3.0, 0.0, 0.0)).length() < 1e-4);
+        assert!(plan.smear_at(ball, 12.0 / 24.0).is_some());
+        assert!(plan.smear_at(ball, 11.0 / 24.0).is_none());
+    }
+
+    #[test]
+    fn test_smear_styles_trail_behind() {
+        let (sg, ball) = scene();
+        let range = FrameRange::new(0.0, 1.0, 24.0);
+        let time = 13.0 / 24.0;
+        for style in [
+            SmearStyle::Stretch,
+            SmearStyle::Multiples {
+                copies: 6,
+                blend: 0.3,
+            },
+            SmearStyle::Ghosts {
+                count: 3,
+                shrink: 0.8,
+            },
+        ] {
+            let config = SmearConfig::default().with_style(style);
+            let plan = SmearPlan::detect(Performance::from(&sg), &[ball], range, config);
+            let sdf = plan.evaluate_actor(Performance::from(&sg), ball, time);
+            // Still covers the current position, and now reaches back toward x = 3
+            assert!(alice_sdf::eval(&sdf, Vec3::new(6.0, 0.0, 0.0)) < 0.0);
+            assert!(alice_sdf::eval(&sdf, Vec3::new(4.0, 0.0, 0.0)) < 0.05);
+            assert!(alice_sdf::eval(&sdf, Vec3::new(8.0, 0.0, 0.0)) > 0.0);
+        }
+        // Off smear frames the scene evaluates as usual
+        let plan = SmearPlan::detect(
+            Performance::from(&sg),
+            &[ball],
+            range,
+            SmearConfig::default(),
+        );
+        let (still, plain) = (
+            plan.evaluate_scene(Performance::from(&sg), 0.75),
+            sg.evaluate_scene(0.75),
+        );
+        for x in [-1.0, 3.0, 5.5, 6.4] {
+            let p = Vec3::new(x, 0.2, 0.0);
+            assert_eq!(alice_sdf::eval(&still, p), alice_sdf::eval(&plain, p));
+        }
+    }
+}
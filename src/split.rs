@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+
+use crate::director::{CutId, Director, Scene};
+use crate::episode::{EpisodePackage, MetadataValue};
+use crate::error::AnimationError;
+
+/// Extension key recording a part's index within the original episode.
+pub const PART_INDEX_KEY: &str = "split.part_index";
+/// Extension key recording a part's start time within the original episode.
+pub const PART_OFFSET_KEY: &str = "split.offset_seconds";
+
+/// Tolerance when matching split points to cut boundaries.
+const BOUNDARY_EPSILON: f32 = 1e-4;
+
+/// Split an episode into consecutive parts at the given times (e.g. act breaks).
+///
+/// Every split point must lie on a cut boundary: no cut may straddle it. Each part starts at
+/// 0: its cuts are re-based and re-numbered from `CutId(0)`, and it keeps every actor with
+/// its animation shifted back by the part's start (`SceneGraph::shift_time`), which is also
+/// recorded under [`PART_OFFSET_KEY`]. Scenes are filtered to the cuts they still
+/// reference, and dialogue lines and overlays go to the part they start in.
+pub fn split_at_cuts(
+    episode: &EpisodePackage,
+    split_points: &[f32],
+) -> crate::error::Result<Vec<EpisodePackage>> {
+    let mut points: Vec<f32> = split_points.to_vec();
+    points.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    points.dedup_by(|a, b| (*a - *b).abs() < BOUNDARY_EPSILON);
+
+    for &t in &points {
+        if let Some((_, cut)) = episode
+            .director
+            .cuts()
+            .find(|(_, c)| c.start_time + BOUNDARY_EPSILON < t && t < c.end_time - BOUNDARY_EPSILON)
+        {
+            return Err(AnimationError::InvalidInput(format!(
+                "Split point {} falls inside cut '{}'",
+                t, cut.name
+            )));
+        }
+    }
+
+    let total = episode.director.duration();
+    let mut bounds = Vec::with_capacity(points.len() + 2);
+    bounds.push(0.0f32);
+    bounds.extend(
+        points
+            .iter()
+            .copied()
+            .filter(|&t| t > BOUNDARY_EPSILON && t < total - BOUNDARY_EPSILON),
+    );
+    bounds.push(total);
+
+    let mut parts = Vec::with_capacity(bounds.len() - 1);
+    for (index, window) in bounds.windows(2).enumerate() {
+        let (start, end) = (window[0], window[1]);
+
+        let mut director = Director::new(episode.director.episode.name.clone());
+        let mut remap: HashMap<CutId, CutId> = HashMap::new();
+        for (old_id, cut) in episode.director.cuts() {
+            if cut.start_time + BOUNDARY_EPSILON >= start && cut.start_time < end - BOUNDARY_EPSILON
+            {
+                let mut cut = cut.clone();
+                cut.shift_time(-start);
+                // The previous cut is in another part: bake the inherited start into the track
+                if let Some(base) = episode.director.inherited_camera(old_id) {
+                    if remap.is_empty() {
+                        let own = cut.camera.evaluate(0.0);
+                        cut.camera.offset(
+                            base.position - own.position,
+                            base.target - own.target,
+                            base.fov - own.fov,
+                        );
+                        cut.inherit_camera = false;
+                    }
+                }
+                remap.insert(old_id, director.add_cut(cut));
+            }
+        }
+        director.episode.scenes = remap_scenes(&episode.director.episode.scenes, &remap);
+        for line in &episode.director.episode.dialogue {
+            if line.start_time + BOUNDARY_EPSILON >= start
+                && line.start_time < end - BOUNDARY_EPSILON
+            {
+                let mut line = line.clone();
+                line.start_time -= start;
+                line.end_time -= start;
+                director.add_dialogue(line);
+            }
+        }
+
+        let mut metadata = episode.metadata.clone();
+        metadata.duration_seconds = end - start;
+        metadata.set_extension(PART_INDEX_KEY, MetadataValue::Int(index as i64));
+        metadata.set_extension(PART_OFFSET_KEY, MetadataValue::Float(start as f64));
+
+        let mut scene_graph = episode.scene_graph.clone();
+        scene_graph.shift_time(-start);
+        let mut part =
+            EpisodePackage::new(metadata, scene_graph, director, episode.shading.clone());
+        part.render_settings = episode.render_settings;
+        for overlay in &episode.overlays {
+            if overlay.start + BOUNDARY_EPSILON >= start && overlay.start < end - BOUNDARY_EPSILON {
+                let mut overlay = overlay.clone();
+                overlay.start -= start;
+                overlay.end -= start;
+                part.overlays.push(overlay);
+            }
+        }
+        parts.push(part);
+    }
+    Ok(parts)
+}
+
+/// Join parts back into one episode, appending each part after the previous one.
+///
+/// The scene graph, shading, render settings, and metadata come from the first part
+/// (split markers removed); cut IDs are re-numbered in playback order and durations summed.
+pub fn join_episodes(parts: &[EpisodePackage]) -> crate::error::Result<EpisodePackage> {
+    let first = parts
+        .first()
+        .ok_or_else(|| AnimationError::InvalidInput("No parts to join".into()))?;
+
+    let mut director = Director::new(first.director.episode.name.clone());
+    let mut overlays = Vec::new();
+    let mut offset = 0.0f32;
+    for part in parts {
+        let mut remap: HashMap<CutId, CutId> = HashMap::new();
+        for (old_id, cut) in part.director.cuts() {
+            let mut cut = cut.clone();
+            cut.shift_time(offset);
+            remap.insert(old_id, director.add_cut(cut));
+        }
+        for scene in remap_scenes(&part.director.episode.scenes, &remap) {
+            // Scenes split across parts share a name: merge them back together
+            match director.episode.scenes.last_mut() {
+                Some(last) if last.name == scene.name => last.cuts.extend(scene.cuts),
+                _ => director.add_scene(scene),
+            }
+        }
+        for line in &part.director.episode.dialogue {
+            let mut line = line.clone();
+            line.start_time += offset;
+            line.end_time += offset;
+            director.add_dialogue(line);
+        }
+        for overlay in &part.overlays {
+            let mut overlay = overlay.clone();
+            overlay.start += offset;
+            overlay.end += offset;
+            overlays.push(overlay);
+        }
+        offset += part.metadata.duration_seconds.max(part.director.duration());
+    }
+
+    let mut metadata = first.metadata.clone();
+    metadata.duration_seconds = offset;
+    metadata.extensions.remove(PART_INDEX_KEY);
+    metadata.extensions.remove(PART_OFFSET_KEY);
+
+    let mut joined = EpisodePackage::new(
+        metadata,
+        first.scene_graph.clone(),
+        director,
+        first.shading.clone(),
+    );
+    joined.render_settings = first.render_settings;
+    joined.overlays = overlays;
+    Ok(joined)
+}
+
+/// Keep only scenes that still reference a cut, rewriting their cut IDs.
+fn remap_scenes(scenes: &[Scene], remap: &HashMap<CutId, CutId>) -> Vec<Scene> {
+    scenes
+        .iter()
+        .filter_map(|scene| {
+            let cuts: Vec<CutId> = scene
+                .cuts
+                .iter()
+                .filter_map(|id| remap.get(id).copied())
+                .collect();
+            if cuts.is_empty() {
+                return None;
+            }
+            Some(Scene {
+                name: scene.name.clone(),
+                cuts,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::Cut;
+    use crate::episode::EpisodeMetadata;
+    use crate::npr::AnimeShading;
+    use crate::cycle::TimelineCycle;
+    use crate::layer::AnimationLayer;
+    use crate::performance::Performance;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Split");
+        let a = dir.add_cut(Cut::new("avant", 0.0, 3.0).with_actors(vec![hero]));
+        let b = dir.add_cut(Cut::new("act_a", 3.0, 8.0).with_actors(vec![hero]));
+        let c = dir.add_cut(Cut::new("act_b", 8.0, 12.0).with_actors(vec![hero]));
+        let mut opening = Scene::new("opening");
+        opening.cuts = vec![a, b];
+        let mut main = Scene::new("main");
+        main.cuts = vec![c];
+        dir.add_scene(opening);
+        dir.add_scene(main);
+        let meta = EpisodeMetadata::new("Split", 1, 12.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_split_rebases_cuts() {
+        let episode = make_test_episode();
+        let parts = split_at_cuts(&episode, &[3.0, 8.0]).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[1].metadata.duration_seconds, 5.0);
+        assert_eq!(
+            parts[1]
+                .metadata
+                .extension(PART_OFFSET_KEY)
+                .and_then(|v| v.as_f64()),
+            Some(3.0)
+        );
+
+        let (id, cut) = parts[1].director.find_active_cut(0.5).unwrap();
+        assert_eq!(id, CutId(0));
+        assert_eq!(cut.name, "act_a");
+        assert_eq!(cut.start_time, 0.0);
+        assert_eq!(parts[1].director.episode.scenes.len(), 1);
+        assert_eq!(parts[1].director.episode.scenes[0].cuts, vec![CutId(0)]);
+    }
+
+    #[test]
+    fn test_split_parts_play_actors_from_their_start() {
+        let mut episode = make_test_episode();
+        let mut walk = Timeline::new("walk");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(12.0, 12.0));
+        walk.add_track(x);
+        let mut bob = Timeline::new("bob");
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.0));
+        y.add_keyframe(Keyframe::new(1.0, 1.0));
+        bob.add_track(y);
+        let hero = episode.scene_graph.find_by_name("hero").unwrap();
+        let actor = episode.scene_graph.get_actor_mut(hero).unwrap();
+        actor.timeline = Some(walk);
+        actor
+            .layers
+            .push(AnimationLayer::new("bob", bob).with_cycle(TimelineCycle::repeat()));
+
+        let parts = split_at_cuts(&episode, &[3.0, 8.0]).unwrap();
+        let whole = Performance::from(&episode.scene_graph).actor_position_at(hero, 4.25);
+        let part = Performance::from(&parts[1].scene_graph).actor_position_at(hero, 1.25);
+        assert!((whole - part).length() < 1e-5, "{whole} vs {part}");
+        assert!((part.x - 4.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_split_inside_cut_rejected() {
+        let episode = make_test_episode();
+        assert!(split_at_cuts(&episode, &[5.0]).is_err());
+    }
+
+    #[test]
+    fn test_split_join_roundtrip() {
+        let episode = make_test_episode();
+        let parts = split_at_cuts(&episode, &[3.0, 8.0]).unwrap();
+        let joined = join_episodes(&parts).unwrap();
+
+        assert_eq!(joined.metadata.duration_seconds, 12.0);
+        assert!(joined.metadata.extension(PART_INDEX_KEY).is_none());
+        assert_eq!(joined.director.cut_count(), 3);
+        assert_eq!(joined.director.duration(), 12.0);
+        let (_, cut) = joined.director.find_active_cut(9.0).unwrap();
+        assert_eq!(cut.name, "act_b");
+        assert_eq!(joined.director.episode.scenes.len(), 2);
+        assert_eq!(joined.director.episode.scenes[0].cuts.len(), 2);
+    }
+
+    #[test]
+    fn test_split_bakes_inherited_camera() {
+        let mut episode = make_test_episode();
+        let ids: Vec<CutId> = episode.director.cuts().map(|(id, _)| id).collect();
+        let first = episode.director.get_cut_mut(ids[0]).unwrap();
+        first.camera.clear_keyframes();
+        first
+            .camera
+            .add_keyframe(0.0, glam::Vec3::new(2.0, 1.0, 6.0), glam::Vec3::Y, 0.7);
+        episode.director.get_cut_mut(ids[1]).unwrap().inherit_camera = true;
+        let expected = episode.director.camera_at(ids[1], 1.0).unwrap();
+
+        let parts = split_at_cuts(&episode, &[3.0]).unwrap();
+        let (id, cut) = parts[1].director.find_active_cut(1.0).unwrap();
+        assert!(!cut.inherit_camera);
+        let baked = parts[1].director.camera_at(id, 1.0).unwrap();
+        assert!((baked.position - expected.position).length() < 1e-5);
+        assert!((baked.target - expected.target).length() < 1e-5);
+        assert!((baked.fov - expected.fov).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,574 @@
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::chunk::{
+    check_header_crc, decode, encode, read_raw_chunk, write_chunk_record, ChunkKind, RawChunk,
+    MAX_CHUNK_SIZE,
+};
+use crate::director::{Cut, CutId, Director, Episode};
+use crate::episode::{
+    header_version, read_header, EpisodeMetadata, EpisodePackage, EPISODE_MAGIC, STREAMED_VERSION,
+};
+use crate::error::AnimationError;
+use crate::npr::AnimeShading;
+use crate::overlay::Overlay;
+use crate::render::RenderSettings;
+use crate::rng::EpisodeRng;
+use crate::scene::SceneGraph;
+
+/// Index chunk tag.
+const INDEX_TAG: [u8; 4] = *b"INDX";
+/// Trailer magic following the index chunk.
+const TRAILER_MAGIC: [u8; 4] = *b"AEND";
+/// Trailer size: `[IndexOffset 8B][Magic "AEND" 4B]`.
+const TRAILER_LEN: i64 = 12;
+
+/// Location of one cut segment in a streamed file.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StreamIndexEntry {
+    pub cut: CutId,
+    pub start_time: f32,
+    pub end_time: f32,
+    /// Byte offset of the segment's chunk record from the start of the file.
+    pub offset: u64,
+}
+
+/// Index written after the last cut segment.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamIndex {
+    /// Episode structure (scenes); cuts are referenced by ID.
+    pub episode: Episode,
+    pub next_cut_id: u32,
+    /// Cut segments in playback order.
+    pub entries: Vec<StreamIndexEntry>,
+}
+
+impl StreamIndex {
+    /// Segment containing `time`, for seeking.
+    pub fn find_segment(&self, time: f32) -> Option<&StreamIndexEntry> {
+        let idx = self.entries.partition_point(|e| e.start_time <= time);
+        if idx == 0 {
+            return None;
+        }
+        let entry = &self.entries[idx - 1];
+        if time < entry.end_time {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+}
+
+/// Writes an episode cut-by-cut so readers can start playback before the file is complete.
+///
+/// Binary format (v3):
+/// `[Magic "ANIM" 4B][Version=3 2B][Flags 2B][Reserved 4B][HeaderCRC32 4B]`,
+/// then `META`, `SCNE`, `SHAD` chunks, an optional `REND` chunk, one `CUT_` chunk per cut
+/// in start-time order,
+/// an `INDX` chunk, and a `[IndexOffset 8B][Magic "AEND" 4B]` trailer.
+/// Chunk records use the v2 layout `[Tag 4B][Size 4B][CRC32 4B][Payload]`; every chunk is
+/// flushed as soon as it is written.
+pub struct EpisodeStreamWriter<W: Write> {
+    writer: W,
+    offset: u64,
+    entries: Vec<StreamIndexEntry>,
+    /// Seeds cut camera shakes that have none yet.
+    rng: EpisodeRng,
+}
+
+impl<W: Write> EpisodeStreamWriter<W> {
+    /// Write the header and the shared sections every cut depends on.
+    pub fn new(
+        mut writer: W,
+        metadata: &EpisodeMetadata,
+        scene_graph: &SceneGraph,
+        shading: &AnimeShading,
+    ) -> crate::error::Result<Self> {
+        let mut header = [0u8; 16];
+        header[0..4].copy_from_slice(&EPISODE_MAGIC);
+        header[4..6].copy_from_slice(&STREAMED_VERSION.to_le_bytes());
+        let header_crc = crc32fast::hash(&header[0..12]);
+        header[12..16].copy_from_slice(&header_crc.to_le_bytes());
+        writer.write_all(&header)?;
+
+        let mut offset = 16u64;
+        offset +=
+            write_chunk_record(&mut writer, ChunkKind::Metadata.tag(), &encode(metadata)?)? as u64;
+        offset += write_chunk_record(
+            &mut writer,
+            ChunkKind::SceneGraph.tag(),
+            &encode(scene_graph)?,
+        )? as u64;
+        offset +=
+            write_chunk_record(&mut writer, ChunkKind::Shading.tag(), &encode(shading)?)? as u64;
+        writer.flush()?;
+
+        Ok(Self {
+            writer,
+            offset,
+            entries: Vec::new(),
+            rng: EpisodeRng::new(metadata.seed()),
+        })
+    }
+
+    /// Write the episode's render settings. Call before the first cut.
+    pub fn write_render_settings(&mut self, settings: &RenderSettings) -> crate::error::Result<()> {
+        let tag = ChunkKind::RenderSettings.tag();
+        self.offset += write_chunk_record(&mut self.writer, tag, &encode(settings)?)? as u64;
+        Ok(self.writer.flush()?)
+    }
+
+    /// Write the episode's overlays. Call before the first cut.
+    pub fn write_overlays(&mut self, overlays: &[Overlay]) -> crate::error::Result<()> {
+        let tag = ChunkKind::Overlays.tag();
+        self.offset += write_chunk_record(&mut self.writer, tag, &encode(&overlays)?)? as u64;
+        Ok(self.writer.flush()?)
+    }
+
+    /// Append one cut segment, seeding its camera shake from the episode seed if it has
+    /// none. Cuts must arrive in start-time order.
+    pub fn write_cut(&mut self, id: CutId, cut: &Cut) -> crate::error::Result<()> {
+        if let Some(last) = self.entries.last() {
+            if cut.start_time < last.start_time {
+                return Err(AnimationError::InvalidInput(format!(
+                    "Cut '{}' written out of order",
+                    cut.name
+                )));
+            }
+        }
+        let entry = StreamIndexEntry {
+            cut: id,
+            start_time: cut.start_time,
+            end_time: cut.end_time,
+            offset: self.offset,
+        };
+        let mut cut = cut.clone();
+        cut.seed_shake(id, &self.rng);
+        let data = encode(&(id, &cut))?;
+        self.offset += write_chunk_record(&mut self.writer, ChunkKind::Cut.tag(), &data)? as u64;
+        self.writer.flush()?;
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Number of cut segments written so far.
+    #[inline]
+    pub fn cut_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Write the index and trailer, returning the writer and total bytes written.
+    pub fn finish(mut self, episode: &Episode, next_cut_id: u32) -> crate::error::Result<(W, u64)> {
+        let index = StreamIndex {
+            episode: episode.clone(),
+            next_cut_id,
+            entries: self.entries,
+        };
+        let index_offset = self.offset;
+        self.offset += write_chunk_record(&mut self.writer, INDEX_TAG, &encode(&index)?)? as u64;
+        self.writer.write_all(&index_offset.to_le_bytes())?;
+        self.writer.write_all(&TRAILER_MAGIC)?;
+        self.writer.flush()?;
+        Ok((self.writer, self.offset + TRAILER_LEN as u64))
+    }
+}
+
+/// Serialize a complete episode in the streamed (v3) format.
+pub fn serialize_episode_streamed<W: Write>(
+    episode: &EpisodePackage,
+    writer: &mut W,
+) -> crate::error::Result<usize> {
+    let mut stream = EpisodeStreamWriter::new(
+        writer,
+        &episode.metadata,
+        &episode.scene_graph,
+        &episode.shading,
+    )?;
+    if let Some(settings) = &episode.render_settings {
+        stream.write_render_settings(settings)?;
+    }
+    if !episode.overlays.is_empty() {
+        stream.write_overlays(&episode.overlays)?;
+    }
+    for (id, cut) in episode.director.cuts() {
+        stream.write_cut(id, cut)?;
+    }
+    let (_, written) = stream.finish(&episode.director.episode, episode.director.next_cut_id())?;
+    Ok(written as usize)
+}
+
+/// Reads a streamed episode incrementally: shared sections first, then one cut at a time.
+pub struct EpisodeStreamReader<R: Read> {
+    reader: R,
+    metadata: EpisodeMetadata,
+    scene_graph: SceneGraph,
+    shading: AnimeShading,
+    render_settings: Option<RenderSettings>,
+    overlays: Vec<Overlay>,
+    index: Option<StreamIndex>,
+    /// Bytes of a chunk record that has not fully arrived yet.
+    pending: Vec<u8>,
+}
+
+impl<R: Read> EpisodeStreamReader<R> {
+    /// Read the header and the shared sections.
+    pub fn new(mut reader: R) -> crate::error::Result<Self> {
+        let header = read_header(&mut reader)?;
+        Self::from_header(&header, reader)
+    }
+
+    fn from_header(header: &[u8; 16], mut reader: R) -> crate::error::Result<Self> {
+        let version = header_version(header);
+        if version != STREAMED_VERSION {
+            return Err(AnimationError::UnsupportedVersion {
+                format: "streamed",
+                version,
+            });
+        }
+        check_header_crc(header)?;
+
+        let metadata = decode(&expect_chunk(&mut reader, ChunkKind::Metadata.tag())?.data)?;
+        let scene_graph = decode(&expect_chunk(&mut reader, ChunkKind::SceneGraph.tag())?.data)?;
+        let shading = decode(&expect_chunk(&mut reader, ChunkKind::Shading.tag())?.data)?;
+        Ok(Self {
+            reader,
+            metadata,
+            scene_graph,
+            shading,
+            render_settings: None,
+            overlays: Vec::new(),
+            index: None,
+            pending: Vec::new(),
+        })
+    }
+
+    #[inline]
+    pub fn metadata(&self) -> &EpisodeMetadata {
+        &self.metadata
+    }
+
+    #[inline]
+    pub fn scene_graph(&self) -> &SceneGraph {
+        &self.scene_graph
+    }
+
+    #[inline]
+    pub fn shading(&self) -> &AnimeShading {
+        &self.shading
+    }
+
+    /// Render settings, if the stream carries them (known once the first cut has been read).
+    #[inline]
+    pub fn render_settings(&self) -> Option<&RenderSettings> {
+        self.render_settings.as_ref()
+    }
+
+    /// Overlays the stream carries (known once the first cut has been read).
+    #[inline]
+    pub fn overlays(&self) -> &[Overlay] {
+        &self.overlays
+    }
+
+    /// Index, available once all cuts have been read.
+    #[inline]
+    pub fn index(&self) -> Option<&StreamIndex> {
+        self.index.as_ref()
+    }
+
+    /// Read the next cut segment. Returns `None` once the index is reached.
+    ///
+    /// On a file still being written or downloaded, a segment that has not fully arrived
+    /// yields `AnimationError::Truncated`; the bytes read so far are kept, so call again once
+    /// more data is available.
+    pub fn next_cut(&mut self) -> crate::error::Result<Option<(CutId, Cut)>> {
+        if self.index.is_some() {
+            return Ok(None);
+        }
+        loop {
+            let raw = self.read_record()?;
+            if raw.tag == INDEX_TAG {
+                self.index = Some(decode(&raw.data)?);
+                return Ok(None);
+            }
+            // Unknown ancillary chunks are skipped
+            match ChunkKind::from_tag(raw.tag) {
+                Some(ChunkKind::Cut) => {
+                    let (id, mut cut): (CutId, Cut) = decode(&raw.data)?;
+                    cut.seed_shake(id, &EpisodeRng::new(self.metadata.seed()));
+                    return Ok(Some((id, cut)));
+                }
+                Some(ChunkKind::RenderSettings) => self.render_settings = Some(decode(&raw.data)?),
+                Some(ChunkKind::Overlays) => self.overlays = decode(&raw.data)?,
+                _ => {}
+            }
+        }
+    }
+
+    /// Next chunk record, buffered in `pending` until it is complete. Sizes past
+    /// `MAX_CHUNK_SIZE` are corruption, and the buffer only grows as data arrives.
+    fn read_record(&mut self) -> crate::error::Result<RawChunk> {
+        self.fill(12)?;
+        let size = u32::from_le_bytes([
+            self.pending[4],
+            self.pending[5],
+            self.pending[6],
+            self.pending[7],
+        ]) as usize;
+        if size > MAX_CHUNK_SIZE {
+            return Err(AnimationError::Corrupt(format!(
+                "Chunk size {size} exceeds the {MAX_CHUNK_SIZE} byte limit"
+            )));
+        }
+        self.fill(12 + size)?;
+        let raw = read_checked_chunk(&mut self.pending.as_slice());
+        self.pending.clear();
+        raw
+    }
+
+    fn fill(&mut self, len: usize) -> crate::error::Result<()> {
+        while self.pending.len() < len {
+            let start = self.pending.len();
+            self.pending.resize(len.min(start + 64 * 1024), 0);
+            let read = self.reader.read(&mut self.pending[start..]);
+            self.pending.truncate(start + *read.as_ref().unwrap_or(&0));
+            match read {
+                Ok(0) => return Err(AnimationError::Truncated("chunk record")),
+                Ok(_) => {}
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Read all remaining cuts and build the full episode.
+    pub fn into_episode(mut self) -> crate::error::Result<EpisodePackage> {
+        let mut cuts = Vec::new();
+        while let Some(cut) = self.next_cut()? {
+            cuts.push(cut);
+        }
+        let index = self
+            .index
+            .take()
+            .ok_or_else(|| AnimationError::Corrupt("Missing INDX chunk".into()))?;
+        let director = Director::from_sorted_cuts(index.episode, cuts, index.next_cut_id);
+        let mut episode =
+            EpisodePackage::new(self.metadata, self.scene_graph, director, self.shading);
+        episode.render_settings = self.render_settings;
+        episode.overlays = self.overlays;
+        Ok(episode)
+    }
+}
+
+/// Load a complete v3 file. Called by `deserialize_episode`.
+pub(crate) fn read_streamed<R: Read>(
+    header: &[u8; 16],
+    reader: &mut R,
+) -> crate::error::Result<EpisodePackage> {
+    EpisodeStreamReader::from_header(header, reader)?.into_episode()
+}
+
+/// Read only the index of a complete streamed file by seeking to its trailer.
+pub fn read_stream_index<R: Read + Seek>(reader: &mut R) -> crate::error::Result<StreamIndex> {
+    reader.seek(SeekFrom::End(-TRAILER_LEN))?;
+    let mut trailer = [0u8; TRAILER_LEN as usize];
+    reader.read_exact(&mut trailer)?;
+    if trailer[8..12] != TRAILER_MAGIC {
+        return Err(AnimationError::Truncated("stream: missing AEND trailer"));
+    }
+    let mut offset = [0u8; 8];
+    offset.copy_from_slice(&trailer[0..8]);
+    reader.seek(SeekFrom::Start(u64::from_le_bytes(offset)))?;
+    let raw = expect_chunk(reader, INDEX_TAG)?;
+    decode(&raw.data)
+}
+
+/// Read a single cut segment at an index entry's offset.
+pub fn read_stream_cut<R: Read + Seek>(
+    reader: &mut R,
+    entry: &StreamIndexEntry,
+) -> crate::error::Result<(CutId, Cut)> {
+    reader.seek(SeekFrom::Start(entry.offset))?;
+    let raw = expect_chunk(reader, ChunkKind::Cut.tag())?;
+    decode(&raw.data)
+}
+
+fn read_checked_chunk<R: Read>(reader: &mut R) -> crate::error::Result<RawChunk> {
+    read_raw_chunk(reader)?.checked()
+}
+
+fn expect_chunk<R: Read>(reader: &mut R, tag: [u8; 4]) -> crate::error::Result<RawChunk> {
+    let raw = read_checked_chunk(reader)?;
+    if raw.tag != tag {
+        return Err(AnimationError::Corrupt(format!(
+            "Expected {} chunk, found {}",
+            String::from_utf8_lossy(&tag),
+            String::from_utf8_lossy(&raw.tag)
+        )));
+    }
+    Ok(raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::Scene;
+    use crate::episode::deserialize_episode;
+    use crate::scene::Actor;
+    use alice_sdf::SdfNode;
+
+    fn make_test_episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let hero = sg.add_actor(Actor::new("hero", SdfNode::sphere(1.0)));
+        let mut dir = Director::new("Streamed");
+        let a = dir.add_cut(Cut::new("intro", 0.0, 3.0).with_actors(vec![hero]));
+        let b = dir.add_cut(Cut::new("battle", 3.0, 8.0).with_actors(vec![hero]));
+        dir.add_cut(Cut::new("outro", 8.0, 10.0).with_actors(vec![hero]));
+        let mut scene = Scene::new("opening");
+        scene.cuts = vec![a, b];
+        dir.add_scene(scene);
+        let meta = EpisodeMetadata::new("Streamed", 1, 10.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_streamed_roundtrip() {
+        let episode = make_test_episode();
+        let mut buf = Vec::new();
+        let written = serialize_episode_streamed(&episode, &mut buf).unwrap();
+        assert_eq!(written, buf.len());
+
+        let restored = deserialize_episode(&mut std::io::Cursor::new(&buf)).unwrap();
+        assert_eq!(restored.metadata.title, "Streamed");
+        assert_eq!(restored.director.cut_count(), 3);
+        assert_eq!(restored.director.episode.scenes[0].cuts.len(), 2);
+    }
+
+    #[test]
+    fn test_play_before_complete() {
+        let episode = make_test_episode();
+        let mut stream = EpisodeStreamWriter::new(
+            Vec::new(),
+            &episode.metadata,
+            &episode.scene_graph,
+            &episode.shading,
+        )
+        .unwrap();
+        let (id, cut) = episode.director.cuts().next().unwrap();
+        stream.write_cut(id, cut).unwrap();
+
+        // Only the first segment has been written so far
+        let partial = stream.writer.clone();
+        let mut reader = EpisodeStreamReader::new(std::io::Cursor::new(&partial)).unwrap();
+        assert_eq!(reader.metadata().title, "Streamed");
+        let (_, first) = reader.next_cut().unwrap().unwrap();
+        assert_eq!(first.name, "intro");
+        assert!(reader.next_cut().is_err());
+        assert!(deserialize_episode(&mut std::io::Cursor::new(&partial)).is_err());
+    }
+
+    /// Reader over `data` that only hands out the first `available` bytes.
+    struct Arriving {
+        data: Vec<u8>,
+        available: std::rc::Rc<std::cell::Cell<usize>>,
+        pos: usize,
+    }
+
+    impl Read for Arriving {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let end = self.available.get().min(self.data.len());
+            let n = buf.len().min(end.saturating_sub(self.pos));
+            buf[..n].copy_from_slice(&self.data[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_resume_after_partial_segment() {
+        let episode = make_test_episode();
+        let mut data = Vec::new();
+        serialize_episode_streamed(&episode, &mut data).unwrap();
+        let mut probe = EpisodeStreamReader::new(data.as_slice()).unwrap();
+        probe.next_cut().unwrap();
+        // Stop the download part-way into the second segment
+        let available =
+            std::rc::Rc::new(std::cell::Cell::new(data.len() - probe.reader.len() + 20));
+        let arriving = Arriving {
+            data: data.clone(),
+            available: available.clone(),
+            pos: 0,
+        };
+
+        let mut reader = EpisodeStreamReader::new(arriving).unwrap();
+        assert_eq!(reader.next_cut().unwrap().unwrap().1.name, "intro");
+        let err = reader.next_cut().unwrap_err();
+        assert!(matches!(err, AnimationError::Truncated(_)));
+        available.set(data.len());
+        assert_eq!(reader.next_cut().unwrap().unwrap().1.name, "battle");
+        assert_eq!(reader.next_cut().unwrap().unwrap().1.name, "outro");
+        assert!(reader.next_cut().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_corrupt_segment_size() {
+        let episode = make_test_episode();
+        let mut data = Vec::new();
+        serialize_episode_streamed(&episode, &mut data).unwrap();
+        let mut probe = EpisodeStreamReader::new(data.as_slice()).unwrap();
+        probe.next_cut().unwrap();
+        let size = data.len() - probe.reader.len() + 4;
+
+        data[size..size + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let mut reader = EpisodeStreamReader::new(data.as_slice()).unwrap();
+        reader.next_cut().unwrap();
+        assert!(matches!(
+            reader.next_cut().unwrap_err(),
+            AnimationError::Corrupt(_)
+        ));
+
+        // Within the limit: buffers what there is, then reports the segment as truncated
+        data[size..size + 4].copy_from_slice(&(1u32 << 24).to_le_bytes());
+        let mut reader = EpisodeStreamReader::new(data.as_slice()).unwrap();
+        reader.next_cut().unwrap();
+        assert!(matches!(
+            reader.next_cut().unwrap_err(),
+            AnimationError::Truncated(_)
+        ));
+        assert!(reader.pending.len() <= data.len());
+    }
+
+    #[test]
+    fn test_seek_via_index() {
+        let episode = make_test_episode();
+        let mut buf = Vec::new();
+        serialize_episode_streamed(&episode, &mut buf).unwrap();
+
+        let mut cursor = std::io::Cursor::new(&buf);
+        let index = read_stream_index(&mut cursor).unwrap();
+        assert_eq!(index.entries.len(), 3);
+        let entry = index.find_segment(9.0).unwrap();
+        let (_, cut) = read_stream_cut(&mut cursor, entry).unwrap();
+        assert_eq!(cut.name, "outro");
+        assert!(index.find_segment(12.0).is_none());
+    }
+
+    #[test]
+    fn test_out_of_order_cut_rejected() {
+        let episode = make_test_episode();
+        let mut stream = EpisodeStreamWriter::new(
+            Vec::new(),
+            &episode.metadata,
+            &episode.scene_graph,
+            &episode.shading,
+        )
+        .unwrap();
+        stream
+            .write_cut(CutId(0), &Cut::new("later", 5.0, 6.0))
+            .unwrap();
+        assert!(stream
+            .write_cut(CutId(1), &Cut::new("earlier", 1.0, 2.0))
+            .is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! SRT / WebVTT subtitle import.
+//!
+//! Cue timing plus script text is turned into coarse per-speaker lip sync and the
+//! director's dialogue track in one step, for when only the subbed script and final mix exist.
+
+use crate::director::{DialogueLine, Director};
+use crate::error::AnimationError;
+use crate::lip_sync::{LipSyncTrack, Phoneme};
+use crate::text_sync::{lip_sync_from_text, TextTiming};
+
+/// Track name used for cues without a speaker.
+pub const UNNAMED_SPEAKER: &str = "dialogue";
+/// Seconds per open/close flap when a cue has no readable kana or romaji (e.g. all kanji).
+const FLAP_INTERVAL: f32 = 0.12;
+/// Longest prefix accepted as a `Name: line` speaker tag.
+const MAX_SPEAKER_CHARS: usize = 24;
+
+/// One timed subtitle cue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubtitleCue {
+    pub start_time: f32,
+    pub end_time: f32,
+    pub speaker: Option<String>,
+    /// Text with markup removed; multi-line cues keep their line breaks.
+    pub text: String,
+}
+
+/// Parse an SRT or WebVTT file (detected by the `WEBVTT` header).
+///
+/// Speakers come from VTT voice tags (`<v Name>`) or a leading `Name:` / `Name：`.
+pub fn parse_subtitles(source: &str) -> crate::error::Result<Vec<SubtitleCue>> {
+    let source = source.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+    let mut cues = Vec::new();
+
+    for (block_index, block) in source.split("\n\n").enumerate() {
+        let lines: Vec<&str> = block.lines().filter(|l| !l.trim().is_empty()).collect();
+        // Headers, NOTE/STYLE blocks and stray text have no timing line
+        let Some(timing_at) = lines.iter().position(|l| l.contains("-->")) else {
+            continue;
+        };
+        let invalid =
+            |msg: String| AnimationError::Corrupt(format!("Cue {}: {}", block_index + 1, msg));
+
+        let (start, rest) = lines[timing_at].split_once("-->").unwrap();
+        // VTT cue settings follow the end timestamp
+        let end = rest.split_whitespace().next().unwrap_or("");
+        let start_time = parse_timestamp(start.trim())
+            .ok_or_else(|| invalid(format!("invalid timestamp '{}'", start.trim())))?;
+        let end_time =
+            parse_timestamp(end).ok_or_else(|| invalid(format!("invalid timestamp '{}'", end)))?;
+        if end_time < start_time {
+            return Err(invalid("ends before it starts".into()));
+        }
+
+        let raw = lines[timing_at + 1..].join("\n");
+        let (voice, body) = split_voice_tag(&raw);
+        let plain = strip_markup(body);
+        let (speaker, text) = match voice {
+            Some(name) => (Some(name), plain.as_str()),
+            None => split_name_prefix(&plain),
+        };
+        cues.push(SubtitleCue {
+            start_time,
+            end_time,
+            speaker,
+            text: text.trim().to_string(),
+        });
+    }
+    Ok(cues)
+}
+
+/// `hh:mm:ss,mmm` (SRT) or `[hh:]mm:ss.mmm` (VTT) to seconds.
+fn parse_timestamp(s: &str) -> Option<f32> {
+    let s = s.replace(',', ".");
+    let parts: Vec<&str> = s.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let mut seconds = 0.0f64;
+    for part in &parts[..parts.len() - 1] {
+        seconds = seconds * 60.0 + part.parse::<u32>().ok()? as f64;
+    }
+    let last: f64 = parts[parts.len() - 1].parse().ok()?;
+    if last < 0.0 {
+        return None;
+    }
+    Some((seconds * 60.0 + last) as f32)
+}
+
+/// Split a VTT voice tag (`<v Name>` or `<v.class Name>`) off the cue text.
+fn split_voice_tag(text: &str) -> (Option<String>, &str) {
+    if let Some(rest) = text.strip_prefix("<v") {
+        if let Some(close) = rest.find('>') {
+            let name = rest[..close]
+                .split_once(' ')
+                .map(|(_, n)| n.trim())
+                .unwrap_or("");
+            let body = &rest[close + 1..];
+            let body = body.strip_suffix("</v>").unwrap_or(body);
+            return ((!name.is_empty()).then(|| name.to_string()), body);
+        }
+    }
+    (None, text)
+}
+
+/// Split a leading `Name:` / `Name：` off plain cue text.
+fn split_name_prefix(text: &str) -> (Option<String>, &str) {
+    let first_line = text.lines().next().unwrap_or("");
+    if let Some((idx, colon)) = first_line
+        .char_indices()
+        .find(|&(_, c)| c == ':' || c == '：')
+    {
+        let name = first_line[..idx].trim();
+        if !name.is_empty() && name.chars().count() <= MAX_SPEAKER_CHARS {
+            return (Some(name.to_string()), &text[idx + colon.len_utf8()..]);
+        }
+    }
+    (None, text)
+}
+
+/// Remove `<i>`-style tags and `{\an8}`-style overrides.
+fn strip_markup(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut depth: Option<char> = None;
+    for c in text.chars() {
+        match (depth, c) {
+            (None, '<') => depth = Some('>'),
+            (None, '{') => depth = Some('}'),
+            (Some(close), c) if c == close => depth = None,
+            (Some(_), _) => {}
+            (None, c) => out.push(c),
+        }
+    }
+    out
+}
+
+/// Coarse lip sync for one cue, relative to the cue start.
+fn cue_lip_sync(cue: &SubtitleCue) -> LipSyncTrack {
+    let duration = (cue.end_time - cue.start_time).max(0.0);
+    let track = lip_sync_from_text("cue", &cue.text, &TextTiming::Total(duration));
+    let speaks = track
+        .phonemes
+        .iter()
+        .any(|kf| kf.phoneme != Phoneme::Closed);
+    if speaks || cue.text.is_empty() {
+        return track;
+    }
+    // Unreadable text: generic open/close flapping over the cue
+    let mut flap = LipSyncTrack::new("cue");
+    let mut t = 0.0f32;
+    let mut open = true;
+    while t < duration - FLAP_INTERVAL * 0.5 {
+        flap.add_phoneme(t, if open { Phoneme::A } else { Phoneme::Closed });
+        open = !open;
+        t += FLAP_INTERVAL;
+    }
+    flap.add_phoneme(duration, Phoneme::Closed);
+    flap
+}
+
+/// Import subtitles: every cue becomes a line on `director`'s dialogue track, and
+/// returns one lip sync track per speaker (named after the speaker, or `UNNAMED_SPEAKER`).
+pub fn import_subtitles(
+    director: &mut Director,
+    source: &str,
+) -> crate::error::Result<Vec<LipSyncTrack>> {
+    let cues = parse_subtitles(source)?;
+    let mut tracks: Vec<LipSyncTrack> = Vec::new();
+    for cue in &cues {
+        let name = cue.speaker.as_deref().unwrap_or(UNNAMED_SPEAKER);
+        let index = match tracks.iter().position(|t| t.name == name) {
+            Some(i) => i,
+            None => {
+                tracks.push(LipSyncTrack::new(name));
+                tracks.len() - 1
+            }
+        };
+        tracks[index].append(&cue_lip_sync(cue), cue.start_time);
+
+        let mut line = DialogueLine::new(cue.start_time, cue.end_time, cue.text.clone());
+        line.speaker = cue.speaker.clone();
+        director.add_dialogue(line);
+    }
+    Ok(tracks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "1\r\n00:00:01,000 --> 00:00:02,000\r\nアリス：こんにちは\r\n\r\n\
+2\r\n00:00:03,500 --> 00:00:04,100\r\n<i>ボブ: はい</i>\r\n\r\n\
+3\r\n00:00:05,000 --> 00:00:06,000\r\n{\\an8}アリス：漢字\r\n";
+
+    #[test]
+    fn test_parse_srt() {
+        let cues = parse_subtitles(SRT).unwrap();
+        assert_eq!(cues.len(), 3);
+        assert_eq!(cues[0].start_time, 1.0);
+        assert_eq!(cues[0].speaker.as_deref(), Some("アリス"));
+        assert_eq!(cues[0].text, "こんにちは");
+        assert_eq!(cues[1].start_time, 3.5);
+        assert_eq!(cues[1].speaker.as_deref(), Some("ボブ"));
+        assert_eq!(cues[1].text, "はい");
+        assert_eq!(cues[2].text, "漢字");
+    }
+
+    #[test]
+    fn test_parse_vtt() {
+        let vtt = "WEBVTT\n\nNOTE timing from final mix\n\n\
+intro\n00:01.250 --> 00:02.000 align:start\n<v Alice>Hello\nthere</v>\n\n\
+01:00:00.000 --> 01:00:01.000\nno speaker\n";
+        let cues = parse_subtitles(vtt).unwrap();
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].start_time, 1.25);
+        assert_eq!(cues[0].speaker.as_deref(), Some("Alice"));
+        assert_eq!(cues[0].text, "Hello\nthere");
+        assert_eq!(cues[1].start_time, 3600.0);
+        assert_eq!(cues[1].speaker, None);
+
+        assert!(parse_subtitles("1\n00:00:xx,000 --> 00:00:01,000\nhi\n").is_err());
+    }
+
+    #[test]
+    fn test_import_subtitles() {
+        let mut director = Director::new("Subbed");
+        let tracks = import_subtitles(&mut director, SRT).unwrap();
+        assert_eq!(tracks.len(), 2);
+        let alice = &tracks[0];
+        assert_eq!(alice.name, "アリス");
+        assert_eq!(alice.phoneme_at(0.5), Phoneme::Closed);
+        assert_ne!(alice.phoneme_at(1.5), Phoneme::Closed);
+        assert_eq!(alice.phoneme_at(2.5), Phoneme::Closed);
+        // Kanji-only cue falls back to flapping
+        assert_eq!(alice.phoneme_at(5.05), Phoneme::A);
+        assert_eq!(alice.phoneme_at(6.5), Phoneme::Closed);
+
+        assert_eq!(director.episode.dialogue.len(), 3);
+        let speaking: Vec<_> = director.dialogue_at(3.6).collect();
+        assert_eq!(speaking.len(), 1);
+        assert_eq!(speaking[0].speaker.as_deref(), Some("ボブ"));
+    }
+}
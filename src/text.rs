@@ -0,0 +1,407 @@
+//! Typography actors: strings laid out with a stroke font and built into flat or extruded
+//! SDF glyphs, so titles and signage live in the scene and pick up NPR outlines.
+//!
+//! Fonts are stroke (centre-line) fonts in em units with the baseline at y = 0. A small
+//! Latin caps / digits font is built in for episode numbers and placeholders; kana and
+//! kanji come from a supplied [`StrokeFont`] (loaded with serde like any other asset).
+
+use std::collections::BTreeMap;
+
+use alice_sdf::SdfNode;
+use glam::Quat;
+use serde::{Deserialize, Serialize};
+
+use crate::scene::Actor;
+
+/// One glyph: its advance and centre-line polylines, in em units.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Glyph {
+    pub advance: f32,
+    pub strokes: Vec<Vec<[f32; 2]>>,
+}
+
+impl Glyph {
+    pub fn new(advance: f32, strokes: Vec<Vec<[f32; 2]>>) -> Self {
+        Self { advance, strokes }
+    }
+}
+
+/// Stroke font with its vertical metrics (em units).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrokeFont {
+    pub name: String,
+    /// Height above the baseline; also the vertical-text advance.
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_gap: f32,
+    /// Stroke width at weight 1.
+    pub stroke_width: f32,
+    /// Advance for characters the font lacks.
+    pub missing_advance: f32,
+    pub glyphs: BTreeMap<char, Glyph>,
+}
+
+impl StrokeFont {
+    pub fn new(name: impl Into<String>, ascent: f32, descent: f32) -> Self {
+        Self {
+            name: name.into(),
+            ascent,
+            descent,
+            line_gap: 0.2 * (ascent + descent),
+            stroke_width: 0.1 * ascent,
+            missing_advance: 0.5 * ascent,
+            glyphs: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_glyph(mut self, ch: char, glyph: Glyph) -> Self {
+        self.glyphs.insert(ch, glyph);
+        self
+    }
+
+    pub fn with_stroke_width(mut self, width: f32) -> Self {
+        self.stroke_width = width.max(0.0);
+        self
+    }
+
+    /// Built-in caps font: A–Z (lowercase maps to caps), 0–9, space and `-.:!?/#`.
+    pub fn builtin() -> Self {
+        // Glyphs drawn on a 4 x 6 grid, 1.0 em cap height
+        let cell = |x: i8, y: i8| [x as f32 * 0.15, y as f32 / 6.0];
+        let mut font = Self::new("alice-caps", 1.0, 0.25).with_stroke_width(0.12);
+        font.missing_advance = 0.8;
+        font.glyphs.insert(' ', Glyph::new(0.5, Vec::new()));
+        for (ch, strokes) in BUILTIN_GLYPHS {
+            let strokes = strokes
+                .iter()
+                .map(|line| line.iter().map(|&(x, y)| cell(x, y)).collect())
+                .collect();
+            font.glyphs.insert(*ch, Glyph::new(0.8, strokes));
+        }
+        font
+    }
+
+    /// Glyph for `ch`, falling back to its uppercase form.
+    pub fn glyph(&self, ch: char) -> Option<&Glyph> {
+        self.glyphs
+            .get(&ch)
+            .or_else(|| self.glyphs.get(&ch.to_ascii_uppercase()))
+    }
+
+    #[inline]
+    pub fn line_height(&self) -> f32 {
+        self.ascent + self.descent + self.line_gap
+    }
+}
+
+type GridStroke = &'static [(i8, i8)];
+
+#[rustfmt::skip]
+const BUILTIN_GLYPHS: &[(char, &[GridStroke])] = &[
+    ('0', &[&[(0, 0), (4, 0), (4, 6), (0, 6), (0, 0)], &[(0, 1), (4, 5)]]),
+    ('1', &[&[(1, 5), (2, 6), (2, 0)], &[(1, 0), (3, 0)]]),
+    ('2', &[&[(0, 6), (4, 6), (4, 3), (0, 3), (0, 0), (4, 0)]]),
+    ('3', &[&[(0, 6), (4, 6), (4, 0), (0, 0)], &[(1, 3), (4, 3)]]),
+    ('4', &[&[(0, 6), (0, 3), (4, 3)], &[(4, 6), (4, 0)]]),
+    ('5', &[&[(4, 6), (0, 6), (0, 4), (3, 4), (4, 3), (4, 1), (3, 0), (0, 0)]]),
+    ('6', &[&[(4, 6), (0, 6), (0, 0), (4, 0), (4, 3), (0, 3)]]),
+    ('7', &[&[(0, 6), (4, 6), (2, 0)]]),
+    ('8', &[&[(0, 0), (4, 0), (4, 6), (0, 6), (0, 0)], &[(0, 3), (4, 3)]]),
+    ('9', &[&[(4, 3), (0, 3), (0, 6), (4, 6), (4, 0), (0, 0)]]),
+    ('A', &[&[(0, 0), (2, 6), (4, 0)], &[(1, 3), (3, 3)]]),
+    ('B', &[&[(0, 0), (0, 6), (3, 6), (4, 5), (4, 4), (3, 3), (0, 3)], &[(3, 3), (4, 2), (4, 1), (3, 0), (0, 0)]]),
+    ('C', &[&[(4, 6), (0, 6), (0, 0), (4, 0)]]),
+    ('D', &[&[(0, 0), (0, 6), (3, 6), (4, 4), (4, 2), (3, 0), (0, 0)]]),
+    ('E', &[&[(4, 6), (0, 6), (0, 0), (4, 0)], &[(0, 3), (3, 3)]]),
+    ('F', &[&[(4, 6), (0, 6), (0, 0)], &[(0, 3), (3, 3)]]),
+    ('G', &[&[(4, 6), (0, 6), (0, 0), (4, 0), (4, 3), (2, 3)]]),
+    ('H', &[&[(0, 0), (0, 6)], &[(4, 0), (4, 6)], &[(0, 3), (4, 3)]]),
+    ('I', &[&[(1, 6), (3, 6)], &[(2, 6), (2, 0)], &[(1, 0), (3, 0)]]),
+    ('J', &[&[(4, 6), (4, 0), (0, 0), (0, 2)]]),
+    ('K', &[&[(0, 0), (0, 6)], &[(4, 6), (0, 3), (4, 0)]]),
+    ('L', &[&[(0, 6), (0, 0), (4, 0)]]),
+    ('M', &[&[(0, 0), (0, 6), (2, 3), (4, 6), (4, 0)]]),
+    ('N', &[&[(0, 0), (0, 6), (4, 0), (4, 6)]]),
+    ('O', &[&[(0, 0), (4, 0), (4, 6), (0, 6), (0, 0)]]),
+    ('P', &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3)]]),
+    ('Q', &[&[(0, 0), (4, 0), (4, 6), (0, 6), (0, 0)], &[(2, 2), (4, -1)]]),
+    ('R', &[&[(0, 0), (0, 6), (4, 6), (4, 3), (0, 3), (4, 0)]]),
+    ('S', &[&[(4, 6), (0, 6), (0, 3), (4, 3), (4, 0), (0, 0)]]),
+    ('T', &[&[(0, 6), (4, 6)], &[(2, 6), (2, 0)]]),
+    ('U', &[&[(0, 6), (0, 0), (4, 0), (4, 6)]]),
+    ('V', &[&[(0, 6), (2, 0), (4, 6)]]),
+    ('W', &[&[(0, 6), (1, 0), (2, 3), (3, 0), (4, 6)]]),
+    ('X', &[&[(0, 0), (4, 6)], &[(0, 6), (4, 0)]]),
+    ('Y', &[&[(0, 6), (2, 3), (4, 6)], &[(2, 3), (2, 0)]]),
+    ('Z', &[&[(0, 6), (4, 6), (0, 0), (4, 0)]]),
+    ('-', &[&[(1, 3), (3, 3)]]),
+    ('.', &[&[(2, 0), (2, 0)]]),
+    (':', &[&[(2, 1), (2, 1)], &[(2, 4), (2, 4)]]),
+    ('!', &[&[(2, 6), (2, 2)], &[(2, 0), (2, 0)]]),
+    ('?', &[&[(0, 5), (1, 6), (4, 6), (4, 3), (2, 3), (2, 2)], &[(2, 0), (2, 0)]]),
+    ('/', &[&[(0, 0), (4, 6)]]),
+    ('#', &[&[(1, 0), (1, 6)], &[(3, 0), (3, 6)], &[(0, 2), (4, 2)], &[(0, 4), (4, 4)]]),
+];
+
+/// Line alignment about the text origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Writing direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextDirection {
+    /// Left to right, lines downward.
+    #[default]
+    Horizontal,
+    /// Top to bottom, columns right to left (tategaki).
+    Vertical,
+}
+
+/// Size and shape of built glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TextStyle {
+    /// World units per em.
+    pub size: f32,
+    /// Extrusion along Z in world units; small values give flat signage.
+    pub depth: f32,
+    /// Stroke width multiplier.
+    pub weight: f32,
+    /// Extra advance between characters, in em.
+    pub letter_spacing: f32,
+    /// Line (or column) pitch multiplier.
+    pub line_spacing: f32,
+    /// Applied per line; vertical columns align along Y.
+    pub align: TextAlign,
+    pub direction: TextDirection,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl TextStyle {
+    /// Flat text `size` world units per em.
+    pub fn new(size: f32) -> Self {
+        Self {
+            size,
+            depth: 0.05 * size,
+            weight: 1.0,
+            letter_spacing: 0.0,
+            line_spacing: 1.0,
+            align: TextAlign::Left,
+            direction: TextDirection::Horizontal,
+        }
+    }
+
+    pub fn with_depth(mut self, depth: f32) -> Self {
+        self.depth = depth.max(0.0);
+        self
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight.max(0.0);
+        self
+    }
+
+    pub fn with_letter_spacing(mut self, spacing: f32) -> Self {
+        self.letter_spacing = spacing;
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+}
+
+/// A character placed by [`layout_text`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlacedGlyph {
+    pub ch: char,
+    /// Baseline-left corner of the glyph, world units.
+    pub origin: [f32; 2],
+}
+
+/// Result of laying out a string.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextLayout {
+    pub glyphs: Vec<PlacedGlyph>,
+    /// Characters the font lacks (laid out as blank advances).
+    pub missing: Vec<char>,
+}
+
+/// Place every character of `text`. The first line's baseline (or the first column's top)
+/// sits at y = 0; `\n` starts a new line or column.
+pub fn layout_text(font: &StrokeFont, text: &str, style: &TextStyle) -> TextLayout {
+    let mut layout = TextLayout::default();
+    let pitch = font.line_height() * style.line_spacing;
+    for (line_index, line) in text.split('\n').enumerate() {
+        let advances: Vec<(char, f32)> = line
+            .chars()
+            .map(|ch| {
+                let advance = match font.glyph(ch) {
+                    Some(glyph) => glyph.advance,
+                    None => {
+                        if !ch.is_whitespace() && !layout.missing.contains(&ch) {
+                            layout.missing.push(ch);
+                        }
+                        font.missing_advance
+                    }
+                };
+                (ch, advance)
+            })
+            .collect();
+        let step = |advance: f32| match style.direction {
+            TextDirection::Horizontal => advance + style.letter_spacing,
+            TextDirection::Vertical => font.ascent + style.letter_spacing,
+        };
+        let length: f32 = advances.iter().map(|&(_, a)| step(a)).sum::<f32>()
+            - if advances.is_empty() {
+                0.0
+            } else {
+                style.letter_spacing
+            };
+        let mut pen = match style.align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => -0.5 * length,
+            TextAlign::Right => -length,
+        };
+        let line_offset = line_index as f32 * pitch;
+        for (ch, advance) in advances {
+            let origin = match style.direction {
+                TextDirection::Horizontal => [pen, -line_offset],
+                // Centre each glyph in its column; columns march leftward
+                TextDirection::Vertical => [-line_offset - 0.5 * advance, -pen - font.ascent],
+            };
+            if font.glyph(ch).is_some_and(|g| !g.strokes.is_empty()) {
+                layout.glyphs.push(PlacedGlyph {
+                    ch,
+                    origin: [origin[0] * style.size, origin[1] * style.size],
+                });
+            }
+            pen += step(advance);
+        }
+    }
+    layout
+}
+
+/// SDF for `text` centred on z = 0, or `None` if nothing visible was laid out.
+pub fn text_sdf(font: &StrokeFont, text: &str, style: &TextStyle) -> Option<SdfNode> {
+    let half_width = 0.5 * font.stroke_width * style.weight * style.size;
+    let half_depth = 0.5 * style.depth.max(1e-4);
+    layout_text(font, text, style)
+        .glyphs
+        .iter()
+        .filter_map(|placed| font.glyph(placed.ch).map(|g| (placed.origin, g)))
+        .flat_map(|(origin, glyph)| {
+            glyph.strokes.iter().flat_map(move |stroke| {
+                let point = move |p: [f32; 2]| {
+                    [origin[0] + p[0] * style.size, origin[1] + p[1] * style.size]
+                };
+                // A single-point stroke is a dot
+                let pairs: Vec<([f32; 2], [f32; 2])> = if stroke.len() == 1 {
+                    vec![(point(stroke[0]), point(stroke[0]))]
+                } else {
+                    stroke
+                        .windows(2)
+                        .map(|w| (point(w[0]), point(w[1])))
+                        .collect()
+                };
+                pairs
+                    .into_iter()
+                    .map(move |(a, b)| stroke_segment(a, b, half_width, half_depth))
+            })
+        })
+        .reduce(|acc, piece| acc.union(piece))
+}
+
+/// Text as a scene actor; `None` for text with no visible glyphs.
+pub fn text_actor(
+    name: impl Into<String>,
+    font: &StrokeFont,
+    text: &str,
+    style: &TextStyle,
+) -> Option<Actor> {
+    text_sdf(font, text, style).map(|sdf| Actor::new(name, sdf))
+}
+
+/// Square-capped bar from `a` to `b` in the XY plane.
+fn stroke_segment(a: [f32; 2], b: [f32; 2], half_width: f32, half_depth: f32) -> SdfNode {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let half_len = 0.5 * dx.hypot(dy);
+    let angle = dy.atan2(dx);
+    SdfNode::box3d(half_len + half_width, half_width, half_depth)
+        .rotate(Quat::from_rotation_z(angle))
+        .translate(0.5 * (a[0] + b[0]), 0.5 * (a[1] + b[1]), 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::Vec3;
+
+    #[test]
+    fn test_layout_advances_and_reports_missing() {
+        let font = StrokeFont::builtin();
+        let layout = layout_text(&font, "Ep 1\n話", &TextStyle::new(2.0));
+        let chars: Vec<char> = layout.glyphs.iter().map(|g| g.ch).collect();
+        assert_eq!(chars, vec!['E', 'p', '1']);
+        assert_eq!(layout.glyphs[1].origin, [1.6, 0.0]);
+        // Space advances 0.5 em without producing a glyph
+        assert_eq!(layout.glyphs[2].origin, [2.0 * (0.8 + 0.8 + 0.5), 0.0]);
+        assert_eq!(layout.missing, vec!['話']);
+
+        let centred = layout_text(
+            &font,
+            "II",
+            &TextStyle::new(1.0).with_align(TextAlign::Center),
+        );
+        assert!((centred.glyphs[0].origin[0] + 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_vertical_uses_supplied_font() {
+        let font = StrokeFont::new("kana", 1.0, 0.0)
+            .with_glyph(
+                'ア',
+                Glyph::new(1.0, vec![vec![[0.1, 0.9], [0.9, 0.9], [0.5, 0.0]]]),
+            )
+            .with_glyph('イ', Glyph::new(1.0, vec![vec![[0.8, 1.0], [0.2, 0.5]]]));
+        let style = TextStyle::new(1.0).with_direction(TextDirection::Vertical);
+        let layout = layout_text(&font, "アイ\nア", &style);
+        let origins: Vec<[f32; 2]> = layout.glyphs.iter().map(|g| g.origin).collect();
+        assert_eq!(origins[0], [-0.5, -1.0]);
+        assert_eq!(origins[1], [-0.5, -2.0]);
+        // Second column to the left of the first
+        assert!(origins[2][0] < origins[0][0] && origins[2][1] == -1.0);
+        assert!(layout.missing.is_empty());
+    }
+
+    #[test]
+    fn test_glyph_sdf_covers_strokes() {
+        let font = StrokeFont::builtin();
+        let style = TextStyle::new(1.0).with_depth(0.2);
+        let sdf = text_sdf(&font, "T", &style).unwrap();
+        // On the stem, inside the extrusion; beside it, outside
+        assert!(alice_sdf::eval(&sdf, Vec3::new(0.3, 0.5, 0.05)) < 0.0);
+        assert!(alice_sdf::eval(&sdf, Vec3::new(0.3, 0.5, 0.2)) > 0.0);
+        assert!(alice_sdf::eval(&sdf, Vec3::new(0.05, 0.5, 0.0)) > 0.0);
+        assert!(text_actor("title", &font, "  ", &style).is_none());
+        assert_eq!(
+            text_actor("title", &font, "T", &style).unwrap().name,
+            "title"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use crate::lip_sync::{LipSyncTrack, Phoneme};
+
+/// Fraction of a mora spent on its consonant onset shape.
+const ONSET_FRACTION: f32 = 0.35;
+
+/// One mora (拍): optional consonant shape followed by the nucleus shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mora {
+    /// Consonant viseme, if the consonant has a distinct mouth shape (k/g/h/r/y do not).
+    pub onset: Option<Phoneme>,
+    /// Vowel, ん (`N`), or `Closed` for っ and pauses.
+    pub nucleus: Phoneme,
+}
+
+impl Mora {
+    #[inline]
+    fn new(onset: Option<Phoneme>, nucleus: Phoneme) -> Self {
+        Self { onset, nucleus }
+    }
+
+    #[inline]
+    fn pause() -> Self {
+        Self::new(None, Phoneme::Closed)
+    }
+}
+
+/// How mora timing is derived when generating from text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextTiming {
+    /// Spread all morae evenly over this many seconds.
+    Total(f32),
+    /// Fixed seconds per mora.
+    MoraDuration(f32),
+    /// Explicit seconds per mora (e.g. from a timing sheet); missing entries reuse the last one.
+    MoraDurations(Vec<f32>),
+}
+
+/// Hiragana rows in あいうえお order with their onset viseme.
+const KANA_ROWS: [(&str, Option<Phoneme>); 13] = [
+    ("あいうえお", None),
+    ("かきくけこ", None),
+    ("がぎぐげご", None),
+    ("さしすせそ", Some(Phoneme::S)),
+    ("ざじずぜぞ", Some(Phoneme::S)),
+    ("たちつてと", Some(Phoneme::S)),
+    ("だぢづでど", Some(Phoneme::S)),
+    ("なにぬねの", Some(Phoneme::N)),
+    ("はひへほ", None),
+    ("ばびぶべぼ", Some(Phoneme::M)),
+    ("ぱぴぷぺぽ", Some(Phoneme::M)),
+    ("まみむめも", Some(Phoneme::M)),
+    ("らりるれろ", None),
+];
+
+const VOWELS: [Phoneme; 5] = [Phoneme::A, Phoneme::I, Phoneme::U, Phoneme::E, Phoneme::O];
+
+/// Map katakana to hiragana; other characters are returned unchanged.
+#[inline]
+fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - 0x60).unwrap_or(c),
+        _ => c,
+    }
+}
+
+fn vowel_from_ascii(c: char) -> Option<Phoneme> {
+    match c {
+        'a' => Some(Phoneme::A),
+        'i' => Some(Phoneme::I),
+        'u' => Some(Phoneme::U),
+        'e' => Some(Phoneme::E),
+        'o' => Some(Phoneme::O),
+        _ => None,
+    }
+}
+
+/// Pause length in morae for punctuation, if `c` is punctuation.
+fn pause_morae(c: char) -> Option<usize> {
+    match c {
+        '、' | ',' | '，' => Some(1),
+        '。' | '.' | '!' | '?' | '！' | '？' | '…' => Some(2),
+        _ => None,
+    }
+}
+
+fn kana_mora(c: char) -> Option<Mora> {
+    match c {
+        'ふ' => return Some(Mora::new(Some(Phoneme::F), Phoneme::U)),
+        'ゔ' => return Some(Mora::new(Some(Phoneme::F), Phoneme::U)),
+        'や' => return Some(Mora::new(None, Phoneme::A)),
+        'ゆ' => return Some(Mora::new(None, Phoneme::U)),
+        'よ' => return Some(Mora::new(None, Phoneme::O)),
+        'わ' => return Some(Mora::new(Some(Phoneme::W), Phoneme::A)),
+        'を' => return Some(Mora::new(Some(Phoneme::W), Phoneme::O)),
+        'ん' => return Some(Mora::new(None, Phoneme::N)),
+        'っ' => return Some(Mora::pause()),
+        _ => {}
+    }
+    for (row, onset) in KANA_ROWS {
+        if let Some(pos) = row.chars().position(|k| k == c) {
+            // は行 lacks ふ in the table, so map positions by the vowel explicitly
+            let vowel = if row.starts_with('は') {
+                [Phoneme::A, Phoneme::I, Phoneme::E, Phoneme::O][pos]
+            } else {
+                VOWELS[pos]
+            };
+            return Some(Mora::new(onset, vowel));
+        }
+    }
+    None
+}
+
+/// Onset viseme for a romaji consonant cluster.
+fn romaji_onset(cluster: &str) -> Option<Phoneme> {
+    match cluster.chars().next()? {
+        'm' | 'b' | 'p' => Some(Phoneme::M),
+        'f' | 'v' => Some(Phoneme::F),
+        'w' => Some(Phoneme::W),
+        's' | 'z' | 't' | 'd' | 'c' | 'j' => Some(Phoneme::S),
+        'n' => Some(Phoneme::N),
+        _ => None,
+    }
+}
+
+/// Split kana (hiragana/katakana) and/or romaji text into morae.
+///
+/// Handles ゃゅょ/ぁぃぅぇぉ glides, っ and doubled romaji consonants (closure), ん/n', ー/-
+/// (long vowel) and punctuation pauses. Unrecognized characters (kanji, symbols) are skipped.
+pub fn text_to_morae(text: &str) -> Vec<Mora> {
+    let mut morae: Vec<Mora> = Vec::new();
+    let chars: Vec<char> = text.chars().map(to_hiragana).collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let lower = c.to_ascii_lowercase();
+        i += 1;
+
+        if let Some(n) = pause_morae(c) {
+            morae.extend(std::iter::repeat_n(Mora::pause(), n));
+            continue;
+        }
+        if c == 'ー' || c == '-' {
+            if let Some(prev) = morae.last() {
+                morae.push(Mora::new(None, prev.nucleus));
+            }
+            continue;
+        }
+        // Small kana modify the previous mora's vowel (きゃ, ファ)
+        let glide = match c {
+            'ゃ' | 'ぁ' => Some(Phoneme::A),
+            'ぃ' => Some(Phoneme::I),
+            'ゅ' | 'ぅ' => Some(Phoneme::U),
+            'ぇ' => Some(Phoneme::E),
+            'ょ' | 'ぉ' => Some(Phoneme::O),
+            _ => None,
+        };
+        if let Some(vowel) = glide {
+            match morae.last_mut() {
+                Some(prev) => prev.nucleus = vowel,
+                None => morae.push(Mora::new(None, vowel)),
+            }
+            continue;
+        }
+        if let Some(mora) = kana_mora(c) {
+            morae.push(mora);
+            continue;
+        }
+
+        if lower.is_ascii_alphabetic() {
+            if let Some(vowel) = vowel_from_ascii(lower) {
+                morae.push(Mora::new(None, vowel));
+                continue;
+            }
+            // Consonant cluster up to the next vowel
+            let start = i - 1;
+            let mut end = i;
+            while end < chars.len()
+                && chars[end].is_ascii_alphabetic()
+                && vowel_from_ascii(chars[end].to_ascii_lowercase()).is_none()
+            {
+                end += 1;
+            }
+            let cluster: String = chars[start..end]
+                .iter()
+                .map(|c| c.to_ascii_lowercase())
+                .collect();
+            let next_vowel = chars
+                .get(end)
+                .and_then(|c| vowel_from_ascii(c.to_ascii_lowercase()));
+
+            if cluster.starts_with('n') && (next_vowel.is_none() || cluster.len() > 1) {
+                let rest = &cluster[1..];
+                // ん: "n" before a consonant/end, "nn", or "n'"
+                morae.push(Mora::new(None, Phoneme::N));
+                i = start + 1;
+                if rest.starts_with('n') && next_vowel.is_none() {
+                    i += 1;
+                }
+                if chars.get(i) == Some(&'\'') {
+                    i += 1;
+                }
+                continue;
+            }
+            match next_vowel {
+                Some(vowel) => {
+                    let mut cluster = cluster.as_str();
+                    let bytes = cluster.as_bytes();
+                    // Doubled consonant → っ closure (kk, tt, pp, tch)
+                    if bytes.len() > 1 && (bytes[0] == bytes[1] || cluster.starts_with("tch")) {
+                        morae.push(Mora::pause());
+                        cluster = &cluster[1..];
+                    }
+                    morae.push(Mora::new(romaji_onset(cluster), vowel));
+                    i = end + 1;
+                }
+                None => i = end,
+            }
+        }
+    }
+    morae
+}
+
+/// Generate a plausible lip sync track from a dialogue string before audio exists.
+///
+/// Each mora becomes an optional consonant keyframe followed by its nucleus at
+/// `ONSET_FRACTION` of the mora; repeated shapes are merged and the mouth closes at the end.
+pub fn lip_sync_from_text(
+    name: impl Into<String>,
+    text: &str,
+    timing: &TextTiming,
+) -> LipSyncTrack {
+    let morae = text_to_morae(text);
+    let mut track = LipSyncTrack::new(name);
+    if morae.is_empty() {
+        return track;
+    }
+
+    let mora_duration = |index: usize| -> f32 {
+        match timing {
+            TextTiming::Total(total) => total / morae.len() as f32,
+            TextTiming::MoraDuration(d) => *d,
+            TextTiming::MoraDurations(ds) => {
+                ds.get(index).or_else(|| ds.last()).copied().unwrap_or(0.0)
+            }
+        }
+    };
+
+    let mut time = 0.0f32;
+    let mut prev = Phoneme::Closed;
+    let mut push = |track: &mut LipSyncTrack, t: f32, phoneme: Phoneme| {
+        if phoneme != prev {
+            track.add_phoneme(t, phoneme);
+            prev = phoneme;
+        }
+    };
+    for (index, mora) in morae.iter().enumerate() {
+        let duration = mora_duration(index).max(0.0);
+        match mora.onset {
+            Some(onset) => {
+                push(&mut track, time, onset);
+                push(&mut track, time + duration * ONSET_FRACTION, mora.nucleus);
+            }
+            None => push(&mut track, time, mora.nucleus),
+        }
+        time += duration;
+    }
+    push(&mut track, time, Phoneme::Closed);
+    track
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kana_morae() {
+        // こんにちは: ko-n-ni-chi-ha (5 morae)
+        let morae = text_to_morae("こんにちは");
+        assert_eq!(morae.len(), 5);
+        assert_eq!(morae[1].nucleus, Phoneme::N);
+        assert_eq!(morae[3], Mora::new(Some(Phoneme::S), Phoneme::I));
+
+        // キャット: kya-(っ)-to, katakana and glides
+        let morae = text_to_morae("キャット");
+        assert_eq!(morae.len(), 3);
+        assert_eq!(morae[0].nucleus, Phoneme::A);
+        assert_eq!(morae[1].nucleus, Phoneme::Closed);
+    }
+
+    #[test]
+    fn test_romaji_matches_kana() {
+        let kana = text_to_morae("まっすぐ、ほんとう");
+        let romaji = text_to_morae("massugu, hontou");
+        assert_eq!(kana, romaji);
+        assert_eq!(kana[0].onset, Some(Phoneme::M));
+        assert_eq!(kana[4].nucleus, Phoneme::Closed); // pause
+    }
+
+    #[test]
+    fn test_lip_sync_from_text_timing() {
+        let track = lip_sync_from_text("line", "ばあ", &TextTiming::Total(1.0));
+        // M onset, A nucleus (held through あ), closed at end
+        let shapes: Vec<Phoneme> = track.phonemes.iter().map(|k| k.phoneme).collect();
+        assert_eq!(shapes, vec![Phoneme::M, Phoneme::A, Phoneme::Closed]);
+        assert!((track.duration() - 1.0).abs() < 1e-5);
+
+        let track = lip_sync_from_text("line", "aiu", &TextTiming::MoraDurations(vec![0.2, 0.3]));
+        assert!((track.duration() - 0.8).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,108 @@
+//! Time-warp curves for clip instances.
+//!
+//! A [`TimeWarp`] remaps the time elapsed in a clip instance before the clip is read, so
+//! a clip is retimed without touching its keys: a walk cycle sped up into a comedic
+//! fast-forward, a wave that freezes mid-gesture, a double take played backwards, or a
+//! stuttering repeat.
+
+use alice_sdf::animation::Track;
+use serde::{Deserialize, Serialize};
+
+use crate::cycle::floor;
+
+/// Remap of elapsed instance time (0..length) to clip time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TimeWarp {
+    /// Constant speed factor (2 = twice as fast).
+    Speed(f32),
+    /// Slow-in / slow-out over the instance: same start and end, eased in between.
+    Ease,
+    /// Play the instance backwards.
+    Reverse,
+    /// Freeze on the pose at `at` for `duration` seconds, then carry on from there.
+    Hold { at: f32, duration: f32 },
+    /// Play each `step` seconds `repeat` times before moving on.
+    Stutter { step: f32, repeat: u32 },
+    /// Keyed remap: the curve's value at the elapsed time is the clip time.
+    Curve(Track),
+}
+
+impl TimeWarp {
+    /// Clip time for `elapsed` seconds into an instance `length` seconds long.
+    pub fn map(&self, elapsed: f32, length: f32) -> f32 {
+        match self {
+            Self::Speed(speed) => elapsed * speed,
+            Self::Ease => {
+                if length <= 0.0 {
+                    return elapsed;
+                }
+                let t = (elapsed / length).clamp(0.0, 1.0);
+                length * t * t * (3.0 - 2.0 * t)
+            }
+            Self::Reverse => length - elapsed,
+            Self::Hold { at, duration } => {
+                if elapsed < *at {
+                    elapsed
+                } else if elapsed < at + duration {
+                    *at
+                } else {
+                    elapsed - duration
+                }
+            }
+            Self::Stutter { step, repeat } => {
+                if *step <= 0.0 || *repeat == 0 {
+                    return elapsed;
+                }
+                let n = floor(elapsed / step);
+                let chunk = n.div_euclid(*repeat as i64);
+                chunk as f32 * step + (elapsed - n as f32 * step)
+            }
+            Self::Curve(curve) if !curve.keyframes.is_empty() => curve.evaluate(elapsed),
+            Self::Curve(_) => elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::animation::Keyframe;
+
+    #[test]
+    fn test_warps_remap_elapsed_time() {
+        assert_eq!(TimeWarp::Speed(2.0).map(0.75, 1.0), 1.5);
+        assert_eq!(TimeWarp::Reverse.map(0.25, 2.0), 1.75);
+        let ease = TimeWarp::Ease;
+        assert_eq!(
+            (ease.map(0.0, 2.0), ease.map(1.0, 2.0), ease.map(2.0, 2.0)),
+            (0.0, 1.0, 2.0)
+        );
+        assert!(ease.map(0.2, 2.0) < 0.2);
+        let hold = TimeWarp::Hold {
+            at: 0.5,
+            duration: 1.0,
+        };
+        assert_eq!(
+            (hold.map(0.25, 3.0), hold.map(1.2, 3.0), hold.map(2.0, 3.0)),
+            (0.25, 0.5, 1.0)
+        );
+    }
+
+    #[test]
+    fn test_stutter_and_curve() {
+        let stutter = TimeWarp::Stutter {
+            step: 0.5,
+            repeat: 2,
+        };
+        // 0..0.5 plays twice, then 0.5..1.0 twice
+        assert!((stutter.map(0.25, 4.0) - 0.25).abs() < 1e-6);
+        assert!((stutter.map(0.75, 4.0) - 0.25).abs() < 1e-6);
+        assert!((stutter.map(1.25, 4.0) - 0.75).abs() < 1e-6);
+        assert!((stutter.map(1.75, 4.0) - 0.75).abs() < 1e-6);
+        let mut curve = Track::new("remap");
+        curve.add_keyframe(Keyframe::new(0.0, 1.0));
+        curve.add_keyframe(Keyframe::new(1.0, 0.0));
+        assert_eq!(TimeWarp::Curve(curve).map(0.25, 1.0), 0.75);
+        assert_eq!(TimeWarp::Curve(Track::new("empty")).map(0.4, 1.0), 0.4);
+    }
+}
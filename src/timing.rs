@@ -0,0 +1,227 @@
+//! Timing charts: traditional slow-in / slow-out spacing between two key poses.
+//!
+//! A chart lists the frames every drawing is exposed on (`1-3-5-7` is one key, two
+//! in-betweens and a key, on twos) and which end the in-betweens favor. Each gap is
+//! `ratio` times the next one toward the favored key, so the default ratio of 2 gives the
+//! classic halves chart. Applying a chart to any track writes the in-betweens as plain keys.
+
+use alice_sdf::animation::{Keyframe, Timeline, Track};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AnimationError;
+
+/// Which key the in-betweens bunch up against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Favor {
+    /// Equal spacing.
+    Even,
+    /// Slow out of the first key.
+    Start,
+    /// Slow into the last key.
+    #[default]
+    End,
+    /// Slow out and slow in around a middle breakdown.
+    Both,
+}
+
+impl Favor {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "even" | "linear" => Some(Self::Even),
+            "start" | "first" | "out" => Some(Self::Start),
+            "end" | "last" | "in" => Some(Self::End),
+            "both" | "middle" => Some(Self::Both),
+            _ => None,
+        }
+    }
+}
+
+/// Exposure frames of a move plus where its in-betweens are favored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimingChart {
+    /// Frame numbers of every drawing, both keys included, in increasing order.
+    pub frames: Vec<u32>,
+    pub favor: Favor,
+    /// Size of each gap relative to the next one toward the favored key (2 = halves).
+    pub ratio: f32,
+}
+
+impl TimingChart {
+    pub fn new(frames: Vec<u32>, favor: Favor) -> Self {
+        Self {
+            frames,
+            favor,
+            ratio: 2.0,
+        }
+    }
+
+    /// `inbetweens` drawings between keys `step` frames apart (2 = on twos).
+    pub fn on(step: u32, inbetweens: u32, favor: Favor) -> Self {
+        let step = step.max(1);
+        Self::new((0..=inbetweens + 1).map(|i| 1 + i * step).collect(), favor)
+    }
+
+    pub fn with_ratio(mut self, ratio: f32) -> Self {
+        self.ratio = ratio;
+        self
+    }
+
+    /// Parse the notation on a key drawing: `"1-3-5-7 favor end"`, `"1-4-7 even"`,
+    /// `"1-3-5-7-9 favoring both"`. Favor defaults to the end.
+    pub fn parse(text: &str) -> crate::error::Result<Self> {
+        let invalid = AnimationError::InvalidInput;
+        let mut words = text.split_whitespace();
+        let numbers = words
+            .next()
+            .ok_or_else(|| invalid("empty timing chart".into()))?;
+        let frames = numbers
+            .split('-')
+            .map(|n| n.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()
+            .ok_or_else(|| invalid(format!("bad chart frames {numbers:?}")))?;
+        if frames.len() < 2 || frames.windows(2).any(|w| w[1] <= w[0]) {
+            return Err(invalid(format!(
+                "chart {numbers:?} needs two or more increasing frames"
+            )));
+        }
+        let mut favor = Favor::default();
+        for word in words {
+            match word.to_ascii_lowercase().as_str() {
+                "favor" | "favoring" | "favour" | "favouring" | "the" | "toward" | "towards" => {}
+                other => {
+                    favor = Favor::parse(other)
+                        .ok_or_else(|| invalid(format!("unknown favor {other:?}")))?;
+                }
+            }
+        }
+        Ok(Self::new(frames, favor))
+    }
+
+    /// Number of in-between drawings.
+    #[inline]
+    pub fn inbetweens(&self) -> usize {
+        self.frames.len().saturating_sub(2)
+    }
+
+    /// Chart length in seconds at `fps`.
+    pub fn duration(&self, fps: f32) -> f32 {
+        match (self.frames.first(), self.frames.last()) {
+            (Some(first), Some(last)) if fps > 0.0 => (last - first) as f32 / fps,
+            _ => 0.0,
+        }
+    }
+
+    /// Position of every drawing between the keys, from 0 (first key) to 1 (last key).
+    pub fn spacing(&self) -> Vec<f32> {
+        let gaps = self.frames.len().saturating_sub(1);
+        if gaps == 0 {
+            return vec![0.0];
+        }
+        let ratio = if self.ratio > 0.0 { self.ratio } else { 1.0 };
+        // Gap i shrinks by `ratio` per step toward each favored key
+        let steps_to_favored = |i: usize| -> i32 {
+            let from_end = (gaps - 1 - i) as i32;
+            match self.favor {
+                Favor::Even => 0,
+                Favor::Start => i as i32,
+                Favor::End => from_end,
+                Favor::Both => (i as i32).min(from_end),
+            }
+        };
+        let sizes: Vec<f32> = (0..gaps).map(|i| ratio.powi(steps_to_favored(i))).collect();
+        let total: f32 = sizes.iter().sum();
+        let mut position = 0.0;
+        let mut spacing = Vec::with_capacity(gaps + 1);
+        spacing.push(0.0);
+        for size in &sizes[..gaps - 1] {
+            position += size / total;
+            spacing.push(position);
+        }
+        spacing.push(1.0);
+        spacing
+    }
+
+    /// In-between keys (keys excluded) from `from` to `to`, with chart frames mapped
+    /// proportionally onto the span between the two key times.
+    pub fn inbetween_keys(&self, from: Keyframe, to: Keyframe) -> Vec<Keyframe> {
+        let (Some(&first), Some(&last)) = (self.frames.first(), self.frames.last()) else {
+            return Vec::new();
+        };
+        let span = (last - first).max(1) as f32;
+        let spacing = self.spacing();
+        self.frames
+            .iter()
+            .zip(spacing)
+            .skip(1)
+            .take(self.inbetweens())
+            .map(|(&frame, s)| {
+                let time = from.time + (to.time - from.time) * (frame - first) as f32 / span;
+                Keyframe::new(time, from.value + (to.value - from.value) * s)
+            })
+            .collect()
+    }
+
+    /// Replace the keys of `track` strictly between `start` and `end` with the chart's
+    /// in-betweens, keying both ends at their current values first.
+    pub fn apply_track(&self, track: &mut Track, start: f32, end: f32) {
+        if end <= start || track.keyframes.is_empty() {
+            return;
+        }
+        let from = Keyframe::new(start, track.evaluate(start));
+        let to = Keyframe::new(end, track.evaluate(end));
+        track.keyframes.retain(|k| k.time < start || k.time > end);
+        for key in [from, to].into_iter().chain(self.inbetween_keys(from, to)) {
+            track.add_keyframe(key);
+        }
+    }
+
+    /// [`apply_track`](Self::apply_track) on every keyed track of `timeline`.
+    pub fn apply(&self, timeline: &mut Timeline, start: f32, end: f32) {
+        for track in &mut timeline.tracks {
+            self.apply_track(track, start, end);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chart_spacing() {
+        let chart = TimingChart::parse("1-3-5-7 favoring the end").unwrap();
+        assert_eq!(chart.inbetweens(), 2);
+        assert!((chart.duration(24.0) - 0.25).abs() < 1e-6);
+        let expected = [0.0, 4.0 / 7.0, 6.0 / 7.0, 1.0];
+        for (s, e) in chart.spacing().iter().zip(expected) {
+            assert!((s - e).abs() < 1e-6);
+        }
+        let both = TimingChart::on(2, 3, Favor::Both).spacing();
+        assert!((both[2] - 0.5).abs() < 1e-6 && (both[1] - 1.0 / 6.0).abs() < 1e-6);
+        let even = TimingChart::parse("1-4-7 even").unwrap().spacing();
+        assert!((even[1] - 0.5).abs() < 1e-6);
+        assert!(matches!(
+            TimingChart::parse("1-5-3"),
+            Err(AnimationError::InvalidInput(_))
+        ));
+        assert!(TimingChart::parse("1-3 favor sideways").is_err());
+    }
+
+    #[test]
+    fn test_apply_chart_to_track() {
+        let mut track = Track::new("translate.x");
+        track.add_keyframe(Keyframe::new(0.0, 0.0));
+        track.add_keyframe(Keyframe::new(0.5, 3.0));
+        track.add_keyframe(Keyframe::new(1.0, 7.0));
+        let chart = TimingChart::on(2, 3, Favor::Start).with_ratio(3.0);
+        chart.apply_track(&mut track, 0.0, 1.0);
+        // The old breakdown is replaced by the chart's drawings
+        let times: Vec<f32> = track.keyframes.iter().map(|k| k.time).collect();
+        assert_eq!(times, vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+        let values: Vec<f32> = track.keyframes.iter().map(|k| k.value).collect();
+        // Gaps 1:3:9:27 of the 7-unit move, slow out of the first key
+        assert!((values[1] - 7.0 / 40.0).abs() < 1e-5);
+        assert!((values[3] - 7.0 * 13.0 / 40.0).abs() < 1e-5);
+        assert_eq!(values[4], 7.0);
+    }
+}
@@ -0,0 +1,200 @@
+//! Tracking shots solved from an actor's motion.
+//!
+//! A [`TrackingShot`] generates the camera track of an action cut from how one actor moves
+//! through it: the camera rides alongside at a fixed offset in the actor's direction of
+//! travel and aims so the actor sits at a chosen screen position. Lag lets the operator
+//! fall behind sudden moves and catch up; framing drift adds a slow wander to the screen
+//! position so the shot does not look locked off.
+
+use std::f32::consts::TAU;
+
+use glam::{Vec2, Vec3};
+
+use crate::camera::CameraTrack;
+use crate::director::Cut;
+use crate::performance::Performance;
+use crate::scene::ActorId;
+
+/// Framing and follow settings for a tracking shot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackingShot {
+    /// Where the actor sits on screen, in normalized device coordinates (x right, y up).
+    pub screen: Vec2,
+    /// Camera offset from the actor in its travel frame: x to the side, y up, z behind.
+    pub offset: Vec3,
+    /// Seconds the camera takes to close most of the gap to a sudden move (0 = locked on).
+    pub lag: f32,
+    /// Wander of the screen position, in normalized device coordinates.
+    pub drift: f32,
+    /// Seconds per wander cycle.
+    pub drift_period: f32,
+    pub fov: f32,
+    /// Frame width over height.
+    pub aspect: f32,
+    /// Camera keys per second.
+    pub sample_rate: f32,
+}
+
+impl Default for TrackingShot {
+    fn default() -> Self {
+        Self {
+            screen: Vec2::ZERO,
+            offset: Vec3::new(6.0, 1.0, 0.0),
+            lag: 0.15,
+            drift: 0.0,
+            drift_period: 3.0,
+            fov: std::f32::consts::FRAC_PI_4,
+            aspect: 16.0 / 9.0,
+            sample_rate: 12.0,
+        }
+    }
+}
+
+impl TrackingShot {
+    /// Keep the actor at `screen` (e.g. `(-0.3, 0.0)` to leave running room ahead).
+    pub fn with_screen(mut self, screen: Vec2) -> Self {
+        self.screen = screen;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_lag(mut self, lag: f32) -> Self {
+        self.lag = lag;
+        self
+    }
+
+    pub fn with_drift(mut self, drift: f32, period: f32) -> Self {
+        self.drift = drift;
+        self.drift_period = period;
+        self
+    }
+
+    pub fn with_fov(mut self, fov: f32, aspect: f32) -> Self {
+        self.fov = fov;
+        self.aspect = aspect;
+        self
+    }
+
+    /// Screen position aimed for at cut-local `time`, drift included.
+    pub fn screen_at(&self, time: f32) -> Vec2 {
+        if self.drift == 0.0 {
+            return self.screen;
+        }
+        // Two incommensurate sways, so the wander never visibly repeats
+        let phase = TAU * time / self.drift_period.max(1e-3);
+        self.screen + Vec2::new(phase.sin(), (phase * 0.618 + 1.3).sin() * 0.6) * self.drift
+    }
+
+    /// Camera track (cut-local keys) following `actor` through `cut`, as `scene` plays it.
+    pub fn solve(&self, scene: Performance<'_>, cut: &Cut, actor: ActorId) -> CameraTrack {
+        let duration = cut.duration().max(0.0);
+        let position = |t: f32| scene.actor_position_at(actor, cut.start_time + t);
+        let travel = (position(duration) - position(0.0)) * Vec3::new(1.0, 0.0, 1.0);
+        let ahead = travel.normalize_or(Vec3::Z);
+        let side = Vec3::Y.cross(ahead);
+        let rig = side * self.offset.x + Vec3::Y * self.offset.y - ahead * self.offset.z;
+
+        let mut track = CameraTrack::default();
+        track.clear_keyframes();
+        let samples = (duration * self.sample_rate).ceil().max(1.0) as u32;
+        let mut anchor = position(0.0);
+        let mut last = 0.0;
+        for s in 0..=samples {
+            let t = (s as f32 / self.sample_rate.max(1e-3)).min(duration);
+            // Exponential chase of the actor
+            let follow = if self.lag > 0.0 {
+                1.0 - (-(t - last) / self.lag).exp()
+            } else {
+                1.0
+            };
+            anchor += (position(t) - anchor) * follow;
+            last = t;
+            let camera = anchor + rig;
+            let forward = self.aim(anchor - camera, self.screen_at(t));
+            let target = camera + forward * (anchor - camera).length().max(1e-3);
+            track.add_keyframe(t, camera, target, self.fov);
+        }
+        track
+    }
+
+    /// View direction that puts direction `to_subject` at `screen`, with no roll.
+    fn aim(&self, to_subject: Vec3, screen: Vec2) -> Vec3 {
+        let d = to_subject.normalize_or(Vec3::Z);
+        let half_height = (self.fov * 0.5).tan();
+        let (a, b) = (screen.x * half_height * self.aspect, screen.y * half_height);
+        // Pitch first: the subject's elevation fixes it for a camera that does not roll
+        let n = (1.0 + a * a + b * b).sqrt();
+        let pitch = (n * d.y / (1.0 + b * b).sqrt()).clamp(-1.0, 1.0).asin() - b.atan();
+        let (sin, cos) = pitch.sin_cos();
+        // Then yaw: turn until the subject's heading matches
+        let unturned = Vec3::new(-a, 0.0, cos - b * sin);
+        let yaw = d.x.atan2(d.z) - unturned.x.atan2(unturned.z);
+        Vec3::new(yaw.sin() * cos, sin, yaw.cos() * cos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Actor, SceneGraph};
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+    use alice_sdf::SdfNode;
+
+    fn sprint() -> (SceneGraph, ActorId) {
+        let mut tl = Timeline::new("sprint");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(2.0, 20.0));
+        tl.add_track(x);
+        let mut y = Track::new("translate.y");
+        y.add_keyframe(Keyframe::new(0.0, 0.0));
+        y.add_keyframe(Keyframe::new(1.0, 1.5));
+        y.add_keyframe(Keyframe::new(2.0, 0.0));
+        tl.add_track(y);
+        let mut sg = SceneGraph::new();
+        let id = sg.add_actor(Actor::new("hero", SdfNode::sphere(0.5)).with_timeline(tl));
+        (sg, id)
+    }
+
+    #[test]
+    fn test_actor_held_at_screen_position() {
+        let (sg, hero) = sprint();
+        let cut = Cut::new("chase", 3.0, 5.0);
+        let screen = Vec2::new(-0.4, 0.2);
+        let shot = TrackingShot::default().with_screen(screen).with_lag(0.0);
+        let scene = Performance::from(&sg);
+        let track = shot.solve(scene, &cut, hero);
+        for t in [0.0, 0.5, 1.25, 2.0] {
+            let camera = track.evaluate(t);
+            let p = scene.actor_position_at(hero, 3.0 + t);
+            let on_screen = camera.project(p, shot.aspect, Vec3::Y).unwrap();
+            assert!((on_screen - screen).length() < 1e-3, "{t}: {on_screen}");
+            // Side-on, six units away
+            assert!((camera.position.distance(p) - 6.0f32.hypot(1.0)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_lag_and_drift_loosen_framing() {
+        let (sg, hero) = sprint();
+        let cut = Cut::new("chase", 0.0, 2.0);
+        let scene = Performance::from(&sg);
+        let lagged = TrackingShot::default()
+            .with_lag(0.3)
+            .solve(scene, &cut, hero);
+        let camera = lagged.evaluate(1.0);
+        let p = scene.actor_position_at(hero, 1.0);
+        // The lagging camera lets the actor run off the frame centre
+        let x = camera.project(p, 16.0 / 9.0, Vec3::Y).unwrap().x;
+        assert!(x.abs() > 0.05);
+
+        let drifting = TrackingShot::default().with_drift(0.1, 2.0);
+        assert_eq!(drifting.screen_at(0.0).x, 0.0);
+        assert!((drifting.screen_at(0.5) - Vec2::new(0.1, 0.0)).length() < 0.07);
+        assert!(drifting.screen_at(1.3).length() <= 0.1 * 1.2);
+    }
+}
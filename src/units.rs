@@ -0,0 +1,241 @@
+//! Project units and axis conventions.
+//!
+//! An episode is authored in the frame its [`Units`] declare: which axis is up and, if the
+//! project uses real-world scale, how many meters one unit is. Camera math takes its up
+//! vector from here, exporters write it out, and the `convert_*` helpers bring points,
+//! transforms, SDFs and keyframed tracks from another tool's convention into the project's.
+//! All conventions are right-handed and keep +X.
+
+use alloc::format;
+use alloc::vec::Vec;
+use core::f32::consts::FRAC_PI_2;
+
+use alice_sdf::animation::{Timeline, Track};
+use alice_sdf::SdfNode;
+use glam::{Mat3, Quat, Vec3};
+use serde::{Deserialize, Serialize};
+
+use crate::scene::ActorTransform;
+
+/// World up axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UpAxis {
+    #[default]
+    Y,
+    Z,
+}
+
+impl UpAxis {
+    #[inline]
+    pub fn vector(self) -> Vec3 {
+        match self {
+            Self::Y => Vec3::Y,
+            Self::Z => Vec3::Z,
+        }
+    }
+
+    /// `"Y"` or `"Z"`, as written by USD and stored in episode metadata.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Y => "Y",
+            Self::Z => "Z",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "Y" | "y" => Some(Self::Y),
+            "Z" | "z" => Some(Self::Z),
+            _ => None,
+        }
+    }
+}
+
+/// Up axis plus length unit of a project or asset.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Units {
+    pub up_axis: UpAxis,
+    /// Length of one unit in meters; `None` for arbitrary units, which are never rescaled.
+    pub meters_per_unit: Option<f32>,
+}
+
+impl Units {
+    pub const fn new(up_axis: UpAxis, meters_per_unit: Option<f32>) -> Self {
+        Self {
+            up_axis,
+            meters_per_unit,
+        }
+    }
+
+    pub const fn meters(up_axis: UpAxis) -> Self {
+        Self::new(up_axis, Some(1.0))
+    }
+
+    pub const fn centimeters(up_axis: UpAxis) -> Self {
+        Self::new(up_axis, Some(0.01))
+    }
+
+    #[inline]
+    pub fn up(&self) -> Vec3 {
+        self.up_axis.vector()
+    }
+
+    /// Rotation taking directions in `from`'s axes to these (a quarter turn about X when the
+    /// up axes differ).
+    pub fn rotation_from(&self, from: &Units) -> Quat {
+        match (from.up_axis, self.up_axis) {
+            (UpAxis::Y, UpAxis::Z) => Quat::from_rotation_x(FRAC_PI_2),
+            (UpAxis::Z, UpAxis::Y) => Quat::from_rotation_x(-FRAC_PI_2),
+            _ => Quat::IDENTITY,
+        }
+    }
+
+    /// Factor turning lengths in `from` units into these; 1 when either side is arbitrary.
+    pub fn scale_from(&self, from: &Units) -> f32 {
+        match (from.meters_per_unit, self.meters_per_unit) {
+            (Some(a), Some(b)) if a > 0.0 && b > 0.0 => a / b,
+            _ => 1.0,
+        }
+    }
+
+    #[inline]
+    pub fn convert_point(&self, p: Vec3, from: &Units) -> Vec3 {
+        self.rotation_from(from) * p * self.scale_from(from)
+    }
+
+    #[inline]
+    pub fn convert_direction(&self, d: Vec3, from: &Units) -> Vec3 {
+        self.rotation_from(from) * d
+    }
+
+    /// Re-express a local transform; converting every transform of a hierarchy keeps its
+    /// world placement consistent.
+    pub fn convert_transform(&self, t: &ActorTransform, from: &Units) -> ActorTransform {
+        let rotation = self.rotation_from(from);
+        ActorTransform {
+            position: self.convert_point(t.position, from),
+            rotation: (rotation * t.rotation * rotation.inverse()).normalize(),
+            scale: (rotation * t.scale).abs(),
+        }
+    }
+
+    /// Wrap an SDF authored in `from`'s convention so it reads correctly in these units.
+    pub fn convert_sdf(&self, node: SdfNode, from: &Units) -> SdfNode {
+        let scale = self.scale_from(from);
+        let node = if scale != 1.0 {
+            node.scale(scale)
+        } else {
+            node
+        };
+        if self.up_axis == from.up_axis {
+            node
+        } else {
+            node.rotate(self.rotation_from(from))
+        }
+    }
+
+    /// Convert the `<prefix>.x/y/z` tracks of `timeline` (translations, positions): axes are
+    /// swapped and negated as the rotation requires and values rescaled.
+    pub fn convert_tracks(&self, timeline: &mut Timeline, prefix: &str, from: &Units) {
+        self.remap_tracks(timeline, prefix, from, self.scale_from(from));
+    }
+
+    /// Convert the `rotate.x/y/z` angle tracks of `timeline`: each turns about the axis
+    /// its own axis maps to, with the same sign change as translations and no rescaling.
+    /// Exact for rotations about a single axis (e.g. a yaw track); keys that combine
+    /// several axes keep their per-axis angles, not their composed orientation.
+    pub fn convert_rotation_tracks(&self, timeline: &mut Timeline, from: &Units) {
+        self.remap_tracks(timeline, "rotate", from, 1.0);
+    }
+
+    fn remap_tracks(&self, timeline: &mut Timeline, prefix: &str, from: &Units, scale: f32) {
+        let names = ["x", "y", "z"].map(|axis| format!("{}.{}", prefix, axis));
+        let mut taken: [Option<Track>; 3] = [None, None, None];
+        let mut kept = Vec::with_capacity(timeline.tracks.len());
+        for track in timeline.tracks.drain(..) {
+            match names.iter().position(|n| *n == track.name) {
+                Some(i) => taken[i] = Some(track),
+                None => kept.push(track),
+            }
+        }
+        timeline.tracks = kept;
+        let matrix = Mat3::from_quat(self.rotation_from(from));
+        for (axis, name) in names.iter().enumerate() {
+            // Output axis `axis` reads the one input axis with a non-zero coefficient
+            let row = matrix.row(axis).to_array();
+            let Some(source) = (0..3).find(|&j| row[j].abs() > 0.5) else {
+                continue;
+            };
+            let Some(mut track) = taken[source].clone() else {
+                continue;
+            };
+            let factor = row[source].signum() * scale;
+            track.name = name.clone();
+            for kf in &mut track.keyframes {
+                kf.value *= factor;
+            }
+            timeline.tracks.push(track);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alice_sdf::animation::Keyframe;
+
+    const Y_CM: Units = Units::centimeters(UpAxis::Y);
+    const Z_M: Units = Units::meters(UpAxis::Z);
+
+    #[test]
+    fn test_points_transforms_and_sdfs() {
+        let p = Z_M.convert_point(Vec3::new(100.0, 200.0, 300.0), &Y_CM);
+        assert!((p - Vec3::new(1.0, -3.0, 2.0)).length() < 1e-5);
+        assert_eq!(Units::default().scale_from(&Y_CM), 1.0);
+        assert!((Y_CM.convert_direction(Vec3::Z, &Z_M) - Vec3::Y).length() < 1e-6);
+
+        let t = ActorTransform {
+            position: Vec3::new(0.0, 100.0, 0.0),
+            rotation: Quat::from_rotation_y(0.5),
+            scale: Vec3::new(1.0, 2.0, 3.0),
+        };
+        let converted = Z_M.convert_transform(&t, &Y_CM);
+        assert!((converted.position - Vec3::Z).length() < 1e-5);
+        assert!((converted.scale - Vec3::new(1.0, 3.0, 2.0)).length() < 1e-5);
+        // A turn about the old up axis is a turn about the new one
+        let (axis, angle) = converted.rotation.to_axis_angle();
+        assert!((axis - Vec3::Z).length() < 1e-4 && (angle - 0.5).abs() < 1e-4);
+
+        // A 1 m tall (Y-up, cm) pillar stands along +Z in a Z-up meter project
+        let pillar = SdfNode::box3d(10.0, 50.0, 10.0).translate(0.0, 50.0, 0.0);
+        let sdf = Z_M.convert_sdf(pillar, &Y_CM);
+        assert!(alice_sdf::eval(&sdf, Vec3::new(0.0, 0.0, 0.9)) < 0.0);
+        assert!(alice_sdf::eval(&sdf, Vec3::new(0.0, 0.9, 0.0)) > 0.0);
+    }
+
+    #[test]
+    fn test_tracks_swap_axes() {
+        let mut timeline = Timeline::new("walk");
+        for (axis, value) in [("x", 100.0), ("y", 50.0), ("z", 200.0)] {
+            let mut track = Track::new(&format!("translate.{}", axis));
+            track.add_keyframe(Keyframe::new(1.0, value));
+            timeline.add_track(track);
+        }
+        timeline.add_track(Track::new("scale"));
+        Z_M.convert_tracks(&mut timeline, "translate", &Y_CM);
+        assert_eq!(timeline.tracks.len(), 4);
+        assert!((timeline.get_value("translate.x", 1.0).unwrap() - 1.0).abs() < 1e-5);
+        assert!((timeline.get_value("translate.y", 1.0).unwrap() + 2.0).abs() < 1e-5);
+        assert!((timeline.get_value("translate.z", 1.0).unwrap() - 0.5).abs() < 1e-5);
+        assert_eq!(UpAxis::parse(UpAxis::Z.name()), Some(UpAxis::Z));
+
+        // Yaw about Y-up is yaw about Z-up; angles are never rescaled
+        let mut turn = Timeline::new("turn");
+        let mut yaw = Track::new("rotate.y");
+        yaw.add_keyframe(Keyframe::new(1.0, 0.5));
+        turn.add_track(yaw);
+        Z_M.convert_rotation_tracks(&mut turn, &Y_CM);
+        assert_eq!(turn.get_value("rotate.z", 1.0), Some(0.5));
+        assert_eq!(turn.get_value("rotate.y", 1.0), None);
+    }
+}
@@ -0,0 +1,433 @@
+//! USD layout export: scene hierarchy, baked actor transforms and the director's
+//! camera as a `.usda` stage, for film pipelines that never touch SDFs.
+
+use std::io::{self, Write};
+
+use glam::Mat4;
+
+use crate::episode::EpisodePackage;
+use crate::export::FrameRange;
+use crate::performance::Performance;
+use crate::scene::{ActorId, SceneGraph};
+
+/// Stage-level export settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsdExportOptions {
+    /// Scene units per meter are `1 / meters_per_unit`; used when the episode's units are
+    /// arbitrary, otherwise the project's value is written.
+    pub meters_per_unit: f32,
+    /// Film back height in millimetres; focal length is derived from the vertical fov.
+    pub vertical_aperture: f32,
+    /// Image aspect ratio (width / height) for the horizontal aperture;
+    /// `None` uses the episode's resolution.
+    pub aspect: Option<f32>,
+    /// Name of the root prim everything is placed under.
+    pub root: String,
+}
+
+impl Default for UsdExportOptions {
+    fn default() -> Self {
+        Self {
+            meters_per_unit: 1.0,
+            vertical_aperture: 24.0,
+            aspect: None,
+            root: "World".to_string(),
+        }
+    }
+}
+
+impl UsdExportOptions {
+    pub fn with_aspect(mut self, aspect: f32) -> Self {
+        self.aspect = Some(aspect);
+        self
+    }
+
+    pub fn with_meters_per_unit(mut self, meters_per_unit: f32) -> Self {
+        self.meters_per_unit = meters_per_unit;
+        self
+    }
+}
+
+/// Make `name` a valid USD prim identifier.
+pub fn usd_identifier(name: &str) -> String {
+    let mut id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if id.is_empty() || id.starts_with(|c: char| c.is_ascii_digit()) {
+        id.insert(0, '_');
+    }
+    id
+}
+
+/// Quote `text` as a USD string: escapes `"`, `\` and control characters, keeps the rest
+/// as UTF-8.
+pub fn usd_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Actor world transform at `time`, with the timeline's (and layers') `translate.*` offset
+/// applied in world space and `scale` on top of the actor's own scale (as the renderer does).
+fn actor_world_at(scene: Performance<'_>, id: ActorId, time: f32) -> Mat4 {
+    let world = scene.get_world_transform(id);
+    let mut scale = world.scale;
+    if let Some(s) = scene.track_value(id, "scale", time) {
+        scale *= s;
+    }
+    Mat4::from_scale_rotation_translation(scale, world.rotation, scene.actor_position_at(id, time))
+}
+
+/// USD `matrix4d` literal. glam columns are USD rows (row-vector convention).
+fn matrix_literal(m: &Mat4) -> String {
+    let rows: Vec<String> = m
+        .to_cols_array_2d()
+        .iter()
+        .map(|r| format!("({}, {}, {}, {})", r[0], r[1], r[2], r[3]))
+        .collect();
+    format!("( {} )", rows.join(", "))
+}
+
+fn write_samples<W: Write>(
+    w: &mut W,
+    indent: &str,
+    decl: &str,
+    samples: &[(u32, String)],
+) -> io::Result<()> {
+    writeln!(w, "{indent}{decl}.timeSamples = {{")?;
+    for (frame, value) in samples {
+        writeln!(w, "{indent}    {frame}: {value},")?;
+    }
+    writeln!(w, "{indent}}}")
+}
+
+/// Sibling-unique prim names for `ids`.
+fn prim_names(scene: &SceneGraph, ids: &[ActorId]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(ids.len());
+    for &id in ids {
+        let base = scene
+            .get_actor(id)
+            .map(|a| usd_identifier(&a.name))
+            .unwrap_or_else(|| format!("Actor_{}", id.0));
+        let mut name = base.clone();
+        let mut n = 1;
+        while names.contains(&name) {
+            name = format!("{base}_{n}");
+            n += 1;
+        }
+        names.push(name);
+    }
+    names
+}
+
+struct Ctx<'a> {
+    scene: &'a SceneGraph,
+    range: FrameRange,
+    /// World matrices per actor id, one per frame.
+    worlds: Vec<Vec<Mat4>>,
+}
+
+fn write_actor<W: Write>(
+    w: &mut W,
+    ctx: &Ctx,
+    id: ActorId,
+    name: &str,
+    parent: Option<ActorId>,
+    depth: usize,
+) -> io::Result<()> {
+    let indent = "    ".repeat(depth);
+    let Some(actor) = ctx.scene.get_actor(id) else {
+        return Ok(());
+    };
+    writeln!(w, "{indent}def Xform \"{name}\" (")?;
+    writeln!(
+        w,
+        "{indent}    customData = {{ string aliceName = {} }}",
+        usd_string(&actor.name)
+    )?;
+    writeln!(w, "{indent})")?;
+    writeln!(w, "{indent}{{")?;
+    let inner = format!("{indent}    ");
+    if !actor.visible {
+        writeln!(w, "{inner}token visibility = \"invisible\"")?;
+    }
+    let samples: Vec<(u32, String)> = (0..ctx.range.frame_count())
+        .map(|i| {
+            let world = ctx.worlds[id.0 as usize][i as usize];
+            let local = match parent {
+                Some(p) => ctx.worlds[p.0 as usize][i as usize].inverse() * world,
+                None => world,
+            };
+            (ctx.range.frame_number(i), matrix_literal(&local))
+        })
+        .collect();
+    write_samples(w, &inner, "matrix4d xformOp:transform", &samples)?;
+    writeln!(
+        w,
+        "{inner}uniform token[] xformOpOrder = [\"xformOp:transform\"]"
+    )?;
+
+    let children: Vec<ActorId> = ctx
+        .scene
+        .actor_ids()
+        .into_iter()
+        .filter(|c| ctx.scene.get_actor(*c).and_then(|a| a.parent) == Some(id))
+        .collect();
+    for (child, child_name) in children.iter().zip(prim_names(ctx.scene, &children)) {
+        writeln!(w)?;
+        write_actor(w, ctx, *child, &child_name, Some(id), depth + 1)?;
+    }
+    writeln!(w, "{indent}}}")
+}
+
+/// Write the episode's layout over `range` as a USD ASCII stage.
+///
+/// Actors become nested `Xform` prims following the parent hierarchy with one
+/// `xformOp:transform` sample per frame; the director's camera becomes a single
+/// `Camera` prim (hard cuts are per-frame jumps) with the active cut name sampled alongside.
+pub fn export_usda<W: Write>(
+    writer: &mut W,
+    episode: &EpisodePackage,
+    range: FrameRange,
+    options: &UsdExportOptions,
+) -> io::Result<()> {
+    if range.frame_count() == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "USD export needs a non-empty frame range",
+        ));
+    }
+    let scene = &episode.scene_graph;
+    let director = &episode.director;
+    let frames = range.frame_count();
+    let slots = scene
+        .actor_ids()
+        .iter()
+        .map(|id| id.0 + 1)
+        .max()
+        .unwrap_or(0);
+    let mut worlds = vec![Vec::new(); slots as usize];
+    let edit = Performance::new(scene, director);
+    for id in scene.actor_ids() {
+        worlds[id.0 as usize] = (0..frames)
+            .map(|i| actor_world_at(edit, id, range.frame_time(i)))
+            .collect();
+    }
+    let ctx = Ctx {
+        scene,
+        range,
+        worlds,
+    };
+    let units = episode.metadata.units();
+    let (width, height) = episode.metadata.resolution;
+    let aspect = options
+        .aspect
+        .unwrap_or(width as f32 / height.max(1) as f32);
+
+    let w = writer;
+    writeln!(w, "#usda 1.0")?;
+    writeln!(w, "(")?;
+    writeln!(w, "    defaultPrim = \"{}\"", usd_identifier(&options.root))?;
+    writeln!(w, "    doc = {}", usd_string(&episode.metadata.title))?;
+    writeln!(w, "    startTimeCode = {}", range.frame_number(0))?;
+    writeln!(w, "    endTimeCode = {}", range.frame_number(frames - 1))?;
+    writeln!(w, "    timeCodesPerSecond = {}", range.fps)?;
+    writeln!(w, "    framesPerSecond = {}", range.fps)?;
+    writeln!(
+        w,
+        "    metersPerUnit = {}",
+        units.meters_per_unit.unwrap_or(options.meters_per_unit)
+    )?;
+    writeln!(w, "    upAxis = \"{}\"", units.up_axis.name())?;
+    writeln!(w, ")")?;
+    writeln!(w)?;
+    writeln!(w, "def Xform \"{}\"", usd_identifier(&options.root))?;
+    writeln!(w, "{{")?;
+
+    let roots: Vec<ActorId> = scene
+        .actor_ids()
+        .into_iter()
+        .filter(|id| scene.get_actor(*id).is_some_and(|a| a.parent.is_none()))
+        .collect();
+    for (id, name) in roots.iter().zip(prim_names(scene, &roots)) {
+        write_actor(w, &ctx, *id, &name, None, 1)?;
+        writeln!(w)?;
+    }
+
+    let mut transform = Vec::with_capacity(frames as usize);
+    let mut focal = Vec::with_capacity(frames as usize);
+    let mut cuts = Vec::with_capacity(frames as usize);
+    for i in 0..frames {
+        let frame = range.frame_number(i);
+        let state = director.evaluate(scene, range.frame_time(i));
+        let camera = &state.camera_state;
+        let focal_length = 0.5 * options.vertical_aperture / (camera.fov * 0.5).tan().max(1e-6);
+        transform.push((
+            frame,
+            matrix_literal(&camera.inverse_view_matrix_up(units.up())),
+        ));
+        focal.push((frame, focal_length.to_string()));
+        let cut = state
+            .active_cut
+            .and_then(|id| director.get_cut(id))
+            .map(|c| c.name.as_str())
+            .unwrap_or("");
+        cuts.push((frame, usd_string(cut)));
+    }
+    writeln!(w, "    def Camera \"Camera\"")?;
+    writeln!(w, "    {{")?;
+    writeln!(
+        w,
+        "        float verticalAperture = {}",
+        options.vertical_aperture
+    )?;
+    writeln!(
+        w,
+        "        float horizontalAperture = {}",
+        options.vertical_aperture * aspect
+    )?;
+    write_samples(w, "        ", "float focalLength", &focal)?;
+    write_samples(w, "        ", "matrix4d xformOp:transform", &transform)?;
+    writeln!(
+        w,
+        "        uniform token[] xformOpOrder = [\"xformOp:transform\"]"
+    )?;
+    write_samples(w, "        ", "custom string alice:cut", &cuts)?;
+    writeln!(w, "    }}")?;
+    writeln!(w, "}}")?;
+    Ok(())
+}
+
+/// `export_usda` into a string.
+pub fn export_usda_string(
+    episode: &EpisodePackage,
+    range: FrameRange,
+    options: &UsdExportOptions,
+) -> io::Result<String> {
+    let mut out = Vec::new();
+    export_usda(&mut out, episode, range, options)?;
+    String::from_utf8(out).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Director};
+    use crate::episode::EpisodeMetadata;
+    use crate::npr::AnimeShading;
+    use crate::scene::{Actor, ActorTransform};
+    use crate::units::{UpAxis, Units};
+    use alice_sdf::animation::{Keyframe, Timeline, Track};
+    use alice_sdf::SdfNode;
+    use glam::Vec3;
+
+    fn episode() -> EpisodePackage {
+        let mut sg = SceneGraph::new();
+        let mut walk = Timeline::new("walk");
+        let mut x = Track::new("translate.x");
+        x.add_keyframe(Keyframe::new(0.0, 0.0));
+        x.add_keyframe(Keyframe::new(1.0, 2.0));
+        walk.add_track(x);
+        let hero = sg.add_actor(
+            Actor::new("hero 1", SdfNode::sphere(1.0))
+                .with_timeline(walk)
+                .with_transform(ActorTransform {
+                    position: Vec3::new(0.0, 1.0, 0.0),
+                    ..ActorTransform::default()
+                }),
+        );
+        sg.add_actor(
+            Actor::new("sword", SdfNode::box3d(0.1, 1.0, 0.1))
+                .with_parent(hero)
+                .with_transform(ActorTransform {
+                    position: Vec3::new(0.5, 0.0, 0.0),
+                    ..ActorTransform::default()
+                }),
+        );
+        sg.add_actor(Actor::new("hero 1", SdfNode::sphere(0.5)));
+
+        let mut dir = Director::new("Layout");
+        dir.add_cut(Cut::new("wide", 0.0, 0.5));
+        dir.add_cut(Cut::new("close", 0.5, 1.0));
+        let meta = EpisodeMetadata::new("Layout \"test\"", 1, 1.0);
+        EpisodePackage::new(meta, sg, dir, AnimeShading::default())
+    }
+
+    #[test]
+    fn test_usd_identifier() {
+        assert_eq!(usd_identifier("hero 1"), "hero_1");
+        assert_eq!(usd_identifier("2nd"), "_2nd");
+        assert_eq!(usd_identifier("アリス"), "___");
+        assert_eq!(usd_identifier(""), "_");
+        assert_eq!(usd_string("アリス \"A\"\n"), "\"アリス \\\"A\\\"\\n\"");
+        assert_eq!(usd_string("a\\b\u{7}"), "\"a\\\\b\\x07\"");
+    }
+
+    #[test]
+    fn test_export_usda_layout() {
+        let ep = episode();
+        let usda = export_usda_string(
+            &ep,
+            FrameRange::new(0.0, 1.0, 4.0),
+            &UsdExportOptions::default(),
+        )
+        .unwrap();
+        assert!(usda.starts_with("#usda 1.0\n"));
+        assert!(usda.contains("doc = \"Layout \\\"test\\\"\""));
+        assert!(usda.contains("startTimeCode = 0\n"));
+        assert!(usda.contains("endTimeCode = 3\n"));
+        assert!(usda.contains("timeCodesPerSecond = 4\n"));
+
+        // Duplicate sibling names are made unique; the child is nested under its parent
+        let hero = usda.find("def Xform \"hero_1\"").unwrap();
+        let sword = usda.find("def Xform \"sword\"").unwrap();
+        let other = usda.find("def Xform \"hero_1_1\"").unwrap();
+        assert!(hero < sword && sword < other);
+        assert!(usda.contains("        def Xform \"sword\""));
+
+        // Frame 2 (0.5s): hero walked to x=1 on top of its y=1 placement. Timeline offsets
+        // are not inherited, so the sword's baked local transform compensates
+        assert!(usda.contains("2: ( (1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (1, 1, 0, 1) ),"));
+        assert!(usda.contains("2: ( (1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (-0.5, 0, 0, 1) ),"));
+
+        assert!(usda.contains("def Camera \"Camera\""));
+        assert!(usda.contains("1: \"wide\","));
+        assert!(usda.contains("3: \"close\","));
+    }
+
+    #[test]
+    fn test_export_usda_camera_and_errors() {
+        let ep = episode();
+        let options = UsdExportOptions::default().with_aspect(2.0);
+        let usda = export_usda_string(&ep, FrameRange::new(0.0, 0.25, 4.0), &options).unwrap();
+        assert!(usda.contains("float horizontalAperture = 48\n"));
+        // Default camera: fov = pi/4 -> 12 / tan(pi/8)
+        let focal = 12.0 / (std::f32::consts::FRAC_PI_8).tan();
+        assert!(usda.contains(&format!("0: {focal},")));
+        // Default camera sits at z=5 looking at the origin
+        assert!(usda.contains("(0, 0, 5, 1) ),"));
+
+        assert!(usda.contains("upAxis = \"Y\"") && usda.contains("metersPerUnit = 1\n"));
+
+        let mut z_up = ep.clone();
+        z_up.metadata.set_units(Units::centimeters(UpAxis::Z));
+        let usda = export_usda_string(&z_up, FrameRange::new(0.0, 0.25, 4.0), &options).unwrap();
+        assert!(usda.contains("upAxis = \"Z\"") && usda.contains("metersPerUnit = 0.01\n"));
+
+        let err = export_usda_string(&ep, FrameRange::new(1.0, 1.0, 24.0), &options).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,438 @@
+//! Weather: rain, snow, heat haze and god rays configured per scene with intensity tracks.
+//!
+//! Precipitation is a stateless particle field around the camera target (seeded from the
+//! episode RNG, so scrubbing and parallel renders agree) unioned into the frame SDF. Rain
+//! and snow also thicken the renderer's fog; haze and god rays are screen-space passes.
+
+use alice_sdf::animation::Track;
+use alice_sdf::SdfNode;
+use glam::Vec3;
+use serde::{Deserialize, Serialize};
+
+use crate::camera::CameraState;
+use crate::director::Director;
+use crate::episode::EpisodePackage;
+use crate::framebuffer::Framebuffer;
+use crate::overlay::composite_overlays;
+use crate::plate::Fog;
+use crate::render::{Renderer, DEFAULT_TILE_SIZE};
+use crate::rng::{EpisodeRng, SeededRng};
+
+/// Effect a layer drives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WeatherKind {
+    Rain,
+    Snow,
+    HeatHaze,
+    GodRays,
+}
+
+/// One effect with its intensity (0..1) over scene-local time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherLayer {
+    pub kind: WeatherKind,
+    pub intensity: f32,
+    /// Overrides `intensity` when present.
+    pub intensity_track: Option<Track>,
+}
+
+impl WeatherLayer {
+    pub fn new(kind: WeatherKind, intensity: f32) -> Self {
+        Self {
+            kind,
+            intensity: intensity.clamp(0.0, 1.0),
+            intensity_track: None,
+        }
+    }
+
+    pub fn with_intensity_track(mut self, track: Track) -> Self {
+        self.intensity_track = Some(track);
+        self
+    }
+
+    /// Intensity `local` seconds into the scene.
+    pub fn intensity_at(&self, local: f32) -> f32 {
+        self.intensity_track
+            .as_ref()
+            .map_or(self.intensity, |t| t.evaluate(local))
+            .clamp(0.0, 1.0)
+    }
+}
+
+/// Weather for one director scene (matched by name).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneWeather {
+    pub scene: String,
+    pub layers: Vec<WeatherLayer>,
+}
+
+impl SceneWeather {
+    pub fn new(scene: impl Into<String>) -> Self {
+        Self {
+            scene: scene.into(),
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn with_layer(mut self, layer: WeatherLayer) -> Self {
+        self.layers.push(layer);
+        self
+    }
+}
+
+/// Effect intensities at one instant.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct WeatherState {
+    pub time: f32,
+    pub rain: f32,
+    pub snow: f32,
+    pub haze: f32,
+    pub god_rays: f32,
+}
+
+impl WeatherState {
+    #[inline]
+    pub fn is_clear(&self) -> bool {
+        self.rain + self.snow + self.haze + self.god_rays <= 0.0
+    }
+
+    /// Thicken `renderer`'s fog for rain and snow (adding a fog if it has none).
+    pub fn apply_environment(&self, renderer: &mut Renderer) {
+        let extra = 0.04 * self.rain + 0.06 * self.snow;
+        if extra <= 0.0 {
+            return;
+        }
+        let tint = if self.snow > self.rain {
+            [0.9, 0.92, 0.95, 1.0]
+        } else {
+            [0.55, 0.6, 0.65, 1.0]
+        };
+        let fog = renderer.fog.get_or_insert(Fog::new(tint, 0.0));
+        fog.density += extra;
+    }
+
+    /// Screen-space passes: heat shimmer (strongest near the bottom of frame) and god rays
+    /// streaming from the key light's screen position.
+    pub fn apply_post(&self, fb: &mut Framebuffer, renderer: &Renderer, camera: &CameraState) {
+        if self.haze > 0.0 {
+            heat_haze(fb, self.haze, self.time);
+        }
+        if self.god_rays > 0.0 {
+            if let Some(sun) = sun_position(renderer, camera) {
+                god_rays(fb, sun, self.god_rays);
+            }
+        }
+    }
+}
+
+/// Per-scene weather for an episode plus the particle field settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Weather {
+    pub scenes: Vec<SceneWeather>,
+    /// Particles at intensity 1 (each is one SDF primitive: keep it modest).
+    pub max_particles: u32,
+    /// Half extents of the particle box around the camera target.
+    pub volume: Vec3,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            scenes: Vec::new(),
+            max_particles: 48,
+            volume: Vec3::new(4.0, 3.0, 4.0),
+        }
+    }
+}
+
+impl Weather {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_scene(mut self, scene: SceneWeather) -> Self {
+        self.scenes.push(scene);
+        self
+    }
+
+    /// Intensities at `time`, from the scene holding the active cut; clear outside any
+    /// configured scene. Tracks run in scene-local time (from the scene's first cut).
+    pub fn evaluate(&self, director: &Director, time: f32) -> WeatherState {
+        let mut state = WeatherState {
+            time,
+            ..Default::default()
+        };
+        let Some((scene, start)) = director.active_scene(time) else {
+            return state;
+        };
+        let Some(weather) = self.scenes.iter().find(|w| w.scene == scene.name) else {
+            return state;
+        };
+        for layer in &weather.layers {
+            let value = layer.intensity_at(time - start);
+            let slot = match layer.kind {
+                WeatherKind::Rain => &mut state.rain,
+                WeatherKind::Snow => &mut state.snow,
+                WeatherKind::HeatHaze => &mut state.haze,
+                WeatherKind::GodRays => &mut state.god_rays,
+            };
+            *slot = slot.max(value);
+        }
+        state
+    }
+
+    /// Rain streaks and snow flakes around `camera`'s target, or `None` when dry.
+    ///
+    /// Particle `i` keeps its identity as intensity changes, so ramps add and remove
+    /// drops instead of reshuffling them.
+    pub fn precipitation(
+        &self,
+        state: &WeatherState,
+        camera: &CameraState,
+        rng: &EpisodeRng,
+    ) -> Option<SdfNode> {
+        let t = state.time;
+        let center = camera.target;
+        let rain = self.field(
+            rng.stream("weather/rain"),
+            state.rain,
+            center,
+            9.0 * t,
+            |p, _| SdfNode::capsule(p, p + Vec3::new(-0.02, 0.35, 0.0), 0.008),
+        );
+        let snow = self.field(
+            rng.stream("weather/snow"),
+            state.snow,
+            center,
+            0.8 * t,
+            |p, phase| {
+                let sway = 0.25 * (1.3 * t + phase * std::f32::consts::TAU).sin();
+                SdfNode::sphere(0.03).translate(p.x + sway, p.y, p.z)
+            },
+        );
+        rain.into_iter().chain(snow).reduce(|acc, p| acc.union(p))
+    }
+
+    /// `intensity * max_particles` particles drawn from `stream`, each `fallen` units down
+    /// from its seeded height and wrapping from the bottom of the volume to the top.
+    fn field<T>(
+        &self,
+        mut stream: SeededRng,
+        intensity: f32,
+        center: Vec3,
+        fallen: f32,
+        particle: impl Fn(Vec3, f32) -> T,
+    ) -> Vec<T> {
+        let count = (intensity * self.max_particles as f32).round() as u32;
+        let half = self.volume;
+        let height = 2.0 * half.y.max(1e-3);
+        (0..count)
+            .map(|_| {
+                let x = stream.range(-half.x, half.x);
+                let z = stream.range(-half.z, half.z);
+                let v = stream.next_f32() - fallen / height;
+                let phase = stream.next_f32();
+                let y = center.y - half.y + (v - v.floor()) * height;
+                particle(Vec3::new(center.x + x, y, center.z + z), phase)
+            })
+            .collect()
+    }
+
+    /// Render `episode` at `time` with this weather: particles join the frame SDF, fog is
+    /// thickened, post passes run, then the episode's overlays go on top.
+    pub fn render_episode(
+        &self,
+        renderer: &Renderer,
+        episode: &EpisodePackage,
+        time: f32,
+    ) -> Framebuffer {
+        let state = self.evaluate(&episode.director, time);
+        let director_state = episode.director.evaluate(&episode.scene_graph, time);
+        let camera = director_state.camera_state;
+        let mut sdf = episode.director.evaluate_scene(&episode.scene_graph, time);
+        if let Some(particles) = self.precipitation(&state, &camera, &episode.rng()) {
+            sdf = sdf.union(particles);
+        }
+        let objects = if renderer.settings().aovs.object_id {
+            episode.director.evaluate_actors(&episode.scene_graph, time)
+        } else {
+            Vec::new()
+        };
+        let mut renderer = renderer.clone();
+        state.apply_environment(&mut renderer);
+        let mut fb =
+            renderer.render_tiled(&sdf, &objects, &camera, &episode.shading, DEFAULT_TILE_SIZE);
+        state.apply_post(&mut fb, &renderer, &camera);
+        composite_overlays(&mut fb, &episode.overlays, time);
+        fb
+    }
+}
+
+/// Key light direction projected to pixel coordinates; `None` when it is behind the camera.
+fn sun_position(renderer: &Renderer, camera: &CameraState) -> Option<(f32, f32)> {
+    let (right, up, forward) = camera.basis(renderer.up);
+    let depth = renderer.light_dir.dot(forward);
+    if depth <= 1e-3 {
+        return None;
+    }
+    let half_height = (camera.fov * 0.5).tan();
+    let (width, height) = (renderer.width() as f32, renderer.height().max(1) as f32);
+    let ndc_x = renderer.light_dir.dot(right) / depth / (half_height * width / height);
+    let ndc_y = renderer.light_dir.dot(up) / depth / half_height;
+    Some(((ndc_x + 1.0) * 0.5 * width, (1.0 - ndc_y) * 0.5 * height))
+}
+
+/// Horizontal shimmer, rows displaced by up to `2 * amount` pixels at the bottom edge.
+fn heat_haze(fb: &mut Framebuffer, amount: f32, time: f32) {
+    let source = fb.color.clone();
+    let (width, height) = (fb.width as usize, fb.height.max(1) as usize);
+    for y in 0..height {
+        let weight = y as f32 / height as f32;
+        let shift = (2.0 * amount * weight * (0.35 * y as f32 + 8.0 * time).sin()).round() as isize;
+        if shift == 0 {
+            continue;
+        }
+        for x in 0..width {
+            let sx = (x as isize + shift).clamp(0, width as isize - 1) as usize;
+            fb.color[y * width + x] = source[y * width + sx];
+        }
+    }
+}
+
+/// Radial light shafts: each pixel gathers bright pixels on its way to the sun.
+fn god_rays(fb: &mut Framebuffer, sun: (f32, f32), amount: f32) {
+    const SAMPLES: u32 = 16;
+    const THRESHOLD: f32 = 0.8;
+    let source = fb.color.clone();
+    let (width, height) = (fb.width, fb.height);
+    let diagonal = (width as f32).hypot(height as f32).max(1.0);
+    for y in 0..height {
+        for x in 0..width {
+            let (dx, dy) = (sun.0 - x as f32, sun.1 - y as f32);
+            // Shafts fade with distance from the sun
+            let falloff = 1.0 / (1.0 + 4.0 * dx.hypot(dy) / diagonal);
+            let mut gathered = [0.0f32; 3];
+            let mut decay = 1.0;
+            for s in 1..=SAMPLES {
+                let f = s as f32 / SAMPLES as f32;
+                let (sx, sy) = (x as f32 + dx * f, y as f32 + dy * f);
+                if sx < 0.0 || sy < 0.0 || sx >= width as f32 || sy >= height as f32 {
+                    break;
+                }
+                let c = source[fb.index(sx as u32, sy as u32)];
+                let luma = 0.2126 * c[0] + 0.7152 * c[1] + 0.0722 * c[2];
+                if luma > THRESHOLD {
+                    for (g, v) in gathered.iter_mut().zip(c) {
+                        *g += v * decay;
+                    }
+                }
+                decay *= 0.9;
+            }
+            let i = fb.index(x, y);
+            for (c, g) in fb.color[i].iter_mut().zip(gathered) {
+                *c += g * amount * falloff / SAMPLES as f32;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::director::{Cut, Scene};
+    use alice_sdf::animation::Keyframe;
+
+    fn director() -> Director {
+        let mut dir = Director::new("Storm");
+        let a = dir.add_cut(Cut::new("street", 0.0, 4.0));
+        let b = dir.add_cut(Cut::new("inside", 4.0, 8.0));
+        let mut outside = Scene::new("outside");
+        outside.cuts.push(a);
+        let mut home = Scene::new("home");
+        home.cuts.push(b);
+        dir.add_scene(outside);
+        dir.add_scene(home);
+        dir
+    }
+
+    fn storm() -> Weather {
+        let mut ramp = Track::new("rain");
+        ramp.add_keyframe(Keyframe::new(0.0, 0.0));
+        ramp.add_keyframe(Keyframe::new(2.0, 1.0));
+        Weather::new().with_scene(
+            SceneWeather::new("outside")
+                .with_layer(WeatherLayer::new(WeatherKind::Rain, 0.0).with_intensity_track(ramp))
+                .with_layer(WeatherLayer::new(WeatherKind::GodRays, 0.3)),
+        )
+    }
+
+    #[test]
+    fn test_intensity_follows_scene_and_track() {
+        let weather = storm();
+        let dir = director();
+        let early = weather.evaluate(&dir, 1.0);
+        assert!((early.rain - 0.5).abs() < 1e-5);
+        assert_eq!(early.god_rays, 0.3);
+        assert_eq!(weather.evaluate(&dir, 3.0).rain, 1.0);
+        assert!(weather.evaluate(&dir, 5.0).is_clear());
+
+        let mut renderer = Renderer::new(4, 4);
+        early.apply_environment(&mut renderer);
+        assert!(renderer.fog.unwrap().density > 0.0);
+    }
+
+    #[test]
+    fn test_precipitation_is_seeded_and_falls() {
+        let weather = storm();
+        let rng = EpisodeRng::new(3);
+        let dry = WeatherState::default();
+        assert!(weather
+            .precipitation(&dry, &CameraState::default(), &rng)
+            .is_none());
+        let drops = |rain: f32, fallen: f32| {
+            weather.field(
+                rng.stream("weather/rain"),
+                rain,
+                Vec3::ZERO,
+                fallen,
+                |p, _| p,
+            )
+        };
+        let a = drops(0.5, 0.0);
+        assert_eq!(a.len(), 24);
+        assert_eq!(drops(0.5, 0.0), a);
+        // Raising intensity keeps the existing drops
+        assert_eq!(drops(1.0, 0.0)[..24], a[..]);
+        let later = drops(0.5, 0.5);
+        assert!(later.iter().zip(&a).any(|(l, p)| l.y < p.y && l.x == p.x));
+        assert!(a.iter().all(|p| p.y.abs() <= 3.0));
+    }
+
+    #[test]
+    fn test_god_rays_brighten_towards_sun() {
+        let renderer = Renderer::new(16, 16).with_light(Vec3::new(0.0, 0.0, -1.0));
+        let camera = CameraState {
+            position: Vec3::new(0.0, 0.0, 5.0),
+            target: Vec3::ZERO,
+            fov: std::f32::consts::FRAC_PI_4,
+            up: None,
+        };
+        let sun = sun_position(&renderer, &camera).unwrap();
+        assert!((sun.0 - 8.0).abs() < 1e-3 && (sun.1 - 8.0).abs() < 1e-3);
+
+        let mut fb = Framebuffer::new(16, 16);
+        fb.fill([0.1, 0.1, 0.1, 1.0]);
+        fb.set_pixel(8, 8, [1.0, 1.0, 1.0, 1.0]);
+        let state = WeatherState {
+            god_rays: 1.0,
+            ..Default::default()
+        };
+        state.apply_post(&mut fb, &renderer, &camera);
+        assert!(fb.pixel(6, 6)[0] > 0.1);
+        assert!(fb.pixel(6, 6)[0] > fb.pixel(0, 15)[0]);
+
+        // Light behind the camera casts no shafts
+        let behind = renderer.clone().with_light(Vec3::Z);
+        assert!(sun_position(&behind, &camera).is_none());
+    }
+}
@@ -0,0 +1,27 @@
+//! Build check for the playback core without `std` (`--no-default-features --features libm`).
+
+use std::process::Command;
+
+#[test]
+fn test_playback_core_builds_without_std() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let output = Command::new(env!("CARGO"))
+        .args([
+            "build",
+            "--lib",
+            "--no-default-features",
+            "--features",
+            "libm",
+        ])
+        .arg("--manifest-path")
+        .arg(format!("{manifest_dir}/Cargo.toml"))
+        // A separate target dir keeps the outer `cargo test` build lock free
+        .env("CARGO_TARGET_DIR", format!("{manifest_dir}/target/no_std"))
+        .output()
+        .expect("failed to run cargo");
+    assert!(
+        output.status.success(),
+        "no_std build failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}